@@ -1,6 +1,7 @@
-use near_primitives::block::BlockValidityError;
 use near_primitives::challenge::{ChunkProofs, ChunkState};
-use near_primitives::errors::{ChunkAccessError, EpochError, StorageError};
+use near_primitives::errors::{
+    ChunkAccessError, EpochError, PrimitiveValidationError, StorageError,
+};
 use near_primitives::shard_layout::ShardLayoutError;
 use near_primitives::sharding::{BadHeaderForProtocolVersionError, ChunkHash, ShardChunkHeader};
 use near_primitives::types::{BlockHeight, EpochId, ShardId, ShardIndex};
@@ -188,6 +189,9 @@ pub enum Error {
     /// Invalid bandwidth requests
     #[error("Invalid bandwidth requests - chunk extra doesn't match chunk header: {0}")]
     InvalidBandwidthRequests(String),
+    /// Invalid outgoing bandwidth
+    #[error("Invalid outgoing bandwidth - outgoing receipts don't match chunk header: {0}")]
+    InvalidOutgoingBandwidth(String),
     /// Invalid shard id
     #[error("Shard id {0} does not exist")]
     InvalidShardId(ShardId),
@@ -248,6 +252,10 @@ pub enum Error {
     /// Invalid chunk header version for protocol version
     #[error(transparent)]
     BadHeaderForProtocolVersion(#[from] BadHeaderForProtocolVersionError),
+
+    /// Invalid data which has no more specific variant above.
+    #[error("Invalid data: {0}")]
+    InvalidData(PrimitiveValidationError),
     /// Anything else
     #[error("Other Error: {0}")]
     Other(String),
@@ -326,6 +334,7 @@ impl Error {
             | Error::InvalidBalanceBurnt
             | Error::InvalidCongestionInfo(_)
             | Error::InvalidBandwidthRequests(_)
+            | Error::InvalidOutgoingBandwidth(_)
             | Error::InvalidShardId(_)
             | Error::InvalidShardIndex(_)
             | Error::NoParentShardId(_)
@@ -336,7 +345,46 @@ impl Error {
             | Error::NotAValidator(_)
             | Error::NotAChunkValidator
             | Error::InvalidChallengeRoot
-            | Error::BadHeaderForProtocolVersion(_) => true,
+            | Error::BadHeaderForProtocolVersion(_)
+            | Error::InvalidData(_) => true,
+        }
+    }
+
+    /// Returns the machine-readable reason if the error is caused by invalid
+    /// block, chunk or challenge data, i.e. if `is_bad_data` holds.
+    pub fn validation_error(&self) -> Option<PrimitiveValidationError> {
+        match self {
+            Error::InvalidData(error) => Some(*error),
+            Error::InvalidSignature | Error::InvalidBlockProposer | Error::InvalidApprovals => {
+                Some(PrimitiveValidationError::InvalidSignature)
+            }
+            Error::InvalidChunkProofs(_)
+            | Error::InvalidReceiptsProof
+            | Error::InvalidOutcomesProof => Some(PrimitiveValidationError::InvalidMerkleProof),
+            Error::InvalidStateRoot => Some(PrimitiveValidationError::InvalidStateRoot),
+            Error::InvalidChunkReceiptsRoot => Some(PrimitiveValidationError::InvalidReceiptRoot),
+            Error::InvalidChunkHeadersRoot => {
+                Some(PrimitiveValidationError::InvalidChunkHeaderRoot)
+            }
+            Error::InvalidTxRoot | Error::InvalidChunkTxRoot => {
+                Some(PrimitiveValidationError::InvalidTransactionRoot)
+            }
+            Error::InvalidChunkMask => Some(PrimitiveValidationError::InvalidChunkMask),
+            Error::InvalidChallengeRoot => Some(PrimitiveValidationError::InvalidChallengeRoot),
+            Error::BadHeaderForProtocolVersion(_) => {
+                Some(PrimitiveValidationError::InvalidChunkHeaderVersion)
+            }
+            Error::InvalidChallenge => Some(PrimitiveValidationError::InvalidChallenge),
+            Error::MaliciousChallenge => Some(PrimitiveValidationError::MaliciousChallenge),
+            Error::NotAValidator(_) => Some(PrimitiveValidationError::NotAValidator),
+            Error::InvalidChunk(_) | Error::InvalidChunkState(_) => {
+                Some(PrimitiveValidationError::InvalidChunk)
+            }
+            Error::InvalidChunkStateWitness(_) | Error::InvalidPartialChunkStateWitness(_) => {
+                Some(PrimitiveValidationError::InvalidStateWitness)
+            }
+            _ if self.is_bad_data() => Some(PrimitiveValidationError::Other),
+            _ => None,
         }
     }
 
@@ -408,6 +456,7 @@ impl Error {
             Error::InvalidBalanceBurnt => "invalid_balance_burnt",
             Error::InvalidCongestionInfo(_) => "invalid_congestion_info",
             Error::InvalidBandwidthRequests(_) => "invalid_bandwidth_requests",
+            Error::InvalidOutgoingBandwidth(_) => "invalid_outgoing_bandwidth",
             Error::InvalidShardId(_) => "invalid_shard_id",
             Error::InvalidShardIndex(_) => "invalid_shard_index",
             Error::NoParentShardId(_) => "no_parent_shard_id",
@@ -420,6 +469,7 @@ impl Error {
             Error::InvalidChallengeRoot => "invalid_challenge_root",
             Error::ReshardingError(_) => "resharding_error",
             Error::BadHeaderForProtocolVersion(_) => "bad_header_for_protocol_version",
+            Error::InvalidData(_) => "invalid_data",
         }
     }
 }
@@ -459,15 +509,26 @@ impl From<ShardLayoutError> for Error {
     }
 }
 
-impl From<BlockValidityError> for Error {
-    fn from(error: BlockValidityError) -> Self {
+/// Reasons without an equivalent variant of `Error` are kept as they are, so
+/// that `Error::validation_error` returns the same reason back.
+impl From<PrimitiveValidationError> for Error {
+    fn from(error: PrimitiveValidationError) -> Self {
         match error {
-            BlockValidityError::InvalidStateRoot => Error::InvalidStateRoot,
-            BlockValidityError::InvalidReceiptRoot => Error::InvalidChunkReceiptsRoot,
-            BlockValidityError::InvalidTransactionRoot => Error::InvalidTxRoot,
-            BlockValidityError::InvalidChunkHeaderRoot => Error::InvalidChunkHeadersRoot,
-            BlockValidityError::InvalidChunkMask => Error::InvalidChunkMask,
-            BlockValidityError::InvalidChallengeRoot => Error::InvalidChallengeRoot,
+            PrimitiveValidationError::InvalidSignature => Error::InvalidSignature,
+            PrimitiveValidationError::InvalidStateRoot => Error::InvalidStateRoot,
+            PrimitiveValidationError::InvalidReceiptRoot => Error::InvalidChunkReceiptsRoot,
+            PrimitiveValidationError::InvalidTransactionRoot => Error::InvalidTxRoot,
+            PrimitiveValidationError::InvalidChunkHeaderRoot => Error::InvalidChunkHeadersRoot,
+            PrimitiveValidationError::InvalidChunkMask => Error::InvalidChunkMask,
+            PrimitiveValidationError::InvalidChallengeRoot => Error::InvalidChallengeRoot,
+            PrimitiveValidationError::InvalidChallenge => Error::InvalidChallenge,
+            PrimitiveValidationError::MaliciousChallenge => Error::MaliciousChallenge,
+            PrimitiveValidationError::InvalidMerkleProof
+            | PrimitiveValidationError::InvalidChunkHeaderVersion
+            | PrimitiveValidationError::NotAValidator
+            | PrimitiveValidationError::InvalidChunk
+            | PrimitiveValidationError::InvalidStateWitness
+            | PrimitiveValidationError::Other => Error::InvalidData(error),
         }
     }
 }
@@ -0,0 +1,29 @@
+#![no_main]
+
+use borsh::BorshDeserialize;
+use near_chain::test_utils::{KeyValueRuntime, MockEpochManager, ValidatorSchedule};
+use near_chain::validate::validate_challenge_body;
+use near_primitives::challenge::Challenge;
+use near_store::test_utils::create_test_store;
+use std::sync::{Arc, OnceLock};
+
+static ENV: OnceLock<(Arc<MockEpochManager>, Arc<KeyValueRuntime>)> = OnceLock::new();
+
+libfuzzer_sys::fuzz_target!(|challenge: Challenge| {
+    let bytes = borsh::to_vec(&challenge).unwrap();
+    let decoded = Challenge::try_from_slice(&bytes).unwrap();
+    assert_eq!(decoded, challenge);
+    assert_eq!(decoded.hash, challenge.hash);
+
+    let (epoch_manager, runtime) = ENV.get_or_init(|| {
+        let store = create_test_store();
+        let vs = ValidatorSchedule::new().block_producers_per_epoch(vec![(0..4)
+            .map(|i| format!("test{}", i).parse().unwrap())
+            .collect()]);
+        let epoch_manager = MockEpochManager::new_with_validators(store.clone(), vs, 5);
+        let runtime = KeyValueRuntime::new(store, &epoch_manager);
+        (epoch_manager, runtime)
+    });
+    // Validation may reject the challenge but must never panic.
+    let _ = validate_challenge_body(epoch_manager.as_ref(), runtime.as_ref(), &decoded.body);
+});
@@ -1,5 +1,5 @@
 use near_chain_primitives::error::Error;
-use near_crypto::Signature;
+use near_crypto::{PublicKey, Signature};
 use near_primitives::{
     block_header::{Approval, ApprovalInner},
     epoch_info::EpochInfo,
@@ -7,6 +7,7 @@ use near_primitives::{
     hash::CryptoHash,
     types::{AccountId, ApprovalStake, Balance, BlockHeight},
 };
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use std::{collections::HashSet, sync::Arc};
 
 pub fn verify_approval_with_approvers_info(
@@ -29,14 +30,29 @@ pub fn verify_approval_with_approvers_info(
         block_height,
     );
 
-    for ((validator, is_slashed), may_be_signature) in info.into_iter().zip(approvals.iter()) {
+    let mut signatures = vec![];
+    for ((validator, is_slashed), may_be_signature) in info.iter().zip(approvals.iter()) {
         if let Some(signature) = may_be_signature {
-            if is_slashed || !signature.verify(message_to_sign.as_ref(), &validator.public_key) {
+            if *is_slashed {
                 return Ok(false);
             }
+            signatures.push((signature.as_ref(), &validator.public_key));
         }
     }
-    Ok(true)
+    Ok(verify_approval_signatures(message_to_sign.as_ref(), &signatures))
+}
+
+/// Checks that all approvals sign the given message. Signatures are verified
+/// one by one, in parallel. Batched verification is not used as it isn't
+/// deterministic: it is cofactored and randomized, so it may accept signatures
+/// rejected by `Signature::verify`, depending on the random coefficients.
+fn verify_approval_signatures(
+    message_to_sign: &[u8],
+    signatures: &[(&Signature, &PublicKey)],
+) -> bool {
+    signatures
+        .par_iter()
+        .all(|(signature, public_key)| signature.verify(message_to_sign, public_key))
 }
 
 /// Verify approvals and check threshold, but ignore next epoch approvals and slashing
@@ -61,12 +77,15 @@ pub fn verify_approvals_and_threshold_orphan(
         block_height,
     );
 
-    for (validator, may_be_signature) in block_approvers.iter().zip(approvals.iter()) {
-        if let Some(signature) = may_be_signature {
-            if !signature.verify(message_to_sign.as_ref(), &validator.public_key) {
-                return Err(Error::InvalidApprovals);
-            }
-        }
+    let signatures: Vec<_> = block_approvers
+        .iter()
+        .zip(approvals.iter())
+        .filter_map(|(validator, may_be_signature)| {
+            may_be_signature.as_ref().map(|signature| (signature.as_ref(), &validator.public_key))
+        })
+        .collect();
+    if !verify_approval_signatures(message_to_sign.as_ref(), &signatures) {
+        return Err(Error::InvalidApprovals);
     }
     let stakes = block_approvers
         .iter()
@@ -105,6 +105,10 @@ impl BlocksInProcessing {
         self.preprocessed_blocks.contains_key(block_hash)
     }
 
+    pub(crate) fn get(&self, block_hash: &CryptoHash) -> Option<&(Block, BlockPreprocessInfo)> {
+        self.preprocessed_blocks.get(block_hash)
+    }
+
     pub(crate) fn remove(
         &mut self,
         block_hash: &CryptoHash,
@@ -133,6 +137,15 @@ impl BlocksInProcessing {
             .any(|(_, (block, _))| block.header().prev_hash() == prev_hash)
     }
 
+    /// Returns the hashes of the blocks in processing whose previous block is `prev_hash`.
+    pub(crate) fn children(&self, prev_hash: &CryptoHash) -> Vec<CryptoHash> {
+        self.preprocessed_blocks
+            .iter()
+            .filter(|(_, (block, _))| block.header().prev_hash() == prev_hash)
+            .map(|(block_hash, _)| *block_hash)
+            .collect()
+    }
+
     /// This function waits until apply_chunks_done is marked as true for all blocks in the pool
     /// Returns true if new blocks are done applying chunks
     pub(crate) fn wait_for_all_blocks(&self) -> bool {
@@ -0,0 +1,277 @@
+//! Re-application of past blocks for debugging.
+//!
+//! A block is re-applied on a shard against the trie state it was originally
+//! applied to, which must not have been garbage collected, e.g. on archival
+//! nodes. The result is compared with the chunk extra, outcomes and outgoing
+//! receipts stored when the block was processed, which surfaces
+//! non-determinism of the runtime without stopping the node to run the state
+//! viewer.
+
+use std::collections::HashMap;
+
+use near_chain_primitives::Error;
+use near_epoch_manager::EpochManagerAdapter;
+use near_primitives::apply::ApplyChunkReason;
+use near_primitives::block::Block;
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::chunk_extra::ChunkExtra;
+use near_primitives::types::ShardId;
+use near_primitives::views::{BlockReplayMismatchView, BlockReplayView};
+
+use crate::chain::collect_receipts_from_response;
+use crate::migrations::check_if_block_is_first_with_chunk_of_version;
+use crate::store::{ChainStore, ChainStoreAccess, ReceiptFilter};
+use crate::types::{ApplyChunkResult, StorageDataSource};
+use crate::update_shard::{
+    apply_new_chunk, apply_old_chunk, NewChunkData, OldChunkData, ShardContext, StorageContext,
+};
+use crate::Chain;
+
+impl Chain {
+    /// Re-applies the block `block_hash` on the shard `shard_id` and returns
+    /// the differences between the result and the stored one.
+    ///
+    /// The trie is read without flat storage, with the gas costs of flat
+    /// storage, so the replay works as long as the trie state of the previous
+    /// block is available. Nothing is written to the store.
+    pub fn replay_block(
+        &self,
+        block_hash: &CryptoHash,
+        shard_id: ShardId,
+    ) -> Result<BlockReplayView, Error> {
+        let block = self.get_block(block_hash)?;
+        let prev_hash = block.header().prev_hash();
+        let prev_block = self.get_block(prev_hash)?;
+        let epoch_id = block.header().epoch_id();
+        let shard_layout = self.epoch_manager.get_shard_layout(epoch_id)?;
+        let shard_index = shard_layout.get_shard_index(shard_id)?;
+        let chunk_header =
+            block.chunks().get(shard_index).ok_or(Error::InvalidShardId(shard_id))?.clone();
+        let shard_uid = self.epoch_manager.shard_id_to_uid(shard_id, epoch_id)?;
+        let stored_chunk_extra = self.get_chunk_extra(block_hash, &shard_uid)?;
+
+        let span = tracing::debug_span!(target: "chain", "replay_block", ?block_hash, ?shard_id);
+        let shard_context = ShardContext { shard_uid, should_apply_chunk: true };
+        let is_new_chunk = chunk_header.is_new_chunk(block.header().height());
+        let apply_result = if is_new_chunk {
+            let data = self.new_chunk_replay_data(&block, &prev_block, shard_id)?;
+            apply_new_chunk(
+                ApplyChunkReason::UpdateTrackedShard,
+                &span,
+                data,
+                shard_context,
+                self.runtime_adapter.as_ref(),
+            )?
+            .apply_result
+        } else {
+            let data = OldChunkData {
+                prev_chunk_extra: ChunkExtra::clone(
+                    self.get_chunk_extra(prev_hash, &shard_uid)?.as_ref(),
+                ),
+                block: Self::get_apply_chunk_block_context(
+                    self.epoch_manager.as_ref(),
+                    &self.chain_store,
+                    &block,
+                    prev_block.header(),
+                    false,
+                )?,
+                storage_context: replay_storage_context(),
+            };
+            apply_old_chunk(
+                ApplyChunkReason::UpdateTrackedShard,
+                &span,
+                data,
+                shard_context,
+                self.runtime_adapter.as_ref(),
+            )?
+            .apply_result
+        };
+
+        let mut mismatches = vec![];
+        if stored_chunk_extra.state_root() != &apply_result.new_root {
+            mismatches.push(BlockReplayMismatchView::StateRoot {
+                stored: *stored_chunk_extra.state_root(),
+                replayed: apply_result.new_root,
+            });
+        }
+        // Without a new chunk the rest of the chunk extra is copied from the
+        // previous block and nothing else is stored.
+        if is_new_chunk {
+            self.compare_new_chunk_result(
+                block_hash,
+                shard_id,
+                &stored_chunk_extra,
+                &apply_result,
+                &mut mismatches,
+            )?;
+        }
+        if !mismatches.is_empty() {
+            tracing::warn!(target: "chain", ?block_hash, ?shard_id, ?mismatches, "Replayed block differs from the stored result");
+        }
+
+        Ok(BlockReplayView {
+            block_hash: *block_hash,
+            height: block.header().height(),
+            shard_id,
+            is_new_chunk,
+            state_root: apply_result.new_root,
+            num_outcomes: apply_result.outcomes.len() as u64,
+            mismatches,
+        })
+    }
+
+    /// Collects what is needed to re-apply the new chunk of `shard_id` in the
+    /// already processed block `block`, reading the state from the trie only.
+    pub(crate) fn new_chunk_replay_data(
+        &self,
+        block: &Block,
+        prev_block: &Block,
+        shard_id: ShardId,
+    ) -> Result<NewChunkData, Error> {
+        new_chunk_replay_data(
+            &self.chain_store,
+            self.epoch_manager.as_ref(),
+            block,
+            prev_block,
+            shard_id,
+        )
+    }
+
+    fn compare_new_chunk_result(
+        &self,
+        block_hash: &CryptoHash,
+        shard_id: ShardId,
+        stored_chunk_extra: &ChunkExtra,
+        apply_result: &ApplyChunkResult,
+        mismatches: &mut Vec<BlockReplayMismatchView>,
+    ) -> Result<(), Error> {
+        if stored_chunk_extra.gas_used() != apply_result.total_gas_burnt {
+            mismatches.push(BlockReplayMismatchView::GasUsed {
+                stored: stored_chunk_extra.gas_used(),
+                replayed: apply_result.total_gas_burnt,
+            });
+        }
+        if stored_chunk_extra.balance_burnt() != apply_result.total_balance_burnt {
+            mismatches.push(BlockReplayMismatchView::BalanceBurnt {
+                stored: stored_chunk_extra.balance_burnt(),
+                replayed: apply_result.total_balance_burnt,
+            });
+        }
+
+        let stored_receipts = self.chain_store.get_outgoing_receipts(block_hash, shard_id)?;
+        let stored_receipts_hash = CryptoHash::hash_borsh(stored_receipts.as_ref());
+        let replayed_receipts_hash = CryptoHash::hash_borsh(&apply_result.outgoing_receipts);
+        if stored_receipts_hash != replayed_receipts_hash {
+            mismatches.push(BlockReplayMismatchView::OutgoingReceipts {
+                stored: stored_receipts_hash,
+                replayed: replayed_receipts_hash,
+            });
+        }
+
+        let (outcome_root, _) = ApplyChunkResult::compute_outcomes_proof(&apply_result.outcomes);
+        if stored_chunk_extra.outcome_root() == &outcome_root {
+            return Ok(());
+        }
+        mismatches.push(BlockReplayMismatchView::OutcomeRoot {
+            stored: *stored_chunk_extra.outcome_root(),
+            replayed: outcome_root,
+        });
+
+        // Find the outcomes which differ, if they weren't garbage collected.
+        let stored_ids =
+            self.chain_store.get_outcomes_by_block_hash_and_shard_id(block_hash, shard_id)?;
+        if stored_ids.is_empty() {
+            return Ok(());
+        }
+        let mut replayed: HashMap<CryptoHash, CryptoHash> = apply_result
+            .outcomes
+            .iter()
+            .map(|outcome| (outcome.id, CryptoHash::hash_borsh(&outcome.outcome)))
+            .collect();
+        for id in stored_ids {
+            let stored = self
+                .chain_store
+                .get_outcome_by_id_and_block_hash(&id, block_hash)?
+                .map(|outcome| CryptoHash::hash_borsh(&outcome.outcome));
+            let replayed = replayed.remove(&id);
+            if stored != replayed {
+                mismatches.push(BlockReplayMismatchView::Outcome { id, stored, replayed });
+            }
+        }
+        for outcome in &apply_result.outcomes {
+            if let Some(replayed) = replayed.remove(&outcome.id) {
+                mismatches.push(BlockReplayMismatchView::Outcome {
+                    id: outcome.id,
+                    stored: None,
+                    replayed: Some(replayed),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collects what is needed to re-apply the new chunk of `shard_id` in the
+/// already processed block `block`, reading the state from the trie only.
+pub(crate) fn new_chunk_replay_data(
+    chain_store: &ChainStore,
+    epoch_manager: &dyn EpochManagerAdapter,
+    block: &Block,
+    prev_block: &Block,
+    shard_id: ShardId,
+) -> Result<NewChunkData, Error> {
+    let block_hash = block.hash();
+    let prev_hash = block.header().prev_hash();
+    let shard_layout = epoch_manager.get_shard_layout(block.header().epoch_id())?;
+    let shard_index = shard_layout.get_shard_index(shard_id)?;
+    let chunk_header =
+        block.chunks().get(shard_index).ok_or(Error::InvalidShardId(shard_id))?.clone();
+    if !chunk_header.is_new_chunk(block.header().height()) {
+        return Err(Error::Other(format!(
+            "block {block_hash} has no new chunk for shard {shard_id}"
+        )));
+    }
+    let prev_chunk_header = Chain::get_prev_chunk_header(epoch_manager, prev_block, shard_id)?;
+    let chunk = chain_store.get_chunk_clone_from_header(&chunk_header)?;
+
+    // The block is already processed, so its incoming receipts are stored
+    // together with the ones of the blocks without a chunk before it.
+    let receipts = chain_store.get_incoming_receipts_for_shard(
+        epoch_manager,
+        shard_id,
+        &shard_layout,
+        *block_hash,
+        prev_chunk_header.height_included(),
+        ReceiptFilter::TargetShard,
+    )?;
+    let receipts = collect_receipts_from_response(&receipts);
+    let is_first_block_with_chunk_of_version = check_if_block_is_first_with_chunk_of_version(
+        chain_store,
+        epoch_manager,
+        prev_hash,
+        shard_id,
+    )?;
+    let block_context = Chain::get_apply_chunk_block_context(
+        epoch_manager,
+        chain_store,
+        block,
+        prev_block.header(),
+        true,
+    )?;
+
+    Ok(NewChunkData {
+        chunk_header,
+        transactions: chunk.transactions().to_vec(),
+        receipts,
+        block: block_context,
+        is_first_block_with_chunk_of_version,
+        storage_context: replay_storage_context(),
+    })
+}
+
+fn replay_storage_context() -> StorageContext {
+    StorageContext {
+        storage_data_source: StorageDataSource::DbTrieOnly,
+        state_patch: Default::default(),
+    }
+}
@@ -0,0 +1,72 @@
+//! Scheduling of catchup, i.e. applying the blocks of the current epoch for
+//! the shards tracked in the next epoch after their state was synced.
+//!
+//! Catchup used to apply all the pending blocks at once on the same threads
+//! as new blocks, which could delay the head enough for validators switching
+//! shards to miss their chunks. Here the chunks of the blocks being caught up
+//! are applied on a separate thread pool, and fewer blocks are caught up at
+//! the same time while new blocks are being applied.
+
+use std::sync::Arc;
+
+use near_chain_configs::CatchupConfig;
+use near_chain_primitives::Error;
+
+pub struct CatchupScheduler {
+    config: CatchupConfig,
+    /// Thread pool applying the chunks of the blocks being caught up.
+    apply_chunks_pool: Arc<rayon::ThreadPool>,
+}
+
+impl CatchupScheduler {
+    pub fn new(config: CatchupConfig) -> Result<Self, Error> {
+        let apply_chunks_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(config.apply_chunks_threads)
+            .thread_name(|i| format!("catchup_apply_chunks_{i}"))
+            .build()
+            .map_err(|err| Error::Other(format!("failed to create catchup pool: {err}")))?;
+        Ok(Self { config, apply_chunks_pool: Arc::new(apply_chunks_pool) })
+    }
+
+    pub fn apply_chunks_pool(&self) -> Arc<rayon::ThreadPool> {
+        self.apply_chunks_pool.clone()
+    }
+
+    /// Returns how many more blocks can be caught up now, given the number of
+    /// blocks already being caught up and whether new blocks are being applied.
+    pub fn num_blocks_to_schedule(
+        &self,
+        num_scheduled_blocks: usize,
+        is_processing_blocks: bool,
+    ) -> usize {
+        let max_scheduled_blocks = if is_processing_blocks {
+            self.config.max_scheduled_blocks_during_block_processing
+        } else {
+            self.config.max_scheduled_blocks
+        };
+        // At least one block is always caught up, so that catchup makes
+        // progress even if new blocks keep arriving.
+        max_scheduled_blocks.max(1).saturating_sub(num_scheduled_blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CatchupScheduler;
+    use near_chain_configs::CatchupConfig;
+
+    #[test]
+    fn test_num_blocks_to_schedule() {
+        let config = CatchupConfig {
+            apply_chunks_threads: 1,
+            max_scheduled_blocks: 4,
+            max_scheduled_blocks_during_block_processing: 0,
+        };
+        let scheduler = CatchupScheduler::new(config).unwrap();
+        assert_eq!(scheduler.num_blocks_to_schedule(0, false), 4);
+        assert_eq!(scheduler.num_blocks_to_schedule(3, false), 1);
+        assert_eq!(scheduler.num_blocks_to_schedule(5, false), 0);
+        assert_eq!(scheduler.num_blocks_to_schedule(0, true), 1);
+        assert_eq!(scheduler.num_blocks_to_schedule(1, true), 0);
+    }
+}
@@ -4,8 +4,12 @@ use crate::block_processing_utils::{
     BlocksInProcessing,
 };
 use crate::blocks_delay_tracker::BlocksDelayTracker;
+use crate::catchup_scheduler::CatchupScheduler;
+use crate::chain_events::ChainEventSubscriber;
 use crate::chain_update::ChainUpdate;
 use crate::crypto_hash_timer::CryptoHashTimer;
+use crate::execution_artifacts::ExecutionArtifactExporter;
+use crate::fork_choice::{ForkChoice, HighestHeightForkChoice};
 use crate::lightclient::get_epoch_block_producers_view;
 use crate::migrations::check_if_block_is_first_with_chunk_of_version;
 use crate::missing_chunks::MissingChunksPool;
@@ -23,12 +27,13 @@ use crate::state_snapshot_actor::SnapshotCallbacks;
 use crate::stateless_validation::chunk_endorsement::{
     validate_chunk_endorsements_in_block, validate_chunk_endorsements_in_header,
 };
+use crate::stateless_validation::chunk_validation::reexecute_challenged_state_witness;
 use crate::store::{
     ChainStore, ChainStoreAccess, ChainStoreUpdate, MerkleProofAccess, ReceiptFilter,
 };
 use crate::types::{
-    AcceptedBlock, ApplyChunkBlockContext, BlockEconomicsConfig, ChainConfig, RuntimeAdapter,
-    StorageDataSource,
+    AcceptedBlock, ApplyChunkBlockContext, ApplyChunkShardContext, BlockEconomicsConfig,
+    ChainConfig, RuntimeAdapter, RuntimeStorageConfig, StorageDataSource,
 };
 pub use crate::update_shard::{
     apply_new_chunk, apply_old_chunk, NewChunkData, NewChunkResult, OldChunkData, OldChunkResult,
@@ -36,7 +41,7 @@ pub use crate::update_shard::{
 };
 use crate::update_shard::{process_shard_update, ShardUpdateReason, ShardUpdateResult};
 use crate::validate::{
-    validate_challenge, validate_chunk_proofs, validate_chunk_with_chunk_extra,
+    validate_challenges_batch, validate_chunk_proofs, validate_chunk_with_chunk_extra,
     validate_transactions_order,
 };
 use crate::{
@@ -46,20 +51,24 @@ use crate::{
 use crate::{metrics, DoomslugThresholdMode};
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use itertools::Itertools;
-use lru::LruCache;
 use near_async::futures::{AsyncComputationSpawner, AsyncComputationSpawnerExt};
 use near_async::messaging::{noop, IntoMultiSender};
 use near_async::time::{Clock, Duration, Instant};
-use near_chain_configs::{MutableConfigValue, MutableValidatorSigner};
+use near_chain_configs::{
+    get_scheduled_protocol_version, CatchupConfig, MutableConfigValue, MutableValidatorSigner,
+    ScheduledProtocolUpgrade,
+};
 use near_chain_primitives::error::{BlockKnownError, Error, LogTransientStorageError};
 use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::EpochManagerAdapter;
+use near_primitives::apply::ApplyChunkReason;
 use near_primitives::bandwidth_scheduler::BandwidthRequests;
-use near_primitives::block::{genesis_chunks, Block, BlockValidityError, Tip};
+use near_primitives::block::{genesis_chunks, Block, Tip};
 use near_primitives::block_header::BlockHeader;
+use near_primitives::block_headers_range::{BlockHeadersRange, BlockHeadersRangeRequest};
 use near_primitives::challenge::{
     BlockDoubleSign, Challenge, ChallengeBody, ChallengesResult, ChunkProofs, ChunkState,
-    MaybeEncodedShardChunk, PartialState, SlashedValidator,
+    MaybeEncodedShardChunk, PartialState, SlashedValidator, SlashingSeverity,
 };
 use near_primitives::checked_feature;
 use near_primitives::congestion_info::CongestionInfo;
@@ -67,7 +76,7 @@ use near_primitives::epoch_block_info::BlockInfo;
 use near_primitives::errors::EpochError;
 use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::merkle::{merklize, verify_path, PartialMerkleTree};
-use near_primitives::receipt::Receipt;
+use near_primitives::receipt::{Receipt, ReceiptEnum};
 use near_primitives::sandbox::state_patch::SandboxStatePatch;
 use near_primitives::shard_layout::{ShardLayout, ShardUId};
 use near_primitives::sharding::{
@@ -82,7 +91,7 @@ use near_primitives::state_sync::{
 use near_primitives::stateless_validation::state_witness::{
     ChunkStateWitness, ChunkStateWitnessSize,
 };
-use near_primitives::transaction::{ExecutionOutcomeWithIdAndProof, SignedTransaction};
+use near_primitives::transaction::{Action, ExecutionOutcomeWithIdAndProof, SignedTransaction};
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::validator_stake::ValidatorStake;
 use near_primitives::types::{
@@ -94,25 +103,23 @@ use near_primitives::utils::MaybeValidated;
 use near_primitives::version::{ProtocolFeature, ProtocolVersion, PROTOCOL_VERSION};
 use near_primitives::views::{
     BlockStatusView, DroppedReason, ExecutionOutcomeWithIdView, ExecutionStatusView,
-    FinalExecutionOutcomeView, FinalExecutionOutcomeWithReceiptView, FinalExecutionStatus,
-    LightClientBlockView, SignedTransactionView,
+    ExecutionTraceEventView, FinalExecutionOutcomeView, FinalExecutionOutcomeWithReceiptView,
+    FinalExecutionStatus, LightClientBlockView, SignedTransactionView,
 };
 use near_store::adapter::StoreUpdateAdapter;
 use near_store::config::StateSnapshotType;
 use near_store::get_genesis_state_roots;
 use near_store::DBCol;
+use near_vm_runner::logic::{RecentBlockHeader, NUM_RECENT_BLOCK_HEADERS};
 use node_runtime::bootstrap_congestion_info;
+use node_runtime::trace::CollectingTracer;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
-use std::num::NonZeroUsize;
 use std::sync::Arc;
 use time::ext::InstantExt as _;
 use tracing::{debug, debug_span, error, info, warn, Span};
 
-/// The size of the invalid_blocks in-memory pool
-pub const INVALID_CHUNKS_POOL_SIZE: usize = 5000;
-
 /// 5000 years in seconds. Big constant for sandbox to allow time traveling.
 #[cfg(feature = "sandbox")]
 const ACCEPTABLE_TIME_DIFFERENCE: i64 = 60 * 60 * 24 * 365 * 5000;
@@ -120,9 +127,8 @@ const ACCEPTABLE_TIME_DIFFERENCE: i64 = 60 * 60 * 24 * 365 * 5000;
 // Number of parent blocks traversed to check if the block can be finalized.
 const NUM_PARENTS_TO_CHECK_FINALITY: usize = 20;
 
-/// Refuse blocks more than this many block intervals in the future (as in bitcoin).
-#[cfg(not(feature = "sandbox"))]
-const ACCEPTABLE_TIME_DIFFERENCE: i64 = 12 * 10;
+/// How far above the head the invalid blocks are persisted.
+const INVALID_BLOCKS_HEIGHT_HORIZON: BlockHeightDelta = 1_000;
 
 /// Private constant for 1 NEAR (copy from near/config.rs) used for reporting.
 const NEAR_BASE: Balance = 1_000_000_000_000_000_000_000_000;
@@ -203,6 +209,7 @@ fn check_known_store(
 ///         Ok(Ok()) otherwise
 pub fn check_known(
     chain: &Chain,
+    height: BlockHeight,
     block_hash: &CryptoHash,
 ) -> Result<Result<(), BlockKnownError>, Error> {
     // TODO: Change the return type to Result<BlockKnownStatusEnum, Error>.
@@ -221,7 +228,7 @@ pub fn check_known(
     if chain.blocks_with_missing_chunks.contains(block_hash) {
         return Ok(Err(BlockKnownError::KnownInMissingChunks));
     }
-    if chain.is_block_invalid(block_hash) {
+    if chain.is_block_invalid(height, block_hash) {
         return Ok(Err(BlockKnownError::KnownAsInvalid));
     }
     check_known_store(chain, block_hash)
@@ -242,6 +249,8 @@ pub struct Chain {
     genesis: Block,
     pub transaction_validity_period: NumBlocks,
     pub epoch_length: BlockHeightDelta,
+    /// Protocol upgrades scheduled at fixed heights in the genesis config.
+    protocol_upgrade_heights: Vec<ScheduledProtocolUpgrade>,
     /// Block economics, relevant to changes when new block must be produced.
     pub block_economics_config: BlockEconomicsConfig,
     pub doomslug_threshold_mode: DoomslugThresholdMode,
@@ -257,11 +266,24 @@ pub struct Chain {
     apply_chunks_receiver: Receiver<BlockApplyChunksResult>,
     /// Used to spawn the apply chunks jobs.
     apply_chunks_spawner: Arc<dyn AsyncComputationSpawner>,
+    /// Thread pool applying the chunks of a block, its size bounds the number
+    /// of shards applied at once.
+    apply_chunks_pool: Arc<rayon::ThreadPool>,
+    /// Decides when the blocks being caught up are applied, and where.
+    catchup_scheduler: CatchupScheduler,
+    /// How far in the future the timestamp of a block may be.
+    max_block_clock_skew: Duration,
+    /// Whether the data needed to produce the state witnesses of all chunks is
+    /// saved, so that the chunks can be shadow validated.
+    shadow_validation: bool,
+    /// Rule selecting the head among the accepted blocks.
+    fork_choice: Arc<dyn ForkChoice>,
+    /// Consumers notified of the changes of the canonical chain.
+    pub(crate) chain_event_subscribers: Vec<Arc<dyn ChainEventSubscriber>>,
+    /// Exports the execution of the applied blocks, if enabled.
+    pub(crate) execution_artifact_exporter: Option<ExecutionArtifactExporter>,
     /// Time when head was updated most recently.
     last_time_head_updated: Instant,
-    /// Prevents re-application of known-to-be-invalid blocks, so that in case of a
-    /// protocol issue we can recover faster by focusing on correct blocks.
-    invalid_blocks: LruCache<CryptoHash, ()>,
 
     /// Support for sandbox's patch_state requests.
     ///
@@ -394,14 +416,26 @@ impl Chain {
             genesis,
             transaction_validity_period: chain_genesis.transaction_validity_period,
             epoch_length: chain_genesis.epoch_length,
+            protocol_upgrade_heights: chain_genesis.protocol_upgrade_heights.clone(),
             block_economics_config: BlockEconomicsConfig::from(chain_genesis),
             doomslug_threshold_mode,
             blocks_delay_tracker: BlocksDelayTracker::new(clock.clone()),
             apply_chunks_sender: sc,
             apply_chunks_receiver: rc,
             apply_chunks_spawner: Arc::new(RayonAsyncComputationSpawner),
+            // The view client never applies chunks.
+            apply_chunks_pool: new_apply_chunks_pool(1)?,
+            catchup_scheduler: CatchupScheduler::new(CatchupConfig {
+                apply_chunks_threads: 1,
+                ..CatchupConfig::default()
+            })?,
+            // The view client never validates blocks.
+            max_block_clock_skew: Duration::ZERO,
+            shadow_validation: false,
+            fork_choice: Arc::new(HighestHeightForkChoice),
+            chain_event_subscribers: vec![],
+            execution_artifact_exporter: None,
             last_time_head_updated: clock.now(),
-            invalid_blocks: LruCache::new(NonZeroUsize::new(INVALID_CHUNKS_POOL_SIZE).unwrap()),
             pending_state_patch: Default::default(),
             requested_state_parts: StateRequestTracker::new(),
             snapshot_callbacks: None,
@@ -424,6 +458,11 @@ impl Chain {
     ) -> Result<Chain, Error> {
         let state_roots = get_genesis_state_roots(runtime_adapter.store())?
             .expect("genesis should be initialized.");
+        // Sandbox allows time traveling.
+        #[cfg(feature = "sandbox")]
+        let max_block_clock_skew = Duration::seconds(ACCEPTABLE_TIME_DIFFERENCE);
+        #[cfg(not(feature = "sandbox"))]
+        let max_block_clock_skew = chain_config.max_block_clock_skew;
         let (genesis, genesis_chunks) = Self::make_genesis_block(
             epoch_manager.as_ref(),
             runtime_adapter.as_ref(),
@@ -578,16 +617,23 @@ impl Chain {
             orphans: OrphanBlockPool::new(),
             blocks_with_missing_chunks: MissingChunksPool::new(),
             blocks_in_processing: BlocksInProcessing::new(),
-            invalid_blocks: LruCache::new(NonZeroUsize::new(INVALID_CHUNKS_POOL_SIZE).unwrap()),
             genesis: genesis.clone(),
             transaction_validity_period: chain_genesis.transaction_validity_period,
             epoch_length: chain_genesis.epoch_length,
+            protocol_upgrade_heights: chain_genesis.protocol_upgrade_heights.clone(),
             block_economics_config: BlockEconomicsConfig::from(chain_genesis),
             doomslug_threshold_mode,
             blocks_delay_tracker: BlocksDelayTracker::new(clock.clone()),
             apply_chunks_sender: sc,
             apply_chunks_receiver: rc,
             apply_chunks_spawner,
+            apply_chunks_pool: new_apply_chunks_pool(chain_config.apply_chunks_threads)?,
+            catchup_scheduler: CatchupScheduler::new(chain_config.catchup)?,
+            max_block_clock_skew,
+            shadow_validation: chain_config.shadow_validation,
+            fork_choice: Arc::new(HighestHeightForkChoice),
+            chain_event_subscribers: vec![],
+            execution_artifact_exporter: None,
             last_time_head_updated: clock.now(),
             pending_state_patch: Default::default(),
             requested_state_parts: StateRequestTracker::new(),
@@ -776,7 +822,7 @@ impl Chain {
         Ok(())
     }
 
-    fn maybe_mark_block_invalid(&mut self, block_hash: CryptoHash, error: &Error) {
+    fn maybe_mark_block_invalid(&self, header: &BlockHeader, error: &Error) {
         // We only mark the block as invalid if the block has bad data (not for other errors that would
         // not be the fault of the block itself), except when the block has a bad signature which means
         // the block might not have been what the block producer originally produced. Either way, it's
@@ -786,7 +832,24 @@ impl Chain {
             && !matches!(error, Error::InvalidSignature | Error::InvalidBlockHeight(_))
         {
             metrics::NUM_INVALID_BLOCKS.with_label_values(&[error.prometheus_label_value()]).inc();
-            self.invalid_blocks.put(block_hash, ());
+            // Blocks far above the head are not persisted, so that the
+            // column only holds the heights between the GC tail and the head.
+            let max_height = self
+                .chain_store
+                .head()
+                .map_or(0, |head| head.height.saturating_add(INVALID_BLOCKS_HEIGHT_HORIZON));
+            if header.height() > max_height {
+                return;
+            }
+            // The verdict is persisted, so that the block is not processed
+            // again after a restart. It can be cleared by the operator.
+            if let Err(err) = self.chain_store.save_invalid_block(
+                header.hash(),
+                header.height(),
+                error.to_string(),
+            ) {
+                warn!(target: "chain", block_hash = ?header.hash(), ?err, "Failed to save invalid block");
+            }
         }
     }
 
@@ -890,7 +953,7 @@ impl Chain {
                 }
             }
         }
-        block.check_validity().map_err(|e| <BlockValidityError as Into<Error>>::into(e))?;
+        block.check_validity()?;
         Ok(())
     }
 
@@ -953,8 +1016,7 @@ impl Chain {
         challenges: &mut Vec<ChallengeBody>,
     ) -> Result<(), Error> {
         // Refuse blocks from the too distant future.
-        if header.timestamp() > self.clock.now_utc() + Duration::seconds(ACCEPTABLE_TIME_DIFFERENCE)
-        {
+        if header.timestamp() > self.clock.now_utc() + self.max_block_clock_skew {
             return Err(Error::InvalidBlockFutureTime(header.timestamp()));
         }
 
@@ -998,6 +1060,17 @@ impl Chain {
                 }
             }
         }
+        if let Some(scheduled_protocol_version) = self.scheduled_protocol_version(header.height()) {
+            if header.latest_protocol_version() < scheduled_protocol_version {
+                error!(
+                    "header protocol version {} smaller than protocol version {} scheduled at height {}",
+                    header.latest_protocol_version(),
+                    scheduled_protocol_version,
+                    header.height()
+                );
+                return Err(Error::InvalidProtocolVersion);
+            }
+        }
 
         let prev_header = self.get_previous_header(header)?;
 
@@ -1138,7 +1211,7 @@ impl Chain {
     ) -> Result<(), Error> {
         debug!(target: "chain", block_hash=?header.hash(), height=header.height(), "process_block_header");
 
-        check_known(self, header.hash())?.map_err(|e| Error::BlockKnown(e))?;
+        check_known(self, header.height(), header.hash())?.map_err(|e| Error::BlockKnown(e))?;
         self.validate_header(header, &Provenance::NONE, challenges)?;
         Ok(())
     }
@@ -1213,31 +1286,53 @@ impl Chain {
         .entered();
         let mut result = vec![];
         let mut challenged_blocks = vec![];
-        for challenge in challenges.iter() {
-            match validate_challenge(
-                self.epoch_manager.as_ref(),
-                self.runtime_adapter.as_ref(),
-                epoch_id,
-                prev_block_hash,
-                challenge,
-            ) {
+        let protocol_version = self.epoch_manager.get_epoch_protocol_version(epoch_id)?;
+        let validation_results = validate_challenges_batch(
+            self.epoch_manager.as_ref(),
+            self.runtime_adapter.as_ref(),
+            epoch_id,
+            prev_block_hash,
+            challenges,
+        );
+        for (challenge, validation_result) in challenges.iter().zip(validation_results) {
+            let validation_result = validation_result.and_then(|result| {
+                if let ChallengeBody::InvalidStateWitness(invalid_state_witness) = &challenge.body {
+                    reexecute_challenged_state_witness(
+                        invalid_state_witness,
+                        self,
+                        self.epoch_manager.as_ref(),
+                        self.runtime_adapter.as_ref(),
+                    )?;
+                }
+                Ok(result)
+            });
+            match validation_result {
                 Ok((hash, account_ids)) => {
-                    let is_double_sign = match challenge.body {
-                        // If it's double signed block, we don't invalidate blocks just slash.
-                        ChallengeBody::BlockDoubleSign(_) => true,
-                        _ => {
+                    match challenge.body {
+                        // If it's double signed block or chunk, we don't invalidate blocks just slash.
+                        // Invalid witness is evidence against validators, `hash` is the chunk hash.
+                        ChallengeBody::BlockDoubleSign(_)
+                        | ChallengeBody::ChunkDoubleSign(_)
+                        | ChallengeBody::InvalidStateWitness(_) => {}
+                        ChallengeBody::ChunkProofs(_) | ChallengeBody::ChunkState(_) => {
                             challenged_blocks.push(hash);
-                            false
                         }
-                    };
+                    }
+                    let severity = challenge.body.slashing_severity(protocol_version);
                     let slash_validators: Vec<_> = account_ids
                         .into_iter()
-                        .map(|id| SlashedValidator::new(id, is_double_sign))
+                        .map(|id| {
+                            SlashedValidator::new(id, severity)
+                                .with_challenger(challenge.account_id.clone(), protocol_version)
+                        })
                         .collect();
                     result.extend(slash_validators);
                 }
                 Err(Error::MaliciousChallenge) => {
-                    result.push(SlashedValidator::new(challenge.account_id.clone(), false));
+                    // Everyone who signed a malicious challenge is slashed.
+                    result.extend(challenge.signers().map(|account_id| {
+                        SlashedValidator::new(account_id.clone(), SlashingSeverity::Other)
+                    }));
                 }
                 Err(err) => return Err(err),
             }
@@ -1262,6 +1357,14 @@ impl Chain {
                         block.header().prev_hash()
                     )));
                 }
+                if !chunk_header.has_valid_num_data_parts(self.epoch_manager.num_data_parts()) {
+                    return Err(Error::InvalidChunk(format!(
+                        "Invalid number of data parts, chunk hash {:?}, num data parts {:?}, encoded length {}",
+                        chunk_header.chunk_hash(),
+                        chunk_header.num_data_parts(),
+                        chunk_header.encoded_length()
+                    )));
+                }
             } else {
                 // old chunk
                 if prev_chunk_header != chunk_header {
@@ -1530,6 +1633,8 @@ impl Chain {
                 apply_chunks_done_sender.clone(),
             ) {
                 Err(e) => {
+                    // The block may have been prepared for optimistic production.
+                    self.epoch_manager.remove_optimistic_block_info(&block_hash);
                     errors.insert(block_hash, e);
                 }
                 Ok(accepted_block) => {
@@ -1545,11 +1650,18 @@ impl Chain {
             &mut self.chain_store,
             self.epoch_manager.clone(),
             self.runtime_adapter.clone(),
+            self.fork_choice.clone(),
             self.doomslug_threshold_mode,
             self.transaction_validity_period,
         )
     }
 
+    /// Replaces the rule selecting the head, which by default is the block
+    /// with the highest height.
+    pub fn set_fork_choice(&mut self, fork_choice: Arc<dyn ForkChoice>) {
+        self.fork_choice = fork_choice;
+    }
+
     /// Process challenge to invalidate chain. This is done between blocks to unroll the chain as
     /// soon as possible and allow next block producer to skip invalid blocks.
     pub fn process_challenge(&mut self, challenge: &Challenge) {
@@ -1800,7 +1912,7 @@ impl Chain {
                 preprocess_res
             }
             Err(e) => {
-                self.maybe_mark_block_invalid(*block.hash(), &e);
+                self.maybe_mark_block_invalid(block.header(), &e);
                 preprocess_timer.stop_and_discard();
                 match &e {
                     Error::Orphan => {
@@ -1900,9 +2012,10 @@ impl Chain {
         apply_chunks_done_sender: Option<near_async::messaging::Sender<ApplyChunksDoneMessage>>,
     ) {
         let sc = self.apply_chunks_sender.clone();
+        let pool = self.apply_chunks_pool.clone();
         self.apply_chunks_spawner.spawn("apply_chunks", move || {
             // do_apply_chunks runs `work` in parallel, but still waits for all of them to finish
-            let res = do_apply_chunks(block_hash, block_height, work);
+            let res = do_apply_chunks(&pool, block_hash, block_height, work);
             // If we encounter error here, that means the receiver is deallocated and the client
             // thread is already shut down. The node is already crashed, so we can unwrap here
             sc.send((block_hash, res)).unwrap();
@@ -1923,8 +2036,11 @@ impl Chain {
     ) -> Result<Option<Tip>, Error> {
         // Save state transition data to the database only if it might later be needed
         // for generating a state witness. Storage space optimization.
-        let should_save_state_transition_data =
-            self.should_produce_state_witness_for_this_or_next_epoch(me, block.header())?;
+        // The exported execution artifacts include the state read while
+        // applying the chunks.
+        let should_save_state_transition_data = self.exports_execution_artifacts()
+            || self.should_produce_state_witness_for_this_or_next_epoch(me, block.header())?;
+        let prev_final_head = self.chain_store.final_head()?;
         let mut chain_update = self.chain_update();
         let new_head = chain_update.postprocess_block(
             &block,
@@ -1932,6 +2048,9 @@ impl Chain {
             apply_results,
             should_save_state_transition_data,
         )?;
+        if let Err(err) = chain_update.record_endorsement_stats(&prev_final_head) {
+            tracing::error!(target: "chain", ?err, "failed to record endorsement stats");
+        }
         chain_update.commit()?;
         Ok(new_head)
     }
@@ -1967,6 +2086,7 @@ impl Chain {
         let shard_layout = self.epoch_manager.get_shard_layout(&epoch_id)?;
 
         let prev_head = self.chain_store.head()?;
+        let prev_final_head = self.chain_store.final_head()?;
         let is_caught_up = block_preprocess_info.is_caught_up;
         let provenance = block_preprocess_info.provenance.clone();
         let block_start_processing_time = block_preprocess_info.block_start_processing_time;
@@ -1984,12 +2104,16 @@ impl Chain {
         let new_head =
             match self.postprocess_block_only(me, &block, block_preprocess_info, apply_results) {
                 Err(err) => {
-                    self.maybe_mark_block_invalid(*block.hash(), &err);
+                    self.maybe_mark_block_invalid(block.header(), &err);
                     self.blocks_delay_tracker.mark_block_errored(&block_hash, err.to_string());
                     return Err(err);
                 }
                 Ok(new_head) => new_head,
             };
+        if let Err(err) = self.notify_chain_events(&prev_head, &prev_final_head, &new_head) {
+            tracing::error!(target: "chain", ?err, "failed to notify chain events");
+        }
+        self.export_execution_artifact(&block);
 
         let epoch_id = block.header().epoch_id();
         let mut shards_cares_this_or_next_epoch = vec![];
@@ -2228,7 +2352,7 @@ impl Chain {
         }
 
         // Check if we have already processed this block previously.
-        check_known(self, header.hash())?.map_err(|e| Error::BlockKnown(e))?;
+        check_known(self, header.height(), header.hash())?.map_err(|e| Error::BlockKnown(e))?;
 
         // Delay hitting the db for current chain head until we know this block is not already known.
         let head = self.head()?;
@@ -3005,7 +3129,9 @@ impl Chain {
         let shard_state_header = self.get_state_header(shard_id, sync_hash)?;
         let chunk = shard_state_header.take_chunk();
         let state_root = *chunk.take_header().take_inner().prev_state_root();
-        if !self.runtime_adapter.validate_state_part(&state_root, part_id, data) {
+        let epoch_id = *self.get_block_header(&sync_hash)?.epoch_id();
+        let protocol_version = self.epoch_manager.get_epoch_protocol_version(&epoch_id)?;
+        if !self.runtime_adapter.validate_state_part(&state_root, part_id, data, protocol_version) {
             byzantine_assert!(false);
             return Err(Error::Other(format!(
                 "set_state_part failed: validate_state_part failed. state_root={:?}",
@@ -3136,7 +3262,23 @@ impl Chain {
         }
         blocks_catch_up_state.processed_blocks = processed_blocks;
 
-        for pending_block in blocks_catch_up_state.pending_blocks.drain(..) {
+        let num_blocks_to_schedule = self
+            .catchup_scheduler
+            .num_blocks_to_schedule(
+                blocks_catch_up_state.scheduled_blocks.len(),
+                self.blocks_in_processing.len() > 0,
+            )
+            .min(blocks_catch_up_state.pending_blocks.len());
+        if num_blocks_to_schedule < blocks_catch_up_state.pending_blocks.len() {
+            tracing::debug!(
+                target: "catchup",
+                num_pending_blocks = blocks_catch_up_state.pending_blocks.len(),
+                num_scheduled_blocks = blocks_catch_up_state.scheduled_blocks.len(),
+                "catchup throttled");
+        }
+        let pending_blocks: Vec<CryptoHash> =
+            blocks_catch_up_state.pending_blocks.drain(..num_blocks_to_schedule).collect();
+        for pending_block in pending_blocks {
             let block = self.chain_store.get_block(&pending_block)?.clone();
             let prev_block = self.chain_store.get_block(block.header().prev_hash())?.clone();
 
@@ -3157,6 +3299,7 @@ impl Chain {
                 block_hash: pending_block,
                 block_height: block.header().height(),
                 work,
+                apply_chunks_pool: self.catchup_scheduler.apply_chunks_pool(),
             });
         }
 
@@ -3211,6 +3354,14 @@ impl Chain {
             }
         };
 
+        // Nested delegate actions can't be decoded by nodes which don't support
+        // them, so a chunk containing them is invalid the same way.
+        if !ProtocolFeature::NestedDelegateActions.enabled(protocol_version)
+            && chunk.transactions().iter().any(has_nested_delegate_action)
+        {
+            return Err(Error::InvalidTransactions);
+        }
+
         Ok(())
     }
 
@@ -3233,6 +3384,7 @@ impl Chain {
     /// about block necessary for processing shard update.
     pub fn get_apply_chunk_block_context(
         epoch_manager: &dyn EpochManagerAdapter,
+        chain_store: &dyn ChainStoreAccess,
         block: &Block,
         prev_block_header: &BlockHeader,
         is_new_chunk: bool,
@@ -3250,15 +3402,86 @@ impl Chain {
             prev_block_header.next_gas_price()
         };
         let congestion_info = block.block_congestion_info();
+        let recent_block_headers =
+            Self::get_recent_block_headers(epoch_manager, chain_store, block_header)?;
 
         Ok(ApplyChunkBlockContext::from_header(
             block_header,
             gas_price,
             congestion_info,
             block.block_bandwidth_requests(),
+            recent_block_headers,
         ))
     }
 
+    /// Returns the headers of the `NUM_RECENT_BLOCK_HEADERS` blocks preceding
+    /// the given block, most recent first, which contracts read with the
+    /// `block_header` host function. Empty before the protocol feature enabling
+    /// the host function.
+    pub fn get_recent_block_headers(
+        epoch_manager: &dyn EpochManagerAdapter,
+        chain_store: &dyn ChainStoreAccess,
+        block_header: &BlockHeader,
+    ) -> Result<Arc<[RecentBlockHeader]>, Error> {
+        let protocol_version = epoch_manager.get_epoch_protocol_version(block_header.epoch_id())?;
+        if !ProtocolFeature::BlockHeaderHostFunction.enabled(protocol_version) {
+            return Ok(Default::default());
+        }
+        let mut headers = Vec::with_capacity(NUM_RECENT_BLOCK_HEADERS);
+        let mut header = block_header.clone();
+        while headers.len() < NUM_RECENT_BLOCK_HEADERS && !header.is_genesis() {
+            header = chain_store.get_previous_header(&header)?;
+            headers.push(RecentBlockHeader {
+                height: header.height(),
+                hash: *header.hash(),
+                timestamp: header.raw_timestamp(),
+                epoch_id: header.epoch_id().0,
+            });
+        }
+        Ok(headers.into())
+    }
+
+    /// Re-applies the chunk of `shard_id` included in the block `block_hash`
+    /// and returns the events traced while executing its receipts. The result of
+    /// the application is discarded.
+    ///
+    /// Works as long as the state the chunk was applied to hasn't been garbage
+    /// collected.
+    pub fn trace_chunk_application(
+        &self,
+        block_hash: &CryptoHash,
+        shard_id: ShardId,
+    ) -> Result<Vec<ExecutionTraceEventView>, Error> {
+        let block = self.get_block(block_hash)?;
+        let prev_block = self.get_block(block.header().prev_hash())?;
+        let NewChunkData {
+            chunk_header,
+            transactions,
+            receipts,
+            block: mut block_context,
+            is_first_block_with_chunk_of_version,
+            ..
+        } = self.new_chunk_replay_data(&block, &prev_block, shard_id)?;
+        let tracer = Arc::new(CollectingTracer::default());
+        block_context.execution_tracer = Some(tracer.clone());
+
+        self.runtime_adapter.apply_chunk(
+            RuntimeStorageConfig::new(chunk_header.prev_state_root(), false),
+            ApplyChunkReason::ViewTrackedShard,
+            ApplyChunkShardContext {
+                shard_id,
+                last_validator_proposals: chunk_header.prev_validator_proposals(),
+                gas_limit: chunk_header.gas_limit(),
+                is_new_chunk: true,
+                is_first_block_with_chunk_of_version,
+            },
+            block_context,
+            &receipts,
+            &transactions,
+        )?;
+        Ok(tracer.take_events())
+    }
+
     fn block_catch_up_postprocess(
         &mut self,
         me: &Option<AccountId>,
@@ -3601,7 +3824,7 @@ impl Chain {
         me: &Option<AccountId>,
         block_header: &BlockHeader,
     ) -> Result<bool, Error> {
-        if cfg!(feature = "shadow_chunk_validation") {
+        if cfg!(feature = "shadow_chunk_validation") || self.shadow_validation {
             return Ok(true);
         }
         let epoch_id = block_header.epoch_id();
@@ -3637,6 +3860,7 @@ impl Chain {
 
         let epoch_id = block.header().epoch_id();
         let shard_layout = self.epoch_manager.get_shard_layout(&epoch_id)?;
+        self.spawn_precompile_transactions_contracts(block, &shard_layout);
 
         let mut maybe_jobs = vec![];
         for (shard_index, (chunk_header, prev_chunk_header)) in
@@ -3689,6 +3913,67 @@ impl Chain {
         Ok(jobs)
     }
 
+    /// Starts compiling the contracts called by transactions of the new
+    /// chunks in `block` before the chunks are applied.
+    ///
+    /// Receipts created by these transactions are applied either in this
+    /// block or, for most of them, in the next one on the receiver's shard.
+    /// Compiling their contracts in the background keeps cold compilations
+    /// off the critical path of applying chunks. The compiled contract cache
+    /// is shared by all shards, so a contract called from several shards is
+    /// compiled once.
+    fn spawn_precompile_transactions_contracts(&self, block: &Block, shard_layout: &ShardLayout) {
+        let mut transactions = vec![];
+        for chunk_header in block.chunks().iter_deprecated() {
+            if !chunk_header.is_new_chunk(block.header().height()) {
+                continue;
+            }
+            let Ok(chunk) = self.get_chunk(&chunk_header.chunk_hash()) else {
+                continue;
+            };
+            transactions.extend(
+                chunk
+                    .transactions()
+                    .iter()
+                    .filter(|transaction| {
+                        transaction
+                            .transaction
+                            .actions()
+                            .iter()
+                            .any(|action| matches!(action, Action::FunctionCall(_)))
+                    })
+                    .cloned(),
+            );
+        }
+        if transactions.is_empty() {
+            return;
+        }
+
+        let prev_hash = *block.header().prev_hash();
+        let state_roots: HashMap<_, _> = shard_layout
+            .shard_uids()
+            .filter_map(|shard_uid| {
+                let chunk_extra = self.get_chunk_extra(&prev_hash, &shard_uid).ok()?;
+                Some((shard_uid.shard_id(), *chunk_extra.state_root()))
+            })
+            .collect();
+        if state_roots.is_empty() {
+            return;
+        }
+
+        let epoch_id = *block.header().epoch_id();
+        let runtime_adapter = self.runtime_adapter.clone();
+        self.apply_chunks_spawner.spawn("precompile_transactions_contracts", move || {
+            if let Err(err) = runtime_adapter.precompile_transactions_contracts(
+                &epoch_id,
+                &state_roots,
+                &transactions,
+            ) {
+                debug!(target: "chain", ?err, ?prev_hash, "failed to precompile contracts");
+            }
+        });
+    }
+
     fn get_shard_context(
         &self,
         me: &Option<AccountId>,
@@ -3732,6 +4017,7 @@ impl Chain {
         let shard_update_reason = if shard_context.should_apply_chunk {
             let block_context = Self::get_apply_chunk_block_context(
                 self.epoch_manager.as_ref(),
+                &self.chain_store,
                 &block,
                 prev_block.header(),
                 is_new_chunk,
@@ -3751,7 +4037,7 @@ impl Chain {
                     self.epoch_manager.as_ref(),
                     prev_hash,
                     prev_chunk_extra.as_ref(),
-                    prev_chunk_height_included,
+                    prev_chunk_header,
                     &chunk_header,
                 )
                 .map_err(|err| {
@@ -4320,9 +4606,18 @@ impl Chain {
         self.chain_store.is_height_processed(height)
     }
 
-    #[inline]
-    pub fn is_block_invalid(&self, hash: &CryptoHash) -> bool {
-        self.invalid_blocks.contains(hash)
+    /// Returns the protocol version which the block at `height` must vote for
+    /// at least, if an upgrade was scheduled at a fixed height before it.
+    pub fn scheduled_protocol_version(&self, height: BlockHeight) -> Option<ProtocolVersion> {
+        get_scheduled_protocol_version(&self.protocol_upgrade_heights, height)
+    }
+
+    /// Returns whether the block was found invalid before. Errors reading the
+    /// store are treated as the block not being known to be invalid.
+    pub fn is_block_invalid(&self, height: BlockHeight, hash: &CryptoHash) -> bool {
+        self.chain_store
+            .get_invalid_block(height, hash)
+            .is_ok_and(|invalid_block| invalid_block.is_some())
     }
 
     /// Check that sync_hash matches the one we expect for the epoch containing that block.
@@ -4382,6 +4677,38 @@ impl Chain {
         Ok(headers)
     }
 
+    /// Returns the headers of the canonical chain at heights
+    /// `start_height..start_height + max_headers` up to the block
+    /// `anchor_hash`, with a proof of the last of them in the block merkle
+    /// root of the anchor. Returns `None` if the anchor isn't on the
+    /// canonical chain or there are no headers in these heights.
+    pub fn retrieve_headers_range(
+        &self,
+        request: &BlockHeadersRangeRequest,
+        max_headers: u64,
+    ) -> Result<Option<BlockHeadersRange>, Error> {
+        let anchor = self.get_block_header(&request.anchor_hash)?;
+        if self.get_block_header_by_height(anchor.height())?.hash() != anchor.hash() {
+            return Ok(None);
+        }
+        let end_height = request.start_height.saturating_add(max_headers).min(anchor.height() + 1);
+        let mut headers = vec![];
+        for height in request.start_height..end_height {
+            if let Ok(header) = self.get_block_header_by_height(height) {
+                headers.push(header);
+            }
+        }
+        let Some(last) = headers.last() else {
+            return Ok(None);
+        };
+        let proof = if last.hash() == anchor.hash() {
+            vec![]
+        } else {
+            self.compute_past_block_proof_in_merkle_tree_of_later_block(last.hash(), anchor.hash())?
+        };
+        Ok(Some(BlockHeadersRange { anchor, headers, proof }))
+    }
+
     /// Returns a vector of chunk headers, each of which corresponds to the chunk in the `prev_block`
     /// This function is important when the block after `prev_block` has different number of chunks
     /// from `prev_block` in cases of resharding.
@@ -4427,7 +4754,7 @@ impl Chain {
     ) -> HashMap<ShardId, Vec<Receipt>> {
         let mut result = HashMap::new();
         for receipt in receipts {
-            let shard_id = shard_layout.account_id_to_shard_id(receipt.receiver_id());
+            let shard_id = receipt.receiver_shard_id(shard_layout);
             let entry = result.entry(shard_id).or_insert_with(Vec::new);
             entry.push(receipt)
         }
@@ -4450,9 +4777,15 @@ impl Chain {
         }
         let mut cache = HashMap::new();
         for receipt in receipts {
-            let &mut shard_id = cache
-                .entry(receipt.receiver_id())
-                .or_insert_with(|| shard_layout.account_id_to_shard_id(receipt.receiver_id()));
+            let shard_id = match receipt.receipt() {
+                // Distribution receipts are routed by their target shard.
+                ReceiptEnum::GlobalContractDistribution(_) => {
+                    receipt.receiver_shard_id(shard_layout)
+                }
+                _ => *cache
+                    .entry(receipt.receiver_id())
+                    .or_insert_with(|| shard_layout.account_id_to_shard_id(receipt.receiver_id())),
+            };
             // This unwrap should be safe as we pre-populated the map with all
             // valid shard ids.
             let shard_index = shard_layout.get_shard_index(shard_id).unwrap();
@@ -4481,7 +4814,22 @@ impl Chain {
     }
 }
 
+/// Creates the thread pool applying chunks, `num_threads` is the maximum number
+/// of shards applied in parallel and 0 means one thread per CPU.
+pub(crate) fn new_apply_chunks_pool(num_threads: usize) -> Result<Arc<rayon::ThreadPool>, Error> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .thread_name(|i| format!("apply_chunks_{i}"))
+        .build()
+        .map_err(|err| Error::Other(format!("failed to create apply chunks pool: {err}")))?;
+    Ok(Arc::new(pool))
+}
+
+/// Applies the chunks of a block on `pool`, at most as many in parallel as the
+/// pool has threads. Results are returned in the order of `work` regardless of
+/// the order in which the shards finish.
 pub fn do_apply_chunks(
+    pool: &rayon::ThreadPool,
     block_hash: CryptoHash,
     block_height: BlockHeight,
     work: Vec<UpdateShardJob>,
@@ -4489,13 +4837,15 @@ pub fn do_apply_chunks(
     let parent_span =
         tracing::debug_span!(target: "chain", "do_apply_chunks", block_height, %block_hash)
             .entered();
-    work.into_par_iter()
-        .map(|(shard_id, task)| {
-            // As chunks can be processed in parallel, make sure they are all tracked as children of
-            // a single span.
-            (shard_id, task(&parent_span))
-        })
-        .collect()
+    pool.install(|| {
+        work.into_par_iter()
+            .map(|(shard_id, task)| {
+                // As chunks can be processed in parallel, make sure they are all tracked as children of
+                // a single span.
+                (shard_id, task(&parent_span))
+            })
+            .collect()
+    })
 }
 
 pub fn collect_receipts<'a, T>(receipt_proofs: T) -> Vec<Receipt>
@@ -4520,6 +4870,8 @@ pub struct BlockCatchUpRequest {
     pub block_hash: CryptoHash,
     pub block_height: BlockHeight,
     pub work: Vec<UpdateShardJob>,
+    /// Pool of the chain applying the chunks of `work`.
+    pub apply_chunks_pool: Arc<rayon::ThreadPool>,
 }
 
 // Skip `work`, because displaying functions is not possible.
@@ -4618,3 +4970,12 @@ impl Chain {
             .collect()
     }
 }
+
+fn has_nested_delegate_action(transaction: &SignedTransaction) -> bool {
+    transaction.transaction.actions().iter().any(|action| match action {
+        Action::Delegate(signed_delegate_action) => {
+            signed_delegate_action.delegate_action.nesting_depth() > 1
+        }
+        _ => false,
+    })
+}
@@ -0,0 +1,106 @@
+//! Notifications about changes of the canonical chain.
+//!
+//! Services following the chain, e.g. the indexer or RPC subscriptions, can
+//! register a [`ChainEventSubscriber`] with [`Chain::subscribe_chain_events`]
+//! instead of polling the head and reconstructing reorgs from block headers.
+//! Subscribers are called synchronously on the thread processing blocks, after
+//! the block is committed, so they must not block.
+
+use crate::Chain;
+use near_chain_primitives::Error;
+use near_primitives::block::Tip;
+use near_primitives::hash::CryptoHash;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainEvent {
+    /// The head of the chain changed to `new_head`. `abandoned` are the blocks
+    /// of the previous canonical chain which are not on the new one, from the
+    /// highest to the lowest. It is empty when the head was extended.
+    HeadSwitch { old_head: Tip, new_head: Tip, abandoned: Vec<CryptoHash> },
+    /// A new block became final.
+    NewFinalBlock(Tip),
+    /// A new block became doomslug-final, i.e. can only be reverted if a
+    /// validator gets slashed.
+    NewDoomslugFinalBlock(Tip),
+}
+
+pub trait ChainEventSubscriber: Send + Sync {
+    fn on_chain_event(&self, event: &ChainEvent);
+}
+
+impl Chain {
+    /// Registers `subscriber` to be notified of the changes of the canonical
+    /// chain.
+    pub fn subscribe_chain_events(&mut self, subscriber: Arc<dyn ChainEventSubscriber>) {
+        self.chain_event_subscribers.push(subscriber);
+    }
+
+    /// Notifies the subscribers of the changes caused by accepting a block,
+    /// given the head and final head before the block was accepted.
+    pub(crate) fn notify_chain_events(
+        &self,
+        prev_head: &Tip,
+        prev_final_head: &Tip,
+        new_head: &Option<Tip>,
+    ) -> Result<(), Error> {
+        if self.chain_event_subscribers.is_empty() {
+            return Ok(());
+        }
+        let mut events = vec![];
+        if let Some(new_head) = new_head {
+            events.push(ChainEvent::HeadSwitch {
+                old_head: prev_head.clone(),
+                new_head: new_head.clone(),
+                abandoned: self.abandoned_blocks(prev_head, new_head)?,
+            });
+        }
+        let final_head = self.final_head()?;
+        if final_head.height > prev_final_head.height {
+            events.push(ChainEvent::NewFinalBlock(final_head));
+        }
+        if let Some(new_head) = new_head {
+            let prev_ds_final = self.doomslug_final_block(prev_head)?;
+            let ds_final = self.doomslug_final_block(new_head)?;
+            if let Some(ds_final) = ds_final {
+                if ds_final.height > prev_ds_final.map_or(0, |tip| tip.height) {
+                    events.push(ChainEvent::NewDoomslugFinalBlock(ds_final));
+                }
+            }
+        }
+        for event in &events {
+            for subscriber in &self.chain_event_subscribers {
+                subscriber.on_chain_event(event);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the last doomslug-final block of the chain ending at `head`,
+    /// `None` if it is the genesis.
+    fn doomslug_final_block(&self, head: &Tip) -> Result<Option<Tip>, Error> {
+        let header = self.get_block_header(&head.last_block_hash)?;
+        let ds_final_hash = header.last_ds_final_block();
+        if ds_final_hash == &CryptoHash::default() || ds_final_hash == self.genesis().hash() {
+            return Ok(None);
+        }
+        Ok(Some(Tip::from_header(&self.get_block_header(ds_final_hash)?)))
+    }
+
+    /// Returns the blocks of the chain ending at `old_head` which are not on
+    /// the chain ending at `new_head`, from the highest.
+    fn abandoned_blocks(&self, old_head: &Tip, new_head: &Tip) -> Result<Vec<CryptoHash>, Error> {
+        let mut abandoned = vec![];
+        let mut old = self.get_block_header(&old_head.last_block_hash)?;
+        let mut new = self.get_block_header(&new_head.last_block_hash)?;
+        while old.hash() != new.hash() {
+            if old.height() >= new.height() {
+                abandoned.push(*old.hash());
+                old = self.get_block_header(old.prev_hash())?;
+            } else {
+                new = self.get_block_header(new.prev_hash())?;
+            }
+        }
+        Ok(abandoned)
+    }
+}
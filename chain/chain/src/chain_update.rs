@@ -1,6 +1,7 @@
 use crate::approval_verification::verify_approvals_and_threshold_orphan;
 use crate::block_processing_utils::BlockPreprocessInfo;
 use crate::chain::collect_receipts_from_response;
+use crate::fork_choice::ForkChoice;
 use crate::metrics::{SHARD_LAYOUT_NUM_SHARDS, SHARD_LAYOUT_VERSION};
 use crate::store::{ChainStore, ChainStoreAccess, ChainStoreUpdate};
 use crate::types::{
@@ -33,6 +34,7 @@ use tracing::{debug, info, warn};
 pub struct ChainUpdate<'a> {
     epoch_manager: Arc<dyn EpochManagerAdapter>,
     runtime_adapter: Arc<dyn RuntimeAdapter>,
+    fork_choice: Arc<dyn ForkChoice>,
     chain_store_update: ChainStoreUpdate<'a>,
     doomslug_threshold_mode: DoomslugThresholdMode,
     #[allow(unused)]
@@ -44,6 +46,7 @@ impl<'a> ChainUpdate<'a> {
         chain_store: &'a mut ChainStore,
         epoch_manager: Arc<dyn EpochManagerAdapter>,
         runtime_adapter: Arc<dyn RuntimeAdapter>,
+        fork_choice: Arc<dyn ForkChoice>,
         doomslug_threshold_mode: DoomslugThresholdMode,
         transaction_validity_period: BlockHeightDelta,
     ) -> Self {
@@ -51,6 +54,7 @@ impl<'a> ChainUpdate<'a> {
         Self::new_impl(
             epoch_manager,
             runtime_adapter,
+            fork_choice,
             doomslug_threshold_mode,
             transaction_validity_period,
             chain_store_update,
@@ -60,6 +64,7 @@ impl<'a> ChainUpdate<'a> {
     fn new_impl(
         epoch_manager: Arc<dyn EpochManagerAdapter>,
         runtime_adapter: Arc<dyn RuntimeAdapter>,
+        fork_choice: Arc<dyn ForkChoice>,
         doomslug_threshold_mode: DoomslugThresholdMode,
         transaction_validity_period: BlockHeightDelta,
         chain_store_update: ChainStoreUpdate<'a>,
@@ -67,12 +72,23 @@ impl<'a> ChainUpdate<'a> {
         ChainUpdate {
             epoch_manager,
             runtime_adapter,
+            fork_choice,
             chain_store_update,
             doomslug_threshold_mode,
             transaction_validity_period,
         }
     }
 
+    /// Records the chunk endorsements of the blocks which became final since
+    /// the final head was `prev_final_head`.
+    pub(crate) fn record_endorsement_stats(&mut self, prev_final_head: &Tip) -> Result<(), Error> {
+        crate::stateless_validation::endorsement_stats::record_endorsement_stats(
+            &mut self.chain_store_update,
+            self.epoch_manager.as_ref(),
+            prev_final_head,
+        )
+    }
+
     /// Commit changes to the chain into the database.
     pub fn commit(self) -> Result<(), Error> {
         self.chain_store_update.commit()
@@ -369,7 +385,7 @@ impl<'a> ChainUpdate<'a> {
         header: &BlockHeader,
     ) -> Result<Option<Tip>, Error> {
         let header_head = self.chain_store_update.header_head()?;
-        if header.height() > header_head.height {
+        if self.fork_choice.is_better_head(&header_head, header) {
             let tip = Tip::from_header(header);
             self.chain_store_update.save_header_head_if_not_challenged(&tip)?;
             debug!(target: "chain", "Header head updated to {} at {}", tip.last_block_hash, tip.height);
@@ -399,13 +415,13 @@ impl<'a> ChainUpdate<'a> {
     }
 
     /// Directly updates the head if we've just appended a new block to it or handle
-    /// the situation where the block has higher height to have a fork
+    /// the situation where the fork choice rule prefers the fork of the block
     fn update_head(&mut self, header: &BlockHeader) -> Result<Option<Tip>, Error> {
-        // if we made a fork with higher height than the head (which should also be true
-        // when extending the head), update it
+        // if we made a fork preferred to the head (which with the default rule is also
+        // true when extending the head), update it
         self.update_final_head_from_block(header)?;
         let head = self.chain_store_update.head()?;
-        if header.height() > head.height {
+        if self.fork_choice.is_better_head(&head, header) {
             let tip = Tip::from_header(header);
 
             self.chain_store_update.save_body_head(&tip)?;
@@ -528,6 +544,11 @@ impl<'a> ChainUpdate<'a> {
         let is_first_block_with_chunk_of_version = false;
 
         let block = self.chain_store_update.get_block(block_header.hash())?;
+        let recent_block_headers = Chain::get_recent_block_headers(
+            self.epoch_manager.as_ref(),
+            &self.chain_store_update,
+            &block_header,
+        )?;
 
         let apply_result = self.runtime_adapter.apply_chunk(
             RuntimeStorageConfig::new(chunk_header.prev_state_root(), true),
@@ -549,6 +570,8 @@ impl<'a> ChainUpdate<'a> {
                 random_seed: *block_header.random_value(),
                 congestion_info: block.block_congestion_info(),
                 bandwidth_requests: block.block_bandwidth_requests(),
+                recent_block_headers,
+                execution_tracer: None,
             },
             &receipts,
             chunk.transactions(),
@@ -657,6 +680,11 @@ impl<'a> ChainUpdate<'a> {
                 prev_block_header.next_gas_price(),
                 block.block_congestion_info(),
                 block.block_bandwidth_requests(),
+                Chain::get_recent_block_headers(
+                    self.epoch_manager.as_ref(),
+                    &self.chain_store_update,
+                    &block_header,
+                )?,
             ),
             &[],
             &[],
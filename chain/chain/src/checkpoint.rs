@@ -0,0 +1,206 @@
+//! Checkpoints of the chain state signed with the node key.
+//!
+//! A checkpoint records the heads of the chain and of the flat storages, and
+//! the epoch sync proof, at the time it was written. On startup the latest
+//! checkpoint is compared with the store, which tells the operator whether a
+//! node that crashed left the store in a consistent state, without scanning
+//! the chain.
+
+use borsh::BorshDeserialize;
+use near_chain_primitives::Error;
+use near_crypto::{PublicKey, SecretKey};
+use near_primitives::block::Tip;
+use near_primitives::checkpoint::{ChainCheckpoint, FlatStorageHead, SignedChainCheckpoint};
+use near_primitives::hash::CryptoHash;
+use near_primitives::shard_layout::ShardUId;
+use near_primitives::utils::index_to_bytes;
+use near_store::flat::FlatStorageStatus;
+use near_store::{DBCol, Store, CHAIN_CHECKPOINT_KEY, FINAL_HEAD_KEY, HEAD_KEY};
+
+fn get_tip(store: &Store, key: &[u8]) -> Result<Tip, Error> {
+    store
+        .get_ser(DBCol::BlockMisc, key)?
+        .ok_or_else(|| Error::DBNotFoundErr(format!("{} is not set", String::from_utf8_lossy(key))))
+}
+
+fn get_flat_storage_heads(store: &Store) -> Result<Vec<FlatStorageHead>, Error> {
+    let mut heads = vec![];
+    for item in store.iter(DBCol::FlatStorageStatus) {
+        let (key, value) = item?;
+        let shard_uid = ShardUId::try_from(key.as_ref())
+            .map_err(|err| Error::Other(format!("invalid flat storage status key: {err}")))?;
+        if let FlatStorageStatus::Ready(status) = FlatStorageStatus::try_from_slice(&value)? {
+            heads.push(FlatStorageHead {
+                shard_uid,
+                block_hash: status.flat_head.hash,
+                height: status.flat_head.height,
+            });
+        }
+    }
+    Ok(heads)
+}
+
+fn get_epoch_sync_proof_hash(store: &Store) -> Result<Option<CryptoHash>, Error> {
+    Ok(store.get(DBCol::EpochSyncProof, &[])?.map(|proof| CryptoHash::hash_bytes(&proof)))
+}
+
+/// Records the current state of the chain in the store.
+pub fn make_checkpoint(store: &Store) -> Result<ChainCheckpoint, Error> {
+    Ok(ChainCheckpoint {
+        head: get_tip(store, HEAD_KEY)?,
+        final_head: get_tip(store, FINAL_HEAD_KEY)?,
+        flat_storage_heads: get_flat_storage_heads(store)?,
+        epoch_sync_proof_hash: get_epoch_sync_proof_hash(store)?,
+    })
+}
+
+/// Writes a checkpoint of the current state of the chain signed with
+/// `secret_key`, replacing the previous one.
+pub fn save_checkpoint(store: &Store, secret_key: &SecretKey) -> Result<ChainCheckpoint, Error> {
+    let checkpoint = make_checkpoint(store)?;
+    let signed = SignedChainCheckpoint::new(checkpoint.clone(), secret_key);
+    let mut store_update = store.store_update();
+    store_update.set_ser(DBCol::BlockMisc, CHAIN_CHECKPOINT_KEY, &signed)?;
+    store_update.commit()?;
+    Ok(checkpoint)
+}
+
+pub fn get_checkpoint(store: &Store) -> Result<Option<SignedChainCheckpoint>, Error> {
+    Ok(store.get_ser(DBCol::BlockMisc, CHAIN_CHECKPOINT_KEY)?)
+}
+
+fn is_canonical(store: &Store, hash: &CryptoHash, height: u64) -> Result<bool, Error> {
+    let canonical: Option<CryptoHash> =
+        store.get_ser(DBCol::BlockHeight, &index_to_bytes(height))?;
+    Ok(canonical.as_ref() == Some(hash))
+}
+
+fn has_header(store: &Store, hash: &CryptoHash) -> Result<bool, Error> {
+    Ok(store.exists(DBCol::BlockHeader, hash.as_ref())?)
+}
+
+/// Checks that the store is consistent with `checkpoint`, which must be
+/// signed with `public_key`. Returns the problems found, empty if none.
+///
+/// The store may have progressed since the checkpoint was written, but it
+/// must not have lost anything the checkpoint refers to.
+pub fn validate_checkpoint(
+    store: &Store,
+    checkpoint: &SignedChainCheckpoint,
+    public_key: &PublicKey,
+) -> Result<Vec<String>, Error> {
+    let mut problems = vec![];
+    if &checkpoint.public_key != public_key {
+        problems.push(format!(
+            "checkpoint signed with {}, expected {}",
+            checkpoint.public_key, public_key
+        ));
+    }
+    if !checkpoint.verify() {
+        problems.push("invalid checkpoint signature".to_string());
+    }
+
+    let recorded = &checkpoint.checkpoint;
+    let final_head = get_tip(store, FINAL_HEAD_KEY)?;
+    let head = get_tip(store, HEAD_KEY)?;
+    if final_head.height < recorded.final_head.height {
+        problems.push(format!(
+            "final head at height {} is below the checkpoint final head at height {}",
+            final_head.height, recorded.final_head.height
+        ));
+    }
+    if head.height < recorded.head.height {
+        problems.push(format!(
+            "head at height {} is below the checkpoint head at height {}",
+            head.height, recorded.head.height
+        ));
+    }
+    let recorded_final_hash = &recorded.final_head.last_block_hash;
+    if !has_header(store, recorded_final_hash)? {
+        problems.push(format!("checkpoint final block {recorded_final_hash} is missing"));
+    } else if !is_canonical(store, recorded_final_hash, recorded.final_head.height)? {
+        problems.push(format!("checkpoint final block {recorded_final_hash} is not canonical"));
+    }
+
+    let flat_storage_heads = get_flat_storage_heads(store)?;
+    for recorded_head in &recorded.flat_storage_heads {
+        let shard_uid = recorded_head.shard_uid;
+        let Some(flat_head) = flat_storage_heads.iter().find(|head| head.shard_uid == shard_uid)
+        else {
+            problems.push(format!("flat storage of {shard_uid} is no longer ready"));
+            continue;
+        };
+        if flat_head.height < recorded_head.height {
+            problems.push(format!(
+                "flat storage head of {shard_uid} at height {} is below the checkpoint height {}",
+                flat_head.height, recorded_head.height
+            ));
+        }
+        if !has_header(store, &flat_head.block_hash)? {
+            problems.push(format!(
+                "flat storage head block {} of {shard_uid} is missing",
+                flat_head.block_hash
+            ));
+        }
+    }
+
+    if recorded.epoch_sync_proof_hash.is_some() && get_epoch_sync_proof_hash(store)?.is_none() {
+        problems.push("epoch sync proof is missing".to_string());
+    }
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_checkpoint, save_checkpoint, validate_checkpoint};
+    use near_crypto::{KeyType, SecretKey};
+    use near_primitives::block::Tip;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::types::EpochId;
+    use near_primitives::utils::index_to_bytes;
+    use near_store::test_utils::create_test_store;
+    use near_store::{DBCol, Store, FINAL_HEAD_KEY, HEAD_KEY};
+
+    fn set_head(store: &Store, height: u64) {
+        let hash = CryptoHash::hash_borsh(height);
+        let tip = Tip {
+            height,
+            last_block_hash: hash,
+            prev_block_hash: CryptoHash::default(),
+            epoch_id: EpochId::default(),
+            next_epoch_id: EpochId::default(),
+        };
+        let mut store_update = store.store_update();
+        store_update.set_ser(DBCol::BlockMisc, HEAD_KEY, &tip).unwrap();
+        store_update.set_ser(DBCol::BlockMisc, FINAL_HEAD_KEY, &tip).unwrap();
+        store_update.set_ser(DBCol::BlockHeight, &index_to_bytes(height), &hash).unwrap();
+        store_update.set(DBCol::BlockHeader, hash.as_ref(), &[]);
+        store_update.commit().unwrap();
+    }
+
+    #[test]
+    fn test_validate_checkpoint() {
+        let store = create_test_store();
+        let secret_key = SecretKey::from_seed(KeyType::ED25519, "node");
+        set_head(&store, 10);
+        save_checkpoint(&store, &secret_key).unwrap();
+        let checkpoint = get_checkpoint(&store).unwrap().unwrap();
+        set_head(&store, 12);
+        assert!(validate_checkpoint(&store, &checkpoint, &secret_key.public_key())
+            .unwrap()
+            .is_empty());
+
+        let other_key = SecretKey::from_seed(KeyType::ED25519, "other");
+        assert_eq!(
+            validate_checkpoint(&store, &checkpoint, &other_key.public_key()).unwrap().len(),
+            1
+        );
+
+        // The head went back, e.g. because the last writes were lost.
+        set_head(&store, 8);
+        assert_eq!(
+            validate_checkpoint(&store, &checkpoint, &secret_key.public_key()).unwrap().len(),
+            2
+        );
+    }
+}
@@ -672,6 +672,25 @@ impl Doomslug {
         let _ = self.on_approval_message_internal(approval, stakes);
     }
 
+    /// Returns whether the block at `target_height` on top of `prev_hash` has
+    /// endorsements from 2/3 of the stake. Unlike `ready_to_produce_block`,
+    /// `prev_hash` doesn't have to be the tip.
+    pub fn has_enough_endorsements(
+        &mut self,
+        prev_hash: &CryptoHash,
+        target_height: BlockHeight,
+    ) -> bool {
+        self.approval_tracking
+            .get_mut(&target_height)
+            .and_then(|trackers| {
+                trackers.approval_trackers.get_mut(&ApprovalInner::Endorsement(*prev_hash))
+            })
+            .is_some_and(|tracker| {
+                tracker.get_block_production_readiness()
+                    != DoomslugBlockProductionReadiness::NotReady
+            })
+    }
+
     /// Gets the current status of approvals for a given height.
     /// It will only work for heights that we have in memory, that is that are not older than MAX_HEIGHTS_BEFORE_TO_STORE_APPROVALS
     /// blocks from the head.
@@ -0,0 +1,260 @@
+//! Export of the execution of every applied block, e.g. for zk-prover
+//! pipelines and audit tooling.
+//!
+//! After a block is applied, everything needed to re-execute it outside of
+//! the node is collected into a [`BlockExecutionArtifact`]: the block, and for
+//! every shard applied by the node, the transactions, incoming receipts, the
+//! state read while applying the chunk, the outcomes and the result. The
+//! artifact is collected from the store and handed over to an
+//! [`ExecutionArtifactSink`] on a separate thread, so neither delays block
+//! processing. If the exporter falls behind by more than the queue size,
+//! blocks are dropped.
+//!
+//! While the export is enabled, the state read while applying chunks is
+//! saved for all blocks, not only the ones the node needs for state
+//! witnesses.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_chain_primitives::Error;
+use near_epoch_manager::EpochManagerAdapter;
+use near_primitives::block::Block;
+use near_primitives::challenge::PartialState;
+use near_primitives::hash::CryptoHash;
+use near_primitives::receipt::Receipt;
+use near_primitives::sharding::ShardChunkHeader;
+use near_primitives::stateless_validation::stored_chunk_state_transition_data::StoredChunkStateTransitionData;
+use near_primitives::transaction::{
+    ExecutionOutcomeWithId, ExecutionOutcomeWithIdAndProof, SignedTransaction,
+};
+use near_primitives::types::chunk_extra::ChunkExtra;
+use near_primitives::types::{BlockHeight, ShardId};
+use near_primitives::utils::get_block_shard_id;
+use near_store::DBCol;
+
+use crate::block_replay::new_chunk_replay_data;
+use crate::{metrics, Chain, ChainStore, ChainStoreAccess};
+
+/// Execution of a block on the shards applied by the node.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct BlockExecutionArtifact {
+    pub block: Block,
+    pub shards: Vec<ShardExecutionArtifact>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ShardExecutionArtifact {
+    pub shard_id: ShardId,
+    pub chunk_header: ShardChunkHeader,
+    /// Whether the block contains a new chunk for the shard. Without a new
+    /// chunk only the implicit state transition is applied, and there are no
+    /// transactions, receipts or outcomes.
+    pub is_new_chunk: bool,
+    pub transactions: Vec<SignedTransaction>,
+    /// Incoming receipts applied with the chunk.
+    pub receipts: Vec<Receipt>,
+    /// State read while applying the chunk, i.e. the main state transition
+    /// of the state witness. Only available if the node recorded it.
+    pub base_state: Option<PartialState>,
+    pub outcomes: Vec<ExecutionOutcomeWithIdAndProof>,
+    pub outgoing_receipts: Vec<Receipt>,
+    /// Result of applying the chunk.
+    pub chunk_extra: ChunkExtra,
+}
+
+impl BlockExecutionArtifact {
+    /// Name of the artifact in the sink, ordered by height.
+    pub fn file_name(height: BlockHeight, block_hash: &CryptoHash) -> String {
+        format!("{height:012}_{block_hash}.borsh")
+    }
+}
+
+/// Destination of the exported artifacts.
+pub trait ExecutionArtifactSink: Send + Sync {
+    /// Stores the borsh-serialized artifact `data` under `file_name`.
+    fn export(&self, file_name: &str, data: &[u8]) -> anyhow::Result<()>;
+}
+
+/// Writes the artifacts to files in `root_dir`.
+pub struct FilesystemArtifactSink {
+    pub root_dir: PathBuf,
+}
+
+impl ExecutionArtifactSink for FilesystemArtifactSink {
+    fn export(&self, file_name: &str, data: &[u8]) -> anyhow::Result<()> {
+        std::fs::create_dir_all(&self.root_dir)?;
+        // Written to a temporary file first, so that readers never see a
+        // partially written artifact.
+        let path = self.root_dir.join(file_name);
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, data)?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+}
+
+/// Collects the artifacts of the applied blocks and hands them over to the
+/// sink on a dedicated thread.
+pub(crate) struct ExecutionArtifactExporter {
+    sender: crossbeam_channel::Sender<Block>,
+}
+
+impl ExecutionArtifactExporter {
+    fn new(
+        chain_store: ChainStore,
+        epoch_manager: Arc<dyn EpochManagerAdapter>,
+        sink: Arc<dyn ExecutionArtifactSink>,
+        queue_size: usize,
+    ) -> Result<Self, Error> {
+        let (sender, receiver) = crossbeam_channel::bounded::<Block>(queue_size);
+        std::thread::Builder::new()
+            .name("execution_artifacts".to_string())
+            .spawn(move || {
+                // Ends when the chain, and with it the sender, is dropped.
+                for block in receiver {
+                    let block_hash = *block.hash();
+                    let file_name =
+                        BlockExecutionArtifact::file_name(block.header().height(), &block_hash);
+                    let result =
+                        collect_execution_artifact(&chain_store, epoch_manager.as_ref(), &block)
+                            .map_err(anyhow::Error::from)
+                            .and_then(|artifact| Ok(borsh::to_vec(&artifact)?))
+                            .and_then(|data| sink.export(&file_name, &data));
+                    match result {
+                        Ok(()) => metrics::EXECUTION_ARTIFACTS_EXPORTED_TOTAL.inc(),
+                        Err(err) => {
+                            metrics::EXECUTION_ARTIFACTS_FAILED_TOTAL.inc();
+                            tracing::warn!(target: "chain", ?block_hash, ?err, "Failed to export execution artifact");
+                        }
+                    }
+                }
+            })
+            .map_err(|err| Error::Other(format!("failed to spawn artifact exporter: {err}")))?;
+        Ok(Self { sender })
+    }
+}
+
+impl Chain {
+    /// Exports the execution of every block applied from now on to `sink`,
+    /// keeping at most `queue_size` blocks waiting for the sink.
+    pub fn set_execution_artifact_sink(
+        &mut self,
+        sink: Arc<dyn ExecutionArtifactSink>,
+        queue_size: usize,
+    ) -> Result<(), Error> {
+        // The exporter reads the artifacts through its own view of the store,
+        // off the thread processing the blocks. It never writes, so whether
+        // it saves trie changes doesn't matter.
+        let chain_store = ChainStore::new(
+            self.chain_store.store().clone(),
+            self.chain_store.get_genesis_height(),
+            false,
+        );
+        self.execution_artifact_exporter = Some(ExecutionArtifactExporter::new(
+            chain_store,
+            self.epoch_manager.clone(),
+            sink,
+            queue_size,
+        )?);
+        Ok(())
+    }
+
+    /// Returns whether the execution of the applied blocks is exported.
+    pub(crate) fn exports_execution_artifacts(&self) -> bool {
+        self.execution_artifact_exporter.is_some()
+    }
+
+    /// Queues the just applied `block` for export, if a sink is set.
+    pub(crate) fn export_execution_artifact(&self, block: &Block) {
+        let Some(exporter) = &self.execution_artifact_exporter else {
+            return;
+        };
+        if exporter.sender.try_send(block.clone()).is_err() {
+            metrics::EXECUTION_ARTIFACTS_DROPPED_TOTAL.inc();
+            tracing::warn!(target: "chain", block_hash = ?block.hash(), "Execution artifact sink is behind, dropping artifact");
+        }
+    }
+
+    /// Returns the execution artifact of the already applied `block`, with
+    /// the shards whose result is stored.
+    pub fn get_execution_artifact(&self, block: &Block) -> Result<BlockExecutionArtifact, Error> {
+        collect_execution_artifact(&self.chain_store, self.epoch_manager.as_ref(), block)
+    }
+}
+
+/// Collects the execution artifact of the already applied `block`, with the
+/// shards whose result is stored.
+fn collect_execution_artifact(
+    chain_store: &ChainStore,
+    epoch_manager: &dyn EpochManagerAdapter,
+    block: &Block,
+) -> Result<BlockExecutionArtifact, Error> {
+    let block_hash = block.hash();
+    let height = block.header().height();
+    let epoch_id = block.header().epoch_id();
+    let prev_block = chain_store.get_block(block.header().prev_hash())?;
+    let mut shards = vec![];
+    for chunk_header in block.chunks().iter_deprecated() {
+        let shard_id = chunk_header.shard_id();
+        let shard_uid = epoch_manager.shard_id_to_uid(shard_id, epoch_id)?;
+        // Shards which aren't tracked by the node are not applied.
+        let Ok(chunk_extra) = chain_store.get_chunk_extra(block_hash, &shard_uid) else {
+            continue;
+        };
+        let is_new_chunk = chunk_header.is_new_chunk(height);
+        let (transactions, receipts, outcomes, outgoing_receipts) = if is_new_chunk {
+            let data =
+                new_chunk_replay_data(chain_store, epoch_manager, block, &prev_block, shard_id)?;
+            (
+                data.transactions,
+                data.receipts,
+                get_outcomes(chain_store, block_hash, shard_id)?,
+                chain_store.get_outgoing_receipts(block_hash, shard_id)?.to_vec(),
+            )
+        } else {
+            (vec![], vec![], vec![], vec![])
+        };
+        let base_state = chain_store
+            .store()
+            .get_ser::<StoredChunkStateTransitionData>(
+                DBCol::StateTransitionData,
+                &get_block_shard_id(block_hash, shard_id),
+            )?
+            .map(|data| match data {
+                StoredChunkStateTransitionData::V1(data) => data.base_state,
+            });
+        shards.push(ShardExecutionArtifact {
+            shard_id,
+            chunk_header: chunk_header.clone(),
+            is_new_chunk,
+            transactions,
+            receipts,
+            base_state,
+            outcomes,
+            outgoing_receipts,
+            chunk_extra: ChunkExtra::clone(&chunk_extra),
+        });
+    }
+    Ok(BlockExecutionArtifact { block: block.clone(), shards })
+}
+
+fn get_outcomes(
+    chain_store: &ChainStore,
+    block_hash: &CryptoHash,
+    shard_id: ShardId,
+) -> Result<Vec<ExecutionOutcomeWithIdAndProof>, Error> {
+    let mut outcomes = vec![];
+    for id in chain_store.get_outcomes_by_block_hash_and_shard_id(block_hash, shard_id)? {
+        let Some(outcome) = chain_store.get_outcome_by_id_and_block_hash(&id, block_hash)? else {
+            continue;
+        };
+        outcomes.push(ExecutionOutcomeWithIdAndProof {
+            proof: outcome.proof,
+            block_hash: *block_hash,
+            outcome_with_id: ExecutionOutcomeWithId { id, outcome: outcome.outcome },
+        });
+    }
+    Ok(outcomes)
+}
@@ -25,7 +25,10 @@ use near_primitives::trie_key::col::{self};
 use near_primitives::trie_key::trie_key_parsers::{
     parse_account_id_from_access_key_key, parse_account_id_from_account_key,
     parse_account_id_from_contract_code_key, parse_account_id_from_contract_data_key,
-    parse_account_id_from_received_data_key, parse_account_id_from_trie_key_with_separator,
+    parse_account_id_from_contract_source_metadata_key,
+    parse_account_id_from_global_contract_deployer_key,
+    parse_account_id_from_migration_grace_period_key, parse_account_id_from_received_data_key,
+    parse_account_id_from_storage_sponsorship_key, parse_account_id_from_trie_key_with_separator,
 };
 #[cfg(feature = "test_features")]
 use near_primitives::types::BlockHeightDelta;
@@ -979,10 +982,41 @@ fn shard_split_handle_key_value(
             store_update,
             parse_account_id_from_received_data_key,
         )?,
+        col::STORAGE_SPONSORSHIP => copy_kv_to_child(
+            &split_params,
+            key,
+            value,
+            store_update,
+            parse_account_id_from_storage_sponsorship_key,
+        )?,
+        col::MIGRATION_GRACE_PERIOD => copy_kv_to_child(
+            &split_params,
+            key,
+            value,
+            store_update,
+            parse_account_id_from_migration_grace_period_key,
+        )?,
+        col::CONTRACT_SOURCE_METADATA => copy_kv_to_child(
+            &split_params,
+            key,
+            value,
+            store_update,
+            parse_account_id_from_contract_source_metadata_key,
+        )?,
+        col::GLOBAL_CONTRACT_DEPLOYER => copy_kv_to_child(
+            &split_params,
+            key,
+            value,
+            store_update,
+            parse_account_id_from_global_contract_deployer_key,
+        )?,
         col::POSTPONED_RECEIPT_ID
         | col::PENDING_DATA_COUNT
         | col::POSTPONED_RECEIPT
-        | col::PROMISE_YIELD_RECEIPT => {
+        | col::PROMISE_YIELD_RECEIPT
+        | col::NAMESPACED_CONTRACT_DATA
+        | col::STORAGE_NAMESPACE_USAGE
+        | col::TOKEN_BALANCE => {
             copy_kv_to_child(&split_params, key, value, store_update, |raw_key: &[u8]| {
                 parse_account_id_from_trie_key_with_separator(
                     key_column_prefix,
@@ -994,7 +1028,9 @@ fn shard_split_handle_key_value(
         col::DELAYED_RECEIPT_OR_INDICES
         | col::PROMISE_YIELD_INDICES
         | col::PROMISE_YIELD_TIMEOUT
-        | col::BANDWIDTH_SCHEDULER_STATE => {
+        | col::PROMISE_YIELD_TIMEOUT_AT
+        | col::BANDWIDTH_SCHEDULER_STATE
+        | col::GLOBAL_CONTRACT_CODE => {
             copy_kv_to_all_children(&split_params, key, value, store_update)
         }
         col::BUFFERED_RECEIPT_INDICES | col::BUFFERED_RECEIPT => {
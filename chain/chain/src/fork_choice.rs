@@ -0,0 +1,24 @@
+//! Rule selecting the head of the chain among the known blocks.
+
+use near_primitives::block::{BlockHeader, Tip};
+
+/// Decides whether a newly accepted block or header becomes the new head.
+///
+/// The rule is applied both to the header head, which also determines the
+/// canonical block at each height, and to the head of the fully processed
+/// blocks. It must never switch to a block behind the final head.
+pub trait ForkChoice: Send + Sync {
+    /// Returns whether the block with `header`, which was just accepted,
+    /// should replace `head`.
+    fn is_better_head(&self, head: &Tip, header: &BlockHeader) -> bool;
+}
+
+/// The default rule: the block with the highest height is the head. On equal
+/// heights the head which was seen first is kept.
+pub struct HighestHeightForkChoice;
+
+impl ForkChoice for HighestHeightForkChoice {
+    fn is_better_head(&self, head: &Tip, header: &BlockHeader) -> bool {
+        header.height() > head.height
+    }
+}
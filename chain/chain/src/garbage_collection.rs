@@ -2,17 +2,19 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::{fmt, io};
 
-use near_chain_configs::GCConfig;
+use near_chain_configs::{GCConfig, GCRetention};
 use near_chain_primitives::Error;
 use near_epoch_manager::EpochManagerAdapter;
-use near_primitives::block::Block;
+use near_primitives::block::{Block, Tip};
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::get_block_shard_uid;
 use near_primitives::state_sync::{StateHeaderKey, StatePartKey};
 use near_primitives::types::{BlockHeight, BlockHeightDelta, EpochId, NumBlocks, ShardId};
 use near_primitives::utils::{get_block_shard_id, get_outcome_id_block_hash, index_to_bytes};
 use near_store::adapter::{StoreAdapter, StoreUpdateAdapter};
-use near_store::{DBCol, KeyForStateChanges, ShardTries, ShardUId};
+use near_store::{
+    DBCol, KeyForStateChanges, ShardTries, ShardUId, HEADER_TAIL_KEY, OUTCOMES_TAIL_KEY,
+};
 
 use crate::types::RuntimeAdapter;
 use crate::{metrics, Chain, ChainStore, ChainStoreAccess, ChainStoreUpdate};
@@ -20,15 +22,31 @@ use crate::{metrics, Chain, ChainStore, ChainStoreAccess, ChainStoreUpdate};
 #[derive(Clone)]
 pub enum GCMode {
     Fork(ShardTries),
-    Canonical(ShardTries),
+    Canonical(ShardTries, GCRetainedData),
     StateSync { clear_block_info: bool },
 }
 
+/// Data of the canonical chain which is kept for longer than the blocks, as
+/// configured by the retention settings of `GCConfig`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GCRetainedData {
+    /// If set, chunks created at this height or above are kept.
+    pub chunks_from_height: Option<BlockHeight>,
+    /// If set, outcomes of the blocks at this height or above are kept.
+    pub outcomes_from_height: Option<BlockHeight>,
+    /// If set, block headers at this height or above are kept.
+    pub headers_from_height: Option<BlockHeight>,
+}
+
+/// Headers are much smaller than blocks, so more of them are garbage
+/// collected at every step.
+const HEADERS_PER_GC_BLOCK: NumBlocks = 100;
+
 impl fmt::Debug for GCMode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             GCMode::Fork(_) => write!(f, "GCMode::Fork"),
-            GCMode::Canonical(_) => write!(f, "GCMode::Canonical"),
+            GCMode::Canonical(..) => write!(f, "GCMode::Canonical"),
             GCMode::StateSync { .. } => write!(f, "GCMode::StateSync"),
         }
     }
@@ -47,6 +65,21 @@ impl Chain {
         self.mut_chain_store().clear_data(gc_config, runtime_adapter, epoch_manager)
     }
 
+    pub fn clear_headers(
+        &mut self,
+        gc_config: &GCConfig,
+        epoch_sync_boundary: BlockHeight,
+    ) -> Result<(), Error> {
+        let runtime_adapter = self.runtime_adapter.clone();
+        let epoch_manager = self.epoch_manager.clone();
+        self.mut_chain_store().clear_headers(
+            gc_config,
+            runtime_adapter,
+            epoch_manager,
+            epoch_sync_boundary,
+        )
+    }
+
     pub fn reset_data_pre_state_sync(&mut self, sync_hash: CryptoHash) -> Result<(), Error> {
         let runtime_adapter = self.runtime_adapter.clone();
         let epoch_manager = self.epoch_manager.clone();
@@ -167,6 +200,19 @@ impl ChainStore {
             fork_tail = gc_stop_height;
         }
         let mut gc_blocks_remaining = gc_config.gc_blocks_limit;
+        let retained =
+            self.gc_retained_data(gc_config, epoch_manager.as_ref(), &head, gc_stop_height)?;
+
+        // Challenges Cleaning
+        let final_height = self.final_head()?.height;
+        let mut chain_store_update = self.store_update();
+        chain_store_update.clear_challenges(gc_stop_height)?;
+        chain_store_update.clear_invalid_blocks(gc_stop_height)?;
+        chain_store_update.clear_pending_partial_chunks(final_height)?;
+        chain_store_update.commit()?;
+
+        // Retained Outcomes Cleaning
+        self.clear_retained_outcomes(gc_config, epoch_manager.as_ref(), &retained)?;
 
         // Forks Cleaning
         let gc_fork_clean_step = gc_config.gc_fork_clean_step;
@@ -211,7 +257,7 @@ impl ChainStore {
                     chain_store_update.clear_block_data(
                         epoch_manager.as_ref(),
                         *block_hash,
-                        GCMode::Canonical(tries.clone()),
+                        GCMode::Canonical(tries.clone(), retained),
                     )?;
                     gc_blocks_remaining -= 1;
                 } else {
@@ -226,6 +272,150 @@ impl ChainStore {
         Ok(())
     }
 
+    /// Returns the heights from which the data with a longer retention than the
+    /// blocks is kept.
+    fn gc_retained_data(
+        &self,
+        gc_config: &GCConfig,
+        epoch_manager: &dyn EpochManagerAdapter,
+        head: &Tip,
+        gc_stop_height: BlockHeight,
+    ) -> Result<GCRetainedData, Error> {
+        if gc_config.chunks_retention == GCRetention::WithBlocks
+            && gc_config.outcomes_retention == GCRetention::WithBlocks
+            && gc_config.headers_retention == GCRetention::WithBlocks
+        {
+            return Ok(GCRetainedData::default());
+        }
+        let epoch_length = epoch_manager.get_epoch_config(&head.epoch_id)?.epoch_length;
+        let genesis_height = self.get_genesis_height();
+        let from_height = |retention| match gc_config.extra_epochs_to_keep(retention) {
+            Some(0) => None,
+            Some(num_epochs) => Some(
+                gc_stop_height
+                    .saturating_sub(num_epochs.saturating_mul(epoch_length))
+                    .max(genesis_height),
+            ),
+            None => Some(genesis_height),
+        };
+        Ok(GCRetainedData {
+            chunks_from_height: from_height(gc_config.chunks_retention),
+            outcomes_from_height: from_height(gc_config.outcomes_retention),
+            headers_from_height: from_height(gc_config.headers_retention),
+        })
+    }
+
+    /// Garbage collects the outcomes of the canonical blocks which were kept
+    /// after the blocks themselves were garbage collected.
+    ///
+    /// The outcomes of the blocks below the outcomes tail are garbage
+    /// collected. The tail is only stored while outcomes are retained or
+    /// before the retained outcomes are all cleared, otherwise the outcomes
+    /// are cleared together with the blocks.
+    fn clear_retained_outcomes(
+        &mut self,
+        gc_config: &GCConfig,
+        epoch_manager: &dyn EpochManagerAdapter,
+        retained: &GCRetainedData,
+    ) -> Result<(), Error> {
+        let tail = self.tail()?;
+        let outcomes_tail = match (
+            self.store().get_ser(DBCol::BlockMisc, OUTCOMES_TAIL_KEY)?,
+            retained.outcomes_from_height,
+        ) {
+            (Some(outcomes_tail), _) => outcomes_tail,
+            // The outcomes of the blocks below the tail were cleared with
+            // the blocks.
+            (None, Some(_)) => tail,
+            (None, None) => return Ok(()),
+        };
+        let stop_height = retained.outcomes_from_height.map_or(tail, |height| height.min(tail));
+        let stop_height = stop_height.min(outcomes_tail.saturating_add(gc_config.gc_blocks_limit));
+
+        let mut chain_store_update = self.store_update();
+        for height in outcomes_tail..stop_height {
+            // Block hashes by height and block headers are only garbage
+            // collected below the outcomes tail, so the outcomes can still
+            // be found.
+            let Ok(block_hash) = chain_store_update.get_block_hash_by_height(height) else {
+                continue;
+            };
+            let epoch_id = *chain_store_update.get_block_header(&block_hash)?.epoch_id();
+            let shard_layout = epoch_manager.get_shard_layout(&epoch_id)?;
+            for shard_id in shard_layout.shard_ids() {
+                chain_store_update.gc_shard_outcomes(&block_hash, shard_id)?;
+            }
+        }
+        let mut store_update = chain_store_update.store().store_update();
+        if retained.outcomes_from_height.is_none() && stop_height >= tail {
+            store_update.delete(DBCol::BlockMisc, OUTCOMES_TAIL_KEY);
+        } else {
+            store_update.set_ser(
+                DBCol::BlockMisc,
+                OUTCOMES_TAIL_KEY,
+                &stop_height.max(outcomes_tail),
+            )?;
+        }
+        chain_store_update.merge(store_update);
+        chain_store_update.commit()?;
+        Ok(())
+    }
+
+    /// Garbage collects the block headers of the canonical chain, together
+    /// with their hashes by height, up to the header retention height.
+    ///
+    /// `epoch_sync_boundary` is the height of the first block in the epoch of
+    /// the epoch sync proof stored by the node. The headers from it on are
+    /// needed to derive more recent proofs for the nodes bootstrapping with
+    /// epoch sync, so only the headers below it are removed. Headers are also
+    /// kept while other data found through them, e.g. retained outcomes, is
+    /// still stored. The genesis header is never removed.
+    pub fn clear_headers(
+        &mut self,
+        gc_config: &GCConfig,
+        runtime_adapter: Arc<dyn RuntimeAdapter>,
+        epoch_manager: Arc<dyn EpochManagerAdapter>,
+        epoch_sync_boundary: BlockHeight,
+    ) -> Result<(), Error> {
+        let _span = tracing::debug_span!(target: "garbage_collection", "clear_headers").entered();
+        let head = self.head()?;
+        let genesis_height = self.get_genesis_height();
+        if head.height == genesis_height {
+            return Ok(());
+        }
+        let gc_stop_height = runtime_adapter.get_gc_stop_height(&head.last_block_hash);
+        let retained =
+            self.gc_retained_data(gc_config, epoch_manager.as_ref(), &head, gc_stop_height)?;
+        let tail = self.tail()?;
+        let mut stop_height = retained
+            .headers_from_height
+            .map_or(tail, |height| height.min(tail))
+            .min(epoch_sync_boundary);
+        if let Some(outcomes_tail) = self.store().get_ser(DBCol::BlockMisc, OUTCOMES_TAIL_KEY)? {
+            stop_height = stop_height.min(outcomes_tail);
+        }
+        let header_tail: BlockHeight =
+            self.store().get_ser(DBCol::BlockMisc, HEADER_TAIL_KEY)?.unwrap_or(genesis_height + 1);
+        let stop_height =
+            stop_height
+                .min(header_tail.saturating_add(
+                    gc_config.gc_blocks_limit.saturating_mul(HEADERS_PER_GC_BLOCK),
+                ));
+        metrics::HEADER_TAIL_HEIGHT.set(header_tail as i64);
+        if stop_height <= header_tail {
+            return Ok(());
+        }
+
+        let mut chain_store_update = self.store_update();
+        chain_store_update.clear_old_headers(header_tail, stop_height)?;
+        let mut store_update = chain_store_update.store().store_update();
+        store_update.set_ser(DBCol::BlockMisc, HEADER_TAIL_KEY, &stop_height)?;
+        chain_store_update.merge(store_update);
+        chain_store_update.commit()?;
+        metrics::HEADER_TAIL_HEIGHT.set(stop_height as i64);
+        Ok(())
+    }
+
     /// Garbage collect data which archival node doesn’t need to keep.
     ///
     /// Normally, archival nodes keep all the data from the genesis block and
@@ -422,6 +612,28 @@ impl<'a> ChainStoreUpdate<'a> {
         Ok(())
     }
 
+    /// Deletes the headers of the heights from `start` to `end` exclusively,
+    /// which are all below the tail. Besides the canonical headers, found by
+    /// height in `DBCol::BlockHeight`, the headers of the forks are deleted if
+    /// their hashes by height weren't garbage collected with the chunks yet.
+    fn clear_old_headers(&mut self, start: BlockHeight, end: BlockHeight) -> Result<(), Error> {
+        for height in start..end {
+            let mut header_hashes = self.chain_store().get_all_header_hashes_by_height(height)?;
+            if let Ok(block_hash) = self.get_block_hash_by_height(height) {
+                header_hashes.insert(block_hash);
+            }
+            for header_hash in header_hashes {
+                self.gc_col(DBCol::BlockHeader, header_hash.as_bytes());
+            }
+            let key = index_to_bytes(height);
+            self.gc_col(DBCol::HeaderHashesByHeight, &key);
+            let mut store_update = self.store().store_update();
+            store_update.delete(DBCol::BlockHeight, &key);
+            self.merge(store_update);
+        }
+        Ok(())
+    }
+
     fn clear_chunk_data_and_headers(&mut self, min_chunk_height: BlockHeight) -> Result<(), Error> {
         let chunk_tail = self.chunk_tail()?;
         for height in chunk_tail..min_chunk_height {
@@ -507,6 +719,73 @@ impl<'a> ChainStoreUpdate<'a> {
         Ok(())
     }
 
+    /// Garbage collect challenges stored in `DBCol::Challenges` which were
+    /// received at a height below `gc_stop_height`. Such challenges refer to
+    /// blocks which are being garbage collected and can no longer be included
+    /// in a block.
+    fn clear_challenges(&mut self, gc_stop_height: BlockHeight) -> Result<(), Error> {
+        let mut keys_to_gc = vec![];
+        let upper_bound = gc_stop_height.to_be_bytes();
+        for item in self.store().iter_range(DBCol::Challenges, None, Some(&upper_bound)) {
+            let (key, _) = item?;
+            keys_to_gc.push(key);
+        }
+        for key in keys_to_gc {
+            self.gc_col(DBCol::Challenges, &key);
+        }
+        Ok(())
+    }
+
+    /// Garbage collect the invalid blocks stored in `DBCol::InvalidBlocks`
+    /// below `gc_stop_height`. Blocks at such heights are not accepted anyway.
+    fn clear_invalid_blocks(&mut self, gc_stop_height: BlockHeight) -> Result<(), Error> {
+        let lower_bound = 0u64.to_be_bytes();
+        let upper_bound = gc_stop_height.to_be_bytes();
+        // Avoid leaving a range tombstone behind on every GC run.
+        if self
+            .store()
+            .iter_range(DBCol::InvalidBlocks, Some(&lower_bound), Some(&upper_bound))
+            .next()
+            .is_none()
+        {
+            return Ok(());
+        }
+        let mut store_update = self.store().store_update();
+        store_update.delete_range(DBCol::InvalidBlocks, &lower_bound, &upper_bound);
+        self.merge(store_update);
+        Ok(())
+    }
+
+    /// Garbage collect the chunk endorsement stats of `epoch_id` stored in
+    /// `DBCol::ChunkEndorsementStats`.
+    fn clear_endorsement_stats(&mut self, epoch_id: &EpochId) -> Result<(), Error> {
+        let keys: Vec<Box<[u8]>> = self
+            .store()
+            .iter_prefix(DBCol::ChunkEndorsementStats, epoch_id.0.as_bytes())
+            .map(|item| item.map(|(key, _)| key))
+            .collect::<io::Result<Vec<_>>>()?;
+        for key in keys {
+            self.gc_col(DBCol::ChunkEndorsementStats, &key);
+        }
+        Ok(())
+    }
+
+    /// Garbage collect the pending partial chunks stored in `DBCol::PendingPartialChunks`
+    /// below `final_height`. The blocks up to the final head are processed, so these chunks
+    /// are either complete or not needed anymore.
+    fn clear_pending_partial_chunks(&mut self, final_height: BlockHeight) -> Result<(), Error> {
+        let mut keys_to_gc = vec![];
+        let upper_bound = final_height.to_be_bytes();
+        for item in self.store().iter_range(DBCol::PendingPartialChunks, None, Some(&upper_bound)) {
+            let (key, _) = item?;
+            keys_to_gc.push(key);
+        }
+        for key in keys_to_gc {
+            self.gc_col(DBCol::PendingPartialChunks, &key);
+        }
+        Ok(())
+    }
+
     // TODO(resharding) Revisit this function, probably it is not needed anymore.
     fn get_shard_uids_to_gc(
         &mut self,
@@ -565,7 +844,7 @@ impl<'a> ChainStoreUpdate<'a> {
                         }
                     }
                 }
-                GCMode::Canonical(tries) => {
+                GCMode::Canonical(tries, _) => {
                     // If the block is on canonical chain, we delete the state that's before applying this block
                     for shard_uid in shard_uids_to_gc {
                         let trie_changes = self.store().get_ser(
@@ -644,7 +923,15 @@ impl<'a> ChainStoreUpdate<'a> {
             self.gc_col(DBCol::StateChanges, &key);
         }
         self.gc_col(DBCol::BlockRefCount, block_hash.as_bytes());
-        self.gc_outcomes(&block)?;
+        let keep_outcomes = match &gc_mode {
+            GCMode::Canonical(_, retained) => {
+                retained.outcomes_from_height.is_some_and(|from_height| height >= from_height)
+            }
+            _ => false,
+        };
+        if !keep_outcomes {
+            self.gc_outcomes(&block)?;
+        }
         match gc_mode {
             GCMode::StateSync { clear_block_info: false } => {}
             _ => self.gc_col(DBCol::BlockInfo, block_hash.as_bytes()),
@@ -659,7 +946,7 @@ impl<'a> ChainStoreUpdate<'a> {
                 // 5. Forks only clearing
                 self.dec_block_refcount(block.header().prev_hash())?;
             }
-            GCMode::Canonical(_) => {
+            GCMode::Canonical(_, retained) => {
                 // 6. Canonical Chain only clearing
                 // Delete chunks, chunk-indexed data and block headers
                 let mut min_chunk_height = self.tail()?;
@@ -668,7 +955,17 @@ impl<'a> ChainStoreUpdate<'a> {
                         min_chunk_height = chunk_header.height_created();
                     }
                 }
+                if let Some(chunks_from_height) = retained.chunks_from_height {
+                    min_chunk_height = min_chunk_height.min(chunks_from_height);
+                }
+                // The chunk tail never moves back, e.g. when the retention of
+                // the chunks was increased.
+                let min_chunk_height = min_chunk_height.max(self.chunk_tail()?);
                 self.clear_chunk_data_and_headers(min_chunk_height)?;
+                // The endorsement stats of an epoch go away with its last block.
+                if epoch_manager.is_last_block_in_finished_epoch(&block_hash)? {
+                    self.clear_endorsement_stats(block.header().epoch_id())?;
+                }
             }
             GCMode::StateSync { .. } => {
                 // 7. State Sync clearing
@@ -859,21 +1156,29 @@ impl<'a> ChainStoreUpdate<'a> {
         {
             // It is ok to use the shard id from the header because it is a new
             // chunk. An old chunk may have the shard id from the parent shard.
-            let shard_id = chunk_header.shard_id();
-            let outcome_ids =
-                self.chain_store().get_outcomes_by_block_hash_and_shard_id(block_hash, shard_id)?;
-            for outcome_id in outcome_ids {
-                self.gc_col(
-                    DBCol::TransactionResultForBlock,
-                    &get_outcome_id_block_hash(&outcome_id, block_hash),
-                );
-            }
-            self.gc_col(DBCol::OutcomeIds, &get_block_shard_id(block_hash, shard_id));
+            self.gc_shard_outcomes(block_hash, chunk_header.shard_id())?;
         }
         self.merge(store_update);
         Ok(())
     }
 
+    fn gc_shard_outcomes(
+        &mut self,
+        block_hash: &CryptoHash,
+        shard_id: ShardId,
+    ) -> Result<(), Error> {
+        let outcome_ids =
+            self.chain_store().get_outcomes_by_block_hash_and_shard_id(block_hash, shard_id)?;
+        for outcome_id in outcome_ids {
+            self.gc_col(
+                DBCol::TransactionResultForBlock,
+                &get_outcome_id_block_hash(&outcome_id, block_hash),
+            );
+        }
+        self.gc_col(DBCol::OutcomeIds, &get_block_shard_id(block_hash, shard_id));
+        Ok(())
+    }
+
     fn gc_col(&mut self, col: DBCol, key: &[u8]) {
         let mut store_update = self.store().store_update();
         match col {
@@ -888,13 +1193,11 @@ impl<'a> ChainStoreUpdate<'a> {
                 store_update.delete(col, key);
             }
             DBCol::BlockHeader => {
-                // TODO(#3488) At the moment header sync needs block headers.
-                // However, we want to eventually garbage collect headers.
-                // When that happens we should make sure that block headers is
-                // copied to the cold storage.
+                // Only collected on nodes configured to prune headers, which
+                // are never archival, so the headers don't need to be copied
+                // to the cold storage first.
                 store_update.delete(col, key);
                 self.chain_store().headers.pop(key);
-                unreachable!();
             }
             DBCol::Block => {
                 store_update.delete(col, key);
@@ -991,12 +1294,25 @@ impl<'a> ChainStoreUpdate<'a> {
             DBCol::StateSyncNewChunks => {
                 store_update.delete(col, key);
             }
+            DBCol::Challenges => {
+                store_update.delete(col, key);
+            }
+            DBCol::InvalidBlocks => {
+                store_update.delete(col, key);
+            }
+            DBCol::PendingPartialChunks => {
+                store_update.delete(col, key);
+            }
+            DBCol::ChunkEndorsementStats => {
+                store_update.delete(col, key);
+            }
             DBCol::DbVersion
             | DBCol::BlockMisc
             | DBCol::_GCCount
             | DBCol::BlockHeight  // block sync needs it + genesis should be accessible
             | DBCol::_Peers
             | DBCol::RecentOutboundConnections
+            | DBCol::PeerReputation
             | DBCol::BlockMerkleTree
             | DBCol::AccountAnnouncements
             | DBCol::EpochLightClientBlocks
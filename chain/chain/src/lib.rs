@@ -16,18 +16,25 @@ pub use types::{Block, BlockHeader, BlockStatus, ChainGenesis, LatestKnown, Prov
 
 mod approval_verification;
 mod block_processing_utils;
+pub mod block_replay;
 pub mod blocks_delay_tracker;
+pub mod catchup_scheduler;
 pub mod chain;
+pub mod chain_events;
 mod chain_update;
+pub mod checkpoint;
 pub mod crypto_hash_timer;
 mod doomslug;
+pub mod execution_artifacts;
 pub mod flat_storage_init;
 pub mod flat_storage_resharder;
+pub mod fork_choice;
 mod garbage_collection;
 mod lightclient;
 pub mod metrics;
 pub mod migrations;
 pub mod missing_chunks;
+pub mod optimistic_block;
 pub mod orphan;
 pub mod resharding;
 pub mod runtime;
@@ -55,6 +55,24 @@ pub static VALIDATOR_ACTIVE_TOTAL: LazyLock<IntGauge> = LazyLock::new(|| {
 });
 pub static NUM_ORPHANS: LazyLock<IntGauge> =
     LazyLock::new(|| try_create_int_gauge("near_num_orphans", "Number of orphan blocks.").unwrap());
+pub static ORPHAN_POOL_BYTES: LazyLock<IntGauge> = LazyLock::new(|| {
+    try_create_int_gauge("near_orphan_pool_bytes", "Total size of the orphan blocks in bytes")
+        .unwrap()
+});
+pub static NUM_ORPHANS_EVICTED: LazyLock<IntCounter> = LazyLock::new(|| {
+    try_create_int_counter(
+        "near_num_orphans_evicted",
+        "Number of orphan blocks evicted because the orphan pool was full",
+    )
+    .unwrap()
+});
+pub static NUM_BLOCKS_MISSING_CHUNKS: LazyLock<IntGauge> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "near_num_blocks_missing_chunks",
+        "Number of blocks waiting for their chunks in the missing chunks pool",
+    )
+    .unwrap()
+});
 pub static HEADER_HEAD_HEIGHT: LazyLock<IntGauge> = LazyLock::new(|| {
     try_create_int_gauge("near_header_head_height", "Height of the header head").unwrap()
 });
@@ -70,6 +88,9 @@ pub static TAIL_HEIGHT: LazyLock<IntGauge> =
 pub static CHUNK_TAIL_HEIGHT: LazyLock<IntGauge> = LazyLock::new(|| {
     try_create_int_gauge("near_chunk_tail_height", "Height of chunk tail").unwrap()
 });
+pub static HEADER_TAIL_HEIGHT: LazyLock<IntGauge> = LazyLock::new(|| {
+    try_create_int_gauge("near_header_tail_height", "Height of header tail").unwrap()
+});
 pub static FORK_TAIL_HEIGHT: LazyLock<IntGauge> =
     LazyLock::new(|| try_create_int_gauge("near_fork_tail_height", "Height of fork tail").unwrap());
 pub static GC_STOP_HEIGHT: LazyLock<IntGauge> =
@@ -172,3 +193,27 @@ pub(crate) static SHARD_LAYOUT_NUM_SHARDS: LazyLock<IntGauge> = LazyLock::new(||
     )
     .unwrap()
 });
+
+pub(crate) static EXECUTION_ARTIFACTS_EXPORTED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    try_create_int_counter(
+        "near_execution_artifacts_exported_total",
+        "Number of block execution artifacts written to the sink",
+    )
+    .unwrap()
+});
+
+pub(crate) static EXECUTION_ARTIFACTS_FAILED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    try_create_int_counter(
+        "near_execution_artifacts_failed_total",
+        "Number of block execution artifacts which failed to be written to the sink",
+    )
+    .unwrap()
+});
+
+pub(crate) static EXECUTION_ARTIFACTS_DROPPED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    try_create_int_counter(
+        "near_execution_artifacts_dropped_total",
+        "Number of block execution artifacts dropped because the sink was behind",
+    )
+    .unwrap()
+});
@@ -9,6 +9,8 @@ use std::collections::{
 };
 use tracing::{debug, warn};
 
+use crate::metrics;
+
 type BlockHash = CryptoHash;
 
 const MAX_BLOCKS_MISSING_CHUNKS: usize = 1024;
@@ -90,9 +92,20 @@ impl<Block: BlockLike> MissingChunksPool<Block> {
         // again, work through the backlog of the pool, then naturally sync the later blocks
         // which were not added initially, or (b) someone will restart the node because something
         // has gone horribly wrong, in which case these HashMaps will be lost anyways.
-        if self.blocks_missing_chunks.len() >= MAX_BLOCKS_MISSING_CHUNKS {
-            warn!(target: "chunks", "Not recording block with hash {} even though it is missing chunks. The missing chunks pool is full.", block_hash);
-            return;
+        // Lower blocks are processed first, so a block lower than all the blocks in the pool
+        // replaces the highest one.
+        if self.blocks_waiting_for_chunks.len() >= MAX_BLOCKS_MISSING_CHUNKS {
+            let highest = self
+                .height_idx
+                .last_key_value()
+                .filter(|(height, _)| **height > block.height())
+                .and_then(|(_, hashes)| hashes.iter().next().copied());
+            let Some(highest) = highest else {
+                warn!(target: "chunks", "Not recording block with hash {} even though it is missing chunks. The missing chunks pool is full.", block_hash);
+                return;
+            };
+            warn!(target: "chunks", "Evicting block with hash {} from the full missing chunks pool to record lower block {}.", highest, block_hash);
+            self.remove_block(&highest);
         }
 
         for chunk_hash in missing_chunks.iter().cloned() {
@@ -121,6 +134,7 @@ impl<Block: BlockLike> MissingChunksPool<Block> {
         let blocks_at_height = self.height_idx.entry(height).or_insert_with(HashSet::new);
         blocks_at_height.insert(block_hash);
         self.blocks_waiting_for_chunks.insert(block_hash, block);
+        self.update_metrics();
     }
 
     pub fn accept_chunk(&mut self, chunk_hash: &ChunkHash) {
@@ -160,32 +174,51 @@ impl<Block: BlockLike> MissingChunksPool<Block> {
             }
             self.blocks_ready_to_process.push(HeightOrdered(block));
         }
+        self.update_metrics();
     }
 
     pub fn prune_blocks_below_height(&mut self, height: BlockHeight) {
         let heights_to_remove: Vec<BlockHeight> =
             self.height_idx.keys().copied().take_while(|h| *h < height).collect();
         for h in heights_to_remove {
-            if let Some(block_hashes) = self.height_idx.remove(&h) {
+            if let Some(block_hashes) = self.height_idx.get(&h).cloned() {
                 for block_hash in block_hashes {
-                    self.blocks_waiting_for_chunks.remove(&block_hash);
-                    if let Some(chunk_hashes) = self.blocks_missing_chunks.remove(&block_hash) {
-                        for chunk_hash in chunk_hashes {
-                            if let hash_map::Entry::Occupied(mut entry) =
-                                self.missing_chunks.entry(chunk_hash)
-                            {
-                                let blocks_for_chunk = entry.get_mut();
-                                blocks_for_chunk.remove(&block_hash);
-                                if blocks_for_chunk.is_empty() {
-                                    entry.remove_entry();
-                                }
-                            }
-                        }
+                    self.remove_block(&block_hash);
+                }
+            }
+        }
+        self.update_metrics();
+    }
+
+    /// Removes the block waiting for chunks from all the indices.
+    fn remove_block(&mut self, block_hash: &BlockHash) {
+        let Some(block) = self.blocks_waiting_for_chunks.remove(block_hash) else {
+            return;
+        };
+        if let btree_map::Entry::Occupied(mut entry) = self.height_idx.entry(block.height()) {
+            let blocks_at_height = entry.get_mut();
+            blocks_at_height.remove(block_hash);
+            if blocks_at_height.is_empty() {
+                entry.remove_entry();
+            }
+        }
+        if let Some(chunk_hashes) = self.blocks_missing_chunks.remove(block_hash) {
+            for chunk_hash in chunk_hashes {
+                if let hash_map::Entry::Occupied(mut entry) = self.missing_chunks.entry(chunk_hash)
+                {
+                    let blocks_for_chunk = entry.get_mut();
+                    blocks_for_chunk.remove(block_hash);
+                    if blocks_for_chunk.is_empty() {
+                        entry.remove_entry();
                     }
                 }
             }
         }
     }
+
+    fn update_metrics(&self) {
+        metrics::NUM_BLOCKS_MISSING_CHUNKS.set(self.blocks_waiting_for_chunks.len() as i64);
+    }
 }
 
 #[cfg(test)]
@@ -269,6 +302,31 @@ mod test {
         assert!(!pool.contains(&block_hash));
     }
 
+    #[test]
+    fn should_evict_highest_block_for_lower_block_when_full() {
+        let mut pool: MissingChunksPool<MockBlock> = MissingChunksPool::default();
+        let mut chunk_hash_idx = 2 * MAX_BLOCKS_MISSING_CHUNKS as BlockHeight;
+
+        for block_height in 1..=MAX_BLOCKS_MISSING_CHUNKS {
+            let block = MockBlock::new(block_height as BlockHeight);
+            chunk_hash_idx += 1;
+            pool.add_block_with_missing_chunks(block, vec![get_chunk_hash(chunk_hash_idx)]);
+        }
+        let highest_block = MockBlock::new(MAX_BLOCKS_MISSING_CHUNKS as BlockHeight);
+        let highest_chunk_hash = get_chunk_hash(chunk_hash_idx);
+        assert!(pool.contains(&highest_block.hash));
+
+        let lower_block = MockBlock::new(0);
+        pool.add_block_with_missing_chunks(lower_block, vec![get_chunk_hash(0)]);
+        assert!(pool.contains(&lower_block.hash));
+        assert!(!pool.contains(&highest_block.hash));
+        assert_eq!(pool.len(), MAX_BLOCKS_MISSING_CHUNKS);
+
+        // the evicted block is not waiting for its chunk anymore
+        pool.accept_chunk(&highest_chunk_hash);
+        assert!(pool.ready_blocks().is_empty());
+    }
+
     #[test]
     fn should_remove_old_blocks_when_prune_called() {
         let mut pool: MissingChunksPool<MockBlock> = MissingChunksPool::default();
@@ -0,0 +1,103 @@
+//! Production of blocks on top of a block whose chunks are still being applied.
+//!
+//! A block producer normally waits until the previous block is applied and
+//! becomes the head before producing its block. Nodes tracking many shards
+//! apply blocks slower than the rest of the network, so by the time they are
+//! done the approvals and chunks for the next height are often already there.
+//!
+//! Everything needed to produce the next block is known once the previous
+//! block is preprocessed, except its epoch information, which the epoch
+//! manager only records once the block is applied. When neither the previous
+//! block nor the next one can start a new epoch, that information only depends
+//! on the header, so it is added to the epoch manager ahead of time and the
+//! block is produced optimistically. If the previous block then fails to
+//! apply, its epoch information is removed and the optimistic block is rolled
+//! back. The optimistic block is signed and broadcast, so its height stays
+//! the latest known one: the producer skips that height rather than signing a
+//! second block at it.
+
+use crate::{Chain, ChainStoreAccess};
+use near_chain_primitives::Error;
+use near_primitives::block::Block;
+use near_primitives::challenge::ChallengesResult;
+use near_primitives::epoch_block_info::BlockInfo;
+use near_primitives::hash::CryptoHash;
+use near_primitives::merkle::PartialMerkleTree;
+use tracing::debug;
+
+/// What block production needs from the block a new block is produced on.
+pub struct ProductionParent {
+    pub block: Block,
+    /// Merkle tree of the blocks before `block`.
+    pub block_merkle_tree: PartialMerkleTree,
+    pub challenges_result: ChallengesResult,
+}
+
+impl Chain {
+    /// Returns the parent to produce a block on top of the accepted block
+    /// `prev_hash`.
+    pub fn production_parent(&self, prev_hash: &CryptoHash) -> Result<ProductionParent, Error> {
+        let block_merkle_tree = self.chain_store.get_block_merkle_tree(prev_hash)?;
+        Ok(ProductionParent {
+            block: self.get_block(prev_hash)?,
+            block_merkle_tree: PartialMerkleTree::clone(&block_merkle_tree),
+            challenges_result: self.get_block_extra(prev_hash)?.challenges_result.clone(),
+        })
+    }
+
+    /// Returns the blocks being applied on top of the head, on which blocks
+    /// may be produced optimistically.
+    pub fn optimistic_block_candidates(&self) -> Result<Vec<CryptoHash>, Error> {
+        Ok(self.blocks_in_processing.children(&self.head()?.last_block_hash))
+    }
+
+    /// Makes the epoch information of `block_hash`, which is being applied on
+    /// top of the head, available before the block is applied. Returns false
+    /// if a block can't be produced on top of it before it is applied.
+    pub fn prepare_optimistic_production(&self, block_hash: &CryptoHash) -> Result<bool, Error> {
+        let Some((block, _)) = self.blocks_in_processing.get(block_hash) else {
+            return Ok(false);
+        };
+        let header = block.header();
+        if header.prev_hash() != &self.head()?.last_block_hash {
+            return Ok(false);
+        }
+        let last_finalized_height = self.chain_store.get_block_height(header.last_final_block())?;
+        Ok(self
+            .epoch_manager
+            .add_optimistic_block_info(BlockInfo::from_header(header, last_finalized_height))?)
+    }
+
+    /// Returns the parent to produce a block on top of `prev_hash` while its
+    /// chunks are still being applied, or `None` if that isn't possible.
+    pub fn optimistic_production_parent(
+        &self,
+        prev_hash: &CryptoHash,
+    ) -> Result<Option<ProductionParent>, Error> {
+        if !self.prepare_optimistic_production(prev_hash)? {
+            return Ok(None);
+        }
+        let Some((block, preprocess_info)) = self.blocks_in_processing.get(prev_hash) else {
+            return Ok(None);
+        };
+        let grandparent_hash = block.header().prev_hash();
+        let mut block_merkle_tree =
+            PartialMerkleTree::clone(&self.chain_store.get_block_merkle_tree(grandparent_hash)?);
+        block_merkle_tree.insert(*grandparent_hash);
+        Ok(Some(ProductionParent {
+            block: block.clone(),
+            block_merkle_tree,
+            challenges_result: preprocess_info.challenges_result.clone(),
+        }))
+    }
+
+    /// Rolls back the blocks produced on top of `prev_hash`, which failed to
+    /// apply, by dropping them from the orphan pool. The latest known height
+    /// isn't restored, as another block signed at the same height would be a
+    /// double sign.
+    pub fn rollback_optimistic_block(&mut self, prev_hash: &CryptoHash) {
+        self.epoch_manager.remove_optimistic_block_info(prev_hash);
+        let orphans = self.orphans.remove_by_prev_hash(*prev_hash).unwrap_or_default();
+        debug!(target: "chain", ?prev_hash, num_orphans = orphans.len(), "Rolling back optimistic block");
+    }
+}
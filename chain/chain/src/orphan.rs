@@ -7,7 +7,7 @@ use near_primitives::hash::CryptoHash;
 use near_primitives::sharding::ShardChunkHeader;
 use near_primitives::types::{AccountId, BlockHeight, EpochId};
 use near_primitives::utils::MaybeValidated;
-use std::collections::{HashMap, HashSet};
+use std::collections::{btree_map, hash_map, BTreeMap, HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use tracing::{debug, debug_span};
 
@@ -17,6 +17,9 @@ use crate::{metrics, BlockProcessingArtifact, Chain, Provenance};
 /// Maximum number of orphans chain can store.
 const MAX_ORPHAN_SIZE: usize = 1024;
 
+/// Maximum total size of the blocks in the orphan pool in bytes.
+const MAX_ORPHAN_BYTES: usize = 64 * 1024 * 1024;
+
 /// Maximum age of orphan to store in the chain.
 const MAX_ORPHAN_AGE_SECS: u64 = 300;
 
@@ -32,6 +35,11 @@ pub const NUM_ORPHAN_ANCESTORS_CHECK: u64 = 3;
 // It should almost never be hit
 const MAX_ORPHAN_MISSING_CHUNKS: usize = 5;
 
+/// Delay before the missing parent of an orphan is requested again. It is
+/// doubled after each request, up to `MAX_PARENT_REQUEST_BACKOFF_MS`.
+const MIN_PARENT_REQUEST_BACKOFF_MS: i64 = 500;
+const MAX_PARENT_REQUEST_BACKOFF_MS: i64 = 30_000;
+
 /// Orphan is a block whose previous block is not accepted (in store) yet.
 /// Therefore, they are not ready to be processed yet.
 /// We save these blocks in an in-memory orphan pool to be processed later
@@ -58,27 +66,53 @@ impl Orphan {
     }
 }
 
+struct OrphanEntry {
+    orphan: Orphan,
+    /// Size of the borsh serialized block, counted towards `MAX_ORPHAN_BYTES`.
+    size: usize,
+}
+
+/// Requests of a block that orphans are waiting for.
+struct RequestBackoff {
+    last_requested: Instant,
+    attempts: u32,
+}
+
+impl RequestBackoff {
+    fn next_request(&self) -> Instant {
+        let backoff_ms = MIN_PARENT_REQUEST_BACKOFF_MS
+            .saturating_mul(1 << self.attempts.saturating_sub(1).min(16))
+            .min(MAX_PARENT_REQUEST_BACKOFF_MS);
+        self.last_requested + Duration::milliseconds(backoff_ms)
+    }
+}
+
 /// OrphanBlockPool stores information of all orphans that are waiting to be processed
 /// A block is added to the orphan pool when process_block failed because the block is an orphan
 /// A block is removed from the pool if
 /// 1) it is ready to be processed
 /// or
-/// 2) size of the pool exceeds MAX_ORPHAN_SIZE and the orphan was added a long time ago
-///    or the height is high
+/// 2) the pool has more than MAX_ORPHAN_SIZE orphans or MAX_ORPHAN_BYTES bytes, and the orphan
+///    was added a long time ago or the height is high
 pub struct OrphanBlockPool {
     /// A map from block hash to a orphan block
-    orphans: HashMap<CryptoHash, Orphan>,
+    orphans: HashMap<CryptoHash, OrphanEntry>,
     /// A set that contains all orphans for which we have requested missing chunks for them
     /// An orphan can be added to this set when it was first added to the pool, or later
     /// when certain requirements are satisfied (see check_orphans)
     /// It can only be removed from this set when the orphan is removed from the pool
     orphans_requested_missing_chunks: HashSet<CryptoHash>,
     /// A map from block heights to orphan blocks at the height
-    /// It's used to evict orphans when the pool is saturated
-    height_idx: HashMap<BlockHeight, Vec<CryptoHash>>,
+    /// It's used to evict orphans with the highest heights when the pool is saturated
+    height_idx: BTreeMap<BlockHeight, Vec<CryptoHash>>,
     /// A map from block hashes to orphan blocks whose prev block is the block
     /// It's used to check which orphan blocks are ready to be processed when a block is accepted
     prev_hash_idx: HashMap<CryptoHash, Vec<CryptoHash>>,
+    /// Requests of the missing parents of the orphans, keyed by the hash of the parent
+    /// Entries are removed together with the last orphan waiting for the parent
+    parent_requests: HashMap<CryptoHash, RequestBackoff>,
+    /// Total size of the orphans in bytes
+    size_bytes: usize,
     /// number of orphans that were evicted
     evicted: usize,
 }
@@ -88,8 +122,10 @@ impl OrphanBlockPool {
         OrphanBlockPool {
             orphans: HashMap::default(),
             orphans_requested_missing_chunks: HashSet::default(),
-            height_idx: HashMap::default(),
+            height_idx: BTreeMap::default(),
             prev_hash_idx: HashMap::default(),
+            parent_requests: HashMap::default(),
+            size_bytes: 0,
             evicted: 0,
         }
     }
@@ -98,56 +134,89 @@ impl OrphanBlockPool {
         self.orphans.len()
     }
 
+    /// Total size of the orphans in bytes
+    pub fn size_bytes(&self) -> usize {
+        self.size_bytes
+    }
+
     fn len_evicted(&self) -> usize {
         self.evicted
     }
 
+    fn is_full(&self) -> bool {
+        self.orphans.len() > MAX_ORPHAN_SIZE || self.size_bytes > MAX_ORPHAN_BYTES
+    }
+
     /// Add a block to the orphan pool
     /// `requested_missing_chunks`: whether missing chunks has been requested for the orphan
     fn add(&mut self, orphan: Orphan, requested_missing_chunks: bool) {
         let block_hash = *orphan.block.hash();
-        let height_hashes = self.height_idx.entry(orphan.block.header().height()).or_default();
-        height_hashes.push(*orphan.block.hash());
-        let prev_hash_entries =
-            self.prev_hash_idx.entry(*orphan.block.header().prev_hash()).or_default();
-        prev_hash_entries.push(block_hash);
-        self.orphans.insert(block_hash, orphan);
+        let size = borsh::object_length(orphan.block.get_inner()).unwrap_or_default();
+        self.height_idx.entry(orphan.block.header().height()).or_default().push(block_hash);
+        self.prev_hash_idx.entry(*orphan.prev_hash()).or_default().push(block_hash);
+        self.orphans.insert(block_hash, OrphanEntry { orphan, size });
+        self.size_bytes += size;
         if requested_missing_chunks {
             self.orphans_requested_missing_chunks.insert(block_hash);
         }
 
-        if self.orphans.len() > MAX_ORPHAN_SIZE {
+        if self.is_full() {
             let old_len = self.orphans.len();
 
-            let mut removed_hashes: HashSet<CryptoHash> = HashSet::default();
-            self.orphans.retain(|_, ref mut x| {
-                let keep = x.added.elapsed() < Duration::seconds(MAX_ORPHAN_AGE_SECS as i64);
-                if !keep {
-                    removed_hashes.insert(*x.block.hash());
-                }
-                keep
-            });
-            let mut heights = self.height_idx.keys().cloned().collect::<Vec<u64>>();
-            heights.sort_unstable();
-            for h in heights.iter().rev() {
-                if let Some(hash) = self.height_idx.remove(h) {
-                    for h in hash {
-                        let _ = self.orphans.remove(&h);
-                        removed_hashes.insert(h);
-                    }
-                }
-                if self.orphans.len() < MAX_ORPHAN_SIZE {
+            // Orphans which didn't get their parent for a long time are evicted first.
+            let max_age = Duration::seconds(MAX_ORPHAN_AGE_SECS as i64);
+            let stale: Vec<CryptoHash> = self
+                .orphans
+                .iter()
+                .filter(|(_, entry)| entry.orphan.added.elapsed() >= max_age)
+                .map(|(hash, _)| *hash)
+                .collect();
+            for hash in stale {
+                self.remove(&hash);
+            }
+            // Then the orphans with the highest heights, which are the last to be processed.
+            while self.is_full() {
+                let Some((_, hashes)) = self.height_idx.last_key_value() else {
                     break;
+                };
+                let hashes = hashes.clone();
+                for hash in hashes {
+                    self.remove(&hash);
                 }
             }
-            self.height_idx.retain(|_, ref mut xs| xs.iter().any(|x| !removed_hashes.contains(x)));
-            self.prev_hash_idx
-                .retain(|_, ref mut xs| xs.iter().any(|x| !removed_hashes.contains(x)));
-            self.orphans_requested_missing_chunks.retain(|x| !removed_hashes.contains(x));
 
-            self.evicted += old_len - self.orphans.len();
+            let num_evicted = old_len - self.orphans.len();
+            self.evicted += num_evicted;
+            metrics::NUM_ORPHANS_EVICTED.inc_by(num_evicted as u64);
+        }
+        self.update_metrics();
+    }
+
+    /// Removes the orphan `block_hash` from the pool and all its indices.
+    fn remove(&mut self, block_hash: &CryptoHash) -> Option<Orphan> {
+        let OrphanEntry { orphan, size } = self.orphans.remove(block_hash)?;
+        self.size_bytes -= size;
+        self.orphans_requested_missing_chunks.remove(block_hash);
+        if let btree_map::Entry::Occupied(mut entry) = self.height_idx.entry(orphan.height()) {
+            entry.get_mut().retain(|hash| hash != block_hash);
+            if entry.get().is_empty() {
+                entry.remove();
+            }
         }
+        let prev_hash = *orphan.prev_hash();
+        if let hash_map::Entry::Occupied(mut entry) = self.prev_hash_idx.entry(prev_hash) {
+            entry.get_mut().retain(|hash| hash != block_hash);
+            if entry.get().is_empty() {
+                entry.remove();
+                self.parent_requests.remove(&prev_hash);
+            }
+        }
+        Some(orphan)
+    }
+
+    fn update_metrics(&self) {
         metrics::NUM_ORPHANS.set(self.orphans.len() as i64);
+        metrics::ORPHAN_POOL_BYTES.set(self.size_bytes as i64);
     }
 
     pub fn contains(&self, hash: &CryptoHash) -> bool {
@@ -155,37 +224,20 @@ impl OrphanBlockPool {
     }
 
     pub fn get(&self, hash: &CryptoHash) -> Option<&Orphan> {
-        self.orphans.get(hash)
+        self.orphans.get(hash).map(|entry| &entry.orphan)
     }
 
-    // // Iterates over existing orphans.
-    // pub fn map(&self, orphan_fn: &mut dyn FnMut(&CryptoHash, &Block, &Instant)) {
-    //     self.orphans
-    //         .iter()
-    //         .map(|it| orphan_fn(it.0, it.1.block.get_inner(), &it.1.added))
-    //         .collect_vec();
-    // }
-
     /// Remove all orphans in the pool that can be "adopted" by block `prev_hash`, i.e., children
-    /// of `prev_hash` and return the list.
+    /// of `prev_hash` and return the list, from the lowest height.
     /// This function is called when `prev_hash` is accepted, thus its children can be removed
     /// from the orphan pool and be processed.
     pub fn remove_by_prev_hash(&mut self, prev_hash: CryptoHash) -> Option<Vec<Orphan>> {
-        let mut removed_hashes: HashSet<CryptoHash> = HashSet::default();
-        let ret = self.prev_hash_idx.remove(&prev_hash).map(|hs| {
-            hs.iter()
-                .filter_map(|h| {
-                    removed_hashes.insert(*h);
-                    self.orphans_requested_missing_chunks.remove(h);
-                    self.orphans.remove(h)
-                })
-                .collect()
-        });
-
-        self.height_idx.retain(|_, ref mut xs| xs.iter().any(|x| !removed_hashes.contains(x)));
-
-        metrics::NUM_ORPHANS.set(self.orphans.len() as i64);
-        ret
+        let hashes = self.prev_hash_idx.get(&prev_hash)?.clone();
+        let mut orphans: Vec<Orphan> = hashes.iter().filter_map(|h| self.remove(h)).collect();
+        orphans.sort_by_key(|orphan| orphan.height());
+        self.parent_requests.remove(&prev_hash);
+        self.update_metrics();
+        Some(orphans)
     }
 
     /// Return a list of orphans that are among the `target_depth` immediate descendants of
@@ -232,6 +284,29 @@ impl OrphanBlockPool {
     fn mark_missing_chunks_requested_for_orphan(&mut self, block_hash: CryptoHash) {
         self.orphans_requested_missing_chunks.insert(block_hash);
     }
+
+    /// Returns true and records the request if the block `parent_hash`, which orphans in
+    /// the pool are waiting for, wasn't requested recently. The delay between the requests
+    /// grows exponentially with the number of requests.
+    fn try_request_parent(&mut self, parent_hash: CryptoHash, now: Instant) -> bool {
+        if !self.prev_hash_idx.contains_key(&parent_hash) {
+            return false;
+        }
+        match self.parent_requests.entry(parent_hash) {
+            hash_map::Entry::Occupied(mut entry) => {
+                let backoff = entry.get_mut();
+                if now < backoff.next_request() {
+                    return false;
+                }
+                backoff.last_requested = now;
+                backoff.attempts += 1;
+            }
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(RequestBackoff { last_requested: now, attempts: 1 });
+            }
+        }
+        true
+    }
 }
 
 /// Contains information needed to request chunks for orphans
@@ -420,4 +495,70 @@ impl Chain {
     pub fn is_orphan(&self, hash: &CryptoHash) -> bool {
         self.orphans.contains(hash)
     }
+
+    /// Returns whether the block `parent_hash`, which orphans are waiting for, should be
+    /// requested now. Repeated requests of the same block are delayed with exponential
+    /// backoff, so that a stream of orphans doesn't flood the peers with block requests.
+    pub fn should_request_orphan_parent(&mut self, parent_hash: CryptoHash) -> bool {
+        if self.orphans.contains(&parent_hash) {
+            return false;
+        }
+        self.orphans.try_request_parent(parent_hash, self.clock.now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Orphan, OrphanBlockPool};
+    use crate::missing_chunks::BlockLike;
+    use crate::test_utils::setup;
+    use crate::Provenance;
+    use near_async::time::{Duration, FakeClock, Utc};
+    use near_primitives::test_utils::TestBlockBuilder;
+    use near_primitives::utils::MaybeValidated;
+
+    #[test]
+    fn test_orphan_parent_requests_backoff() {
+        let clock = FakeClock::new(Utc::UNIX_EPOCH);
+        let (chain, _, _, signer) = setup(clock.clock());
+        let genesis = chain.get_block(chain.genesis().hash()).unwrap();
+        let parent = TestBlockBuilder::new(clock.clock(), &genesis, signer.clone()).build();
+        let child = TestBlockBuilder::new(clock.clock(), &parent, signer.clone()).build();
+        let grandchild = TestBlockBuilder::new(clock.clock(), &child, signer).build();
+
+        let mut pool = OrphanBlockPool::new();
+        // Nothing waits for the parent yet.
+        assert!(!pool.try_request_parent(*parent.hash(), clock.now()));
+        for block in [grandchild, child] {
+            let orphan = Orphan {
+                block: MaybeValidated::from(block),
+                provenance: Provenance::NONE,
+                added: clock.now(),
+            };
+            pool.add(orphan, false);
+        }
+        assert_eq!(pool.len(), 2);
+        assert!(pool.size_bytes() > 0);
+
+        assert!(pool.try_request_parent(*parent.hash(), clock.now()));
+        assert!(!pool.try_request_parent(*parent.hash(), clock.now()));
+        clock.advance(Duration::milliseconds(500));
+        assert!(pool.try_request_parent(*parent.hash(), clock.now()));
+        // The delay doubles after each request.
+        clock.advance(Duration::milliseconds(500));
+        assert!(!pool.try_request_parent(*parent.hash(), clock.now()));
+        clock.advance(Duration::milliseconds(500));
+        assert!(pool.try_request_parent(*parent.hash(), clock.now()));
+
+        let orphans = pool.remove_by_prev_hash(*parent.hash()).unwrap();
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].height(), parent.header().height() + 1);
+        assert_eq!(pool.len(), 1);
+        assert!(!pool.try_request_parent(*parent.hash(), clock.now()));
+
+        let orphans = pool.remove_by_prev_hash(orphans[0].hash()).unwrap();
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(pool.len(), 0);
+        assert_eq!(pool.size_bytes(), 0);
+    }
 }
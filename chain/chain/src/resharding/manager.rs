@@ -10,7 +10,6 @@ use near_chain_configs::{MutableConfigValue, ReshardingConfig, ReshardingHandle}
 use near_chain_primitives::Error;
 use near_epoch_manager::EpochManagerAdapter;
 use near_primitives::block::Block;
-use near_primitives::challenge::PartialState;
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::{get_block_shard_uid, ShardLayout};
 use near_primitives::types::chunk_extra::ChunkExtra;
@@ -208,9 +207,7 @@ impl ReshardingManager {
 
             let trie_changes = mem_trie_update.retain_split_shard(&boundary_account, retain_mode);
             let partial_storage = trie_recorder.recorded_storage();
-            let partial_state_len = match &partial_storage.nodes {
-                PartialState::TrieValues(values) => values.len(),
-            };
+            let partial_state_len = partial_storage.nodes.len();
             let mem_changes = trie_changes.mem_trie_changes.as_ref().unwrap();
             let new_state_root = mem_tries.apply_memtrie_changes(block_height, mem_changes);
             // TODO(resharding): set all fields of `ChunkExtra`. Consider stronger
@@ -12,8 +12,9 @@ use near_crypto::PublicKey;
 use near_epoch_manager::{EpochManagerAdapter, EpochManagerHandle};
 use near_parameters::{ActionCosts, ExtCosts, RuntimeConfig, RuntimeConfigStore};
 use near_pool::types::TransactionGroupIterator;
-use near_primitives::account::{AccessKey, Account};
+use near_primitives::account::{AccessKey, Account, ContractSourceMetadata, StorageSponsorship};
 use near_primitives::apply::ApplyChunkReason;
+use near_primitives::challenge::{PartialState, SlashingSeverity};
 use near_primitives::congestion_info::{
     CongestionControl, ExtendedCongestionInfo, RejectTransactionReason, ShardAcceptsTransactions,
 };
@@ -24,7 +25,7 @@ use near_primitives::runtime::migration_data::{MigrationData, MigrationFlags};
 use near_primitives::sandbox::state_patch::SandboxStatePatch;
 use near_primitives::shard_layout::ShardUId;
 use near_primitives::state_part::PartId;
-use near_primitives::transaction::SignedTransaction;
+use near_primitives::transaction::{Action, SignedTransaction};
 use near_primitives::trie_key::TrieKey;
 use near_primitives::types::{
     AccountId, Balance, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, Gas, MerkleHash,
@@ -33,14 +34,14 @@ use near_primitives::types::{
 use near_primitives::version::{ProtocolFeature, ProtocolVersion};
 use near_primitives::views::{
     AccessKeyInfoView, CallResult, ContractCodeView, QueryRequest, QueryResponse,
-    QueryResponseKind, ViewStateResult,
+    QueryResponseKind, TokenBalanceView, ViewStateResult,
 };
 use near_store::adapter::{StoreAdapter, StoreUpdateAdapter};
 use near_store::flat::FlatStorageManager;
 use near_store::metadata::DbKind;
 use near_store::{
-    ApplyStatePartResult, DBCol, ShardTries, StateSnapshotConfig, Store, Trie, TrieConfig,
-    TrieUpdate, WrappedTrieChanges, COLD_HEAD_KEY,
+    get_account, ApplyStatePartResult, DBCol, ShardTries, StateSnapshotConfig, Store, Trie,
+    TrieConfig, TrieUpdate, WrappedTrieChanges, COLD_HEAD_KEY,
 };
 use near_vm_runner::ContractCode;
 use near_vm_runner::{precompile_contract, ContractRuntimeCache};
@@ -50,7 +51,7 @@ use node_runtime::{
     validate_transaction, verify_and_charge_transaction, ApplyState, Runtime,
     ValidatorAccountsUpdate,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::{debug, error, info, instrument};
 
@@ -185,6 +186,8 @@ impl NightshadeRuntime {
             random_seed,
             congestion_info,
             bandwidth_requests,
+            recent_block_headers,
+            execution_tracer,
         } = block;
         let ApplyChunkShardContext {
             shard_id,
@@ -204,10 +207,10 @@ impl NightshadeRuntime {
             let mut slashing_info: HashMap<_, _> = challenges_result
                 .iter()
                 .filter_map(|s| {
-                    if shard_layout.account_id_to_shard_id(&s.account_id) == shard_id
-                        && !s.is_double_sign
+                    if shard_layout.account_id_to_shard_id(s.account_id()) == shard_id
+                        && s.severity() == SlashingSeverity::Other
                     {
-                        Some((s.account_id.clone(), None))
+                        Some((s.account_id().clone(), None))
                     } else {
                         None
                     }
@@ -215,7 +218,7 @@ impl NightshadeRuntime {
                 .collect();
 
             if epoch_manager.is_next_block_epoch_start(prev_block_hash)? {
-                let (stake_info, validator_reward, double_sign_slashing_info) =
+                let (stake_info, validator_reward, partial_slashing_info) =
                     epoch_manager.compute_stake_return_info(prev_block_hash)?;
                 let stake_info = stake_info
                     .into_iter()
@@ -236,14 +239,14 @@ impl NightshadeRuntime {
                         acc.insert(account_id, stake);
                         acc
                     });
-                let double_sign_slashing_info: HashMap<_, _> = double_sign_slashing_info
+                let partial_slashing_info: HashMap<_, _> = partial_slashing_info
                     .into_iter()
                     .filter(|(account_id, _)| {
                         shard_layout.account_id_to_shard_id(account_id) == shard_id
                     })
                     .map(|(account_id, stake)| (account_id, Some(stake)))
                     .collect();
-                slashing_info.extend(double_sign_slashing_info);
+                slashing_info.extend(partial_slashing_info);
                 Some(ValidatorAccountsUpdate {
                     stake_info,
                     validator_rewards,
@@ -307,6 +310,8 @@ impl NightshadeRuntime {
             },
             congestion_info,
             bandwidth_requests,
+            recent_block_headers,
+            execution_tracer,
         };
 
         let instant = Instant::now();
@@ -642,7 +647,7 @@ impl RuntimeAdapter for NightshadeRuntime {
                 storage,
                 storage_config.state_root,
                 storage_config.use_flat_storage,
-            ),
+            )?,
         };
         // StateWitnessSizeLimit: We need to start recording reads if the stateless validation is
         // enabled in the next epoch. We need to save the state transition data in the current epoch
@@ -688,7 +693,20 @@ impl RuntimeAdapter for NightshadeRuntime {
             chunk.last_chunk_transactions_size,
             transactions_gas_limit,
         );
+        // Gas burnt and size of the transactions of each signer, checked against the quotas of a
+        // single account when `AccountTransactionQuota` is enabled.
+        let account_quota_enabled =
+            ProtocolFeature::AccountTransactionQuota.enabled(protocol_version);
+        let account_gas_quota =
+            runtime_config.congestion_control_config.account_tx_gas_quota(transactions_gas_limit);
+        let account_size_quota = runtime_config.congestion_control_config.account_tx_size_quota(
+            size_limit,
+            runtime_config.wasm_config.limit_config.max_transaction_size,
+        );
+        let mut account_usage: HashMap<AccountId, (Gas, u64)> = HashMap::new();
+
         // for metrics only
+        let mut skipped_due_to_account_quota = 0;
         let mut rejected_due_to_congestion = 0;
         let mut rejected_invalid_tx = 0;
         let mut rejected_invalid_for_chain = 0;
@@ -743,6 +761,24 @@ impl RuntimeAdapter for NightshadeRuntime {
                     break 'add_txs_loop;
                 }
 
+                // AccountTransactionQuota: Leave the transactions of an account that used its
+                // share of the chunk in the pool, they can be included in a later chunk. A chunk
+                // validator rejects a chunk including them because it skips them as well.
+                if account_quota_enabled {
+                    let (gas_used, size_used) = account_usage
+                        .get(tx_peek.transaction.signer_id())
+                        .copied()
+                        .unwrap_or_default();
+                    if gas_used >= account_gas_quota
+                        || size_used.saturating_add(tx_peek.get_size()) > account_size_quota
+                    {
+                        tracing::trace!(target: "runtime", tx=?tx_peek.get_hash(), "skipping transaction of an account that used its quota");
+                        skipped_due_to_account_quota += 1;
+                        transaction_group_iter.defer();
+                        continue 'add_txs_loop;
+                    }
+                }
+
                 // Take the transaction out of the pool. Please take note that
                 // the transaction may still be rejected in which case it will
                 // not be returned to the pool. Most notably this may happen
@@ -787,6 +823,13 @@ impl RuntimeAdapter for NightshadeRuntime {
                         state_update.commit(StateChangeCause::NotWritableToDisk);
                         total_gas_burnt += verification_result.gas_burnt;
                         total_size += tx.get_size();
+                        if account_quota_enabled {
+                            let (gas_used, size_used) = account_usage
+                                .entry(tx.transaction.signer_id().clone())
+                                .or_default();
+                            *gas_used += verification_result.gas_burnt;
+                            *size_used += tx.get_size();
+                        }
                         result.transactions.push(tx);
                         // Take one transaction from this group, no more.
                         break;
@@ -811,6 +854,9 @@ impl RuntimeAdapter for NightshadeRuntime {
         metrics::PREPARE_TX_REJECTED
             .with_label_values(&[&shard_label, "invalid_block_hash"])
             .observe(rejected_invalid_for_chain as f64);
+        metrics::PREPARE_TX_REJECTED
+            .with_label_values(&[&shard_label, "account_quota"])
+            .observe(skipped_due_to_account_quota as f64);
         metrics::PREPARE_TX_GAS.with_label_values(&[&shard_label]).observe(total_gas_burnt as f64);
         metrics::CONGESTION_PREPARE_TX_GAS_LIMIT
             .with_label_values(&[&shard_label])
@@ -869,7 +915,7 @@ impl RuntimeAdapter for NightshadeRuntime {
                 storage,
                 storage_config.state_root,
                 storage_config.use_flat_storage,
-            ),
+            )?,
         };
         let next_epoch_id =
             self.epoch_manager.get_next_epoch_id_from_prev_block(&block.prev_block_hash)?;
@@ -1053,6 +1099,57 @@ impl RuntimeAdapter for NightshadeRuntime {
                     block_hash: *block_hash,
                 })
             }
+            QueryRequest::ViewStorageSponsorship { account_id } => {
+                let sponsorship = self
+                    .view_storage_sponsorship(&shard_uid, *state_root, account_id)
+                    .map_err(|err| {
+                        crate::near_chain_primitives::error::QueryError::from_view_account_error(
+                            err,
+                            block_height,
+                            *block_hash,
+                        )
+                    })?;
+                Ok(QueryResponse {
+                    kind: QueryResponseKind::StorageSponsorship(sponsorship.into()),
+                    block_height,
+                    block_hash: *block_hash,
+                })
+            }
+            QueryRequest::ViewContractSourceMetadata { account_id } => {
+                let metadata = self
+                    .view_contract_source_metadata(&shard_uid, *state_root, account_id)
+                    .map_err(|err| {
+                        crate::near_chain_primitives::error::QueryError::from_view_account_error(
+                            err,
+                            block_height,
+                            *block_hash,
+                        )
+                    })?;
+                Ok(QueryResponse {
+                    kind: QueryResponseKind::ContractSourceMetadata(metadata.into()),
+                    block_height,
+                    block_hash: *block_hash,
+                })
+            }
+            QueryRequest::ViewTokenBalance { account_id, token_id } => {
+                let balance = self
+                    .view_token_balance(&shard_uid, *state_root, account_id, token_id)
+                    .map_err(|err| {
+                        crate::near_chain_primitives::error::QueryError::from_view_account_error(
+                            err,
+                            block_height,
+                            *block_hash,
+                        )
+                    })?;
+                Ok(QueryResponse {
+                    kind: QueryResponseKind::TokenBalance(TokenBalanceView {
+                        token_id: token_id.clone(),
+                        balance,
+                    }),
+                    block_height,
+                    block_hash: *block_hash,
+                })
+            }
         }
     }
 
@@ -1083,8 +1180,20 @@ impl RuntimeAdapter for NightshadeRuntime {
         res
     }
 
-    fn validate_state_part(&self, state_root: &StateRoot, part_id: PartId, data: &[u8]) -> bool {
+    fn validate_state_part(
+        &self,
+        state_root: &StateRoot,
+        part_id: PartId,
+        data: &[u8],
+        protocol_version: ProtocolVersion,
+    ) -> bool {
         match BorshDeserialize::try_from_slice(data) {
+            Ok(PartialState::StatePartRange(_))
+                if !ProtocolFeature::StatePartRange.enabled(protocol_version) =>
+            {
+                tracing::error!(target: "state-parts", "StatePartRange state part is not supported");
+                false
+            }
             Ok(trie_nodes) => {
                 match Trie::validate_state_part(state_root, part_id, trie_nodes) {
                     Ok(_) => true,
@@ -1261,6 +1370,45 @@ impl RuntimeAdapter for NightshadeRuntime {
         });
         Ok(())
     }
+
+    fn precompile_transactions_contracts(
+        &self,
+        epoch_id: &EpochId,
+        state_roots: &HashMap<ShardId, StateRoot>,
+        transactions: &[SignedTransaction],
+    ) -> Result<(), Error> {
+        let _span = tracing::debug_span!(
+            target: "runtime",
+            "precompile_transactions_contracts",
+            num_transactions = transactions.len())
+        .entered();
+        let shard_layout = self.epoch_manager.get_shard_layout(epoch_id)?;
+        let mut tries = HashMap::new();
+        let mut code_hashes = HashSet::new();
+        let mut contract_codes = vec![];
+        for transaction in transactions {
+            let transaction = &transaction.transaction;
+            if !transaction.actions().iter().any(|action| matches!(action, Action::FunctionCall(_)))
+            {
+                continue;
+            }
+            let receiver_id = transaction.receiver_id();
+            let shard_id = shard_layout.account_id_to_shard_id(receiver_id);
+            let Some(state_root) = state_roots.get(&shard_id) else { continue };
+            let trie: &Trie = tries.entry(shard_id).or_insert_with(|| {
+                let shard_uid = ShardUId::from_shard_id_and_layout(shard_id, &shard_layout);
+                self.tries.get_trie_for_shard(shard_uid, *state_root)
+            });
+            let Some(account) = get_account(trie, receiver_id)? else { continue };
+            let code_hash = account.code_hash();
+            if code_hash == CryptoHash::default() || !code_hashes.insert(code_hash) {
+                continue;
+            }
+            let code = trie.retrieve_value(&code_hash)?;
+            contract_codes.push(ContractCode::new(code, Some(code_hash)));
+        }
+        self.precompile_contracts(epoch_id, contract_codes)
+    }
 }
 
 /// Get the limit on the number of new receipts imposed by the local congestion control.
@@ -1442,6 +1590,39 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         )
     }
 
+    fn view_storage_sponsorship(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        account_id: &AccountId,
+    ) -> Result<Option<StorageSponsorship>, node_runtime::state_viewer::errors::ViewAccountError>
+    {
+        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.trie_viewer.view_storage_sponsorship(&state_update, account_id)
+    }
+
+    fn view_contract_source_metadata(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        account_id: &AccountId,
+    ) -> Result<Option<ContractSourceMetadata>, node_runtime::state_viewer::errors::ViewAccountError>
+    {
+        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.trie_viewer.view_contract_source_metadata(&state_update, account_id)
+    }
+
+    fn view_token_balance(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        account_id: &AccountId,
+        token_id: &AccountId,
+    ) -> Result<Balance, node_runtime::state_viewer::errors::ViewAccountError> {
+        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.trie_viewer.view_token_balance(&state_update, account_id, token_id)
+    }
+
     fn view_access_key(
         &self,
         shard_uid: &ShardUId,
@@ -16,6 +16,7 @@ use near_primitives::bandwidth_scheduler::BlockBandwidthRequests;
 use near_primitives::congestion_info::{BlockCongestionInfo, ExtendedCongestionInfo};
 use near_primitives::epoch_block_info::BlockInfo;
 use near_primitives::receipt::{ActionReceipt, ReceiptV1};
+use near_primitives::state_record::StateRecord;
 use near_primitives::stateless_validation::ChunkProductionKey;
 use near_primitives::test_utils::create_test_signer;
 use near_primitives::types::validator_stake::{ValidatorStake, ValidatorStakeIter};
@@ -36,7 +37,9 @@ use near_chain_configs::{
 use near_crypto::{InMemorySigner, Signer};
 use near_o11y::testonly::init_test_logger;
 use near_primitives::block::Tip;
-use near_primitives::challenge::{ChallengesResult, PartialState, SlashedValidator};
+use near_primitives::challenge::{
+    ChallengesResult, PartialState, SlashedValidator, SlashingSeverity,
+};
 use near_primitives::transaction::{Action, DeleteAccountAction, StakeAction, TransferAction};
 use near_primitives::types::{
     BlockHeightDelta, Nonce, ValidatorId, ValidatorInfoIdentifier, ValidatorKickoutReason,
@@ -267,6 +270,8 @@ impl TestEnv {
                     random_seed: CryptoHash::default(),
                     congestion_info,
                     bandwidth_requests: BlockBandwidthRequests::empty(),
+                    recent_block_headers: Default::default(),
+                    execution_tracer: None,
                 },
                 receipts,
                 transactions,
@@ -782,9 +787,15 @@ fn test_state_sync() {
     assert!(!new_env.runtime.validate_state_part(
         &Trie::EMPTY_ROOT,
         PartId::new(0, 1),
-        &state_part
+        &state_part,
+        PROTOCOL_VERSION,
     ));
-    new_env.runtime.validate_state_part(&env.state_roots[0], PartId::new(0, 1), &state_part);
+    new_env.runtime.validate_state_part(
+        &env.state_roots[0],
+        PartId::new(0, 1),
+        &state_part,
+        PROTOCOL_VERSION,
+    );
     let epoch_id = &new_env.head.epoch_id;
     new_env
         .runtime
@@ -897,6 +908,7 @@ fn test_get_validator_info() {
             num_expected_endorsements: expected_endorsements[0],
             num_expected_endorsements_per_shard: vec![expected_endorsements[0]],
             num_produced_endorsements_per_shard: vec![expected_endorsements[0]],
+            metadata: None,
         },
         CurrentEpochValidatorInfo {
             account_id: "test2".parse().unwrap(),
@@ -915,6 +927,7 @@ fn test_get_validator_info() {
             num_expected_endorsements: expected_endorsements[1],
             num_expected_endorsements_per_shard: vec![expected_endorsements[1]],
             num_produced_endorsements_per_shard: vec![expected_endorsements[1]],
+            metadata: None,
         },
     ];
     let next_epoch_validator_info = vec![
@@ -923,12 +936,14 @@ fn test_get_validator_info() {
             public_key: block_producers[0].public_key(),
             stake: TESTING_INIT_STAKE,
             shards: vec![shard_id],
+            metadata: None,
         },
         NextEpochValidatorInfo {
             account_id: "test2".parse().unwrap(),
             public_key: block_producers[1].public_key(),
             stake: TESTING_INIT_STAKE,
             shards: vec![shard_id],
+            metadata: None,
         },
     ];
     let response = env
@@ -1001,6 +1016,7 @@ fn test_get_validator_info() {
             public_key: block_producers[1].public_key(),
             stake: TESTING_INIT_STAKE,
             shards: vec![ShardId::new(0)],
+            metadata: None,
         }]
     );
     assert!(response.current_proposals.is_empty());
@@ -1021,7 +1037,7 @@ fn test_challenges() {
     env.step(
         vec![vec![]],
         vec![true],
-        vec![SlashedValidator::new("test2".parse().unwrap(), false)],
+        vec![SlashedValidator::new("test2".parse().unwrap(), SlashingSeverity::Other)],
     );
     assert_eq!(env.view_account(&"test2".parse().unwrap()).locked, 0);
     let mut bps = env
@@ -1070,7 +1086,7 @@ fn test_double_sign_challenge_not_all_slashed() {
     env.step(
         vec![vec![staking_transaction]],
         vec![true],
-        vec![SlashedValidator::new("test2".parse().unwrap(), true)],
+        vec![SlashedValidator::new("test2".parse().unwrap(), SlashingSeverity::DoubleSign)],
     );
     assert_eq!(env.view_account(&"test2".parse().unwrap()).locked, TESTING_INIT_STAKE);
     let mut bps = env
@@ -1106,7 +1122,11 @@ fn test_double_sign_challenge_not_all_slashed() {
     for _ in 2..11 {
         env.step(vec![vec![]], vec![true], vec![]);
     }
-    env.step(vec![vec![]], vec![true], vec![SlashedValidator::new("test3".parse().unwrap(), true)]);
+    env.step(
+        vec![vec![]],
+        vec![true],
+        vec![SlashedValidator::new("test3".parse().unwrap(), SlashingSeverity::DoubleSign)],
+    );
     let account = env.view_account(&"test3".parse().unwrap());
     assert_eq!(account.locked, TESTING_INIT_STAKE / 3);
     assert_eq!(account.amount, TESTING_INIT_BALANCE - TESTING_INIT_STAKE / 3);
@@ -1143,8 +1163,16 @@ fn test_double_sign_challenge_all_slashed() {
         .collect::<Vec<_>>();
     let mut env = TestEnv::new(vec![validators.clone()], 5, false);
     let signers: Vec<_> = validators.iter().map(|id| InMemorySigner::test_signer(&id)).collect();
-    env.step(vec![vec![]], vec![true], vec![SlashedValidator::new("test1".parse().unwrap(), true)]);
-    env.step(vec![vec![]], vec![true], vec![SlashedValidator::new("test2".parse().unwrap(), true)]);
+    env.step(
+        vec![vec![]],
+        vec![true],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::DoubleSign)],
+    );
+    env.step(
+        vec![vec![]],
+        vec![true],
+        vec![SlashedValidator::new("test2".parse().unwrap(), SlashingSeverity::DoubleSign)],
+    );
     let msg = vec![0, 1, 2];
     for i in 0..=1 {
         let signature = signers[i].sign(&msg);
@@ -1186,16 +1214,16 @@ fn test_double_sign_with_other_challenges() {
         vec![vec![]],
         vec![true],
         vec![
-            SlashedValidator::new("test1".parse().unwrap(), true),
-            SlashedValidator::new("test2".parse().unwrap(), false),
+            SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::DoubleSign),
+            SlashedValidator::new("test2".parse().unwrap(), SlashingSeverity::Other),
         ],
     );
     env.step(
         vec![vec![]],
         vec![true],
         vec![
-            SlashedValidator::new("test1".parse().unwrap(), false),
-            SlashedValidator::new("test2".parse().unwrap(), true),
+            SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::Other),
+            SlashedValidator::new("test2".parse().unwrap(), SlashingSeverity::DoubleSign),
         ],
     );
 
@@ -1788,7 +1816,9 @@ fn test_storage_proof_garbage() {
     });
     let apply_result =
         env.apply_new_chunk(shard_id, hash(&[42]), &[], &[receipt], ChallengesResult::default());
-    let PartialState::TrieValues(storage_proof) = apply_result.proof.unwrap().nodes;
+    let PartialState::TrieValues(storage_proof) = apply_result.proof.unwrap().nodes else {
+        panic!("expected trie values in partial storage");
+    };
     let total_size: usize = storage_proof.iter().map(|v| v.len()).sum();
     assert_eq!(total_size / 1000_000, garbage_size_mb);
 }
@@ -1863,6 +1893,90 @@ fn test_precompile_contracts_updates_cache() {
     }
 }
 
+/// Tests that contracts called by transactions are precompiled from the state
+/// of the receiver's shard.
+#[test]
+fn test_precompile_transactions_contracts() {
+    let accounts: Vec<AccountId> = vec!["test0".parse().unwrap(), "test1".parse().unwrap()];
+    let code = ContractCode::new(near_test_contracts::rs_contract().to_vec(), None);
+    let mut genesis = Genesis::test(accounts.clone(), 1);
+    let records = genesis.force_read_records().as_mut();
+    for record in records.iter_mut() {
+        if let StateRecord::Account { account_id, account } = record {
+            if account_id == &accounts[1] {
+                account.set_code_hash(*code.hash());
+            }
+        }
+    }
+    records.push(StateRecord::Contract {
+        account_id: accounts[1].clone(),
+        code: code.code().to_vec(),
+    });
+    let store = near_store::test_utils::create_test_store();
+    let tempdir = tempfile::tempdir().unwrap();
+    initialize_genesis_state(store.clone(), &genesis, Some(tempdir.path()));
+    let state_roots = get_genesis_state_roots(&store).unwrap().unwrap();
+    let epoch_manager = EpochManager::new_arc_handle(store.clone(), &genesis.config, None);
+
+    let contract_cache = FilesystemContractRuntimeCache::new(tempdir.path(), None::<&str>)
+        .expect("filesystem contract cache");
+    let runtime = NightshadeRuntime::test_with_runtime_config_store(
+        tempdir.path(),
+        store,
+        contract_cache.handle(),
+        &genesis.config,
+        epoch_manager,
+        RuntimeConfigStore::new(None),
+        StateSnapshotType::EveryEpoch,
+    );
+    let cache_key = get_contract_cache_key(
+        *code.hash(),
+        &runtime.get_runtime_config(PROTOCOL_VERSION).unwrap().wasm_config,
+    );
+
+    let signer = InMemorySigner::test_signer(&accounts[0]);
+    let transfer = SignedTransaction::send_money(
+        1,
+        accounts[0].clone(),
+        accounts[1].clone(),
+        &signer,
+        1,
+        CryptoHash::default(),
+    );
+    let call = SignedTransaction::call(
+        2,
+        accounts[0].clone(),
+        accounts[1].clone(),
+        &signer,
+        0,
+        "log_something".to_string(),
+        vec![],
+        10u64.pow(12),
+        CryptoHash::default(),
+    );
+    let state_roots = HashMap::from([(ShardId::new(0), state_roots[0])]);
+
+    // Transactions without function calls don't compile anything.
+    runtime
+        .precompile_transactions_contracts(&EpochId::default(), &state_roots, &[transfer.clone()])
+        .unwrap();
+    assert!(contract_cache.get(&cache_key).unwrap().is_none());
+
+    // Receivers in shards without a state root are skipped.
+    runtime
+        .precompile_transactions_contracts(&EpochId::default(), &HashMap::new(), &[call.clone()])
+        .unwrap();
+    assert!(contract_cache.get(&cache_key).unwrap().is_none());
+
+    runtime
+        .precompile_transactions_contracts(&EpochId::default(), &state_roots, &[transfer, call])
+        .unwrap();
+    assert_matches!(
+        contract_cache.get(&cache_key).unwrap(),
+        Some(CompiledContractInfo { compiled: CompiledContract::Code(_), .. })
+    );
+}
+
 fn stake(
     nonce: Nonce,
     signer: &Signer,
@@ -11,7 +11,9 @@ use crate::types::{
     ApplyChunkBlockContext, ApplyChunkResult, PreparedTransactions, RuntimeAdapter,
     RuntimeStorageConfig, StorageDataSource,
 };
-use crate::validate::validate_chunk_with_chunk_extra_and_receipts_root;
+use crate::validate::{
+    decode_challenged_state_witness, validate_chunk_with_chunk_extra_and_receipts_root,
+};
 use crate::{Chain, ChainStore, ChainStoreAccess};
 use lru::LruCache;
 use near_async::futures::AsyncComputationSpawnerExt;
@@ -19,7 +21,9 @@ use near_chain_primitives::Error;
 use near_epoch_manager::EpochManagerAdapter;
 use near_pool::TransactionGroupIteratorWrapper;
 use near_primitives::apply::ApplyChunkReason;
+use near_primitives::bandwidth_scheduler::OutgoingBandwidth;
 use near_primitives::block::Block;
+use near_primitives::challenge::{InvalidStateWitness, PartialState};
 use near_primitives::checked_feature;
 use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::merkle::merklize;
@@ -31,8 +35,9 @@ use near_primitives::stateless_validation::state_witness::{
 };
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::chunk_extra::ChunkExtra;
-use near_primitives::types::{AccountId, ProtocolVersion, ShardId, ShardIndex};
+use near_primitives::types::{AccountId, ProtocolVersion, ShardId, ShardIndex, StateRoot};
 use near_primitives::utils::compression::CompressedData;
+use near_primitives::version::ProtocolFeature;
 use near_store::trie::ops::resharding::RetainMode;
 use near_store::{PartialStorage, Trie};
 use std::collections::HashMap;
@@ -62,6 +67,14 @@ impl MainTransition {
             Self::NewChunk(data) => data.chunk_header.shard_id(),
         }
     }
+
+    /// State root the main transition is applied to.
+    pub fn pre_state_root(&self) -> StateRoot {
+        match self {
+            Self::Genesis { chunk_extra, .. } => *chunk_extra.state_root(),
+            Self::NewChunk(data) => data.chunk_header.prev_state_root(),
+        }
+    }
 }
 
 pub struct PreValidationOutput {
@@ -221,6 +234,7 @@ fn get_state_witness_block_range(
             0 => {
                 let block_context = Chain::get_apply_chunk_block_context(
                     epoch_manager,
+                    store,
                     &position.prev_block,
                     &store.get_block_header(&prev_prev_hash)?,
                     false,
@@ -329,6 +343,17 @@ pub fn pre_validate_chunk_state_witness(
     let protocol_version =
         epoch_manager.get_epoch_info(&state_witness.epoch_id)?.protocol_version();
     state_witness.chunk_header.validate_version(protocol_version)?;
+    if !ProtocolFeature::StatePartRange.enabled(protocol_version) {
+        let mut transitions = std::iter::once(&state_witness.main_state_transition)
+            .chain(&state_witness.implicit_transitions);
+        if transitions
+            .any(|transition| !matches!(transition.base_state, PartialState::TrieValues(_)))
+        {
+            return Err(Error::InvalidChunkStateWitness(
+                "StatePartRange base state is not supported in this protocol version".into(),
+            ));
+        }
+    }
 
     // First, go back through the blockchain history to locate the last new chunk
     // and last last new chunk for the shard.
@@ -448,6 +473,7 @@ pub fn pre_validate_chunk_state_witness(
             receipts: receipts_to_apply,
             block: Chain::get_apply_chunk_block_context(
                 epoch_manager,
+                store,
                 last_chunk_block,
                 &store.get_block_header(last_chunk_block.header().prev_hash())?,
                 true,
@@ -608,6 +634,7 @@ pub fn validate_chunk_state_witness(
             .get_mut(&witness_chunk_shard_uid)
             .and_then(|cache| cache.get(&block_hash).cloned())
     };
+    let pre_state_root = pre_validation_output.main_transition_params.pre_state_root();
     let (mut chunk_extra, mut outgoing_receipts) =
         match (pre_validation_output.main_transition_params, cache_result) {
             (MainTransition::Genesis { chunk_extra, .. }, _) => (chunk_extra, vec![]),
@@ -639,8 +666,8 @@ pub fn validate_chunk_state_witness(
         )));
     }
 
-    // Compute receipt hashes here to avoid copying receipts
-    let outgoing_receipts_hashes = {
+    // Compute receipt hashes and outgoing bandwidth here to avoid copying receipts
+    let (outgoing_receipts_hashes, outgoing_bandwidth) = {
         let chunk_epoch_id = epoch_manager.get_epoch_id(&block_hash)?;
         let chunk_shard_layout = epoch_manager.get_shard_layout(&chunk_epoch_id)?;
         if chunk_shard_layout != witness_shard_layout {
@@ -652,7 +679,10 @@ pub fn validate_chunk_state_witness(
                 shard_id,
             )?;
         }
-        Chain::build_receipts_hashes(&outgoing_receipts, &witness_shard_layout)
+        (
+            Chain::build_receipts_hashes(&outgoing_receipts, &witness_shard_layout),
+            OutgoingBandwidth::from_receipts(&outgoing_receipts, &witness_shard_layout),
+        )
     };
     // Save main state transition result to cache.
     {
@@ -716,7 +746,7 @@ pub fn validate_chunk_state_witness(
                     PartialStorage { nodes: transition.base_state },
                     old_root,
                     true,
-                );
+                )?;
                 let new_root = trie.retain_split_shard(&boundary_account, retain_mode)?;
                 (child_shard_uid, new_root)
             }
@@ -738,13 +768,64 @@ pub fn validate_chunk_state_witness(
     let (outgoing_receipts_root, _) = merklize(&outgoing_receipts_hashes);
     validate_chunk_with_chunk_extra_and_receipts_root(
         &chunk_extra,
+        &pre_state_root,
         &state_witness.chunk_header,
         &outgoing_receipts_root,
+        &outgoing_bandwidth,
     )?;
 
     Ok(())
 }
 
+/// Re-executes the state witness from an `InvalidStateWitness` challenge with
+/// its own base state, as signed by the chunk producer.
+/// Only invalid witness data is evidence against the validators: missing blocks,
+/// state or storage errors depend on the node, so they make the challenge malicious.
+/// Returns `Error::MaliciousChallenge` if the witness turns out to be valid.
+pub fn reexecute_challenged_state_witness(
+    invalid_state_witness: &InvalidStateWitness,
+    chain: &Chain,
+    epoch_manager: &dyn EpochManagerAdapter,
+    runtime_adapter: &dyn RuntimeAdapter,
+) -> Result<(), Error> {
+    let state_witness = decode_challenged_state_witness(epoch_manager, invalid_state_witness)?;
+    let pre_validation_output = match pre_validate_chunk_state_witness(
+        &state_witness,
+        chain,
+        epoch_manager,
+        runtime_adapter,
+    ) {
+        Ok(pre_validation_output) => pre_validation_output,
+        Err(err) if err.is_bad_data() => {
+            tracing::debug!(target: "chain", ?err, "Challenged state witness failed pre-validation");
+            return Ok(());
+        }
+        Err(err) => {
+            tracing::debug!(target: "chain", ?err, "Could not pre-validate challenged state witness");
+            return Err(Error::MaliciousChallenge);
+        }
+    };
+    // Use a fresh cache so that the result is not taken from a previous validation.
+    let main_state_transition_cache = MainStateTransitionCache::default();
+    match validate_chunk_state_witness(
+        state_witness,
+        pre_validation_output,
+        epoch_manager,
+        runtime_adapter,
+        &main_state_transition_cache,
+    ) {
+        Ok(()) => Err(Error::MaliciousChallenge),
+        Err(err) if err.is_bad_data() => {
+            tracing::debug!(target: "chain", ?err, "Challenged state witness failed validation");
+            Ok(())
+        }
+        Err(err) => {
+            tracing::debug!(target: "chain", ?err, "Could not validate challenged state witness");
+            Err(Error::MaliciousChallenge)
+        }
+    }
+}
+
 pub fn apply_result_to_chunk_extra(
     protocol_version: ProtocolVersion,
     apply_result: ApplyChunkResult,
@@ -764,6 +845,10 @@ pub fn apply_result_to_chunk_extra(
     )
 }
 
+/// Called with the error of a chunk which failed shadow validation, on the
+/// thread which validated it.
+pub type ShadowValidationFailureHandler = Box<dyn FnOnce(&Error) + Send>;
+
 impl Chain {
     pub fn shadow_validate_state_witness(
         &self,
@@ -771,6 +856,7 @@ impl Chain {
         epoch_manager: &dyn EpochManagerAdapter,
         runtime_adapter: &dyn RuntimeAdapter,
         processing_done_tracker: Option<ProcessingDoneTracker>,
+        on_failure: Option<ShadowValidationFailureHandler>,
     ) -> Result<(), Error> {
         let shard_id = witness.chunk_header.shard_id();
         let height_created = witness.chunk_header.height_created();
@@ -839,6 +925,9 @@ impl Chain {
                     crate::stateless_validation::metrics::CHUNK_WITNESS_VALIDATION_FAILED_TOTAL
                         .with_label_values(&[&shard_id.to_string(), err.prometheus_label_value()])
                         .inc();
+                    crate::stateless_validation::metrics::SHADOW_CHUNK_VALIDATION_DIVERGENCES_TOTAL
+                        .with_label_values(&[&shard_id.to_string()])
+                        .inc();
                     tracing::error!(
                         parent: &parent_span,
                         ?err,
@@ -846,6 +935,9 @@ impl Chain {
                         ?chunk_hash,
                         "shadow chunk validation failed"
                     );
+                    if let Some(on_failure) = on_failure {
+                        on_failure(&err);
+                    }
                 }
             }
         });
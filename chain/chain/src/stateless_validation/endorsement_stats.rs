@@ -0,0 +1,126 @@
+//! Per-epoch statistics of the chunk endorsements of each chunk validator.
+//!
+//! The epoch manager only keeps the total number of endorsements of each
+//! validator in an epoch, which is not enough to tell why a validator was
+//! kicked out. Here the endorsements included in the final blocks are
+//! recorded per shard, together with the latest chunks the validator failed
+//! to endorse.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use near_chain_primitives::Error;
+use near_epoch_manager::EpochManagerAdapter;
+use near_primitives::block::Tip;
+use near_primitives::stateless_validation::endorsement_stats::ValidatorEndorsementStats;
+use near_primitives::types::{AccountId, EpochId};
+use near_store::DBCol;
+
+use crate::{Chain, ChainStoreAccess, ChainStoreUpdate};
+
+fn get_endorsement_stats_key(epoch_id: &EpochId, account_id: &AccountId) -> Vec<u8> {
+    let mut key = epoch_id.0.as_bytes().to_vec();
+    key.extend(account_id.as_bytes());
+    key
+}
+
+impl Chain {
+    /// Returns the endorsement statistics of `account_id` in the epoch
+    /// `epoch_id`, recorded from the final blocks of the epoch so far.
+    pub fn get_validator_endorsement_stats(
+        &self,
+        epoch_id: &EpochId,
+        account_id: &AccountId,
+    ) -> Result<ValidatorEndorsementStats, Error> {
+        Ok(self
+            .chain_store
+            .store()
+            .get_ser(
+                DBCol::ChunkEndorsementStats,
+                &get_endorsement_stats_key(epoch_id, account_id),
+            )?
+            .unwrap_or_default())
+    }
+}
+
+/// Records in `chain_store_update` the endorsements included in the blocks
+/// which became final since the final head was `prev_final_head`, so that
+/// they are committed together with the block which finalized them.
+pub(crate) fn record_endorsement_stats(
+    chain_store_update: &mut ChainStoreUpdate,
+    epoch_manager: &dyn EpochManagerAdapter,
+    prev_final_head: &Tip,
+) -> Result<(), Error> {
+    let final_head = chain_store_update.final_head()?;
+    let mut blocks = vec![];
+    let mut block_hash = final_head.last_block_hash;
+    while block_hash != prev_final_head.last_block_hash {
+        // Blocks below the sync point after state sync are not available.
+        let Ok(block) = chain_store_update.get_block(&block_hash) else {
+            break;
+        };
+        if block.header().height() <= prev_final_head.height {
+            break;
+        }
+        block_hash = *block.header().prev_hash();
+        blocks.push(block);
+    }
+
+    let store = chain_store_update.store().clone();
+    let mut stats: HashMap<(EpochId, AccountId), ValidatorEndorsementStats> = HashMap::new();
+    for block in blocks.iter().rev() {
+        let epoch_id = *block.header().epoch_id();
+        for (chunk_header, signatures) in
+            block.chunks().iter_deprecated().zip(block.chunk_endorsements())
+        {
+            if chunk_header.height_included() != block.header().height() {
+                continue;
+            }
+            let shard_id = chunk_header.shard_id();
+            let height_created = chunk_header.height_created();
+            let chunk_validator_assignments = epoch_manager.get_chunk_validator_assignments(
+                &epoch_id,
+                shard_id,
+                height_created,
+            )?;
+            let ordered_chunk_validators = chunk_validator_assignments.ordered_chunk_validators();
+            // Blocks from before stateless validation have no endorsements.
+            if ordered_chunk_validators.len() != signatures.len() {
+                continue;
+            }
+            for (account_id, signature) in ordered_chunk_validators.iter().zip(signatures) {
+                let validator_stats = match stats.entry((epoch_id, account_id.clone())) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(
+                        store
+                            .get_ser(
+                                DBCol::ChunkEndorsementStats,
+                                &get_endorsement_stats_key(&epoch_id, account_id),
+                            )?
+                            .unwrap_or_default(),
+                    ),
+                };
+                validator_stats.record(
+                    shard_id,
+                    height_created,
+                    &chunk_header.chunk_hash(),
+                    signature.is_some(),
+                );
+            }
+        }
+    }
+    if stats.is_empty() {
+        return Ok(());
+    }
+
+    let mut store_update = store.store_update();
+    for ((epoch_id, account_id), validator_stats) in stats {
+        store_update.set_ser(
+            DBCol::ChunkEndorsementStats,
+            &get_endorsement_stats_key(&epoch_id, &account_id),
+            &validator_stats,
+        )?;
+    }
+    chain_store_update.merge(store_update);
+    Ok(())
+}
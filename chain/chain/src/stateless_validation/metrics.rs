@@ -70,6 +70,16 @@ pub static SHADOW_CHUNK_VALIDATION_FAILED_TOTAL: LazyLock<IntCounter> = LazyLock
     .unwrap()
 });
 
+pub static SHADOW_CHUNK_VALIDATION_DIVERGENCES_TOTAL: LazyLock<IntCounterVec> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "near_shadow_chunk_validation_divergences_total",
+            "Number of chunks whose state witness produced by the node failed validation",
+            &["shard_id"],
+        )
+        .unwrap()
+    });
+
 pub static CHUNK_WITNESS_VALIDATION_FAILED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "near_chunk_witness_validation_failed_total",
@@ -1,5 +1,6 @@
 pub mod chunk_endorsement;
 pub mod chunk_validation;
+pub mod endorsement_stats;
 pub mod metrics;
 pub mod processing_tracker;
 pub(crate) mod state_transition_data;
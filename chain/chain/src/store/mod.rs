@@ -8,7 +8,8 @@ use near_cache::CellLruCache;
 
 use near_chain_primitives::error::Error;
 use near_epoch_manager::EpochManagerAdapter;
-use near_primitives::block::Tip;
+use near_primitives::block::{StoredInvalidBlock, Tip};
+use near_primitives::challenge::StoredChallenge;
 use near_primitives::checked_feature;
 use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
@@ -37,8 +38,8 @@ use near_primitives::types::{
     StateChangesKinds, StateChangesKindsExt, StateChangesRequest,
 };
 use near_primitives::utils::{
-    get_block_shard_id, get_outcome_id_block_hash, get_outcome_id_block_hash_rev, index_to_bytes,
-    to_timestamp,
+    get_block_shard_id, get_challenge_key, get_invalid_block_key, get_outcome_id_block_hash,
+    get_outcome_id_block_hash_rev, index_to_bytes, to_timestamp,
 };
 use near_primitives::version::ProtocolVersion;
 use near_primitives::views::LightClientBlockView;
@@ -387,8 +388,7 @@ pub fn filter_incoming_receipts_for_shard(
         let mut filtered_receipts = vec![];
         let ReceiptProof(receipts, shard_proof) = receipt_proof.clone();
         for receipt in receipts {
-            let receiver_shard_id =
-                target_shard_layout.account_id_to_shard_id(receipt.receiver_id());
+            let receiver_shard_id = receipt.receiver_shard_id(target_shard_layout);
             if receiver_shard_id == target_shard_id {
                 tracing::trace!(target: "chain", receipt_id=?receipt.receipt_id(), "including receipt");
                 filtered_receipts.push(receipt);
@@ -523,6 +523,83 @@ impl ChainStore {
             .collect()
     }
 
+    /// Returns all challenges persisted in `DBCol::Challenges`.
+    pub fn iterate_challenges(&self) -> Result<Vec<StoredChallenge>, Error> {
+        self.store.iter_ser::<StoredChallenge>(DBCol::Challenges).map(|item| Ok(item?.1)).collect()
+    }
+
+    /// Returns the stored verdict if the block `block_hash` at `height` was
+    /// found invalid.
+    pub fn get_invalid_block(
+        &self,
+        height: BlockHeight,
+        block_hash: &CryptoHash,
+    ) -> Result<Option<StoredInvalidBlock>, Error> {
+        Ok(self.store.get_ser(DBCol::InvalidBlocks, &get_invalid_block_key(height, block_hash))?)
+    }
+
+    /// Persists that the block `block_hash` at `height` is invalid because of
+    /// `reason`.
+    pub fn save_invalid_block(
+        &self,
+        block_hash: &CryptoHash,
+        height: BlockHeight,
+        reason: String,
+    ) -> Result<(), Error> {
+        let mut store_update = self.store.store_update();
+        store_update.set_ser(
+            DBCol::InvalidBlocks,
+            &get_invalid_block_key(height, block_hash),
+            &StoredInvalidBlock { height, reason },
+        )?;
+        Ok(store_update.commit()?)
+    }
+
+    /// Returns all blocks persisted in `DBCol::InvalidBlocks` with their hashes.
+    pub fn iterate_invalid_blocks(&self) -> Result<Vec<(CryptoHash, StoredInvalidBlock)>, Error> {
+        self.store
+            .iter_ser::<StoredInvalidBlock>(DBCol::InvalidBlocks)
+            .map(|item| {
+                let (key, invalid_block) = item?;
+                // The key is the height followed by the block hash.
+                let block_hash = key
+                    .get(size_of::<BlockHeight>()..)
+                    .and_then(|block_hash| CryptoHash::try_from(block_hash).ok())
+                    .ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("wrong key length: {key:?}"),
+                        )
+                    })?;
+                Ok((block_hash, invalid_block))
+            })
+            .collect()
+    }
+
+    /// Removes the verdict of the block `block_hash`, so that it is processed
+    /// again when it is received. Returns the removed verdict, if any.
+    ///
+    /// The column is keyed by height, so this scans it. It only holds the
+    /// invalid blocks between the GC tail and slightly above the head.
+    pub fn clear_invalid_block(
+        &self,
+        block_hash: &CryptoHash,
+    ) -> Result<Option<StoredInvalidBlock>, Error> {
+        let invalid_block = self
+            .iterate_invalid_blocks()?
+            .into_iter()
+            .find_map(|(hash, invalid_block)| (&hash == block_hash).then_some(invalid_block));
+        if let Some(invalid_block) = &invalid_block {
+            let mut store_update = self.store.store_update();
+            store_update.delete(
+                DBCol::InvalidBlocks,
+                &get_invalid_block_key(invalid_block.height, block_hash),
+            );
+            store_update.commit()?;
+        }
+        Ok(invalid_block)
+    }
+
     /// Get outgoing receipts that will be *sent* from shard `shard_id` from block whose prev block
     /// is `prev_block_hash`
     /// Note that the meaning of outgoing receipts here are slightly different from
@@ -1396,6 +1473,8 @@ pub struct ChainStoreUpdate<'a> {
     add_state_sync_infos: Vec<StateSyncInfo>,
     remove_state_sync_infos: Vec<CryptoHash>,
     challenged_blocks: HashSet<CryptoHash>,
+    add_challenges: Vec<StoredChallenge>,
+    remove_challenges: Vec<(BlockHeight, CryptoHash)>,
 }
 
 impl<'a> ChainStoreUpdate<'a> {
@@ -1419,6 +1498,8 @@ impl<'a> ChainStoreUpdate<'a> {
             add_state_sync_infos: vec![],
             remove_state_sync_infos: vec![],
             challenged_blocks: HashSet::default(),
+            add_challenges: vec![],
+            remove_challenges: vec![],
         }
     }
 }
@@ -2058,6 +2139,14 @@ impl<'a> ChainStoreUpdate<'a> {
         self.challenged_blocks.insert(hash);
     }
 
+    pub fn add_challenge(&mut self, challenge: StoredChallenge) {
+        self.add_challenges.push(challenge);
+    }
+
+    pub fn remove_challenge(&mut self, received_height: BlockHeight, hash: CryptoHash) {
+        self.remove_challenges.push((received_height, hash));
+    }
+
     pub fn save_invalid_chunk(&mut self, chunk: EncodedShardChunk) {
         self.chain_store_cache_update.invalid_chunks.insert(chunk.chunk_hash(), Arc::new(chunk));
     }
@@ -2584,6 +2673,19 @@ impl<'a> ChainStoreUpdate<'a> {
         for hash in self.challenged_blocks.drain() {
             store_update.set_ser(DBCol::ChallengedBlocks, hash.as_ref(), &true)?;
         }
+        for stored_challenge in self.add_challenges.drain(..) {
+            store_update.set_ser(
+                DBCol::Challenges,
+                &get_challenge_key(
+                    stored_challenge.received_height,
+                    &stored_challenge.challenge.hash,
+                ),
+                &stored_challenge,
+            )?;
+        }
+        for (received_height, hash) in self.remove_challenges.drain(..) {
+            store_update.delete(DBCol::Challenges, &get_challenge_key(received_height, &hash));
+        }
         for (chunk_hash, chunk) in self.chain_store_cache_update.invalid_chunks.iter() {
             store_update.insert_ser(DBCol::InvalidChunks, chunk_hash.as_ref(), chunk)?;
         }
@@ -6,6 +6,7 @@ use std::sync::Arc;
 
 use crate::block_processing_utils::BlockNotInPoolError;
 use crate::chain::Chain;
+use crate::fork_choice::ForkChoice;
 use crate::rayon_spawner::RayonAsyncComputationSpawner;
 use crate::runtime::NightshadeRuntime;
 use crate::store::ChainStoreAccess;
@@ -17,7 +18,7 @@ use near_chain_configs::{Genesis, MutableConfigValue};
 use near_chain_primitives::Error;
 use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::{EpochManager, EpochManagerHandle};
-use near_primitives::block::Block;
+use near_primitives::block::{Block, BlockHeader, Tip};
 use near_primitives::hash::CryptoHash;
 use near_primitives::stateless_validation::ChunkProductionKey;
 use near_primitives::test_utils::create_test_signer;
@@ -171,6 +172,16 @@ pub fn setup_with_tx_validity_period(
     (chain, epoch_manager, runtime, signer)
 }
 
+/// Adversarial fork choice rule switching the head to every accepted block,
+/// even on a fork lower than the current head, to exercise reorgs.
+pub struct LatestBlockForkChoice;
+
+impl ForkChoice for LatestBlockForkChoice {
+    fn is_better_head(&self, _head: &Tip, _header: &BlockHeader) -> bool {
+        true
+    }
+}
+
 pub fn format_hash(hash: CryptoHash) -> String {
     let mut hash = hash.to_string();
     hash.truncate(6);
@@ -46,7 +46,7 @@ use near_primitives::types::{
 use near_primitives::version::{ProtocolFeature, ProtocolVersion, PROTOCOL_VERSION};
 use near_primitives::views::{
     AccessKeyInfoView, AccessKeyList, CallResult, ContractCodeView, EpochValidatorInfo,
-    QueryRequest, QueryResponse, QueryResponseKind, ViewStateResult,
+    QueryRequest, QueryResponse, QueryResponseKind, TokenBalanceView, ViewStateResult,
 };
 use near_store::test_utils::TestTriesBuilder;
 use near_store::{
@@ -850,6 +850,12 @@ impl EpochManagerAdapter for MockEpochManager {
         Ok(self.store.store_update())
     }
 
+    fn add_optimistic_block_info(&self, _block_info: BlockInfo) -> Result<bool, EpochError> {
+        Ok(false)
+    }
+
+    fn remove_optimistic_block_info(&self, _block_hash: &CryptoHash) {}
+
     fn get_epoch_protocol_version(
         &self,
         _epoch_id: &EpochId,
@@ -1326,6 +1332,24 @@ impl RuntimeAdapter for KeyValueRuntime {
                 block_height,
                 block_hash: *block_hash,
             }),
+            QueryRequest::ViewStorageSponsorship { .. } => Ok(QueryResponse {
+                kind: QueryResponseKind::StorageSponsorship(None.into()),
+                block_height,
+                block_hash: *block_hash,
+            }),
+            QueryRequest::ViewContractSourceMetadata { .. } => Ok(QueryResponse {
+                kind: QueryResponseKind::ContractSourceMetadata(None.into()),
+                block_height,
+                block_hash: *block_hash,
+            }),
+            QueryRequest::ViewTokenBalance { token_id, .. } => Ok(QueryResponse {
+                kind: QueryResponseKind::TokenBalance(TokenBalanceView {
+                    token_id: token_id.clone(),
+                    balance: 0,
+                }),
+                block_height,
+                block_hash: *block_hash,
+            }),
         }
     }
 
@@ -1344,7 +1368,13 @@ impl RuntimeAdapter for KeyValueRuntime {
         Ok(data)
     }
 
-    fn validate_state_part(&self, _state_root: &StateRoot, _part_id: PartId, _data: &[u8]) -> bool {
+    fn validate_state_part(
+        &self,
+        _state_root: &StateRoot,
+        _part_id: PartId,
+        _data: &[u8],
+        _protocol_version: ProtocolVersion,
+    ) -> bool {
         // We do not care about deeper validation in test_utils
         true
     }
@@ -1454,4 +1484,13 @@ impl RuntimeAdapter for KeyValueRuntime {
         // Note that KeyValueRuntime does not use compiled contract cache, so this is no-op.
         Ok(())
     }
+
+    fn precompile_transactions_contracts(
+        &self,
+        _epoch_id: &EpochId,
+        _state_roots: &HashMap<ShardId, StateRoot>,
+        _transactions: &[SignedTransaction],
+    ) -> Result<(), Error> {
+        Ok(())
+    }
 }
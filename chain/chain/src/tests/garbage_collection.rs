@@ -3,7 +3,7 @@ use rand::Rng;
 use std::sync::Arc;
 
 use crate::chain::Chain;
-use crate::garbage_collection::GCMode;
+use crate::garbage_collection::{GCMode, GCRetainedData};
 use crate::test_utils::{
     get_chain, get_chain_with_epoch_length, get_chain_with_epoch_length_and_num_shards,
     get_chain_with_num_shards,
@@ -11,15 +11,19 @@ use crate::test_utils::{
 use crate::types::Tip;
 use crate::{ChainStoreAccess, StoreValidator};
 
-use near_chain_configs::{GCConfig, GenesisConfig, DEFAULT_GC_NUM_EPOCHS_TO_KEEP};
+use near_chain_configs::{GCConfig, GCRetention, GenesisConfig, DEFAULT_GC_NUM_EPOCHS_TO_KEEP};
 use near_epoch_manager::EpochManagerAdapter;
 use near_primitives::block::Block;
+use near_primitives::challenge::{BlockDoubleSign, Challenge, ChallengeBody, StoredChallenge};
 use near_primitives::epoch_block_info::BlockInfo;
+use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::PartialMerkleTree;
 use near_primitives::shard_layout::ShardUId;
+use near_primitives::sharding::ChunkHash;
 use near_primitives::test_utils::{create_test_signer, TestBlockBuilder};
-use near_primitives::types::{BlockHeight, NumBlocks, StateRoot};
+use near_primitives::types::{BlockHeight, EpochId, NumBlocks, StateRoot};
 use near_primitives::validator_signer::ValidatorSigner;
+use near_store::adapter::chunk_store::get_pending_partial_chunk_key;
 use near_store::test_utils::gen_changes;
 use near_store::{DBCol, ShardTries, Trie, WrappedTrieChanges};
 
@@ -720,6 +724,195 @@ fn test_clear_old_data() {
     }
 }
 
+/// Test that block headers are garbage collected below both the tail and the
+/// epoch sync boundary, and only if they are not kept forever.
+#[test]
+fn test_clear_old_headers() {
+    let max_height = 14usize;
+    let mut chain = get_chain_with_epoch_length(Clock::real(), 1);
+    let epoch_manager = chain.epoch_manager.clone();
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let signer = Arc::new(create_test_signer("test1"));
+    let mut prev_block = genesis;
+    let mut blocks = vec![prev_block.clone()];
+    for i in 1..=max_height {
+        add_block(
+            &mut chain,
+            epoch_manager.as_ref(),
+            &mut prev_block,
+            &mut blocks,
+            signer.clone(),
+            i as BlockHeight,
+        );
+    }
+
+    let gc_config = GCConfig { gc_blocks_limit: 100, ..GCConfig::default() };
+    chain.clear_data(&gc_config).unwrap();
+    let epoch_sync_boundary = 6;
+    chain.clear_headers(&gc_config, epoch_sync_boundary).unwrap();
+    for block in &blocks {
+        assert!(chain.get_block_header(block.hash()).is_ok());
+    }
+
+    let gc_config = GCConfig { headers_retention: GCRetention::WithBlocks, ..gc_config };
+    chain.clear_headers(&gc_config, epoch_sync_boundary).unwrap();
+    for i in 0..=max_height {
+        let expected_removed = i > 0 && i < epoch_sync_boundary as usize;
+        assert_eq!(chain.get_block_header(blocks[i].hash()).is_err(), expected_removed);
+        assert_eq!(
+            chain.chain_store().get_block_hash_by_height(i as BlockHeight).is_err(),
+            expected_removed
+        );
+    }
+}
+
+/// Test that challenges persisted in `DBCol::Challenges` are garbage collected
+/// once the height at which they were received falls behind the GC stop height.
+#[test]
+fn test_clear_old_challenges() {
+    let max_height = 14usize;
+    let mut chain = get_chain_with_epoch_length(Clock::real(), 1);
+    let epoch_manager = chain.epoch_manager.clone();
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let signer = Arc::new(create_test_signer("test1"));
+    let mut prev_block = genesis;
+    let mut blocks = vec![prev_block.clone()];
+    for i in 1..=max_height {
+        add_block(
+            &mut chain,
+            epoch_manager.as_ref(),
+            &mut prev_block,
+            &mut blocks,
+            signer.clone(),
+            i as BlockHeight,
+        );
+    }
+
+    let make_challenge = |header: Vec<u8>| {
+        Challenge::produce(
+            ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+                left_block_header: header,
+                right_block_header: vec![],
+            }),
+            EpochId::default(),
+            0,
+            &signer,
+        )
+    };
+    let old_challenge = make_challenge(vec![1]);
+    let recent_challenge = make_challenge(vec![2]);
+    let mut store_update = chain.mut_chain_store().store_update();
+    store_update
+        .add_challenge(StoredChallenge { received_height: 1, challenge: old_challenge.clone() });
+    store_update.add_challenge(StoredChallenge {
+        received_height: max_height as BlockHeight,
+        challenge: recent_challenge.clone(),
+    });
+    store_update.commit().unwrap();
+    assert_eq!(chain.chain_store().iterate_challenges().unwrap().len(), 2);
+
+    chain.clear_data(&GCConfig { gc_blocks_limit: 100, ..GCConfig::default() }).unwrap();
+
+    let remaining = chain.chain_store().iterate_challenges().unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].challenge, recent_challenge);
+    assert_eq!(remaining[0].challenge.hash, recent_challenge.hash);
+}
+
+/// Test that invalid blocks persisted in `DBCol::InvalidBlocks` are rejected
+/// until cleared, and garbage collected once their height falls behind the
+/// GC stop height.
+#[test]
+fn test_clear_old_invalid_blocks() {
+    let max_height = 14usize;
+    let mut chain = get_chain_with_epoch_length(Clock::real(), 1);
+    let epoch_manager = chain.epoch_manager.clone();
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let signer = Arc::new(create_test_signer("test1"));
+    let mut prev_block = genesis;
+    let mut blocks = vec![prev_block.clone()];
+    for i in 1..=max_height {
+        add_block(
+            &mut chain,
+            epoch_manager.as_ref(),
+            &mut prev_block,
+            &mut blocks,
+            signer.clone(),
+            i as BlockHeight,
+        );
+    }
+
+    let old_hash = CryptoHash::hash_bytes(&[1]);
+    let recent_hash = CryptoHash::hash_bytes(&[2]);
+    let cleared_hash = CryptoHash::hash_bytes(&[3]);
+    let chain_store = chain.chain_store();
+    chain_store.save_invalid_block(&old_hash, 1, "old".to_string()).unwrap();
+    chain_store
+        .save_invalid_block(&recent_hash, max_height as BlockHeight, "recent".to_string())
+        .unwrap();
+    chain_store
+        .save_invalid_block(&cleared_hash, max_height as BlockHeight, "cleared".to_string())
+        .unwrap();
+    let max_height = max_height as BlockHeight;
+    assert!(chain.is_block_invalid(max_height, &cleared_hash));
+    assert!(!chain.is_block_invalid(max_height - 1, &cleared_hash));
+    let cleared = chain.chain_store().clear_invalid_block(&cleared_hash).unwrap().unwrap();
+    assert_eq!(cleared.reason, "cleared");
+    assert!(!chain.is_block_invalid(max_height, &cleared_hash));
+    assert!(chain.chain_store().clear_invalid_block(&cleared_hash).unwrap().is_none());
+
+    chain.clear_data(&GCConfig { gc_blocks_limit: 100, ..GCConfig::default() }).unwrap();
+
+    let remaining = chain.chain_store().iterate_invalid_blocks().unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].0, recent_hash);
+    assert_eq!(remaining[0].1.reason, "recent");
+    assert!(!chain.is_block_invalid(1, &old_hash));
+    assert!(chain.is_block_invalid(max_height, &recent_hash));
+}
+
+/// Test that pending partial chunks are garbage collected once the final head
+/// passes their height.
+#[test]
+fn test_clear_old_pending_partial_chunks() {
+    let max_height = 14usize;
+    let mut chain = get_chain_with_epoch_length(Clock::real(), 1);
+    let epoch_manager = chain.epoch_manager.clone();
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let signer = Arc::new(create_test_signer("test1"));
+    let mut prev_block = genesis;
+    let mut blocks = vec![prev_block.clone()];
+    for i in 1..=max_height {
+        add_block(
+            &mut chain,
+            epoch_manager.as_ref(),
+            &mut prev_block,
+            &mut blocks,
+            signer.clone(),
+            i as BlockHeight,
+        );
+    }
+
+    let old_key = get_pending_partial_chunk_key(1, &ChunkHash(CryptoHash::hash_bytes(&[1])));
+    let recent_key = get_pending_partial_chunk_key(
+        max_height as BlockHeight + 1,
+        &ChunkHash(CryptoHash::hash_bytes(&[2])),
+    );
+    let store = chain.chain_store().store().clone();
+    let mut store_update = store.store_update();
+    store_update.set(DBCol::PendingPartialChunks, &old_key, &[]);
+    store_update.set(DBCol::PendingPartialChunks, &recent_key, &[]);
+    store_update.commit().unwrap();
+
+    chain.clear_data(&GCConfig { gc_blocks_limit: 100, ..GCConfig::default() }).unwrap();
+
+    let remaining = store
+        .iter(DBCol::PendingPartialChunks)
+        .map(|item| item.unwrap().0.to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(remaining, vec![recent_key]);
+}
+
 // Adds block to the chain at given height after prev_block.
 fn add_block(
     chain: &mut Chain,
@@ -807,7 +1000,11 @@ fn test_clear_old_data_fixed_height() {
     let trie = chain.runtime_adapter.get_tries();
     let mut store_update = chain.mut_chain_store().store_update();
     assert!(store_update
-        .clear_block_data(epoch_manager.as_ref(), *blocks[5].hash(), GCMode::Canonical(trie))
+        .clear_block_data(
+            epoch_manager.as_ref(),
+            *blocks[5].hash(),
+            GCMode::Canonical(trie, GCRetainedData::default())
+        )
         .is_ok());
     store_update.commit().unwrap();
 
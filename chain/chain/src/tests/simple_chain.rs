@@ -1,14 +1,24 @@
+use crate::chain::{do_apply_chunks, new_apply_chunks_pool};
+use crate::chain_events::{ChainEvent, ChainEventSubscriber};
+use crate::execution_artifacts::{BlockExecutionArtifact, ExecutionArtifactSink};
 use crate::near_chain_primitives::error::BlockKnownError;
-use crate::test_utils::{setup, wait_for_all_blocks_in_processing};
+use crate::test_utils::{setup, wait_for_all_blocks_in_processing, LatestBlockForkChoice};
+use crate::update_shard::ShardUpdateResult;
 use crate::{Block, BlockProcessingArtifact, ChainStoreAccess, Error};
 use assert_matches::assert_matches;
+use borsh::BorshDeserialize;
 use near_async::time::{Clock, Duration, FakeClock, Utc};
 use near_o11y::testonly::init_test_logger;
+use near_primitives::block::Tip;
+use near_primitives::types::ShardId;
 use near_primitives::{
     block::MaybeNew, hash::CryptoHash, sharding::ShardChunkHeader, test_utils::TestBlockBuilder,
     version::PROTOCOL_VERSION,
 };
 use num_rational::Ratio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::Span;
 
 #[test]
 fn build_chain() {
@@ -228,6 +238,140 @@ fn blocks_at_height() {
     assert_eq!(chain.get_block_header_by_height(7).unwrap().hash(), &e_7_hash);
 }
 
+/// Checks that the head and the blocks by height follow a fork choice rule
+/// switching to lower forks.
+///
+/// 0 -> b1 -> b3
+///        \
+///         -> c2 -> c4
+#[test]
+fn adversarial_fork_choice() {
+    init_test_logger();
+    let (mut chain, _, _, signer) = setup(Clock::real());
+    chain.set_fork_choice(Arc::new(LatestBlockForkChoice));
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let b_1 = TestBlockBuilder::new(Clock::real(), &genesis, signer.clone()).height(1).build();
+    let b_3 = TestBlockBuilder::new(Clock::real(), &b_1, signer.clone()).height(3).build();
+    let c_2 = TestBlockBuilder::new(Clock::real(), &b_1, signer.clone()).height(2).build();
+    let c_4 = TestBlockBuilder::new(Clock::real(), &c_2, signer).height(4).build();
+    let b_3_hash = *b_3.hash();
+    let c_2_hash = *c_2.hash();
+    let c_4_hash = *c_4.hash();
+
+    chain.process_block_test(&None, b_1).unwrap();
+    chain.process_block_test(&None, b_3).unwrap();
+    assert_eq!(chain.head().unwrap().last_block_hash, b_3_hash);
+    assert_eq!(chain.get_block_header_by_height(3).unwrap().hash(), &b_3_hash);
+
+    chain.process_block_test(&None, c_2).unwrap();
+    assert_eq!(chain.head().unwrap().last_block_hash, c_2_hash);
+    assert_eq!(chain.header_head().unwrap().last_block_hash, c_2_hash);
+    assert_eq!(chain.get_block_header_by_height(2).unwrap().hash(), &c_2_hash);
+    assert!(chain.get_block_header_by_height(3).is_err());
+
+    chain.process_block_test(&None, c_4).unwrap();
+    assert_eq!(chain.head().unwrap().last_block_hash, c_4_hash);
+    assert!(chain.get_block_header_by_height(3).is_err());
+    assert_eq!(chain.get_block_header_by_height(4).unwrap().hash(), &c_4_hash);
+}
+
+#[derive(Default)]
+struct RecordingSubscriber {
+    events: Mutex<Vec<ChainEvent>>,
+}
+
+impl ChainEventSubscriber for RecordingSubscriber {
+    fn on_chain_event(&self, event: &ChainEvent) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+/// Checks the events delivered to chain event subscribers.
+///
+/// 0 -> b1 -> b2 -> b3
+///              \
+///               -> c5
+#[test]
+fn chain_events() {
+    init_test_logger();
+    let (mut chain, _, _, signer) = setup(Clock::real());
+    let subscriber = Arc::new(RecordingSubscriber::default());
+    chain.subscribe_chain_events(subscriber.clone());
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let b_1 = TestBlockBuilder::new(Clock::real(), &genesis, signer.clone()).height(1).build();
+    let b_2 = TestBlockBuilder::new(Clock::real(), &b_1, signer.clone()).height(2).build();
+    let b_3 = TestBlockBuilder::new(Clock::real(), &b_2, signer.clone()).height(3).build();
+    let c_5 = TestBlockBuilder::new(Clock::real(), &b_2, signer).height(5).build();
+    let tip = |block: &Block| Tip::from_header(block.header());
+    let head_switch = |old: &Block, new: &Block, abandoned: Vec<CryptoHash>| {
+        ChainEvent::HeadSwitch { old_head: tip(old), new_head: tip(new), abandoned }
+    };
+    let take_events = || std::mem::take(&mut *subscriber.events.lock().unwrap());
+
+    chain.process_block_test(&None, b_1.clone()).unwrap();
+    assert_eq!(take_events(), vec![head_switch(&genesis, &b_1, vec![])]);
+
+    chain.process_block_test(&None, b_2.clone()).unwrap();
+    assert_eq!(
+        take_events(),
+        vec![head_switch(&b_1, &b_2, vec![]), ChainEvent::NewDoomslugFinalBlock(tip(&b_1))]
+    );
+
+    chain.process_block_test(&None, b_3.clone()).unwrap();
+    assert_eq!(
+        take_events(),
+        vec![
+            head_switch(&b_2, &b_3, vec![]),
+            ChainEvent::NewFinalBlock(tip(&b_1)),
+            ChainEvent::NewDoomslugFinalBlock(tip(&b_2)),
+        ]
+    );
+
+    // The fork has a higher height but its last doomslug-final block is lower.
+    chain.process_block_test(&None, c_5.clone()).unwrap();
+    assert_eq!(take_events(), vec![head_switch(&b_3, &c_5, vec![*b_3.hash()])]);
+}
+
+struct ChannelArtifactSink {
+    sender: Mutex<std::sync::mpsc::Sender<(String, Vec<u8>)>>,
+}
+
+impl ExecutionArtifactSink for ChannelArtifactSink {
+    fn export(&self, file_name: &str, data: &[u8]) -> anyhow::Result<()> {
+        self.sender.lock().unwrap().send((file_name.to_string(), data.to_vec()))?;
+        Ok(())
+    }
+}
+
+/// Checks that the execution of every applied block is exported to the sink.
+#[test]
+fn execution_artifacts() {
+    init_test_logger();
+    let (mut chain, _, _, signer) = setup(Clock::real());
+    let (sender, receiver) = std::sync::mpsc::channel();
+    chain
+        .set_execution_artifact_sink(
+            Arc::new(ChannelArtifactSink { sender: Mutex::new(sender) }),
+            8,
+        )
+        .unwrap();
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let b_1 = TestBlockBuilder::new(Clock::real(), &genesis, signer.clone()).height(1).build();
+    let b_2 = TestBlockBuilder::new(Clock::real(), &b_1, signer).height(2).build();
+    chain.process_block_test(&None, b_1.clone()).unwrap();
+    chain.process_block_test(&None, b_2.clone()).unwrap();
+
+    for block in [b_1, b_2] {
+        let (file_name, data) = receiver.recv_timeout(std::time::Duration::from_secs(10)).unwrap();
+        assert_eq!(
+            file_name,
+            BlockExecutionArtifact::file_name(block.header().height(), block.hash())
+        );
+        let artifact = BlockExecutionArtifact::try_from_slice(&data).unwrap();
+        assert_eq!(artifact.block.hash(), block.hash());
+    }
+}
+
 #[test]
 fn next_blocks() {
     init_test_logger();
@@ -290,3 +434,36 @@ fn block_chunk_headers_iter() {
     assert_eq!(new_headers.len(), 8);
     assert_eq!(raw_headers.len(), old_headers.len() + new_headers.len());
 }
+
+/// Checks that the chunks of a block are applied on at most as many threads as
+/// the pool has, and that the results keep the order of the shards even when
+/// the last shards finish first.
+#[test]
+fn apply_chunks_bounded_parallelism() {
+    let pool = new_apply_chunks_pool(2).unwrap();
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+    let num_shards = 6;
+    let work = (0..num_shards)
+        .map(|i| {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            let task: Box<dyn FnOnce(&Span) -> Result<ShardUpdateResult, Error> + Send + Sync> =
+                Box::new(move |_| {
+                    let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now_running, Ordering::SeqCst);
+                    std::thread::sleep(std::time::Duration::from_millis(10 * (num_shards - i)));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Err(Error::Other(i.to_string()))
+                });
+            (ShardId::new(i), task)
+        })
+        .collect();
+
+    let results = do_apply_chunks(&pool, CryptoHash::default(), 1, work);
+    assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    for (i, (shard_id, result)) in results.into_iter().enumerate() {
+        assert_eq!(shard_id, ShardId::new(i as u64));
+        assert_matches!(result, Err(Error::Other(msg)) if msg == i.to_string());
+    }
+}
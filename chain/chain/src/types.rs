@@ -1,9 +1,12 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_async::time::{Duration, Utc};
+use near_chain_configs::BlockTimestampConfig;
+use near_chain_configs::CatchupConfig;
 use near_chain_configs::GenesisConfig;
 use near_chain_configs::MutableConfigValue;
 use near_chain_configs::ProtocolConfig;
 use near_chain_configs::ReshardingConfig;
+use near_chain_configs::ScheduledProtocolUpgrade;
 use near_chain_primitives::Error;
 pub use near_epoch_manager::EpochManagerAdapter;
 use near_parameters::RuntimeConfig;
@@ -40,9 +43,13 @@ use near_primitives::views::{QueryRequest, QueryResponse};
 use near_schema_checker_lib::ProtocolSchema;
 use near_store::flat::FlatStorageManager;
 use near_store::{PartialStorage, ShardTries, Store, Trie, WrappedTrieChanges};
+use near_vm_runner::logic::RecentBlockHeader;
 use near_vm_runner::ContractCode;
 use near_vm_runner::ContractRuntimeCache;
+use node_runtime::trace::ExecutionTracer;
 use num_rational::Rational32;
+use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::instrument;
 
 #[derive(Eq, PartialEq, Debug, Clone)]
@@ -209,6 +216,7 @@ pub struct ChainGenesis {
     pub transaction_validity_period: NumBlocks,
     pub epoch_length: BlockHeightDelta,
     pub protocol_version: ProtocolVersion,
+    pub protocol_upgrade_heights: Vec<ScheduledProtocolUpgrade>,
 }
 
 #[derive(Clone)]
@@ -218,6 +226,16 @@ pub struct ChainConfig {
     /// Number of threads to execute background migration work.
     /// Currently used for flat storage background creation.
     pub background_migration_threads: usize,
+    /// Maximum number of shards of a block applied in parallel, 0 means one
+    /// thread per CPU.
+    pub apply_chunks_threads: usize,
+    /// Limits of the resources used by catchup.
+    pub catchup: CatchupConfig,
+    /// How far in the future the timestamp of a block may be.
+    pub max_block_clock_skew: Duration,
+    /// Whether the chunks of every processed block are validated as if the
+    /// node was a chunk validator, see `ShadowValidationConfig`.
+    pub shadow_validation: bool,
     /// The resharding configuration.
     pub resharding_config: MutableConfigValue<ReshardingConfig>,
 }
@@ -227,6 +245,10 @@ impl ChainConfig {
         Self {
             save_trie_changes: true,
             background_migration_threads: 1,
+            apply_chunks_threads: 2,
+            catchup: CatchupConfig::default(),
+            max_block_clock_skew: BlockTimestampConfig::default().max_clock_skew,
+            shadow_validation: false,
             resharding_config: MutableConfigValue::new(
                 ReshardingConfig::default(),
                 "resharding_config",
@@ -249,6 +271,7 @@ impl ChainGenesis {
             transaction_validity_period: genesis_config.transaction_validity_period,
             epoch_length: genesis_config.epoch_length,
             protocol_version: genesis_config.protocol_version,
+            protocol_upgrade_heights: genesis_config.protocol_upgrade_heights.clone(),
         }
     }
 }
@@ -308,6 +331,12 @@ pub struct ApplyChunkBlockContext {
     pub random_seed: CryptoHash,
     pub congestion_info: BlockCongestionInfo,
     pub bandwidth_requests: BlockBandwidthRequests,
+    /// Headers of the blocks preceding this one, most recent first, which
+    /// contracts read with the `block_header` host function.
+    pub recent_block_headers: Arc<[RecentBlockHeader]>,
+    /// Receives events about the execution of receipts. Only set when
+    /// re-applying a chunk for debugging.
+    pub execution_tracer: Option<Arc<dyn ExecutionTracer>>,
 }
 
 impl ApplyChunkBlockContext {
@@ -316,6 +345,7 @@ impl ApplyChunkBlockContext {
         gas_price: Balance,
         congestion_info: BlockCongestionInfo,
         bandwidth_requests: BlockBandwidthRequests,
+        recent_block_headers: Arc<[RecentBlockHeader]>,
     ) -> Self {
         Self {
             height: header.height(),
@@ -327,6 +357,8 @@ impl ApplyChunkBlockContext {
             random_seed: *header.random_value(),
             congestion_info,
             bandwidth_requests,
+            recent_block_headers,
+            execution_tracer: None,
         }
     }
 }
@@ -500,8 +532,15 @@ pub trait RuntimeAdapter: Send + Sync {
     ) -> Result<Vec<u8>, Error>;
 
     /// Validate state part that expected to be given state root with provided data.
-    /// Returns false if the resulting part doesn't match the expected one.
-    fn validate_state_part(&self, state_root: &StateRoot, part_id: PartId, data: &[u8]) -> bool;
+    /// Returns false if the resulting part doesn't match the expected one, or if
+    /// its format is not supported in `protocol_version`.
+    fn validate_state_part(
+        &self,
+        state_root: &StateRoot,
+        part_id: PartId,
+        data: &[u8],
+        protocol_version: ProtocolVersion,
+    ) -> bool;
 
     /// Should be executed after accepting all the parts to set up a new state.
     fn apply_state_part(
@@ -544,6 +583,19 @@ pub trait RuntimeAdapter: Send + Sync {
         epoch_id: &EpochId,
         contract_codes: Vec<ContractCode>,
     ) -> Result<(), Error>;
+
+    /// Precompiles the contracts called by function calls in `transactions`,
+    /// so that they are already compiled once the receipts created by these
+    /// transactions are applied.
+    ///
+    /// Contract code is read from the state of the receiver's shard given in
+    /// `state_roots`. Receivers in shards without a state root are skipped.
+    fn precompile_transactions_contracts(
+        &self,
+        epoch_id: &EpochId,
+        state_roots: &HashMap<ShardId, StateRoot>,
+        transactions: &[SignedTransaction],
+    ) -> Result<(), Error>;
 }
 
 /// The last known / checked height and time when we have processed it.
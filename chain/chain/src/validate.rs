@@ -1,22 +1,37 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use borsh::BorshDeserialize;
 
 use near_crypto::PublicKey;
 use near_epoch_manager::EpochManagerAdapter;
-use near_primitives::bandwidth_scheduler::BandwidthRequests;
+use near_primitives::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 use near_primitives::block::{Block, BlockHeader};
 use near_primitives::challenge::{
-    BlockDoubleSign, Challenge, ChallengeBody, ChunkProofs, ChunkState, MaybeEncodedShardChunk,
+    BlockDoubleSign, Challenge, ChallengeBody, ChunkDoubleSign, ChunkProofs, ChunkState,
+    InvalidStateWitness, MaybeEncodedShardChunk,
 };
 use near_primitives::congestion_info::CongestionInfo;
+use near_primitives::errors::EpochError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::merklize;
+use near_primitives::reed_solomon::{
+    InsertPartResult, ReedSolomonEncoder, ReedSolomonPartsTracker,
+};
 use near_primitives::sharding::{ShardChunk, ShardChunkHeader};
+use near_primitives::stateless_validation::chunk_endorsement::ChunkEndorsement;
+use near_primitives::stateless_validation::partial_witness::{
+    MAX_COMPRESSED_STATE_WITNESS_SIZE, WITNESS_RATIO_DATA_PARTS,
+};
+use near_primitives::stateless_validation::state_witness::{
+    ChunkStateWitness, EncodedChunkStateWitness,
+};
 use near_primitives::stateless_validation::ChunkProductionKey;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::chunk_extra::ChunkExtra;
-use near_primitives::types::{AccountId, BlockHeight, EpochId, Nonce};
+use near_primitives::types::{AccountId, BlockHeight, EpochId, Nonce, StateRoot};
+use near_primitives::utils::compression::CompressedData;
+use near_primitives::version::ProtocolFeature;
 
 use crate::signature_verification::{
     verify_block_header_signature_with_epoch_manager,
@@ -116,38 +131,52 @@ pub fn validate_chunk_with_chunk_extra(
     epoch_manager: &dyn EpochManagerAdapter,
     prev_block_hash: &CryptoHash,
     prev_chunk_extra: &ChunkExtra,
-    prev_chunk_height_included: BlockHeight,
+    prev_chunk_header: &ShardChunkHeader,
     chunk_header: &ShardChunkHeader,
 ) -> Result<(), Error> {
     let outgoing_receipts = chain_store.get_outgoing_receipts_for_shard(
         epoch_manager,
         *prev_block_hash,
         chunk_header.shard_id(),
-        prev_chunk_height_included,
+        prev_chunk_header.height_included(),
     )?;
-    let outgoing_receipts_hashes = {
-        let shard_layout = epoch_manager.get_shard_layout_from_prev_block(prev_block_hash)?;
-        Chain::build_receipts_hashes(&outgoing_receipts, &shard_layout)
-    };
+    let shard_layout = epoch_manager.get_shard_layout_from_prev_block(prev_block_hash)?;
+    let outgoing_receipts_hashes = Chain::build_receipts_hashes(&outgoing_receipts, &shard_layout);
     let (outgoing_receipts_root, _) = merklize(&outgoing_receipts_hashes);
+    let outgoing_bandwidth = OutgoingBandwidth::from_receipts(&outgoing_receipts, &shard_layout);
 
     validate_chunk_with_chunk_extra_and_receipts_root(
         prev_chunk_extra,
+        &prev_chunk_header.prev_state_root(),
         chunk_header,
         &outgoing_receipts_root,
+        &outgoing_bandwidth,
     )
 }
 
 /// Validate that all next chunk information matches previous chunk extra.
+/// `prev_pre_state_root` is the state root the previous chunk was applied to.
+/// `outgoing_receipts_root` and `outgoing_bandwidth` are computed from the
+/// outgoing receipts of the previous chunk.
 pub fn validate_chunk_with_chunk_extra_and_receipts_root(
     prev_chunk_extra: &ChunkExtra,
+    prev_pre_state_root: &StateRoot,
     chunk_header: &ShardChunkHeader,
     outgoing_receipts_root: &CryptoHash,
+    outgoing_bandwidth: &OutgoingBandwidth,
 ) -> Result<(), Error> {
     if *prev_chunk_extra.state_root() != chunk_header.prev_state_root() {
         return Err(Error::InvalidStateRoot);
     }
 
+    // Headers which don't commit to the pre state root are rejected by
+    // `validate_version` once the commitment is required.
+    if let Some(header_prev_pre_state_root) = chunk_header.prev_pre_state_root() {
+        if header_prev_pre_state_root != prev_pre_state_root {
+            return Err(Error::InvalidStateRoot);
+        }
+    }
+
     if *prev_chunk_extra.outcome_root() != chunk_header.prev_outcome_root() {
         return Err(Error::InvalidOutcomesProof);
     }
@@ -188,6 +217,7 @@ pub fn validate_chunk_with_chunk_extra_and_receipts_root(
         prev_chunk_extra.bandwidth_requests(),
         chunk_header.bandwidth_requests(),
     )?;
+    validate_outgoing_bandwidth(outgoing_bandwidth, chunk_header.prev_outgoing_bandwidth())?;
 
     Ok(())
 }
@@ -245,6 +275,26 @@ fn validate_bandwidth_requests(
     Ok(())
 }
 
+/// Validate the outgoing bandwidth committed to in the chunk header against the
+/// one computed from the outgoing receipts of the previous chunk. Headers which
+/// don't commit to the outgoing bandwidth are rejected by `validate_version`
+/// once the commitment is required.
+fn validate_outgoing_bandwidth(
+    receipts_outgoing_bandwidth: &OutgoingBandwidth,
+    header_outgoing_bandwidth: Option<&OutgoingBandwidth>,
+) -> Result<(), Error> {
+    let Some(header_outgoing_bandwidth) = header_outgoing_bandwidth else {
+        return Ok(());
+    };
+    if receipts_outgoing_bandwidth != header_outgoing_bandwidth {
+        return Err(Error::InvalidOutgoingBandwidth(format!(
+            "outgoing receipts: {:?}, chunk header: {:?}",
+            receipts_outgoing_bandwidth.sizes, header_outgoing_bandwidth.sizes
+        )));
+    }
+    Ok(())
+}
+
 /// Validates a double sign challenge.
 /// Only valid if ancestors of both blocks are present in the chain.
 fn validate_double_sign(
@@ -283,6 +333,54 @@ fn validate_double_sign(
     }
 }
 
+/// Validates that both chunk headers are signed by the chunk producer assigned
+/// to the same height and shard. Returns the higher of the two chunk hashes
+/// along with the chunk producer.
+fn validate_chunk_double_sign(
+    epoch_manager: &dyn EpochManagerAdapter,
+    chunk_double_sign: &ChunkDoubleSign,
+) -> Result<(CryptoHash, Vec<AccountId>), Error> {
+    let left_chunk_header = &chunk_double_sign.left_chunk_header;
+    let right_chunk_header = &chunk_double_sign.right_chunk_header;
+    if left_chunk_header.chunk_hash() == right_chunk_header.chunk_hash()
+        || left_chunk_header.height_created() != right_chunk_header.height_created()
+        || left_chunk_header.shard_id() != right_chunk_header.shard_id()
+    {
+        return Err(Error::MaliciousChallenge);
+    }
+    let epoch_id =
+        epoch_manager.get_epoch_id_from_prev_block(left_chunk_header.prev_block_hash())?;
+    if epoch_manager.get_epoch_id_from_prev_block(right_chunk_header.prev_block_hash())? != epoch_id
+    {
+        return Err(Error::MaliciousChallenge);
+    }
+    let chunk_producer = epoch_manager
+        .get_chunk_producer_info(&ChunkProductionKey {
+            epoch_id,
+            height_created: left_chunk_header.height_created(),
+            shard_id: left_chunk_header.shard_id(),
+        })?
+        .take_account_id();
+    if verify_chunk_header_signature_with_epoch_manager(
+        epoch_manager,
+        left_chunk_header,
+        left_chunk_header.prev_block_hash(),
+        epoch_id,
+    )? && verify_chunk_header_signature_with_epoch_manager(
+        epoch_manager,
+        right_chunk_header,
+        right_chunk_header.prev_block_hash(),
+        epoch_id,
+    )? {
+        // Deterministically return chunk with higher hash.
+        let left_chunk_hash = left_chunk_header.chunk_hash().0;
+        let right_chunk_hash = right_chunk_header.chunk_hash().0;
+        Ok((std::cmp::max(left_chunk_hash, right_chunk_hash), vec![chunk_producer]))
+    } else {
+        Err(Error::MaliciousChallenge)
+    }
+}
+
 fn validate_header_authorship(
     epoch_manager: &dyn EpochManagerAdapter,
     block_header: &BlockHeader,
@@ -325,6 +423,16 @@ fn validate_chunk_proofs_challenge(
 ) -> Result<(CryptoHash, Vec<AccountId>), Error> {
     let block_header = BlockHeader::try_from_slice(&chunk_proofs.block_header)?;
     validate_header_authorship(epoch_manager, &block_header)?;
+    validate_chunk_proofs_challenge_with_header(epoch_manager, &block_header, chunk_proofs)
+}
+
+/// Validates `ChunkProofs` challenge given its block header, which must be
+/// already decoded and checked for authorship.
+fn validate_chunk_proofs_challenge_with_header(
+    epoch_manager: &dyn EpochManagerAdapter,
+    block_header: &BlockHeader,
+    chunk_proofs: &ChunkProofs,
+) -> Result<(CryptoHash, Vec<AccountId>), Error> {
     let chunk_header = match &*chunk_proofs.chunk {
         MaybeEncodedShardChunk::Encoded(encoded_chunk) => encoded_chunk.cloned_header(),
         MaybeEncodedShardChunk::Decoded(chunk) => chunk.cloned_header(),
@@ -343,7 +451,9 @@ fn validate_chunk_proofs_challenge(
     let tmp_chunk;
     let chunk_ref = match &*chunk_proofs.chunk {
         MaybeEncodedShardChunk::Encoded(encoded_chunk) => {
-            match encoded_chunk.decode_chunk(epoch_manager.num_data_parts()) {
+            let data_parts =
+                encoded_chunk.cloned_header().num_data_parts_or(epoch_manager.num_data_parts());
+            match encoded_chunk.decode_chunk(data_parts) {
                 Ok(chunk) => {
                     tmp_chunk = Some(chunk);
                     tmp_chunk.as_ref().unwrap()
@@ -449,6 +559,114 @@ fn validate_chunk_state_challenge(
     Err(Error::MaliciousChallenge)
 }
 
+/// Decodes the state witness of an `InvalidStateWitness` challenge from its parts.
+/// Every part must be signed by the producer of the chunk, so that the challenger
+/// can neither make up a witness nor alter the one the producer distributed.
+pub fn decode_challenged_state_witness(
+    epoch_manager: &dyn EpochManagerAdapter,
+    invalid_state_witness: &InvalidStateWitness,
+) -> Result<ChunkStateWitness, Error> {
+    let Some(first_part) = invalid_state_witness.witness_parts.first() else {
+        return Err(Error::InvalidChallenge);
+    };
+    let key = first_part.chunk_production_key();
+    let encoded_length = first_part.encoded_length();
+    if encoded_length > MAX_COMPRESSED_STATE_WITNESS_SIZE.as_u64() as usize {
+        return Err(Error::InvalidChallenge);
+    }
+    let chunk_producer = epoch_manager.get_chunk_producer_info(&key)?;
+    // The witness is split into one part per chunk validator.
+    let num_parts = epoch_manager
+        .get_chunk_validator_assignments(&key.epoch_id, key.shard_id, key.height_created)?
+        .len();
+    let encoder = Arc::new(ReedSolomonEncoder::new(num_parts, WITNESS_RATIO_DATA_PARTS));
+    let mut parts =
+        ReedSolomonPartsTracker::<EncodedChunkStateWitness>::new(encoder, encoded_length);
+    let mut encoded_witness = None;
+    for part in &invalid_state_witness.witness_parts {
+        if encoded_witness.is_some()
+            || part.chunk_production_key() != key
+            || part.encoded_length() != encoded_length
+            || !part.verify(chunk_producer.public_key())
+        {
+            // Parts must all be for the same chunk, signed by its producer, and
+            // there must be no more of them than needed for decoding.
+            return Err(Error::InvalidChallenge);
+        }
+        match parts.insert_part(part.part_ord(), part.clone().into_part()) {
+            InsertPartResult::Accepted => {}
+            InsertPartResult::Decoded(result) => {
+                encoded_witness = Some(result.map_err(|_| Error::InvalidChallenge)?);
+            }
+            InsertPartResult::PartAlreadyAvailable | InsertPartResult::InvalidPartOrd => {
+                return Err(Error::InvalidChallenge);
+            }
+        }
+    }
+    let Some(encoded_witness) = encoded_witness else {
+        return Err(Error::InvalidChallenge);
+    };
+    let (state_witness, _) = encoded_witness.decode().map_err(|_| Error::InvalidChallenge)?;
+    if state_witness.chunk_production_key() != key
+        || &state_witness.chunk_producer != chunk_producer.account_id()
+    {
+        return Err(Error::InvalidChallenge);
+    }
+    Ok(state_witness)
+}
+
+/// Validates the witness parts, authorship of the witness chunk and the
+/// endorsements included in the challenge. Re-execution of the witness
+/// requires the chain store and is done separately by
+/// `reexecute_challenged_state_witness`.
+/// Returns chunk hash along with chunk producer and all endorsing validators.
+fn validate_invalid_state_witness_challenge(
+    epoch_manager: &dyn EpochManagerAdapter,
+    invalid_state_witness: &InvalidStateWitness,
+) -> Result<(CryptoHash, Vec<AccountId>), Error> {
+    let state_witness = decode_challenged_state_witness(epoch_manager, invalid_state_witness)?;
+    let chunk_header = &state_witness.chunk_header;
+    let chunk_producer = validate_chunk_authorship(epoch_manager, chunk_header)?;
+    if chunk_producer != state_witness.chunk_producer {
+        // The parts were signed by the producer of a different chunk.
+        return Err(Error::InvalidChallenge);
+    }
+    if invalid_state_witness.endorsements.is_empty() {
+        // Nobody endorsed the witness, there is nothing to challenge.
+        return Err(Error::InvalidChallenge);
+    }
+
+    let chunk_validator_assignments = epoch_manager.get_chunk_validator_assignments(
+        &state_witness.epoch_id,
+        chunk_header.shard_id(),
+        chunk_header.height_created(),
+    )?;
+    let mut accounts_to_slash = vec![chunk_producer];
+    let mut seen_validators = HashSet::new();
+    for (account_id, signature) in &invalid_state_witness.endorsements {
+        if !seen_validators.insert(account_id) || !chunk_validator_assignments.contains(account_id)
+        {
+            return Err(Error::InvalidChallenge);
+        }
+        let (validator, _) = epoch_manager.get_validator_by_account_id(
+            &state_witness.epoch_id,
+            chunk_header.prev_block_hash(),
+            account_id,
+        )?;
+        if !ChunkEndorsement::validate_signature(
+            chunk_header.chunk_hash(),
+            signature,
+            validator.public_key(),
+        ) {
+            return Err(Error::InvalidChallenge);
+        }
+        if !accounts_to_slash.contains(account_id) {
+            accounts_to_slash.push(account_id.clone());
+        }
+    }
+    Ok((chunk_header.chunk_hash().0, accounts_to_slash))
+}
+
 /// Returns `Some(block_hash, vec![account_id])` of invalid block and who to
 /// slash if challenge is correct and None if incorrect.
 pub fn validate_challenge(
@@ -458,17 +676,183 @@ pub fn validate_challenge(
     last_block_hash: &CryptoHash,
     challenge: &Challenge,
 ) -> Result<(CryptoHash, Vec<AccountId>), Error> {
-    // Check signature is correct on the challenge.
-    if !epoch_manager.verify_validator_or_fisherman_signature(
-        epoch_id,
-        last_block_hash,
-        &challenge.account_id,
-        challenge.hash.as_ref(),
-        &challenge.signature,
-    )? {
+    validate_challenge_signature(epoch_manager, epoch_id, last_block_hash, challenge)?;
+    validate_challenge_target(epoch_manager, challenge)?;
+    validate_challenge_body(epoch_manager, runtime, &challenge.body)
+}
+
+/// Returns epoch and height of the block or chunk challenged by the body.
+pub fn challenge_target(
+    epoch_manager: &dyn EpochManagerAdapter,
+    body: &ChallengeBody,
+) -> Result<(EpochId, BlockHeight), Error> {
+    let block_header_bytes = match body {
+        ChallengeBody::BlockDoubleSign(block_double_sign) => &block_double_sign.left_block_header,
+        ChallengeBody::ChunkProofs(chunk_proofs) => &chunk_proofs.block_header,
+        ChallengeBody::ChunkState(chunk_state) => &chunk_state.block_header,
+        ChallengeBody::InvalidStateWitness(invalid_state_witness) => {
+            let Some(part) = invalid_state_witness.witness_parts.first() else {
+                return Err(Error::InvalidChallenge);
+            };
+            let key = part.chunk_production_key();
+            return Ok((key.epoch_id, key.height_created));
+        }
+        ChallengeBody::ChunkDoubleSign(chunk_double_sign) => {
+            let chunk_header = &chunk_double_sign.left_chunk_header;
+            let epoch_id =
+                epoch_manager.get_epoch_id_from_prev_block(chunk_header.prev_block_hash())?;
+            return Ok((epoch_id, chunk_header.height_created()));
+        }
+    };
+    let block_header = BlockHeader::try_from_slice(block_header_bytes)?;
+    Ok((*block_header.epoch_id(), block_header.height()))
+}
+
+/// Checks that the target epoch and height of the challenge match its body.
+/// Challenges are rejected before `ProtocolFeature::ChallengeTargetEpoch`.
+pub fn validate_challenge_target(
+    epoch_manager: &dyn EpochManagerAdapter,
+    challenge: &Challenge,
+) -> Result<(), Error> {
+    let protocol_version = epoch_manager.get_epoch_protocol_version(&challenge.target_epoch_id)?;
+    if !ProtocolFeature::ChallengeTargetEpoch.enabled(protocol_version) {
         return Err(Error::InvalidChallenge);
     }
-    match &challenge.body {
+    let target = challenge_target(epoch_manager, &challenge.body)?;
+    if target != (challenge.target_epoch_id, challenge.target_height) {
+        return Err(Error::InvalidChallenge);
+    }
+    Ok(())
+}
+
+/// Checks that the challenge is signed by a validator or fisherman, as well as
+/// all of its co-signers.
+pub fn validate_challenge_signature(
+    epoch_manager: &dyn EpochManagerAdapter,
+    epoch_id: &EpochId,
+    last_block_hash: &CryptoHash,
+    challenge: &Challenge,
+) -> Result<(), Error> {
+    let Some(public_keys) =
+        challenge_signer_public_keys(epoch_manager, epoch_id, last_block_hash, challenge)?
+    else {
+        return Err(Error::InvalidChallenge);
+    };
+    if !verify_challenge_signatures(challenge, &public_keys) {
+        return Err(Error::InvalidChallenge);
+    }
+    Ok(())
+}
+
+/// Checks the signatures of the challenge against the public keys of its
+/// signers, in the order of `Challenge::signatures`.
+fn verify_challenge_signatures(challenge: &Challenge, public_keys: &[PublicKey]) -> bool {
+    challenge
+        .signatures()
+        .zip(public_keys)
+        .all(|((_, signature), public_key)| signature.verify(challenge.hash.as_ref(), public_key))
+}
+
+/// Validates a batch of challenges, e.g. all challenges included in a block.
+/// Equivalent to calling `validate_challenge` for each challenge, but block
+/// headers shared by `ChunkProofs` challenges are decoded and authenticated once.
+/// Returns validation result for each challenge, in the same order.
+pub fn validate_challenges_batch(
+    epoch_manager: &dyn EpochManagerAdapter,
+    runtime: &dyn RuntimeAdapter,
+    epoch_id: &EpochId,
+    last_block_hash: &CryptoHash,
+    challenges: &[Challenge],
+) -> Vec<Result<(CryptoHash, Vec<AccountId>), Error>> {
+    let mut block_headers: HashMap<&[u8], BlockHeader> = HashMap::new();
+    challenges
+        .iter()
+        .map(|challenge| {
+            let Some(public_keys) =
+                challenge_signer_public_keys(epoch_manager, epoch_id, last_block_hash, challenge)?
+            else {
+                return Err(Error::InvalidChallenge);
+            };
+            if !verify_challenge_signatures(challenge, &public_keys) {
+                return Err(Error::InvalidChallenge);
+            }
+            validate_challenge_target(epoch_manager, challenge)?;
+            let ChallengeBody::ChunkProofs(chunk_proofs) = &challenge.body else {
+                return validate_challenge_body(epoch_manager, runtime, &challenge.body);
+            };
+            let header_bytes = chunk_proofs.block_header.as_slice();
+            if !block_headers.contains_key(header_bytes) {
+                let block_header = BlockHeader::try_from_slice(header_bytes)?;
+                validate_header_authorship(epoch_manager, &block_header)?;
+                block_headers.insert(header_bytes, block_header);
+            }
+            validate_chunk_proofs_challenge_with_header(
+                epoch_manager,
+                &block_headers[header_bytes],
+                chunk_proofs,
+            )
+        })
+        .collect()
+}
+
+/// Returns public key of the validator or fisherman who signed a challenge,
+/// or `None` if they are slashed.
+fn challenger_public_key(
+    epoch_manager: &dyn EpochManagerAdapter,
+    epoch_id: &EpochId,
+    last_block_hash: &CryptoHash,
+    account_id: &AccountId,
+) -> Result<Option<PublicKey>, Error> {
+    let (stake, is_slashed) =
+        match epoch_manager.get_validator_by_account_id(epoch_id, last_block_hash, account_id) {
+            Err(EpochError::NotAValidator(..)) => {
+                epoch_manager.get_fisherman_by_account_id(epoch_id, last_block_hash, account_id)?
+            }
+            result => result?,
+        };
+    Ok((!is_slashed).then(|| stake.public_key().clone()))
+}
+
+/// Returns public keys of all accounts which signed a challenge, in the order
+/// of `Challenge::signatures`, or `None` if any of them is slashed or signed
+/// the challenge more than once.
+fn challenge_signer_public_keys(
+    epoch_manager: &dyn EpochManagerAdapter,
+    epoch_id: &EpochId,
+    last_block_hash: &CryptoHash,
+    challenge: &Challenge,
+) -> Result<Option<Vec<PublicKey>>, Error> {
+    if !challenge.co_signatures.is_empty()
+        && !ProtocolFeature::ChallengeCoSignatures
+            .enabled(epoch_manager.get_epoch_protocol_version(epoch_id)?)
+    {
+        return Ok(None);
+    }
+    let mut seen_signers = HashSet::new();
+    let mut public_keys = Vec::with_capacity(1 + challenge.co_signatures.len());
+    for account_id in challenge.signers() {
+        if !seen_signers.insert(account_id) {
+            return Ok(None);
+        }
+        let Some(public_key) =
+            challenger_public_key(epoch_manager, epoch_id, last_block_hash, account_id)?
+        else {
+            return Ok(None);
+        };
+        public_keys.push(public_key);
+    }
+    Ok(Some(public_keys))
+}
+
+/// Validates proofs included in the challenge body, without checking the
+/// signature of the challenger. Returns hash of the challenged block or chunk
+/// and accounts to slash.
+pub fn validate_challenge_body(
+    epoch_manager: &dyn EpochManagerAdapter,
+    runtime: &dyn RuntimeAdapter,
+    challenge_body: &ChallengeBody,
+) -> Result<(CryptoHash, Vec<AccountId>), Error> {
+    match challenge_body {
         ChallengeBody::BlockDoubleSign(block_double_sign) => {
             validate_double_sign(epoch_manager, block_double_sign)
         }
@@ -478,12 +862,19 @@ pub fn validate_challenge(
         ChallengeBody::ChunkState(chunk_state) => {
             validate_chunk_state_challenge(runtime, chunk_state)
         }
+        ChallengeBody::InvalidStateWitness(invalid_state_witness) => {
+            validate_invalid_state_witness_challenge(epoch_manager, invalid_state_witness)
+        }
+        ChallengeBody::ChunkDoubleSign(chunk_double_sign) => {
+            validate_chunk_double_sign(epoch_manager, chunk_double_sign)
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use near_crypto::{InMemorySigner, KeyType};
+    use near_primitives::types::ShardId;
 
     use super::*;
 
@@ -566,4 +957,25 @@ mod tests {
         ];
         assert!(!validate_transactions_order(&transactions));
     }
+
+    #[test]
+    pub fn test_outgoing_bandwidth() {
+        let receipts_outgoing_bandwidth =
+            OutgoingBandwidth { sizes: vec![(ShardId::new(0), 100), (ShardId::new(1), 200)] };
+        assert!(validate_outgoing_bandwidth(&receipts_outgoing_bandwidth, None).is_ok());
+        assert!(validate_outgoing_bandwidth(
+            &receipts_outgoing_bandwidth,
+            Some(&receipts_outgoing_bandwidth.clone())
+        )
+        .is_ok());
+
+        let header_outgoing_bandwidth = OutgoingBandwidth { sizes: vec![(ShardId::new(0), 100)] };
+        assert!(matches!(
+            validate_outgoing_bandwidth(
+                &receipts_outgoing_bandwidth,
+                Some(&header_outgoing_bandwidth)
+            ),
+            Err(Error::InvalidOutgoingBandwidth(_))
+        ));
+    }
 }
@@ -1,6 +1,7 @@
 use std::fmt;
 
 use near_primitives::errors::EpochError;
+use near_primitives::sharding::ChunkPartsDecompressionError;
 
 #[derive(Debug)]
 pub enum Error {
@@ -14,6 +15,7 @@ pub enum Error {
     DuplicateChunkHeight,
     UnknownChunk,
     KnownPart,
+    InvalidCompressedParts(ChunkPartsDecompressionError),
     ChainError(near_chain_primitives::Error),
     IOError(std::io::Error),
 }
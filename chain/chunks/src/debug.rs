@@ -0,0 +1,193 @@
+//! Per-chunk accounting of the parts received, requested and forwarded by the
+//! ShardsManager, exposed by the `/debug/api/chunk_status` endpoint.
+
+use near_async::time::{Clock, Utc};
+use near_primitives::sharding::ChunkHash;
+use near_primitives::types::{AccountId, BlockHeight, ShardId};
+use near_primitives::views::{
+    ChunkPartInfo, ChunkPartsForwardInfo, ChunkPartsRequestInfo, ChunkPartsStatusView,
+};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+
+use crate::metrics;
+
+/// Number of chunks whose status is kept.
+const CHUNK_STATUS_CACHE_SIZE: usize = 1000;
+
+/// Request for the status of a chunk, see `ChunkStatusTracker`.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "Option<ChunkPartsStatusView>")]
+pub struct GetChunkPartsStatus {
+    pub chunk_hash: ChunkHash,
+}
+
+/// Message which delivered a part.
+#[derive(Clone, Copy, Debug, strum::IntoStaticStr)]
+pub(crate) enum ChunkPartSource {
+    /// The PartialEncodedChunk message from the chunk producer.
+    Chunk,
+    /// A PartialEncodedChunkForward message from the part owner.
+    Forward,
+    /// A response to our request.
+    Response,
+}
+
+#[derive(Default)]
+struct PartArrivals {
+    received_time: Option<Utc>,
+    forwarded_received_time: Option<Utc>,
+    chunk_received_time: Option<Utc>,
+}
+
+struct ChunkStatus {
+    height_created: Option<BlockHeight>,
+    shard_id: Option<ShardId>,
+    first_seen_timestamp: Utc,
+    completed_timestamp: Option<Utc>,
+    parts: HashMap<u64, PartArrivals>,
+    requests: Vec<ChunkPartsRequestInfo>,
+    forwards: Vec<ChunkPartsForwardInfo>,
+}
+
+/// Keeps track of what happened to the parts of the recent chunks.
+pub(crate) struct ChunkStatusTracker {
+    clock: Clock,
+    chunks: lru::LruCache<ChunkHash, ChunkStatus>,
+}
+
+impl ChunkStatusTracker {
+    pub fn new(clock: Clock) -> Self {
+        Self {
+            clock,
+            chunks: lru::LruCache::new(NonZeroUsize::new(CHUNK_STATUS_CACHE_SIZE).unwrap()),
+        }
+    }
+
+    fn get_or_insert(&mut self, chunk_hash: &ChunkHash) -> &mut ChunkStatus {
+        let now = self.clock.now_utc();
+        self.chunks.get_or_insert_mut(chunk_hash.clone(), || ChunkStatus {
+            height_created: None,
+            shard_id: None,
+            first_seen_timestamp: now,
+            completed_timestamp: None,
+            parts: HashMap::new(),
+            requests: vec![],
+            forwards: vec![],
+        })
+    }
+
+    pub fn record_header(
+        &mut self,
+        chunk_hash: &ChunkHash,
+        height_created: BlockHeight,
+        shard_id: ShardId,
+    ) {
+        let status = self.get_or_insert(chunk_hash);
+        status.height_created = Some(height_created);
+        status.shard_id = Some(shard_id);
+    }
+
+    pub fn record_parts_received(
+        &mut self,
+        chunk_hash: &ChunkHash,
+        part_ords: impl Iterator<Item = u64>,
+        source: ChunkPartSource,
+    ) {
+        let now = self.clock.now_utc();
+        let status = self.get_or_insert(chunk_hash);
+        let mut num_parts = 0u64;
+        for part_ord in part_ords {
+            let arrivals = status.parts.entry(part_ord).or_default();
+            match source {
+                ChunkPartSource::Chunk => {
+                    arrivals.chunk_received_time.get_or_insert(now);
+                }
+                ChunkPartSource::Forward => {
+                    arrivals.forwarded_received_time.get_or_insert(now);
+                }
+                ChunkPartSource::Response => {}
+            }
+            arrivals.received_time.get_or_insert(now);
+            num_parts += 1;
+        }
+        metrics::PARTIAL_ENCODED_CHUNK_PARTS_RECEIVED
+            .with_label_values(&[source.into()])
+            .inc_by(num_parts);
+    }
+
+    pub fn record_request(&mut self, chunk_hash: &ChunkHash, target: String, part_ords: Vec<u64>) {
+        let timestamp = self.clock.now_utc();
+        self.get_or_insert(chunk_hash).requests.push(ChunkPartsRequestInfo {
+            target,
+            part_ords,
+            timestamp,
+        });
+    }
+
+    pub fn record_forward(
+        &mut self,
+        chunk_hash: &ChunkHash,
+        account_id: AccountId,
+        part_ords: Vec<u64>,
+        retransmit: bool,
+    ) {
+        let timestamp = self.clock.now_utc();
+        self.get_or_insert(chunk_hash).forwards.push(ChunkPartsForwardInfo {
+            account_id,
+            part_ords,
+            timestamp,
+            retransmit,
+        });
+    }
+
+    pub fn record_completed(&mut self, chunk_hash: &ChunkHash) {
+        let now = self.clock.now_utc();
+        let status = self.get_or_insert(chunk_hash);
+        if status.completed_timestamp.is_none() {
+            status.completed_timestamp = Some(now);
+            metrics::CHUNK_PARTS_COLLECTION_TIME
+                .observe((now - status.first_seen_timestamp).as_seconds_f64());
+        }
+    }
+
+    /// Returns the status of the chunk. `part_owners` are the owners of all the parts of the
+    /// chunk, by part ordinal, if they can be determined.
+    pub fn get_status(
+        &self,
+        chunk_hash: &ChunkHash,
+        part_owners: Option<Vec<AccountId>>,
+    ) -> Option<ChunkPartsStatusView> {
+        let status = self.chunks.peek(chunk_hash)?;
+        let mut part_ords = match &part_owners {
+            Some(part_owners) => (0..part_owners.len() as u64).collect::<Vec<_>>(),
+            None => status.parts.keys().copied().collect(),
+        };
+        part_ords.sort();
+        let parts = part_ords
+            .into_iter()
+            .map(|part_ord| {
+                let arrivals = status.parts.get(&part_ord);
+                ChunkPartInfo {
+                    part_ord,
+                    part_owner: part_owners
+                        .as_ref()
+                        .map(|owners| owners[part_ord as usize].clone()),
+                    received_time: arrivals.and_then(|a| a.received_time),
+                    forwarded_received_time: arrivals.and_then(|a| a.forwarded_received_time),
+                    chunk_received_time: arrivals.and_then(|a| a.chunk_received_time),
+                }
+            })
+            .collect();
+        Some(ChunkPartsStatusView {
+            chunk_hash: chunk_hash.clone(),
+            height_created: status.height_created,
+            shard_id: status.shard_id,
+            first_seen_timestamp: status.first_seen_timestamp,
+            completed_timestamp: status.completed_timestamp,
+            parts,
+            requests: status.requests.clone(),
+            forwards: status.forwards.clone(),
+        })
+    }
+}
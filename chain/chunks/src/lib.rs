@@ -1,7 +1,9 @@
 pub mod adapter;
 mod chunk_cache;
 pub mod client;
+pub mod debug;
 pub mod logic;
 pub mod metrics;
+mod receipt_proofs_cache;
 pub mod shards_manager_actor;
 pub mod test_utils;
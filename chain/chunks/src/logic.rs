@@ -181,7 +181,9 @@ pub fn decode_encoded_chunk(
     .entered();
 
     if let Ok(shard_chunk) = encoded_chunk
-        .decode_chunk(epoch_manager.num_data_parts())
+        .decode_chunk(
+            encoded_chunk.cloned_header().num_data_parts_or(epoch_manager.num_data_parts()),
+        )
         .map_err(|err| Error::from(err))
         .and_then(|shard_chunk| {
             if !validate_chunk_proofs(&shard_chunk, epoch_manager)? {
@@ -65,3 +65,53 @@ pub static PARTIAL_ENCODED_CHUNK_FORWARD_CACHED_WITHOUT_PREV_BLOCK: LazyLock<Cou
     )
     .unwrap()
     });
+
+pub static PARTIAL_ENCODED_CHUNK_FORWARD_RETRANSMITTED: LazyLock<Counter> = LazyLock::new(|| {
+    near_o11y::metrics::try_create_counter(
+        "near_partial_encoded_chunk_forward_retransmitted",
+        "Number of times pushed chunk parts were pushed again because they were not acknowledged",
+    )
+    .unwrap()
+});
+
+pub static PARTIAL_ENCODED_CHUNK_PARTS_RECEIVED: LazyLock<near_o11y::metrics::IntCounterVec> =
+    LazyLock::new(|| {
+        near_o11y::metrics::try_create_int_counter_vec(
+            "near_partial_encoded_chunk_parts_received",
+            "Number of chunk parts received, by the message which delivered them",
+            &["source"],
+        )
+        .unwrap()
+    });
+
+pub static CHUNK_PARTS_COLLECTION_TIME: LazyLock<Histogram> = LazyLock::new(|| {
+    try_create_histogram(
+        "near_chunk_parts_collection_time",
+        "Time in seconds between first hearing of a chunk and having all its parts we need",
+    )
+    .unwrap()
+});
+
+pub static OUTGOING_RECEIPTS_PROOFS_CACHE_HITS: LazyLock<Counter> = LazyLock::new(|| {
+    near_o11y::metrics::try_create_counter(
+        "near_outgoing_receipts_proofs_cache_hits",
+        "Number of times the proofs of the outgoing receipts of a chunk were found in the cache",
+    )
+    .unwrap()
+});
+
+pub static OUTGOING_RECEIPTS_PROOFS_CACHE_MISSES: LazyLock<Counter> = LazyLock::new(|| {
+    near_o11y::metrics::try_create_counter(
+        "near_outgoing_receipts_proofs_cache_misses",
+        "Number of times the proofs of the outgoing receipts of a chunk had to be computed",
+    )
+    .unwrap()
+});
+
+pub static OUTGOING_RECEIPTS_PROOFS_COMPUTE_TIME: LazyLock<Histogram> = LazyLock::new(|| {
+    try_create_histogram(
+        "near_outgoing_receipts_proofs_compute_time",
+        "Time in seconds taken to compute the proofs of the outgoing receipts of a chunk",
+    )
+    .unwrap()
+});
@@ -0,0 +1,121 @@
+//! Cache of the proofs of the outgoing receipts of the recent chunks.
+//!
+//! The outgoing receipts of a chunk are the ones produced by applying the
+//! previous chunk of the shard, so they only depend on the previous block and
+//! the shard. Their Merkle proofs are needed when the chunk producer distributes
+//! the chunk, and each time someone requests the receipts of a chunk we only
+//! have in storage, which happens several times for the same chunk.
+
+use near_cache::SyncLruCache;
+use near_chain::types::EpochManagerAdapter;
+use near_primitives::errors::EpochError;
+use near_primitives::hash::CryptoHash;
+use near_primitives::receipt::Receipt;
+use near_primitives::sharding::{ReceiptProof, ShardChunkHeader};
+use near_primitives::types::ShardId;
+use std::sync::Arc;
+
+use crate::logic::make_outgoing_receipts_proofs;
+use crate::metrics;
+
+/// Number of (prev block, shard) pairs whose proofs are kept.
+const OUTGOING_RECEIPTS_PROOFS_CACHE_SIZE: usize = 100;
+
+struct CachedProofs {
+    /// Root of the outgoing receipts the proofs were computed for.
+    outgoing_receipts_root: CryptoHash,
+    proofs: Arc<Vec<Arc<ReceiptProof>>>,
+}
+
+pub(crate) struct OutgoingReceiptsProofsCache {
+    cache: SyncLruCache<(CryptoHash, ShardId), Arc<CachedProofs>>,
+}
+
+impl OutgoingReceiptsProofsCache {
+    pub fn new() -> Self {
+        Self { cache: SyncLruCache::new(OUTGOING_RECEIPTS_PROOFS_CACHE_SIZE) }
+    }
+
+    /// Returns the proofs of `outgoing_receipts`, the outgoing receipts of the chunk with
+    /// `chunk_header`, computing them with `make_outgoing_receipts_proofs` if they are not
+    /// cached yet.
+    pub fn get_or_make(
+        &self,
+        chunk_header: &ShardChunkHeader,
+        outgoing_receipts: &[Receipt],
+        epoch_manager: &dyn EpochManagerAdapter,
+    ) -> Result<Arc<Vec<Arc<ReceiptProof>>>, EpochError> {
+        let key = (*chunk_header.prev_block_hash(), chunk_header.shard_id());
+        let outgoing_receipts_root = chunk_header.prev_outgoing_receipts_root();
+        // A chunk with different outgoing receipts for the same previous block is invalid, don't
+        // let it evict the proofs of the valid one.
+        let cached = self.cache.get(&key);
+        if let Some(cached) = &cached {
+            if cached.outgoing_receipts_root == outgoing_receipts_root {
+                metrics::OUTGOING_RECEIPTS_PROOFS_CACHE_HITS.inc();
+                return Ok(cached.proofs.clone());
+            }
+        }
+        metrics::OUTGOING_RECEIPTS_PROOFS_CACHE_MISSES.inc();
+        let proofs = Self::make(chunk_header, outgoing_receipts, epoch_manager)?;
+        if cached.is_none() {
+            self.cache.put(
+                key,
+                Arc::new(CachedProofs { outgoing_receipts_root, proofs: proofs.clone() }),
+            );
+        }
+        Ok(proofs)
+    }
+
+    fn make(
+        chunk_header: &ShardChunkHeader,
+        outgoing_receipts: &[Receipt],
+        epoch_manager: &dyn EpochManagerAdapter,
+    ) -> Result<Arc<Vec<Arc<ReceiptProof>>>, EpochError> {
+        let _timer = metrics::OUTGOING_RECEIPTS_PROOFS_COMPUTE_TIME.start_timer();
+        let proofs = make_outgoing_receipts_proofs(chunk_header, outgoing_receipts, epoch_manager)?
+            .into_iter()
+            .map(Arc::new)
+            .collect();
+        Ok(Arc::new(proofs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutgoingReceiptsProofsCache;
+    use crate::logic::make_outgoing_receipts_proofs;
+    use crate::test_utils::ChunkTestFixture;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_outgoing_receipts_proofs_cache() {
+        let fixture = ChunkTestFixture::default();
+        let cache = OutgoingReceiptsProofsCache::new();
+        let expected = make_outgoing_receipts_proofs(
+            &fixture.mock_chunk_header,
+            &fixture.mock_outgoing_receipts,
+            &fixture.epoch_manager,
+        )
+        .unwrap();
+
+        let proofs = cache
+            .get_or_make(
+                &fixture.mock_chunk_header,
+                &fixture.mock_outgoing_receipts,
+                &fixture.epoch_manager,
+            )
+            .unwrap();
+        assert_eq!(proofs.iter().map(|proof| proof.as_ref().clone()).collect::<Vec<_>>(), expected);
+
+        // The second lookup returns the same proofs, without computing them again.
+        let cached = cache
+            .get_or_make(
+                &fixture.mock_chunk_header,
+                &fixture.mock_outgoing_receipts,
+                &fixture.epoch_manager,
+            )
+            .unwrap();
+        assert!(Arc::ptr_eq(&proofs, &cached));
+    }
+}
@@ -38,6 +38,17 @@
 //! which is done periodically through the ShardsManagerActor. A request is only removed from
 //! the pool when all needed parts and receipts in the requested chunk are received.
 //!
+//! The part requests are not sent right away, but queued and sent by `send_pending_part_requests`
+//! once all chunks of a RequestChunks message or of a `resend_chunk_requests` round are handled.
+//! Requests to the same target, e.g. for the chunks of a shard at several heights when catching
+//! up, are sent in one PartialEncodedChunkRequestBatch message, answered with one
+//! PartialEncodedChunkResponseBatch. The network sends the batch only to directly connected
+//! peers whose protocol version supports it, and sends one request per chunk otherwise.
+//!
+//! With ChunkPartDistributionMode::Push, the owners of parts push them again until they are
+//! acknowledged, see the section about forwarding below. A node waiting for forwarded parts
+//! then sends its first requests only once the owners stopped pushing.
+//!
 //! ** Storing chunks
 //! Before a chunk can be reconstructed fully, parts and receipts in the chunk are stored in
 //! `encoded_chunks`. Full chunks will be persisted in the database storage after they are
@@ -51,6 +62,9 @@
 //! part can only be processed after the node has the corresponding chunk header, either from blocks
 //! or partial chunk requests. Before that, they are temporarily stored in `chunk_forwards_cache`.
 //! After that, they are processed as a PartialEncodedChunk message containing the cached parts.
+//! With ChunkPartDistributionMode::Push, the receiver acknowledges the forwarded parts to their
+//! owner, which pushes the parts that were not acknowledged again every `retransmit_period`,
+//! at most `max_retransmits` times (see `retransmit_unacked_part_forwards`).
 //!
 //! ** Processing chunks
 //! Function `process_partial_encoded_chunk` processes a partial encoded chunk message.
@@ -63,6 +77,13 @@
 //!    of this is that the previous block is accepted. If missing the previous block is the only
 //!    blocker, there's another chance to trigger this processing again in check_incomplete_chunks,
 //!    which is triggered by sending the CheckIncompleteChunks message from the client.
+//!    If a reconstruction worker is set, the chunk is reconstructed on the worker as soon as
+//!    enough parts are received, and completed when the ChunkReconstructed message comes back,
+//!    so that the actor keeps processing parts of other chunks meanwhile.
+//! 6) if the node is a validator and the chunk is still incomplete, saves the parts and receipts
+//!    received so far to DBCol::PendingPartialChunks. They are processed again when the node
+//!    restarts (see `restore_pending_partial_chunks`), and garbage collected by the chain once
+//!    the final head passes their height.
 //!
 //! ** Validating chunks
 //! Before `process_partial_encoded_chunk` returns HaveAllPartsAndReceipts, it will perform
@@ -81,48 +102,60 @@
 use crate::adapter::ShardsManagerRequestFromClient;
 use crate::chunk_cache::{EncodedChunksCache, EncodedChunksCacheEntry};
 use crate::client::ShardsManagerResponse;
+use crate::debug::{ChunkPartSource, ChunkStatusTracker, GetChunkPartsStatus};
 use crate::logic::{
     cares_about_shard_this_or_next_epoch, chunk_needs_to_be_fetched_from_archival,
-    decode_encoded_chunk, make_outgoing_receipts_proofs,
-    make_partial_encoded_chunk_from_owned_parts_and_needed_receipts, need_part, need_receipt,
+    decode_encoded_chunk, make_partial_encoded_chunk_from_owned_parts_and_needed_receipts,
+    need_part, need_receipt,
 };
 use crate::metrics;
+use crate::receipt_proofs_cache::OutgoingReceiptsProofsCache;
 use ::time::ext::InstantExt as _;
 use actix::Actor;
+use near_async::actix::AddrWithAutoSpanContextExt;
 use near_async::actix_wrapper::ActixWrapper;
-use near_async::futures::{DelayedActionRunner, DelayedActionRunnerExt};
-use near_async::messaging::{self, Handler, Sender};
+use near_async::futures::{
+    AsyncComputationSpawner, AsyncComputationSpawnerExt, DelayedActionRunner,
+    DelayedActionRunnerExt,
+};
+use near_async::messaging::{self, Handler, IntoSender, Sender};
 use near_async::time::Duration;
 use near_async::time::{self, Clock};
 use near_chain::byzantine_assert;
 use near_chain::near_chain_primitives::error::Error::DBNotFoundErr;
+use near_chain::rayon_spawner::RayonAsyncComputationSpawner;
 use near_chain::signature_verification::{
     verify_chunk_header_signature_with_epoch_manager,
     verify_chunk_header_signature_with_epoch_manager_and_parts,
 };
 use near_chain::types::EpochManagerAdapter;
-use near_chain_configs::MutableValidatorSigner;
+use near_chain_configs::{
+    ChunkPartDistributionConfig, ChunkPartForwardingPolicy, MutableValidatorSigner,
+};
 pub use near_chunks_primitives::Error;
 use near_epoch_manager::shard_tracker::ShardTracker;
 use near_network::shards_manager::ShardsManagerRequestFromNetwork;
 use near_network::types::{
-    AccountIdOrPeerTrackingShard, PartialEncodedChunkForwardMsg, PartialEncodedChunkRequestMsg,
-    PartialEncodedChunkResponseMsg,
+    AccountIdOrPeerTrackingShard, PartialEncodedChunkForwardAckMsg, PartialEncodedChunkForwardMsg,
+    PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg,
 };
-use near_network::types::{NetworkRequests, PeerManagerMessageRequest};
+use near_network::types::{NetworkRequests, PeerManagerMessageRequest, ReasonForBan};
 use near_performance_metrics_macros::perf;
-use near_primitives::bandwidth_scheduler::BandwidthRequests;
+use near_primitives::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 use near_primitives::block::Tip;
 use near_primitives::congestion_info::CongestionInfo;
 use near_primitives::errors::EpochError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{verify_path, MerklePath};
+use near_primitives::network::PeerId;
 use near_primitives::receipt::Receipt;
-use near_primitives::reed_solomon::{reed_solomon_decode, reed_solomon_encode};
+use near_primitives::reed_solomon::{
+    reed_solomon_decode, reed_solomon_encode, reed_solomon_with_data_parts,
+};
 use near_primitives::sharding::{
-    ChunkHash, EncodedShardChunk, EncodedShardChunkBody, PartialEncodedChunk,
-    PartialEncodedChunkPart, PartialEncodedChunkV2, ShardChunk, ShardChunkHeader,
-    TransactionReceipt,
+    ChunkHash, ChunkPartsCompression, EncodedShardChunk, EncodedShardChunkBody,
+    PartialEncodedChunk, PartialEncodedChunkPart, PartialEncodedChunkV2, ShardChunk,
+    ShardChunkHeader, TransactionReceipt,
 };
 use near_primitives::stateless_validation::ChunkProductionKey;
 use near_primitives::transaction::SignedTransaction;
@@ -134,7 +167,8 @@ use near_primitives::unwrap_or_return;
 use near_primitives::utils::MaybeValidated;
 use near_primitives::validator_signer::ValidatorSigner;
 use near_primitives::version::{ProtocolFeature, ProtocolVersion};
-use near_store::adapter::chunk_store::ChunkStoreAdapter;
+use near_primitives::views::ChunkPartsStatusView;
+use near_store::adapter::chunk_store::{ChunkStoreAdapter, PendingPartialChunkItem};
 use near_store::adapter::StoreAdapter;
 use near_store::{DBCol, Store, HEADER_HEAD_KEY, HEAD_KEY};
 use rand::seq::IteratorRandom;
@@ -152,6 +186,9 @@ const CHUNK_REQUEST_RETRY_MAX: time::Duration = time::Duration::seconds(1000);
 const CHUNK_FORWARD_CACHE_SIZE: usize = 1000;
 // Only request chunks from peers whose latest height >= chunk_height - CHUNK_REQUEST_PEER_HORIZON
 const CHUNK_REQUEST_PEER_HORIZON: BlockHeightDelta = 5;
+// Max number of chunks requested in one PartialEncodedChunkRequestBatch message. Larger batches
+// received from peers are truncated.
+const MAX_CHUNK_REQUESTS_PER_BATCH: usize = 16;
 
 #[derive(PartialEq, Eq)]
 pub enum ChunkStatus {
@@ -173,6 +210,34 @@ pub enum ProcessPartialEncodedChunkResult {
     NeedBlock,
 }
 
+/// Result of reconstructing a chunk on the reconstruction worker.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "()")]
+pub struct ChunkReconstructed {
+    chunk_hash: ChunkHash,
+    cares_about_shard: bool,
+    result: Result<(ShardChunk, PartialEncodedChunk), Error>,
+}
+
+/// Parts pushed to an account which did not acknowledge them yet.
+struct UnackedPartForward {
+    forward: PartialEncodedChunkForwardMsg,
+    last_sent: time::Instant,
+    num_retransmits: u32,
+}
+
+/// Parts and receipts of an incomplete chunk which are saved in DBCol::PendingPartialChunks.
+#[derive(Default)]
+struct PersistedPartialChunk {
+    parts: HashSet<u64>,
+    receipts: HashSet<ShardId>,
+}
+
+struct ChunkReconstructionWorker {
+    spawner: Arc<dyn AsyncComputationSpawner>,
+    done_sender: Sender<ChunkReconstructed>,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct ChunkRequestInfo {
     height: BlockHeight,
@@ -228,6 +293,13 @@ impl RequestPool {
         self.requests.remove(chunk_hash);
     }
 
+    /// Delays the next time the request is fetched by `delay`.
+    pub fn delay(&mut self, chunk_hash: &ChunkHash, delay: time::Duration) {
+        if let Some(chunk_request) = self.requests.get_mut(chunk_hash) {
+            chunk_request.last_requested += delay;
+        }
+    }
+
     pub fn fetch(&mut self, current_time: time::Instant) -> Vec<(ChunkHash, ChunkRequestInfo)> {
         let mut removed_requests = HashSet::<ChunkHash>::default();
         let mut requests = Vec::new();
@@ -272,6 +344,9 @@ pub struct ShardsManagerActor {
     encoded_chunks: EncodedChunksCache,
     requested_partial_encoded_chunks: RequestPool,
     chunk_forwards_cache: lru::LruCache<ChunkHash, HashMap<u64, PartialEncodedChunkPart>>,
+    /// Proofs of the outgoing receipts of the recent chunks, used both when distributing a
+    /// chunk we produced and when answering requests for receipts.
+    outgoing_receipts_proofs_cache: OutgoingReceiptsProofsCache,
 
     // This is a best-effort cache of the chain's head, not the source of truth. The source
     // of truth is in the chain store and written to by the Client.
@@ -283,10 +358,28 @@ pub struct ShardsManagerActor {
     // header_head is much newer.
     chain_header_head: Tip,
     chunk_request_retry_period: Duration,
+
+    /// Part requests queued by `request_partial_encoded_chunk`, see `send_pending_part_requests`.
+    pending_part_requests: Vec<(AccountIdOrPeerTrackingShard, PartialEncodedChunkRequestMsg)>,
+    /// If set, chunks are reconstructed on the worker instead of on the actor.
+    reconstruction_worker: Option<ChunkReconstructionWorker>,
+    /// Chunks currently being reconstructed by the worker.
+    chunks_in_reconstruction: HashSet<ChunkHash>,
+    /// Chunks whose parts and receipts received so far are saved in
+    /// DBCol::PendingPartialChunks, see `persist_pending_partial_chunk`.
+    pending_partial_chunks_in_store: HashMap<ChunkHash, PersistedPartialChunk>,
+
+    chunk_part_distribution: ChunkPartDistributionConfig,
+    /// With ChunkPartDistributionMode::Push, the parts we pushed which were not acknowledged yet,
+    /// by chunk and receiver.
+    unacked_part_forwards: HashMap<(ChunkHash, AccountId), UnackedPartForward>,
+    /// What happened to the parts of the recent chunks, for debugging.
+    chunk_status_tracker: ChunkStatusTracker,
 }
 
 impl messaging::Actor for ShardsManagerActor {
     fn start_actor(&mut self, ctx: &mut dyn DelayedActionRunner<Self>) {
+        self.restore_pending_partial_chunks();
         self.periodically_resend_chunk_requests(ctx)
     }
 }
@@ -305,6 +398,19 @@ impl Handler<ShardsManagerRequestFromNetwork> for ShardsManagerActor {
     }
 }
 
+impl Handler<ChunkReconstructed> for ShardsManagerActor {
+    #[perf]
+    fn handle(&mut self, msg: ChunkReconstructed) {
+        self.on_chunk_reconstructed(msg);
+    }
+}
+
+impl Handler<GetChunkPartsStatus> for ShardsManagerActor {
+    fn handle(&mut self, msg: GetChunkPartsStatus) -> Option<ChunkPartsStatusView> {
+        self.get_chunk_parts_status(&msg.chunk_hash)
+    }
+}
+
 pub fn start_shards_manager(
     epoch_manager: Arc<dyn EpochManagerAdapter>,
     view_epoch_manager: Arc<dyn EpochManagerAdapter>,
@@ -314,6 +420,7 @@ pub fn start_shards_manager(
     validator_signer: MutableValidatorSigner,
     store: Store,
     chunk_request_retry_period: Duration,
+    chunk_part_distribution: ChunkPartDistributionConfig,
 ) -> (actix::Addr<ActixWrapper<ShardsManagerActor>>, actix::ArbiterHandle) {
     let shards_manager_arbiter = actix::Arbiter::new().handle();
     // TODO: make some better API for accessing chain properties like head.
@@ -325,7 +432,7 @@ pub fn start_shards_manager(
         .get_ser::<Tip>(DBCol::BlockMisc, HEADER_HEAD_KEY)
         .unwrap()
         .expect("ShardsManager must be initialized after the chain is initialized");
-    let shards_manager = ShardsManagerActor::new(
+    let mut shards_manager = ShardsManagerActor::new(
         Clock::real(),
         validator_signer,
         epoch_manager,
@@ -338,9 +445,14 @@ pub fn start_shards_manager(
         chain_header_head,
         chunk_request_retry_period,
     );
+    shards_manager.set_chunk_part_distribution(chunk_part_distribution);
 
     let shards_manager_addr =
-        ActixWrapper::<ShardsManagerActor>::start_in_arbiter(&shards_manager_arbiter, move |_| {
+        ActixWrapper::<ShardsManagerActor>::start_in_arbiter(&shards_manager_arbiter, move |ctx| {
+            shards_manager.set_reconstruction_worker(
+                Arc::new(RayonAsyncComputationSpawner),
+                ctx.address().with_auto_span_context().into_sender(),
+            );
             ActixWrapper::new(shards_manager)
         });
     (shards_manager_addr, shards_manager_arbiter)
@@ -361,6 +473,7 @@ impl ShardsManagerActor {
         chunk_request_retry_period: Duration,
     ) -> Self {
         Self {
+            chunk_status_tracker: ChunkStatusTracker::new(clock.clone()),
             clock,
             validator_signer,
             store,
@@ -384,12 +497,33 @@ impl ShardsManagerActor {
             chunk_forwards_cache: lru::LruCache::new(
                 NonZeroUsize::new(CHUNK_FORWARD_CACHE_SIZE).unwrap(),
             ),
+            outgoing_receipts_proofs_cache: OutgoingReceiptsProofsCache::new(),
             chain_head: initial_chain_head,
             chain_header_head: initial_chain_header_head,
             chunk_request_retry_period,
+            pending_part_requests: vec![],
+            reconstruction_worker: None,
+            chunks_in_reconstruction: HashSet::new(),
+            pending_partial_chunks_in_store: HashMap::new(),
+            chunk_part_distribution: ChunkPartDistributionConfig::default(),
+            unacked_part_forwards: HashMap::new(),
         }
     }
 
+    pub fn set_chunk_part_distribution(&mut self, config: ChunkPartDistributionConfig) {
+        self.chunk_part_distribution = config;
+    }
+
+    /// Reconstructs chunks with `spawner` from now on. The results are sent back to the
+    /// actor through `done_sender`.
+    pub fn set_reconstruction_worker(
+        &mut self,
+        spawner: Arc<dyn AsyncComputationSpawner>,
+        done_sender: Sender<ChunkReconstructed>,
+    ) {
+        self.reconstruction_worker = Some(ChunkReconstructionWorker { spawner, done_sender });
+    }
+
     pub fn periodically_resend_chunk_requests(
         &mut self,
         delayed_action_runner: &mut dyn DelayedActionRunner<Self>,
@@ -399,6 +533,7 @@ impl ShardsManagerActor {
             self.chunk_request_retry_period,
             move |this, delayed_action_runner| {
                 this.resend_chunk_requests();
+                this.retransmit_unacked_part_forwards();
                 this.periodically_resend_chunk_requests(delayed_action_runner);
             },
         )
@@ -555,14 +690,7 @@ impl ShardsManagerActor {
                     only_archival: request_from_archival,
                     min_height: height.saturating_sub(CHUNK_REQUEST_PEER_HORIZON),
                 };
-
-                self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-                    NetworkRequests::PartialEncodedChunkRequest {
-                        target,
-                        request,
-                        create_time: self.clock.now(),
-                    },
-                ));
+                self.pending_part_requests.push((target, request));
             } else {
                 warn!(target: "client", "{:?} requests parts {:?} for chunk {:?} from self",
                     me, part_ords, chunk_hash
@@ -573,6 +701,53 @@ impl ShardsManagerActor {
         Ok(())
     }
 
+    /// Sends the part requests queued by `request_partial_encoded_chunk`. The requests to the
+    /// same target are sent in one PartialEncodedChunkRequestBatch message.
+    fn send_pending_part_requests(&mut self) {
+        let mut batches = HashMap::<_, (AccountIdOrPeerTrackingShard, Vec<_>)>::new();
+        for (target, request) in std::mem::take(&mut self.pending_part_requests) {
+            let key = (target.account_id.clone(), target.shard_id, target.only_archival);
+            let (batch_target, requests) =
+                batches.entry(key).or_insert_with(|| (target.clone(), vec![]));
+            batch_target.min_height = batch_target.min_height.min(target.min_height);
+            requests.push(request);
+        }
+        let create_time = self.clock.now();
+        for (target, mut requests) in batches.into_values() {
+            while !requests.is_empty() {
+                let rest = requests.split_off(requests.len().min(MAX_CHUNK_REQUESTS_PER_BATCH));
+                let mut batch = std::mem::replace(&mut requests, rest);
+                let target_name = match &target.account_id {
+                    Some(account_id) => account_id.to_string(),
+                    None => format!("peer tracking shard {}", target.shard_id),
+                };
+                for request in &batch {
+                    self.chunk_status_tracker.record_request(
+                        &request.chunk_hash,
+                        target_name.clone(),
+                        request.part_ords.clone(),
+                    );
+                }
+                let network_request = if batch.len() == 1 {
+                    NetworkRequests::PartialEncodedChunkRequest {
+                        target: target.clone(),
+                        request: batch.pop().unwrap(),
+                        create_time,
+                    }
+                } else {
+                    debug!(target: "chunks", ?target, num_requests = batch.len(), "Sending batched part requests");
+                    NetworkRequests::PartialEncodedChunkRequestBatch {
+                        target: target.clone(),
+                        requests: batch,
+                        create_time,
+                    }
+                };
+                self.peer_manager_adapter
+                    .send(PeerManagerMessageRequest::NetworkRequests(network_request));
+            }
+        }
+    }
+
     /// Get a random shard block producer that is not me.
     fn get_random_target_tracking_shard(
         &self,
@@ -677,7 +852,19 @@ impl ShardsManagerActor {
         self.request_chunk_single(chunk_header, *chunk_header.prev_block_hash(), true, me)
     }
 
-    /// send partial chunk requests for one chunk
+    /// Same as `queue_chunk_request`, but sends the part requests right away.
+    fn request_chunk_single(
+        &mut self,
+        chunk_header: &ShardChunkHeader,
+        ancestor_hash: CryptoHash,
+        mark_only: bool,
+        me: Option<&AccountId>,
+    ) {
+        self.queue_chunk_request(chunk_header, ancestor_hash, mark_only, me);
+        self.send_pending_part_requests();
+    }
+
+    /// queue partial chunk requests for one chunk, to be sent by `send_pending_part_requests`
     /// `chunk_header`: the chunk being requested
     /// `ancestor_hash`: hash of an ancestor block of the requested chunk.
     ///                  It must satisfy
@@ -685,7 +872,7 @@ impl ShardsManagerActor {
     ///                  2) it is processed
     ///                  If the above conditions are not met, the request will be dropped
     /// `mark_only`: if true, only add the request to the pool, but do not send it.
-    fn request_chunk_single(
+    fn queue_chunk_request(
         &mut self,
         chunk_header: &ShardChunkHeader,
         ancestor_hash: CryptoHash,
@@ -780,6 +967,12 @@ impl ShardsManagerActor {
             }
         } else {
             debug!(target: "chunks",should_wait_for_chunk_forwarding, fetch_from_archival, old_block,  "Delaying the chunk request.");
+            if self.chunk_part_distribution.is_push() {
+                // The owners push their parts until we acknowledge them, so only request the
+                // parts which are still missing once they stopped.
+                self.requested_partial_encoded_chunks
+                    .delay(&chunk_hash, self.chunk_part_distribution.push_duration());
+            }
         }
     }
 
@@ -800,8 +993,9 @@ impl ShardsManagerActor {
             num_chunks_to_request = chunks_to_request.len())
         .entered();
         for chunk_header in chunks_to_request {
-            self.request_chunk_single(&chunk_header, prev_hash, false, me);
+            self.queue_chunk_request(&chunk_header, prev_hash, false, me);
         }
+        self.send_pending_part_requests();
     }
 
     /// Request chunks for an orphan block.
@@ -833,8 +1027,9 @@ impl ShardsManagerActor {
         }
 
         for chunk_header in chunks_to_request {
-            self.request_chunk_single(&chunk_header, ancestor_hash, false, me)
+            self.queue_chunk_request(&chunk_header, ancestor_hash, false, me)
         }
+        self.send_pending_part_requests();
     }
 
     /// Resends chunk requests if haven't received it within expected time.
@@ -879,6 +1074,7 @@ impl ShardsManagerActor {
                 }
             }
         }
+        self.send_pending_part_requests();
     }
 
     fn process_partial_encoded_chunk_request(
@@ -898,6 +1094,44 @@ impl ShardsManagerActor {
             shards = ?request.tracking_shards,
             account = ?me);
 
+        let response = self.prepare_partial_encoded_chunk_response_with_metrics(request);
+        self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::PartialEncodedChunkResponse { route_back, response },
+        ));
+    }
+
+    /// Answers all requests of a batch in one PartialEncodedChunkResponseBatch message, since
+    /// the route back is only usable once. Requests beyond MAX_CHUNK_REQUESTS_PER_BATCH are
+    /// dropped.
+    fn process_partial_encoded_chunk_request_batch(
+        &self,
+        requests: Vec<PartialEncodedChunkRequestMsg>,
+        route_back: CryptoHash,
+        me: Option<&AccountId>,
+    ) {
+        let _span = tracing::debug_span!(
+            target: "chunks",
+            "process_partial_encoded_chunk_request_batch",
+            num_requests = requests.len())
+        .entered();
+        debug!(target: "chunks",
+            chunk_hashes = ?requests.iter().map(|request| &request.chunk_hash.0).collect::<Vec<_>>(),
+            account = ?me);
+
+        let responses = requests
+            .into_iter()
+            .take(MAX_CHUNK_REQUESTS_PER_BATCH)
+            .map(|request| self.prepare_partial_encoded_chunk_response_with_metrics(request))
+            .collect();
+        self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::PartialEncodedChunkResponseBatch { route_back, responses },
+        ));
+    }
+
+    fn prepare_partial_encoded_chunk_response_with_metrics(
+        &self,
+        request: PartialEncodedChunkRequestMsg,
+    ) -> PartialEncodedChunkResponseMsg {
         let started = self.clock.now();
         let (source, response) = self.prepare_partial_encoded_chunk_response(request);
         let elapsed = (self.clock.now().signed_duration_since(started)).as_seconds_f64();
@@ -908,10 +1142,7 @@ impl ShardsManagerActor {
         metrics::PARTIAL_ENCODED_CHUNK_REQUEST_PROCESSING_TIME
             .with_label_values(&labels)
             .observe(elapsed);
-
-        self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-            NetworkRequests::PartialEncodedChunkResponse { route_back, response },
-        ));
+        response
     }
 
     /// Finds the parts and receipt proofs asked for in the request, and returns a response
@@ -1057,15 +1288,16 @@ impl ShardsManagerActor {
         // Get outgoing receipts for the chunk and construct vector of their
         // proofs.
         let outgoing_receipts = chunk.prev_outgoing_receipts();
-        let outgoing_receipts_proofs = make_outgoing_receipts_proofs(
+        let outgoing_receipts_proofs = self.outgoing_receipts_proofs_cache.get_or_make(
             &header,
             &outgoing_receipts,
             self.view_epoch_manager.as_ref(),
         );
         let present_receipts: HashMap<ShardId, _> = match outgoing_receipts_proofs {
-            Ok(receipts) => {
-                receipts.into_iter().map(|receipt| (receipt.1.to_shard_id, receipt)).collect()
-            }
+            Ok(receipts) => receipts
+                .iter()
+                .map(|receipt| (receipt.1.to_shard_id, receipt.as_ref().clone()))
+                .collect(),
             Err(e) => {
                 warn!(target: "chunks", "Not sending {:?}, failed to make outgoing receipts proofs: {}", chunk.chunk_hash(), e);
                 return;
@@ -1075,8 +1307,12 @@ impl ShardsManagerActor {
         // Construct EncodedShardChunk.  If we earlier determined that we will
         // need parity parts, instruct the constructor to calculate them as
         // well.  Otherwise we won’t bother.
-        let (parts, encoded_length) = reed_solomon_encode(
+        let rs = reed_solomon_with_data_parts(
             &self.rs,
+            header.num_data_parts_or(self.epoch_manager.num_data_parts()),
+        );
+        let (parts, encoded_length) = reed_solomon_encode(
+            &rs,
             TransactionReceipt(chunk.transactions().to_vec(), outgoing_receipts.to_vec()),
         );
 
@@ -1130,30 +1366,9 @@ impl ShardsManagerActor {
             chunk_hash = ?chunk.chunk_hash())
         .entered();
 
-        let data_parts = self.epoch_manager.num_data_parts();
-        if chunk.content().num_fetched_parts() < data_parts {
-            debug!(target: "chunks", num_fetched_parts = chunk.content().num_fetched_parts(), data_parts, "Incomplete");
-            return ChunkStatus::Incomplete;
-        }
-
-        let encoded_length = chunk.encoded_length();
-        if let Err(err) = reed_solomon_decode::<TransactionReceipt>(
-            &self.rs,
-            chunk.content_mut().parts.as_mut_slice(),
-            encoded_length as usize,
-        ) {
-            debug!(target: "chunks", ?err, "Invalid: Failed to decode");
-            return ChunkStatus::Invalid;
-        }
-
-        let (merkle_root, merkle_paths) = chunk.content().get_merkle_hash_and_paths();
-        if merkle_root != chunk.encoded_merkle_root() {
-            debug!(target: "chunks", ?merkle_root, chunk_encoded_merkle_root = ?chunk.encoded_merkle_root(), "Invalid: Wrong merkle root");
-            return ChunkStatus::Invalid;
-        }
-
-        debug!(target: "chunks", "Complete");
-        ChunkStatus::Complete(merkle_paths)
+        let data_parts =
+            chunk.cloned_header().num_data_parts_or(self.epoch_manager.num_data_parts());
+        check_encoded_chunk_complete(&self.rs, data_parts, chunk)
     }
 
     /// Add a part to current encoded chunk stored in memory. It's present only if One Part was present and signed correctly.
@@ -1301,12 +1516,14 @@ impl ShardsManagerActor {
         let maybe_header = self
             .validate_partial_encoded_chunk_forward(&forward)
             .and_then(|_| self.get_partial_encoded_chunk_header(&forward.chunk_hash));
+        let part_ords = forward.parts.iter().map(|part| part.part_ord).collect::<Vec<_>>();
 
         let header = match maybe_header {
             Ok(header) => Ok(header),
             Err(Error::UnknownChunk) => {
                 // We don't know this chunk yet; cache the forwarded part
                 // to be used after we get the header.
+                self.ack_part_forward(&forward.chunk_hash, &forward.prev_block_hash, part_ords, me);
                 self.insert_forwarded_chunk(forward);
                 metrics::PARTIAL_ENCODED_CHUNK_FORWARD_CACHED_WITHOUT_HEADER.inc();
                 return Ok(()); // a normal and expected case, not error
@@ -1318,6 +1535,12 @@ impl ShardsManagerActor {
                         // we don't know `prev_block`, however the signature is checked when
                         // forwarded parts are later processed as partial encoded chunks, so we
                         // can mark it as unknown for now.
+                        self.ack_part_forward(
+                            &forward.chunk_hash,
+                            &forward.prev_block_hash,
+                            part_ords,
+                            me,
+                        );
                         self.insert_forwarded_chunk(forward);
                         metrics::PARTIAL_ENCODED_CHUNK_FORWARD_CACHED_WITHOUT_PREV_BLOCK.inc();
                         return Ok(()); // a normal and expected case, not error
@@ -1334,9 +1557,132 @@ impl ShardsManagerActor {
             prev_outgoing_receipts: Vec::new(),
         });
         self.process_partial_encoded_chunk(MaybeValidated::from_validated(partial_chunk), me)?;
+        self.ack_part_forward(&forward.chunk_hash, &forward.prev_block_hash, part_ords, me);
         Ok(())
     }
 
+    /// With ChunkPartDistributionMode::Push, acknowledges forwarded parts to their owner once
+    /// they are accepted or cached, so that it doesn't push them again.
+    fn ack_part_forward(
+        &self,
+        chunk_hash: &ChunkHash,
+        prev_block_hash: &CryptoHash,
+        part_ords: Vec<u64>,
+        me: Option<&AccountId>,
+    ) {
+        if !self.chunk_part_distribution.is_push() {
+            return;
+        }
+        let (Some(me), Some(&part_ord)) = (me, part_ords.first()) else {
+            return;
+        };
+        // As when forwarding, the epoch of the chain head is a good enough guess if the previous
+        // block is not known yet.
+        let owner = self
+            .epoch_manager
+            .get_epoch_id_from_prev_block(prev_block_hash)
+            .or_else(|_| {
+                self.epoch_manager.get_epoch_id_from_prev_block(&self.chain_head.last_block_hash)
+            })
+            .and_then(|epoch_id| self.epoch_manager.get_part_owner(&epoch_id, part_ord));
+        let owner = match owner {
+            Ok(owner) => owner,
+            Err(err) => {
+                debug!(target: "chunks", ?chunk_hash, ?err, "Cannot acknowledge forwarded parts, owner unknown");
+                return;
+            }
+        };
+        if &owner == me {
+            return;
+        }
+        self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::PartialEncodedChunkForwardAck {
+                account_id: owner,
+                ack: PartialEncodedChunkForwardAckMsg {
+                    chunk_hash: chunk_hash.clone(),
+                    account_id: me.clone(),
+                    part_ords,
+                },
+            },
+        ));
+    }
+
+    fn process_partial_encoded_chunk_forward_ack(&mut self, ack: PartialEncodedChunkForwardAckMsg) {
+        let key = (ack.chunk_hash, ack.account_id);
+        let Some(unacked) = self.unacked_part_forwards.get_mut(&key) else {
+            return;
+        };
+        unacked.forward.parts.retain(|part| !ack.part_ords.contains(&part.part_ord));
+        if unacked.forward.parts.is_empty() {
+            self.unacked_part_forwards.remove(&key);
+        }
+    }
+
+    /// Sends our parts of a chunk to `account_id`. With ChunkPartDistributionMode::Push, the parts
+    /// are pushed again until `account_id` acknowledges them.
+    fn send_part_forward(&mut self, account_id: AccountId, forward: PartialEncodedChunkForwardMsg) {
+        if self.chunk_part_distribution.is_push() {
+            let now = self.clock.now();
+            let unacked = self
+                .unacked_part_forwards
+                .entry((forward.chunk_hash.clone(), account_id.clone()))
+                .or_insert_with(|| UnackedPartForward {
+                    forward: PartialEncodedChunkForwardMsg { parts: vec![], ..forward.clone() },
+                    last_sent: now,
+                    num_retransmits: 0,
+                });
+            for part in &forward.parts {
+                if unacked.forward.parts.iter().all(|p| p.part_ord != part.part_ord) {
+                    unacked.forward.parts.push(part.clone());
+                }
+            }
+            unacked.last_sent = now;
+        }
+        self.chunk_status_tracker.record_forward(
+            &forward.chunk_hash,
+            account_id.clone(),
+            forward.parts.iter().map(|part| part.part_ord).collect(),
+            false,
+        );
+        self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::PartialEncodedChunkForward { account_id, forward },
+        ));
+    }
+
+    /// Pushes again the parts which were not acknowledged within `retransmit_period`, and gives
+    /// up on those already pushed again `max_retransmits` times.
+    fn retransmit_unacked_part_forwards(&mut self) {
+        let now = self.clock.now();
+        let ChunkPartDistributionConfig { retransmit_period, max_retransmits, .. } =
+            self.chunk_part_distribution;
+        let mut forwards_to_send = vec![];
+        self.unacked_part_forwards.retain(|(chunk_hash, account_id), unacked| {
+            if now - unacked.last_sent < retransmit_period {
+                return true;
+            }
+            if unacked.num_retransmits >= max_retransmits {
+                debug!(target: "chunks", ?chunk_hash, ?account_id, "Giving up pushing unacknowledged parts");
+                return false;
+            }
+            unacked.num_retransmits += 1;
+            unacked.last_sent = now;
+            forwards_to_send.push((account_id.clone(), unacked.forward.clone()));
+            true
+        });
+        for (account_id, forward) in forwards_to_send {
+            metrics::PARTIAL_ENCODED_CHUNK_FORWARD_RETRANSMITTED.inc();
+            self.chunk_status_tracker.record_forward(
+                &forward.chunk_hash,
+                account_id.clone(),
+                forward.parts.iter().map(|part| part.part_ord).collect(),
+                true,
+            );
+            self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+                NetworkRequests::PartialEncodedChunkForward { account_id, forward },
+            ));
+        }
+    }
+
     /// Validate a chunk header
     /// 1) check that the chunk header is signed by the correct chunk producer for the chunk at
     ///    the height for the shard
@@ -1419,9 +1765,10 @@ impl ShardsManagerActor {
             };
         }
 
-        // 2. check protocol version
+        // 2. check protocol version and the number of data parts the chunk is encoded with
         let protocol_version = self.epoch_manager.get_epoch_protocol_version(&epoch_id)?;
-        if header.validate_version(protocol_version).is_ok() {
+        let valid_data_parts = header.has_valid_num_data_parts(self.epoch_manager.num_data_parts());
+        if header.validate_version(protocol_version).is_ok() && valid_data_parts {
             Ok(())
         } else if epoch_id_confirmed {
             Err(Error::InvalidChunkHeader)
@@ -1438,6 +1785,13 @@ impl ShardsManagerActor {
         header: &ShardChunkHeader,
     ) -> bool {
         let header_known_before = self.encoded_chunks.get(&header.chunk_hash()).is_some();
+        if !header_known_before {
+            self.chunk_status_tracker.record_header(
+                &header.chunk_hash(),
+                header.height_created(),
+                header.shard_id(),
+            );
+        }
         if self.encoded_chunks.get_or_insert_from_header(header).complete {
             return false;
         }
@@ -1487,8 +1841,19 @@ impl ShardsManagerActor {
         partial_encoded_chunk: MaybeValidated<PartialEncodedChunk>,
         me: Option<&AccountId>,
     ) -> Result<ProcessPartialEncodedChunkResult, Error> {
-        let partial_encoded_chunk =
-            partial_encoded_chunk.map(|chunk| PartialEncodedChunkV2::from(chunk));
+        let validated = partial_encoded_chunk.is_validated();
+        let partial_encoded_chunk = partial_encoded_chunk
+            .into_inner()
+            .into_v2(self.epoch_manager.num_data_parts(), self.epoch_manager.num_total_parts())
+            .map_err(|err| {
+                byzantine_assert!(false);
+                Error::InvalidCompressedParts(err)
+            })?;
+        let partial_encoded_chunk = if validated {
+            MaybeValidated::from_validated(partial_encoded_chunk)
+        } else {
+            MaybeValidated::from(partial_encoded_chunk)
+        };
         let header = &partial_encoded_chunk.header;
         let chunk_hash = header.chunk_hash();
         let _span = debug_span!(
@@ -1509,9 +1874,9 @@ impl ShardsManagerActor {
             if entry.complete {
                 return Ok(ProcessPartialEncodedChunkResult::Known);
             }
-            debug!(target: "chunks", num_parts_in_cache = entry.parts.len(), total_needed = self.epoch_manager.num_data_parts());
+            debug!(target: "chunks", num_parts_in_cache = entry.parts.len(), total_needed = header.num_data_parts_or(self.epoch_manager.num_data_parts()));
         } else {
-            debug!(target: "chunks", num_parts_in_cache = 0, total_needed = self.epoch_manager.num_data_parts());
+            debug!(target: "chunks", num_parts_in_cache = 0, total_needed = header.num_data_parts_or(self.epoch_manager.num_data_parts()));
         }
 
         // 1.b Checking chunk height
@@ -1575,6 +1940,8 @@ impl ShardsManagerActor {
         // into chunk cache
         let new_part_ords =
             self.encoded_chunks.merge_in_partial_encoded_chunk(partial_encoded_chunk);
+        let received_new_data =
+            !new_part_ords.is_empty() || !partial_encoded_chunk.prev_outgoing_receipts.is_empty();
 
         // 3. Forward my parts to others tracking this chunk's shard
         // It's possible that the previous block has not been processed yet. We will want to
@@ -1621,9 +1988,97 @@ impl ShardsManagerActor {
             }
             _ => {}
         }
+
+        // 6. Validators persist what they have of incomplete chunks, so that they don't need to
+        // fetch it again if they restart.
+        if me.is_some()
+            && received_new_data
+            && !matches!(result, ProcessPartialEncodedChunkResult::HaveAllPartsAndReceipts)
+        {
+            self.persist_pending_partial_chunk(&chunk_hash);
+        }
         Ok(result)
     }
 
+    /// Saves the parts and receipts of the chunk received since the last call to
+    /// DBCol::PendingPartialChunks, with the header if the chunk is not saved yet.
+    fn persist_pending_partial_chunk(&mut self, chunk_hash: &ChunkHash) {
+        let Some(entry) = self.encoded_chunks.get(chunk_hash) else {
+            return;
+        };
+        if entry.complete {
+            return;
+        }
+        let persisted = self.pending_partial_chunks_in_store.get(chunk_hash);
+        let mut items = vec![];
+        if persisted.is_none() {
+            items.push(PendingPartialChunkItem::Header(entry.header.clone()));
+        }
+        let new_parts = entry
+            .parts
+            .iter()
+            .filter(|(part_ord, _)| !persisted.is_some_and(|p| p.parts.contains(*part_ord)))
+            .map(|(_, part)| part);
+        items.extend(new_parts.cloned().map(PendingPartialChunkItem::Part));
+        let new_receipts = entry
+            .receipts
+            .iter()
+            .filter(|(shard_id, _)| !persisted.is_some_and(|p| p.receipts.contains(*shard_id)))
+            .map(|(_, receipt)| receipt);
+        items.extend(new_receipts.cloned().map(PendingPartialChunkItem::Receipt));
+        if items.is_empty() {
+            return;
+        }
+        if let Err(err) = self.store.save_pending_partial_chunk_items(
+            entry.header.height_created(),
+            chunk_hash,
+            &items,
+        ) {
+            warn!(target: "chunks", ?chunk_hash, ?err, "Failed to persist pending partial chunk");
+            return;
+        }
+        let persisted = self.pending_partial_chunks_in_store.entry(chunk_hash.clone()).or_default();
+        persisted.parts.extend(entry.parts.keys().copied());
+        persisted.receipts.extend(entry.receipts.keys().copied());
+    }
+
+    /// Processes again the parts and receipts of incomplete chunks persisted before a restart.
+    /// Only chunks above the chain head are restored; the chunks of processed blocks are complete.
+    fn restore_pending_partial_chunks(&mut self) {
+        let me = self.validator_signer.get().map(|signer| signer.validator_id().clone());
+        if me.is_none() {
+            return;
+        }
+        let pending_chunks =
+            match self.store.get_pending_partial_chunks(self.chain_head.height.saturating_add(1)) {
+                Ok(pending_chunks) => pending_chunks,
+                Err(err) => {
+                    warn!(target: "chunks", ?err, "Failed to read pending partial chunks");
+                    return;
+                }
+            };
+        for partial_chunk in pending_chunks {
+            let chunk_hash = partial_chunk.chunk_hash();
+            debug!(target: "chunks", ?chunk_hash, num_parts = partial_chunk.parts().len(), "Restoring pending partial chunk");
+            self.pending_partial_chunks_in_store.insert(
+                chunk_hash.clone(),
+                PersistedPartialChunk {
+                    parts: partial_chunk.parts().iter().map(|part| part.part_ord).collect(),
+                    receipts: partial_chunk
+                        .prev_outgoing_receipts()
+                        .iter()
+                        .map(|receipt| receipt.1.to_shard_id)
+                        .collect(),
+                },
+            );
+            if let Err(err) =
+                self.process_partial_encoded_chunk(MaybeValidated::from(partial_chunk), me.as_ref())
+            {
+                debug!(target: "chunks", ?chunk_hash, ?err, "Failed to restore pending partial chunk");
+            }
+        }
+    }
+
     fn process_partial_encoded_chunk_response(
         &mut self,
         response: PartialEncodedChunkResponseMsg,
@@ -1718,7 +2173,8 @@ impl ShardsManagerActor {
         let have_all_parts = self.has_all_parts(&prev_block_hash, entry, me)?;
         let have_all_receipts = self.has_all_receipts(&prev_block_hash, entry, me)?;
 
-        let can_reconstruct = entry.parts.len() >= self.epoch_manager.num_data_parts();
+        let can_reconstruct =
+            entry.parts.len() >= header.num_data_parts_or(self.epoch_manager.num_data_parts());
         let chunk_producer = self
             .epoch_manager
             .get_chunk_producer_info(&ChunkProductionKey {
@@ -1779,6 +2235,30 @@ impl ShardsManagerActor {
                     Some(part_entry.part.clone());
             }
 
+            if let Some(worker) = &self.reconstruction_worker {
+                if !self.chunks_in_reconstruction.insert(chunk_hash.clone()) {
+                    debug!(target: "chunks", "Chunk is already being reconstructed");
+                    return Ok(ProcessPartialEncodedChunkResult::NeedMorePartsOrReceipts);
+                }
+                let data_parts = header.num_data_parts_or(self.epoch_manager.num_data_parts());
+                let rs = reed_solomon_with_data_parts(&self.rs, data_parts).into_owned();
+                let epoch_manager = self.epoch_manager.clone();
+                let shard_tracker = self.shard_tracker.clone();
+                let me = me.cloned();
+                let done_sender = worker.done_sender.clone();
+                worker.spawner.spawn("reconstruct_chunk", move || {
+                    let result = reconstruct_chunk(
+                        &rs,
+                        encoded_chunk,
+                        me.as_ref(),
+                        epoch_manager.as_ref(),
+                        &shard_tracker,
+                    );
+                    done_sender.send(ChunkReconstructed { chunk_hash, cares_about_shard, result });
+                });
+                return Ok(ProcessPartialEncodedChunkResult::HaveAllPartsAndReceipts);
+            }
+
             let (shard_chunk, partial_chunk) = self
                 .decode_encoded_chunk_if_complete(encoded_chunk, me)?
                 .expect("decoding shouldn't fail");
@@ -1796,6 +2276,29 @@ impl ShardsManagerActor {
         Ok(ProcessPartialEncodedChunkResult::NeedMorePartsOrReceipts)
     }
 
+    fn on_chunk_reconstructed(&mut self, msg: ChunkReconstructed) {
+        let ChunkReconstructed { chunk_hash, cares_about_shard, result } = msg;
+        let _span = debug_span!(target: "chunks", "on_chunk_reconstructed", ?chunk_hash).entered();
+        self.chunks_in_reconstruction.remove(&chunk_hash);
+        match self.encoded_chunks.get(&chunk_hash) {
+            Some(entry) if !entry.complete => {}
+            _ => {
+                debug!(target: "chunks", "Chunk is already complete or no longer cached");
+                return;
+            }
+        }
+        match result {
+            Ok((shard_chunk, partial_chunk)) => {
+                // For consistency, only persist shard_chunk if we actually care about the shard.
+                self.complete_chunk(partial_chunk, cares_about_shard.then_some(shard_chunk));
+            }
+            Err(err) => {
+                warn!(target: "chunks", ?err, "Failed to reconstruct chunk");
+                self.encoded_chunks.remove(&chunk_hash);
+            }
+        }
+    }
+
     /// A helper function to be called after a chunk is considered complete
     fn complete_chunk(
         &mut self,
@@ -1805,8 +2308,16 @@ impl ShardsManagerActor {
         let _span = debug_span!(target: "chunks", "complete_chunk").entered();
         let chunk_hash = partial_chunk.chunk_hash();
         self.encoded_chunks.mark_entry_complete(&chunk_hash);
+        self.chunk_status_tracker.record_completed(&chunk_hash);
         self.encoded_chunks.remove_from_cache_if_outside_horizon(&chunk_hash);
         self.requested_partial_encoded_chunks.remove(&chunk_hash);
+        if self.pending_partial_chunks_in_store.remove(&chunk_hash).is_some() {
+            if let Err(err) =
+                self.store.delete_pending_partial_chunk(partial_chunk.height_created(), &chunk_hash)
+            {
+                warn!(target: "chunks", ?chunk_hash, ?err, "Failed to delete pending partial chunk");
+            }
+        }
         debug!(target: "chunks", "Completed chunk {:?}", chunk_hash);
         self.client_adapter
             .send(ShardsManagerResponse::ChunkCompleted { partial_chunk, shard_chunk });
@@ -1877,15 +2388,27 @@ impl ShardsManagerActor {
         );
 
         let protocol_version = self.epoch_manager.get_epoch_protocol_version(epoch_id)?;
-        let block_producers =
-            self.epoch_manager.get_epoch_block_producers_ordered(&epoch_id, latest_block_hash)?;
+        let block_producers = match self.chunk_part_distribution.forwarding_policy {
+            ChunkPartForwardingPolicy::AllTrackers => self
+                .epoch_manager
+                .get_epoch_block_producers_ordered(&epoch_id, latest_block_hash)?
+                .into_iter()
+                .map(|(bp, _)| bp.take_account_id())
+                .collect(),
+            ChunkPartForwardingPolicy::NextBlockProducers { num_block_producers } => self
+                .get_next_block_producers(
+                    &partial_encoded_chunk.header,
+                    epoch_id,
+                    num_block_producers,
+                )?,
+        };
         let current_chunk_height = partial_encoded_chunk.header.height_created();
 
+        // The accounts to forward to, by priority.
+        let mut recipients = vec![];
         // SingleShardTracking: If enabled, we only forward the parts to the block producers
         if ProtocolFeature::StatelessValidation.enabled(protocol_version) {
             let shard_id = partial_encoded_chunk.header.shard_id();
-            let mut accounts_forwarded_to = HashSet::new();
-            accounts_forwarded_to.insert(me.clone());
             let next_chunk_producer = self
                 .epoch_manager
                 .get_chunk_producer_info(&ChunkProductionKey {
@@ -1894,83 +2417,73 @@ impl ShardsManagerActor {
                     shard_id,
                 })?
                 .take_account_id();
-            for (bp, _) in block_producers {
-                let bp_account_id = bp.take_account_id();
-
-                if cares_about_shard_this_or_next_epoch(
-                    Some(&bp_account_id),
+            recipients.push(next_chunk_producer);
+            recipients.extend(block_producers.into_iter().filter(|bp_account_id| {
+                cares_about_shard_this_or_next_epoch(
+                    Some(bp_account_id),
                     latest_block_hash,
                     shard_id,
                     false,
                     &self.shard_tracker,
-                ) {
-                    if accounts_forwarded_to.insert(bp_account_id.clone()) {
-                        self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-                            NetworkRequests::PartialEncodedChunkForward {
-                                account_id: bp_account_id,
-                                forward: forward.clone(),
-                            },
-                        ));
-                    }
-                }
-            }
-
-            if accounts_forwarded_to.insert(next_chunk_producer.clone()) {
-                self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-                    NetworkRequests::PartialEncodedChunkForward {
-                        account_id: next_chunk_producer,
-                        forward,
-                    },
-                ));
-            }
+                )
+            }));
         } else {
             // Without SingleShardTracking, we're asking all validators to track all shards.
             // So we may as well forward every part to all block producers, and we also
             // forward to incoming chunk producers so they can produce the next chunks without
             // delay.
-            let mut next_chunk_producers = self
-                .epoch_manager
-                .shard_ids(&epoch_id)?
-                .into_iter()
-                .map(|shard_id| {
+            for shard_id in self.epoch_manager.shard_ids(&epoch_id)? {
+                recipients.push(
                     self.epoch_manager
                         .get_chunk_producer_info(&ChunkProductionKey {
                             epoch_id: *epoch_id,
                             height_created: current_chunk_height + 1,
                             shard_id,
-                        })
-                        .map(|info| info.take_account_id())
-                })
-                .collect::<Result<HashSet<_>, _>>()?;
-            next_chunk_producers.remove(me);
-            for (bp, _) in block_producers {
-                let bp_account_id = bp.take_account_id();
-                // no need to send anything to myself
-                if me == &bp_account_id {
-                    continue;
-                }
-                next_chunk_producers.remove(&bp_account_id);
-
-                self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-                    NetworkRequests::PartialEncodedChunkForward {
-                        account_id: bp_account_id,
-                        forward: forward.clone(),
-                    },
-                ));
+                        })?
+                        .take_account_id(),
+                );
             }
+            recipients.extend(block_producers);
+        }
 
-            for next_chunk_producer in next_chunk_producers {
-                self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-                    NetworkRequests::PartialEncodedChunkForward {
-                        account_id: next_chunk_producer,
-                        forward: forward.clone(),
-                    },
-                ));
-            }
+        // no need to send anything to myself
+        let mut accounts_forwarded_to = HashSet::from([me.clone()]);
+        recipients.retain(|account_id| accounts_forwarded_to.insert(account_id.clone()));
+        if let Some(max_forward_fanout) = self.chunk_part_distribution.max_forward_fanout {
+            recipients.truncate(max_forward_fanout);
+        }
+        for account_id in recipients {
+            self.send_part_forward(account_id, forward.clone());
         }
         Ok(())
     }
 
+    /// Producers of the `num_block_producers` blocks following the chunk. Near the expected end
+    /// of the epoch, the producers of the next epoch are added as well, since the blocks may be
+    /// in either epoch.
+    fn get_next_block_producers(
+        &self,
+        header: &ShardChunkHeader,
+        epoch_id: &EpochId,
+        num_block_producers: u64,
+    ) -> Result<Vec<AccountId>, Error> {
+        let epoch_start_height =
+            self.epoch_manager.get_epoch_start_height(header.prev_block_hash())?;
+        let epoch_length = self.epoch_manager.get_epoch_config(epoch_id)?.epoch_length;
+        let next_epoch_id =
+            self.epoch_manager.get_next_epoch_id_from_prev_block(header.prev_block_hash())?;
+        let mut block_producers = vec![];
+        let first_height = header.height_created() + 1;
+        for height in first_height..first_height + num_block_producers {
+            block_producers.push(self.epoch_manager.get_block_producer(epoch_id, height)?);
+            if height + num_block_producers >= epoch_start_height + epoch_length {
+                block_producers
+                    .push(self.epoch_manager.get_block_producer(&next_epoch_id, height)?);
+            }
+        }
+        Ok(block_producers)
+    }
+
     /// Returns true if we have all the necessary receipts for this chunk entry to process it.
     /// NOTE: this doesn't mean that we got *all* the receipts.
     /// It means that we have all receipts included in this chunk sending to the shards we track.
@@ -2027,6 +2540,8 @@ impl ShardsManagerActor {
         tx_root: CryptoHash,
         congestion_info: Option<CongestionInfo>,
         bandwidth_requests: Option<BandwidthRequests>,
+        prev_outgoing_bandwidth: Option<OutgoingBandwidth>,
+        prev_pre_state_root: Option<StateRoot>,
         signer: &ValidatorSigner,
         rs: &ReedSolomon,
         protocol_version: ProtocolVersion,
@@ -2048,6 +2563,8 @@ impl ShardsManagerActor {
             prev_outgoing_receipts_root,
             congestion_info,
             bandwidth_requests,
+            prev_outgoing_bandwidth,
+            prev_pre_state_root,
             signer,
             protocol_version,
         )
@@ -2086,14 +2603,18 @@ impl ShardsManagerActor {
             entry.push(part_ord);
         }
 
-        let receipt_proofs = make_outgoing_receipts_proofs(
+        let protocol_version = self.epoch_manager.get_epoch_protocol_version(&epoch_id)?;
+        let compression = if ProtocolFeature::ChunkPartCompression.enabled(protocol_version) {
+            ChunkPartsCompression::Zstd
+        } else {
+            ChunkPartsCompression::None
+        };
+
+        let receipt_proofs = self.outgoing_receipts_proofs_cache.get_or_make(
             &chunk_header,
             &outgoing_receipts,
             self.epoch_manager.as_ref(),
-        )?
-        .into_iter()
-        .map(Arc::new)
-        .collect::<Vec<_>>();
+        )?;
         for (to_whom, part_ords) in block_producer_mapping {
             let part_receipt_proofs = receipt_proofs
                 .iter()
@@ -2110,12 +2631,13 @@ impl ShardsManagerActor {
                 .cloned()
                 .collect();
 
-            let partial_encoded_chunk = encoded_chunk
+            let mut partial_encoded_chunk = encoded_chunk
                 .create_partial_encoded_chunk_with_arc_receipts(
                     part_ords,
                     part_receipt_proofs,
                     &merkle_paths,
                 );
+            partial_encoded_chunk.compression = compression;
 
             if Some(&to_whom) != me {
                 self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
@@ -2208,6 +2730,20 @@ impl ShardsManagerActor {
         }
     }
 
+    fn get_chunk_parts_status(&self, chunk_hash: &ChunkHash) -> Option<ChunkPartsStatusView> {
+        let part_owners = self.encoded_chunks.get(chunk_hash).and_then(|entry| {
+            let epoch_id = self
+                .epoch_manager
+                .get_epoch_id_from_prev_block(entry.header.prev_block_hash())
+                .ok()?;
+            (0..self.epoch_manager.num_total_parts() as u64)
+                .map(|part_ord| self.epoch_manager.get_part_owner(&epoch_id, part_ord))
+                .collect::<Result<Vec<_>, _>>()
+                .ok()
+        });
+        self.chunk_status_tracker.get_status(chunk_hash, part_owners)
+    }
+
     pub fn handle_network_request(&mut self, request: ShardsManagerRequestFromNetwork) {
         let _span = tracing::debug_span!(
             target: "chunks",
@@ -2218,21 +2754,57 @@ impl ShardsManagerActor {
         let me = self.validator_signer.get().map(|signer| signer.validator_id().clone());
         let me = me.as_ref();
         match request {
-            ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(partial_encoded_chunk) => {
-                if let Err(e) = self.process_partial_encoded_chunk(partial_encoded_chunk.into(), me)
-                {
-                    warn!(target: "chunks", "Error processing partial encoded chunk: {:?}", e);
+            ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
+                partial_encoded_chunk,
+                peer_id,
+            } => {
+                let chunk_hash = partial_encoded_chunk.chunk_hash();
+                let part_ords = partial_encoded_chunk
+                    .parts()
+                    .iter()
+                    .map(|part| part.part_ord)
+                    .collect::<Vec<_>>();
+                match self.process_partial_encoded_chunk(partial_encoded_chunk.into(), me) {
+                    Ok(_) => self.chunk_status_tracker.record_parts_received(
+                        &chunk_hash,
+                        part_ords.into_iter(),
+                        ChunkPartSource::Chunk,
+                    ),
+                    Err(Error::InvalidCompressedParts(err)) => {
+                        warn!(target: "chunks", ?err, ?peer_id, "Banning peer for sending invalid compressed chunk parts");
+                        self.peer_manager_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+                            NetworkRequests::BanPeer { peer_id, ban_reason: ReasonForBan::Abusive },
+                        ));
+                    }
+                    Err(e) => {
+                        warn!(target: "chunks", "Error processing partial encoded chunk: {:?}", e);
+                    }
                 }
             }
             ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForward(
                 partial_encoded_chunk_forward,
             ) => {
-                if let Err(e) =
-                    self.process_partial_encoded_chunk_forward(partial_encoded_chunk_forward, me)
+                let chunk_hash = partial_encoded_chunk_forward.chunk_hash.clone();
+                let part_ords = partial_encoded_chunk_forward
+                    .parts
+                    .iter()
+                    .map(|part| part.part_ord)
+                    .collect::<Vec<_>>();
+                match self.process_partial_encoded_chunk_forward(partial_encoded_chunk_forward, me)
                 {
-                    warn!(target: "chunks", "Error processing partial encoded chunk forward: {:?}", e);
+                    Ok(_) => self.chunk_status_tracker.record_parts_received(
+                        &chunk_hash,
+                        part_ords.into_iter(),
+                        ChunkPartSource::Forward,
+                    ),
+                    Err(e) => {
+                        warn!(target: "chunks", "Error processing partial encoded chunk forward: {:?}", e);
+                    }
                 }
             }
+            ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForwardAck(ack) => {
+                self.process_partial_encoded_chunk_forward_ack(ack);
+            }
             ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
                 partial_encoded_chunk_response,
                 received_time,
@@ -2240,10 +2812,23 @@ impl ShardsManagerActor {
                 metrics::PARTIAL_ENCODED_CHUNK_RESPONSE_DELAY.observe(
                     (self.clock.now().signed_duration_since(received_time)).as_seconds_f64(),
                 );
-                if let Err(e) =
-                    self.process_partial_encoded_chunk_response(partial_encoded_chunk_response, me)
+                let chunk_hash = partial_encoded_chunk_response.chunk_hash.clone();
+                let part_ords = partial_encoded_chunk_response
+                    .parts
+                    .iter()
+                    .map(|part| part.part_ord)
+                    .collect::<Vec<_>>();
+                match self
+                    .process_partial_encoded_chunk_response(partial_encoded_chunk_response, me)
                 {
-                    warn!(target: "chunks", "Error processing partial encoded chunk response: {:?}", e);
+                    Ok(()) => self.chunk_status_tracker.record_parts_received(
+                        &chunk_hash,
+                        part_ords.into_iter(),
+                        ChunkPartSource::Response,
+                    ),
+                    Err(e) => {
+                        warn!(target: "chunks", "Error processing partial encoded chunk response: {:?}", e);
+                    }
                 }
             }
             ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequest {
@@ -2256,7 +2841,68 @@ impl ShardsManagerActor {
                     me,
                 );
             }
+            ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequestBatch {
+                partial_encoded_chunk_requests,
+                route_back,
+            } => {
+                self.process_partial_encoded_chunk_request_batch(
+                    partial_encoded_chunk_requests,
+                    route_back,
+                    me,
+                );
+            }
+        }
+    }
+}
+
+/// Decodes the chunk if it has at least `data_parts` parts, and checks the decoded parts
+/// against the encoded merkle root.
+fn check_encoded_chunk_complete(
+    rs: &ReedSolomon,
+    data_parts: usize,
+    chunk: &mut EncodedShardChunk,
+) -> ChunkStatus {
+    if chunk.content().num_fetched_parts() < data_parts {
+        debug!(target: "chunks", num_fetched_parts = chunk.content().num_fetched_parts(), data_parts, "Incomplete");
+        return ChunkStatus::Incomplete;
+    }
+
+    let encoded_length = chunk.encoded_length();
+    if let Err(err) = reed_solomon_decode::<TransactionReceipt>(
+        &reed_solomon_with_data_parts(rs, data_parts),
+        chunk.content_mut().parts.as_mut_slice(),
+        encoded_length as usize,
+    ) {
+        debug!(target: "chunks", ?err, "Invalid: Failed to decode");
+        return ChunkStatus::Invalid;
+    }
+
+    let (merkle_root, merkle_paths) = chunk.content().get_merkle_hash_and_paths();
+    if merkle_root != chunk.encoded_merkle_root() {
+        debug!(target: "chunks", ?merkle_root, chunk_encoded_merkle_root = ?chunk.encoded_merkle_root(), "Invalid: Wrong merkle root");
+        return ChunkStatus::Invalid;
+    }
+
+    debug!(target: "chunks", "Complete");
+    ChunkStatus::Complete(merkle_paths)
+}
+
+/// Reconstructs the chunk from its parts, on the reconstruction worker.
+fn reconstruct_chunk(
+    rs: &ReedSolomon,
+    mut encoded_chunk: EncodedShardChunk,
+    me: Option<&AccountId>,
+    epoch_manager: &dyn EpochManagerAdapter,
+    shard_tracker: &ShardTracker,
+) -> Result<(ShardChunk, PartialEncodedChunk), Error> {
+    let _span =
+        debug_span!(target: "chunks", "reconstruct_chunk", chunk_hash = ?encoded_chunk.chunk_hash())
+            .entered();
+    match check_encoded_chunk_complete(rs, rs.data_shard_count(), &mut encoded_chunk) {
+        ChunkStatus::Complete(merkle_paths) => {
+            decode_encoded_chunk(&encoded_chunk, merkle_paths, me, epoch_manager, shard_tracker)
         }
+        ChunkStatus::Incomplete | ChunkStatus::Invalid => Err(Error::InvalidChunk),
     }
 }
 
@@ -2291,13 +2937,14 @@ mod test {
     use assert_matches::assert_matches;
     use near_async::messaging::IntoSender;
     use near_async::time::FakeClock;
-    use near_chain_configs::MutableConfigValue;
+    use near_chain_configs::{ChunkPartDistributionMode, MutableConfigValue};
     use near_epoch_manager::shard_tracker::TrackedConfig;
     use near_epoch_manager::test_utils::setup_epoch_manager_with_block_and_chunk_producers;
     use near_network::test_utils::MockPeerManagerAdapter;
     use near_network::types::NetworkRequests;
     use near_primitives::block::Tip;
     use near_primitives::hash::{hash, CryptoHash};
+    use near_primitives::sharding::{ChunkPartsDecompressionError, PartialEncodedChunkV3};
     use near_primitives::types::EpochId;
     use near_primitives::validator_signer::EmptyValidatorSigner;
     use near_store::test_utils::create_test_store;
@@ -2887,6 +3534,105 @@ mod test {
         assert_eq!(response.parts.len(), fixture.all_part_ords.len());
     }
 
+    #[test]
+    fn test_process_compressed_partial_encoded_chunk() {
+        let fixture = ChunkTestFixture::default();
+        let mut shards_manager = ShardsManagerActor::new(
+            FakeClock::default().clock(),
+            mutable_validator_signer(&fixture.mock_shard_tracker),
+            Arc::new(fixture.epoch_manager.clone()),
+            Arc::new(fixture.epoch_manager.clone()),
+            fixture.shard_tracker.clone(),
+            fixture.mock_network.as_sender(),
+            fixture.mock_client_adapter.as_sender(),
+            fixture.store.clone(),
+            fixture.mock_chain_head.clone(),
+            fixture.mock_chain_head.clone(),
+            Duration::hours(1),
+        );
+
+        let num_data_parts = fixture.epoch_manager.num_data_parts();
+        let num_total_parts = fixture.epoch_manager.num_total_parts();
+        let partial_encoded_chunk = fixture
+            .make_partial_encoded_chunk(&fixture.all_part_ords)
+            .into_v2(num_data_parts, num_total_parts)
+            .unwrap();
+        let compressed = PartialEncodedChunkV3::compress(partial_encoded_chunk.clone());
+        assert_eq!(compressed.compression, ChunkPartsCompression::Zstd);
+        assert_ne!(compressed.parts, partial_encoded_chunk.parts);
+        // Compressed parts are restored to exactly the original ones.
+        assert_eq!(
+            partial_encoded_chunk,
+            compressed.clone().decompress(num_data_parts, num_total_parts).unwrap()
+        );
+
+        shards_manager
+            .process_partial_encoded_chunk(
+                PartialEncodedChunk::V3(compressed).into(),
+                Some(&fixture.mock_shard_tracker),
+            )
+            .unwrap();
+        let (source, response) =
+            shards_manager.prepare_partial_encoded_chunk_response(PartialEncodedChunkRequestMsg {
+                chunk_hash: fixture.mock_chunk_header.chunk_hash(),
+                part_ords: fixture.all_part_ords.clone(),
+                tracking_shards: HashSet::new(),
+            });
+        assert_eq!(source, PartialEncodedChunkResponseSource::InMemoryCache);
+        assert_eq!(response.parts.len(), fixture.all_part_ords.len());
+    }
+
+    /// A compressed part which decompresses into more than the part length
+    /// given by the header gets the whole message rejected and the peer banned.
+    #[test]
+    fn test_process_compressed_partial_encoded_chunk_with_oversized_part() {
+        let fixture = ChunkTestFixture::default();
+        let mut shards_manager = ShardsManagerActor::new(
+            FakeClock::default().clock(),
+            mutable_validator_signer(&fixture.mock_shard_tracker),
+            Arc::new(fixture.epoch_manager.clone()),
+            Arc::new(fixture.epoch_manager.clone()),
+            fixture.shard_tracker.clone(),
+            fixture.mock_network.as_sender(),
+            fixture.mock_client_adapter.as_sender(),
+            fixture.store.clone(),
+            fixture.mock_chain_head.clone(),
+            fixture.mock_chain_head.clone(),
+            Duration::hours(1),
+        );
+
+        let num_data_parts = fixture.epoch_manager.num_data_parts();
+        let num_total_parts = fixture.epoch_manager.num_total_parts();
+        let mut partial_encoded_chunk = fixture
+            .make_partial_encoded_chunk(&fixture.all_part_ords)
+            .into_v2(num_data_parts, num_total_parts)
+            .unwrap();
+        let part_length = partial_encoded_chunk.parts[0].part.len();
+        partial_encoded_chunk.parts[0].part = vec![0; part_length + 1].into_boxed_slice();
+        let compressed = PartialEncodedChunkV3::compress(partial_encoded_chunk);
+        assert_matches!(
+            compressed.clone().decompress(num_data_parts, num_total_parts),
+            Err(ChunkPartsDecompressionError::InvalidPart { part_ord: 0, .. })
+        );
+
+        let peer_id = PeerId::random();
+        shards_manager.handle_network_request(
+            ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
+                partial_encoded_chunk: PartialEncodedChunk::V3(compressed),
+                peer_id: peer_id.clone(),
+            },
+        );
+        assert!(shards_manager
+            .get_chunk_parts_status(&fixture.mock_chunk_header.chunk_hash())
+            .is_none());
+        let request = fixture.mock_network.pop().unwrap();
+        assert_matches!(
+            request.as_network_requests_ref(),
+            NetworkRequests::BanPeer { peer_id: banned, ban_reason: ReasonForBan::Abusive }
+                if banned == &peer_id
+        );
+    }
+
     #[test]
     fn test_chunk_response_for_uncached_partial_chunk() {
         let mut fixture = ChunkTestFixture::default();
@@ -3247,4 +3993,339 @@ mod test {
             .unwrap();
         assert_eq!(fixture.count_chunk_ready_for_inclusion_messages(), 0);
     }
+
+    #[test]
+    fn test_send_pending_part_requests_batches_by_target() {
+        let mut fixture = ChunkTestFixture::default();
+        let mut shards_manager = ShardsManagerActor::new(
+            FakeClock::default().clock(),
+            mutable_validator_signer(&fixture.mock_shard_tracker),
+            Arc::new(fixture.epoch_manager.clone()),
+            Arc::new(fixture.epoch_manager.clone()),
+            fixture.shard_tracker.clone(),
+            fixture.mock_network.as_sender(),
+            fixture.mock_client_adapter.as_sender(),
+            fixture.store.clone(),
+            fixture.mock_chain_head.clone(),
+            fixture.mock_chain_head.clone(),
+            Duration::hours(1),
+        );
+        let target = |shard_id: u64, min_height: BlockHeight| AccountIdOrPeerTrackingShard {
+            account_id: Some(fixture.mock_chunk_part_owner.clone()),
+            prefer_peer: false,
+            shard_id: ShardId::new(shard_id),
+            only_archival: false,
+            min_height,
+        };
+        let request = |seed: &[u8]| PartialEncodedChunkRequestMsg {
+            chunk_hash: ChunkHash(hash(seed)),
+            part_ords: vec![0],
+            tracking_shards: HashSet::new(),
+        };
+        shards_manager.pending_part_requests.push((target(0, 10), request(b"a")));
+        shards_manager.pending_part_requests.push((target(0, 8), request(b"b")));
+        shards_manager.pending_part_requests.push((target(1, 10), request(b"c")));
+        shards_manager.send_pending_part_requests();
+        assert!(shards_manager.pending_part_requests.is_empty());
+
+        let mut num_batches = 0;
+        let mut num_single_requests = 0;
+        while let Some(r) = fixture.mock_network.pop() {
+            match r.as_network_requests_ref() {
+                NetworkRequests::PartialEncodedChunkRequestBatch { target, requests, .. } => {
+                    assert_eq!(target.shard_id, ShardId::new(0));
+                    assert_eq!(target.min_height, 8);
+                    assert_eq!(requests.len(), 2);
+                    num_batches += 1;
+                }
+                NetworkRequests::PartialEncodedChunkRequest { target, request, .. } => {
+                    assert_eq!(target.shard_id, ShardId::new(1));
+                    assert_eq!(request.chunk_hash, ChunkHash(hash(b"c")));
+                    num_single_requests += 1;
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(num_batches, 1);
+        assert_eq!(num_single_requests, 1);
+    }
+
+    #[test]
+    fn test_chunk_response_for_request_batch() {
+        let mut fixture = ChunkTestFixture::default();
+        let shards_manager = ShardsManagerActor::new(
+            FakeClock::default().clock(),
+            mutable_validator_signer(&fixture.mock_shard_tracker),
+            Arc::new(fixture.epoch_manager.clone()),
+            Arc::new(fixture.epoch_manager.clone()),
+            fixture.shard_tracker.clone(),
+            fixture.mock_network.as_sender(),
+            fixture.mock_client_adapter.as_sender(),
+            fixture.store.clone(),
+            fixture.mock_chain_head.clone(),
+            fixture.mock_chain_head.clone(),
+            Duration::hours(1),
+        );
+
+        persist_chunk(
+            fixture.make_partial_encoded_chunk(&fixture.all_part_ords),
+            None,
+            &mut fixture.chain_store,
+        )
+        .unwrap();
+
+        let mut requests = vec![PartialEncodedChunkRequestMsg {
+            chunk_hash: fixture.mock_chunk_header.chunk_hash(),
+            part_ords: fixture.all_part_ords.clone(),
+            tracking_shards: HashSet::new(),
+        }];
+        // Requests beyond the batch limit are not answered.
+        requests.extend((0..MAX_CHUNK_REQUESTS_PER_BATCH).map(|_| PartialEncodedChunkRequestMsg {
+            chunk_hash: ChunkHash(hash(b"nonexistent")),
+            part_ords: vec![0],
+            tracking_shards: HashSet::new(),
+        }));
+        let route_back = hash(b"route_back");
+        shards_manager.process_partial_encoded_chunk_request_batch(requests, route_back, None);
+
+        let r = fixture.mock_network.pop().unwrap();
+        match r.as_network_requests_ref() {
+            NetworkRequests::PartialEncodedChunkResponseBatch {
+                route_back: actual_route_back,
+                responses,
+            } => {
+                assert_eq!(actual_route_back, &route_back);
+                assert_eq!(responses.len(), MAX_CHUNK_REQUESTS_PER_BATCH);
+                assert_eq!(responses[0].parts.len(), fixture.all_part_ords.len());
+                assert!(responses[1..].iter().all(|response| response.parts.is_empty()));
+            }
+            _ => panic!("expected a response batch"),
+        }
+        assert!(fixture.mock_network.pop().is_none());
+    }
+
+    #[test]
+    fn test_pending_partial_chunk_restored_after_restart() {
+        let fixture = ChunkTestFixture::new(true, 3, 6, 1, true);
+        let make_shards_manager = || {
+            ShardsManagerActor::new(
+                FakeClock::default().clock(),
+                mutable_validator_signer(&fixture.mock_shard_tracker),
+                Arc::new(fixture.epoch_manager.clone()),
+                Arc::new(fixture.epoch_manager.clone()),
+                fixture.shard_tracker.clone(),
+                fixture.mock_network.as_sender(),
+                fixture.mock_client_adapter.as_sender(),
+                fixture.store.clone(),
+                fixture.mock_chain_head.clone(),
+                fixture.mock_chain_head.clone(),
+                Duration::hours(1),
+            )
+        };
+        let mut shards_manager = make_shards_manager();
+        let result = shards_manager
+            .process_partial_encoded_chunk(
+                MaybeValidated::from(fixture.make_partial_encoded_chunk(&[0])),
+                Some(&fixture.mock_shard_tracker),
+            )
+            .unwrap();
+        assert_matches!(result, ProcessPartialEncodedChunkResult::NeedBlock);
+        let pending_chunks = fixture.store.get_pending_partial_chunks(0).unwrap();
+        assert_eq!(pending_chunks.len(), 1);
+        assert_eq!(pending_chunks[0].chunk_hash(), fixture.mock_chunk_header.chunk_hash());
+        assert_eq!(pending_chunks[0].parts().len(), 1);
+
+        // Parts received later are added to the saved ones.
+        shards_manager
+            .process_partial_encoded_chunk(
+                MaybeValidated::from(fixture.make_partial_encoded_chunk(&[1])),
+                Some(&fixture.mock_shard_tracker),
+            )
+            .unwrap();
+        let pending_chunks = fixture.store.get_pending_partial_chunks(0).unwrap();
+        assert_eq!(pending_chunks.len(), 1);
+        assert_eq!(pending_chunks[0].parts().len(), 2);
+
+        let mut restarted_shards_manager = make_shards_manager();
+        restarted_shards_manager.restore_pending_partial_chunks();
+        let entry = restarted_shards_manager
+            .encoded_chunks
+            .get(&fixture.mock_chunk_header.chunk_hash())
+            .unwrap();
+        let mut part_ords = entry.parts.keys().copied().collect::<Vec<_>>();
+        part_ords.sort();
+        assert_eq!(part_ords, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_push_unacked_part_forwards_again() {
+        let fixture = ChunkTestFixture::default();
+        let clock = FakeClock::default();
+        let mut shards_manager = ShardsManagerActor::new(
+            clock.clock(),
+            mutable_validator_signer(&fixture.mock_chunk_part_owner),
+            Arc::new(fixture.epoch_manager.clone()),
+            Arc::new(fixture.epoch_manager.clone()),
+            fixture.shard_tracker.clone(),
+            fixture.mock_network.as_sender(),
+            fixture.mock_client_adapter.as_sender(),
+            fixture.store.clone(),
+            fixture.mock_chain_head.clone(),
+            fixture.mock_chain_head.clone(),
+            Duration::hours(1),
+        );
+        shards_manager.set_chunk_part_distribution(ChunkPartDistributionConfig {
+            mode: ChunkPartDistributionMode::Push,
+            retransmit_period: Duration::milliseconds(100),
+            max_retransmits: 1,
+            ..Default::default()
+        });
+        let count_num_forward_msgs = |fixture: &ChunkTestFixture| {
+            fixture
+                .mock_network
+                .requests
+                .read()
+                .unwrap()
+                .iter()
+                .filter(|request| {
+                    matches!(
+                        request.as_network_requests_ref(),
+                        NetworkRequests::PartialEncodedChunkForward { .. }
+                    )
+                })
+                .count()
+        };
+        shards_manager
+            .process_partial_encoded_chunk(
+                MaybeValidated::from(
+                    fixture.make_partial_encoded_chunk(&[fixture.mock_part_ords[0]]),
+                ),
+                Some(&fixture.mock_chunk_part_owner),
+            )
+            .unwrap();
+        let num_forwards = count_num_forward_msgs(&fixture);
+        assert!(num_forwards > 0);
+        assert_eq!(shards_manager.unacked_part_forwards.len(), num_forwards);
+
+        // Nothing is pushed again before the retransmit period.
+        shards_manager.retransmit_unacked_part_forwards();
+        assert_eq!(count_num_forward_msgs(&fixture), num_forwards);
+
+        let (chunk_hash, acked_account_id) =
+            shards_manager.unacked_part_forwards.keys().next().unwrap().clone();
+        shards_manager.process_partial_encoded_chunk_forward_ack(
+            PartialEncodedChunkForwardAckMsg {
+                chunk_hash,
+                account_id: acked_account_id,
+                part_ords: vec![fixture.mock_part_ords[0]],
+            },
+        );
+        assert_eq!(shards_manager.unacked_part_forwards.len(), num_forwards - 1);
+
+        // Only the parts which were not acknowledged are pushed again.
+        clock.advance(Duration::milliseconds(150));
+        shards_manager.retransmit_unacked_part_forwards();
+        assert_eq!(count_num_forward_msgs(&fixture), 2 * num_forwards - 1);
+
+        // After `max_retransmits`, the owner gives up.
+        clock.advance(Duration::milliseconds(150));
+        shards_manager.retransmit_unacked_part_forwards();
+        assert_eq!(count_num_forward_msgs(&fixture), 2 * num_forwards - 1);
+        assert!(shards_manager.unacked_part_forwards.is_empty());
+    }
+
+    #[test]
+    // Test that a forward received before the previous block is cached and acknowledged.
+    fn test_ack_forward_before_prev_block() {
+        let fixture = ChunkTestFixture::new(true, 2, 4, 4, false);
+        let mut shards_manager = ShardsManagerActor::new(
+            FakeClock::default().clock(),
+            mutable_validator_signer(&fixture.mock_shard_tracker),
+            Arc::new(fixture.epoch_manager.clone()),
+            Arc::new(fixture.epoch_manager.clone()),
+            fixture.shard_tracker.clone(),
+            fixture.mock_network.as_sender(),
+            fixture.mock_client_adapter.as_sender(),
+            fixture.store.clone(),
+            fixture.mock_chain_head.clone(),
+            fixture.mock_chain_head.clone(),
+            Duration::hours(1),
+        );
+        shards_manager.set_chunk_part_distribution(ChunkPartDistributionConfig {
+            mode: ChunkPartDistributionMode::Push,
+            ..Default::default()
+        });
+        let owned_parts = fixture
+            .mock_chunk_parts
+            .iter()
+            .filter(|part| fixture.mock_part_ords.contains(&part.part_ord))
+            .cloned()
+            .collect();
+        let forward = PartialEncodedChunkForwardMsg::from_header_and_parts(
+            &fixture.mock_chunk_header,
+            owned_parts,
+        );
+        shards_manager
+            .process_partial_encoded_chunk_forward(forward, Some(&fixture.mock_shard_tracker))
+            .unwrap();
+        assert!(shards_manager
+            .chunk_forwards_cache
+            .contains(&fixture.mock_chunk_header.chunk_hash()));
+        let acks = fixture
+            .mock_network
+            .requests
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|request| match request.as_network_requests_ref() {
+                NetworkRequests::PartialEncodedChunkForwardAck { account_id, ack } => {
+                    Some((account_id.clone(), ack.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(acks.len(), 1);
+        let (owner, ack) = &acks[0];
+        assert_eq!(owner, &fixture.mock_chunk_part_owner);
+        assert_eq!(ack.chunk_hash, fixture.mock_chunk_header.chunk_hash());
+        assert_eq!(ack.account_id, fixture.mock_shard_tracker);
+        assert_eq!(ack.part_ords, fixture.mock_part_ords);
+    }
+
+    #[test]
+    fn test_chunk_parts_status() {
+        let fixture = ChunkTestFixture::default();
+        let mut shards_manager = ShardsManagerActor::new(
+            FakeClock::default().clock(),
+            mutable_validator_signer(&fixture.mock_shard_tracker),
+            Arc::new(fixture.epoch_manager.clone()),
+            Arc::new(fixture.epoch_manager.clone()),
+            fixture.shard_tracker.clone(),
+            fixture.mock_network.as_sender(),
+            fixture.mock_client_adapter.as_sender(),
+            fixture.store.clone(),
+            fixture.mock_chain_head.clone(),
+            fixture.mock_chain_head.clone(),
+            Duration::hours(1),
+        );
+        let chunk_hash = fixture.mock_chunk_header.chunk_hash();
+        assert!(shards_manager.get_chunk_parts_status(&chunk_hash).is_none());
+
+        shards_manager.handle_network_request(
+            ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
+                partial_encoded_chunk: fixture.make_partial_encoded_chunk(&[0]),
+                peer_id: PeerId::random(),
+            },
+        );
+        let status = shards_manager.get_chunk_parts_status(&chunk_hash).unwrap();
+        assert_eq!(status.height_created, Some(fixture.mock_chunk_header.height_created()));
+        assert_eq!(status.shard_id, Some(fixture.mock_chunk_header.shard_id()));
+        assert_eq!(status.parts.len(), fixture.epoch_manager.num_total_parts());
+        for part in &status.parts {
+            assert!(part.part_owner.is_some());
+            assert_eq!(part.chunk_received_time.is_some(), part.part_ord == 0);
+            assert!(part.forwarded_received_time.is_none());
+        }
+        assert!(status.completed_timestamp.is_none());
+    }
 }
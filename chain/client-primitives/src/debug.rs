@@ -1,6 +1,7 @@
 //! Structs in this module are used for debug purposes, and might change at any time
 //! without backwards compatibility of JSON encoding.
 use crate::types::StatusError;
+use near_primitives::challenge::{Challenge, SlashedValidator};
 use near_primitives::congestion_info::CongestionInfo;
 use near_primitives::types::{EpochId, ShardId};
 use near_primitives::views::{
@@ -174,6 +175,63 @@ pub struct ValidatorStatus {
     pub banned_chunk_producers: Vec<(EpochId, Vec<AccountId>)>,
 }
 
+/// Outcome of a single check performed while validating a challenge.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum ChallengeCheckResult {
+    Passed,
+    Failed { error: String },
+    // The check is not applicable to the challenge or an earlier check failed.
+    Skipped,
+}
+
+/// Result of validating a challenge without applying or broadcasting it.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ChallengeValidationReport {
+    pub challenge_hash: CryptoHash,
+    // Signature of the challenger, checked against the validators at the chain head.
+    pub signature: ChallengeCheckResult,
+    // Proofs included in the challenge body.
+    pub proofs: ChallengeCheckResult,
+    // Re-execution of the challenged state witness, only for `InvalidStateWitness`.
+    pub state_reexecution: ChallengeCheckResult,
+    // Validators which would be slashed if the challenge was included into a block.
+    // For a malicious challenge this is the challenger.
+    pub slashed_validators: Vec<SlashedValidator>,
+}
+
+impl ChallengeValidationReport {
+    pub fn is_valid(&self) -> bool {
+        [&self.signature, &self.proofs, &self.state_reexecution]
+            .iter()
+            .all(|result| !matches!(result, ChallengeCheckResult::Failed { .. }))
+    }
+}
+
+/// Outcome of processing a challenge received from the network.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum ChallengeVerdict {
+    Accepted,
+    // The target epoch is outside of the slashing window.
+    Expired,
+    Rejected { error: String },
+}
+
+/// Challenge recently received by the node, shown on the challenges debug page.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ChallengeDebugInfo {
+    pub challenge_hash: CryptoHash,
+    pub challenger: AccountId,
+    // Name of the `ChallengeBody` variant.
+    pub kind: &'static str,
+    pub target_epoch_id: EpochId,
+    pub target_height: BlockHeight,
+    pub received_at: Utc,
+    pub verdict: ChallengeVerdict,
+    // Validators slashed if the challenge is included into a block.
+    pub slashed_validators: Vec<SlashedValidator>,
+}
+
 // Different debug requests that can be sent by HTML pages, via GET.
 #[derive(Debug)]
 pub enum DebugStatus {
@@ -193,6 +251,10 @@ pub enum DebugStatus {
     ChainProcessingStatus,
     // The state parts already requested.
     RequestedStateParts,
+    // Dry-run validation of a challenge, sent via POST.
+    ValidateChallenge(Box<Challenge>),
+    // Challenges recently received from the network.
+    Challenges,
 }
 
 impl actix::Message for DebugStatus {
@@ -214,4 +276,8 @@ pub enum DebugStatusResponse {
     ChainProcessingStatus(ChainProcessingInfo),
     // The state parts already requested.
     RequestedStateParts(Vec<RequestedStatePartsView>),
+    // Result of the dry-run challenge validation.
+    ChallengeValidation(ChallengeValidationReport),
+    // Challenges recently received from the network, most recent first.
+    Challenges(Vec<ChallengeDebugInfo>),
 }
@@ -1,8 +1,9 @@
 use actix::Message;
 use near_chain_configs::{ClientConfig, ProtocolConfigView};
+use near_primitives::challenge::Challenge;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{MerklePath, PartialMerkleTree};
-use near_primitives::network::PeerId;
+use near_primitives::network::{PeerId, Reachability};
 use near_primitives::sharding::{ChunkHash, ShardChunk};
 use near_primitives::types::{
     AccountId, BlockHeight, BlockReference, EpochId, EpochReference, MaybeBlockId, ShardId,
@@ -10,10 +11,12 @@ use near_primitives::types::{
 };
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
 use near_primitives::views::{
-    BlockView, ChunkView, EpochValidatorInfo, ExecutionOutcomeWithIdView, GasPriceView,
+    BlockReplayView, BlockView, ChallengeStatusView, ChunkView, EpochValidatorInfo,
+    ExecutionOutcomeWithIdView, ExecutionTraceEventView, GasPriceView, InvalidBlockView,
     LightClientBlockLiteView, LightClientBlockView, MaintenanceWindowsView, QueryRequest,
     QueryResponse, ReceiptView, SplitStorageInfoView, StateChangesKindsView,
     StateChangesRequestView, StateChangesView, StateSyncStatusView, SyncStatusView, TxStatusView,
+    ValidatorEndorsementStatsView,
 };
 pub use near_primitives::views::{StatusResponse, StatusSyncInfo};
 use near_time::Duration;
@@ -591,6 +594,8 @@ pub struct NetworkInfoResponse {
     pub received_bytes_per_sec: u64,
     /// Accounts of known block and chunk producers from routing table.
     pub known_producers: Vec<KnownProducer>,
+    /// Whether other nodes can connect to this node.
+    pub reachability: Reachability,
 }
 
 /// Status of given transaction including all the subsequent receipts.
@@ -655,6 +660,18 @@ impl From<near_chain_primitives::Error> for GetValidatorInfoError {
     }
 }
 
+/// Chunk endorsements of a validator in the given epoch, or in the epoch of
+/// the final head if not set.
+#[derive(Debug)]
+pub struct GetValidatorEndorsementStats {
+    pub account_id: AccountId,
+    pub epoch_id: Option<EpochId>,
+}
+
+impl Message for GetValidatorEndorsementStats {
+    type Result = Result<ValidatorEndorsementStatsView, GetValidatorInfoError>;
+}
+
 #[derive(Debug)]
 pub struct GetValidatorOrdered {
     pub block_id: MaybeBlockId,
@@ -873,7 +890,12 @@ pub enum GetReceiptError {
 impl From<near_chain_primitives::Error> for GetReceiptError {
     fn from(error: near_chain_primitives::Error) -> Self {
         match error {
-            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            near_chain_primitives::Error::IOErr(_)
+            | near_chain_primitives::Error::StorageError(_) => Self::IOError(error.to_string()),
+            near_chain_primitives::Error::DBNotFoundErr(_)
+            | near_chain_primitives::Error::EpochOutOfBounds(_) => {
+                Self::UnknownBlock(error.to_string())
+            }
             _ => Self::Unreachable(error.to_string()),
         }
     }
@@ -883,6 +905,128 @@ impl Message for GetReceipt {
     type Result = Result<Option<ReceiptView>, GetReceiptError>;
 }
 
+/// Re-applies the chunks in which the transaction and the receipts it produced
+/// were executed and returns the execution trace of these receipts.
+#[derive(Debug)]
+pub struct TraceTransaction {
+    pub tx_hash: CryptoHash,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TraceTransactionError {
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("Transaction {0} has not been executed on this node")]
+    UnknownTransaction(CryptoHash),
+    #[error("The state needed to re-apply the chunks of the transaction is not available: {0}")]
+    StateUnavailable(String),
+    // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
+    // expected cases, we cannot statically guarantee that no other errors will be returned
+    // in the future.
+    // TODO #3851: Remove this variant once we can exhaustively match all the underlying errors
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for TraceTransactionError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            near_chain_primitives::Error::DBNotFoundErr(error_message) => {
+                Self::StateUnavailable(error_message)
+            }
+            near_chain_primitives::Error::StorageError(error) => {
+                Self::StateUnavailable(error.to_string())
+            }
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
+impl Message for TraceTransaction {
+    type Result = Result<Vec<ExecutionTraceEventView>, TraceTransactionError>;
+}
+
+/// Re-applies a processed block on a shard and compares the result with the
+/// stored one.
+#[derive(Debug)]
+pub struct ReplayBlock {
+    pub block_hash: CryptoHash,
+    pub shard_id: ShardId,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReplayBlockError {
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("Block {0} is not available on this node")]
+    UnknownBlock(CryptoHash),
+    #[error("Shard {0} does not exist in the epoch of the block")]
+    InvalidShardId(ShardId),
+    #[error("The state needed to replay the block is not available: {0}")]
+    StateUnavailable(String),
+    // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
+    // expected cases, we cannot statically guarantee that no other errors will be returned
+    // in the future.
+    // TODO #3851: Remove this variant once we can exhaustively match all the underlying errors
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for ReplayBlockError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            near_chain_primitives::Error::InvalidShardId(shard_id) => {
+                Self::InvalidShardId(shard_id)
+            }
+            near_chain_primitives::Error::DBNotFoundErr(error_message) => {
+                Self::StateUnavailable(error_message)
+            }
+            near_chain_primitives::Error::StorageError(error) => {
+                Self::StateUnavailable(error.to_string())
+            }
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
+impl Message for ReplayBlock {
+    type Result = Result<BlockReplayView, ReplayBlockError>;
+}
+
+/// Removes the given blocks from the blocks the node found invalid, so that
+/// they are processed again when received. Returns the removed entries.
+#[derive(Debug)]
+pub struct ClearInvalidBlocks {
+    pub block_hashes: Vec<CryptoHash>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ClearInvalidBlocksError {
+    #[error("IO Error: {0}")]
+    IOError(String),
+    // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
+    // expected cases, we cannot statically guarantee that no other errors will be returned
+    // in the future.
+    // TODO #3851: Remove this variant once we can exhaustively match all the underlying errors
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for ClearInvalidBlocksError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
+impl Message for ClearInvalidBlocks {
+    type Result = Result<Vec<InvalidBlockView>, ClearInvalidBlocksError>;
+}
+
 #[derive(Debug)]
 pub struct GetProtocolConfig(pub BlockReference);
 
@@ -1002,6 +1146,44 @@ impl From<std::io::Error> for GetSplitStorageInfoError {
     }
 }
 
+/// Validates a challenge against the current head of the chain.
+#[derive(Debug)]
+pub struct GetChallengeStatus {
+    pub challenge: Challenge,
+}
+
+impl Message for GetChallengeStatus {
+    type Result = Result<ChallengeStatusView, GetChallengeStatusError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetChallengeStatusError {
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("Block or epoch referenced by the challenge is unknown: {0}")]
+    UnknownBlock(String),
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for GetChallengeStatusError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
+/// Adds a challenge submitted externally, e.g. through the JSON-RPC, to the pool
+/// of challenges and broadcasts it to peers.
+#[derive(Debug)]
+pub struct BroadcastChallenge(pub Challenge);
+
+impl Message for BroadcastChallenge {
+    type Result = ();
+}
+
 #[cfg(feature = "sandbox")]
 #[derive(Debug)]
 pub enum SandboxMessage {
@@ -15,6 +15,8 @@ pub fn client_sender_for_network(
         block_headers: client_addr.clone().into_sender(),
         block_approval: client_addr.clone().into_sender(),
         block_headers_request: view_client_addr.clone().into_sender(),
+        block_headers_range: client_addr.clone().into_sender(),
+        block_headers_range_request: view_client_addr.clone().into_sender(),
         block_request: view_client_addr.clone().into_sender(),
         challenge: client_addr.clone().into_sender(),
         network_info: client_addr.clone().into_sender(),
@@ -0,0 +1,146 @@
+//! Warms the storage caches with the state read by the transactions of a chunk.
+//!
+//! Converting a transaction to a receipt reads the account and the access key
+//! of its signer. When the shard isn't loaded in memory, these reads often hit
+//! cold storage, and applying the chunk stalls on them. Once a chunk is
+//! received, its transactions are known long before the block including it is
+//! applied, so the reads are done ahead of time in the background, against the
+//! state the chunk will be applied on. The reads populate the shard cache and
+//! the cache of the database, where applying the chunk will find them.
+
+use near_async::futures::{AsyncComputationSpawner, AsyncComputationSpawnerExt};
+use near_chain::types::RuntimeAdapter;
+use near_crypto::PublicKey;
+use near_primitives::errors::StorageError;
+use near_primitives::sharding::ShardChunk;
+use near_primitives::types::AccountId;
+use near_store::{get_access_key, get_account, Trie};
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::metrics;
+
+pub(crate) struct ChunkStatePrefetcher {
+    runtime_adapter: Arc<dyn RuntimeAdapter>,
+    spawner: Arc<dyn AsyncComputationSpawner>,
+}
+
+impl ChunkStatePrefetcher {
+    pub fn new(
+        runtime_adapter: Arc<dyn RuntimeAdapter>,
+        spawner: Arc<dyn AsyncComputationSpawner>,
+    ) -> Self {
+        Self { runtime_adapter, spawner }
+    }
+
+    /// Starts reading, in the background, the accounts and access keys of the
+    /// signers of the transactions of `chunk`.
+    pub fn prefetch(&self, chunk: &ShardChunk) {
+        if chunk.transactions().is_empty() {
+            return;
+        }
+        let shard_id = chunk.shard_id();
+        let prev_block_hash = *chunk.prev_block();
+        // The chunk is applied on the state its header commits to.
+        let trie = match self.runtime_adapter.get_trie_for_shard(
+            shard_id,
+            &prev_block_hash,
+            chunk.prev_state_root(),
+            true,
+        ) {
+            Ok(trie) => trie,
+            Err(err) => {
+                debug!(target: "client", ?shard_id, ?prev_block_hash, ?err, "Cannot prefetch chunk state");
+                return;
+            }
+        };
+        // The reads would not touch storage.
+        if trie.has_memtries() {
+            return;
+        }
+        let signers = chunk
+            .transactions()
+            .iter()
+            .map(|tx| (tx.transaction.signer_id().clone(), tx.transaction.public_key().clone()))
+            .collect::<Vec<_>>();
+        self.spawner.spawn("prefetch_chunk_state", move || {
+            // Errors surface again, and are handled, when applying the chunk.
+            if let Err(err) = prefetch_signers(&trie, &signers) {
+                debug!(target: "client", ?shard_id, ?err, "Failed to prefetch chunk state");
+            }
+        });
+    }
+}
+
+/// Reads the accounts and access keys of `signers` through `trie`, which keeps
+/// the trie nodes on the way in its shard cache.
+fn prefetch_signers(trie: &Trie, signers: &[(AccountId, PublicKey)]) -> Result<(), StorageError> {
+    for (account_id, public_key) in signers {
+        get_account(trie, account_id)?;
+        get_access_key(trie, account_id, public_key)?;
+        metrics::CHUNK_STATE_PREFETCHED_SIGNERS_TOTAL.inc();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{KeyType, SecretKey};
+    use near_primitives::account::{AccessKey, Account};
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::shard_layout::ShardUId;
+    use near_primitives::trie_key::TrieKey;
+    use near_primitives::version::PROTOCOL_VERSION;
+    use near_store::adapter::StoreAdapter;
+    use near_store::test_utils::{test_populate_trie, TestTriesBuilder};
+    use near_store::DBCol;
+
+    #[test]
+    fn test_prefetch_warms_shard_cache() {
+        let account_id: AccountId = "alice.near".parse().unwrap();
+        let public_key = SecretKey::from_seed(KeyType::ED25519, "alice.near").public_key();
+        let account = Account::new(1, 0, 0, CryptoHash::default(), 0, PROTOCOL_VERSION);
+        let tries = TestTriesBuilder::new().build();
+        let shard_uid = ShardUId::single_shard();
+        let state_root = test_populate_trie(
+            &tries,
+            &Trie::EMPTY_ROOT,
+            shard_uid,
+            vec![
+                (
+                    TrieKey::Account { account_id: account_id.clone() }.to_vec(),
+                    Some(borsh::to_vec(&account).unwrap()),
+                ),
+                (
+                    TrieKey::AccessKey {
+                        account_id: account_id.clone(),
+                        public_key: public_key.clone(),
+                    }
+                    .to_vec(),
+                    Some(borsh::to_vec(&AccessKey::full_access()).unwrap()),
+                ),
+            ],
+        );
+
+        // Tries on the same store, with empty caches.
+        let store = tries.store().store();
+        let tries = TestTriesBuilder::new().with_store(store.clone()).build();
+        prefetch_signers(
+            &tries.get_trie_for_shard(shard_uid, state_root),
+            &[(account_id.clone(), public_key.clone())],
+        )
+        .unwrap();
+
+        // With the state gone from storage, the reads are served from the shard cache.
+        let mut store_update = store.store_update();
+        store_update.delete_all(DBCol::State);
+        store_update.commit().unwrap();
+        let trie = tries.get_trie_for_shard(shard_uid, state_root);
+        assert_eq!(get_account(&trie, &account_id).unwrap(), Some(account));
+        assert_eq!(
+            get_access_key(&trie, &account_id, &public_key).unwrap(),
+            Some(AccessKey::full_access())
+        );
+    }
+}
@@ -3,11 +3,15 @@
 
 use crate::chunk_distribution_network::{ChunkDistributionClient, ChunkDistributionNetwork};
 use crate::chunk_inclusion_tracker::ChunkInclusionTracker;
+use crate::chunk_state_prefetcher::ChunkStatePrefetcher;
 use crate::debug::BlockProductionTracker;
 use crate::debug::PRODUCTION_TIMES_CACHE_SIZE;
+use crate::execution_artifacts::create_execution_artifact_sink;
+use crate::future_blocks::FutureBlockPool;
 use crate::stateless_validation::chunk_endorsement::ChunkEndorsementTracker;
 use crate::stateless_validation::chunk_validator::ChunkValidator;
 use crate::stateless_validation::partial_witness::partial_witness_actor::PartialWitnessSenderForClient;
+use crate::stateless_validation::shadow_validate::ShadowValidationWebhook;
 use crate::sync::block::BlockSync;
 use crate::sync::header::HeaderSync;
 use crate::sync::state::{StateSync, StateSyncResult};
@@ -21,26 +25,37 @@ use near_chain::chain::{
     ApplyChunksDoneMessage, BlockCatchUpRequest, BlockMissingChunks, BlocksCatchUpState,
     VerifyBlockHashAndSignatureResult,
 };
+use near_chain::optimistic_block::ProductionParent;
 use near_chain::orphan::OrphanMissingChunks;
 use near_chain::state_snapshot_actor::SnapshotCallbacks;
+use near_chain::stateless_validation::chunk_validation::reexecute_challenged_state_witness;
 use near_chain::test_utils::format_hash;
 use near_chain::types::PrepareTransactionsChunkContext;
 use near_chain::types::{
     ChainConfig, LatestKnown, PreparedTransactions, RuntimeAdapter, RuntimeStorageConfig,
     StorageDataSource,
 };
+use near_chain::validate::{
+    challenge_target, validate_challenge, validate_challenge_body, validate_challenge_signature,
+    validate_challenge_target,
+};
 use near_chain::{
     BlockProcessingArtifact, BlockStatus, Chain, ChainGenesis, ChainStoreAccess, Doomslug,
     DoomslugThresholdMode, Provenance,
 };
-use near_chain_configs::{ClientConfig, MutableValidatorSigner, UpdateableClientConfig};
+use near_chain_configs::{
+    ClientConfig, FutureBlockPolicy, MutableValidatorSigner, UpdateableClientConfig,
+};
 use near_chunks::adapter::ShardsManagerRequestFromClient;
 use near_chunks::client::ShardedTransactionPool;
 use near_chunks::logic::{
     cares_about_shard_this_or_next_epoch, decode_encoded_chunk, persist_chunk,
 };
 use near_chunks::shards_manager_actor::ShardsManagerActor;
-use near_client_primitives::debug::ChunkProduction;
+use near_client_primitives::debug::{
+    ChallengeCheckResult, ChallengeDebugInfo, ChallengeValidationReport, ChallengeVerdict,
+    ChunkProduction,
+};
 use near_client_primitives::types::{Error, StateSyncStatus};
 use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::EpochManagerAdapter;
@@ -51,13 +66,17 @@ use near_network::types::{
 };
 
 use near_pool::InsertTransactionResult;
+use near_primitives::bandwidth_scheduler::OutgoingBandwidth;
 use near_primitives::block::{Approval, ApprovalInner, ApprovalMessage, Block, BlockHeader, Tip};
 use near_primitives::block_header::ApprovalType;
-use near_primitives::challenge::{Challenge, ChallengeBody, PartialState};
+use near_primitives::challenge::{
+    Challenge, ChallengeBody, PartialState, SlashedValidator, SlashingSeverity, StoredChallenge,
+    CHALLENGE_SLASHING_WINDOW_EPOCHS,
+};
 use near_primitives::epoch_info::RngSeed;
 use near_primitives::errors::EpochError;
 use near_primitives::hash::CryptoHash;
-use near_primitives::merkle::{merklize, MerklePath, PartialMerkleTree};
+use near_primitives::merkle::{merklize, MerklePath};
 use near_primitives::network::PeerId;
 use near_primitives::receipt::Receipt;
 use near_primitives::sharding::{
@@ -77,7 +96,7 @@ use near_primitives::views::{CatchupStatusView, DroppedReason};
 use near_store::ShardUId;
 use reed_solomon_erasure::galois_8::ReedSolomon;
 use std::cmp::max;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use time::ext::InstantExt as _;
@@ -94,6 +113,13 @@ const NUM_REBROADCAST_BLOCKS: usize = 30;
 /// Drop blocks whose height are beyond head + horizon if it is not in the current epoch.
 const BLOCK_HORIZON: u64 = 500;
 
+/// Number of recently seen challenges remembered to avoid processing and
+/// gossiping the same challenge again.
+const NUM_SEEN_CHALLENGES: usize = 1000;
+
+/// Number of recently processed challenges shown on the debug page.
+const NUM_RECENT_CHALLENGES: usize = 100;
+
 /// number of blocks at the epoch start for which we will log more detailed info
 pub const EPOCH_START_INFO_BLOCKS: u64 = 500;
 
@@ -117,6 +143,11 @@ pub struct CatchupState {
     pub catchup: BlocksCatchUpState,
 }
 
+/// Block produced on top of a block whose chunks were still being applied.
+struct OptimisticBlock {
+    prev_hash: CryptoHash,
+}
+
 pub struct Client {
     /// Adversarial controls - should be enabled only to test disruptive
     /// behaviour on chain.
@@ -166,7 +197,11 @@ pub struct Client {
     state_sync_future_spawner: Arc<dyn FutureSpawner>,
     chain_sender_for_state_sync: ChainSenderForStateSync,
     /// List of currently accumulated challenges.
-    pub challenges: HashMap<CryptoHash, Challenge>,
+    pub challenges: HashMap<CryptoHash, StoredChallenge>,
+    /// Challenges that have been received recently. They should not be processed again.
+    seen_challenges: lru::LruCache<CryptoHash, ()>,
+    /// Outcomes of recently processed challenges, most recent first. Used only for debug purposes.
+    pub recent_challenges: VecDeque<ChallengeDebugInfo>,
     /// A ReedSolomon instance to reconstruct shard.
     pub rs_for_chunk_production: ReedSolomon,
     /// Blocks that have been re-broadcast recently. They should not be broadcast again.
@@ -200,6 +235,15 @@ pub struct Client {
     chunk_distribution_network: Option<ChunkDistributionNetwork>,
     /// Upgrade schedule which determines when the client starts voting for new protocol versions.
     upgrade_schedule: ProtocolUpgradeVotingSchedule,
+    /// Last block produced optimistically whose previous block is still being applied.
+    optimistic_block: Option<OptimisticBlock>,
+    /// Blocks from the future held until the clock of the node catches up with them.
+    future_blocks: FutureBlockPool,
+    /// Receives the chunks which failed shadow validation, if configured.
+    shadow_validation_webhook: Option<Arc<ShadowValidationWebhook>>,
+    /// Reads the state used by the transactions of the received chunks ahead
+    /// of applying them, if enabled.
+    chunk_state_prefetcher: Option<ChunkStatePrefetcher>,
 }
 
 impl AsRef<Client> for Client {
@@ -266,9 +310,13 @@ impl Client {
         let chain_config = ChainConfig {
             save_trie_changes: config.save_trie_changes,
             background_migration_threads: config.client_background_migration_threads,
+            apply_chunks_threads: config.apply_chunks_threads,
+            catchup: config.catchup.clone(),
+            max_block_clock_skew: config.block_timestamp.max_clock_skew,
+            shadow_validation: config.shadow_validation.enabled,
             resharding_config: config.resharding_config.clone(),
         };
-        let chain = Chain::new(
+        let mut chain = Chain::new(
             clock.clone(),
             epoch_manager.clone(),
             shard_tracker.clone(),
@@ -282,6 +330,12 @@ impl Client {
             resharding_sender.clone(),
         )?;
         chain.init_flat_storage()?;
+        if let Some(artifacts_config) = &config.execution_artifacts {
+            let sink = create_execution_artifact_sink(artifacts_config).map_err(|err| {
+                Error::Other(format!("failed to create execution artifact sink: {err}"))
+            })?;
+            chain.set_execution_artifact_sink(sink, artifacts_config.queue_size)?;
+        }
         let sharded_tx_pool =
             ShardedTransactionPool::new(rng_seed, config.transaction_pool_size_limit);
         let sync_status = SyncStatus::AwaitingPeers;
@@ -301,6 +355,7 @@ impl Client {
             config.header_sync_stall_ban_timeout,
             config.header_sync_expected_height_per_second,
             config.expected_shutdown.clone(),
+            config.header_sync_max_prefetched_ranges,
         );
         let block_sync = BlockSync::new(
             clock.clone(),
@@ -335,7 +390,7 @@ impl Client {
             chain.chain_store().largest_target_height()?,
             config.min_block_production_delay,
             config.max_block_production_delay,
-            config.max_block_production_delay / 10,
+            config.doomslug_delay_step,
             config.max_block_wait_delay,
             doomslug_threshold_mode,
         );
@@ -343,6 +398,16 @@ impl Client {
             epoch_manager.clone(),
             chain.chain_store().store().clone(),
         );
+        // Restore challenges which were pending before the node was restarted.
+        let challenges = chain
+            .chain_store()
+            .iterate_challenges()?
+            .into_iter()
+            .map(|stored_challenge| (stored_challenge.challenge.hash, stored_challenge))
+            .collect();
+        let chunk_state_prefetcher = config.prefetch_chunk_state.then(|| {
+            ChunkStatePrefetcher::new(runtime_adapter.clone(), async_computation_spawner.clone())
+        });
         let chunk_validator = ChunkValidator::new(
             epoch_manager.clone(),
             network_adapter.clone().into_sender(),
@@ -351,6 +416,8 @@ impl Client {
             async_computation_spawner,
         );
         let chunk_distribution_network = ChunkDistributionNetwork::from_config(&config);
+        let shadow_validation_webhook =
+            ShadowValidationWebhook::from_config(&config.shadow_validation);
         Ok(Self {
             #[cfg(feature = "test_features")]
             adv_produce_blocks: None,
@@ -384,7 +451,9 @@ impl Client {
             state_sync,
             state_sync_future_spawner,
             chain_sender_for_state_sync,
-            challenges: Default::default(),
+            challenges,
+            seen_challenges: lru::LruCache::new(NonZeroUsize::new(NUM_SEEN_CHALLENGES).unwrap()),
+            recent_challenges: VecDeque::with_capacity(NUM_RECENT_CHALLENGES),
             rs_for_chunk_production: ReedSolomon::new(data_parts, parity_parts).unwrap(),
             rebroadcasted_blocks: lru::LruCache::new(
                 NonZeroUsize::new(NUM_REBROADCAST_BLOCKS).unwrap(),
@@ -403,6 +472,10 @@ impl Client {
             partial_witness_adapter,
             chunk_distribution_network,
             upgrade_schedule,
+            optimistic_block: None,
+            future_blocks: FutureBlockPool::new(),
+            shadow_validation_webhook,
+            chunk_state_prefetcher,
         })
     }
 
@@ -448,7 +521,7 @@ impl Client {
             }
         }
         for challenge in block.challenges().iter() {
-            self.challenges.remove(&challenge.hash);
+            self.remove_challenge(&challenge.hash);
         }
         Ok(())
     }
@@ -489,11 +562,42 @@ impl Client {
             }
         }
         for challenge in block.challenges().iter() {
-            self.challenges.insert(challenge.hash, challenge.clone());
+            self.add_challenge(challenge.clone());
         }
         Ok(())
     }
 
+    /// Adds challenge to the pool of accumulated challenges and persists it in
+    /// `DBCol::Challenges`, so that it is not lost on restart.
+    fn add_challenge(&mut self, challenge: Challenge) {
+        let received_height = match self.chain.head() {
+            Ok(head) => head.height,
+            Err(err) => {
+                error!(target: "client", ?err, "Error saving challenge");
+                return;
+            }
+        };
+        let stored_challenge = StoredChallenge { received_height, challenge };
+        let mut update = self.chain.mut_chain_store().store_update();
+        update.add_challenge(stored_challenge.clone());
+        if let Err(err) = update.commit() {
+            error!(target: "client", ?err, "Error saving challenge");
+        }
+        self.challenges.insert(stored_challenge.challenge.hash, stored_challenge);
+    }
+
+    /// Removes challenge from the pool of accumulated challenges and from the store.
+    fn remove_challenge(&mut self, hash: &CryptoHash) {
+        let Some(stored_challenge) = self.challenges.remove(hash) else {
+            return;
+        };
+        let mut update = self.chain.mut_chain_store().store_update();
+        update.remove_challenge(stored_challenge.received_height, *hash);
+        if let Err(err) = update.commit() {
+            error!(target: "client", ?err, "Error removing challenge");
+        }
+    }
+
     /// Checks couple conditions whether Client can produce new block on height
     /// `height` on top of block with `prev_header`.
     /// Needed to skip several checks in case of adversarial controls enabled.
@@ -585,16 +689,72 @@ impl Client {
         &mut self,
         height: BlockHeight,
         prev_hash: CryptoHash,
+    ) -> Result<Option<Block>, Error> {
+        let parent = self.chain.production_parent(&prev_hash)?;
+        self.produce_block_on_parent(height, parent)
+    }
+
+    /// Prepares the production of a block on top of `prev_hash`, which is
+    /// still being applied, see `Chain::prepare_optimistic_production`. Once
+    /// the epoch information of `prev_hash` is known, the approvals and chunks
+    /// built on top of it which were waiting for it can be processed.
+    pub fn prepare_optimistic_block_production(
+        &mut self,
+        prev_hash: &CryptoHash,
+        signer: &Option<Arc<ValidatorSigner>>,
+    ) -> Result<bool, Error> {
+        let already_prepared = self.epoch_manager.get_block_info(prev_hash).is_ok();
+        if !self.chain.prepare_optimistic_production(prev_hash)? {
+            return Ok(false);
+        }
+        if already_prepared {
+            return Ok(true);
+        }
+        let endorsements =
+            self.pending_approvals.pop(&ApprovalInner::Endorsement(*prev_hash)).unwrap_or_default();
+        for (_account_id, (approval, approval_type)) in endorsements {
+            self.collect_block_approval(&approval, approval_type, signer);
+        }
+        self.shards_manager_adapter
+            .send(ShardsManagerRequestFromClient::CheckIncompleteChunks(*prev_hash));
+        Ok(true)
+    }
+
+    /// Produce block for given `height` on top of block `prev_hash` while the
+    /// chunks of `prev_hash` are still being applied. Returns `None` if the
+    /// block can't be produced optimistically, see `Chain::optimistic_production_parent`.
+    pub fn produce_optimistic_block_on(
+        &mut self,
+        height: BlockHeight,
+        prev_hash: CryptoHash,
+    ) -> Result<Option<Block>, Error> {
+        let Some(parent) = self.chain.optimistic_production_parent(&prev_hash)? else {
+            return Ok(None);
+        };
+        let Some(block) = self.produce_block_on_parent(height, parent)? else {
+            return Ok(None);
+        };
+        debug!(target: "client", height, ?prev_hash, block_hash = ?block.hash(), "Produced optimistic block");
+        self.optimistic_block = Some(OptimisticBlock { prev_hash });
+        metrics::OPTIMISTIC_BLOCKS_PRODUCED_TOTAL.inc();
+        Ok(Some(block))
+    }
+
+    fn produce_block_on_parent(
+        &mut self,
+        height: BlockHeight,
+        parent: ProductionParent,
     ) -> Result<Option<Block>, Error> {
         let validator_signer = self.validator_signer.get().ok_or_else(|| {
             Error::BlockProducer("Called without block producer info.".to_string())
         })?;
+        let prev_hash = *parent.block.hash();
 
         // Check that we are were called at the block that we are producer for.
         let epoch_id = self.epoch_manager.get_epoch_id_from_prev_block(&prev_hash).unwrap();
         let next_block_proposer = self.epoch_manager.get_block_producer(&epoch_id, height)?;
 
-        let prev = self.chain.get_block_header(&prev_hash)?;
+        let prev = parent.block.header();
         let prev_height = prev.height();
         let prev_epoch_id = *prev.epoch_id();
         let prev_next_bp_hash = *prev.next_bp_hash();
@@ -604,7 +764,7 @@ impl Client {
         let _ = self.check_and_update_doomslug_tip()?;
 
         if !self.can_produce_block(
-            &prev,
+            prev,
             height,
             validator_signer.validator_id(),
             &next_block_proposer,
@@ -710,18 +870,14 @@ impl Client {
         #[cfg(not(feature = "sandbox"))]
         let sandbox_delta_time = None;
 
-        // Get block extra from previous block.
-        let block_merkle_tree = self.chain.chain_store().get_block_merkle_tree(&prev_hash)?;
-        let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
+        let mut block_merkle_tree = parent.block_merkle_tree.clone();
         block_merkle_tree.insert(prev_hash);
         let block_merkle_root = block_merkle_tree.root();
         // The number of leaves in Block Merkle Tree is the amount of Blocks on the Canonical Chain by construction.
         // The ordinal of the next Block will be equal to this amount plus one.
         let block_ordinal: NumBlocks = block_merkle_tree.size() + 1;
-        let prev_block_extra = self.chain.get_block_extra(&prev_hash)?;
-        let prev_block = self.chain.get_block(&prev_hash)?;
         let mut chunk_headers =
-            Chain::get_prev_chunk_headers(self.epoch_manager.as_ref(), &prev_block)?;
+            Chain::get_prev_chunk_headers(self.epoch_manager.as_ref(), &parent.block)?;
         let mut chunk_endorsements = vec![vec![]; chunk_headers.len()];
 
         // Add debug information about the block production (and info on when did the chunks arrive).
@@ -753,8 +909,6 @@ impl Client {
                 .ok_or(near_chain_primitives::Error::InvalidShardId(shard_id))? = chunk_endorsement;
         }
 
-        let prev_header = &prev_block.header();
-
         let next_epoch_id = self.epoch_manager.get_next_epoch_id_from_prev_block(&prev_hash)?;
 
         let minted_amount = if self.epoch_manager.is_next_block_epoch_start(&prev_hash)? {
@@ -764,7 +918,7 @@ impl Client {
         };
 
         let epoch_sync_data_hash = if self.epoch_manager.is_next_block_epoch_start(&prev_hash)? {
-            let last_block_info = self.epoch_manager.get_block_info(prev_block.hash())?;
+            let last_block_info = self.epoch_manager.get_block_info(&prev_hash)?;
             let prev_epoch_id = *last_block_info.epoch_id();
             let prev_epoch_first_block_info =
                 self.epoch_manager.get_block_info(last_block_info.epoch_first_block())?;
@@ -787,18 +941,24 @@ impl Client {
 
         // Get all the current challenges.
         // TODO(2445): Enable challenges when they are working correctly.
-        // let challenges = self.challenges.drain().map(|(_, challenge)| challenge).collect();
+        // let challenges = self.challenges.drain().map(|(_, stored)| stored.challenge).collect();
         let this_epoch_protocol_version =
             self.epoch_manager.get_epoch_protocol_version(&epoch_id)?;
         let next_epoch_protocol_version =
             self.epoch_manager.get_epoch_protocol_version(&next_epoch_id)?;
+        let mut protocol_version_to_vote_for = self
+            .upgrade_schedule
+            .protocol_version_to_vote_for(self.clock.now_utc(), next_epoch_protocol_version);
+        if let Some(scheduled_protocol_version) = self.chain.scheduled_protocol_version(height) {
+            protocol_version_to_vote_for =
+                protocol_version_to_vote_for.max(scheduled_protocol_version);
+        }
 
         let block = Block::produce(
             this_epoch_protocol_version,
             next_epoch_protocol_version,
-            self.upgrade_schedule
-                .protocol_version_to_vote_for(self.clock.now_utc(), next_epoch_protocol_version),
-            prev_header,
+            protocol_version_to_vote_for,
+            prev,
             height,
             block_ordinal,
             chunk_headers,
@@ -811,7 +971,7 @@ impl Client {
             min_gas_price,
             max_gas_price,
             minted_amount,
-            prev_block_extra.challenges_result.clone(),
+            parent.challenges_result,
             vec![],
             &*validator_signer,
             next_bp_hash,
@@ -933,6 +1093,17 @@ impl Client {
 
         let outgoing_receipts_root = self.calculate_receipts_root(epoch_id, &outgoing_receipts)?;
         let protocol_version = self.epoch_manager.get_epoch_protocol_version(epoch_id)?;
+        let prev_outgoing_bandwidth =
+            if ProtocolFeature::ChunkBandwidthCommitment.enabled(protocol_version) {
+                let shard_layout = self.epoch_manager.get_shard_layout(epoch_id)?;
+                Some(OutgoingBandwidth::from_receipts(&outgoing_receipts, &shard_layout))
+            } else {
+                None
+            };
+        // The previous chunk was applied to the state root committed to in its header.
+        let prev_pre_state_root = ProtocolFeature::ExecutionProofs
+            .enabled(protocol_version)
+            .then(|| last_header.prev_state_root());
         let gas_used = chunk_extra.gas_used();
         #[cfg(feature = "test_features")]
         let gas_used = if self.produce_invalid_chunks { gas_used + 1 } else { gas_used };
@@ -954,6 +1125,8 @@ impl Client {
             tx_root,
             congestion_info,
             chunk_extra.bandwidth_requests().cloned(),
+            prev_outgoing_bandwidth,
+            prev_pre_state_root,
             &*validator_signer,
             &mut self.rs_for_chunk_production,
             protocol_version,
@@ -1104,8 +1277,14 @@ impl Client {
     ) {
         if let Some(validator_signer) = &signer {
             for body in challenges {
-                let challenge = Challenge::produce(body, &**validator_signer);
-                self.challenges.insert(challenge.hash, challenge.clone());
+                let challenge = match self.produce_challenge(body, validator_signer) {
+                    Ok(challenge) => challenge,
+                    Err(err) => {
+                        error!(target: "client", ?err, "Failed to produce challenge");
+                        continue;
+                    }
+                };
+                self.add_challenge(challenge.clone());
                 self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
                     NetworkRequests::Challenge(challenge),
                 ));
@@ -1113,6 +1292,151 @@ impl Client {
         }
     }
 
+    /// Signs the challenge body, targeting the epoch and height of the challenged
+    /// block or chunk.
+    pub fn produce_challenge(
+        &self,
+        body: ChallengeBody,
+        signer: &ValidatorSigner,
+    ) -> Result<Challenge, Error> {
+        let (target_epoch_id, target_height) =
+            challenge_target(self.epoch_manager.as_ref(), &body)?;
+        Ok(Challenge::produce(body, target_epoch_id, target_height, signer))
+    }
+
+    /// Whether challenges are processed and relayed at the current head, see
+    /// `ProtocolFeature::ChallengeTargetEpoch`.
+    fn challenges_enabled(&self) -> bool {
+        self.chain
+            .head()
+            .and_then(|head| Ok(self.epoch_manager.get_epoch_protocol_version(&head.epoch_id)?))
+            .is_ok_and(|protocol_version| {
+                ProtocolFeature::ChallengeTargetEpoch.enabled(protocol_version)
+            })
+    }
+
+    /// Returns whether the challenge targets an epoch within the slashing window
+    /// relative to the current head.
+    pub fn is_challenge_within_slashing_window(
+        &self,
+        challenge: &Challenge,
+    ) -> Result<bool, Error> {
+        let head = self.chain.head()?;
+        let head_epoch_height = self.epoch_manager.get_epoch_info(&head.epoch_id)?.epoch_height();
+        let target_epoch_height =
+            match self.epoch_manager.get_epoch_info(&challenge.target_epoch_id) {
+                Ok(epoch_info) => epoch_info.epoch_height(),
+                // Unknown epoch is either in the future or garbage collected.
+                Err(EpochError::EpochOutOfBounds(_)) => return Ok(false),
+                Err(err) => return Err(err.into()),
+            };
+        // Chunks produced on top of the head may already belong to the next epoch.
+        Ok(target_epoch_height <= head_epoch_height + 1
+            && head_epoch_height.saturating_sub(target_epoch_height)
+                <= CHALLENGE_SLASHING_WINDOW_EPOCHS)
+    }
+
+    /// Adds an externally submitted challenge, which is expected to be already
+    /// validated, including the re-execution of a challenged state witness, to
+    /// the pool of challenges and broadcasts it to peers.
+    pub fn broadcast_challenge(&mut self, challenge: Challenge) {
+        if self.challenges.contains_key(&challenge.hash) || !self.challenges_enabled() {
+            return;
+        }
+        debug!(target: "client", challenge_hash = ?challenge.hash, "Broadcasting challenge");
+        self.add_challenge(challenge.clone());
+        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::Challenge(challenge),
+        ));
+    }
+
+    /// Fully validates the challenge against the current chain head without
+    /// adding it to the pool or broadcasting it. Checks are performed in
+    /// order, once one of them fails the remaining ones are skipped.
+    pub fn validate_challenge(&self, challenge: &Challenge) -> ChallengeValidationReport {
+        let mut report = ChallengeValidationReport {
+            challenge_hash: challenge.hash,
+            signature: ChallengeCheckResult::Skipped,
+            proofs: ChallengeCheckResult::Skipped,
+            state_reexecution: ChallengeCheckResult::Skipped,
+            slashed_validators: vec![],
+        };
+        let epoch_manager = self.epoch_manager.as_ref();
+        let runtime_adapter = self.runtime_adapter.as_ref();
+        // Malicious challenge slashes its signers instead of the challenged validators.
+        let failed = |report: &mut ChallengeValidationReport, err: near_chain_primitives::Error| {
+            if let near_chain_primitives::Error::MaliciousChallenge = err {
+                report.slashed_validators = challenge
+                    .signers()
+                    .map(|account_id| {
+                        SlashedValidator::new(account_id.clone(), SlashingSeverity::Other)
+                    })
+                    .collect();
+            }
+            ChallengeCheckResult::Failed { error: err.to_string() }
+        };
+
+        let signature_result = self.chain.head().and_then(|head| {
+            validate_challenge_signature(
+                epoch_manager,
+                &head.epoch_id,
+                &head.last_block_hash,
+                challenge,
+            )
+        });
+        if let Err(err) = signature_result {
+            report.signature = failed(&mut report, err);
+            return report;
+        }
+        report.signature = ChallengeCheckResult::Passed;
+
+        let body_result = validate_challenge_target(epoch_manager, challenge).and_then(|()| {
+            validate_challenge_body(epoch_manager, runtime_adapter, &challenge.body)
+        });
+        let account_ids = match body_result {
+            Ok((_, account_ids)) => account_ids,
+            Err(err) => {
+                report.proofs = failed(&mut report, err);
+                return report;
+            }
+        };
+        report.proofs = ChallengeCheckResult::Passed;
+
+        if let ChallengeBody::InvalidStateWitness(invalid_state_witness) = &challenge.body {
+            if let Err(err) = reexecute_challenged_state_witness(
+                invalid_state_witness,
+                &self.chain,
+                epoch_manager,
+                runtime_adapter,
+            ) {
+                report.state_reexecution = failed(&mut report, err);
+                return report;
+            }
+            report.state_reexecution = ChallengeCheckResult::Passed;
+        }
+
+        let protocol_version = match self
+            .chain
+            .head()
+            .and_then(|head| Ok(epoch_manager.get_epoch_protocol_version(&head.epoch_id)?))
+        {
+            Ok(protocol_version) => protocol_version,
+            Err(err) => {
+                report.state_reexecution = failed(&mut report, err);
+                return report;
+            }
+        };
+        let severity = challenge.body.slashing_severity(protocol_version);
+        report.slashed_validators = account_ids
+            .into_iter()
+            .map(|id| {
+                SlashedValidator::new(id, severity)
+                    .with_challenger(challenge.account_id.clone(), protocol_version)
+            })
+            .collect();
+        report
+    }
+
     /// Processes received block. Ban peer if the block header is invalid or the block is ill-formed.
     // This function is just a wrapper for process_block_impl that makes error propagation easier.
     pub fn receive_block(
@@ -1201,6 +1525,13 @@ impl Client {
             return Err(near_chain::Error::InvalidSignature);
         }
 
+        if !was_requested {
+            self.observe_block_timestamp(block.header());
+        }
+        if self.hold_future_block(&block, &peer_id, was_requested) {
+            return Ok(());
+        }
+
         let prev_hash = *block.header().prev_hash();
         let block = block.into();
         self.verify_and_rebroadcast_block(&block, was_requested, &peer_id)?;
@@ -1210,8 +1541,8 @@ impl Client {
         match &res {
             Err(near_chain::Error::Orphan) => {
                 debug!(target: "chain", ?prev_hash, "Orphan error");
-                if !self.chain.is_orphan(&prev_hash) {
-                    debug!(target: "chain", "not orphan");
+                if self.chain.should_request_orphan_parent(prev_hash) {
+                    debug!(target: "chain", "request orphan parent");
                     self.request_block(prev_hash, peer_id)
                 }
             }
@@ -1222,6 +1553,73 @@ impl Client {
         res
     }
 
+    /// Records the difference between the timestamp of a block just produced and the clock of
+    /// the node, which includes the time the block took to reach the node.
+    fn observe_block_timestamp(&self, header: &BlockHeader) {
+        let skew = (header.timestamp() - self.clock.now_utc()).as_seconds_f64();
+        metrics::BLOCK_TIMESTAMP_SKEW.observe(skew);
+        if let Ok(block_producer) =
+            self.epoch_manager.get_block_producer(header.epoch_id(), header.height())
+        {
+            metrics::BLOCK_PRODUCER_TIMESTAMP_SKEW
+                .with_label_values(&[block_producer.as_str()])
+                .set(skew);
+        }
+    }
+
+    /// Holds the block to be processed later if its timestamp is too far in the future and
+    /// the policy for such blocks is `FutureBlockPolicy::Delay`. Returns whether the block
+    /// is held.
+    fn hold_future_block(&mut self, block: &Block, peer_id: &PeerId, was_requested: bool) -> bool {
+        let config = &self.config.block_timestamp;
+        if config.future_block_policy != FutureBlockPolicy::Delay {
+            return false;
+        }
+        let now = self.clock.now_utc();
+        let ready_at = block.header().timestamp() - config.max_clock_skew;
+        if ready_at <= now || ready_at > now + config.max_future_block_delay {
+            return false;
+        }
+        if !self.future_blocks.add(block.clone(), peer_id.clone(), was_requested, ready_at) {
+            return false;
+        }
+        debug!(
+            target: "client",
+            block_hash = ?block.hash(),
+            timestamp = ?block.header().timestamp(),
+            delay = ?(ready_at - now),
+            "Holding block from the future");
+        metrics::FUTURE_BLOCKS_TOTAL.with_label_values(&["delayed"]).inc();
+        metrics::NUM_FUTURE_BLOCKS.set(self.future_blocks.len() as i64);
+        true
+    }
+
+    /// Processes the blocks from the future whose timestamp is now within the allowed
+    /// clock skew.
+    pub(crate) fn process_ready_future_blocks(
+        &mut self,
+        apply_chunks_done_sender: Option<Sender<ApplyChunksDoneMessage>>,
+        signer: &Option<Arc<ValidatorSigner>>,
+    ) {
+        let ready = self.future_blocks.take_ready(self.clock.now_utc());
+        metrics::NUM_FUTURE_BLOCKS.set(self.future_blocks.len() as i64);
+        for future_block in ready {
+            self.receive_block(
+                future_block.block,
+                future_block.peer_id,
+                future_block.was_requested,
+                apply_chunks_done_sender.clone(),
+                signer,
+            );
+        }
+    }
+
+    /// Returns when the next block from the future held by the client can be
+    /// processed.
+    pub(crate) fn next_future_block_ready_at(&self) -> Option<near_async::time::Utc> {
+        self.future_blocks.next_ready_at()
+    }
+
     /// To protect ourselves from spamming, we do some pre-check on block height before we do any
     /// processing. This function returns true if the block height is valid.
     fn check_block_height(
@@ -1286,7 +1684,9 @@ impl Client {
                 // We don't ban a peer if the block timestamp is too much in the future since it's possible
                 // that a block is considered valid in one machine and invalid in another machine when their
                 // clocks are not synced.
-                if !matches!(e, near_chain::Error::InvalidBlockFutureTime(_)) {
+                if matches!(e, near_chain::Error::InvalidBlockFutureTime(_)) {
+                    metrics::FUTURE_BLOCKS_TOTAL.with_label_values(&["rejected"]).inc();
+                } else {
                     self.ban_peer(peer_id.clone(), ReasonForBan::BadBlockHeader);
                 }
                 Err(e)
@@ -1337,24 +1737,23 @@ impl Client {
         // Send out challenge if the block was found to be invalid.
         if let Some(signer) = signer {
             if let Err(e) = &result {
-                match e {
+                let body = match e {
                     near_chain::Error::InvalidChunkProofs(chunk_proofs) => {
-                        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-                            NetworkRequests::Challenge(Challenge::produce(
-                                ChallengeBody::ChunkProofs(*chunk_proofs.clone()),
-                                &*signer,
-                            )),
-                        ));
+                        Some(ChallengeBody::ChunkProofs(*chunk_proofs.clone()))
                     }
                     near_chain::Error::InvalidChunkState(chunk_state) => {
+                        Some(ChallengeBody::ChunkState(*chunk_state.clone()))
+                    }
+                    _ => None,
+                };
+                match body.map(|body| self.produce_challenge(body, &signer)) {
+                    Some(Ok(challenge)) => {
                         self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-                            NetworkRequests::Challenge(Challenge::produce(
-                                ChallengeBody::ChunkState(*chunk_state.clone()),
-                                &*signer,
-                            )),
+                            NetworkRequests::Challenge(challenge),
                         ));
                     }
-                    _ => {}
+                    Some(Err(err)) => error!(target: "client", ?err, "Failed to produce challenge"),
+                    None => {}
                 }
             }
         }
@@ -1386,8 +1785,9 @@ impl Client {
             });
         }
         self.process_block_processing_artifact(block_processing_artifacts, signer);
-        let accepted_blocks_hashes =
+        let accepted_blocks_hashes: Vec<_> =
             accepted_blocks.iter().map(|accepted_block| accepted_block.hash).collect();
+        self.reconcile_optimistic_block(&accepted_blocks_hashes, &errors);
         for accepted_block in accepted_blocks {
             self.on_block_accepted_with_optional_chunk_produce(
                 accepted_block.hash,
@@ -1402,6 +1802,28 @@ impl Client {
         (accepted_blocks_hashes, errors)
     }
 
+    /// Checks whether the block the last optimistic block was produced on has
+    /// finished applying, and rolls the optimistic block back if it failed.
+    fn reconcile_optimistic_block(
+        &mut self,
+        accepted_blocks: &[CryptoHash],
+        errors: &HashMap<CryptoHash, near_chain::Error>,
+    ) {
+        let Some(optimistic_block) = &self.optimistic_block else {
+            return;
+        };
+        let prev_hash = optimistic_block.prev_hash;
+        if accepted_blocks.contains(&prev_hash) {
+            metrics::OPTIMISTIC_BLOCKS_CONFIRMED_TOTAL.inc();
+            self.optimistic_block = None;
+        } else if let Some(err) = errors.get(&prev_hash) {
+            warn!(target: "client", ?prev_hash, ?err, "Block with an optimistic block on top failed to apply");
+            metrics::OPTIMISTIC_BLOCKS_MISPREDICTED_TOTAL.inc();
+            self.optimistic_block = None;
+            self.chain.rollback_optimistic_block(&prev_hash);
+        }
+    }
+
     /// Process the result of block processing from chain, finish the steps that can't be done
     /// in chain, including
     ///  - sending challenges
@@ -1497,6 +1919,10 @@ impl Client {
             shard_layout.get_shard_index(shard_id).expect("Could not obtain shard index");
         self.block_production_info
             .record_chunk_collected(partial_chunk.height_created(), shard_index);
+        if let (Some(prefetcher), Some(shard_chunk)) = (&self.chunk_state_prefetcher, &shard_chunk)
+        {
+            prefetcher.prefetch(shard_chunk);
+        }
 
         // TODO(#10569) We would like a proper error handling here instead of `expect`.
         persist_chunk(partial_chunk, shard_chunk, self.chain.mut_chain_store())
@@ -2631,30 +3057,122 @@ impl Client {
     }
 
     /// When accepting challenge, we verify that it's valid given signature with current validators.
-    pub fn process_challenge(&mut self, _challenge: Challenge) -> Result<(), Error> {
-        // TODO(2445): Enable challenges when they are working correctly.
-        //        if self.challenges.contains_key(&challenge.hash) {
-        //            return Ok(());
-        //        }
-        //        debug!(target: "client", "Received challenge: {:?}", challenge);
-        //        let head = self.chain.head()?;
-        //        if self.runtime_adapter.verify_validator_or_fisherman_signature(
-        //            &head.epoch_id,
-        //            &head.prev_block_hash,
-        //            &challenge.account_id,
-        //            challenge.hash.as_ref(),
-        //            &challenge.signature,
-        //        )? {
-        //            // If challenge is not double sign, we should process it right away to invalidate the chain.
-        //            match challenge.body {
-        //                ChallengeBody::BlockDoubleSign(_) => {}
-        //                _ => {
-        //                    self.chain.process_challenge(&challenge);
-        //                }
-        //            }
-        //            self.challenges.insert(challenge.hash, challenge);
-        //        }
-        Ok(())
+    /// Processes challenge received from the network. Duplicated challenges and
+    /// challenges targeting epochs outside of the slashing window are dropped,
+    /// valid ones are added to the pool and gossiped further.
+    pub fn process_challenge(&mut self, challenge: Challenge) -> Result<(), Error> {
+        let kind: &'static str = (&challenge.body).into();
+        metrics::CHALLENGES_RECEIVED_TOTAL.with_label_values(&[kind]).inc();
+        if self.seen_challenges.put(challenge.hash, ()).is_some()
+            || self.challenges.contains_key(&challenge.hash)
+        {
+            return Ok(());
+        }
+        debug!(target: "client", challenge_hash = ?challenge.hash, "Received challenge");
+        if !self.challenges_enabled() {
+            debug!(target: "client", challenge_hash = ?challenge.hash, "Challenges are not enabled yet, dropping challenge");
+            return Ok(());
+        }
+        if !self.is_challenge_within_slashing_window(&challenge)? {
+            debug!(
+                target: "client",
+                challenge_hash = ?challenge.hash,
+                target_epoch_id = ?challenge.target_epoch_id,
+                target_height = challenge.target_height,
+                "Dropping challenge outside of the slashing window"
+            );
+            metrics::CHALLENGES_INVALID_TOTAL.with_label_values(&[kind, "expired"]).inc();
+            self.record_challenge(&challenge, ChallengeVerdict::Expired, vec![]);
+            return Ok(());
+        }
+        let head = self.chain.head()?;
+        let validation_result = {
+            let _timer =
+                metrics::CHALLENGE_VERIFICATION_TIME.with_label_values(&[kind]).start_timer();
+            validate_challenge(
+                self.epoch_manager.as_ref(),
+                self.runtime_adapter.as_ref(),
+                &head.epoch_id,
+                &head.last_block_hash,
+                &challenge,
+            )
+            .and_then(|result| {
+                // The witness is re-executed before the challenge is relayed, so that
+                // this node never gossips a challenge which would slash honest validators.
+                if let ChallengeBody::InvalidStateWitness(invalid_state_witness) = &challenge.body {
+                    reexecute_challenged_state_witness(
+                        invalid_state_witness,
+                        &self.chain,
+                        self.epoch_manager.as_ref(),
+                        self.runtime_adapter.as_ref(),
+                    )?;
+                }
+                Ok(result)
+            })
+        };
+        match validation_result {
+            Ok((_, account_ids)) => {
+                metrics::CHALLENGES_VALID_TOTAL.with_label_values(&[kind]).inc();
+                let protocol_version =
+                    self.epoch_manager.get_epoch_protocol_version(&head.epoch_id)?;
+                let severity = challenge.body.slashing_severity(protocol_version);
+                let slashed_validators = account_ids
+                    .into_iter()
+                    .map(|id| {
+                        SlashedValidator::new(id, severity)
+                            .with_challenger(challenge.account_id.clone(), protocol_version)
+                    })
+                    .collect();
+                self.record_challenge(&challenge, ChallengeVerdict::Accepted, slashed_validators);
+                self.broadcast_challenge(challenge);
+                Ok(())
+            }
+            Err(near_chain::Error::IOErr(err)) => Err(near_chain::Error::IOErr(err).into()),
+            Err(err) => {
+                debug!(target: "client", challenge_hash = ?challenge.hash, ?err, "Rejected challenge");
+                let (reason, slashed_validators) = match err {
+                    near_chain::Error::MaliciousChallenge => (
+                        "malicious",
+                        challenge
+                            .signers()
+                            .map(|account_id| {
+                                SlashedValidator::new(account_id.clone(), SlashingSeverity::Other)
+                            })
+                            .collect(),
+                    ),
+                    _ => ("invalid", vec![]),
+                };
+                metrics::CHALLENGES_INVALID_TOTAL.with_label_values(&[kind, reason]).inc();
+                self.record_challenge(
+                    &challenge,
+                    ChallengeVerdict::Rejected { error: err.to_string() },
+                    slashed_validators,
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Remembers the outcome of processing the challenge for the debug page.
+    fn record_challenge(
+        &mut self,
+        challenge: &Challenge,
+        verdict: ChallengeVerdict,
+        slashed_validators: Vec<SlashedValidator>,
+    ) {
+        if self.recent_challenges.len() == NUM_RECENT_CHALLENGES {
+            self.recent_challenges.pop_back();
+        }
+        self.recent_challenges.push_front(ChallengeDebugInfo {
+            challenge_hash: challenge.hash,
+            challenger: challenge.account_id.clone(),
+            kind: (&challenge.body).into(),
+            target_epoch_id: challenge.target_epoch_id,
+            target_height: challenge.target_height,
+            received_at: self.clock.now_utc(),
+            verdict,
+            slashed_validators,
+        });
     }
 }
 
@@ -2805,6 +3323,9 @@ impl Client {
                 sync_block_height,
                 shard_sync_status,
                 blocks_to_catchup: self.chain.get_block_catchup_status(catchup),
+                num_done_blocks: catchup.done_blocks.len(),
+                num_scheduled_blocks: catchup.scheduled_blocks.len(),
+                num_pending_blocks: catchup.pending_blocks.len(),
             });
         }
         Ok(ret)
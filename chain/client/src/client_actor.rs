@@ -46,14 +46,15 @@ use near_chunks::adapter::ShardsManagerRequestFromClient;
 use near_chunks::client::ShardsManagerResponse;
 use near_chunks::logic::get_shards_cares_about_this_or_next_epoch;
 use near_client_primitives::types::{
-    Error, GetClientConfig, GetClientConfigError, GetNetworkInfo, NetworkInfoResponse,
-    StateSyncStatus, Status, StatusError, StatusSyncInfo, SyncStatus,
+    BroadcastChallenge, Error, GetClientConfig, GetClientConfigError, GetNetworkInfo,
+    NetworkInfoResponse, StateSyncStatus, Status, StatusError, StatusSyncInfo, SyncStatus,
 };
 use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::{EpochManagerAdapter, RngSeed};
 use near_network::client::{
-    BlockApproval, BlockHeadersResponse, BlockResponse, ChunkEndorsementMessage, ProcessTxRequest,
-    ProcessTxResponse, RecvChallenge, SetNetworkInfo, StateResponseReceived,
+    BlockApproval, BlockHeadersRangeResponse, BlockHeadersResponse, BlockResponse,
+    ChunkEndorsementMessage, ProcessTxRequest, ProcessTxResponse, RecvChallenge, SetNetworkInfo,
+    StateResponseReceived,
 };
 use near_network::types::ReasonForBan;
 use near_network::types::{
@@ -367,6 +368,7 @@ impl ClientActorInner {
             network_info: NetworkInfo {
                 connected_peers: vec![],
                 tier1_connections: vec![],
+                reachability: Default::default(),
                 num_connected_peers: 0,
                 peer_max_count: 0,
                 highest_height_peers: vec![],
@@ -565,6 +567,7 @@ impl Handler<BlockHeadersResponse> for ClientActorInner {
         let BlockHeadersResponse(headers, peer_id) = msg;
         let validator_signer = self.client.validator_signer.get();
         if self.receive_headers(headers, peer_id, &validator_signer) {
+            self.receive_prefetched_headers(&validator_signer);
             Ok(())
         } else {
             warn!(target: "client", "Banning node for sending invalid block headers");
@@ -573,6 +576,19 @@ impl Handler<BlockHeadersResponse> for ClientActorInner {
     }
 }
 
+impl Handler<BlockHeadersRangeResponse> for ClientActorInner {
+    fn handle(&mut self, msg: BlockHeadersRangeResponse) -> Result<(), ReasonForBan> {
+        let BlockHeadersRangeResponse { range, from_peer } = msg;
+        if !self.client.header_sync.receive_headers_range(range, &from_peer) {
+            warn!(target: "client", "Banning node for sending an invalid range of block headers");
+            return Err(ReasonForBan::BadBlockHeader);
+        }
+        let validator_signer = self.client.validator_signer.get();
+        self.receive_prefetched_headers(&validator_signer);
+        Ok(())
+    }
+}
+
 impl Handler<BlockApproval> for ClientActorInner {
     fn handle(&mut self, msg: BlockApproval) {
         let BlockApproval(approval, peer_id) = msg;
@@ -644,6 +660,13 @@ impl Handler<RecvChallenge> for ClientActorInner {
     }
 }
 
+impl Handler<BroadcastChallenge> for ClientActorInner {
+    fn handle(&mut self, msg: BroadcastChallenge) {
+        let BroadcastChallenge(challenge) = msg;
+        self.client.broadcast_challenge(challenge);
+    }
+}
+
 impl Handler<SetNetworkInfo> for ClientActorInner {
     fn handle(&mut self, msg: SetNetworkInfo) {
         // SetNetworkInfo is a large message. Avoid printing it at the `debug` verbosity.
@@ -847,6 +870,7 @@ impl Handler<GetNetworkInfo> for ClientActorInner {
                 .iter()
                 .map(|p| make_known_producer(p.clone()))
                 .collect(),
+            reachability: self.network_info.reachability,
         })
     }
 }
@@ -855,6 +879,10 @@ impl Handler<ApplyChunksDoneMessage> for ClientActorInner {
     fn handle(&mut self, _msg: ApplyChunksDoneMessage) {
         let validator_signer = self.client.validator_signer.get();
         self.try_process_unfinished_blocks(&validator_signer);
+        self.client.process_ready_future_blocks(
+            Some(self.myself_sender.apply_chunks_done.clone()),
+            &validator_signer,
+        );
     }
 }
 
@@ -1156,6 +1184,90 @@ impl ClientActorInner {
         Ok(())
     }
 
+    /// Produces the block at the next height on top of a block whose chunks are
+    /// still being applied, if we are its producer and its chunks and
+    /// endorsements are already there.
+    fn handle_optimistic_block_production(
+        &mut self,
+        signer: &Option<Arc<ValidatorSigner>>,
+    ) -> Result<(), Error> {
+        if !self.client.config.produce_optimistic_blocks || self.client.sync_status.is_syncing() {
+            return Ok(());
+        }
+        let Some(me) = signer.as_ref().map(|signer| signer.validator_id().clone()) else {
+            return Ok(());
+        };
+        let _span =
+            tracing::debug_span!(target: "client", "handle_optimistic_block_production").entered();
+        let latest_known = self.client.chain.chain_store().get_latest_known()?;
+        for prev_hash in self.client.chain.optimistic_block_candidates()? {
+            let prev_height = self.client.chain.get_block_header(&prev_hash)?.height();
+            let height = prev_height + 1;
+            if height <= latest_known.height {
+                continue;
+            }
+            if !self.client.prepare_optimistic_block_production(&prev_hash, signer)? {
+                continue;
+            }
+            let epoch_id = self.client.epoch_manager.get_epoch_id_from_prev_block(&prev_hash)?;
+            if self.client.epoch_manager.get_block_producer(&epoch_id, height)? != me {
+                continue;
+            }
+            self.client.chunk_inclusion_tracker.prepare_chunk_headers_ready_for_inclusion(
+                &prev_hash,
+                &mut self.client.chunk_endorsement_tracker,
+            )?;
+            let num_chunks = self
+                .client
+                .chunk_inclusion_tracker
+                .num_chunk_headers_ready_for_inclusion(&epoch_id, &prev_hash);
+            let num_shards = self.client.epoch_manager.shard_ids(&epoch_id)?.len();
+            if num_chunks < num_shards
+                || !self.client.doomslug.has_enough_endorsements(&prev_hash, height)
+            {
+                continue;
+            }
+            if let Err(err) = self.produce_optimistic_block(height, prev_hash, signer) {
+                error!(target: "client", height, ?prev_hash, ?err, "Optimistic block production failed");
+            }
+            // Only one block can be produced at the height.
+            break;
+        }
+        Ok(())
+    }
+
+    fn produce_optimistic_block(
+        &mut self,
+        height: BlockHeight,
+        prev_hash: CryptoHash,
+        signer: &Option<Arc<ValidatorSigner>>,
+    ) -> Result<(), Error> {
+        let Some(block) = self.client.produce_optimistic_block_on(height, prev_hash)? else {
+            return Ok(());
+        };
+        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::Block { block: block.clone() },
+        ));
+        // The block is kept as an orphan until its previous block is applied.
+        let res = self.client.start_process_block(
+            MaybeValidated::from_validated(block),
+            Provenance::PRODUCED,
+            Some(self.myself_sender.apply_chunks_done.clone()),
+            signer,
+        );
+        match res {
+            Ok(()) | Err(near_chain::Error::Orphan) => Ok(()),
+            Err(near_chain::Error::ChunksMissing(_)) => {
+                debug!(target: "client", "chunks missing");
+                Ok(())
+            }
+            Err(error) => {
+                error!(target: "client", ?error, "Failed to process optimistic block");
+                Err(error.into())
+            }
+        }
+    }
+
     fn schedule_triggers(&mut self, ctx: &mut dyn DelayedActionRunner<Self>) {
         let wait = self.check_triggers(ctx);
 
@@ -1212,9 +1324,18 @@ impl ClientActorInner {
 
         let validator_signer = self.client.validator_signer.get();
         self.try_process_unfinished_blocks(&validator_signer);
+        // Blocks from the future are held until the clock catches up with
+        // them, which doesn't come with any message.
+        self.client.process_ready_future_blocks(
+            Some(self.myself_sender.apply_chunks_done.clone()),
+            &validator_signer,
+        );
 
         let mut delay = near_async::time::Duration::seconds(1);
         let now = self.clock.now_utc();
+        if let Some(ready_at) = self.client.next_future_block_ready_at() {
+            delay = std::cmp::min(delay, ready_at - now);
+        }
 
         let timer = metrics::CHECK_TRIGGERS_TIME.start_timer();
         if self.sync_started {
@@ -1294,6 +1415,9 @@ impl ClientActorInner {
         if let Err(err) = self.handle_block_production(&signer) {
             tracing::error!(target: "client", ?err, "Handle block production failed")
         }
+        if let Err(err) = self.handle_optimistic_block_production(&signer) {
+            tracing::error!(target: "client", ?err, "Handle optimistic block production failed")
+        }
     }
 
     fn try_doomslug_timer(&mut self) {
@@ -1474,6 +1598,26 @@ impl ClientActorInner {
         }
     }
 
+    /// Adds the headers prefetched by header sync which continue the header
+    /// chain, until a range isn't received yet.
+    fn receive_prefetched_headers(&mut self, signer: &Option<Arc<ValidatorSigner>>) {
+        loop {
+            let Ok(header_head) = self.client.chain.header_head() else {
+                return;
+            };
+            let Some((headers, peer_id)) =
+                self.client.header_sync.take_prefetched_headers(&header_head)
+            else {
+                return;
+            };
+            if !self.receive_headers(headers, peer_id.clone(), signer) {
+                warn!(target: "client", "Banning node for sending invalid block headers");
+                self.client.ban_peer(peer_id, ReasonForBan::BadBlockHeader);
+                return;
+            }
+        }
+    }
+
     /// Check whether need to (continue) sync.
     /// Also return higher height with known peers at that height.
     fn syncing_info(&self) -> Result<SyncRequirement, near_chain::Error> {
@@ -1489,7 +1633,9 @@ impl ClientActorInner {
             .network_info
             .highest_height_peers
             .iter()
-            .filter(|p| !self.client.chain.is_block_invalid(&p.highest_block_hash))
+            .filter(|p| {
+                !self.client.chain.is_block_invalid(p.highest_block_height, &p.highest_block_hash)
+            })
             .collect();
         metrics::PEERS_WITH_INVALID_HASH
             .set(self.network_info.highest_height_peers.len() as i64 - eligible_peers.len() as i64);
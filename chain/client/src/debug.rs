@@ -191,6 +191,14 @@ impl Handler<DebugStatus> for ClientActorInner {
             DebugStatus::ChainProcessingStatus => Ok(DebugStatusResponse::ChainProcessingStatus(
                 self.client.chain.get_chain_processing_info(),
             )),
+            DebugStatus::ValidateChallenge(challenge) => {
+                Ok(DebugStatusResponse::ChallengeValidation(
+                    self.client.validate_challenge(&challenge),
+                ))
+            }
+            DebugStatus::Challenges => Ok(DebugStatusResponse::Challenges(
+                self.client.recent_challenges.iter().cloned().collect(),
+            )),
         }
     }
 }
@@ -787,7 +795,7 @@ fn new_peer_info_view(chain: &Chain, connected_peer_info: &ConnectedPeerInfo) ->
         is_highest_block_invalid: full_peer_info
             .chain_info
             .last_block
-            .map(|x| chain.is_block_invalid(&x.hash))
+            .map(|x| chain.is_block_invalid(x.height, &x.hash))
             .unwrap_or_default(),
         tracked_shards: full_peer_info.chain_info.tracked_shards.clone(),
         archival: full_peer_info.chain_info.archival,
@@ -805,6 +813,7 @@ fn new_peer_info_view(chain: &Chain, connected_peer_info: &ConnectedPeerInfo) ->
             .whole_milliseconds() as u64,
         is_outbound_peer: connected_peer_info.peer_type == PeerType::Outbound,
         nonce: connected_peer_info.nonce,
+        reputation: connected_peer_info.reputation,
     }
 }
 
@@ -852,5 +861,6 @@ pub(crate) fn new_network_info_view(chain: &Chain, network_info: &NetworkInfo) -
             .iter()
             .map(|full_peer_info| new_peer_info_view(chain, full_peer_info))
             .collect::<Vec<_>>(),
+        reachability: network_info.reachability,
     }
 }
@@ -0,0 +1,51 @@
+use crate::sync::external::{create_bucket_readwrite, ExternalConnection};
+use near_chain::execution_artifacts::{ExecutionArtifactSink, FilesystemArtifactSink};
+use near_chain_configs::{ExecutionArtifactsConfig, ExternalStorageLocation};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Writes the execution artifacts to S3 or GCS.
+struct ExternalArtifactSink {
+    connection: ExternalConnection,
+    /// The sink is called from the exporter thread, outside of any runtime.
+    runtime: tokio::runtime::Runtime,
+}
+
+impl ExecutionArtifactSink for ExternalArtifactSink {
+    fn export(&self, file_name: &str, data: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.connection.put_object(data, file_name))
+    }
+}
+
+/// Creates the sink of the execution artifacts configured by `config`.
+pub(crate) fn create_execution_artifact_sink(
+    config: &ExecutionArtifactsConfig,
+) -> anyhow::Result<Arc<dyn ExecutionArtifactSink>> {
+    let connection = match &config.location {
+        ExternalStorageLocation::Filesystem { root_dir } => {
+            return Ok(Arc::new(FilesystemArtifactSink { root_dir: root_dir.clone() }));
+        }
+        ExternalStorageLocation::S3 { bucket, region } => ExternalConnection::S3 {
+            bucket: Arc::new(create_bucket_readwrite(
+                bucket,
+                region,
+                Duration::from_secs(30),
+                config.credentials_file.clone(),
+            )?),
+        },
+        ExternalStorageLocation::GCS { bucket } => {
+            // Same as the state dump, the GCS client reads the credentials
+            // from the `SERVICE_ACCOUNT` environment variable.
+            if let Some(credentials_file) = &config.credentials_file {
+                std::env::set_var("SERVICE_ACCOUNT", credentials_file);
+            }
+            ExternalConnection::GCS {
+                gcs_client: Arc::new(cloud_storage::Client::default()),
+                reqwest_client: Arc::new(reqwest::Client::default()),
+                bucket: bucket.clone(),
+            }
+        }
+    };
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    Ok(Arc::new(ExternalArtifactSink { connection, runtime }))
+}
@@ -0,0 +1,65 @@
+use near_async::time::Utc;
+use near_network::types::PeerId;
+use near_primitives::block::Block;
+
+/// Maximum number of blocks held until their timestamp is within the allowed
+/// clock skew. More blocks from the future are rejected.
+const MAX_FUTURE_BLOCKS: usize = 32;
+
+pub(crate) struct FutureBlock {
+    pub block: Block,
+    pub peer_id: PeerId,
+    pub was_requested: bool,
+    /// Time when the timestamp of the block is within the allowed clock skew.
+    ready_at: Utc,
+}
+
+/// Blocks received with a timestamp too far in the future, held until the clock
+/// of the node catches up with them, see `FutureBlockPolicy::Delay`.
+pub(crate) struct FutureBlockPool {
+    blocks: Vec<FutureBlock>,
+}
+
+impl FutureBlockPool {
+    pub fn new() -> Self {
+        Self { blocks: vec![] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Holds `block` until `ready_at`. Returns false if the block can't be
+    /// held because the pool is full.
+    pub fn add(
+        &mut self,
+        block: Block,
+        peer_id: PeerId,
+        was_requested: bool,
+        ready_at: Utc,
+    ) -> bool {
+        if self.blocks.iter().any(|future_block| future_block.block.hash() == block.hash()) {
+            return true;
+        }
+        if self.blocks.len() >= MAX_FUTURE_BLOCKS {
+            return false;
+        }
+        self.blocks.push(FutureBlock { block, peer_id, was_requested, ready_at });
+        true
+    }
+
+    /// Returns when the next held block can be processed.
+    pub fn next_ready_at(&self) -> Option<Utc> {
+        self.blocks.iter().map(|future_block| future_block.ready_at).min()
+    }
+
+    /// Removes and returns the blocks which can be processed at `now`, from the
+    /// lowest height.
+    pub fn take_ready(&mut self, now: Utc) -> Vec<FutureBlock> {
+        let (mut ready, waiting): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.blocks).into_iter().partition(|block| block.ready_at <= now);
+        self.blocks = waiting;
+        ready.sort_by_key(|future_block| future_block.block.header().height());
+        ready
+    }
+}
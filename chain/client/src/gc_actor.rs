@@ -1,14 +1,16 @@
 use crate::metrics;
+use crate::sync::epoch::EpochSync;
 use near_async::futures::{DelayedActionRunner, DelayedActionRunnerExt};
 use near_async::messaging::Actor;
 #[cfg(feature = "test_features")]
 use near_async::messaging::Handler;
 use near_chain::{types::RuntimeAdapter, ChainStore, ChainStoreAccess};
-use near_chain_configs::GCConfig;
+use near_chain_configs::{GCConfig, GCRetention};
 use near_epoch_manager::EpochManagerAdapter;
-use near_primitives::types::BlockHeight;
+use near_primitives::epoch_sync::CompressedEpochSyncProof;
+use near_primitives::types::{BlockHeight, BlockHeightDelta, EpochId};
 use near_store::{metadata::DbKind, Store};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tracing::warn;
 
 /// An actor for garbage collection that runs in its own thread
@@ -20,6 +22,12 @@ pub struct GCActor {
     epoch_manager: Arc<dyn EpochManagerAdapter>,
     gc_config: GCConfig,
     is_archive: bool,
+    transaction_validity_period: BlockHeightDelta,
+    /// Epoch of the final head when the stored epoch sync proof was last
+    /// updated, and the height from which the headers are kept for it, if the
+    /// proof could be derived.
+    epoch_sync_boundary: Option<(EpochId, Option<BlockHeight>)>,
+    epoch_sync_proof_cache: Arc<Mutex<Option<(EpochId, CompressedEpochSyncProof)>>>,
     /// In some tests we may want to temporarily disable GC
     no_gc: bool,
 }
@@ -32,6 +40,7 @@ impl GCActor {
         epoch_manager: Arc<dyn EpochManagerAdapter>,
         gc_config: GCConfig,
         is_archive: bool,
+        transaction_validity_period: BlockHeightDelta,
     ) -> Self {
         GCActor {
             store: ChainStore::new(store, genesis_height, true),
@@ -39,6 +48,9 @@ impl GCActor {
             gc_config,
             epoch_manager,
             is_archive,
+            transaction_validity_period,
+            epoch_sync_boundary: None,
+            epoch_sync_proof_cache: Arc::new(Mutex::new(None)),
             no_gc: false,
         }
     }
@@ -46,11 +58,12 @@ impl GCActor {
     fn clear_data(&mut self) -> Result<(), near_chain::Error> {
         // A RPC node should do regular garbage collection.
         if !self.is_archive {
-            return self.store.clear_data(
+            self.store.clear_data(
                 &self.gc_config,
                 self.runtime_adapter.clone(),
                 self.epoch_manager.clone(),
-            );
+            )?;
+            return self.clear_headers();
         }
 
         // An archival node with split storage should perform garbage collection
@@ -72,6 +85,44 @@ impl GCActor {
         self.store.clear_archive_data(self.gc_config.gc_blocks_limit, self.runtime_adapter.clone())
     }
 
+    /// Garbage collects the old block headers if they are not kept forever.
+    ///
+    /// The headers needed to serve epoch sync to other nodes are replaced by
+    /// an epoch sync proof, which is updated once per epoch, before the
+    /// headers before its epoch are garbage collected.
+    fn clear_headers(&mut self) -> Result<(), near_chain::Error> {
+        if self.gc_config.headers_retention == GCRetention::Forever {
+            return Ok(());
+        }
+        let final_head_epoch_id = self.store.final_head()?.epoch_id;
+        let epoch_sync_boundary = match self.epoch_sync_boundary {
+            Some((epoch_id, height)) if epoch_id == final_head_epoch_id => height,
+            _ => {
+                // Early in the chain there may be no epoch to derive a proof for.
+                let height = EpochSync::update_stored_epoch_sync_proof(
+                    self.store.store().clone(),
+                    self.transaction_validity_period,
+                    self.epoch_sync_proof_cache.clone(),
+                )
+                .inspect_err(|err| {
+                    tracing::debug!(target: "garbage_collection", ?err, "Failed to update epoch sync proof")
+                })
+                .ok();
+                self.epoch_sync_boundary = Some((final_head_epoch_id, height));
+                height
+            }
+        };
+        let Some(epoch_sync_boundary) = epoch_sync_boundary else {
+            return Ok(());
+        };
+        self.store.clear_headers(
+            &self.gc_config,
+            self.runtime_adapter.clone(),
+            self.epoch_manager.clone(),
+            epoch_sync_boundary,
+        )
+    }
+
     fn gc(&mut self, ctx: &mut dyn DelayedActionRunner<Self>) {
         if !self.no_gc {
             let timer = metrics::GC_TIME.start_timer();
@@ -700,6 +700,9 @@ pub fn log_catchup_status(catchup_status: Vec<CatchupStatusView>) {
         tracing::info!(
             sync_hash=?catchup_status.sync_block_hash,
             sync_height=?catchup_status.sync_block_height,
+            num_done_blocks=catchup_status.num_done_blocks,
+            num_scheduled_blocks=catchup_status.num_scheduled_blocks,
+            num_pending_blocks=catchup_status.num_pending_blocks,
             "Catchup Status - shard sync status: {}, next blocks to catch up: {}",
             shard_sync_string,
             block_catchup_string,
@@ -1053,6 +1056,7 @@ mod tests {
                 received_bytes_per_sec: 0,
                 known_producers: vec![],
                 tier1_connections: vec![],
+                reachability: Default::default(),
                 tier1_accounts_keys: vec![],
                 tier1_accounts_data: vec![],
             },
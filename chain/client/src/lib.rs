@@ -1,11 +1,14 @@
 pub use near_client_primitives::types::{
-    Error, GetBlock, GetBlockProof, GetBlockProofResponse, GetBlockWithMerkleTree, GetChunk,
+    BroadcastChallenge, ClearInvalidBlocks, ClearInvalidBlocksError, Error, GetBlock,
+    GetBlockProof, GetBlockProofResponse, GetBlockWithMerkleTree, GetChallengeStatus, GetChunk,
     GetClientConfig, GetExecutionOutcome, GetExecutionOutcomeResponse,
     GetExecutionOutcomesForBlock, GetGasPrice, GetMaintenanceWindows, GetNetworkInfo,
     GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetShardChunk, GetSplitStorageInfo,
     GetStateChanges, GetStateChangesInBlock, GetStateChangesWithCauseInBlock,
-    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfo, GetValidatorOrdered, Query,
-    QueryError, Status, StatusResponse, SyncStatus, TxStatus, TxStatusError,
+    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorEndorsementStats,
+    GetValidatorInfo, GetValidatorOrdered, Query, QueryError, ReplayBlock, ReplayBlockError,
+    Status, StatusResponse, SyncStatus, TraceTransaction, TraceTransactionError, TxStatus,
+    TxStatusError,
 };
 
 pub use crate::client::{Client, ProduceChunkResult};
@@ -30,10 +33,13 @@ pub mod adapter;
 pub mod adversarial;
 mod chunk_distribution_network;
 mod chunk_inclusion_tracker;
+mod chunk_state_prefetcher;
 mod client;
 pub mod client_actor;
 mod config_updater;
 pub mod debug;
+mod execution_artifacts;
+mod future_blocks;
 pub mod gc_actor;
 mod info;
 mod metrics;
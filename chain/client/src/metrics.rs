@@ -1,8 +1,9 @@
 use near_o11y::metrics::{
     exponential_buckets, linear_buckets, try_create_counter, try_create_counter_vec,
-    try_create_gauge, try_create_histogram, try_create_histogram_vec, try_create_int_counter,
-    try_create_int_counter_vec, try_create_int_gauge, try_create_int_gauge_vec, Counter,
-    CounterVec, Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    try_create_gauge, try_create_gauge_vec, try_create_histogram, try_create_histogram_vec,
+    try_create_histogram_with_buckets, try_create_int_counter, try_create_int_counter_vec,
+    try_create_int_gauge, try_create_int_gauge_vec, Counter, CounterVec, Gauge, GaugeVec,
+    Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use std::sync::LazyLock;
 
@@ -14,6 +15,32 @@ pub(crate) static BLOCK_PRODUCED_TOTAL: LazyLock<IntCounter> = LazyLock::new(||
     .unwrap()
 });
 
+pub(crate) static OPTIMISTIC_BLOCKS_PRODUCED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    try_create_int_counter(
+        "near_optimistic_blocks_produced_total",
+        "Total number of blocks produced on top of a block which was still being applied",
+    )
+    .unwrap()
+});
+
+pub(crate) static OPTIMISTIC_BLOCKS_CONFIRMED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    try_create_int_counter(
+        "near_optimistic_blocks_confirmed_total",
+        "Total number of optimistically produced blocks whose previous block applied successfully",
+    )
+    .unwrap()
+});
+
+pub(crate) static OPTIMISTIC_BLOCKS_MISPREDICTED_TOTAL: LazyLock<IntCounter> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "near_optimistic_blocks_mispredicted_total",
+            "Total number of optimistically produced blocks rolled back because their previous \
+            block failed to apply",
+        )
+        .unwrap()
+    });
+
 pub(crate) static CHUNK_PRODUCED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
     try_create_int_counter(
         "near_chunk_produced_total",
@@ -708,3 +735,89 @@ pub(crate) static DECODE_PARTIAL_WITNESS_ACCESSED_CONTRACTS_STATE_COUNT: LazyLoc
         )
         .unwrap()
     });
+
+pub(crate) static CHALLENGES_RECEIVED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "near_challenges_received_total",
+        "Number of challenges received from the network, including duplicates",
+        &["kind"],
+    )
+    .unwrap()
+});
+
+pub(crate) static CHALLENGES_VALID_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "near_challenges_valid_total",
+        "Number of received challenges which passed validation",
+        &["kind"],
+    )
+    .unwrap()
+});
+
+pub(crate) static CHALLENGES_INVALID_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "near_challenges_invalid_total",
+        "Number of received challenges which were rejected. The reason label is either \
+        expired, malicious or invalid.",
+        &["kind", "reason"],
+    )
+    .unwrap()
+});
+
+pub(crate) static CHALLENGE_VERIFICATION_TIME: LazyLock<HistogramVec> = LazyLock::new(|| {
+    try_create_histogram_vec(
+        "near_challenge_verification_time",
+        "Time taken to validate a challenge received from the network",
+        &["kind"],
+        Some(exponential_buckets(0.001, 2.0, 16).unwrap()),
+    )
+    .unwrap()
+});
+
+pub(crate) static BLOCK_TIMESTAMP_SKEW: LazyLock<Histogram> = LazyLock::new(|| {
+    try_create_histogram_with_buckets(
+        "near_block_timestamp_skew_seconds",
+        "Difference between the timestamp of a received block and the clock of the node, \
+        positive when the block is from the future. Includes the propagation delay.",
+        vec![-30.0, -10.0, -5.0, -2.0, -1.0, -0.5, 0.0, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 120.0],
+    )
+    .unwrap()
+});
+
+pub(crate) static BLOCK_PRODUCER_TIMESTAMP_SKEW: LazyLock<GaugeVec> = LazyLock::new(|| {
+    try_create_gauge_vec(
+        "near_block_producer_timestamp_skew_seconds",
+        "Difference between the timestamp of the last received block of a block producer and \
+        the clock of the node, positive when the block is from the future",
+        &["account_id"],
+    )
+    .unwrap()
+});
+
+pub(crate) static FUTURE_BLOCKS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "near_future_blocks_total",
+        "Number of received blocks from too far in the future, by the action taken, either \
+        rejected or delayed",
+        &["action"],
+    )
+    .unwrap()
+});
+
+pub(crate) static NUM_FUTURE_BLOCKS: LazyLock<IntGauge> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "near_num_future_blocks",
+        "Number of blocks from the future held until the clock of the node catches up",
+    )
+    .unwrap()
+});
+
+pub(crate) static CHUNK_STATE_PREFETCHED_SIGNERS_TOTAL: LazyLock<IntCounter> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "near_chunk_state_prefetched_signers_total",
+            "Total number of transaction signers whose account and access key were read ahead of \
+        applying their chunk",
+        )
+        .unwrap()
+    });
@@ -114,7 +114,7 @@ impl ChunkValidator {
                 self.epoch_manager.as_ref(),
                 prev_block_hash,
                 &prev_chunk_extra,
-                last_header.height_included(),
+                &last_header,
                 &chunk_header,
             ) {
                 Ok(()) => {
@@ -199,6 +199,9 @@ mod tests {
                 ShardChunkHeaderInner::V2(inner) => inner.encoded_length = encoded_length,
                 ShardChunkHeaderInner::V3(inner) => inner.encoded_length = encoded_length,
                 ShardChunkHeaderInner::V4(inner) => inner.encoded_length = encoded_length,
+                ShardChunkHeaderInner::V5(inner) => inner.encoded_length = encoded_length,
+                ShardChunkHeaderInner::V6(inner) => inner.encoded_length = encoded_length,
+                ShardChunkHeaderInner::V7(inner) => inner.encoded_length = encoded_length,
             },
             _ => unimplemented!(),
         }
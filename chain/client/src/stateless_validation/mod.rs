@@ -1,7 +1,7 @@
 pub mod chunk_endorsement;
 pub mod chunk_validator;
 pub mod partial_witness;
-mod shadow_validate;
+pub(crate) mod shadow_validate;
 mod state_witness_producer;
 pub mod state_witness_tracker;
 mod validate;
@@ -423,7 +423,13 @@ impl PartialEncodedStateWitnessTracker {
             }
 
             // Merge accessed contracts into the main transition's partial state.
-            let PartialState::TrieValues(values) = &mut witness.main_state_transition.base_state;
+            let PartialState::TrieValues(values) = &mut witness.main_state_transition.base_state
+            else {
+                return Err(Error::InvalidPartialChunkStateWitness(format!(
+                    "Main transition base state of witness {:?} must consist of trie values",
+                    key,
+                )));
+            };
             values.extend(accessed_contracts.into_iter().map(|code| code.0.into()));
 
             tracing::debug!(target: "client", ?key, "Sending encoded witness to client.");
@@ -1,29 +1,128 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use crate::stateless_validation::state_witness_producer::CreateWitnessResult;
-use near_chain::stateless_validation::chunk_validation::validate_prepared_transactions;
+use near_chain::stateless_validation::chunk_validation::{
+    validate_prepared_transactions, ShadowValidationFailureHandler,
+};
 use near_chain::types::{RuntimeStorageConfig, StorageDataSource};
 use near_chain::{Block, BlockHeader};
+use near_chain_configs::ShadowValidationConfig;
 use near_chain_primitives::Error;
-use near_primitives::sharding::{ShardChunk, ShardChunkHeader};
+use near_primitives::hash::CryptoHash;
+use near_primitives::sharding::{ChunkHash, ShardChunk, ShardChunkHeader};
+use near_primitives::types::{BlockHeight, ShardId};
 
 use crate::Client;
 
+/// Timeout of the requests to the shadow validation webhook.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum number of failures waiting to be reported to the webhook. More
+/// failures are only logged.
+const WEBHOOK_QUEUE_SIZE: usize = 100;
+
+/// Body of the request sent to the shadow validation webhook.
+#[derive(Debug, serde::Serialize)]
+struct ShadowValidationDivergence {
+    prev_block_hash: CryptoHash,
+    height_created: BlockHeight,
+    shard_id: ShardId,
+    chunk_hash: ChunkHash,
+    error: String,
+}
+
+/// Reports the chunks which failed shadow validation to the webhook
+/// configured in `ShadowValidationConfig::webhook_url`. The requests are sent
+/// from a dedicated thread, so that a slow webhook doesn't hold up the
+/// validation of the chunks.
+pub(crate) struct ShadowValidationWebhook {
+    sender: crossbeam_channel::Sender<ShadowValidationDivergence>,
+}
+
+impl ShadowValidationWebhook {
+    pub(crate) fn from_config(config: &ShadowValidationConfig) -> Option<Arc<Self>> {
+        let url = config.webhook_url.clone()?;
+        let client = reqwest::blocking::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|err| tracing::error!(target: "client", ?err, "failed to create the shadow validation webhook client"))
+            .ok()?;
+        let (sender, receiver) =
+            crossbeam_channel::bounded::<ShadowValidationDivergence>(WEBHOOK_QUEUE_SIZE);
+        std::thread::Builder::new()
+            .name("shadow_validation_webhook".to_string())
+            .spawn(move || {
+                // Ends when the client, and with it the sender, is dropped.
+                for divergence in receiver {
+                    Self::send(&client, &url, &divergence);
+                }
+            })
+            .map_err(|err| tracing::error!(target: "client", ?err, "failed to spawn the shadow validation webhook thread"))
+            .ok()?;
+        Some(Arc::new(Self { sender }))
+    }
+
+    /// Queues `divergence` to be sent to the webhook.
+    fn report(&self, divergence: ShadowValidationDivergence) {
+        if let Err(err) = self.sender.try_send(divergence) {
+            tracing::warn!(target: "client", divergence = ?err.into_inner(), "shadow validation webhook is behind, dropping failure report");
+        }
+    }
+
+    fn send(
+        client: &reqwest::blocking::Client,
+        url: &str,
+        divergence: &ShadowValidationDivergence,
+    ) {
+        let body = match serde_json::to_vec(divergence) {
+            Ok(body) => body,
+            Err(err) => {
+                tracing::error!(target: "client", ?err, "failed to serialize shadow validation failure");
+                return;
+            }
+        };
+        let result = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .and_then(|response| response.error_for_status());
+        if let Err(err) = result {
+            tracing::warn!(target: "client", ?err, url, "failed to report shadow validation failure");
+        }
+    }
+}
+
 impl Client {
-    // Temporary feature to make node produce state witness for every chunk in every processed block
-    // and then self-validate it.
+    /// Produces the state witness of every new chunk of the tracked shards in
+    /// `block` and validates it, as if the node was their chunk validator.
+    /// Enabled by `ShadowValidationConfig` or the `shadow_chunk_validation`
+    /// feature.
     pub(crate) fn shadow_validate_block_chunks(&mut self, block: &Block) -> Result<(), Error> {
-        if !cfg!(feature = "shadow_chunk_validation") {
+        if !cfg!(feature = "shadow_chunk_validation") && !self.config.shadow_validation.enabled {
             return Ok(());
         }
         let block_hash = block.hash();
         tracing::debug!(target: "client", ?block_hash, "shadow validation for block chunks");
         let prev_block = self.chain.get_block(block.header().prev_hash())?;
         let prev_block_chunks = prev_block.chunks();
+        let me = self.validator_signer.get().map(|signer| signer.validator_id().clone());
         for (shard_index, chunk) in block
             .chunks()
             .iter_deprecated()
             .enumerate()
             .filter(|(_, chunk)| chunk.is_new_chunk(block.header().height()))
         {
+            // The state witness can only be produced from the local state.
+            if !self.shard_tracker.care_about_shard(
+                me.as_ref(),
+                block.header().prev_hash(),
+                chunk.shard_id(),
+                true,
+            ) {
+                continue;
+            }
             let chunk = self.chain.get_chunk_clone_from_header(chunk)?;
             // TODO(resharding) This doesn't work if shard layout changes.
             let prev_chunk_header = prev_block_chunks.get(shard_index).unwrap();
@@ -86,11 +185,24 @@ impl Client {
         if self.config.save_latest_witnesses {
             self.chain.chain_store.save_latest_chunk_state_witness(&state_witness)?;
         }
+        let on_failure = self.shadow_validation_webhook.clone().map(|webhook| {
+            let divergence = ShadowValidationDivergence {
+                prev_block_hash: *state_witness.chunk_header.prev_block_hash(),
+                height_created: state_witness.chunk_header.height_created(),
+                shard_id: state_witness.chunk_header.shard_id(),
+                chunk_hash: state_witness.chunk_header.chunk_hash(),
+                error: String::new(),
+            };
+            Box::new(move |err: &Error| {
+                webhook.report(ShadowValidationDivergence { error: err.to_string(), ..divergence })
+            }) as ShadowValidationFailureHandler
+        });
         self.chain.shadow_validate_state_witness(
             state_witness,
             self.epoch_manager.as_ref(),
             self.runtime_adapter.as_ref(),
             None,
+            on_failure,
         )?;
         Ok(())
     }
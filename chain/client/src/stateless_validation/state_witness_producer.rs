@@ -280,7 +280,9 @@ impl Client {
                 let message = format!(
                     "Missing transition state proof for block {block_hash} and shard {shard_id}"
                 );
-                if !cfg!(feature = "shadow_chunk_validation") {
+                if !cfg!(feature = "shadow_chunk_validation")
+                    && !self.config.shadow_validation.enabled
+                {
                     log_assert_fail!("{message}");
                 }
                 Error::Other(message)
@@ -198,8 +198,8 @@ impl BlockSync {
                     _ => return Err(e),
                 },
             }
-            if let Ok(_) = check_known(chain, &next_hash)? {
-                let next_height = chain.get_block_header(&next_hash)?.height();
+            let next_height = chain.get_block_header(&next_hash)?.height();
+            if let Ok(_) = check_known(chain, next_height, &next_hash)? {
                 requests.push((next_height, next_hash));
             }
         }
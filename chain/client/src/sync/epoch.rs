@@ -135,6 +135,37 @@ impl EpochSync {
         Ok(proof)
     }
 
+    /// Derives an epoch sync proof for a recent epoch and stores it in place of the stored one,
+    /// if the target epoch changed. Returns the height of the first block of the target epoch.
+    ///
+    /// A node keeps all headers from this height on, so that it can still derive proofs from the
+    /// stored one after garbage collecting the older headers.
+    pub fn update_stored_epoch_sync_proof(
+        store: Store,
+        transaction_validity_period: BlockHeightDelta,
+        cache: Arc<Mutex<Option<(EpochId, CompressedEpochSyncProof)>>>,
+    ) -> Result<BlockHeight, Error> {
+        let stored_proof = store.get_ser::<EpochSyncProof>(DBCol::EpochSyncProof, &[])?;
+        let proof =
+            Self::derive_epoch_sync_proof(store.clone(), transaction_validity_period, cache)?;
+        let (proof, _) = proof.decode().map_err(|err| {
+            Error::Other(format!("Failed to decompress epoch sync proof: {:?}", err))
+        })?;
+        let first_block_header = &proof.as_v1().current_epoch.first_block_header_in_epoch;
+        if stored_proof.is_some_and(|stored_proof| {
+            stored_proof.as_v1().current_epoch.first_block_header_in_epoch.hash()
+                == first_block_header.hash()
+        }) {
+            return Ok(first_block_header.height());
+        }
+        let height = first_block_header.height();
+        let mut store_update = store.store_update();
+        store_update.set_ser(DBCol::EpochSyncProof, &[], &proof)?;
+        store_update.commit()?;
+        tracing::debug!(target: "epoch_sync", height, "Stored a new epoch sync proof");
+        Ok(height)
+    }
+
     /// Figures out which target epoch we should produce a proof for, based on the current
     /// state of the blockchain.
     ///
@@ -143,10 +143,16 @@ impl ExternalConnection {
         shard_id: ShardId,
         location: &str,
     ) -> Result<(), anyhow::Error> {
+        self.put_object(data, location).await?;
+        tracing::debug!(target: "state_sync_dump", ?shard_id, part_length = data.len(), ?location, ?file_type, "Wrote a state part");
+        Ok(())
+    }
+
+    /// Writes `data` to `location` in the external storage.
+    pub async fn put_object(&self, data: &[u8], location: &str) -> Result<(), anyhow::Error> {
         match self {
             ExternalConnection::S3 { bucket } => {
                 bucket.put_object(&location, data).await?;
-                tracing::debug!(target: "state_sync_dump", ?shard_id, part_length = data.len(), ?location, ?file_type, "Wrote a state part to S3");
                 Ok(())
             }
             ExternalConnection::Filesystem { root_dir } => {
@@ -160,7 +166,6 @@ impl ExternalConnection {
                     .truncate(true)
                     .open(&path)?;
                 file.write_all(data)?;
-                tracing::debug!(target: "state_sync_dump", ?shard_id, part_length = data.len(), ?location, ?file_type, "Wrote a state part to a file");
                 Ok(())
             }
             ExternalConnection::GCS { gcs_client, bucket, .. } => {
@@ -168,7 +173,6 @@ impl ExternalConnection {
                     .object()
                     .create(bucket, data.to_vec(), location, "application/octet-stream")
                     .await?;
-                tracing::debug!(target: "state_sync_dump", ?shard_id, part_length = data.len(), ?location, ?file_type, "Wrote a state part to GCS");
                 Ok(())
             }
         }
@@ -5,11 +5,15 @@ use near_client_primitives::types::SyncStatus;
 use near_network::types::PeerManagerMessageRequest;
 use near_network::types::{HighestHeightPeerInfo, NetworkRequests, PeerManagerAdapter};
 use near_primitives::block::Tip;
+use near_primitives::block_header::BlockHeader;
+use near_primitives::block_headers_range::{BlockHeadersRange, BlockHeadersRangeRequest};
 use near_primitives::hash::CryptoHash;
+use near_primitives::network::PeerId;
 use near_primitives::types::BlockHeight;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use std::cmp::min;
+use std::collections::BTreeMap;
 use tracing::{debug, warn};
 
 /// Maximum number of block headers send over the network.
@@ -31,6 +35,14 @@ struct BatchProgress {
     highest_height_of_peers: BlockHeight,
 }
 
+/// Range of headers ahead of the header head requested from a peer.
+struct PrefetchedRange {
+    request: BlockHeadersRangeRequest,
+    peer_id: PeerId,
+    requested_at: Utc,
+    range: Option<Box<BlockHeadersRange>>,
+}
+
 /// Helper to keep track of sync headers.
 /// Handles major re-orgs by finding closest header that matches and re-downloading headers from that point.
 pub struct HeaderSync {
@@ -64,6 +76,14 @@ pub struct HeaderSync {
     /// Expected height when node will be automatically shut down, so header
     /// sync can be stopped.
     shutdown_height: near_chain_configs::MutableConfigValue<Option<BlockHeight>>,
+
+    /// Ranges of headers ahead of the currently requested batch, requested
+    /// from other peers in parallel, by start height. A range is added to
+    /// the chain once the header head reaches it.
+    prefetched_ranges: BTreeMap<BlockHeight, PrefetchedRange>,
+
+    /// Maximum number of ranges in `prefetched_ranges`.
+    max_prefetched_ranges: u64,
 }
 
 impl HeaderSync {
@@ -75,6 +95,7 @@ impl HeaderSync {
         stall_ban_timeout: Duration,
         expected_height_per_second: u64,
         shutdown_height: near_chain_configs::MutableConfigValue<Option<BlockHeight>>,
+        max_prefetched_ranges: u64,
     ) -> Self {
         HeaderSync {
             clock: clock.clone(),
@@ -92,6 +113,8 @@ impl HeaderSync {
             stall_ban_timeout,
             expected_height_per_second,
             shutdown_height,
+            prefetched_ranges: BTreeMap::new(),
+            max_prefetched_ranges,
         }
     }
 
@@ -161,9 +184,121 @@ impl HeaderSync {
                 self.syncing_peer = Some(peer);
             }
         }
+        self.request_header_ranges(&header_head, highest_height_peers);
         Ok(())
     }
 
+    /// Requests the ranges of headers following the batch requested with the
+    /// locator from random peers, anchored at their highest block.
+    fn request_header_ranges(
+        &mut self,
+        header_head: &Tip,
+        highest_height_peers: &[HighestHeightPeerInfo],
+    ) {
+        let now = self.clock.now_utc();
+        let initial_timeout = self.initial_timeout;
+        // Drop the ranges below the header head and the requests which weren't
+        // answered in time, so that they are requested again.
+        self.prefetched_ranges.retain(|start_height, prefetched| {
+            start_height + MAX_BLOCK_HEADERS > header_head.height + 1
+                && (prefetched.range.is_some() || now < prefetched.requested_at + initial_timeout)
+        });
+
+        let shutdown_height = self.shutdown_height.get().unwrap_or(u64::MAX);
+        let first_range = header_head.height / MAX_BLOCK_HEADERS + 2;
+        for index in first_range..first_range + self.max_prefetched_ranges {
+            let start_height = index * MAX_BLOCK_HEADERS;
+            if start_height > shutdown_height {
+                break;
+            }
+            if self.prefetched_ranges.contains_key(&start_height) {
+                continue;
+            }
+            let peers: Vec<_> = highest_height_peers
+                .iter()
+                .filter(|peer| peer.highest_block_height >= start_height)
+                .collect();
+            let Some(peer) = peers.choose(&mut thread_rng()) else {
+                break;
+            };
+            let request =
+                BlockHeadersRangeRequest { anchor_hash: peer.highest_block_hash, start_height };
+            debug!(target: "sync", "Sync: request headers range: asking {} for {:?}", peer.peer_info.id, request);
+            self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+                NetworkRequests::BlockHeadersRangeRequest {
+                    request: request.clone(),
+                    peer_id: peer.peer_info.id.clone(),
+                },
+            ));
+            self.prefetched_ranges.insert(
+                start_height,
+                PrefetchedRange {
+                    request,
+                    peer_id: peer.peer_info.id.clone(),
+                    requested_at: now,
+                    range: None,
+                },
+            );
+        }
+    }
+
+    /// Records a range of headers received from `peer_id`. Returns false if
+    /// the range doesn't answer any of the requests sent to the peer. The
+    /// headers are only validated once the header head reaches them.
+    /// Ranges which weren't requested, e.g. because the request timed out,
+    /// are ignored.
+    pub fn receive_headers_range(
+        &mut self,
+        range: Box<BlockHeadersRange>,
+        peer_id: &PeerId,
+    ) -> bool {
+        let mut requested = false;
+        for prefetched in self.prefetched_ranges.values_mut() {
+            if &prefetched.peer_id != peer_id
+                || &prefetched.request.anchor_hash != range.anchor.hash()
+                || prefetched.range.is_some()
+            {
+                continue;
+            }
+            requested = true;
+            if range.verify(&prefetched.request, MAX_BLOCK_HEADERS) {
+                prefetched.range = Some(range);
+                return true;
+            }
+        }
+        !requested
+    }
+
+    /// Takes the headers of the received range which continue the header
+    /// chain at `header_head`, and the peer which sent them.
+    pub fn take_prefetched_headers(
+        &mut self,
+        header_head: &Tip,
+    ) -> Option<(Vec<BlockHeader>, PeerId)> {
+        let next_height = header_head.height + 1;
+        self.prefetched_ranges
+            .retain(|start_height, _| start_height + MAX_BLOCK_HEADERS > next_height);
+        let entry = self.prefetched_ranges.first_entry()?;
+        if *entry.key() > next_height || entry.get().range.is_none() {
+            return None;
+        }
+        let prefetched = entry.remove();
+        let headers: Vec<_> = prefetched
+            .range?
+            .headers
+            .into_iter()
+            .filter(|header| header.height() > header_head.height)
+            .collect();
+        // The range may be on another fork than the header head, in which case
+        // the locator-based sync resolves it.
+        match headers.first() {
+            Some(first) if first.prev_hash() == &header_head.last_block_hash => {
+                Some((headers, prefetched.peer_id))
+            }
+            _ => None,
+        }
+    }
+
     /// Returns the height that we expect to reach starting from `old_height` after `time_delta`.
     fn compute_expected_height(
         &self,
@@ -402,6 +537,7 @@ mod test {
         BlockInfo, FullPeerInfo, HighestHeightPeerInfo, NetworkRequests, PeerInfo,
     };
     use near_primitives::block::{Approval, Block, GenesisId};
+    use near_primitives::block_headers_range::BlockHeadersRangeRequest;
     use near_primitives::merkle::PartialMerkleTree;
     use near_primitives::network::PeerId;
     use near_primitives::test_utils::TestBlockBuilder;
@@ -411,7 +547,9 @@ mod test {
     use std::sync::Arc;
     use std::thread;
 
-    use crate::sync::header::{get_locator_ordinals, HeaderSync, MAX_BLOCK_HEADERS};
+    use crate::sync::header::{
+        get_locator_ordinals, HeaderSync, PrefetchedRange, MAX_BLOCK_HEADERS,
+    };
 
     #[test]
     fn test_get_locator_ordinals() {
@@ -464,6 +602,7 @@ mod test {
             Duration::seconds(120),
             1_000_000_000,
             MutableConfigValue::new(None, "expected_shutdown"),
+            0,
         );
         let (mut chain, _, _, signer) = setup(Clock::real());
         for _ in 0..3 {
@@ -541,6 +680,68 @@ mod test {
         );
     }
 
+    /// Checks that a range of headers is verified against the request sent to
+    /// the peer and taken once the header head reaches it.
+    #[test]
+    fn test_headers_range() {
+        let (mut chain, _, _, signer) = setup(Clock::real());
+        for _ in 0..10 {
+            let prev = chain.get_block(&chain.head().unwrap().last_block_hash).unwrap();
+            let block = TestBlockBuilder::new(Clock::real(), &prev, signer.clone()).build();
+            process_block_sync(
+                &mut chain,
+                &None,
+                block.into(),
+                Provenance::PRODUCED,
+                &mut BlockProcessingArtifact::default(),
+            )
+            .unwrap();
+        }
+        let anchor_hash = chain.head().unwrap().last_block_hash;
+        let request = BlockHeadersRangeRequest { anchor_hash, start_height: 4 };
+        let range = chain.retrieve_headers_range(&request, 4).unwrap().unwrap();
+        assert_eq!(range.headers.iter().map(|h| h.height()).collect::<Vec<_>>(), vec![4, 5, 6, 7]);
+        assert!(range.verify(&request, 4));
+        assert!(!range.verify(&BlockHeadersRangeRequest { anchor_hash, start_height: 5 }, 4));
+        assert!(!range.verify(&request, 3));
+
+        let mock_adapter = Arc::new(MockPeerManagerAdapter::default());
+        let mut header_sync = HeaderSync::new(
+            Clock::real(),
+            mock_adapter.as_multi_sender(),
+            Duration::seconds(10),
+            Duration::seconds(2),
+            Duration::seconds(120),
+            1_000_000_000,
+            MutableConfigValue::new(None, "expected_shutdown"),
+            1,
+        );
+        let peer_id = PeerId::random();
+        header_sync.prefetched_ranges.insert(
+            4,
+            PrefetchedRange {
+                request,
+                peer_id: peer_id.clone(),
+                requested_at: Clock::real().now_utc(),
+                range: None,
+            },
+        );
+        // Ranges from other peers are ignored.
+        assert!(header_sync.receive_headers_range(Box::new(range.clone()), &PeerId::random()));
+        let mut invalid_range = range.clone();
+        invalid_range.headers.remove(1);
+        assert!(!header_sync.receive_headers_range(Box::new(invalid_range), &peer_id));
+        assert!(header_sync.receive_headers_range(Box::new(range), &peer_id));
+
+        let header_at =
+            |height| Tip::from_header(&chain.get_block_header_by_height(height).unwrap());
+        assert!(header_sync.take_prefetched_headers(&header_at(2)).is_none());
+        let (headers, from_peer) = header_sync.take_prefetched_headers(&header_at(5)).unwrap();
+        assert_eq!(headers.iter().map(|h| h.height()).collect::<Vec<_>>(), vec![6, 7]);
+        assert_eq!(from_peer, peer_id);
+        assert!(header_sync.prefetched_ranges.is_empty());
+    }
+
     #[test]
     fn test_sync_headers_fork_from_final_block() {
         let mock_adapter = Arc::new(MockPeerManagerAdapter::default());
@@ -552,6 +753,7 @@ mod test {
             Duration::seconds(120),
             1_000_000_000,
             MutableConfigValue::new(None, "expected_shutdown"),
+            0,
         );
         let (mut chain, _, _, signer) = setup(Clock::real());
         let (mut chain2, _, _, signer2) = setup(Clock::real());
@@ -664,6 +866,7 @@ mod test {
             Duration::seconds(3),
             25,
             MutableConfigValue::new(None, "expected_shutdown"),
+            0,
         );
 
         let set_syncing_peer = |header_sync: &mut HeaderSync| {
@@ -761,6 +964,7 @@ mod test {
             Duration::seconds(120),
             1_000_000_000,
             MutableConfigValue::new(None, "expected_shutdown"),
+            0,
         );
 
         let clock = FakeClock::new(Utc::UNIX_EPOCH);
@@ -10,7 +10,7 @@ use near_chain::types::RuntimeAdapter;
 use near_primitives::hash::CryptoHash;
 use near_primitives::state_part::PartId;
 use near_primitives::state_sync::{ShardStateSyncResponseHeader, StatePartKey};
-use near_primitives::types::ShardId;
+use near_primitives::types::{ProtocolVersion, ShardId};
 use near_store::{DBCol, Store};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -134,6 +134,7 @@ impl StateSyncDownloader {
         state_root: CryptoHash,
         num_state_parts: u64,
         part_id: u64,
+        protocol_version: ProtocolVersion,
         num_prior_attempts: usize,
         cancel: CancellationToken,
     ) -> BoxFuture<'static, Result<(), near_chain::Error>> {
@@ -178,6 +179,7 @@ impl StateSyncDownloader {
                     &state_root,
                     PartId { idx: part_id, total: num_state_parts },
                     &part,
+                    protocol_version,
                 ) {
                     let mut store_update = store.store_update();
                     let key = borsh::to_vec(&StatePartKey(sync_hash, shard_id, part_id)).unwrap();
@@ -78,6 +78,7 @@ pub(super) async fn run_state_sync_for_shard(
         )?;
     let epoch_id = *block_header.epoch_id();
     let shard_uid = epoch_manager.shard_id_to_uid(shard_id, &epoch_id)?;
+    let protocol_version = epoch_manager.get_epoch_protocol_version(&epoch_id)?;
     metrics::STATE_SYNC_PARTS_TOTAL
         .with_label_values(&[&shard_id.to_string()])
         .set(num_parts as i64);
@@ -106,6 +107,7 @@ pub(super) async fn run_state_sync_for_shard(
                     state_root,
                     num_parts,
                     part_id,
+                    protocol_version,
                     attempt_count,
                     cancel.clone(),
                 );
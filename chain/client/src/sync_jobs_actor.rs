@@ -43,7 +43,8 @@ impl SyncJobsActor {
 
     pub fn handle_block_catch_up_request(&mut self, msg: BlockCatchUpRequest) {
         tracing::debug!(target: "sync", ?msg);
-        let results = do_apply_chunks(msg.block_hash, msg.block_height, msg.work);
+        let results =
+            do_apply_chunks(&msg.apply_chunks_pool, msg.block_hash, msg.block_height, msg.work);
 
         self.client_sender.send(BlockCatchUpResponse {
             sync_hash: msg.sync_hash,
@@ -229,6 +229,8 @@ pub fn create_chunk(
             header.prev_outgoing_receipts_root(),
             header.congestion_info(),
             header.bandwidth_requests().cloned(),
+            header.prev_outgoing_bandwidth().cloned(),
+            header.prev_pre_state_root().copied(),
             &*signer,
             PROTOCOL_VERSION,
         )
@@ -307,10 +309,11 @@ pub fn run_catchup(
         client.run_catchup(highest_height_peers, &block_catch_up, None, &signer)?;
         let mut catchup_done = true;
         for msg in block_messages.write().unwrap().drain(..) {
-            let results = do_apply_chunks(msg.block_hash, msg.block_height, msg.work)
-                .into_iter()
-                .map(|res| res.1)
-                .collect_vec();
+            let results =
+                do_apply_chunks(&msg.apply_chunks_pool, msg.block_hash, msg.block_height, msg.work)
+                    .into_iter()
+                    .map(|res| res.1)
+                    .collect_vec();
             if let Some(CatchupState { catchup, .. }) =
                 client.catchup_state_syncs.get_mut(&msg.sync_hash)
             {
@@ -29,7 +29,8 @@ use near_chain::test_utils::{KeyValueRuntime, MockEpochManager, ValidatorSchedul
 use near_chain::types::{ChainConfig, RuntimeAdapter};
 use near_chain::{Chain, ChainGenesis, DoomslugThresholdMode};
 use near_chain_configs::{
-    ChunkDistributionNetworkConfig, ClientConfig, MutableConfigValue, ReshardingConfig,
+    BlockTimestampConfig, CatchupConfig, ChunkDistributionNetworkConfig, ClientConfig,
+    MutableConfigValue, ReshardingConfig,
 };
 use near_chunks::adapter::ShardsManagerRequestFromClient;
 use near_chunks::client::ShardsManagerResponse;
@@ -120,6 +121,7 @@ pub fn setup(
         transaction_validity_period,
         epoch_length,
         protocol_version: PROTOCOL_VERSION,
+        protocol_upgrade_heights: vec![],
     };
 
     let signer = MutableConfigValue::new(
@@ -204,6 +206,7 @@ pub fn setup(
         MutableConfigValue::new(validator_signer, "validator_signer"),
         store,
         config.chunk_request_retry_period,
+        config.chunk_part_distribution.clone(),
     );
     let shards_manager_adapter = shards_manager_addr.with_auto_span_context();
     shards_manager_adapter_for_client.bind(shards_manager_adapter.clone());
@@ -248,6 +251,7 @@ pub fn setup_only_view(
         transaction_validity_period,
         epoch_length,
         protocol_version: PROTOCOL_VERSION,
+        protocol_upgrade_heights: vec![],
     };
 
     let doomslug_threshold_mode = if enable_doomslug {
@@ -265,6 +269,10 @@ pub fn setup_only_view(
         ChainConfig {
             save_trie_changes: true,
             background_migration_threads: 1,
+            apply_chunks_threads: 2,
+            catchup: CatchupConfig::default(),
+            max_block_clock_skew: BlockTimestampConfig::default().max_clock_skew,
+            shadow_validation: false,
             resharding_config: MutableConfigValue::new(
                 ReshardingConfig::default(),
                 "resharding_config",
@@ -465,12 +473,14 @@ fn process_peer_manager_message_default(
                 connection_established_time: near_async::time::Instant::now(),
                 peer_type: PeerType::Outbound,
                 nonce: 3,
+                reputation: 0,
             })
             .collect();
         let peers2 = peers.iter().filter_map(|it| it.full_peer_info.clone().into()).collect();
         let info = NetworkInfo {
             connected_peers: peers,
             tier1_connections: vec![],
+            reachability: Default::default(),
             num_connected_peers: key_pairs.len(),
             peer_max_count: key_pairs.len() as u32,
             highest_height_peers: peers2,
@@ -532,6 +542,32 @@ fn process_peer_manager_message_default(
                 });
             });
         }
+        NetworkRequests::PartialEncodedChunkRequestBatch { target, requests, .. } => {
+            send_chunks(
+                connectors,
+                validators.iter().map(|s| Some(s.clone())).enumerate(),
+                target.account_id.as_ref().map(|s| s.clone()),
+                drop_chunks,
+                |c| {
+                    c.send(
+                        ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequestBatch {
+                            partial_encoded_chunk_requests: requests.clone(),
+                            route_back: my_address,
+                        },
+                    );
+                },
+            );
+        }
+        NetworkRequests::PartialEncodedChunkResponseBatch { route_back, responses } => {
+            send_chunks(connectors, addresses.iter().enumerate(), route_back, drop_chunks, |c| {
+                for response in responses {
+                    c.send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
+                        partial_encoded_chunk_response: response.clone(),
+                        received_time: Instant::now(),
+                    });
+                }
+            });
+        }
         NetworkRequests::PartialEncodedChunkMessage { account_id, partial_encoded_chunk } => {
             send_chunks(
                 connectors,
@@ -539,9 +575,10 @@ fn process_peer_manager_message_default(
                 account_id.clone(),
                 drop_chunks,
                 |c| {
-                    c.send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(
-                        partial_encoded_chunk.clone().into(),
-                    ));
+                    c.send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
+                        partial_encoded_chunk: partial_encoded_chunk.clone().into(),
+                        peer_id: PeerId::random(),
+                    });
                 },
             );
         }
@@ -558,6 +595,19 @@ fn process_peer_manager_message_default(
                 },
             );
         }
+        NetworkRequests::PartialEncodedChunkForwardAck { account_id, ack } => {
+            send_chunks(
+                connectors,
+                validators.iter().cloned().enumerate(),
+                account_id.clone(),
+                drop_chunks,
+                |c| {
+                    c.send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForwardAck(
+                        ack.clone(),
+                    ));
+                },
+            );
+        }
         NetworkRequests::BlockRequest { hash, peer_id } => {
             for (i, peer_info) in key_pairs.iter().enumerate() {
                 let peer_id = peer_id.clone();
@@ -1118,6 +1168,10 @@ pub fn setup_synchronous_shards_manager(
         ChainConfig {
             save_trie_changes: true,
             background_migration_threads: 1,
+            apply_chunks_threads: 2,
+            catchup: CatchupConfig::default(),
+            max_block_clock_skew: BlockTimestampConfig::default().max_clock_skew,
+            shadow_validation: false,
             resharding_config: MutableConfigValue::new(
                 ReshardingConfig::default(),
                 "resharding_config",
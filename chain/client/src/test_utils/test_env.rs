@@ -26,6 +26,7 @@ use near_primitives::block::Block;
 use near_primitives::epoch_info::RngSeed;
 use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
+use near_primitives::network::PeerId;
 use near_primitives::sharding::{ChunkHash, PartialEncodedChunk};
 use near_primitives::stateless_validation::state_witness::ChunkStateWitness;
 use near_primitives::stateless_validation::ChunkProductionKey;
@@ -223,7 +224,8 @@ impl TestEnv {
 
                 keep_going |= network_adapter.handle_filtered(|request| match request {
                     PeerManagerMessageRequest::NetworkRequests(
-                        NetworkRequests::PartialEncodedChunkRequest { .. },
+                        NetworkRequests::PartialEncodedChunkRequest { .. }
+                        | NetworkRequests::PartialEncodedChunkRequestBatch { .. },
                     ) => {
                         self.process_partial_encoded_chunk_request(i, request);
                         None
@@ -236,9 +238,10 @@ impl TestEnv {
                     ) => {
                         let partial_encoded_chunk =
                             PartialEncodedChunk::from(partial_encoded_chunk);
-                        let message = ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(
+                        let message = ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
                             partial_encoded_chunk,
-                        );
+                            peer_id: PeerId::random(),
+                        };
                         self.shards_manager(&account_id).send(message);
                         None
                     }
@@ -252,6 +255,16 @@ impl TestEnv {
                         self.shards_manager(&account_id).send(message);
                         None
                     }
+                    PeerManagerMessageRequest::NetworkRequests(
+                        NetworkRequests::PartialEncodedChunkForwardAck { account_id, ack },
+                    ) => {
+                        let message =
+                            ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForwardAck(
+                                ack,
+                            );
+                        self.shards_manager(&account_id).send(message);
+                        None
+                    }
                     _ => Some(request),
                 });
             }
@@ -266,17 +279,24 @@ impl TestEnv {
         }
     }
 
-    /// Send the PartialEncodedChunkRequest to the target client, get response and process the response
+    /// Send the PartialEncodedChunkRequest to the target client, get response and process the response.
+    /// The requests of a PartialEncodedChunkRequestBatch are sent one by one.
     pub fn process_partial_encoded_chunk_request(
         &mut self,
         id: usize,
         request: PeerManagerMessageRequest,
     ) {
-        if let PeerManagerMessageRequest::NetworkRequests(
-            NetworkRequests::PartialEncodedChunkRequest { target, request, .. },
-        ) = request
-        {
-            let target_id = self.account_indices.index(&target.account_id.unwrap());
+        let (target, requests) = match request {
+            PeerManagerMessageRequest::NetworkRequests(
+                NetworkRequests::PartialEncodedChunkRequest { target, request, .. },
+            ) => (target, vec![request]),
+            PeerManagerMessageRequest::NetworkRequests(
+                NetworkRequests::PartialEncodedChunkRequestBatch { target, requests, .. },
+            ) => (target, requests),
+            request => panic!("The request is not a PartialEncodedChunk request {:?}", request),
+        };
+        let target_id = self.account_indices.index(&target.account_id.unwrap());
+        for request in requests {
             let response = self.get_partial_encoded_chunk_response(target_id, request);
             tracing::info!("Got response for PartialEncodedChunkRequest: {:?}", response);
             if let Some(response) = response {
@@ -287,8 +307,6 @@ impl TestEnv {
                     },
                 );
             }
-        } else {
-            panic!("The request is not a PartialEncodedChunk request {:?}", request);
         }
     }
 
@@ -10,6 +10,7 @@ use futures::FutureExt;
 use near_async::messaging::CanSend;
 use near_async::time::Clock;
 use near_network::shards_manager::ShardsManagerRequestFromNetwork;
+use near_primitives::network::PeerId;
 use near_primitives::types::ShardIndex;
 use rand::{thread_rng, Rng};
 
@@ -95,9 +96,10 @@ fn slow_test_repro_1183() {
                             for (i, name) in validators.iter().enumerate() {
                                 if name == account_id {
                                     connectors1.write().unwrap()[i].shards_manager_adapter.send(
-                                        ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(
-                                            partial_encoded_chunk.clone().into(),
-                                        ),
+                                        ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
+                                            partial_encoded_chunk: partial_encoded_chunk.clone().into(),
+                                            peer_id: PeerId::random(),
+                                        },
                                     );
                                 }
                             }
@@ -88,6 +88,9 @@ fn test_bad_shard_id() {
         chunk.prev_validator_proposals().collect(),
         congestion_info,
         chunk.bandwidth_requests().cloned(),
+        chunk.prev_outgoing_bandwidth().cloned(),
+        chunk.prev_pre_state_root().copied(),
+        chunk.num_data_parts().map(|num_data_parts| num_data_parts as u64),
         &validator_signer,
     );
     modified_chunk.height_included = 2;
@@ -236,6 +239,9 @@ fn test_bad_congestion_info_impl(mode: BadCongestionInfoMode) {
         chunk.prev_validator_proposals().collect(),
         Some(congestion_info),
         chunk.bandwidth_requests().cloned(),
+        chunk.prev_outgoing_bandwidth().cloned(),
+        chunk.prev_pre_state_root().copied(),
+        chunk.num_data_parts().map(|num_data_parts| num_data_parts as u64),
         &validator_signer,
     );
     modified_chunk_header.height_included = 2;
@@ -246,12 +252,13 @@ fn test_bad_congestion_info_impl(mode: BadCongestionInfoMode) {
     let prev_block_hash = block.header().prev_hash();
     let client = &env.clients[0];
     let prev_chunk_extra = client.chain.get_chunk_extra(prev_block_hash, &shard_uid).unwrap();
+    let prev_chunk_header = client.chain.get_block(prev_block_hash).unwrap().chunks()[0].clone();
     let result: Result<(), near_chain::Error> = validate_chunk_with_chunk_extra(
         &client.chain.chain_store,
         client.epoch_manager.as_ref(),
         prev_block_hash,
         &prev_chunk_extra,
-        1,
+        &prev_chunk_header,
         &modified_chunk,
     );
 
@@ -9,7 +9,9 @@ use actix::{Addr, SyncArbiter};
 use near_async::actix_wrapper::SyncActixWrapper;
 use near_async::messaging::{Actor, CanSend, Handler};
 use near_async::time::{Clock, Duration, Instant};
+use near_chain::stateless_validation::chunk_validation::reexecute_challenged_state_witness;
 use near_chain::types::{RuntimeAdapter, Tip};
+use near_chain::validate::validate_challenge;
 use near_chain::{
     get_epoch_block_producers_view, Chain, ChainGenesis, ChainStoreAccess, DoomslugThresholdMode,
     MerkleProofAccess,
@@ -18,20 +20,22 @@ use near_chain::{
 use near_chain_configs::{ClientConfig, MutableValidatorSigner, ProtocolConfigView};
 use near_chain_primitives::error::EpochErrorResultToChainError;
 use near_client_primitives::types::{
-    Error, GetBlock, GetBlockError, GetBlockProof, GetBlockProofError, GetBlockProofResponse,
-    GetBlockWithMerkleTree, GetChunkError, GetExecutionOutcome, GetExecutionOutcomeError,
+    ClearInvalidBlocks, ClearInvalidBlocksError, Error, GetBlock, GetBlockError, GetBlockProof,
+    GetBlockProofError, GetBlockProofResponse, GetBlockWithMerkleTree, GetChallengeStatus,
+    GetChallengeStatusError, GetChunkError, GetExecutionOutcome, GetExecutionOutcomeError,
     GetExecutionOutcomesForBlock, GetGasPrice, GetGasPriceError, GetMaintenanceWindows,
     GetMaintenanceWindowsError, GetNextLightClientBlockError, GetProtocolConfig,
     GetProtocolConfigError, GetReceipt, GetReceiptError, GetSplitStorageInfo,
     GetSplitStorageInfoError, GetStateChangesError, GetStateChangesWithCauseInBlock,
-    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfoError, Query, QueryError,
-    TxStatus, TxStatusError,
+    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorEndorsementStats,
+    GetValidatorInfoError, Query, QueryError, ReplayBlock, ReplayBlockError, TraceTransaction,
+    TraceTransactionError, TxStatus, TxStatusError,
 };
 use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::EpochManagerAdapter;
 use near_network::client::{
-    AnnounceAccountRequest, BlockHeadersRequest, BlockRequest, StateRequestHeader,
-    StateRequestPart, StateResponse, TxStatusRequest, TxStatusResponse,
+    AnnounceAccountRequest, BlockHeadersRangeRequestMessage, BlockHeadersRequest, BlockRequest,
+    StateRequestHeader, StateRequestPart, StateResponse, TxStatusRequest, TxStatusResponse,
 };
 use near_network::types::{
     NetworkRequests, PeerManagerAdapter, PeerManagerMessageRequest, ReasonForBan,
@@ -39,7 +43,10 @@ use near_network::types::{
 };
 use near_performance_metrics_macros::perf;
 use near_primitives::block::{Block, BlockHeader};
+use near_primitives::block_headers_range::BlockHeadersRange;
+use near_primitives::challenge::ChallengeBody;
 use near_primitives::epoch_info::EpochInfo;
+use near_primitives::errors::EpochError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{merklize, PartialMerkleTree};
 use near_primitives::network::AnnounceAccount;
@@ -57,11 +64,12 @@ use near_primitives::types::{
 use near_primitives::validator_signer::ValidatorSigner;
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
 use near_primitives::views::{
-    BlockView, ChunkView, EpochValidatorInfo, ExecutionOutcomeWithIdView, ExecutionStatusView,
+    BlockReplayView, BlockView, ChallengeStatusView, ChunkView, EpochValidatorInfo,
+    ExecutionOutcomeWithIdView, ExecutionStatusView, ExecutionTraceEventView,
     FinalExecutionOutcomeView, FinalExecutionOutcomeViewEnum, FinalExecutionStatus, GasPriceView,
-    LightClientBlockView, MaintenanceWindowsView, QueryRequest, QueryResponse, ReceiptView,
-    SignedTransactionView, SplitStorageInfoView, StateChangesKindsView, StateChangesView,
-    TxExecutionStatus, TxStatusView,
+    InvalidBlockView, LightClientBlockView, MaintenanceWindowsView, QueryRequest, QueryResponse,
+    ReceiptView, SignedTransactionView, SplitStorageInfoView, StateChangesKindsView,
+    StateChangesView, TxExecutionStatus, TxStatusView, ValidatorEndorsementStatsView,
 };
 use near_store::flat::{FlatStorageReadyStatus, FlatStorageStatus};
 use near_store::{DBCol, COLD_HEAD_KEY, FINAL_HEAD_KEY, HEAD_KEY};
@@ -369,6 +377,9 @@ impl ViewClientActorInner {
             QueryRequest::ViewAccessKeyList { account_id, .. } => account_id,
             QueryRequest::CallFunction { account_id, .. } => account_id,
             QueryRequest::ViewCode { account_id, .. } => account_id,
+            QueryRequest::ViewStorageSponsorship { account_id } => account_id,
+            QueryRequest::ViewContractSourceMetadata { account_id } => account_id,
+            QueryRequest::ViewTokenBalance { account_id, .. } => account_id,
         };
         let shard_id = self
             .epoch_manager
@@ -900,6 +911,31 @@ impl Handler<GetValidatorInfo> for ViewClientActorInner {
     }
 }
 
+impl Handler<GetValidatorEndorsementStats> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: GetValidatorEndorsementStats,
+    ) -> Result<ValidatorEndorsementStatsView, GetValidatorInfoError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetValidatorEndorsementStats"])
+            .start_timer();
+        let epoch_id = match msg.epoch_id {
+            Some(epoch_id) => epoch_id,
+            None => self.chain.final_head()?.epoch_id,
+        };
+        let epoch_config = self.epoch_manager.get_epoch_config(&epoch_id).into_chain_error()?;
+        let stats = self.chain.get_validator_endorsement_stats(&epoch_id, &msg.account_id)?;
+        Ok(ValidatorEndorsementStatsView::new(
+            msg.account_id,
+            epoch_id,
+            epoch_config.chunk_validator_only_kickout_threshold,
+            stats,
+        ))
+    }
+}
+
 impl Handler<GetValidatorOrdered> for ViewClientActorInner {
     #[perf]
     fn handle(
@@ -1194,6 +1230,93 @@ impl Handler<GetReceipt> for ViewClientActorInner {
     }
 }
 
+impl Handler<TraceTransaction> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: TraceTransaction,
+    ) -> Result<Vec<ExecutionTraceEventView>, TraceTransactionError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["TraceTransaction"])
+            .start_timer();
+        let outcome = match self.chain.get_final_transaction_result(&msg.tx_hash) {
+            Ok(outcome) => outcome,
+            Err(near_chain::Error::DBNotFoundErr(_)) => {
+                return Err(TraceTransactionError::UnknownTransaction(msg.tx_hash));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let receipt_ids: HashSet<CryptoHash> =
+            outcome.receipts_outcome.iter().map(|outcome| outcome.id).collect();
+        // Receipts executed in the same chunk are traced with a single
+        // re-application of the chunk.
+        let mut chunks = BTreeSet::new();
+        for receipt_outcome in &outcome.receipts_outcome {
+            let header = self.chain.get_block_header(&receipt_outcome.block_hash)?;
+            let shard_id = self
+                .epoch_manager
+                .account_id_to_shard_id(&receipt_outcome.outcome.executor_id, header.epoch_id())
+                .into_chain_error()?;
+            chunks.insert((header.height(), receipt_outcome.block_hash, shard_id));
+        }
+        let mut events = vec![];
+        for (_, block_hash, shard_id) in chunks {
+            events.extend(
+                self.chain
+                    .trace_chunk_application(&block_hash, shard_id)?
+                    .into_iter()
+                    .filter(|event| receipt_ids.contains(event.receipt_id())),
+            );
+        }
+        Ok(events)
+    }
+}
+
+impl Handler<ReplayBlock> for ViewClientActorInner {
+    #[perf]
+    fn handle(&mut self, msg: ReplayBlock) -> Result<BlockReplayView, ReplayBlockError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer =
+            metrics::VIEW_CLIENT_MESSAGE_TIME.with_label_values(&["ReplayBlock"]).start_timer();
+        match self.chain.get_block_header(&msg.block_hash) {
+            Ok(_) => {}
+            Err(near_chain::Error::DBNotFoundErr(_)) => {
+                return Err(ReplayBlockError::UnknownBlock(msg.block_hash));
+            }
+            Err(err) => return Err(err.into()),
+        }
+        Ok(self.chain.replay_block(&msg.block_hash, msg.shard_id)?)
+    }
+}
+
+impl Handler<ClearInvalidBlocks> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: ClearInvalidBlocks,
+    ) -> Result<Vec<InvalidBlockView>, ClearInvalidBlocksError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["ClearInvalidBlocks"])
+            .start_timer();
+        let mut cleared = vec![];
+        for block_hash in msg.block_hashes {
+            if let Some(invalid_block) =
+                self.chain.chain_store().clear_invalid_block(&block_hash)?
+            {
+                tracing::info!(target: "client", ?block_hash, reason = %invalid_block.reason, "Cleared invalid block");
+                cleared.push(InvalidBlockView {
+                    block_hash,
+                    height: invalid_block.height,
+                    reason: invalid_block.reason,
+                });
+            }
+        }
+        Ok(cleared)
+    }
+}
+
 impl Handler<GetBlockProof> for ViewClientActorInner {
     #[perf]
     fn handle(&mut self, msg: GetBlockProof) -> Result<GetBlockProofResponse, GetBlockProofError> {
@@ -1343,6 +1466,28 @@ impl Handler<BlockHeadersRequest> for ViewClientActorInner {
     }
 }
 
+impl Handler<BlockHeadersRangeRequestMessage> for ViewClientActorInner {
+    #[perf]
+    fn handle(&mut self, msg: BlockHeadersRangeRequestMessage) -> Option<Box<BlockHeadersRange>> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["BlockHeadersRangeRequest"])
+            .start_timer();
+        let BlockHeadersRangeRequestMessage(request) = msg;
+
+        if self.adv.disable_header_sync() {
+            return None;
+        }
+        match self.chain.retrieve_headers_range(&request, sync::header::MAX_BLOCK_HEADERS) {
+            Ok(range) => range.map(Box::new),
+            Err(err) => {
+                tracing::debug!(target: "client", ?request, ?err, "Failed to retrieve headers range");
+                None
+            }
+        }
+    }
+}
+
 impl Handler<StateRequestHeader> for ViewClientActorInner {
     #[perf]
     fn handle(&mut self, msg: StateRequestHeader) -> Option<StateResponse> {
@@ -1552,6 +1697,61 @@ impl Handler<GetMaintenanceWindows> for ViewClientActorInner {
     }
 }
 
+impl Handler<GetChallengeStatus> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: GetChallengeStatus,
+    ) -> Result<ChallengeStatusView, GetChallengeStatusError> {
+        tracing::debug!(target: "client", challenge_hash = ?msg.challenge.hash, "GetChallengeStatus");
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetChallengeStatus"])
+            .start_timer();
+        let head = self.chain.head()?;
+        let accounts_to_slash = match validate_challenge(
+            self.epoch_manager.as_ref(),
+            self.runtime.as_ref(),
+            &head.epoch_id,
+            &head.last_block_hash,
+            &msg.challenge,
+        )
+        .and_then(|result| {
+            if let ChallengeBody::InvalidStateWitness(invalid_state_witness) = &msg.challenge.body {
+                reexecute_challenged_state_witness(
+                    invalid_state_witness,
+                    &self.chain,
+                    self.epoch_manager.as_ref(),
+                    self.runtime.as_ref(),
+                )?;
+            }
+            Ok(result)
+        }) {
+            Ok((_, accounts_to_slash)) => accounts_to_slash,
+            Err(err) => {
+                // Only invalid challenge data is a rejection, e.g. an unknown
+                // block is reported as an error of the request.
+                let Some(code) = err.validation_error() else {
+                    return Err(err.into());
+                };
+                return Ok(ChallengeStatusView::Rejected { reason: err.to_string(), code });
+            }
+        };
+        for account_id in &accounts_to_slash {
+            match self.epoch_manager.get_validator_by_account_id(
+                &head.epoch_id,
+                &head.last_block_hash,
+                account_id,
+            ) {
+                Ok((_, false)) => return Ok(ChallengeStatusView::Accepted),
+                // Accounts which are not validators anymore can't be slashed either.
+                Ok((_, true)) | Err(EpochError::NotAValidator(..)) => {}
+                Err(err) => return Err(near_chain_primitives::Error::from(err).into()),
+            }
+        }
+        Ok(ChallengeStatusView::AlreadySlashed)
+    }
+}
+
 impl Handler<GetSplitStorageInfo> for ViewClientActorInner {
     fn handle(
         &mut self,
@@ -289,6 +289,13 @@ pub trait EpochManagerAdapter: Send + Sync {
         random_value: CryptoHash,
     ) -> Result<StoreUpdate, EpochError>;
 
+    /// Makes the epoch information of a block still being applied available,
+    /// see `EpochManager::add_optimistic_block_info`.
+    fn add_optimistic_block_info(&self, block_info: BlockInfo) -> Result<bool, EpochError>;
+
+    /// Removes the information of a block which failed to apply.
+    fn remove_optimistic_block_info(&self, block_hash: &CryptoHash);
+
     /// Epoch active protocol version.
     fn get_epoch_protocol_version(&self, epoch_id: &EpochId)
         -> Result<ProtocolVersion, EpochError>;
@@ -827,6 +834,16 @@ impl EpochManagerAdapter for EpochManagerHandle {
         epoch_manager.add_validator_proposals(block_info, random_value)
     }
 
+    fn add_optimistic_block_info(&self, block_info: BlockInfo) -> Result<bool, EpochError> {
+        let mut epoch_manager = self.write();
+        epoch_manager.add_optimistic_block_info(block_info)
+    }
+
+    fn remove_optimistic_block_info(&self, block_hash: &CryptoHash) {
+        let mut epoch_manager = self.write();
+        epoch_manager.remove_optimistic_block_info(block_hash)
+    }
+
     fn get_epoch_protocol_version(
         &self,
         epoch_id: &EpochId,
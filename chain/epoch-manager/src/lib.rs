@@ -5,6 +5,7 @@ use itertools::Itertools;
 use near_cache::SyncLruCache;
 use near_chain_configs::{Genesis, GenesisConfig};
 use near_primitives::block::{BlockHeader, Tip};
+use near_primitives::challenge::SlashingSeverity;
 use near_primitives::epoch_block_info::{BlockInfo, SlashState};
 use near_primitives::epoch_info::EpochInfo;
 use near_primitives::epoch_manager::{
@@ -155,6 +156,10 @@ pub struct EpochManager {
     epochs_info: SyncLruCache<EpochId, Arc<EpochInfo>>,
     /// Cache of block information.
     blocks_info: SyncLruCache<CryptoHash, Arc<BlockInfo>>,
+    /// Information of blocks still being applied, added by
+    /// [`Self::add_optimistic_block_info`] and removed once the block is
+    /// recorded or fails to apply.
+    optimistic_blocks_info: HashMap<CryptoHash, Arc<BlockInfo>>,
     /// Cache of epoch id to epoch start height
     epoch_id_to_start: SyncLruCache<EpochId, BlockHeight>,
     /// Epoch validators ordered by `block_producer_settlement`.
@@ -324,6 +329,7 @@ impl EpochManager {
             genesis_num_block_producer_seats,
             epochs_info: SyncLruCache::new(EPOCH_CACHE_SIZE),
             blocks_info: SyncLruCache::new(BLOCK_CACHE_SIZE),
+            optimistic_blocks_info: HashMap::new(),
             epoch_id_to_start: SyncLruCache::new(EPOCH_CACHE_SIZE),
             epoch_validators_ordered: SyncLruCache::new(EPOCH_CACHE_SIZE),
             epoch_validators_ordered_unique: SyncLruCache::new(EPOCH_CACHE_SIZE),
@@ -823,7 +829,7 @@ impl EpochManager {
             ..
         } = epoch_summary;
 
-        let (validator_reward, minted_amount) = {
+        let (mut validator_reward, mut minted_amount) = {
             let last_epoch_last_block_hash =
                 *self.get_block_info(block_info.epoch_first_block())?.prev_hash();
             let last_block_in_last_epoch = self.get_block_info(&last_epoch_last_block_hash)?;
@@ -864,6 +870,12 @@ impl EpochManager {
                 online_thresholds,
             )
         };
+        // Challenger rewards are minted together with validator rewards and distributed at the
+        // start of the next epoch.
+        for (account_id, reward) in self.compute_challenger_rewards(last_block_hash)? {
+            *validator_reward.entry(account_id).or_default() += reward;
+            minted_amount += reward;
+        }
         let next_next_epoch_config = self.config.for_protocol_version(next_next_epoch_version);
         let next_epoch_version = next_epoch_info.protocol_version();
         let next_shard_layout = self.config.for_protocol_version(next_epoch_version).shard_layout;
@@ -915,6 +927,7 @@ impl EpochManager {
     ) -> Result<StoreUpdate, EpochError> {
         let current_hash = *block_info.hash();
         let mut store_update = self.store.store_update();
+        self.optimistic_blocks_info.remove(&current_hash);
         // Check that we didn't record this block yet.
         if !self.has_block_info(&current_hash)? {
             if block_info.is_genesis() {
@@ -930,56 +943,7 @@ impl EpochManager {
                 )?;
             } else {
                 let prev_block_info = self.get_block_info(block_info.prev_hash())?;
-
-                let mut is_epoch_start = false;
-                if prev_block_info.is_genesis() {
-                    // This is first real block, starts the new epoch.
-                    *block_info.epoch_id_mut() = EpochId::default();
-                    *block_info.epoch_first_block_mut() = current_hash;
-                    is_epoch_start = true;
-                } else if self.is_next_block_in_next_epoch(&prev_block_info)? {
-                    // Current block is in the new epoch, finalize the one in prev_block.
-                    *block_info.epoch_id_mut() =
-                        self.get_next_epoch_id_from_info(&prev_block_info)?;
-                    *block_info.epoch_first_block_mut() = current_hash;
-                    is_epoch_start = true;
-                } else {
-                    // Same epoch as parent, copy epoch_id and epoch_start_height.
-                    *block_info.epoch_id_mut() = *prev_block_info.epoch_id();
-                    *block_info.epoch_first_block_mut() = *prev_block_info.epoch_first_block();
-                }
-                let epoch_info = self.get_epoch_info(block_info.epoch_id())?;
-
-                // Keep `slashed` from previous block if they are still in the epoch info stake change
-                // (e.g. we need to keep track that they are still slashed, because when we compute
-                // returned stake we are skipping account ids that are slashed in `stake_change`).
-                for (account_id, slash_state) in prev_block_info.slashed() {
-                    if is_epoch_start {
-                        if slash_state == &SlashState::DoubleSign
-                            || slash_state == &SlashState::Other
-                        {
-                            block_info
-                                .slashed_mut()
-                                .entry(account_id.clone())
-                                .or_insert(SlashState::AlreadySlashed);
-                        } else if epoch_info.stake_change().contains_key(account_id) {
-                            block_info
-                                .slashed_mut()
-                                .entry(account_id.clone())
-                                .or_insert_with(|| slash_state.clone());
-                        }
-                    } else {
-                        block_info
-                            .slashed_mut()
-                            .entry(account_id.clone())
-                            .and_modify(|e| {
-                                if let SlashState::Other = slash_state {
-                                    *e = SlashState::Other;
-                                }
-                            })
-                            .or_insert_with(|| slash_state.clone());
-                    }
-                }
+                let is_epoch_start = self.inherit_block_info(&mut block_info, &prev_block_info)?;
 
                 if is_epoch_start {
                     self.save_epoch_start(
@@ -1014,6 +978,74 @@ impl EpochManager {
         Ok(store_update)
     }
 
+    /// Sets the epoch of `block_info` and the fields it inherits from the
+    /// previous block. Returns whether the block starts a new epoch.
+    fn inherit_block_info(
+        &self,
+        block_info: &mut BlockInfo,
+        prev_block_info: &BlockInfo,
+    ) -> Result<bool, EpochError> {
+        let current_hash = *block_info.hash();
+        let mut is_epoch_start = false;
+        if prev_block_info.is_genesis() {
+            // This is first real block, starts the new epoch.
+            *block_info.epoch_id_mut() = EpochId::default();
+            *block_info.epoch_first_block_mut() = current_hash;
+            is_epoch_start = true;
+        } else if self.is_next_block_in_next_epoch(prev_block_info)? {
+            // Current block is in the new epoch, finalize the one in prev_block.
+            *block_info.epoch_id_mut() = self.get_next_epoch_id_from_info(prev_block_info)?;
+            *block_info.epoch_first_block_mut() = current_hash;
+            is_epoch_start = true;
+        } else {
+            // Same epoch as parent, copy epoch_id and epoch_start_height.
+            *block_info.epoch_id_mut() = *prev_block_info.epoch_id();
+            *block_info.epoch_first_block_mut() = *prev_block_info.epoch_first_block();
+        }
+        let epoch_info = self.get_epoch_info(block_info.epoch_id())?;
+
+        // Keep `slashed` from previous block if they are still in the epoch info stake change
+        // (e.g. we need to keep track that they are still slashed, because when we compute
+        // returned stake we are skipping account ids that are slashed in `stake_change`).
+        for (account_id, slash_state) in prev_block_info.slashed() {
+            if is_epoch_start {
+                if slash_state != &SlashState::AlreadySlashed {
+                    block_info
+                        .slashed_mut()
+                        .entry(account_id.clone())
+                        .or_insert(SlashState::AlreadySlashed);
+                } else if epoch_info.stake_change().contains_key(account_id) {
+                    block_info
+                        .slashed_mut()
+                        .entry(account_id.clone())
+                        .or_insert_with(|| slash_state.clone());
+                }
+            } else {
+                block_info
+                    .slashed_mut()
+                    .entry(account_id.clone())
+                    .and_modify(|e| {
+                        if let SlashState::Other = slash_state {
+                            *e = SlashState::Other;
+                        }
+                    })
+                    .or_insert_with(|| slash_state.clone());
+            }
+        }
+        // Challengers are only rewarded once, at the end of the epoch in which the
+        // challenge was included, so they are not kept across epochs.
+        if !is_epoch_start {
+            if let (Some(prev_challengers), Some(challengers)) =
+                (prev_block_info.challengers(), block_info.challengers_mut())
+            {
+                for (account_id, challenger) in prev_challengers {
+                    challengers.entry(account_id.clone()).or_insert_with(|| challenger.clone());
+                }
+            }
+        }
+        Ok(is_epoch_start)
+    }
+
     /// Given epoch id and height, returns validator information that suppose to produce
     /// the block at that height. We don't require caller to know about EpochIds.
     pub fn get_block_producer_info(
@@ -1373,7 +1405,7 @@ impl EpochManager {
     ///
     /// # Returns
     /// If successful, a triple of (hashmap of account id to max of stakes in the past three epochs,
-    /// validator rewards in the last epoch, double sign slashing for the past epoch).
+    /// validator rewards in the last epoch, partial slashing for the past epoch).
     pub fn compute_stake_return_info(
         &self,
         last_block_hash: &CryptoHash,
@@ -1425,20 +1457,23 @@ impl EpochManager {
                 vec![prev_prev_stake, prev_stake, new_stake].into_iter().max().unwrap();
             stake_info.insert(account_id.clone(), max_of_stakes);
         }
-        let slashing_info = self.compute_double_sign_slashing_info(last_block_hash)?;
+        let slashing_info = self.compute_slashing_info(last_block_hash)?;
         debug!(target: "epoch_manager", "stake_info: {:?}, validator_reward: {:?}", stake_info, validator_reward);
         Ok((stake_info, validator_reward, slashing_info))
     }
 
-    /// Compute slashing information. Returns a hashmap of account id to slashed amount for double sign
-    /// slashing.
-    fn compute_double_sign_slashing_info(
+    /// Compute slashing information. Returns a hashmap of account id to slashed amount for
+    /// misbehavior which is partially slashed at the end of the epoch. Slashed fraction of stake
+    /// depends on the kind of misbehavior and is configured by `EpochConfig::slashing_fractions`.
+    fn compute_slashing_info(
         &self,
         last_block_hash: &CryptoHash,
     ) -> Result<HashMap<AccountId, Balance>, EpochError> {
         let last_block_info = self.get_block_info(last_block_hash)?;
         let epoch_id = self.get_epoch_id(last_block_hash)?;
         let epoch_info = self.get_epoch_info(&epoch_id)?;
+        let slashing_fractions =
+            self.config.for_protocol_version(epoch_info.protocol_version()).slashing_fractions;
         let total_stake: Balance = epoch_info.validators_iter().map(|v| v.stake()).sum();
         let total_slashed_stake: Balance = last_block_info
             .slashed()
@@ -1455,21 +1490,76 @@ impl EpochManager {
         let is_totally_slashed = total_slashed_stake * 3 >= total_stake;
         let mut res = HashMap::default();
         for (account_id, slash_state) in last_block_info.slashed() {
-            if let SlashState::DoubleSign = slash_state {
-                if let Some(&idx) = epoch_info.get_validator_id(account_id) {
-                    let stake = epoch_info.validator_stake(idx);
-                    let slashed_stake = if is_totally_slashed {
-                        stake
-                    } else {
-                        let stake = U256::from(stake);
-                        // 3 * (total_slashed_stake / total_stake) * stake
-                        (U256::from(3) * U256::from(total_slashed_stake) * stake
-                            / U256::from(total_stake))
-                        .as_u128()
-                    };
-                    res.insert(account_id.clone(), slashed_stake);
+            let severity = match slash_state {
+                SlashState::DoubleSign => SlashingSeverity::DoubleSign,
+                SlashState::InvalidChunk => SlashingSeverity::InvalidChunk,
+                SlashState::InvalidWitness => SlashingSeverity::InvalidWitness,
+                SlashState::AlreadySlashed | SlashState::Other => continue,
+            };
+            let Some(&idx) = epoch_info.get_validator_id(account_id) else {
+                continue;
+            };
+            let stake = epoch_info.validator_stake(idx);
+            let slashable_stake = if severity == SlashingSeverity::DoubleSign && !is_totally_slashed
+            {
+                let stake = U256::from(stake);
+                // 3 * (total_slashed_stake / total_stake) * stake
+                (U256::from(3) * U256::from(total_slashed_stake) * stake / U256::from(total_stake))
+                    .as_u128()
+            } else {
+                stake
+            };
+            let fraction = slashing_fractions.for_severity(severity);
+            let slashed_stake = (U256::from(slashable_stake)
+                * U256::from((*fraction.numer()).max(0) as u64)
+                / U256::from((*fraction.denom()).max(1) as u64))
+            .as_u128()
+            .min(stake);
+            res.insert(account_id.clone(), slashed_stake);
+        }
+        Ok(res)
+    }
+
+    /// Compute rewards of the accounts whose challenges led to slashing in the epoch ending with
+    /// `last_block_hash`. Each challenger receives `SlashingFractions::challenger_reward` of the
+    /// stake slashed because of its challenge. Returns a hashmap of challenger account id to reward.
+    fn compute_challenger_rewards(
+        &self,
+        last_block_hash: &CryptoHash,
+    ) -> Result<HashMap<AccountId, Balance>, EpochError> {
+        let last_block_info = self.get_block_info(last_block_hash)?;
+        let Some(challengers) = last_block_info.challengers() else {
+            return Ok(HashMap::default());
+        };
+        if challengers.is_empty() {
+            return Ok(HashMap::default());
+        }
+        let epoch_id = self.get_epoch_id(last_block_hash)?;
+        let epoch_info = self.get_epoch_info(&epoch_id)?;
+        let challenger_reward = self
+            .config
+            .for_protocol_version(epoch_info.protocol_version())
+            .slashing_fractions
+            .challenger_reward;
+        let partial_slashing_info = self.compute_slashing_info(last_block_hash)?;
+        let mut res: HashMap<AccountId, Balance> = HashMap::default();
+        for (account_id, challenger) in challengers {
+            let slashed_stake = match last_block_info.slashed().get(account_id) {
+                // Stake is slashed in full right away, the reward is based on the stake in the epoch.
+                Some(SlashState::Other) => epoch_info
+                    .get_validator_id(account_id)
+                    .map_or(0, |id| epoch_info.validator_stake(*id)),
+                Some(slash_state) if slash_state.is_partial() => {
+                    partial_slashing_info.get(account_id).copied().unwrap_or(0)
                 }
-            }
+                _ => continue,
+            };
+            let reward = (U256::from(slashed_stake)
+                * U256::from((*challenger_reward.numer()).max(0) as u64)
+                / U256::from((*challenger_reward.denom()).max(1) as u64))
+            .as_u128()
+            .min(slashed_stake);
+            *res.entry(challenger.clone()).or_default() += reward;
         }
         Ok(res)
     }
@@ -1528,6 +1618,7 @@ impl EpochManager {
                         shards_produced.sort();
                         // TODO: Compute the set of shards validated.
                         let shards_endorsed = vec![];
+                        let metadata = info.metadata().cloned();
                         let (account_id, public_key, stake) = info.destructure();
                         Ok(CurrentEpochValidatorInfo {
                             is_slashed: false, // currently there is no slashing
@@ -1554,6 +1645,7 @@ impl EpochManager {
                             num_expected_endorsements_per_shard: vec![0; shards_endorsed.len()],
                             shards_produced,
                             shards_endorsed,
+                            metadata,
                         })
                     })
                     .collect::<Result<Vec<CurrentEpochValidatorInfo>, EpochError>>()?;
@@ -1616,6 +1708,7 @@ impl EpochManager {
                             })
                             .collect_vec();
                         shards_endorsed.sort();
+                        let metadata = info.metadata().cloned();
                         let (account_id, public_key, stake) = info.destructure();
                         Ok(CurrentEpochValidatorInfo {
                             is_slashed: false, // currently there is no slashing
@@ -1662,6 +1755,7 @@ impl EpochManager {
                                 .collect(),
                             shards_produced,
                             shards_endorsed,
+                            metadata,
                         })
                     })
                     .collect::<Result<Vec<CurrentEpochValidatorInfo>, EpochError>>()?;
@@ -1694,8 +1788,9 @@ impl EpochManager {
                     .into_iter()
                     .collect::<Vec<ShardId>>();
                 shards.sort();
+                let metadata = info.metadata().cloned();
                 let (account_id, public_key, stake) = info.destructure();
-                NextEpochValidatorInfo { account_id, public_key, stake, shards }
+                NextEpochValidatorInfo { account_id, public_key, stake, shards, metadata }
             })
             .collect();
         let prev_epoch_kickout = next_epoch_info
@@ -1739,6 +1834,39 @@ impl EpochManager {
         self.record_block_info(block_info, rng_seed)
     }
 
+    /// Makes the epoch information of a block available in memory before the
+    /// block is applied and recorded with [`Self::add_validator_proposals`],
+    /// so that the next block can be produced optimistically. Only done for
+    /// blocks which neither start nor end an epoch, returns whether the
+    /// information is available.
+    pub fn add_optimistic_block_info(
+        &mut self,
+        mut block_info: BlockInfo,
+    ) -> Result<bool, EpochError> {
+        let block_hash = *block_info.hash();
+        if self.has_block_info(&block_hash)? {
+            return Ok(true);
+        }
+        let prev_block_info = self.get_block_info(block_info.prev_hash())?;
+        if prev_block_info.is_genesis() || self.is_next_block_in_next_epoch(&prev_block_info)? {
+            return Ok(false);
+        }
+        self.inherit_block_info(&mut block_info, &prev_block_info)?;
+        let block_info = Arc::new(block_info);
+        self.optimistic_blocks_info.insert(block_hash, block_info.clone());
+        if self.is_next_block_in_next_epoch(&block_info)? {
+            self.optimistic_blocks_info.remove(&block_hash);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Removes the information added by [`Self::add_optimistic_block_info`]
+    /// for a block which failed to apply.
+    pub fn remove_optimistic_block_info(&mut self, block_hash: &CryptoHash) {
+        self.optimistic_blocks_info.remove(block_hash);
+    }
+
     /// Compare two epoch ids based on their start height. This works because finality gadget
     /// guarantees that we cannot have two different epochs on two forks
     pub fn compare_epoch_id(
@@ -1922,11 +2050,19 @@ impl EpochManager {
     /// EpochError::IOErr if storage returned an error
     /// EpochError::MissingBlock if block is not in storage
     pub fn get_block_info(&self, hash: &CryptoHash) -> Result<Arc<BlockInfo>, EpochError> {
-        self.blocks_info.get_or_try_put(*hash, |hash| {
+        let block_info = self.blocks_info.get_or_try_put(*hash, |hash| {
             self.store
                 .get_ser(DBCol::BlockInfo, hash.as_ref())?
                 .ok_or(EpochError::MissingBlock(*hash))
-        })
+        });
+        match block_info {
+            Err(EpochError::MissingBlock(_)) => self
+                .optimistic_blocks_info
+                .get(hash)
+                .cloned()
+                .ok_or(EpochError::MissingBlock(*hash)),
+            block_info => block_info,
+        }
     }
 
     fn save_block_info(
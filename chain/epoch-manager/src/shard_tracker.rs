@@ -259,6 +259,7 @@ mod tests {
             minimum_stake_ratio: Ratio::new(160i32, 1_000_000i32),
             chunk_producer_assignment_changes_limit: 5,
             shuffle_shard_assignment_for_chunk_producers: false,
+            slashing_fractions: Default::default(),
             validator_max_kickout_stake_perc: 100,
         };
         let reward_calculator = RewardCalculator {
@@ -158,6 +158,7 @@ pub fn epoch_config_with_production_config(
         minimum_stake_ratio: Ratio::new(160i32, 1_000_000i32),
         chunk_producer_assignment_changes_limit: 5,
         shuffle_shard_assignment_for_chunk_producers: false,
+        slashing_fractions: Default::default(),
         shard_layout: ShardLayout::multi_shard(num_shards, 0),
         validator_max_kickout_stake_perc: 100,
     };
@@ -13,12 +13,12 @@ use itertools::Itertools;
 use near_crypto::{KeyType, PublicKey};
 use near_o11y::testonly::init_test_logger;
 use near_primitives::account::id::AccountIdRef;
-use near_primitives::bandwidth_scheduler::BandwidthRequests;
+use near_primitives::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 use near_primitives::block::Tip;
-use near_primitives::challenge::SlashedValidator;
+use near_primitives::challenge::{SlashedValidator, SlashingSeverity};
 use near_primitives::congestion_info::CongestionInfo;
 use near_primitives::epoch_block_info::BlockInfoV3;
-use near_primitives::epoch_manager::EpochConfig;
+use near_primitives::epoch_manager::{EpochConfig, SlashingFractions};
 use near_primitives::hash::hash;
 use near_primitives::shard_layout::ShardLayout;
 use near_primitives::sharding::{ShardChunkHeader, ShardChunkHeaderV3};
@@ -457,7 +457,7 @@ fn test_slashing() {
         h[1],
         1,
         vec![],
-        vec![SlashedValidator::new("test1".parse().unwrap(), false)],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::Other)],
     );
 
     let epoch_id = epoch_manager.get_epoch_id(&h[1]).unwrap();
@@ -522,8 +522,8 @@ fn test_double_sign_slashing1() {
         2,
         vec![],
         vec![
-            SlashedValidator::new("test1".parse().unwrap(), true),
-            SlashedValidator::new("test1".parse().unwrap(), false),
+            SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::DoubleSign),
+            SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::Other),
         ],
     );
     let slashed: Vec<_> =
@@ -541,7 +541,7 @@ fn test_double_sign_slashing1() {
         h[4],
         4,
         vec![],
-        vec![SlashedValidator::new("test1".parse().unwrap(), true)],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::DoubleSign)],
     );
 
     // Epoch 3 -> defined by proposals/slashes in h[1].
@@ -590,7 +590,7 @@ fn test_double_sign_slashing2() {
         h[1],
         1,
         vec![],
-        vec![SlashedValidator::new("test1".parse().unwrap(), true)],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::DoubleSign)],
     );
 
     let slashed: Vec<_> =
@@ -608,13 +608,110 @@ fn test_double_sign_slashing2() {
         h[3],
         3,
         vec![],
-        vec![SlashedValidator::new("test1".parse().unwrap(), true)],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::DoubleSign)],
     );
     let slashed: Vec<_> =
         epoch_manager.get_block_info(&h[3]).unwrap().slashed().clone().into_iter().collect();
     assert_eq!(slashed, vec![("test1".parse().unwrap(), SlashState::DoubleSign)]);
 }
 
+/// Test that slashing severity is recorded in block info and carried over to the next block.
+#[test]
+fn test_slashing_severity() {
+    let amount_staked = 1_000_000;
+    let validators =
+        vec![("test1".parse().unwrap(), amount_staked), ("test2".parse().unwrap(), amount_staked)];
+    let mut epoch_manager = setup_default_epoch_manager(validators, 10, 1, 2, 90, 60);
+
+    let h = hash_range(10);
+    record_block(&mut epoch_manager, CryptoHash::default(), h[0], 0, vec![]);
+    record_block_with_slashes(
+        &mut epoch_manager,
+        h[0],
+        h[1],
+        1,
+        vec![],
+        vec![
+            SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::InvalidChunk),
+            SlashedValidator::new("test2".parse().unwrap(), SlashingSeverity::InvalidWitness),
+        ],
+    );
+    record_block(&mut epoch_manager, h[1], h[2], 2, vec![]);
+    let slashed = epoch_manager.get_block_info(&h[2]).unwrap().slashed().clone();
+    assert_eq!(
+        slashed,
+        vec![
+            ("test1".parse().unwrap(), SlashState::InvalidChunk),
+            ("test2".parse().unwrap(), SlashState::InvalidWitness),
+        ]
+        .into_iter()
+        .collect()
+    );
+}
+
+/// Test that the account which submitted a challenge is rewarded with a fraction of the slashed stake.
+#[test]
+fn test_challenger_reward() {
+    let amount_staked = 1_000_000;
+    let validators = vec![
+        ("test1".parse().unwrap(), amount_staked),
+        ("test2".parse().unwrap(), amount_staked),
+        ("test3".parse().unwrap(), amount_staked),
+    ];
+    let mut epoch_manager = setup_default_epoch_manager(validators, 2, 1, 3, 90, 60);
+
+    let h = hash_range(10);
+    record_block(&mut epoch_manager, CryptoHash::default(), h[0], 0, vec![]);
+    record_block_with_slashes(
+        &mut epoch_manager,
+        h[0],
+        h[1],
+        1,
+        vec![],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::Other)
+            .with_challenger(
+                "test2".parse().unwrap(),
+                ProtocolFeature::ChallengerReward.protocol_version(),
+            )],
+    );
+    let mut last_block_in_epoch = None;
+    for i in 2..5 {
+        if epoch_manager.is_next_block_epoch_start(&h[i - 1]).unwrap() {
+            last_block_in_epoch = Some(h[i - 1]);
+            break;
+        }
+        record_block(&mut epoch_manager, h[i - 1], h[i], i as u64, vec![]);
+    }
+    let last_block_in_epoch = last_block_in_epoch.unwrap();
+    let block_info = epoch_manager.get_block_info(&last_block_in_epoch).unwrap();
+    assert_eq!(
+        block_info.challengers(),
+        Some(&[("test1".parse().unwrap(), "test2".parse().unwrap())].into_iter().collect())
+    );
+
+    let expected_reward = amount_staked / 10;
+    let epoch_info = epoch_manager.get_epoch_info(&EpochId(last_block_in_epoch)).unwrap();
+    assert_eq!(
+        epoch_info.validator_reward().get(AccountIdRef::new_or_panic("test2")),
+        Some(&expected_reward)
+    );
+    assert_eq!(epoch_info.minted_amount(), expected_reward);
+}
+
+#[test]
+fn test_slashing_fractions() {
+    let fractions = SlashingFractions {
+        double_sign: Ratio::new(1, 2),
+        invalid_chunk: Ratio::new(1, 10),
+        invalid_witness: Ratio::new(1, 5),
+        challenger_reward: Ratio::new(1, 10),
+    };
+    assert_eq!(fractions.for_severity(SlashingSeverity::Other), Ratio::from_integer(1));
+    assert_eq!(fractions.for_severity(SlashingSeverity::DoubleSign), Ratio::new(1, 2));
+    assert_eq!(fractions.for_severity(SlashingSeverity::InvalidChunk), Ratio::new(1, 10));
+    assert_eq!(fractions.for_severity(SlashingSeverity::InvalidWitness), Ratio::new(1, 5));
+}
+
 /// If all current validator try to unstake, we disallow that.
 #[test]
 fn test_all_validators_unstake() {
@@ -1955,7 +2052,7 @@ fn test_unstake_slash() {
         h[2],
         2,
         vec![],
-        vec![SlashedValidator::new("test1".parse().unwrap(), false)],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::Other)],
     );
     record_block(&mut epoch_manager, h[2], h[3], 3, vec![]);
     record_block(
@@ -2004,7 +2101,7 @@ fn test_no_unstake_slash() {
         h[1],
         1,
         vec![],
-        vec![SlashedValidator::new("test1".parse().unwrap(), false)],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::Other)],
     );
     record_block(&mut epoch_manager, h[1], h[2], 2, vec![]);
     record_block(&mut epoch_manager, h[2], h[3], 3, vec![]);
@@ -2056,7 +2153,7 @@ fn test_slash_non_validator() {
         h[3],
         3,
         vec![],
-        vec![SlashedValidator::new("test1".parse().unwrap(), false)],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::Other)],
     );
     record_block(&mut epoch_manager, h[3], h[4], 4, vec![]);
     record_block(
@@ -2107,7 +2204,7 @@ fn test_slash_restake() {
         h[1],
         1,
         vec![],
-        vec![SlashedValidator::new("test1".parse().unwrap(), false)],
+        vec![SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::Other)],
     );
     record_block(
         &mut epoch_manager,
@@ -2226,6 +2323,7 @@ fn set_block_info_protocol_version(info: &mut BlockInfo, protocol_version: Proto
         BlockInfo::V1(v1) => v1.latest_protocol_version = protocol_version,
         BlockInfo::V2(v2) => v2.latest_protocol_version = protocol_version,
         BlockInfo::V3(v2) => v2.latest_protocol_version = protocol_version,
+        BlockInfo::V4(v4) => v4.latest_protocol_version = protocol_version,
     }
 }
 
@@ -2343,6 +2441,7 @@ fn test_protocol_version_switch_with_many_seats() {
         minimum_stake_ratio: Ratio::new(160i32, 1_000_000i32),
         chunk_producer_assignment_changes_limit: 5,
         shuffle_shard_assignment_for_chunk_producers: false,
+        slashing_fractions: Default::default(),
         validator_max_kickout_stake_perc: 100,
     };
     let config = AllEpochConfig::new(false, PROTOCOL_VERSION, epoch_config, "test-chain");
@@ -3332,6 +3431,9 @@ fn test_chunk_header(h: &[CryptoHash], signer: &ValidatorSigner) -> ShardChunkHe
         vec![],
         congestion_info,
         BandwidthRequests::default_for_protocol_version(PROTOCOL_VERSION),
+        OutgoingBandwidth::default_for_protocol_version(PROTOCOL_VERSION),
+        ProtocolFeature::ExecutionProofs.enabled(PROTOCOL_VERSION).then_some(h[2]),
+        ProtocolFeature::AdaptiveChunkEncoding.enabled(PROTOCOL_VERSION).then_some(1),
         signer,
     ))
 }
@@ -3859,3 +3961,53 @@ fn test_get_shard_uids_pending_resharding_double_same() {
     ]);
     assert_eq!(shard_uids, vec![s1].into_iter().collect::<HashSet<_>>());
 }
+
+#[test]
+fn test_optimistic_block_info() {
+    let validators = vec![("test1".parse().unwrap(), 1_000)];
+    let mut epoch_manager = setup_default_epoch_manager(validators, 10, 1, 2, 90, 60);
+    let h = hash_range(11);
+    let optimistic_block_info = |height: BlockHeight| {
+        BlockInfo::new(
+            h[height as usize],
+            height,
+            height - 2,
+            h[height as usize - 1],
+            h[height as usize - 1],
+            vec![],
+            vec![],
+            vec![],
+            DEFAULT_TOTAL_SUPPLY,
+            PROTOCOL_VERSION,
+            height * NUM_NS_IN_SECOND,
+            None,
+        )
+    };
+    record_block(&mut epoch_manager, CryptoHash::default(), h[0], 0, vec![]);
+    for height in 1..3 {
+        record_block(&mut epoch_manager, h[height - 1], h[height], height as u64, vec![]);
+    }
+
+    assert!(epoch_manager.add_optimistic_block_info(optimistic_block_info(3)).unwrap());
+    let epoch_id = epoch_manager.get_epoch_id(&h[2]).unwrap();
+    assert_eq!(epoch_manager.get_epoch_id_from_prev_block(&h[3]).unwrap(), epoch_id);
+    epoch_manager.remove_optimistic_block_info(&h[3]);
+    assert!(matches!(epoch_manager.get_block_info(&h[3]), Err(EpochError::MissingBlock(_))));
+
+    // Recording the block stores it and drops its optimistic information.
+    assert!(epoch_manager.add_optimistic_block_info(optimistic_block_info(3)).unwrap());
+    record_block(&mut epoch_manager, h[2], h[3], 3, vec![]);
+    assert!(epoch_manager.optimistic_blocks_info.is_empty());
+    assert!(epoch_manager
+        .store
+        .get_ser::<BlockInfo>(DBCol::BlockInfo, h[3].as_ref())
+        .unwrap()
+        .is_some());
+
+    // The block at height 10 is the last one of the epoch.
+    for height in 4..10 {
+        record_block(&mut epoch_manager, h[height - 1], h[height], height as u64, vec![]);
+    }
+    assert!(!epoch_manager.add_optimistic_block_info(optimistic_block_info(10)).unwrap());
+    assert!(epoch_manager.optimistic_blocks_info.is_empty());
+}
@@ -7,7 +7,7 @@ use crate::test_utils::{
     hash_range, record_block_with_slashes, setup_default_epoch_manager, stake,
 };
 use crate::EpochManager;
-use near_primitives::challenge::SlashedValidator;
+use near_primitives::challenge::{SlashedValidator, SlashingSeverity};
 use near_primitives::epoch_block_info::{BlockInfo, SlashState};
 use near_primitives::epoch_info::EpochInfo;
 use near_primitives::hash::CryptoHash;
@@ -101,7 +101,7 @@ fn random_slashes<RngImpl: Rng>(rng: &mut RngImpl) -> Vec<SlashedValidator> {
     let slash_chance = 0.2;
     if rng.gen_range(0.0..1.0) < slash_chance {
         let account_id = AccountId::try_from(format!("test{}", rng.gen_range(1..6))).unwrap();
-        slashes.push(SlashedValidator::new(account_id, true));
+        slashes.push(SlashedValidator::new(account_id, SlashingSeverity::DoubleSign));
     }
     slashes
 }
@@ -264,12 +264,7 @@ fn verify_slashes(
 
         let this_block_slashes = slashes_per_block[i]
             .iter()
-            .map(|sv| {
-                (
-                    sv.account_id.clone(),
-                    if sv.is_double_sign { SlashState::DoubleSign } else { SlashState::Other },
-                )
-            })
+            .map(|sv| (sv.account_id().clone(), SlashState::from(sv.severity())))
             .collect::<HashMap<_, _>>();
         if epoch_manager.is_next_block_epoch_start(block_infos[i].prev_hash()).unwrap() {
             let epoch_info = epoch_manager.get_epoch_info(block_infos[i].epoch_id()).unwrap();
@@ -2,7 +2,7 @@ use actix::Addr;
 
 use near_indexer_primitives::IndexerTransactionWithOutcome;
 use near_parameters::RuntimeConfig;
-use near_primitives::version::ProtocolVersion;
+use near_primitives::version::{ProtocolFeature, ProtocolVersion};
 use near_primitives::views;
 use node_runtime::config::tx_cost;
 
@@ -66,6 +66,12 @@ pub(crate) async fn convert_transactions_sir_into_local_receipts(
                         is_promise_yield: false,
                     },
                     priority: 0,
+                    expiry_height: ProtocolFeature::ReceiptExpiry.enabled(protocol_version).then(
+                        || {
+                            block.header.height
+                                + runtime_config.wasm_config.limit_config.receipt_ttl_in_blocks
+                        },
+                    ),
                 }
             })
             .collect();
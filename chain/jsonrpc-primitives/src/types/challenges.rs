@@ -0,0 +1,54 @@
+use near_primitives::challenge::Challenge;
+use near_primitives::hash::CryptoHash;
+use near_primitives::views::ChallengeStatusView;
+use serde_json::Value;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RpcChallengeRequest {
+    /// Borsh-serialized challenge encoded in base64.
+    #[serde(rename = "signed_challenge_base64")]
+    pub challenge: Challenge,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct RpcChallengeResponse {
+    pub challenge_hash: CryptoHash,
+    #[serde(flatten)]
+    pub status: ChallengeStatusView,
+}
+
+#[derive(thiserror::Error, Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcChallengeError {
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+    #[error("Block or epoch referenced by the challenge either has never been observed on the node or has been garbage collected: {error_message}")]
+    UnknownBlock {
+        #[serde(skip_serializing)]
+        error_message: String,
+    },
+}
+
+impl From<RpcChallengeError> for crate::errors::RpcError {
+    fn from(error: RpcChallengeError) -> Self {
+        let error_data = match &error {
+            RpcChallengeError::InternalError { .. } => Some(Value::String(error.to_string())),
+            RpcChallengeError::UnknownBlock { error_message } => Some(Value::String(format!(
+                "DB Not Found Error: {} \n Cause: Unknown",
+                error_message
+            ))),
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcChallengeError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
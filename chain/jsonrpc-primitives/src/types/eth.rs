@@ -0,0 +1,112 @@
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::AccountId;
+use serde_json::Value;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct RpcRelayEthTransactionRequest {
+    /// Signed Ethereum transaction, RLP-encoded as sent to `eth_sendRawTransaction`,
+    /// as a 0x-prefixed hex string.
+    pub signed_tx: String,
+    /// Account the action of the transaction is sent to, the ETH-implicit
+    /// account of the `to` address of the transaction if omitted.
+    #[serde(default)]
+    pub target: Option<AccountId>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcRelayEthTransactionResponse {
+    /// Keccak-256 hash of the Ethereum transaction, as a 0x-prefixed hex string.
+    pub eth_transaction_hash: String,
+    /// Hash of the Near transaction relaying the Ethereum transaction.
+    pub transaction_hash: CryptoHash,
+    /// Relayer account signing the Near transaction.
+    pub sender_account_id: AccountId,
+    /// ETH-implicit account of the signer of the Ethereum transaction.
+    pub receiver_id: AccountId,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcEthTransactionStatusRequest {
+    /// Hash returned by `EXPERIMENTAL_relay_eth_transaction`.
+    pub eth_transaction_hash: String,
+    #[serde(default)]
+    pub wait_until: near_primitives::views::TxExecutionStatus,
+}
+
+/// Result of the Ethereum transaction as reported by the wallet contract.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EthExecutionResult {
+    pub success: bool,
+    pub success_value: Option<Vec<u8>>,
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcEthTransactionStatusResponse {
+    pub transaction_hash: CryptoHash,
+    /// Only set once the relaying Near transaction and the receipts it produced
+    /// have been executed successfully.
+    pub eth_result: Option<EthExecutionResult>,
+    #[serde(flatten)]
+    pub final_execution_outcome: Option<near_primitives::views::FinalExecutionOutcomeViewEnum>,
+    pub final_execution_status: near_primitives::views::TxExecutionStatus,
+}
+
+#[derive(thiserror::Error, Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcEthTransactionError {
+    #[error("The node is not configured to relay Ethereum transactions")]
+    RelayerDisabled,
+    #[error("Invalid Ethereum transaction: {error_message}")]
+    InvalidTransaction { error_message: String },
+    #[error("Transaction nonce {transaction_nonce} doesn't match the nonce {account_nonce} of the wallet contract")]
+    NonceMismatch { transaction_nonce: u64, account_nonce: u64 },
+    #[error("Transactions signed by {account_id} are not relayed by this node")]
+    SignerNotAllowed { account_id: AccountId },
+    #[error("The node relayed too many transactions recently. Try again later.")]
+    TooManyRequests,
+    #[error("Account {account_id} doesn't exist or has no wallet contract")]
+    UnknownAccount { account_id: AccountId },
+    #[error("Ethereum transaction {eth_transaction_hash} hasn't been relayed by this node")]
+    UnknownTransaction { eth_transaction_hash: String },
+    #[error("The relaying transaction is invalid: {context:?}")]
+    RelayingTransactionRejected {
+        #[serde(skip_serializing)]
+        context: near_primitives::errors::InvalidTxError,
+    },
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+    #[error("Timeout")]
+    TimeoutError,
+}
+
+impl From<RpcEthTransactionError> for crate::errors::RpcError {
+    fn from(error: RpcEthTransactionError) -> Self {
+        let error_data = match &error {
+            RpcEthTransactionError::RelayingTransactionRejected { context } => {
+                if let Ok(value) =
+                    serde_json::to_value(crate::errors::ServerError::TxExecutionError(
+                        near_primitives::errors::TxExecutionError::InvalidTxError(context.clone()),
+                    ))
+                {
+                    value
+                } else {
+                    Value::String(error.to_string())
+                }
+            }
+            _ => Value::String(error.to_string()),
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcEthTransactionError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(Some(error_data), error_data_value)
+    }
+}
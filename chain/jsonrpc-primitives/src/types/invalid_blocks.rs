@@ -0,0 +1,35 @@
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcClearInvalidBlocksRequest {
+    pub block_hashes: Vec<near_primitives::hash::CryptoHash>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcClearInvalidBlocksResponse {
+    /// The cleared blocks. Blocks which were not known to be invalid are
+    /// skipped.
+    pub cleared: Vec<near_primitives::views::InvalidBlockView>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcClearInvalidBlocksError {
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+    #[error("Clearing invalid blocks requires the debug RPC to be enabled")]
+    DebugRpcDisabled,
+}
+
+impl From<RpcClearInvalidBlocksError> for crate::errors::RpcError {
+    fn from(error: RpcClearInvalidBlocksError) -> Self {
+        let error_data = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcClearInvalidBlocksError: {:?}", err),
+                )
+            }
+        };
+        Self::new_internal_or_handler_error(Some(error_data.clone()), error_data)
+    }
+}
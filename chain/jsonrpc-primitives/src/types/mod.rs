@@ -1,18 +1,23 @@
 pub mod blocks;
+pub mod challenges;
 pub mod changes;
 pub mod chunks;
 pub mod client_config;
 pub mod config;
 pub mod congestion;
 pub mod entity_debug;
+pub mod eth;
 pub mod gas_price;
+pub mod invalid_blocks;
 pub mod light_client;
 pub mod maintenance;
 pub mod network_info;
 pub mod query;
 pub mod receipts;
+pub mod replay;
 pub mod sandbox;
 pub mod split_storage;
 pub mod status;
+pub mod trace;
 pub mod transactions;
 pub mod validator;
@@ -1,4 +1,4 @@
-use near_primitives::network::PeerId;
+use near_primitives::network::{PeerId, Reachability};
 use near_primitives::types::AccountId;
 use std::net::SocketAddr;
 
@@ -25,6 +25,9 @@ pub struct RpcNetworkInfoResponse {
     pub received_bytes_per_sec: u64,
     /// Accounts of known block and chunk producers from routing table.
     pub known_producers: Vec<RpcKnownProducer>,
+    /// Whether other nodes can connect to this node.
+    #[serde(default)]
+    pub reachability: Reachability,
 }
 
 #[derive(thiserror::Error, Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -81,6 +81,9 @@ pub enum QueryResponseKind {
     CallResult(near_primitives::views::CallResult),
     AccessKey(near_primitives::views::AccessKeyView),
     AccessKeyList(near_primitives::views::AccessKeyList),
+    StorageSponsorship(near_primitives::views::StorageSponsorshipView),
+    ContractSourceMetadata(near_primitives::views::ContractSourceMetadataView),
+    TokenBalance(near_primitives::views::TokenBalanceView),
 }
 
 impl From<RpcQueryError> for crate::errors::RpcError {
@@ -0,0 +1,37 @@
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcReplayBlockRequest {
+    pub block_hash: near_primitives::hash::CryptoHash,
+    pub shard_id: near_primitives::types::ShardId,
+}
+
+pub type RpcReplayBlockResponse = near_primitives::views::BlockReplayView;
+
+#[derive(thiserror::Error, Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcReplayBlockError {
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+    #[error("Block {block_hash} is not available on this node")]
+    UnknownBlock { block_hash: near_primitives::hash::CryptoHash },
+    #[error("Shard {shard_id} does not exist in the epoch of the block")]
+    InvalidShardId { shard_id: near_primitives::types::ShardId },
+    #[error("The state needed to replay the block is not available: {error_message}")]
+    StateUnavailable { error_message: String },
+    #[error("Replaying blocks requires the debug RPC to be enabled")]
+    DebugRpcDisabled,
+}
+
+impl From<RpcReplayBlockError> for crate::errors::RpcError {
+    fn from(error: RpcReplayBlockError) -> Self {
+        let error_data = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcReplayBlockError: {:?}", err),
+                )
+            }
+        };
+        Self::new_internal_or_handler_error(Some(error_data.clone()), error_data)
+    }
+}
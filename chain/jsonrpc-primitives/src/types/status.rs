@@ -1,12 +1,13 @@
 #[cfg(feature = "debug_types")]
 use near_client_primitives::debug::{
-    DebugBlockStatusData, EpochInfoView, TrackedShardsView, ValidatorStatus,
+    ChallengeDebugInfo, ChallengeValidationReport, DebugBlockStatusData, EpochInfoView,
+    TrackedShardsView, ValidatorStatus,
 };
 #[cfg(feature = "debug_types")]
 use near_primitives::views::{
-    CatchupStatusView, ChainProcessingInfo, NetworkGraphView, NetworkRoutesView, PeerStoreView,
-    RecentOutboundConnectionsView, RequestedStatePartsView, SnapshotHostsView,
-    SplitStorageInfoView, SyncStatusView,
+    CatchupStatusView, ChainProcessingInfo, ChunkPartsStatusView, NetworkGraphView,
+    NetworkRoutesView, PeerStoreView, RecentOutboundConnectionsView, RequestedStatePartsView,
+    SnapshotHostsView, SplitStorageInfoView, SyncStatusView,
 };
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -36,6 +37,10 @@ pub enum DebugStatusResponse {
     Routes(NetworkRoutesView),
     SnapshotHosts(SnapshotHostsView),
     SplitStoreStatus(SplitStorageInfoView),
+    ChallengeValidation(ChallengeValidationReport),
+    Challenges(Vec<ChallengeDebugInfo>),
+    // What the node saw of the parts of a chunk, None if it doesn't know the chunk.
+    ChunkStatus(Option<ChunkPartsStatusView>),
 }
 
 #[cfg(feature = "debug_types")]
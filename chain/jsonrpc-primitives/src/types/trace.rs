@@ -0,0 +1,37 @@
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcTraceTransactionRequest {
+    pub tx_hash: near_primitives::hash::CryptoHash,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcTraceTransactionResponse {
+    pub events: Vec<near_primitives::views::ExecutionTraceEventView>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcTraceTransactionError {
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+    #[error("Transaction {transaction_hash} has not been executed on this node")]
+    UnknownTransaction { transaction_hash: near_primitives::hash::CryptoHash },
+    #[error("The state needed to trace the transaction is not available: {error_message}")]
+    StateUnavailable { error_message: String },
+    #[error("Tracing transactions requires the debug RPC to be enabled")]
+    DebugRpcDisabled,
+}
+
+impl From<RpcTraceTransactionError> for crate::errors::RpcError {
+    fn from(error: RpcTraceTransactionError) -> Self {
+        let error_data = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcTraceTransactionError: {:?}", err),
+                )
+            }
+        };
+        Self::new_internal_or_handler_error(Some(error_data.clone()), error_data)
+    }
+}
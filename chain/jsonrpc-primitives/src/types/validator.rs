@@ -25,6 +25,17 @@ pub struct RpcValidatorsOrderedRequest {
     pub block_id: near_primitives::types::MaybeBlockId,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcValidatorEndorsementStatsRequest {
+    pub account_id: near_primitives::types::AccountId,
+    /// Epoch of the final head if not set.
+    #[serde(default)]
+    pub epoch_id: Option<near_primitives::types::EpochId>,
+}
+
+pub type RpcValidatorEndorsementStatsResponse =
+    near_primitives::views::ValidatorEndorsementStatsView;
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct RpcValidatorResponse {
     #[serde(flatten)]
@@ -2,6 +2,7 @@ use awc::{Client, Connector};
 use futures::{future, future::LocalBoxFuture, FutureExt, TryFutureExt};
 use near_jsonrpc_primitives::errors::RpcError;
 use near_jsonrpc_primitives::message::{from_slice, Message};
+use near_jsonrpc_primitives::types::challenges::{RpcChallengeRequest, RpcChallengeResponse};
 use near_jsonrpc_primitives::types::changes::{
     RpcStateChangesInBlockByTypeRequest, RpcStateChangesInBlockByTypeResponse,
 };
@@ -214,6 +215,20 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "block", request)
     }
 
+    pub fn challenge_status(
+        &self,
+        request: RpcChallengeRequest,
+    ) -> RpcRequest<RpcChallengeResponse> {
+        call_method(&self.client, &self.server_addr, "challenge_status", request)
+    }
+
+    pub fn broadcast_challenge(
+        &self,
+        request: RpcChallengeRequest,
+    ) -> RpcRequest<RpcChallengeResponse> {
+        call_method(&self.client, &self.server_addr, "broadcast_challenge", request)
+    }
+
     pub fn tx(&self, request: RpcTransactionStatusRequest) -> RpcRequest<RpcTransactionResponse> {
         call_method(&self.client, &self.server_addr, "tx", request)
     }
@@ -242,6 +257,22 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_validators_ordered", request)
     }
 
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_relay_eth_transaction(
+        &self,
+        request: near_jsonrpc_primitives::types::eth::RpcRelayEthTransactionRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::eth::RpcRelayEthTransactionResponse> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_relay_eth_transaction", request)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_eth_tx_status(
+        &self,
+        request: near_jsonrpc_primitives::types::eth::RpcEthTransactionStatusRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::eth::RpcEthTransactionStatusResponse> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_eth_tx_status", request)
+    }
+
     #[allow(non_snake_case)]
     pub fn EXPERIMENTAL_receipt(
         &self,
@@ -267,6 +298,31 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_split_storage_info", request)
     }
 
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_trace_transaction(
+        &self,
+        request: near_jsonrpc_primitives::types::trace::RpcTraceTransactionRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::trace::RpcTraceTransactionResponse> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_trace_transaction", request)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_replay_block(
+        &self,
+        request: near_jsonrpc_primitives::types::replay::RpcReplayBlockRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::replay::RpcReplayBlockResponse> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_replay_block", request)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_clear_invalid_blocks(
+        &self,
+        request: near_jsonrpc_primitives::types::invalid_blocks::RpcClearInvalidBlocksRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::invalid_blocks::RpcClearInvalidBlocksResponse>
+    {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_clear_invalid_blocks", request)
+    }
+
     pub fn validators(
         &self,
         epoch_id_or_block_id: Option<EpochReference>,
@@ -59,6 +59,9 @@ pub fn start_all_with_validity_period(
         actor_handles.client_actor.clone().with_auto_span_context().into_multi_sender(),
         actor_handles.view_client_actor.clone().with_auto_span_context().into_multi_sender(),
         noop().into_multi_sender(),
+        noop().into_multi_sender(),
+        #[cfg(feature = "test_features")]
+        noop().into_multi_sender(),
         #[cfg(feature = "test_features")]
         noop().into_multi_sender(),
         Arc::new(DummyEntityDebugHandler {}),
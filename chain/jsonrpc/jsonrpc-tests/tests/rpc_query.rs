@@ -9,14 +9,17 @@ use serde_json::json;
 use near_actix_test_utils::run_actix;
 use near_crypto::{KeyType, PublicKey, Signature};
 use near_jsonrpc::client::{new_client, ChunkId};
+use near_jsonrpc_primitives::types::challenges::RpcChallengeRequest;
 use near_jsonrpc_primitives::types::query::QueryResponseKind;
 use near_jsonrpc_primitives::types::validator::RpcValidatorsOrderedRequest;
 use near_network::test_utils::wait_or_timeout;
 use near_o11y::testonly::init_test_logger;
 use near_primitives::account::{AccessKey, AccessKeyPermission};
+use near_primitives::challenge::{BlockDoubleSign, Challenge, ChallengeBody};
 use near_primitives::hash::CryptoHash;
 use near_primitives::types::{BlockId, BlockReference, EpochId, ShardId, SyncCheckpoint};
-use near_primitives::views::QueryRequest;
+use near_primitives::validator_signer::InMemoryValidatorSigner;
+use near_primitives::views::{ChallengeStatusView, QueryRequest};
 use near_time::Clock;
 
 use near_jsonrpc_tests::{self as test_utils, test_with_client};
@@ -552,6 +555,28 @@ fn test_invalid_methods() {
     });
 }
 
+/// Challenge signed by an account which is neither a validator nor a fisherman is rejected.
+#[test]
+fn test_challenge_status_rejects_invalid_challenge() {
+    test_with_client!(test_utils::NodeType::NonValidator, client, async move {
+        let signer =
+            InMemoryValidatorSigner::from_seed("test2".parse().unwrap(), KeyType::ED25519, "test2");
+        let challenge = Challenge::produce(
+            ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+                left_block_header: vec![1, 2, 3],
+                right_block_header: vec![4, 5, 6],
+            }),
+            EpochId::default(),
+            0,
+            &signer,
+        );
+        let challenge_hash = challenge.hash;
+        let response = client.challenge_status(RpcChallengeRequest { challenge }).await.unwrap();
+        assert_eq!(response.challenge_hash, challenge_hash);
+        assert!(matches!(response.status, ChallengeStatusView::Rejected { .. }));
+    });
+}
+
 #[test]
 fn test_parse_error_status_code() {
     test_with_client!(test_utils::NodeType::NonValidator, client, async move {
@@ -0,0 +1,43 @@
+use near_async::messaging::AsyncSendError;
+use serde_json::Value;
+
+use near_client_primitives::types::GetChallengeStatusError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::challenges::{RpcChallengeError, RpcChallengeRequest};
+use near_primitives::challenge::Challenge;
+
+use super::{Params, RpcFrom, RpcRequest};
+
+impl RpcRequest for RpcChallengeRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::new(value)
+            .try_singleton(|challenge: Challenge| Ok(RpcChallengeRequest { challenge }))
+            .unwrap_or_parse()
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcChallengeError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<GetChallengeStatusError> for RpcChallengeError {
+    fn rpc_from(error: GetChallengeStatusError) -> Self {
+        match error {
+            GetChallengeStatusError::IOError(error_message) => {
+                Self::InternalError { error_message }
+            }
+            GetChallengeStatusError::UnknownBlock(error_message) => {
+                Self::UnknownBlock { error_message }
+            }
+            GetChallengeStatusError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
+                    .with_label_values(&["RpcChallengeError"])
+                    .inc();
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
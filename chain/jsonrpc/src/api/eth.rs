@@ -0,0 +1,51 @@
+use super::{Params, RpcFrom, RpcRequest};
+use near_async::messaging::AsyncSendError;
+use near_client_primitives::types::QueryError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::eth::{
+    RpcEthTransactionError, RpcEthTransactionStatusRequest, RpcRelayEthTransactionRequest,
+};
+use near_jsonrpc_primitives::types::transactions::RpcTransactionError;
+use serde_json::Value;
+
+impl RpcRequest for RpcRelayEthTransactionRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcRequest for RpcEthTransactionStatusRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcEthTransactionError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<QueryError> for RpcEthTransactionError {
+    fn rpc_from(error: QueryError) -> Self {
+        match error {
+            QueryError::UnknownAccount { requested_account_id: account_id, .. }
+            | QueryError::NoContractCode { contract_account_id: account_id, .. } => {
+                Self::UnknownAccount { account_id }
+            }
+            _ => Self::InternalError { error_message: error.to_string() },
+        }
+    }
+}
+
+impl RpcFrom<RpcTransactionError> for RpcEthTransactionError {
+    fn rpc_from(error: RpcTransactionError) -> Self {
+        match error {
+            RpcTransactionError::InvalidTransaction { context } => {
+                Self::RelayingTransactionRejected { context }
+            }
+            RpcTransactionError::TimeoutError => Self::TimeoutError,
+            _ => Self::InternalError { error_message: error.to_string() },
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use super::{Params, RpcFrom, RpcRequest};
+use near_async::messaging::AsyncSendError;
+use near_client_primitives::types::ClearInvalidBlocksError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::invalid_blocks::{
+    RpcClearInvalidBlocksError, RpcClearInvalidBlocksRequest,
+};
+use serde_json::Value;
+
+impl RpcRequest for RpcClearInvalidBlocksRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcClearInvalidBlocksError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<ClearInvalidBlocksError> for RpcClearInvalidBlocksError {
+    fn rpc_from(error: ClearInvalidBlocksError) -> Self {
+        match error {
+            ClearInvalidBlocksError::IOError(error_message) => {
+                Self::InternalError { error_message }
+            }
+            ClearInvalidBlocksError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
+                    .with_label_values(&["RpcClearInvalidBlocksError"])
+                    .inc();
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
@@ -5,20 +5,25 @@ use near_jsonrpc_primitives::errors::RpcParseError;
 use near_jsonrpc_primitives::errors::{RpcError, ServerError};
 
 mod blocks;
+mod challenges;
 mod changes;
 mod chunks;
 mod client_config;
 mod config;
 mod congestion;
+mod eth;
 mod gas_price;
+mod invalid_blocks;
 mod light_client;
 mod maintenance;
 mod network_info;
 mod query;
 mod receipts;
+mod replay;
 mod sandbox;
 mod split_storage;
 mod status;
+mod trace;
 mod transactions;
 mod validator;
 
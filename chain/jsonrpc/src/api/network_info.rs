@@ -45,6 +45,7 @@ impl RpcFrom<NetworkInfoResponse> for RpcNetworkInfoResponse {
                 .iter()
                 .map(|kp| kp.clone().rpc_into())
                 .collect(),
+            reachability: network_info_response.reachability,
         }
     }
 }
@@ -167,6 +167,15 @@ impl RpcFrom<near_primitives::views::QueryResponseKind>
             near_primitives::views::QueryResponseKind::AccessKeyList(access_key_list) => {
                 Self::AccessKeyList(access_key_list)
             }
+            near_primitives::views::QueryResponseKind::StorageSponsorship(sponsorship) => {
+                Self::StorageSponsorship(sponsorship)
+            }
+            near_primitives::views::QueryResponseKind::ContractSourceMetadata(metadata) => {
+                Self::ContractSourceMetadata(metadata)
+            }
+            near_primitives::views::QueryResponseKind::TokenBalance(balance) => {
+                Self::TokenBalance(balance)
+            }
         }
     }
 }
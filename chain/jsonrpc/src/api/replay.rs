@@ -0,0 +1,38 @@
+use super::{Params, RpcFrom, RpcRequest};
+use near_async::messaging::AsyncSendError;
+use near_client_primitives::types::ReplayBlockError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::replay::{RpcReplayBlockError, RpcReplayBlockRequest};
+use serde_json::Value;
+
+impl RpcRequest for RpcReplayBlockRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcReplayBlockError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<ReplayBlockError> for RpcReplayBlockError {
+    fn rpc_from(error: ReplayBlockError) -> Self {
+        match error {
+            ReplayBlockError::IOError(error_message) => Self::InternalError { error_message },
+            ReplayBlockError::UnknownBlock(block_hash) => Self::UnknownBlock { block_hash },
+            ReplayBlockError::InvalidShardId(shard_id) => Self::InvalidShardId { shard_id },
+            ReplayBlockError::StateUnavailable(error_message) => {
+                Self::StateUnavailable { error_message }
+            }
+            ReplayBlockError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
+                    .with_label_values(&["RpcReplayBlockError"])
+                    .inc();
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
@@ -50,6 +50,12 @@ impl RpcFrom<near_client_primitives::debug::DebugStatusResponse>
                     x,
                 )
             }
+            near_client_primitives::debug::DebugStatusResponse::ChallengeValidation(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::ChallengeValidation(x)
+            }
+            near_client_primitives::debug::DebugStatusResponse::Challenges(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::Challenges(x)
+            }
         }
     }
 }
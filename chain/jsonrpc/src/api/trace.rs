@@ -0,0 +1,39 @@
+use super::{Params, RpcFrom, RpcRequest};
+use near_async::messaging::AsyncSendError;
+use near_client_primitives::types::TraceTransactionError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::trace::{RpcTraceTransactionError, RpcTraceTransactionRequest};
+use serde_json::Value;
+
+impl RpcRequest for RpcTraceTransactionRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcTraceTransactionError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<TraceTransactionError> for RpcTraceTransactionError {
+    fn rpc_from(error: TraceTransactionError) -> Self {
+        match error {
+            TraceTransactionError::IOError(error_message) => Self::InternalError { error_message },
+            TraceTransactionError::UnknownTransaction(transaction_hash) => {
+                Self::UnknownTransaction { transaction_hash }
+            }
+            TraceTransactionError::StateUnavailable(error_message) => {
+                Self::StateUnavailable { error_message }
+            }
+            TraceTransactionError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
+                    .with_label_values(&["RpcTraceTransactionError"])
+                    .inc();
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
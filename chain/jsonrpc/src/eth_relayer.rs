@@ -0,0 +1,414 @@
+//! Relaying of Ethereum transactions to the wallet contracts of ETH-implicit
+//! accounts.
+//!
+//! The wallet contract executes an Ethereum transaction signed by the owner of
+//! the account when its `rlp_execute` method is called. The relayer is a Near
+//! account of the node operator, signing the transaction calling the method and
+//! paying for its gas, so that users only need their Ethereum key.
+
+use aurora_engine_transactions::{EthTransactionKind, NormalizedEthTransaction};
+use near_crypto::{InMemorySigner, Signer};
+use near_jsonrpc_primitives::types::eth::RpcEthTransactionError;
+use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::{
+    Action, FunctionCallAction, SignedTransaction, Transaction, TransactionV0,
+};
+use near_primitives::types::{AccountId, Gas, Nonce};
+use std::collections::{HashSet, VecDeque};
+use std::io::{BufRead, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Gas attached to `rlp_execute`, the wallet contract forwards what it doesn't
+/// use to the action of the Ethereum transaction.
+const RLP_EXECUTE_GAS: Gas = 300_000_000_000_000;
+
+/// Number of relayed transactions whose Near transaction hash is remembered.
+const RELAYED_TRANSACTIONS_CACHE_SIZE: usize = 100_000;
+
+/// Number of signers whose last relayed Ethereum nonce is remembered.
+const PENDING_NONCES_CACHE_SIZE: usize = 100_000;
+
+/// Window over which `max_transactions_per_minute` is enforced.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct EthRelayerConfig {
+    /// Key file of the account signing the relaying transactions, relative to
+    /// the home directory of the node.
+    pub key_file: PathBuf,
+    /// File the hashes of the relayed transactions are appended to, relative to
+    /// the home directory of the node, so that their status can still be
+    /// queried after a restart.
+    #[serde(default = "default_relayed_transactions_file")]
+    pub relayed_transactions_file: PathBuf,
+    /// If set, only transactions signed by these ETH-implicit accounts are
+    /// relayed. The relayer pays for the gas, so public nodes should set it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_signers: Option<HashSet<AccountId>>,
+    /// Maximal number of transactions relayed per minute, across all signers.
+    #[serde(default = "default_max_transactions_per_minute")]
+    pub max_transactions_per_minute: usize,
+}
+
+fn default_relayed_transactions_file() -> PathBuf {
+    PathBuf::from("eth_relayed_transactions")
+}
+
+fn default_max_transactions_per_minute() -> usize {
+    60
+}
+
+/// Ethereum transaction as seen by the relayer.
+pub(crate) struct EthTransaction {
+    /// Keccak-256 hash of the RLP encoding of the transaction.
+    pub hash: [u8; 32],
+    /// ETH-implicit account of the signer of the transaction.
+    pub signer_id: AccountId,
+    pub nonce: Nonce,
+    /// ETH-implicit account of the `to` address, `None` for contract deployments.
+    pub to: Option<AccountId>,
+}
+
+impl EthTransaction {
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let tx: EthTransactionKind = bytes.try_into().map_err(|err| format!("{err:?}"))?;
+        let tx: NormalizedEthTransaction = tx.try_into().map_err(|err| format!("{err:?}"))?;
+        if tx.nonce > u64::MAX.into() {
+            return Err(format!("nonce {} is too large", tx.nonce));
+        }
+        Ok(Self {
+            hash: keccak256(bytes),
+            signer_id: eth_implicit_account_id(tx.address.as_bytes()),
+            nonce: tx.nonce.low_u64(),
+            to: tx.to.map(|address| eth_implicit_account_id(address.as_bytes())),
+        })
+    }
+}
+
+/// Outcome of [`EthRelayer::reserve`].
+pub(crate) enum Reservation {
+    /// The transaction must be sent; it is already recorded as relayed.
+    New(SignedTransaction),
+    /// The Ethereum transaction has already been relayed by this transaction.
+    AlreadyRelayed(CryptoHash),
+}
+
+struct RelayerState {
+    /// Nonce of the last transaction signed by the relayer. Several transactions
+    /// may be relayed before the first one is included in a chunk.
+    last_nonce: Nonce,
+    /// Ethereum nonce of the last transaction relayed for each signer. The
+    /// wallet contract only sees it once the relaying transaction executes, so
+    /// the next transaction of the signer must use the following nonce.
+    pending_nonces: lru::LruCache<AccountId, Nonce>,
+    /// Hashes of the relaying Near transactions by the hash of the Ethereum
+    /// transactions, so that their status can be queried.
+    relayed: lru::LruCache<[u8; 32], CryptoHash>,
+    /// Times at which the transactions of the last `RATE_LIMIT_WINDOW` were relayed.
+    recent: VecDeque<Instant>,
+    /// Append-only log of `relayed`.
+    relayed_file: std::fs::File,
+}
+
+pub(crate) struct EthRelayer {
+    signer: Signer,
+    allowed_signers: Option<HashSet<AccountId>>,
+    max_transactions_per_minute: usize,
+    state: Mutex<RelayerState>,
+}
+
+impl EthRelayer {
+    pub fn new(config: &EthRelayerConfig) -> std::io::Result<Self> {
+        let mut relayed =
+            lru::LruCache::new(NonZeroUsize::new(RELAYED_TRANSACTIONS_CACHE_SIZE).unwrap());
+        load_relayed_transactions(&config.relayed_transactions_file, &mut relayed)?;
+        let relayed_file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&config.relayed_transactions_file)?;
+        Ok(Self {
+            signer: InMemorySigner::from_file(&config.key_file)?,
+            allowed_signers: config.allowed_signers.clone(),
+            max_transactions_per_minute: config.max_transactions_per_minute,
+            state: Mutex::new(RelayerState {
+                last_nonce: 0,
+                pending_nonces: lru::LruCache::new(
+                    NonZeroUsize::new(PENDING_NONCES_CACHE_SIZE).unwrap(),
+                ),
+                relayed,
+                recent: VecDeque::new(),
+                relayed_file,
+            }),
+        })
+    }
+
+    pub fn signer(&self) -> &Signer {
+        &self.signer
+    }
+
+    pub fn check_signer(&self, eth_tx: &EthTransaction) -> Result<(), RpcEthTransactionError> {
+        match &self.allowed_signers {
+            Some(allowed) if !allowed.contains(&eth_tx.signer_id) => {
+                Err(RpcEthTransactionError::SignerNotAllowed {
+                    account_id: eth_tx.signer_id.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks `eth_tx` against the transactions relayed so far and, unless it
+    /// has already been relayed, signs the transaction relaying it and records
+    /// it. `wallet_nonce` is the nonce of the wallet contract and
+    /// `access_key_nonce` the nonce of the access key of the relayer, both in
+    /// the latest state known to the node. If sending the returned transaction
+    /// fails, [`Self::release`] must be called.
+    pub fn reserve(
+        &self,
+        eth_tx: &EthTransaction,
+        eth_tx_bytes: &[u8],
+        target: &AccountId,
+        wallet_nonce: Nonce,
+        access_key_nonce: Nonce,
+        block_hash: CryptoHash,
+    ) -> Result<Reservation, RpcEthTransactionError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(tx_hash) = state.relayed.get(&eth_tx.hash) {
+            return Ok(Reservation::AlreadyRelayed(*tx_hash));
+        }
+        let expected_nonce = match state.pending_nonces.get(&eth_tx.signer_id) {
+            Some(&pending_nonce) if pending_nonce >= wallet_nonce => pending_nonce + 1,
+            _ => wallet_nonce,
+        };
+        if eth_tx.nonce != expected_nonce {
+            return Err(RpcEthTransactionError::NonceMismatch {
+                transaction_nonce: eth_tx.nonce,
+                account_nonce: expected_nonce,
+            });
+        }
+        let now = Instant::now();
+        while state.recent.front().is_some_and(|&time| now - time >= RATE_LIMIT_WINDOW) {
+            state.recent.pop_front();
+        }
+        if state.recent.len() >= self.max_transactions_per_minute {
+            return Err(RpcEthTransactionError::TooManyRequests);
+        }
+        state.recent.push_back(now);
+        state.last_nonce = state.last_nonce.max(access_key_nonce) + 1;
+        let tx =
+            self.relaying_transaction(eth_tx, eth_tx_bytes, target, state.last_nonce, block_hash);
+        state.pending_nonces.put(eth_tx.signer_id.clone(), eth_tx.nonce);
+        state.relayed.put(eth_tx.hash, tx.get_hash());
+        Ok(Reservation::New(tx))
+    }
+
+    /// Forgets a transaction reserved with [`Self::reserve`] which could not
+    /// be sent, so that it can be relayed again.
+    pub fn release(&self, eth_tx: &EthTransaction) {
+        let mut state = self.state.lock().unwrap();
+        state.relayed.pop(&eth_tx.hash);
+        if state.pending_nonces.peek(&eth_tx.signer_id) == Some(&eth_tx.nonce) {
+            state.pending_nonces.pop(&eth_tx.signer_id);
+        }
+    }
+
+    /// Persists a transaction reserved with [`Self::reserve`] once it has been sent.
+    pub fn persist(&self, eth_tx_hash: [u8; 32], tx_hash: CryptoHash) -> std::io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        writeln!(state.relayed_file, "0x{} {}", hex::encode(eth_tx_hash), tx_hash)?;
+        state.relayed_file.flush()
+    }
+
+    /// Signs the transaction executing `eth_tx_bytes` with the wallet contract
+    /// of `eth_tx`'s signer.
+    fn relaying_transaction(
+        &self,
+        eth_tx: &EthTransaction,
+        eth_tx_bytes: &[u8],
+        target: &AccountId,
+        nonce: Nonce,
+        block_hash: CryptoHash,
+    ) -> SignedTransaction {
+        let args = serde_json::json!({
+            "target": target,
+            "tx_bytes_b64": near_primitives::serialize::to_base64(eth_tx_bytes),
+        });
+        let transaction = Transaction::V0(TransactionV0 {
+            nonce,
+            signer_id: self.signer.get_account_id(),
+            public_key: self.signer.public_key(),
+            receiver_id: eth_tx.signer_id.clone(),
+            block_hash,
+            actions: vec![Action::FunctionCall(Box::new(FunctionCallAction {
+                method_name: "rlp_execute".to_string(),
+                args: args.to_string().into_bytes(),
+                gas: RLP_EXECUTE_GAS,
+                deposit: 0,
+            }))],
+        });
+        let signature = self.signer.sign(transaction.get_hash_and_size().0.as_ref());
+        SignedTransaction::new(signature, transaction)
+    }
+
+    pub fn relayed(&self, eth_tx_hash: &[u8; 32]) -> Option<CryptoHash> {
+        self.state.lock().unwrap().relayed.get(eth_tx_hash).copied()
+    }
+}
+
+/// Loads the log written by [`EthRelayer::persist`], if it exists.
+fn load_relayed_transactions(
+    path: &Path,
+    relayed: &mut lru::LruCache<[u8; 32], CryptoHash>,
+) -> std::io::Result<()> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line?;
+        match parse_relayed_transaction(&line) {
+            Some((eth_tx_hash, tx_hash)) => {
+                relayed.put(eth_tx_hash, tx_hash);
+            }
+            None => {
+                tracing::warn!(target: "jsonrpc", ?line, "Skipping invalid relayed transaction")
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_relayed_transaction(line: &str) -> Option<([u8; 32], CryptoHash)> {
+    let (eth_tx_hash, tx_hash) = line.split_once(' ')?;
+    let eth_tx_hash = decode_hex(eth_tx_hash).ok()?.try_into().ok()?;
+    Some((eth_tx_hash, tx_hash.parse().ok()?))
+}
+
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    use sha3::Digest;
+    sha3::Keccak256::digest(bytes).into()
+}
+
+fn eth_implicit_account_id(address: &[u8]) -> AccountId {
+    format!("0x{}", hex::encode(address)).parse().unwrap()
+}
+
+/// Parses the result of the `get_nonce` view method of the wallet contract, a
+/// JSON string holding the decimal nonce.
+pub(crate) fn parse_wallet_nonce(result: &[u8]) -> Result<Nonce, String> {
+    let nonce: String = serde_json::from_slice(result).map_err(|err| err.to_string())?;
+    nonce.parse().map_err(|err| format!("invalid nonce {nonce:?}: {err}"))
+}
+
+/// Decodes a 0x-prefixed hex string, the usual encoding of Ethereum data.
+pub(crate) fn decode_hex(value: &str) -> Result<Vec<u8>, String> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    hex::decode(value).map_err(|err| format!("invalid hex string: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("0x0aff").unwrap(), vec![0x0a, 0xff]);
+        assert_eq!(decode_hex("0aff").unwrap(), vec![0x0a, 0xff]);
+        assert!(decode_hex("0xzz").is_err());
+    }
+
+    #[test]
+    fn test_parse_wallet_nonce() {
+        assert_eq!(parse_wallet_nonce(br#""42""#).unwrap(), 42);
+        assert!(parse_wallet_nonce(b"42").is_err());
+        assert!(parse_wallet_nonce(br#""-1""#).is_err());
+    }
+
+    #[test]
+    fn test_parse_relayed_transaction() {
+        let tx_hash = CryptoHash::hash_bytes(b"tx");
+        let line = format!("0x{} {}", hex::encode([7; 32]), tx_hash);
+        assert_eq!(parse_relayed_transaction(&line), Some(([7; 32], tx_hash)));
+        assert_eq!(parse_relayed_transaction("0x07"), None);
+        assert_eq!(parse_relayed_transaction(&format!("0x07 {tx_hash}")), None);
+    }
+
+    fn eth_transaction(hash: u8, signer_id: &str, nonce: Nonce) -> EthTransaction {
+        EthTransaction { hash: [hash; 32], signer_id: signer_id.parse().unwrap(), nonce, to: None }
+    }
+
+    fn reserve(relayer: &EthRelayer, eth_tx: &EthTransaction, wallet_nonce: Nonce) -> Reservation {
+        let target = "target.near".parse().unwrap();
+        relayer.reserve(eth_tx, &[], &target, wallet_nonce, 0, CryptoHash::default()).unwrap()
+    }
+
+    #[test]
+    fn test_reserve() {
+        let dir = tempfile::tempdir().unwrap();
+        let relayer_id = "relayer.near".parse().unwrap();
+        let config = EthRelayerConfig {
+            key_file: dir.path().join("key.json"),
+            relayed_transactions_file: dir.path().join("relayed"),
+            allowed_signers: None,
+            max_transactions_per_minute: 3,
+        };
+        InMemorySigner::test_signer(&relayer_id).write_to_file(&config.key_file).unwrap();
+        let relayer = EthRelayer::new(&config).unwrap();
+        let signer = "0x0000000000000000000000000000000000000001";
+
+        // The next transaction of a signer must follow the one in flight.
+        let first = eth_transaction(1, signer, 5);
+        let Reservation::New(tx) = reserve(&relayer, &first, 5) else { panic!() };
+        relayer.persist(first.hash, tx.get_hash()).unwrap();
+        assert!(matches!(
+            relayer.reserve(
+                &eth_transaction(2, signer, 5),
+                &[],
+                &relayer_id,
+                5,
+                0,
+                CryptoHash::default()
+            ),
+            Err(RpcEthTransactionError::NonceMismatch { transaction_nonce: 5, account_nonce: 6 })
+        ));
+        // Relaying the same transaction again doesn't create another one.
+        assert!(matches!(
+            reserve(&relayer, &first, 5),
+            Reservation::AlreadyRelayed(hash) if hash == tx.get_hash()
+        ));
+
+        // A transaction which couldn't be sent can be relayed again.
+        let second = eth_transaction(2, signer, 6);
+        assert!(matches!(reserve(&relayer, &second, 5), Reservation::New(_)));
+        relayer.release(&second);
+        assert!(matches!(reserve(&relayer, &second, 5), Reservation::New(_)));
+
+        assert!(matches!(
+            relayer.reserve(
+                &eth_transaction(3, signer, 7),
+                &[],
+                &relayer_id,
+                5,
+                0,
+                CryptoHash::default()
+            ),
+            Err(RpcEthTransactionError::TooManyRequests)
+        ));
+
+        // Persisted transactions are known after a restart.
+        drop(relayer);
+        let relayer = EthRelayer::new(&config).unwrap();
+        assert_eq!(relayer.relayed(&first.hash), Some(tx.get_hash()));
+        assert_eq!(relayer.relayed(&second.hash), None);
+    }
+
+    #[test]
+    fn test_decode_invalid_transaction() {
+        assert!(EthTransaction::decode(&[]).is_err());
+        assert!(EthTransaction::decode(&[0x01, 0xc0]).is_err());
+    }
+}
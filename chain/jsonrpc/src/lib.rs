@@ -5,16 +5,20 @@ use actix_web::http::header;
 use actix_web::HttpRequest;
 use actix_web::{get, http, middleware, web, App, Error as HttpError, HttpResponse, HttpServer};
 pub use api::{RpcFrom, RpcInto, RpcRequest};
+pub use eth_relayer::EthRelayerConfig;
 use near_async::actix::ActixResult;
 use near_async::messaging::{
     AsyncSendError, AsyncSender, CanSend, MessageWithCallback, SendAsync, Sender,
 };
 use near_chain_configs::GenesisConfig;
+use near_chunks::debug::GetChunkPartsStatus;
 use near_client::{
-    DebugStatus, GetBlock, GetBlockProof, GetChunk, GetClientConfig, GetExecutionOutcome,
-    GetGasPrice, GetMaintenanceWindows, GetNetworkInfo, GetNextLightClientBlock, GetProtocolConfig,
-    GetReceipt, GetStateChanges, GetStateChangesInBlock, GetValidatorInfo, GetValidatorOrdered,
-    ProcessTxRequest, ProcessTxResponse, Query, Status, TxStatus,
+    BroadcastChallenge, ClearInvalidBlocks, DebugStatus, GetBlock, GetBlockProof,
+    GetChallengeStatus, GetChunk, GetClientConfig, GetExecutionOutcome, GetGasPrice,
+    GetMaintenanceWindows, GetNetworkInfo, GetNextLightClientBlock, GetProtocolConfig, GetReceipt,
+    GetStateChanges, GetStateChangesInBlock, GetValidatorEndorsementStats, GetValidatorInfo,
+    GetValidatorOrdered, ProcessTxRequest, ProcessTxResponse, Query, ReplayBlock, Status,
+    TraceTransaction, TxStatus,
 };
 use near_client_primitives::types::GetSplitStorageInfo;
 pub use near_jsonrpc_client as client;
@@ -24,6 +28,9 @@ use near_jsonrpc_primitives::message::{Message, Request};
 use near_jsonrpc_primitives::types::blocks::RpcBlockRequest;
 use near_jsonrpc_primitives::types::config::{RpcProtocolConfigError, RpcProtocolConfigResponse};
 use near_jsonrpc_primitives::types::entity_debug::{EntityDebugHandler, EntityQueryWithParams};
+use near_jsonrpc_primitives::types::invalid_blocks::{
+    RpcClearInvalidBlocksError, RpcClearInvalidBlocksRequest, RpcClearInvalidBlocksResponse,
+};
 use near_jsonrpc_primitives::types::query::RpcQueryRequest;
 use near_jsonrpc_primitives::types::split_storage::{
     RpcSplitStorageInfoRequest, RpcSplitStorageInfoResponse,
@@ -35,9 +42,10 @@ use near_network::debug::GetDebugStatus;
 use near_network::tcp::{self, ListenerAddr};
 use near_o11y::metrics::{prometheus, Encoder, TextEncoder};
 use near_primitives::hash::CryptoHash;
+use near_primitives::sharding::ChunkHash;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, BlockHeight, BlockId, BlockReference};
-use near_primitives::views::{QueryRequest, TxExecutionStatus};
+use near_primitives::views::{ChallengeStatusView, QueryRequest, TxExecutionStatus};
 use serde_json::{json, Value};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -46,6 +54,7 @@ use tokio::time::{sleep, timeout};
 use tracing::{error, info};
 
 mod api;
+mod eth_relayer;
 mod metrics;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
@@ -96,6 +105,10 @@ pub struct RpcConfig {
     // be read from this directory, instead of the contents compiled into the binary. This allows
     // for quick iterative development.
     pub experimental_debug_pages_src_path: Option<String>,
+    // If provided, the node relays Ethereum transactions to the wallet contracts of ETH-implicit
+    // accounts, signing the relaying transactions with the given key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eth_relayer: Option<EthRelayerConfig>,
 }
 
 impl Default for RpcConfig {
@@ -108,6 +121,7 @@ impl Default for RpcConfig {
             limits_config: Default::default(),
             enable_debug_rpc: false,
             experimental_debug_pages_src_path: None,
+            eth_relayer: None,
         }
     }
 }
@@ -228,6 +242,7 @@ pub struct ClientSenderForRpc(
     AsyncSender<GetNetworkInfo, ActixResult<GetNetworkInfo>>,
     AsyncSender<ProcessTxRequest, ActixResult<ProcessTxRequest>>,
     AsyncSender<Status, ActixResult<Status>>,
+    Sender<BroadcastChallenge>,
     Sender<ProcessTxRequest>,
     #[cfg(feature = "test_features")] Sender<near_client::NetworkAdversarialMessage>,
     #[cfg(feature = "test_features")]
@@ -244,8 +259,10 @@ pub struct ClientSenderForRpc(
 
 #[derive(Clone, near_async::MultiSend, near_async::MultiSenderFrom)]
 pub struct ViewClientSenderForRpc(
+    AsyncSender<ClearInvalidBlocks, ActixResult<ClearInvalidBlocks>>,
     AsyncSender<GetBlock, ActixResult<GetBlock>>,
     AsyncSender<GetBlockProof, ActixResult<GetBlockProof>>,
+    AsyncSender<GetChallengeStatus, ActixResult<GetChallengeStatus>>,
     AsyncSender<GetChunk, ActixResult<GetChunk>>,
     AsyncSender<GetExecutionOutcome, ActixResult<GetExecutionOutcome>>,
     AsyncSender<GetGasPrice, ActixResult<GetGasPrice>>,
@@ -256,9 +273,12 @@ pub struct ViewClientSenderForRpc(
     AsyncSender<GetSplitStorageInfo, ActixResult<GetSplitStorageInfo>>,
     AsyncSender<GetStateChanges, ActixResult<GetStateChanges>>,
     AsyncSender<GetStateChangesInBlock, ActixResult<GetStateChangesInBlock>>,
+    AsyncSender<GetValidatorEndorsementStats, ActixResult<GetValidatorEndorsementStats>>,
     AsyncSender<GetValidatorInfo, ActixResult<GetValidatorInfo>>,
     AsyncSender<GetValidatorOrdered, ActixResult<GetValidatorOrdered>>,
     AsyncSender<Query, ActixResult<Query>>,
+    AsyncSender<ReplayBlock, ActixResult<ReplayBlock>>,
+    AsyncSender<TraceTransaction, ActixResult<TraceTransaction>>,
     AsyncSender<TxStatus, ActixResult<TxStatus>>,
     #[cfg(feature = "test_features")] Sender<near_client::NetworkAdversarialMessage>,
 );
@@ -272,20 +292,38 @@ pub struct GCSenderForRpc(
     >,
 );
 
+#[cfg(feature = "test_features")]
+#[derive(Clone, near_async::MultiSend, near_async::MultiSenderFrom)]
+pub struct NetworkChaosSenderForRpc(
+    AsyncSender<
+        near_network::chaos::SetNetworkChaos,
+        ActixResult<near_network::chaos::SetNetworkChaos>,
+    >,
+);
+
 #[derive(Clone, near_async::MultiSend, near_async::MultiSenderFrom)]
 pub struct PeerManagerSenderForRpc(AsyncSender<GetDebugStatus, ActixResult<GetDebugStatus>>);
 
+#[derive(Clone, near_async::MultiSend, near_async::MultiSenderFrom)]
+pub struct ShardsManagerSenderForRpc(
+    AsyncSender<GetChunkPartsStatus, ActixResult<GetChunkPartsStatus>>,
+);
+
 struct JsonRpcHandler {
     client_sender: ClientSenderForRpc,
     view_client_sender: ViewClientSenderForRpc,
     peer_manager_sender: PeerManagerSenderForRpc,
+    shards_manager_sender: ShardsManagerSenderForRpc,
     #[cfg(feature = "test_features")]
     gc_sender: GCSenderForRpc,
+    #[cfg(feature = "test_features")]
+    network_chaos_sender: NetworkChaosSenderForRpc,
     polling_config: RpcPollingConfig,
     genesis_config: GenesisConfig,
     enable_debug_rpc: bool,
     debug_pages_src_path: Option<PathBuf>,
     entity_debug_handler: Arc<dyn EntityDebugHandler>,
+    eth_relayer: Option<Arc<eth_relayer::EthRelayer>>,
 }
 
 impl JsonRpcHandler {
@@ -356,6 +394,11 @@ impl JsonRpcHandler {
                     QueryRequest::ViewAccessKey { .. } => "query_view_access_key",
                     QueryRequest::ViewAccessKeyList { .. } => "query_view_access_key_list",
                     QueryRequest::CallFunction { .. } => "query_call_function",
+                    QueryRequest::ViewStorageSponsorship { .. } => "query_view_storage_sponsorship",
+                    QueryRequest::ViewContractSourceMetadata { .. } => {
+                        "query_view_contract_source_metadata"
+                    }
+                    QueryRequest::ViewTokenBalance { .. } => "query_view_token_balance",
                 };
                 (metrics_name.to_string(), process_query_response(self.query(params).await))
             }
@@ -379,9 +422,15 @@ impl JsonRpcHandler {
                 })
                 .await
             }
+            "broadcast_challenge" => {
+                process_method_call(request, |params| self.broadcast_challenge(params)).await
+            }
             "broadcast_tx_commit" => {
                 process_method_call(request, |params| self.send_tx_commit(params)).await
             }
+            "challenge_status" => {
+                process_method_call(request, |params| self.challenge_status(params)).await
+            }
             "chunk" => process_method_call(request, |params| self.chunk(params)).await,
             "gas_price" => process_method_call(request, |params| self.gas_price(params)).await,
             "health" => process_method_call(request, |_params: ()| self.health()).await,
@@ -401,6 +450,10 @@ impl JsonRpcHandler {
                 process_method_call(request, |params| self.tx_status_common(params, false)).await
             }
             "validators" => process_method_call(request, |params| self.validators(params)).await,
+            "validator_endorsement_stats" => {
+                process_method_call(request, |params| self.validator_endorsement_stats(params))
+                    .await
+            }
             "client_config" => {
                 process_method_call(request, |_params: ()| self.client_config()).await
             }
@@ -413,6 +466,9 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_congestion_level" => {
                 process_method_call(request, |params| self.congestion_level(params)).await
             }
+            "EXPERIMENTAL_eth_tx_status" => {
+                process_method_call(request, |params| self.eth_tx_status(params)).await
+            }
             "EXPERIMENTAL_genesis_config" => {
                 process_method_call(request, |_params: ()| async {
                     Result::<_, std::convert::Infallible>::Ok(&self.genesis_config)
@@ -434,6 +490,18 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_receipt" => {
                 process_method_call(request, |params| self.receipt(params)).await
             }
+            "EXPERIMENTAL_relay_eth_transaction" => {
+                process_method_call(request, |params| self.relay_eth_transaction(params)).await
+            }
+            "EXPERIMENTAL_trace_transaction" => {
+                process_method_call(request, |params| self.trace_transaction(params)).await
+            }
+            "EXPERIMENTAL_replay_block" => {
+                process_method_call(request, |params| self.replay_block(params)).await
+            }
+            "EXPERIMENTAL_clear_invalid_blocks" => {
+                process_method_call(request, |params| self.clear_invalid_blocks(params)).await
+            }
             "EXPERIMENTAL_tx_status" => {
                 process_method_call(request, |params| self.tx_status_common(params, true)).await
             }
@@ -486,6 +554,7 @@ impl JsonRpcHandler {
             "adv_switch_to_height" => self.adv_switch_to_height(request.params).await,
             "adv_get_saved_blocks" => self.adv_get_saved_blocks(request.params).await,
             "adv_check_store" => self.adv_check_store(request.params).await,
+            "adv_set_network_chaos" => self.adv_set_network_chaos(request.params).await,
             _ => return Err(request),
         })
     }
@@ -527,6 +596,15 @@ impl JsonRpcHandler {
         self.peer_manager_sender.send_async(msg).await.map_err(RpcFrom::rpc_from)
     }
 
+    async fn shards_manager_send<M, T, E>(&self, msg: M) -> Result<T, E>
+    where
+        ShardsManagerSenderForRpc: CanSend<MessageWithCallback<M, T>>,
+        T: Send + 'static,
+        E: RpcFrom<AsyncSendError> + Send + 'static,
+    {
+        self.shards_manager_sender.send_async(msg).await.map_err(RpcFrom::rpc_from)
+    }
+
     async fn send_tx_async(
         &self,
         request_data: near_jsonrpc_primitives::types::transactions::RpcSendTransactionRequest,
@@ -800,6 +878,9 @@ impl JsonRpcHandler {
                     "/debug/api/requested_state_parts" => {
                         self.client_send(DebugStatus::RequestedStateParts).await?.rpc_into()
                     }
+                    "/debug/api/challenges" => {
+                        self.client_send(DebugStatus::Challenges).await?.rpc_into()
+                    }
                     "/debug/api/peer_store" => self
                         .peer_manager_send(near_network::debug::GetDebugStatus::PeerStore)
                         .await?
@@ -858,6 +939,48 @@ impl JsonRpcHandler {
         }
     }
 
+    pub async fn debug_chunk_status(
+        &self,
+        chunk_hash: ChunkHash,
+    ) -> Result<
+        Option<near_jsonrpc_primitives::types::status::RpcDebugStatusResponse>,
+        near_jsonrpc_primitives::types::status::RpcStatusError,
+    > {
+        if self.enable_debug_rpc {
+            let chunk_status = self.shards_manager_send(GetChunkPartsStatus { chunk_hash }).await?;
+            Ok(Some(near_jsonrpc_primitives::types::status::RpcDebugStatusResponse {
+                status_response:
+                    near_jsonrpc_primitives::types::status::DebugStatusResponse::ChunkStatus(
+                        chunk_status,
+                    ),
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Validates the challenge without adding it to the pool or broadcasting it
+    /// and returns a report of the performed checks.
+    pub async fn debug_validate_challenge(
+        &self,
+        request: near_jsonrpc_primitives::types::challenges::RpcChallengeRequest,
+    ) -> Result<
+        Option<near_jsonrpc_primitives::types::status::RpcDebugStatusResponse>,
+        near_jsonrpc_primitives::types::status::RpcStatusError,
+    > {
+        if self.enable_debug_rpc {
+            let debug_status = self
+                .client_send(DebugStatus::ValidateChallenge(Box::new(request.challenge)))
+                .await?
+                .rpc_into();
+            Ok(Some(near_jsonrpc_primitives::types::status::RpcDebugStatusResponse {
+                status_response: debug_status,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
     pub async fn protocol_config(
         &self,
         request_data: near_jsonrpc_primitives::types::config::RpcProtocolConfigRequest,
@@ -977,6 +1100,222 @@ impl JsonRpcHandler {
         }
     }
 
+    async fn trace_transaction(
+        &self,
+        request_data: near_jsonrpc_primitives::types::trace::RpcTraceTransactionRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::trace::RpcTraceTransactionResponse,
+        near_jsonrpc_primitives::types::trace::RpcTraceTransactionError,
+    > {
+        // Re-applying chunks is expensive, so it is only allowed on nodes
+        // opting in to debug RPCs.
+        if !self.enable_debug_rpc {
+            return Err(
+                near_jsonrpc_primitives::types::trace::RpcTraceTransactionError::DebugRpcDisabled,
+            );
+        }
+        let events =
+            self.view_client_send(TraceTransaction { tx_hash: request_data.tx_hash }).await?;
+        Ok(near_jsonrpc_primitives::types::trace::RpcTraceTransactionResponse { events })
+    }
+
+    async fn replay_block(
+        &self,
+        request_data: near_jsonrpc_primitives::types::replay::RpcReplayBlockRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::replay::RpcReplayBlockResponse,
+        near_jsonrpc_primitives::types::replay::RpcReplayBlockError,
+    > {
+        // Same as tracing, replaying a block re-applies its chunk.
+        if !self.enable_debug_rpc {
+            return Err(
+                near_jsonrpc_primitives::types::replay::RpcReplayBlockError::DebugRpcDisabled,
+            );
+        }
+        let near_jsonrpc_primitives::types::replay::RpcReplayBlockRequest { block_hash, shard_id } =
+            request_data;
+        Ok(self.view_client_send(ReplayBlock { block_hash, shard_id }).await?)
+    }
+
+    async fn clear_invalid_blocks(
+        &self,
+        request_data: RpcClearInvalidBlocksRequest,
+    ) -> Result<RpcClearInvalidBlocksResponse, RpcClearInvalidBlocksError> {
+        // Clearing a verdict lets peers make the node process the block again.
+        if !self.enable_debug_rpc {
+            return Err(RpcClearInvalidBlocksError::DebugRpcDisabled);
+        }
+        let block_hashes = request_data.block_hashes;
+        let cleared = self.view_client_send(ClearInvalidBlocks { block_hashes }).await?;
+        Ok(RpcClearInvalidBlocksResponse { cleared })
+    }
+
+    async fn relay_eth_transaction(
+        &self,
+        request_data: near_jsonrpc_primitives::types::eth::RpcRelayEthTransactionRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::eth::RpcRelayEthTransactionResponse,
+        near_jsonrpc_primitives::types::eth::RpcEthTransactionError,
+    > {
+        use near_jsonrpc_primitives::types::eth::RpcEthTransactionError;
+
+        let relayer = self.eth_relayer.as_ref().ok_or(RpcEthTransactionError::RelayerDisabled)?;
+        let eth_tx_bytes =
+            eth_relayer::decode_hex(&request_data.signed_tx).map_err(|error_message| {
+                RpcEthTransactionError::InvalidTransaction { error_message }
+            })?;
+        let eth_tx =
+            eth_relayer::EthTransaction::decode(&eth_tx_bytes).map_err(|error_message| {
+                RpcEthTransactionError::InvalidTransaction { error_message }
+            })?;
+        relayer.check_signer(&eth_tx)?;
+        let target = request_data.target.or_else(|| eth_tx.to.clone()).ok_or_else(|| {
+            RpcEthTransactionError::InvalidTransaction {
+                error_message: "contract deployments are not supported".to_string(),
+            }
+        })?;
+        let signer = relayer.signer();
+        let response = |transaction_hash| {
+            near_jsonrpc_primitives::types::eth::RpcRelayEthTransactionResponse {
+                eth_transaction_hash: format!("0x{}", hex::encode(eth_tx.hash)),
+                transaction_hash,
+                sender_account_id: signer.get_account_id(),
+                receiver_id: eth_tx.signer_id.clone(),
+            }
+        };
+        if let Some(tx_hash) = relayer.relayed(&eth_tx.hash) {
+            return Ok(response(tx_hash));
+        }
+
+        // The wallet contract rejects transactions whose nonce isn't its current one, check it
+        // here, together with the transactions still in flight, so that the relayer doesn't pay
+        // for transactions bound to fail.
+        let nonce_response: near_primitives::views::QueryResponse = self
+            .view_client_send(Query::new(
+                BlockReference::Finality(near_primitives::types::Finality::None),
+                QueryRequest::CallFunction {
+                    account_id: eth_tx.signer_id.clone(),
+                    method_name: "get_nonce".to_string(),
+                    args: b"{}".to_vec().into(),
+                },
+            ))
+            .await?;
+        let account_nonce = match nonce_response.kind {
+            near_primitives::views::QueryResponseKind::CallResult(result) => {
+                eth_relayer::parse_wallet_nonce(&result.result).map_err(|error_message| {
+                    RpcEthTransactionError::InternalError { error_message }
+                })?
+            }
+            kind => {
+                return Err(RpcEthTransactionError::InternalError {
+                    error_message: format!("unexpected query response {kind:?}"),
+                })
+            }
+        };
+
+        let access_key_response: near_primitives::views::QueryResponse = self
+            .view_client_send(Query::new(
+                BlockReference::Finality(near_primitives::types::Finality::None),
+                QueryRequest::ViewAccessKey {
+                    account_id: signer.get_account_id(),
+                    public_key: signer.public_key(),
+                },
+            ))
+            .await?;
+        let access_key_nonce = match access_key_response.kind {
+            near_primitives::views::QueryResponseKind::AccessKey(access_key) => access_key.nonce,
+            kind => {
+                return Err(RpcEthTransactionError::InternalError {
+                    error_message: format!("unexpected query response {kind:?}"),
+                })
+            }
+        };
+
+        let tx = match relayer.reserve(
+            &eth_tx,
+            &eth_tx_bytes,
+            &target,
+            account_nonce,
+            access_key_nonce,
+            access_key_response.block_hash,
+        )? {
+            eth_relayer::Reservation::New(tx) => tx,
+            eth_relayer::Reservation::AlreadyRelayed(tx_hash) => return Ok(response(tx_hash)),
+        };
+        let tx_hash = tx.get_hash();
+        let result = match self.send_tx_internal(tx, false).await {
+            Ok(ProcessTxResponse::ValidTx | ProcessTxResponse::RequestRouted) => Ok(()),
+            Ok(network_client_response) => Err(RpcFrom::rpc_from(
+                near_jsonrpc_primitives::types::transactions::RpcTransactionError::from_network_client_responses(
+                    network_client_response,
+                ),
+            )),
+            Err(err) => Err(RpcFrom::rpc_from(err)),
+        };
+        if let Err(err) = result {
+            relayer.release(&eth_tx);
+            return Err(err);
+        }
+        if let Err(err) = relayer.persist(eth_tx.hash, tx_hash) {
+            tracing::warn!(target: "jsonrpc", ?err, ?tx_hash, "Failed to persist relayed transaction");
+        }
+        Ok(response(tx_hash))
+    }
+
+    async fn eth_tx_status(
+        &self,
+        request_data: near_jsonrpc_primitives::types::eth::RpcEthTransactionStatusRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::eth::RpcEthTransactionStatusResponse,
+        near_jsonrpc_primitives::types::eth::RpcEthTransactionError,
+    > {
+        use near_jsonrpc_primitives::types::eth::RpcEthTransactionError;
+
+        let relayer = self.eth_relayer.as_ref().ok_or(RpcEthTransactionError::RelayerDisabled)?;
+        let unknown_transaction = || RpcEthTransactionError::UnknownTransaction {
+            eth_transaction_hash: request_data.eth_transaction_hash.clone(),
+        };
+        let eth_tx_hash: [u8; 32] = eth_relayer::decode_hex(&request_data.eth_transaction_hash)
+            .ok()
+            .and_then(|hash| hash.try_into().ok())
+            .ok_or_else(unknown_transaction)?;
+        let tx_hash = relayer.relayed(&eth_tx_hash).ok_or_else(unknown_transaction)?;
+
+        let tx_status = self
+            .tx_status_fetch(
+                near_jsonrpc_primitives::types::transactions::TransactionInfo::TransactionId {
+                    tx_hash,
+                    sender_account_id: relayer.signer().get_account_id(),
+                },
+                request_data.wait_until,
+                false,
+            )
+            .await
+            .map_err(RpcFrom::rpc_from)?;
+        let outcome = tx_status.final_execution_outcome.as_ref().map(|outcome| {
+            match outcome {
+            near_primitives::views::FinalExecutionOutcomeViewEnum::FinalExecutionOutcome(
+                outcome,
+            ) => &outcome.status,
+            near_primitives::views::FinalExecutionOutcomeViewEnum::FinalExecutionOutcomeWithReceipt(
+                outcome,
+            ) => &outcome.final_outcome.status,
+        }
+        });
+        let eth_result = match outcome {
+            Some(near_primitives::views::FinalExecutionStatus::SuccessValue(value)) => {
+                serde_json::from_slice(value).ok()
+            }
+            _ => None,
+        };
+        Ok(near_jsonrpc_primitives::types::eth::RpcEthTransactionStatusResponse {
+            transaction_hash: tx_hash,
+            eth_result,
+            final_execution_outcome: tx_status.final_execution_outcome,
+            final_execution_status: tx_status.final_execution_status,
+        })
+    }
+
     async fn changes_in_block(
         &self,
         request: near_jsonrpc_primitives::types::changes::RpcStateChangesInBlockRequest,
@@ -1120,6 +1459,22 @@ impl JsonRpcHandler {
         Ok(near_jsonrpc_primitives::types::validator::RpcValidatorResponse { validator_info })
     }
 
+    /// Returns the chunk endorsements of a validator in an epoch, per shard,
+    /// with the latest chunks it failed to endorse.
+    async fn validator_endorsement_stats(
+        &self,
+        request: near_jsonrpc_primitives::types::validator::RpcValidatorEndorsementStatsRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::validator::RpcValidatorEndorsementStatsResponse,
+        near_jsonrpc_primitives::types::validator::RpcValidatorError,
+    > {
+        let near_jsonrpc_primitives::types::validator::RpcValidatorEndorsementStatsRequest {
+            account_id,
+            epoch_id,
+        } = request;
+        Ok(self.view_client_send(GetValidatorEndorsementStats { account_id, epoch_id }).await?)
+    }
+
     /// Returns the current epoch validators ordered in the block producer order with repetition.
     /// This endpoint is solely used for bridge currently and is not intended for other external use
     /// cases.
@@ -1164,6 +1519,41 @@ impl JsonRpcHandler {
         Ok(windows.iter().map(|r| (r.start, r.end)).collect())
     }
 
+    /// Validates the challenge against the current head of the chain and returns
+    /// whether it was accepted, rejected or all accused accounts are already slashed.
+    async fn challenge_status(
+        &self,
+        request: near_jsonrpc_primitives::types::challenges::RpcChallengeRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::challenges::RpcChallengeResponse,
+        near_jsonrpc_primitives::types::challenges::RpcChallengeError,
+    > {
+        let challenge_hash = request.challenge.hash;
+        let status =
+            self.view_client_send(GetChallengeStatus { challenge: request.challenge }).await?;
+        Ok(near_jsonrpc_primitives::types::challenges::RpcChallengeResponse {
+            challenge_hash,
+            status,
+        })
+    }
+
+    /// Same as `challenge_status`, but if the challenge is accepted, also adds it
+    /// to the pool of challenges of the node and broadcasts it to peers.
+    async fn broadcast_challenge(
+        &self,
+        request: near_jsonrpc_primitives::types::challenges::RpcChallengeRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::challenges::RpcChallengeResponse,
+        near_jsonrpc_primitives::types::challenges::RpcChallengeError,
+    > {
+        let challenge = request.challenge.clone();
+        let response = self.challenge_status(request).await?;
+        if response.status == ChallengeStatusView::Accepted {
+            self.client_sender.send(BroadcastChallenge(challenge));
+        }
+        Ok(response)
+    }
+
     async fn client_config(
         &self,
     ) -> Result<
@@ -1353,6 +1743,18 @@ impl JsonRpcHandler {
             .map_err(|_| RpcError::server_error::<String>(None))?;
         Ok(ret_val)
     }
+
+    /// Replaces the faults injected into the connections of this node,
+    /// see `near_network::chaos::Config`. An empty config removes all the faults.
+    async fn adv_set_network_chaos(&self, params: Value) -> Result<Value, RpcError> {
+        let (config,) = crate::api::Params::parse(params)?;
+        self.network_chaos_sender
+            .send_async(near_network::chaos::SetNetworkChaos(config))
+            .await
+            .map_err(|_| RpcError::server_error::<String>(None))?
+            .map_err(RpcError::invalid_params)?;
+        Ok(Value::String(String::new()))
+    }
 }
 
 async fn handle_unknown_block(
@@ -1472,6 +1874,33 @@ async fn debug_block_status_handler(
     }
 }
 
+#[derive(serde::Deserialize)]
+struct DebugChunkStatusQuery {
+    chunk_hash: CryptoHash,
+}
+
+async fn debug_chunk_status_handler(
+    query: web::Query<DebugChunkStatusQuery>,
+    handler: web::Data<JsonRpcHandler>,
+) -> Result<HttpResponse, HttpError> {
+    match handler.debug_chunk_status(ChunkHash(query.chunk_hash)).await {
+        Ok(Some(value)) => Ok(HttpResponse::Ok().json(&value)),
+        Ok(None) => Ok(HttpResponse::MethodNotAllowed().finish()),
+        Err(_) => Ok(HttpResponse::ServiceUnavailable().finish()),
+    }
+}
+
+async fn debug_validate_challenge_handler(
+    req: web::Json<near_jsonrpc_primitives::types::challenges::RpcChallengeRequest>,
+    handler: web::Data<JsonRpcHandler>,
+) -> Result<HttpResponse, HttpError> {
+    match handler.debug_validate_challenge(req.0).await {
+        Ok(Some(value)) => Ok(HttpResponse::Ok().json(&value)),
+        Ok(None) => Ok(HttpResponse::MethodNotAllowed().finish()),
+        Err(_) => Ok(HttpResponse::ServiceUnavailable().finish()),
+    }
+}
+
 async fn health_handler(handler: web::Data<JsonRpcHandler>) -> Result<HttpResponse, HttpError> {
     match handler.health().await {
         Ok(value) => Ok(HttpResponse::Ok().json(&value)),
@@ -1565,6 +1994,7 @@ async fn display_debug_html(
         "congestion_control" => Some(debug_page_string!("congestion_control.html", handler)),
         "congestion_control.css" => Some(debug_page_string!("congestion_control.css", handler)),
         "congestion_control.js" => Some(debug_page_string!("congestion_control.js", handler)),
+        "challenges" => Some(debug_page_string!("challenges.html", handler)),
         _ => None,
     };
 
@@ -1593,7 +2023,9 @@ pub fn start_http(
     client_sender: ClientSenderForRpc,
     view_client_sender: ViewClientSenderForRpc,
     peer_manager_sender: PeerManagerSenderForRpc,
+    shards_manager_sender: ShardsManagerSenderForRpc,
     #[cfg(feature = "test_features")] gc_sender: GCSenderForRpc,
+    #[cfg(feature = "test_features")] network_chaos_sender: NetworkChaosSenderForRpc,
     entity_debug_handler: Arc<dyn EntityDebugHandler>,
 ) -> Vec<(&'static str, actix_web::dev::ServerHandle)> {
     let RpcConfig {
@@ -1604,10 +2036,18 @@ pub fn start_http(
         limits_config,
         enable_debug_rpc,
         experimental_debug_pages_src_path: debug_pages_src_path,
+        eth_relayer,
     } = config;
     let prometheus_addr = prometheus_addr.filter(|it| it != &addr.to_string());
     let cors_allowed_origins_clone = cors_allowed_origins.clone();
     info!(target:"network", "Starting http server at {}", addr);
+    let eth_relayer = eth_relayer.map(|config| {
+        let relayer = eth_relayer::EthRelayer::new(&config).unwrap_or_else(|err| {
+            panic!("Failed to load the Ethereum relayer key from {:?}: {}", config.key_file, err)
+        });
+        info!(target: "network", "Relaying Ethereum transactions as {}", relayer.signer().get_account_id());
+        Arc::new(relayer)
+    });
     let mut servers = Vec::new();
     let listener = HttpServer::new(move || {
         App::new()
@@ -1616,13 +2056,17 @@ pub fn start_http(
                 client_sender: client_sender.clone(),
                 view_client_sender: view_client_sender.clone(),
                 peer_manager_sender: peer_manager_sender.clone(),
+                shards_manager_sender: shards_manager_sender.clone(),
                 polling_config,
                 genesis_config: genesis_config.clone(),
                 enable_debug_rpc,
                 debug_pages_src_path: debug_pages_src_path.clone().map(Into::into),
                 entity_debug_handler: entity_debug_handler.clone(),
+                eth_relayer: eth_relayer.clone(),
                 #[cfg(feature = "test_features")]
                 gc_sender: gc_sender.clone(),
+                #[cfg(feature = "test_features")]
+                network_chaos_sender: network_chaos_sender.clone(),
             }))
             .app_data(web::JsonConfig::default().limit(limits_config.json_payload_max_size))
             .wrap(middleware::Logger::default())
@@ -1640,6 +2084,14 @@ pub fn start_http(
             .service(web::resource("/network_info").route(web::get().to(network_info_handler)))
             .service(web::resource("/metrics").route(web::get().to(prometheus_handler)))
             .service(web::resource("/debug/api/entity").route(web::post().to(handle_entity_debug)))
+            .service(
+                web::resource("/debug/api/validate_challenge")
+                    .route(web::post().to(debug_validate_challenge_handler)),
+            )
+            .service(
+                web::resource("/debug/api/chunk_status")
+                    .route(web::get().to(debug_chunk_status_handler)),
+            )
             .service(web::resource("/debug/api/{api}").route(web::get().to(debug_handler)))
             .service(
                 web::resource("/debug/api/block_status/{starting_height}")
@@ -0,0 +1,121 @@
+//! Faults injected into the network at runtime, to test how nodes behave under message loss,
+//! latency and network partitions, without orchestrating the OS firewall.
+//!
+//! Faults are applied to the messages received on established connections, by class.
+//! Partitioned peers are cut off in both directions: messages from them are dropped, and
+//! messages to them are not sent. The connections themselves stay open, as after a silent
+//! network failure.
+use crate::network_protocol::PeerMessage;
+use crate::rate_limits::send_shaping::MessageClass;
+use near_async::time;
+use near_primitives::network::PeerId;
+use rand::Rng as _;
+use std::collections::{HashMap, HashSet};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MessageFaults {
+    /// Probability of dropping a received message.
+    #[serde(default)]
+    pub drop_probability: f64,
+    /// Probability of processing a received message twice.
+    #[serde(default)]
+    pub duplicate_probability: f64,
+    /// Time to wait before processing a received message.
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    /// Faults applied to the received messages of each class.
+    /// Classes which are not listed are not affected.
+    #[serde(default)]
+    pub faults: HashMap<MessageClass, MessageFaults>,
+    /// Peers which this node doesn't exchange messages with.
+    #[serde(default)]
+    pub partitioned_peers: HashSet<PeerId>,
+}
+
+/// What to do with a received message.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Action {
+    Drop,
+    Process { copies: usize, delay: time::Duration },
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<(), String> {
+        for (class, faults) in &self.faults {
+            for probability in [faults.drop_probability, faults.duplicate_probability] {
+                if !(0. ..=1.).contains(&probability) {
+                    return Err(format!("probabilities for {class} must be between 0 and 1"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_partitioned(&self, peer_id: &PeerId) -> bool {
+        self.partitioned_peers.contains(peer_id)
+    }
+
+    pub(crate) fn on_received(&self, peer_id: &PeerId, msg: &PeerMessage) -> Action {
+        if self.is_partitioned(peer_id) {
+            return Action::Drop;
+        }
+        let Some(faults) = self.faults.get(&MessageClass::of(msg)) else {
+            return Action::Process { copies: 1, delay: time::Duration::ZERO };
+        };
+        let mut rng = rand::thread_rng();
+        if rng.gen_bool(faults.drop_probability) {
+            return Action::Drop;
+        }
+        Action::Process {
+            copies: 1 + rng.gen_bool(faults.duplicate_probability) as usize,
+            delay: time::Duration::milliseconds(faults.delay_ms as i64),
+        }
+    }
+}
+
+/// Replaces the faults injected by the PeerManager.
+#[derive(actix::Message, Clone, Debug)]
+#[rtype(result = "Result<(), String>")]
+pub struct SetNetworkChaos(pub Config);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_protocol::testonly as data;
+    use crate::network_protocol::{Disconnect, PeersRequest};
+    use crate::testonly::make_rng;
+
+    #[test]
+    fn received_message_faults() {
+        let mut rng = make_rng(921853233);
+        let peer_id = data::make_peer_id(&mut rng);
+        let control = PeerMessage::Disconnect(Disconnect { remove_from_connection_store: false });
+        let other =
+            PeerMessage::PeersRequest(PeersRequest { max_peers: None, max_direct_peers: None });
+        let mut config = Config::default();
+        config.faults.insert(
+            MessageClass::Other,
+            MessageFaults { drop_probability: 0., duplicate_probability: 1., delay_ms: 100 },
+        );
+        config.validate().unwrap();
+        assert_eq!(
+            config.on_received(&peer_id, &control),
+            Action::Process { copies: 1, delay: time::Duration::ZERO }
+        );
+        assert_eq!(
+            config.on_received(&peer_id, &other),
+            Action::Process { copies: 2, delay: time::Duration::milliseconds(100) }
+        );
+
+        config.partitioned_peers.insert(peer_id.clone());
+        assert_eq!(config.on_received(&peer_id, &control), Action::Drop);
+        assert!(!config.is_partitioned(&data::make_peer_id(&mut rng)));
+
+        config.faults.get_mut(&MessageClass::Other).unwrap().drop_probability = 1.5;
+        assert!(config.validate().is_err());
+    }
+}
@@ -3,6 +3,7 @@ use crate::types::{NetworkInfo, ReasonForBan};
 use near_async::messaging::{AsyncSender, Sender};
 use near_async::{MultiSend, MultiSendMessage, MultiSenderFrom};
 use near_primitives::block::{Approval, Block, BlockHeader};
+use near_primitives::block_headers_range::{BlockHeadersRange, BlockHeadersRangeRequest};
 use near_primitives::challenge::Challenge;
 use near_primitives::epoch_sync::CompressedEpochSyncProof;
 use near_primitives::errors::InvalidTxError;
@@ -54,6 +55,19 @@ pub struct BlockHeadersRequest(pub Vec<CryptoHash>);
 #[rtype(result = "Result<(),ReasonForBan>")]
 pub struct BlockHeadersResponse(pub Vec<BlockHeader>, pub PeerId);
 
+/// Request a range of headers committed to by a later block.
+#[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
+#[rtype(result = "Option<Box<BlockHeadersRange>>")]
+pub struct BlockHeadersRangeRequestMessage(pub BlockHeadersRangeRequest);
+
+/// Range of headers response.
+#[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
+#[rtype(result = "Result<(),ReasonForBan>")]
+pub struct BlockHeadersRangeResponse {
+    pub range: Box<BlockHeadersRange>,
+    pub from_peer: PeerId,
+}
+
 /// State request header.
 #[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
 #[rtype(result = "Option<StateResponse>")]
@@ -153,6 +167,9 @@ pub struct ClientSenderForNetwork {
     pub block_headers_request: AsyncSender<BlockHeadersRequest, Option<Vec<BlockHeader>>>,
     pub block: AsyncSender<BlockResponse, ()>,
     pub block_headers: AsyncSender<BlockHeadersResponse, Result<(), ReasonForBan>>,
+    pub block_headers_range_request:
+        AsyncSender<BlockHeadersRangeRequestMessage, Option<Box<BlockHeadersRange>>>,
+    pub block_headers_range: AsyncSender<BlockHeadersRangeResponse, Result<(), ReasonForBan>>,
     pub challenge: AsyncSender<RecvChallenge, ()>,
     pub network_info: AsyncSender<SetNetworkInfo, ()>,
     pub announce_account:
@@ -1,9 +1,11 @@
 use crate::blacklist;
 use crate::concurrency::rate;
+use crate::network_protocol::compression;
 use crate::network_protocol::PeerAddr;
 use crate::network_protocol::PeerInfo;
 use crate::peer_manager::peer_store;
-use crate::rate_limits::messages_limits;
+use crate::proxy;
+use crate::rate_limits::{messages_limits, send_shaping};
 use crate::snapshot_hosts;
 use crate::stun;
 use crate::tcp;
@@ -107,11 +109,13 @@ pub struct Tier1 {
 pub struct SocketOptions {
     pub recv_buffer_size: Option<u32>,
     pub send_buffer_size: Option<u32>,
+    /// Proxy through which the outbound connections are established.
+    pub proxy: Option<proxy::Config>,
 }
 
 impl SocketOptions {
     pub fn default() -> SocketOptions {
-        SocketOptions { recv_buffer_size: None, send_buffer_size: None }
+        SocketOptions { recv_buffer_size: None, send_buffer_size: None, proxy: None }
     }
 }
 
@@ -195,6 +199,12 @@ pub struct NetworkConfig {
 
     /// Configuration of rate limits for incoming messages.
     pub received_messages_rate_limits: messages_limits::Config,
+    /// Per class limits of the messages sent to each peer.
+    pub sent_messages_shaping: send_shaping::Config,
+    /// Compression of the large routed messages exchanged with TIER2 peers.
+    pub routed_message_compression: compression::Config,
+    /// Dictionary loaded from `routed_message_compression.dictionary_file`.
+    pub routed_message_dictionary: Option<Arc<compression::Dictionary>>,
 
     #[cfg(test)]
     pub(crate) event_sink:
@@ -244,6 +254,12 @@ impl NetworkConfig {
         if let Some(rate_limits) = overrides.received_messages_rate_limits {
             self.received_messages_rate_limits.apply_overrides(rate_limits);
         }
+        if let Some(shaping) = overrides.sent_messages_shaping {
+            self.sent_messages_shaping = shaping;
+        }
+        if let Some(compression) = overrides.routed_message_compression {
+            self.routed_message_compression = compression;
+        }
     }
 
     pub fn new(
@@ -349,6 +365,7 @@ impl NetworkConfig {
             socket_options: SocketOptions {
                 recv_buffer_size: cfg.so_recv_buffer_size,
                 send_buffer_size: cfg.so_send_buffer_size,
+                proxy: cfg.proxy,
             },
             peer_recent_time_window: cfg.peer_recent_time_window.try_into()?,
             safe_set_size: cfg.safe_set_size,
@@ -380,12 +397,20 @@ impl NetworkConfig {
             },
             // Use a preset to configure rate limits and override entries with user defined values later.
             received_messages_rate_limits: messages_limits::Config::standard_preset(),
+            sent_messages_shaping: send_shaping::Config::default(),
+            routed_message_compression: compression::Config::default(),
+            routed_message_dictionary: None,
+            routed_message_dictionary: None,
             #[cfg(test)]
             event_sink: near_async::messaging::IntoSender::into_sender(
                 near_async::messaging::noop(),
             ),
         };
         this.override_config(cfg.experimental.network_config_overrides);
+        this.routed_message_dictionary = this
+            .routed_message_compression
+            .load_dictionary()
+            .context("routed_message_compression.dictionary_file")?;
         Ok(this)
     }
 
@@ -430,7 +455,7 @@ impl NetworkConfig {
             minimum_outbound_peers: 5,
             ideal_connections_lo: 30,
             ideal_connections_hi: 35,
-            socket_options: SocketOptions { recv_buffer_size: None, send_buffer_size: None },
+            socket_options: SocketOptions::default(),
             peer_recent_time_window: time::Duration::seconds(600),
             safe_set_size: 20,
             archival_peer_connections_lower_bound: 10,
@@ -458,6 +483,8 @@ impl NetworkConfig {
             }),
             skip_tombstones: None,
             received_messages_rate_limits: messages_limits::Config::default(),
+            sent_messages_shaping: send_shaping::Config::default(),
+            routed_message_compression: compression::Config::default(),
             #[cfg(test)]
             event_sink: near_async::messaging::IntoSender::into_sender(
                 near_async::messaging::noop(),
@@ -514,6 +541,9 @@ impl NetworkConfig {
         if let Err(err) = self.received_messages_rate_limits.validate() {
             anyhow::bail!("One or more invalid rate limits: {err:?}");
         }
+        if let Err(classes) = self.sent_messages_shaping.validate() {
+            anyhow::bail!("bytes_per_second must be positive for message classes {classes:?}");
+        }
 
         Ok(VerifiedConfig { node_id: self.node_id(), inner: self })
     }
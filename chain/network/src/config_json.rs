@@ -1,5 +1,7 @@
+use crate::network_protocol::compression;
 use crate::network_protocol::PeerAddr;
-use crate::rate_limits::messages_limits;
+use crate::proxy;
+use crate::rate_limits::{messages_limits, send_shaping};
 use crate::stun;
 use near_async::time::Duration;
 
@@ -209,6 +211,13 @@ pub struct Config {
     /// such a case.
     #[serde(default = "default_trusted_stun_servers")]
     pub trusted_stun_servers: Vec<stun::ServerAddr>,
+    /// SOCKS5 or HTTP CONNECT proxy through which all the outbound connections to peers are
+    /// established, for example
+    /// `{"protocol": "socks5", "addr": "127.0.0.1:1080"}`.
+    /// Optional `"credentials": {"username": ..., "password": ...}` are sent to the proxy
+    /// in plain text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<proxy::Config>,
     // Experimental part of the JSON config. Regular users/validators should not have to set any values there.
     // Field names in here can change/disappear at any moment without warning.
     #[serde(default)]
@@ -287,6 +296,8 @@ pub struct NetworkConfigOverrides {
     pub routing_table_update_rate_limit_burst: Option<u64>,
     pub routing_table_update_rate_limit_qps: Option<f64>,
     pub received_messages_rate_limits: Option<messages_limits::OverrideConfig>,
+    pub sent_messages_shaping: Option<send_shaping::Config>,
+    pub routed_message_compression: Option<compression::Config>,
 }
 
 impl Default for ExperimentalConfig {
@@ -332,6 +343,7 @@ impl Default for Config {
             public_addrs: vec![],
             allow_private_ip_in_public_addrs: false,
             trusted_stun_servers: default_trusted_stun_servers(),
+            proxy: None,
             experimental: Default::default(),
         }
     }
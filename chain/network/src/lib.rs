@@ -9,6 +9,7 @@ mod network_protocol;
 mod peer;
 mod peer_manager;
 mod private_actix;
+mod proxy;
 mod rate_limits;
 mod snapshot_hosts;
 mod stats;
@@ -32,6 +33,8 @@ pub mod test_loop;
 pub mod test_utils;
 pub mod types;
 
+#[cfg(feature = "test_features")]
+pub mod chaos;
 #[cfg(test)]
 pub(crate) mod testonly;
 
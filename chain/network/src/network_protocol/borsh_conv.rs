@@ -14,6 +14,8 @@ impl From<&net::Handshake> for mem::Handshake {
             sender_chain_info: x.sender_chain_info.clone(),
             partial_edge_info: x.partial_edge_info.clone(),
             owned_account: None,
+            supports_zstd: false,
+            zstd_dictionary: None,
         }
     }
 }
@@ -264,6 +266,18 @@ impl From<&mem::PeerMessage> for net::PeerMessage {
             mem::PeerMessage::EpochSyncResponse(proof) => {
                 net::PeerMessage::EpochSyncResponse(proof)
             }
+            mem::PeerMessage::BlockHeadersRangeRequest(_) => {
+                panic!("BlockHeadersRangeRequest is not supported in Borsh encoding")
+            }
+            mem::PeerMessage::BlockHeadersRange(_) => {
+                panic!("BlockHeadersRange is not supported in Borsh encoding")
+            }
+            mem::PeerMessage::ReachabilityProbeRequest => {
+                panic!("ReachabilityProbeRequest is not supported in Borsh encoding")
+            }
+            mem::PeerMessage::ReachabilityProbeResponse(_) => {
+                panic!("ReachabilityProbeResponse is not supported in Borsh encoding")
+            }
         }
     }
 }
@@ -0,0 +1,130 @@
+//! zstd compression of large TIER2 routed messages.
+//!
+//! Peers advertise in the Handshake whether they want to receive compressed messages.
+//! A compressed message is sent as a `PeerMessage` whose only content is the zstd compressed
+//! encoding of the original `PeerMessage`, so the receiver can always tell the two apart.
+//! Compression is only supported for the proto encoding.
+//!
+//! Peers may additionally load a zstd dictionary (trained on typical routed messages) and
+//! advertise its hash in the Handshake. If both peers advertise the same hash, the messages
+//! between them are compressed with that dictionary.
+use crate::network_protocol::proto;
+use crate::network_protocol::proto::peer_message::Message_type as ProtoMT;
+use near_primitives::hash::CryptoHash;
+use protobuf::Message as _;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Level used for compressing messages. Higher levels barely reduce the size of
+/// chunk parts and witnesses further, while costing a lot more CPU.
+const COMPRESSION_LEVEL: i32 = 3;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Whether to ask peers to compress the messages they send us, and compress the messages
+    /// sent to peers which asked for it.
+    pub enabled: bool,
+    /// Routed messages smaller than that are not compressed,
+    /// since the saved bytes are not worth the CPU.
+    pub min_message_size: usize,
+    /// Path to a zstd dictionary used for compressing messages exchanged with the
+    /// peers which use the same dictionary.
+    #[serde(default)]
+    pub dictionary_file: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { enabled: true, min_message_size: 16 * 1024, dictionary_file: None }
+    }
+}
+
+impl Config {
+    /// Loads the dictionary from `dictionary_file`, if set.
+    pub fn load_dictionary(&self) -> anyhow::Result<Option<Arc<Dictionary>>> {
+        let Some(path) = &self.dictionary_file else { return Ok(None) };
+        let bytes = std::fs::read(path)
+            .map_err(|err| anyhow::anyhow!("failed to read {}: {err}", path.display()))?;
+        Ok(Some(Arc::new(Dictionary::new(&bytes))))
+    }
+}
+
+/// zstd dictionary, prepared for compressing and decompressing messages.
+pub struct Dictionary {
+    /// Hash of the dictionary content, advertised in the Handshake.
+    pub(crate) hash: CryptoHash,
+    encoder: zstd::dict::EncoderDictionary<'static>,
+    decoder: zstd::dict::DecoderDictionary<'static>,
+}
+
+impl Dictionary {
+    pub fn new(bytes: &[u8]) -> Self {
+        Self {
+            hash: CryptoHash::hash_bytes(bytes),
+            encoder: zstd::dict::EncoderDictionary::copy(bytes, COMPRESSION_LEVEL),
+            decoder: zstd::dict::DecoderDictionary::copy(bytes),
+        }
+    }
+}
+
+/// Compression negotiated with a TIER2 peer during the Handshake.
+/// Compressed messages are accepted only from peers with which compression was negotiated.
+#[derive(Clone)]
+pub(crate) struct Negotiated {
+    /// Routed messages smaller than that are sent uncompressed.
+    pub min_message_size: usize,
+    /// Dictionary used by both peers, if any.
+    pub dictionary: Option<Arc<Dictionary>>,
+}
+
+/// Wraps the proto encoded `PeerMessage` into a compressed `PeerMessage`.
+pub(crate) fn compress(
+    encoded: &[u8],
+    dictionary: Option<&Dictionary>,
+) -> std::io::Result<Vec<u8>> {
+    let zstd = match dictionary {
+        Some(dictionary) => zstd::bulk::Compressor::with_prepared_dictionary(&dictionary.encoder)?
+            .compress(encoded)?,
+        None => zstd::bulk::compress(encoded, COMPRESSION_LEVEL)?,
+    };
+    let msg = proto::PeerMessage {
+        message_type: Some(ProtoMT::Compressed(proto::Compressed {
+            zstd,
+            dictionary: dictionary.map(|d| (&d.hash).into()).into(),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    Ok(msg.write_to_bytes().unwrap())
+}
+
+/// Decompresses the content of a compressed `PeerMessage`.
+/// Fails if the decompressed message is larger than `max_size`, to protect against zip bombs.
+/// Fails if the message was compressed with a dictionary other than `dictionary`.
+pub(crate) fn decompress(
+    compressed: &proto::Compressed,
+    dictionary: Option<&Dictionary>,
+    max_size: usize,
+) -> std::io::Result<Vec<u8>> {
+    let decoder: Box<dyn Read + '_> = match (compressed.dictionary.as_ref(), dictionary) {
+        (None, _) => Box::new(zstd::stream::Decoder::new(&compressed.zstd[..])?),
+        (Some(hash), Some(dictionary)) if *hash == proto::CryptoHash::from(&dictionary.hash) => {
+            Box::new(zstd::stream::Decoder::with_prepared_dictionary(
+                &compressed.zstd[..],
+                &dictionary.decoder,
+            )?)
+        }
+        (Some(_), _) => {
+            return Err(std::io::Error::other("message compressed with an unknown dictionary"))
+        }
+    };
+    let mut encoded = vec![];
+    decoder.take(max_size as u64 + 1).read_to_end(&mut encoded)?;
+    if encoded.len() > max_size {
+        return Err(std::io::Error::other(format!(
+            "decompressed message exceeds the limit of {max_size} bytes"
+        )));
+    }
+    Ok(encoded)
+}
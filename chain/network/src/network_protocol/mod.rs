@@ -2,6 +2,7 @@
 #[path = "borsh.rs"]
 mod borsh_;
 mod borsh_conv;
+pub mod compression;
 mod edge;
 mod peer;
 mod proto_conv;
@@ -41,6 +42,7 @@ use near_crypto::PublicKey;
 use near_crypto::Signature;
 use near_o11y::OpenTelemetrySpanExt;
 use near_primitives::block::{Approval, Block, BlockHeader, GenesisId};
+use near_primitives::block_headers_range::{BlockHeadersRange, BlockHeadersRangeRequest};
 use near_primitives::challenge::Challenge;
 use near_primitives::epoch_sync::CompressedEpochSyncProof;
 use near_primitives::hash::CryptoHash;
@@ -365,6 +367,10 @@ pub struct Handshake {
     pub(crate) partial_edge_info: PartialEdgeInfo,
     /// Account owned by the sender.
     pub(crate) owned_account: Option<SignedOwnedAccount>,
+    /// Whether the sender wants to receive large routed messages compressed.
+    pub(crate) supports_zstd: bool,
+    /// Hash of the zstd dictionary used by the sender, if any.
+    pub(crate) zstd_dictionary: Option<CryptoHash>,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, strum::IntoStaticStr)]
@@ -448,6 +454,15 @@ pub enum PeerMessage {
 
     EpochSyncRequest,
     EpochSyncResponse(CompressedEpochSyncProof),
+
+    BlockHeadersRangeRequest(BlockHeadersRangeRequest),
+    BlockHeadersRange(Box<BlockHeadersRange>),
+
+    /// Asks the peer to open a connection to the address which this node advertised
+    /// in the handshake, to check whether other nodes can connect to it.
+    ReachabilityProbeRequest,
+    /// Whether the peer managed to connect to the node.
+    ReachabilityProbeResponse(bool),
 }
 
 impl fmt::Display for PeerMessage {
@@ -472,6 +487,12 @@ pub enum ParsePeerMessageError {
     ProtoDecode(#[source] protobuf::Error),
     #[error("ProtoConv")]
     ProtoConv(#[source] proto_conv::ParsePeerMessageError),
+    #[error("Decompress")]
+    Decompress(#[source] std::io::Error),
+    #[error("compressed message received, but compression was not negotiated")]
+    CompressionNotNegotiated,
+    #[error("compressed message doesn't contain a routed message")]
+    CompressedNotRouted,
 }
 
 impl PeerMessage {
@@ -489,9 +510,13 @@ impl PeerMessage {
         }
     }
 
+    /// Deserializes a message in the given encoding.
+    /// `negotiated` is the compression negotiated with the sender: compressed messages
+    /// are rejected if it is None.
     pub(crate) fn deserialize(
         enc: Encoding,
         data: &[u8],
+        negotiated: Option<&compression::Negotiated>,
     ) -> Result<PeerMessage, ParsePeerMessageError> {
         let span = tracing::trace_span!(target: "network", "deserialize").entered();
         Ok(match enc {
@@ -500,8 +525,28 @@ impl PeerMessage {
                 .try_into()
                 .map_err(ParsePeerMessageError::BorshConv)?,
             Encoding::Proto => {
-                let proto_msg: proto::PeerMessage = proto::PeerMessage::parse_from_bytes(data)
+                let mut proto_msg: proto::PeerMessage = proto::PeerMessage::parse_from_bytes(data)
                     .map_err(ParsePeerMessageError::ProtoDecode)?;
+                if let Some(proto::peer_message::Message_type::Compressed(c)) =
+                    &proto_msg.message_type
+                {
+                    let negotiated =
+                        negotiated.ok_or(ParsePeerMessageError::CompressionNotNegotiated)?;
+                    let data = compression::decompress(
+                        c,
+                        negotiated.dictionary.as_deref(),
+                        crate::peer::NETWORK_MESSAGE_MAX_SIZE_BYTES,
+                    )
+                    .map_err(ParsePeerMessageError::Decompress)?;
+                    proto_msg = proto::PeerMessage::parse_from_bytes(&data)
+                        .map_err(ParsePeerMessageError::ProtoDecode)?;
+                    if !matches!(
+                        proto_msg.message_type,
+                        Some(proto::peer_message::Message_type::Routed(_))
+                    ) {
+                        return Err(ParsePeerMessageError::CompressedNotRouted);
+                    }
+                }
                 if let Ok(extracted_span_context) = extract_span_context(&proto_msg.trace_context) {
                     span.clone().or_current().add_link(extracted_span_context);
                 }
@@ -564,6 +609,12 @@ pub enum RoutedMessageBody {
     ContractCodeRequest(ContractCodeRequest),
     ContractCodeResponse(ContractCodeResponse),
     PartialEncodedContractDeploys(PartialEncodedContractDeploys),
+    Challenge(Challenge),
+    /// Requests for parts of several chunks, answered with a single
+    /// `PartialEncodedChunkResponseBatch`.
+    PartialEncodedChunkRequestBatch(Vec<PartialEncodedChunkRequestMsg>),
+    PartialEncodedChunkResponseBatch(Vec<PartialEncodedChunkResponseMsg>),
+    PartialEncodedChunkForwardAck(PartialEncodedChunkForwardAckMsg),
 }
 
 impl RoutedMessageBody {
@@ -573,8 +624,11 @@ impl RoutedMessageBody {
         match self {
             // These messages are important because they are critical for block and chunk production,
             // and lost messages cannot be requested again.
+            // Challenges are important because they carry evidence of misbehavior which must
+            // reach validators even when the network is congested.
             RoutedMessageBody::BlockApproval(_)
-            | RoutedMessageBody::VersionedPartialEncodedChunk(_) => IMPORTANT_MESSAGE_RESENT_COUNT,
+            | RoutedMessageBody::VersionedPartialEncodedChunk(_)
+            | RoutedMessageBody::Challenge(_) => IMPORTANT_MESSAGE_RESENT_COUNT,
             // Default value is sending just once.
             _ => 1,
         }
@@ -635,6 +689,11 @@ impl fmt::Debug for RoutedMessageBody {
                 forward.chunk_hash,
                 forward.parts.iter().map(|p| p.part_ord).collect::<Vec<_>>(),
             ),
+            RoutedMessageBody::PartialEncodedChunkForwardAck(ack) => write!(
+                f,
+                "PartialChunkForwardAck({:?}, {:?}, {:?})",
+                ack.chunk_hash, ack.account_id, ack.part_ords,
+            ),
             RoutedMessageBody::Ping(_) => write!(f, "Ping"),
             RoutedMessageBody::Pong(_) => write!(f, "Pong"),
             RoutedMessageBody::_UnusedVersionedStateResponse => write!(f, "VersionedStateResponse"),
@@ -667,6 +726,17 @@ impl fmt::Debug for RoutedMessageBody {
             RoutedMessageBody::PartialEncodedContractDeploys(deploys) => {
                 write!(f, "PartialEncodedContractDeploys(part={:?}", deploys.part())
             }
+            RoutedMessageBody::Challenge(challenge) => write!(f, "Challenge({})", challenge.hash),
+            RoutedMessageBody::PartialEncodedChunkRequestBatch(requests) => write!(
+                f,
+                "PartialChunkRequestBatch({:?})",
+                requests.iter().map(|r| &r.chunk_hash).collect::<Vec<_>>()
+            ),
+            RoutedMessageBody::PartialEncodedChunkResponseBatch(responses) => write!(
+                f,
+                "PartialChunkResponseBatch({:?})",
+                responses.iter().map(|r| &r.chunk_hash).collect::<Vec<_>>()
+            ),
         }
     }
 }
@@ -752,6 +822,7 @@ impl RoutedMessage {
             RoutedMessageBody::Ping(_)
                 | RoutedMessageBody::TxStatusRequest(_, _)
                 | RoutedMessageBody::PartialEncodedChunkRequest(_)
+                | RoutedMessageBody::PartialEncodedChunkRequestBatch(_)
         )
     }
 
@@ -798,6 +869,19 @@ pub struct PartialEncodedChunkForwardMsg {
     pub parts: Vec<PartialEncodedChunkPart>,
 }
 
+/// Acknowledges the parts of a `PartialEncodedChunkForwardMsg` to the part owner when chunk
+/// parts are pushed, see `ChunkPartDistributionMode::Push`. The owner pushes again only the
+/// parts which were not acknowledged.
+#[derive(
+    Clone, Debug, Eq, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize, ProtocolSchema,
+)]
+pub struct PartialEncodedChunkForwardAckMsg {
+    pub chunk_hash: ChunkHash,
+    /// Account which received the parts.
+    pub account_id: AccountId,
+    pub part_ords: Vec<u64>,
+}
+
 /// Test code that someone become part of our protocol?
 #[derive(
     borsh::BorshSerialize,
@@ -77,6 +77,8 @@ pub enum ParseHandshakeError {
     PartialEdgeInfo(ParseRequiredError<ParsePartialEdgeInfoError>),
     #[error("owned_account {0}")]
     OwnedAccount(ParseSignedOwnedAccountError),
+    #[error("zstd_dictionary {0}")]
+    ZstdDictionary(ParseCryptoHashError),
 }
 
 impl From<&Handshake> for proto::Handshake {
@@ -90,6 +92,8 @@ impl From<&Handshake> for proto::Handshake {
             sender_chain_info: MF::some((&x.sender_chain_info).into()),
             partial_edge_info: MF::some((&x.partial_edge_info).into()),
             owned_account: x.owned_account.as_ref().map(Into::into).into(),
+            supports_zstd: x.supports_zstd,
+            zstd_dictionary: x.zstd_dictionary.as_ref().map(Into::into).into(),
             ..Self::default()
         }
     }
@@ -120,6 +124,9 @@ impl TryFrom<&proto::Handshake> for Handshake {
                 .map_err(Self::Error::PartialEdgeInfo)?,
             owned_account: try_from_optional(&p.owned_account)
                 .map_err(Self::Error::OwnedAccount)?,
+            supports_zstd: p.supports_zstd,
+            zstd_dictionary: try_from_optional(&p.zstd_dictionary)
+                .map_err(Self::Error::ZstdDictionary)?,
         })
     }
 }
@@ -13,6 +13,7 @@ use crate::types::StateResponseInfo;
 use borsh::BorshDeserialize as _;
 use near_async::time::error::ComponentRange;
 use near_primitives::block::{Block, BlockHeader};
+use near_primitives::block_headers_range::{BlockHeadersRange, BlockHeadersRangeRequest};
 use near_primitives::challenge::Challenge;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::utils::compression::CompressedData;
@@ -342,6 +343,30 @@ impl From<&PeerMessage> for proto::PeerMessage {
                         ..Default::default()
                     })
                 }
+                PeerMessage::BlockHeadersRangeRequest(r) => {
+                    ProtoMT::BlockHeadersRangeRequest(proto::BlockHeadersRangeRequest {
+                        anchor_hash: MF::some((&r.anchor_hash).into()),
+                        start_height: r.start_height,
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::BlockHeadersRange(r) => {
+                    ProtoMT::BlockHeadersRangeResponse(proto::BlockHeadersRangeResponse {
+                        borsh: borsh::to_vec(&r).unwrap(),
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::ReachabilityProbeRequest => {
+                    ProtoMT::ReachabilityProbeRequest(proto::ReachabilityProbeRequest {
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::ReachabilityProbeResponse(reachable) => {
+                    ProtoMT::ReachabilityProbeResponse(proto::ReachabilityProbeResponse {
+                        reachable: *reachable,
+                        ..Default::default()
+                    })
+                }
             }),
             ..Default::default()
         }
@@ -352,6 +377,7 @@ pub type ParsePeersRequestError = std::io::Error;
 pub type ParseTransactionError = std::io::Error;
 pub type ParseRoutedError = std::io::Error;
 pub type ParseChallengeError = std::io::Error;
+pub type ParseBlockHeadersRangeError = std::io::Error;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ParsePeerMessageError {
@@ -397,6 +423,12 @@ pub enum ParsePeerMessageError {
     StateResponse(ParseRequiredError<ParseStateInfoError>),
     #[error("sync_snapshot_hosts: {0}")]
     SyncSnapshotHosts(ParseSyncSnapshotHostsError),
+    #[error("block_headers_range_request: {0}")]
+    BlockHeadersRangeRequest(ParseRequiredError<ParseCryptoHashError>),
+    #[error("block_headers_range_response: {0}")]
+    BlockHeadersRangeResponse(ParseBlockHeadersRangeError),
+    #[error("compressed message inside a compressed message")]
+    NestedCompressed,
 }
 
 impl TryFrom<&proto::PeerMessage> for PeerMessage {
@@ -505,6 +537,23 @@ impl TryFrom<&proto::PeerMessage> for PeerMessage {
             ProtoMT::EpochSyncResponse(esr) => PeerMessage::EpochSyncResponse(
                 CompressedData::from_boxed_slice(esr.compressed_proof.clone().into_boxed_slice()),
             ),
+            ProtoMT::BlockHeadersRangeRequest(r) => {
+                PeerMessage::BlockHeadersRangeRequest(BlockHeadersRangeRequest {
+                    anchor_hash: try_from_required(&r.anchor_hash)
+                        .map_err(Self::Error::BlockHeadersRangeRequest)?,
+                    start_height: r.start_height,
+                })
+            }
+            ProtoMT::BlockHeadersRangeResponse(r) => PeerMessage::BlockHeadersRange(Box::new(
+                BlockHeadersRange::try_from_slice(&r.borsh)
+                    .map_err(Self::Error::BlockHeadersRangeResponse)?,
+            )),
+            ProtoMT::ReachabilityProbeRequest(_) => PeerMessage::ReachabilityProbeRequest,
+            ProtoMT::ReachabilityProbeResponse(r) => {
+                PeerMessage::ReachabilityProbeResponse(r.reachable)
+            }
+            // `PeerMessage::deserialize` unwraps the compressed message before the conversion.
+            ProtoMT::Compressed(_) => return Err(Self::Error::NestedCompressed),
         })
     }
 }
@@ -167,6 +167,8 @@ pub fn make_challenge<R: Rng>(rng: &mut R) -> Challenge {
             left_block_header: rng.sample_iter(&Standard).take(65).collect(),
             right_block_header: rng.sample_iter(&Standard).take(34).collect(),
         }),
+        EpochId::default(),
+        rng.gen(),
         &make_validator_signer(rng),
     )
 }
@@ -337,6 +339,8 @@ pub fn make_handshake<R: Rng>(rng: &mut R, chain: &Chain) -> Handshake {
         sender_chain_info: chain.get_peer_chain_info(),
         partial_edge_info: make_partial_edge(rng),
         owned_account: None,
+        supports_zstd: false,
+        zstd_dictionary: None,
     }
 }
 
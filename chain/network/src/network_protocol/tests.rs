@@ -72,13 +72,97 @@ fn serialize_deserialize_protobuf_only() {
         }),
     ];
     for m in msgs {
-        let m2 = PeerMessage::deserialize(Encoding::Proto, &m.serialize(Encoding::Proto))
+        let m2 = PeerMessage::deserialize(Encoding::Proto, &m.serialize(Encoding::Proto), None)
             .with_context(|| m.to_string())
             .unwrap();
         assert_eq!(m, m2);
     }
 }
 
+#[test]
+fn serialize_deserialize_compressed() {
+    let mut rng = make_rng(39521947542);
+    let mut clock = time::FakeClock::default();
+    let chain = data::Chain::make(&mut clock, &mut rng, 12);
+    let chunk_hash = chain.blocks[3].chunks()[0].chunk_hash();
+    let msg = PeerMessage::Routed(Box::new(data::make_routed_message(
+        &mut rng,
+        RoutedMessageBody::PartialEncodedChunkResponse(PartialEncodedChunkResponseMsg {
+            chunk_hash: chunk_hash.clone(),
+            parts: data::make_chunk_parts(chain.chunks[&chunk_hash].clone()),
+            receipts: vec![],
+        }),
+    )));
+    let encoded = msg.serialize(Encoding::Proto);
+    let negotiated = compression::Negotiated { min_message_size: 0, dictionary: None };
+    let compressed = compression::compress(&encoded, None).unwrap();
+    assert_eq!(
+        msg,
+        PeerMessage::deserialize(Encoding::Proto, &compressed, Some(&negotiated)).unwrap()
+    );
+
+    // Compressed messages are accepted only if compression was negotiated.
+    assert!(PeerMessage::deserialize(Encoding::Proto, &compressed, None).is_err());
+
+    // Only routed messages can be compressed,
+    // in particular a compressed message is not allowed inside another one.
+    let nested = compression::compress(&compressed, None).unwrap();
+    assert!(PeerMessage::deserialize(Encoding::Proto, &nested, Some(&negotiated)).is_err());
+    let disconnect = PeerMessage::Disconnect(Disconnect { remove_from_connection_store: true });
+    let disconnect = compression::compress(&disconnect.serialize(Encoding::Proto), None).unwrap();
+    assert!(PeerMessage::deserialize(Encoding::Proto, &disconnect, Some(&negotiated)).is_err());
+
+    // Decompressing fails instead of allocating more than the limit.
+    let proto::PeerMessage {
+        message_type: Some(proto::peer_message::Message_type::Compressed(c)),
+        ..
+    } = proto::PeerMessage::parse_from_bytes(&compressed).unwrap()
+    else {
+        panic!("expected a compressed message");
+    };
+    assert_eq!(encoded, compression::decompress(&c, None, encoded.len()).unwrap());
+    assert!(compression::decompress(&c, None, encoded.len() - 1).is_err());
+}
+
+#[test]
+fn serialize_deserialize_compressed_with_dictionary() {
+    let mut rng = make_rng(39521947542);
+    let mut clock = time::FakeClock::default();
+    let chain = data::Chain::make(&mut clock, &mut rng, 12);
+    let chunk_hash = chain.blocks[3].chunks()[0].chunk_hash();
+    let msg = PeerMessage::Routed(Box::new(data::make_routed_message(
+        &mut rng,
+        RoutedMessageBody::PartialEncodedChunkResponse(PartialEncodedChunkResponseMsg {
+            chunk_hash: chunk_hash.clone(),
+            parts: data::make_chunk_parts(chain.chunks[&chunk_hash].clone()),
+            receipts: vec![],
+        }),
+    )));
+    let encoded = msg.serialize(Encoding::Proto);
+    let dictionary = Arc::new(compression::Dictionary::new(&encoded));
+    let negotiated =
+        compression::Negotiated { min_message_size: 0, dictionary: Some(dictionary.clone()) };
+    let compressed = compression::compress(&encoded, Some(&dictionary)).unwrap();
+    assert!(compressed.len() < compression::compress(&encoded, None).unwrap().len());
+    assert_eq!(
+        msg,
+        PeerMessage::deserialize(Encoding::Proto, &compressed, Some(&negotiated)).unwrap()
+    );
+
+    // Decompressing fails without the dictionary, or with a different one.
+    let without_dictionary = compression::Negotiated { min_message_size: 0, dictionary: None };
+    assert!(
+        PeerMessage::deserialize(Encoding::Proto, &compressed, Some(&without_dictionary)).is_err()
+    );
+    let other_dictionary = compression::Negotiated {
+        min_message_size: 0,
+        dictionary: Some(Arc::new(compression::Dictionary::new(&encoded[1..]))),
+    };
+    assert!(
+        PeerMessage::deserialize(Encoding::Proto, &compressed, Some(&other_dictionary)).is_err()
+    );
+}
+
 #[test]
 fn serialize_deserialize() -> anyhow::Result<()> {
     let mut rng = make_rng(89028037453);
@@ -135,7 +219,7 @@ fn serialize_deserialize() -> anyhow::Result<()> {
     for enc in [Encoding::Proto, Encoding::Borsh] {
         for m in &msgs {
             (|| {
-                let m2 = PeerMessage::deserialize(enc, &m.serialize(enc))
+                let m2 = PeerMessage::deserialize(enc, &m.serialize(enc), None)
                     .with_context(|| m.to_string())?;
                 if *m != m2 {
                     bail!("deserialize(serialize({m}) = {m2}");
@@ -159,7 +243,7 @@ fn serialize_deserialize() -> anyhow::Result<()> {
     for (from, to) in [(Encoding::Proto, Encoding::Borsh), (Encoding::Borsh, Encoding::Proto)] {
         for m in &msgs {
             let bytes = &m.serialize(from);
-            match PeerMessage::deserialize(to, bytes) {
+            match PeerMessage::deserialize(to, bytes, None) {
                 Err(_) => {}
                 Ok(m2) => {
                     bail!("from={from:?},to={to:?}: deserialize(serialize({m})) = {m2}, want error")
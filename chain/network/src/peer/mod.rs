@@ -3,6 +3,8 @@ mod stream;
 mod tracker;
 mod transfer_stats;
 
+pub(crate) use stream::NETWORK_MESSAGE_MAX_SIZE_BYTES;
+
 #[cfg(test)]
 pub(crate) mod testonly;
 #[cfg(test)]
@@ -1,12 +1,16 @@
 use crate::accounts_data::AccountDataError;
+#[cfg(feature = "test_features")]
+use crate::chaos;
 use crate::client::{
-    AnnounceAccountRequest, BlockHeadersRequest, BlockHeadersResponse, BlockRequest, BlockResponse,
+    AnnounceAccountRequest, BlockHeadersRangeRequestMessage, BlockHeadersRangeResponse,
+    BlockHeadersRequest, BlockHeadersResponse, BlockRequest, BlockResponse,
     EpochSyncRequestMessage, EpochSyncResponseMessage, ProcessTxRequest, RecvChallenge,
     StateRequestHeader, StateRequestPart, StateResponseReceived,
 };
 use crate::concurrency::atomic_cell::AtomicCell;
 use crate::concurrency::demux;
 use crate::config::PEERS_RESPONSE_MAX_PEERS;
+use crate::network_protocol::compression;
 #[cfg(feature = "distance_vector_routing")]
 use crate::network_protocol::DistanceVector;
 use crate::network_protocol::{
@@ -24,6 +28,7 @@ use crate::peer_manager::peer_manager_actor::Event;
 use crate::peer_manager::peer_manager_actor::MAX_TIER2_PEERS;
 use crate::private_actix::{RegisterPeerError, SendMessage};
 use crate::rate_limits::messages_limits;
+use crate::rate_limits::send_shaping::MessageClass;
 use crate::routing::edge::verify_nonce;
 #[cfg(feature = "distance_vector_routing")]
 use crate::routing::NetworkTopologyChange;
@@ -179,6 +184,9 @@ pub(crate) struct PeerActor {
     /// Whether the PeerActor should skip protobuf support detection and use
     /// a given encoding right away.
     force_encoding: Option<Encoding>,
+    /// Compression negotiated with the peer during the Handshake.
+    /// None if the peer didn't ask for compression, or if it is disabled.
+    compression: Option<compression::Negotiated>,
 
     /// Peer status.
     peer_status: PeerStatus,
@@ -340,7 +348,12 @@ impl PeerActor {
                 let peer_addr = stream.peer_addr;
                 let stream_type = stream.type_.clone();
                 let stats = Arc::new(connection::Stats::default());
-                let framed = stream::FramedStream::spawn(ctx, stream, stats.clone());
+                let framed = stream::FramedStream::spawn(
+                    ctx,
+                    stream,
+                    stats.clone(),
+                    &network_state.config.sent_messages_shaping,
+                );
                 Self {
                     closing_reason: None,
                     clock,
@@ -360,6 +373,7 @@ impl PeerActor {
                     ),
                     protocol_buffers_supported: false,
                     force_encoding,
+                    compression: None,
                     peer_info: match &stream_type {
                         tcp::StreamType::Inbound => None,
                         tcp::StreamType::Outbound { peer_id, .. } => Some(PeerInfo {
@@ -396,13 +410,13 @@ impl PeerActor {
 
     fn parse_message(&mut self, msg: &[u8]) -> Result<PeerMessage, ParsePeerMessageError> {
         if let Some(e) = self.encoding() {
-            return PeerMessage::deserialize(e, msg);
+            return PeerMessage::deserialize(e, msg, self.compression.as_ref());
         }
-        if let Ok(msg) = PeerMessage::deserialize(Encoding::Proto, msg) {
+        if let Ok(msg) = PeerMessage::deserialize(Encoding::Proto, msg, None) {
             self.protocol_buffers_supported = true;
             return Ok(msg);
         }
-        return PeerMessage::deserialize(Encoding::Borsh, msg);
+        return PeerMessage::deserialize(Encoding::Borsh, msg, None);
     }
 
     fn send_message_or_log(&self, msg: &PeerMessage) {
@@ -413,6 +427,11 @@ impl PeerActor {
         if let (PeerStatus::Ready(conn), PeerMessage::PeersRequest(_)) = (&self.peer_status, msg) {
             conn.last_time_peer_requested.store(Some(self.clock.now()));
         }
+        if let (PeerStatus::Ready(conn), Some(kind)) =
+            (&self.peer_status, connection::RequestKind::of_request(msg))
+        {
+            conn.stats.request_sent(kind, self.clock.now());
+        }
         if let Some(enc) = self.encoding() {
             return self.send_message_with_encoding(msg, enc);
         }
@@ -428,6 +447,12 @@ impl PeerActor {
         fields(msg_type = msg.msg_variant())
     )]
     fn send_message_with_encoding(&self, msg: &PeerMessage, enc: Encoding) {
+        #[cfg(feature = "test_features")]
+        if let PeerStatus::Ready(conn) = &self.peer_status {
+            if self.network_state.chaos.load().is_partitioned(&conn.peer_info.id) {
+                return;
+            }
+        }
         // Skip sending block and headers if we received it or header from this peer.
         // Record block requests in tracker.
         match msg {
@@ -452,19 +477,58 @@ impl PeerActor {
             _ => (),
         };
 
-        let bytes = msg.serialize(enc);
-        self.tracker.lock().increment_sent(&self.clock, bytes.len() as u64);
+        let mut bytes = msg.serialize(enc);
+        if let (Encoding::Proto, PeerMessage::Routed(_), Some(negotiated)) =
+            (enc, msg, &self.compression)
+        {
+            if bytes.len() >= negotiated.min_message_size {
+                bytes = self.compress(msg, bytes, negotiated);
+            }
+        }
         let bytes_len = bytes.len();
         tracing::trace!(target: "network", msg_len = bytes_len);
-        self.framed.send(stream::Frame(bytes));
-        metrics::PEER_DATA_SENT_BYTES.inc_by(bytes_len as u64);
         let msg_type = msg.msg_variant();
+        if !self.framed.send(MessageClass::of(msg), stream::Frame(bytes)) {
+            metrics::MessageDropped::SendQueueFull.inc_msg_type(msg_type);
+            return;
+        }
+        self.tracker.lock().increment_sent(&self.clock, bytes_len as u64);
+        metrics::PEER_DATA_SENT_BYTES.inc_by(bytes_len as u64);
         metrics::PEER_MESSAGE_SENT_BY_TYPE_TOTAL.with_label_values(&[msg_type]).inc();
         metrics::PEER_MESSAGE_SENT_BY_TYPE_BYTES
             .with_label_values(&[msg_type])
             .inc_by(bytes_len as u64);
     }
 
+    /// Returns the compressed message, or the original one if compression didn't help.
+    fn compress(
+        &self,
+        msg: &PeerMessage,
+        bytes: Vec<u8>,
+        negotiated: &compression::Negotiated,
+    ) -> Vec<u8> {
+        let msg_type = msg.msg_variant();
+        let compressed = {
+            let _timer =
+                metrics::PEER_MESSAGE_COMPRESSION_TIME.with_label_values(&[msg_type]).start_timer();
+            compression::compress(&bytes, negotiated.dictionary.as_deref())
+        };
+        match compressed {
+            Ok(compressed) => {
+                metrics::PEER_MESSAGE_COMPRESSION_RATIO
+                    .with_label_values(&[msg_type])
+                    .observe(compressed.len() as f64 / bytes.len() as f64);
+                if compressed.len() < bytes.len() {
+                    return compressed;
+                }
+            }
+            Err(err) => {
+                tracing::warn!(target: "network", ?err, msg_type, "failed to compress message")
+            }
+        }
+        bytes
+    }
+
     fn send_handshake(&self, spec: HandshakeSpec) {
         let (height, tracked_shards) =
             if let Some(chain_info) = self.network_state.chain_info.load().as_ref() {
@@ -494,6 +558,13 @@ impl PeerActor {
                 }
                 .sign(&signer)
             }),
+            supports_zstd: self.network_state.config.routed_message_compression.enabled,
+            zstd_dictionary: self
+                .network_state
+                .config
+                .routed_message_dictionary
+                .as_ref()
+                .map(|dictionary| dictionary.hash),
         };
         let msg = match spec.tier {
             tcp::Tier::T1 => PeerMessage::Tier1Handshake(handshake),
@@ -665,11 +736,26 @@ impl PeerActor {
             account_id: None,
         };
 
+        let compression = &self.network_state.config.routed_message_compression;
+        self.compression = (tier == tcp::Tier::T2
+            && compression.enabled
+            && handshake.supports_zstd)
+            .then(|| compression::Negotiated {
+                min_message_size: compression.min_message_size,
+                dictionary: self
+                    .network_state
+                    .config
+                    .routed_message_dictionary
+                    .clone()
+                    .filter(|dictionary| Some(dictionary.hash) == handshake.zstd_dictionary),
+            });
+
         let now = self.clock.now();
         let conn = Arc::new(connection::Connection {
             tier,
             addr: ctx.address(),
             peer_info: peer_info.clone(),
+            protocol_version: handshake.protocol_version,
             owned_account: handshake.owned_account.clone(),
             genesis_id: handshake.sender_chain_info.genesis_id.clone(),
             tracked_shards: handshake.sender_chain_info.tracked_shards.clone(),
@@ -677,6 +763,11 @@ impl PeerActor {
             last_block: Default::default(),
             peer_type: self.peer_type,
             stats: self.stats.clone(),
+            past_reputation: self
+                .network_state
+                .peer_store
+                .get_peer_state(&peer_info.id)
+                .map_or(0, |peer_state| peer_state.reputation),
             _peer_connections_metric: metrics::PEER_CONNECTIONS.new_point(&metrics::Connection {
                 tier: tier,
                 type_: self.peer_type,
@@ -979,10 +1070,11 @@ impl PeerActor {
         clock: &time::Clock,
         network_state: &Arc<NetworkState>,
         peer_id: PeerId,
+        author: &PeerId,
         msg_hash: CryptoHash,
         body: RoutedMessageBody,
     ) -> Result<Option<RoutedMessageBody>, ReasonForBan> {
-        Ok(network_state.receive_routed_message(clock, peer_id, msg_hash, body).await)
+        Ok(network_state.receive_routed_message(clock, peer_id, author, msg_hash, body).await)
     }
 
     fn receive_message(
@@ -1021,15 +1113,19 @@ impl PeerActor {
         };
         let clock = self.clock.clone();
         let network_state = self.network_state.clone();
+        let peer_info = conn.peer_info.clone();
         let peer_id = conn.peer_info.id.clone();
+        let stats = conn.stats.clone();
         let handling_future = async move {
             Ok(match msg {
                 PeerMessage::Routed(msg) => {
                     let msg_hash = msg.hash();
+                    let author = msg.msg.author.clone();
                     Self::receive_routed_message(
                         &clock,
                         &network_state,
                         peer_id,
+                        &author,
                         msg_hash,
                         msg.msg.body,
                     )
@@ -1085,6 +1181,23 @@ impl PeerActor {
                     }
                     None
                 }
+                PeerMessage::BlockHeadersRangeRequest(request) => network_state
+                    .client
+                    .send_async(BlockHeadersRangeRequestMessage(request))
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(PeerMessage::BlockHeadersRange),
+                PeerMessage::BlockHeadersRange(range) => {
+                    if let Ok(Err(ban_reason)) = network_state
+                        .client
+                        .send_async(BlockHeadersRangeResponse { range, from_peer: peer_id })
+                        .await
+                    {
+                        return Err(ban_reason);
+                    }
+                    None
+                }
                 PeerMessage::Challenge(challenge) => {
                     network_state.client.send_async(RecvChallenge(*challenge)).await.ok();
                     None
@@ -1125,8 +1238,25 @@ impl PeerActor {
                         .send(EpochSyncResponseMessage { from_peer: peer_id, proof });
                     None
                 }
+                PeerMessage::ReachabilityProbeRequest => {
+                    Some(PeerMessage::ReachabilityProbeResponse(
+                        network_state.dial_back(&peer_info).await,
+                    ))
+                }
+                PeerMessage::ReachabilityProbeResponse(reachable) => {
+                    if !network_state.reachability_prober.lock().finish(
+                        &peer_id,
+                        reachable,
+                        clock.now(),
+                    ) {
+                        tracing::debug!(target: "network", ?peer_id, "Unexpected reachability probe response");
+                        stats.invalid_messages.fetch_add(1, Ordering::Relaxed);
+                    }
+                    None
+                }
                 msg => {
                     tracing::error!(target: "network", "Peer received unexpected type: {:?}", msg);
+                    stats.invalid_messages.fetch_add(1, Ordering::Relaxed);
                     None
                 }
             })
@@ -1388,10 +1518,13 @@ impl PeerActor {
                         .network_state
                         .recent_routed_messages
                         .lock()
-                        .put(CryptoHash::hash_borsh(&msg.body), ())
+                        .put(CryptoHash::hash_borsh_non_consensus(&msg.body), ())
                         .is_none();
                     // Register that the message has been received.
                     metrics::record_routed_msg_metrics(&self.clock, &msg, conn.tier, fastest);
+                    if fastest && connection::carries_useful_data(&msg.body) {
+                        conn.stats.useful_messages.fetch_add(1, Ordering::Relaxed);
+                    }
                 }
 
                 // Drop duplicated messages routed within DROP_DUPLICATED_MESSAGES_PERIOD ms
@@ -1713,6 +1846,7 @@ impl actix::Handler<stream::Frame> for PeerActor {
                 .inc_by(msg.len() as u64);
             if !self.received_messages_rate_limits.is_allowed(&peer_msg, now) {
                 metrics::PEER_MESSAGE_RATE_LIMITED_BY_TYPE_TOTAL.with_label_values(&labels).inc();
+                self.stats.invalid_messages.fetch_add(1, Ordering::Relaxed);
                 tracing::debug!(target: "network", "Peer {} is being rate limited for message {}", self.peer_info, peer_msg.msg_variant());
                 return;
             }
@@ -1749,6 +1883,43 @@ impl actix::Handler<stream::Frame> for PeerActor {
                         }
                     }
                 }
+                // Only the data which the node requested or hasn't received yet is useful.
+                // Routed messages are counted once they are checked for duplicates.
+                let useful = match &peer_msg {
+                    PeerMessage::Block(block) => {
+                        self.tracker.lock().has_request(block.hash())
+                            || match self.network_state.chain_info.load().as_ref() {
+                                Some(chain_info) => {
+                                    block.header().height() > chain_info.block.header().height()
+                                }
+                                None => true,
+                            }
+                    }
+                    msg => connection::RequestKind::of_response(msg)
+                        .map_or(false, |kind| conn.stats.response_received(kind, now)),
+                };
+                if useful {
+                    conn.stats.useful_messages.fetch_add(1, Ordering::Relaxed);
+                }
+                #[cfg(feature = "test_features")]
+                match self.network_state.chaos.load().on_received(&conn.peer_info.id, &peer_msg) {
+                    chaos::Action::Drop => return,
+                    chaos::Action::Process { copies, delay }
+                        if copies > 1 || delay.is_positive() =>
+                    {
+                        let conn = conn.clone();
+                        ctx.run_later(delay.unsigned_abs(), move |act, ctx| {
+                            for _ in 0..copies {
+                                if act.closing_reason.is_some() {
+                                    return;
+                                }
+                                act.handle_msg_ready(ctx, conn.clone(), peer_msg.clone());
+                            }
+                        });
+                        return;
+                    }
+                    chaos::Action::Process { .. } => {}
+                }
                 // Handle the message.
                 self.handle_msg_ready(ctx, conn.clone(), peer_msg);
             }
@@ -1,4 +1,5 @@
 use crate::peer_manager::connection;
+use crate::rate_limits::send_shaping::{self, MessageClass, Pop, SendQueue};
 use crate::stats::metrics;
 use crate::tcp;
 use actix::fut::future::wrap_future;
@@ -13,7 +14,7 @@ use tokio::io::AsyncWriteExt as _;
 
 /// Maximum size of network message in encoded format.
 /// We encode length as `u32`, and therefore maximum size can't be larger than `u32::MAX`.
-const NETWORK_MESSAGE_MAX_SIZE_BYTES: usize = 512 * MIB as usize;
+pub(crate) const NETWORK_MESSAGE_MAX_SIZE_BYTES: usize = 512 * MIB as usize;
 /// Maximum capacity of write buffer in bytes.
 const MAX_WRITE_BUFFER_CAPACITY_BYTES: usize = GIB as usize;
 
@@ -57,7 +58,7 @@ pub(crate) enum Error {
 }
 
 pub(crate) struct FramedStream<Actor: actix::Actor> {
-    queue_send: tokio::sync::mpsc::UnboundedSender<Frame>,
+    send_queue: Arc<SendQueue>,
     stats: Arc<connection::Stats>,
     send_buf_size_metric: Arc<metrics::IntGaugeGuard>,
     addr: actix::Addr<Actor>,
@@ -73,9 +74,10 @@ where
         ctx: &mut actix::Context<Actor>,
         stream: tcp::Stream,
         stats: Arc<connection::Stats>,
+        shaping: &send_shaping::Config,
     ) -> Self {
         let (tcp_recv, tcp_send) = tokio::io::split(stream.stream);
-        let (queue_send, queue_recv) = tokio::sync::mpsc::unbounded_channel();
+        let send_queue = Arc::new(SendQueue::new(shaping, std::time::Instant::now()));
         let send_buf_size_metric = Arc::new(metrics::MetricGuard::new(
             &*metrics::PEER_DATA_WRITE_BUFFER_SIZE,
             vec![stream.peer_addr.to_string()],
@@ -84,8 +86,9 @@ where
             let addr = ctx.address();
             let stats = stats.clone();
             let m = send_buf_size_metric.clone();
+            let send_queue = send_queue.clone();
             async move {
-                if let Err(err) = Self::run_send_loop(tcp_send, queue_recv, stats, m).await {
+                if let Err(err) = Self::run_send_loop(tcp_send, send_queue, stats, m).await {
                     addr.do_send(Error::Send(SendError::IO(err)));
                 }
             }
@@ -101,14 +104,15 @@ where
                 }
             }
         }));
-        Self { queue_send, stats, send_buf_size_metric, addr: ctx.address() }
+        Self { send_queue, stats, send_buf_size_metric, addr: ctx.address() }
     }
 
-    /// Pushes `msg` to the send queue.
+    /// Pushes `msg` to the send queue of its class.
     /// Silently drops message if the connection has been closed.
     /// If the message is too large, it will be silently dropped inside run_send_loop.
+    /// Returns false if the message was dropped because the queue of its class is full.
     /// Emits a critical error to Actor if send queue is full.
-    pub fn send(&self, frame: Frame) {
+    pub fn send(&self, class: MessageClass, frame: Frame) -> bool {
         let msg = &frame.0;
         let mut buf_size =
             self.stats.bytes_to_send.fetch_add(msg.len() as u64, Ordering::Acquire) as usize;
@@ -125,7 +129,14 @@ where
                 want_max_bytes: MAX_WRITE_BUFFER_CAPACITY_BYTES,
             }));
         }
-        let _ = self.queue_send.send(frame);
+        let len = msg.len();
+        if !self.send_queue.push(class, frame.0) {
+            self.stats.bytes_to_send.fetch_sub(len as u64, Ordering::Release);
+            self.stats.messages_to_send.fetch_sub(1, Ordering::Release);
+            self.send_buf_size_metric.sub(len as i64);
+            return false;
+        }
+        true
     }
 
     /// Event loop receiving and processing messages.
@@ -177,39 +188,60 @@ where
     }
     async fn run_send_loop(
         tcp_send: WriteHalf,
-        mut queue_recv: tokio::sync::mpsc::UnboundedReceiver<Frame>,
+        send_queue: Arc<SendQueue>,
         stats: Arc<connection::Stats>,
         buf_size_metric: Arc<metrics::IntGaugeGuard>,
     ) -> io::Result<()> {
         const WRITE_BUFFER_CAPACITY: usize = 8 * 1024;
         let mut writer = tokio::io::BufWriter::with_capacity(WRITE_BUFFER_CAPACITY, tcp_send);
-        while let Some(Frame(mut msg)) = queue_recv.recv().await {
-            // Try writing a batch of messages and flush once at the end.
-            loop {
-                // TODO(gprusak): sending a too large message should probably be treated as a bug,
-                // since dropping messages may lead to hard-to-debug high-level issues.
-                if msg.len() > NETWORK_MESSAGE_MAX_SIZE_BYTES {
-                    metrics::MessageDropped::InputTooLong.inc_unknown_msg();
-                } else {
-                    writer.write_u32_le(msg.len() as u32).await?;
-                    writer.write_all(&msg[..]).await?;
+        // Whether messages were written since the last flush. Messages are written in
+        // batches, with a flush once there is nothing left to send.
+        let mut unflushed = false;
+        loop {
+            match send_queue.pop(std::time::Instant::now()) {
+                Pop::Frame(msg) => {
+                    // TODO(gprusak): sending a too large message should probably be treated as a bug,
+                    // since dropping messages may lead to hard-to-debug high-level issues.
+                    if msg.len() > NETWORK_MESSAGE_MAX_SIZE_BYTES {
+                        metrics::MessageDropped::InputTooLong.inc_unknown_msg();
+                    } else {
+                        writer.write_u32_le(msg.len() as u32).await?;
+                        writer.write_all(&msg[..]).await?;
+                        unflushed = true;
+                    }
+                    stats.messages_to_send.fetch_sub(1, Ordering::Release);
+                    stats.bytes_to_send.fetch_sub(msg.len() as u64, Ordering::Release);
+                    buf_size_metric.sub(msg.len() as i64);
+                }
+                // This is an unconditional flush, which means that even if new messages
+                // will be added to the queue in the meantime, we will wait for the buffer
+                // to be flushed before sending them. This is suboptimal in case messages are
+                // small and added to the queue at a rate similar to flush latency.
+                Pop::Wait(_) if unflushed => {
+                    writer.flush().await?;
+                    unflushed = false;
+                }
+                // `Notify` keeps a permit for a notification without waiter, so a message pushed
+                // since `pop` is not missed.
+                Pop::Wait(None) => send_queue.notify.notified().await,
+                Pop::Wait(Some(wait)) => {
+                    tokio::select! {
+                        _ = send_queue.notify.notified() => {}
+                        _ = tokio::time::sleep(wait) => {}
+                    }
+                }
+                Pop::Closed => {
+                    writer.flush().await?;
+                    return Ok(());
                 }
-                stats.messages_to_send.fetch_sub(1, Ordering::Release);
-                stats.bytes_to_send.fetch_sub(msg.len() as u64, Ordering::Release);
-                buf_size_metric.sub(msg.len() as i64);
-                msg = match queue_recv.try_recv() {
-                    Ok(Frame(it)) => it,
-                    Err(_) => break,
-                };
             }
-            // This is an unconditional flush, which means that even if new messages
-            // will be added to the queue in the meantime, we will wait for the buffer
-            // to be flushed before sending them. This is suboptimal in case messages are small
-            // and added to the queue at a rate similar to flush latency. To fix that
-            // we would need to put writer.flush() and queue_recv.recv() into a tokio::select
-            // and make sure that both are cancellation-safe.
-            writer.flush().await?;
         }
-        Ok(())
+    }
+}
+
+impl<Actor: actix::Actor> Drop for FramedStream<Actor> {
+    fn drop(&mut self) {
+        // Lets the send loop finish once the queued messages are sent.
+        self.send_queue.close();
     }
 }
@@ -1,6 +1,7 @@
 use crate::actix::ActixSystem;
 use crate::network_protocol::testonly as data;
 use crate::peer::stream;
+use crate::rate_limits::send_shaping::MessageClass;
 use crate::tcp;
 use crate::testonly::make_rng;
 use actix::Actor as _;
@@ -25,7 +26,7 @@ struct SendFrame(stream::Frame);
 impl actix::Handler<SendFrame> for Actor {
     type Result = ();
     fn handle(&mut self, SendFrame(frame): SendFrame, _ctx: &mut Self::Context) {
-        self.stream.send(frame);
+        assert!(self.stream.send(MessageClass::Other, frame));
     }
 }
 
@@ -55,7 +56,8 @@ impl Actor {
             queue_recv,
             system: ActixSystem::spawn(|| {
                 Actor::create(|ctx| {
-                    let stream = stream::FramedStream::spawn(ctx, s, Arc::default());
+                    let stream =
+                        stream::FramedStream::spawn(ctx, s, Arc::default(), &Default::default());
                     Self { stream, queue_send }
                 })
             })
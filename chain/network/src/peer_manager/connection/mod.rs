@@ -18,10 +18,12 @@ use near_o11y::WithSpanContextExt;
 use near_primitives::block::GenesisId;
 use near_primitives::network::PeerId;
 use near_primitives::types::ShardId;
-use std::collections::{hash_map::Entry, HashMap};
+use near_primitives::version::{ProtocolFeature, ProtocolVersion};
+use parking_lot::Mutex;
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
 use std::fmt;
 use std::future::Future;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Weak};
 
 #[cfg(test)]
@@ -60,7 +62,11 @@ impl tcp::Tier {
             | PeerMessage::StateRequestHeader(..)
             | PeerMessage::StateRequestPart(..)
             | PeerMessage::EpochSyncRequest
-            | PeerMessage::EpochSyncResponse(..) => self == tcp::Tier::T2,
+            | PeerMessage::EpochSyncResponse(..)
+            | PeerMessage::BlockHeadersRangeRequest(..)
+            | PeerMessage::BlockHeadersRange(..)
+            | PeerMessage::ReachabilityProbeRequest
+            | PeerMessage::ReachabilityProbeResponse(..) => self == tcp::Tier::T2,
         }
     }
 
@@ -74,16 +80,20 @@ impl tcp::Tier {
             | RoutedMessageBody::VersionedPartialEncodedChunk(..)
             | RoutedMessageBody::ChunkContractAccesses(_)
             | RoutedMessageBody::ContractCodeRequest(_)
-            | RoutedMessageBody::ContractCodeResponse(_) => true,
+            | RoutedMessageBody::ContractCodeResponse(_)
+            | RoutedMessageBody::Challenge(_) => true,
             // Rest
             RoutedMessageBody::ForwardTx(..)
             | RoutedMessageBody::TxStatusRequest(..)
             | RoutedMessageBody::TxStatusResponse(..)
             | RoutedMessageBody::PartialEncodedChunkRequest(..)
             | RoutedMessageBody::PartialEncodedChunkResponse(..)
+            | RoutedMessageBody::PartialEncodedChunkRequestBatch(..)
+            | RoutedMessageBody::PartialEncodedChunkResponseBatch(..)
             | RoutedMessageBody::Ping(..)
             | RoutedMessageBody::Pong(..)
             | RoutedMessageBody::PartialEncodedChunkForward(..)
+            | RoutedMessageBody::PartialEncodedChunkForwardAck(..)
             | RoutedMessageBody::ChunkStateWitnessAck(..)
             | RoutedMessageBody::StatePartRequest(..)
             | RoutedMessageBody::PartialEncodedContractDeploys(..) => self == tcp::Tier::T2,
@@ -105,6 +115,102 @@ impl tcp::Tier {
     }
 }
 
+/// Protocol feature which introduced the message, if the message can't be decoded
+/// by peers with older protocol versions.
+pub(crate) fn required_protocol_feature(msg: &PeerMessage) -> Option<ProtocolFeature> {
+    match msg {
+        PeerMessage::Challenge(challenge) if !challenge.co_signatures.is_empty() => {
+            Some(ProtocolFeature::ChallengeCoSignatures)
+        }
+        // Challenges carry the target epoch since `ChallengeTargetEpoch`.
+        PeerMessage::Challenge(_) => Some(ProtocolFeature::ChallengeTargetEpoch),
+        PeerMessage::Routed(msg) => required_protocol_feature_routed(&msg.body),
+        PeerMessage::ReachabilityProbeRequest | PeerMessage::ReachabilityProbeResponse(_) => {
+            Some(ProtocolFeature::ReachabilityProbe)
+        }
+        _ => None,
+    }
+}
+
+/// Same as `required_protocol_feature`, for the body of a routed message.
+pub(crate) fn required_protocol_feature_routed(
+    body: &RoutedMessageBody,
+) -> Option<ProtocolFeature> {
+    match body {
+        RoutedMessageBody::Challenge(challenge) if !challenge.co_signatures.is_empty() => {
+            Some(ProtocolFeature::ChallengeCoSignatures)
+        }
+        RoutedMessageBody::Challenge(_) => Some(ProtocolFeature::RoutedChallenge),
+        RoutedMessageBody::PartialEncodedChunkRequestBatch(_)
+        | RoutedMessageBody::PartialEncodedChunkResponseBatch(_) => {
+            Some(ProtocolFeature::PartialEncodedChunkRequestBatch)
+        }
+        RoutedMessageBody::PartialEncodedChunkForwardAck(_) => {
+            Some(ProtocolFeature::PartialEncodedChunkForwardAck)
+        }
+        _ => None,
+    }
+}
+
+/// Reputation lost for each invalid message, relative to a useful one.
+const INVALID_MESSAGE_PENALTY: i64 = 10;
+/// Reputation lost for each `LATENCY_PENALTY_UNIT` of the average response latency.
+const LATENCY_PENALTY_UNIT: time::Duration = time::Duration::milliseconds(100);
+/// Weight of the older samples in the average response latency.
+const LATENCY_EWMA_WEIGHT: u64 = 8;
+/// Maximal number of requests of a kind awaiting a response. The oldest request is
+/// forgotten when a new one is sent, it is most likely not going to be answered anyway.
+const MAX_PENDING_REQUESTS: usize = 32;
+
+/// Whether the routed message carries data the node needs: chunk parts and state witnesses.
+/// Only the first copy of the message received by the node is useful.
+pub(crate) fn carries_useful_data(body: &RoutedMessageBody) -> bool {
+    matches!(
+        body,
+        RoutedMessageBody::VersionedPartialEncodedChunk(..)
+            | RoutedMessageBody::PartialEncodedChunkResponse(..)
+            | RoutedMessageBody::PartialEncodedChunkResponseBatch(..)
+            | RoutedMessageBody::PartialEncodedChunkForward(..)
+            | RoutedMessageBody::PartialEncodedStateWitness(..)
+            | RoutedMessageBody::PartialEncodedStateWitnessForward(..)
+    )
+}
+
+/// Kinds of requests sent directly to a peer, which it answers with a single message.
+/// Responses are matched with the requests, to measure how long the peer takes to
+/// respond and to tell the requested data apart from the unsolicited one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum RequestKind {
+    BlockHeaders,
+    BlockHeadersRange,
+    State,
+    EpochSync,
+}
+
+impl RequestKind {
+    pub fn of_request(msg: &PeerMessage) -> Option<Self> {
+        match msg {
+            PeerMessage::BlockHeadersRequest(..) => Some(Self::BlockHeaders),
+            PeerMessage::BlockHeadersRangeRequest(..) => Some(Self::BlockHeadersRange),
+            PeerMessage::StateRequestHeader(..) | PeerMessage::StateRequestPart(..) => {
+                Some(Self::State)
+            }
+            PeerMessage::EpochSyncRequest => Some(Self::EpochSync),
+            _ => None,
+        }
+    }
+
+    pub fn of_response(msg: &PeerMessage) -> Option<Self> {
+        match msg {
+            PeerMessage::BlockHeaders(..) => Some(Self::BlockHeaders),
+            PeerMessage::BlockHeadersRange(..) => Some(Self::BlockHeadersRange),
+            PeerMessage::VersionedStateResponse(..) => Some(Self::State),
+            PeerMessage::EpochSyncResponse(..) => Some(Self::EpochSync),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct Stats {
     /// Number of messages received since the last reset of the counter.
@@ -120,6 +226,61 @@ pub(crate) struct Stats {
     pub messages_to_send: AtomicU64,
     /// Number of bytes (sum of message sizes) in the buffer to send.
     pub bytes_to_send: AtomicU64,
+
+    /// Number of received messages carrying data which the node requested
+    /// or hasn't received before.
+    pub useful_messages: AtomicU64,
+    /// Number of received messages which were rate limited or unexpected.
+    pub invalid_messages: AtomicU64,
+    /// Send times of the requests awaiting a response, oldest first.
+    pub pending_requests: Mutex<HashMap<RequestKind, VecDeque<time::Instant>>>,
+    /// Moving average of the time the peer takes to respond to a request,
+    /// in milliseconds. 0 until the first response.
+    pub avg_response_latency_ms: AtomicU64,
+}
+
+impl Stats {
+    /// Reputation earned on this connection. Slow peers are penalized
+    /// in proportion to their average response latency.
+    pub fn reputation(&self) -> i64 {
+        let useful = self.useful_messages.load(Ordering::Relaxed) as i64;
+        let invalid = self.invalid_messages.load(Ordering::Relaxed) as i64;
+        let latency_ms = self.avg_response_latency_ms.load(Ordering::Relaxed) as i64;
+        let latency_penalty = latency_ms / LATENCY_PENALTY_UNIT.whole_milliseconds() as i64;
+        useful
+            .saturating_sub(invalid.saturating_mul(INVALID_MESSAGE_PENALTY))
+            .saturating_sub(latency_penalty)
+    }
+
+    /// Records a request sent to the peer, so that the response can be matched with it.
+    pub fn request_sent(&self, kind: RequestKind, now: time::Instant) {
+        let mut pending_requests = self.pending_requests.lock();
+        let sent = pending_requests.entry(kind).or_default();
+        if sent.len() >= MAX_PENDING_REQUESTS {
+            sent.pop_front();
+        }
+        sent.push_back(now);
+    }
+
+    /// Matches the response with the oldest pending request of the same kind and
+    /// records the latency of the response. Returns false if the response was not
+    /// requested.
+    pub fn response_received(&self, kind: RequestKind, now: time::Instant) -> bool {
+        let Some(sent) = self.pending_requests.lock().get_mut(&kind).and_then(VecDeque::pop_front)
+        else {
+            return false;
+        };
+        let latency_ms = (now - sent).whole_milliseconds().max(0) as u64;
+        let _ = self.avg_response_latency_ms.fetch_update(
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+            |avg| match avg {
+                0 => Some(latency_ms),
+                avg => Some((avg * (LATENCY_EWMA_WEIGHT - 1) + latency_ms) / LATENCY_EWMA_WEIGHT),
+            },
+        );
+        true
+    }
 }
 
 /// Contains information relevant to a connected peer.
@@ -131,6 +292,8 @@ pub(crate) struct Connection {
     pub addr: actix::Addr<PeerActor>,
 
     pub peer_info: PeerInfo,
+    /// Protocol version of the peer, as announced in the handshake.
+    pub protocol_version: ProtocolVersion,
     /// AccountKey ownership proof.
     pub owned_account: Option<SignedOwnedAccount>,
     /// Chain Id and hash of genesis block.
@@ -152,6 +315,8 @@ pub(crate) struct Connection {
     pub last_time_received_message: AtomicCell<time::Instant>,
     /// Connection stats
     pub stats: Arc<Stats>,
+    /// Reputation of the peer earned on previous connections, as recorded in the PeerStore.
+    pub past_reputation: i64,
     /// prometheus gauge point guard.
     pub _peer_connections_metric: metrics::GaugePoint,
 
@@ -182,14 +347,36 @@ impl Connection {
         FullPeerInfo { peer_info: self.peer_info.clone(), chain_info }
     }
 
+    /// Reputation of the peer, over this and the previous connections. Peers with lower
+    /// reputation are disconnected first and are asked for chunks last.
+    pub fn reputation(&self) -> i64 {
+        self.past_reputation.saturating_add(self.stats.reputation())
+    }
+
     pub fn stop(&self, ban_reason: Option<ReasonForBan>) {
         self.addr.do_send(peer_actor::Stop { ban_reason }.with_span_context());
     }
 
+    /// Whether the peer is able to decode the message.
+    pub fn supports(&self, msg: &PeerMessage) -> bool {
+        required_protocol_feature(msg)
+            .map_or(true, |feature| feature.enabled(self.protocol_version))
+    }
+
+    /// Whether the peer is able to decode a routed message with the given body.
+    pub fn supports_routed(&self, body: &RoutedMessageBody) -> bool {
+        required_protocol_feature_routed(body)
+            .map_or(true, |feature| feature.enabled(self.protocol_version))
+    }
+
     // TODO(gprusak): embed Stream directly in Connection,
     // so that we can skip actix queue when sending messages.
     pub fn send_message(&self, msg: Arc<PeerMessage>) {
         let msg_kind = msg.msg_variant().to_string();
+        if !self.supports(&msg) {
+            tracing::debug!(target: "network", ?msg_kind, peer_id = ?self.peer_info.id, protocol_version = self.protocol_version, "Drop message unsupported by the peer");
+            return;
+        }
         tracing::trace!(target: "network", ?msg_kind, "Send message");
         self.addr.do_send(SendMessage { message: msg }.with_span_context());
     }
@@ -529,6 +716,10 @@ impl Pool {
     pub fn send_message(&self, peer_id: PeerId, msg: Arc<PeerMessage>) -> bool {
         let pool = self.load();
         if let Some(peer) = pool.ready.get(&peer_id) {
+            if !peer.supports(&msg) {
+                tracing::debug!(target: "network", to = ?peer_id, protocol_version = peer.protocol_version, ?msg, "Failed sending message: unsupported by the peer");
+                return false;
+            }
             peer.send_message(msg);
             return true;
         }
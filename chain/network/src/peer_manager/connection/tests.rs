@@ -120,3 +120,21 @@ async fn duplicate_connections() {
     );
     drop(conn1);
 }
+
+#[test]
+fn slow_responses_lower_reputation() {
+    let clock = time::FakeClock::default();
+    let stats = connection::Stats::default();
+    let kind = connection::RequestKind::BlockHeaders;
+
+    // Unsolicited responses are not matched with any request.
+    assert!(!stats.response_received(kind, clock.now()));
+    assert_eq!(stats.reputation(), 0);
+
+    stats.request_sent(kind, clock.now());
+    clock.advance(time::Duration::milliseconds(500));
+    assert!(stats.response_received(kind, clock.now()));
+    assert_eq!(stats.reputation(), -5);
+    // The request is answered already.
+    assert!(!stats.response_received(kind, clock.now()));
+}
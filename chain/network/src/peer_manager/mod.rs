@@ -3,6 +3,7 @@ pub(crate) mod connection_store;
 pub(crate) mod network_state;
 pub(crate) mod peer_manager_actor;
 pub(crate) mod peer_store;
+pub(crate) mod reachability;
 
 #[cfg(test)]
 pub(crate) mod testonly;
@@ -1,9 +1,14 @@
 use crate::accounts_data::{AccountDataCache, AccountDataError};
 use crate::announce_accounts::AnnounceAccountCache;
+#[cfg(feature = "test_features")]
+use crate::chaos;
 use crate::client::{
     BlockApproval, ChunkEndorsementMessage, ClientSenderForNetwork, ProcessTxRequest,
-    TxStatusRequest, TxStatusResponse,
+    RecvChallenge, TxStatusRequest, TxStatusResponse,
 };
+#[cfg(feature = "test_features")]
+use crate::concurrency::arc_mutex::ArcMutex;
+use crate::concurrency::atomic_cell::AtomicCell;
 use crate::concurrency::demux;
 use crate::concurrency::runtime::Runtime;
 use crate::config;
@@ -16,6 +21,7 @@ use crate::peer::peer_actor::PeerActor;
 use crate::peer_manager::connection;
 use crate::peer_manager::connection_store;
 use crate::peer_manager::peer_store;
+use crate::peer_manager::reachability;
 use crate::private_actix::RegisterPeerError;
 use crate::routing::route_back_cache::RouteBackCache;
 #[cfg(feature = "distance_vector_routing")]
@@ -41,9 +47,10 @@ use near_async::messaging::{CanSend, SendAsync, Sender};
 use near_async::time;
 use near_primitives::block::GenesisId;
 use near_primitives::hash::CryptoHash;
-use near_primitives::network::PeerId;
+use near_primitives::network::{PeerId, Reachability};
 use near_primitives::types::AccountId;
 use parking_lot::{Mutex, RwLock};
+use rand::seq::IteratorRandom as _;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
 use std::sync::atomic::AtomicUsize;
@@ -70,6 +77,10 @@ pub const PRUNE_EDGES_AFTER: time::Duration = time::Duration::minutes(30);
 /// How long to wait between reconnection attempts to the same peer
 pub(crate) const RECONNECT_ATTEMPT_INTERVAL: time::Duration = time::Duration::seconds(10);
 
+/// If no peer connected to us for this long, while we are connected to peers, we are considered
+/// unreachable. Peers learn our address from the routing table, once we connected to them.
+pub(crate) const REACHABILITY_WINDOW: time::Duration = time::Duration::minutes(10);
+
 impl WhitelistNode {
     pub fn from_peer_info(pi: &PeerInfo) -> anyhow::Result<Self> {
         Ok(Self {
@@ -126,6 +137,13 @@ pub(crate) struct NetworkState {
     pub inbound_handshake_permits: Arc<tokio::sync::Semaphore>,
     /// The public IP of this node; available after connecting to any one peer.
     pub my_public_addr: Arc<RwLock<Option<std::net::SocketAddr>>>,
+    /// Last time a peer opened a connection to us, see `reachability`.
+    pub last_inbound_stream: AtomicCell<Option<time::Instant>>,
+    /// Dial back probes of the reachability, see `probe_reachability`.
+    pub reachability_prober: Mutex<reachability::Prober>,
+    /// Faults injected into the connections, set via the adversarial RPC.
+    #[cfg(feature = "test_features")]
+    pub chaos: ArcMutex<chaos::Config>,
     /// Peer store that provides read/write access to peers.
     pub peer_store: peer_store::PeerStore,
     /// Information about state snapshots hosted by network peers.
@@ -211,6 +229,10 @@ impl NetworkState {
             tier3: connection::Pool::new(config.node_id()),
             inbound_handshake_permits: Arc::new(tokio::sync::Semaphore::new(LIMIT_PENDING_PEERS)),
             my_public_addr: Arc::new(RwLock::new(None)),
+            last_inbound_stream: AtomicCell::new(None),
+            reachability_prober: Mutex::new(reachability::Prober::new()),
+            #[cfg(feature = "test_features")]
+            chaos: ArcMutex::new(Default::default()),
             peer_store,
             snapshot_hosts: Arc::new(SnapshotHostsCache::new(config.snapshot_hosts.clone())),
             connection_store: connection_store::ConnectionStore::new(store.clone()).unwrap(),
@@ -267,6 +289,73 @@ impl NetworkState {
         }
     }
 
+    /// Whether other nodes can connect to us: they did recently, an inbound TIER2
+    /// connection is still open, or a peer managed to dial back.
+    pub fn reachability(&self, clock: &time::Clock) -> Reachability {
+        if !self.is_listening() {
+            return Reachability::NotListening;
+        }
+        let now = clock.now();
+        if self.has_inbound_connections(now) {
+            return Reachability::Reachable;
+        }
+        match self.reachability_prober.lock().reachable(now) {
+            Some(true) => return Reachability::Reachable,
+            Some(false) => return Reachability::Unreachable,
+            None => {}
+        }
+        let has_outbound =
+            self.tier2.load().ready.values().any(|conn| conn.peer_type == PeerType::Outbound);
+        if has_outbound && now - self.created_at >= REACHABILITY_WINDOW {
+            return Reachability::Unreachable;
+        }
+        Reachability::Unknown
+    }
+
+    fn is_listening(&self) -> bool {
+        !self.config.inbound_disabled && self.config.node_addr.is_some()
+    }
+
+    /// Whether a peer connected to us recently, which proves that we are reachable.
+    fn has_inbound_connections(&self, now: time::Instant) -> bool {
+        self.last_inbound_stream.load().is_some_and(|t| now - t < REACHABILITY_WINDOW)
+            || self.tier2.load().ready.values().any(|conn| conn.peer_type == PeerType::Inbound)
+    }
+
+    /// Asks a random TIER2 peer to dial back, unless inbound connections already prove
+    /// that we are reachable. The response is handled by `reachability_prober`.
+    pub fn probe_reachability(&self, clock: &time::Clock) {
+        let now = clock.now();
+        if !self.is_listening() || self.has_inbound_connections(now) {
+            return;
+        }
+        let mut prober = self.reachability_prober.lock();
+        if !prober.should_start(now) {
+            return;
+        }
+        let msg = Arc::new(PeerMessage::ReachabilityProbeRequest);
+        let tier2 = self.tier2.load();
+        let Some(conn) =
+            tier2.ready.values().filter(|conn| conn.supports(&msg)).choose(&mut rand::thread_rng())
+        else {
+            return;
+        };
+        tracing::debug!(target: "network", peer_id = ?conn.peer_info.id, "Asking peer to dial back");
+        prober.start(conn.peer_info.id.clone(), now);
+        conn.send_message(msg);
+    }
+
+    /// Checks whether the peer accepts connections at the address which it advertised.
+    pub async fn dial_back(&self, peer_info: &PeerInfo) -> bool {
+        match tcp::Stream::connect(peer_info, tcp::Tier::T2, &self.config.socket_options).await {
+            Ok(_) => true,
+            Err(err) => {
+                tracing::debug!(target: "network", peer_id = ?peer_info.id, ?err, "Failed to dial back");
+                false
+            }
+        }
+    }
+
     /// is_peer_whitelisted checks whether a peer is a whitelisted node.
     /// whitelisted nodes are allowed to connect, even if the inbound connections limit has
     /// been reached. This predicate should be evaluated AFTER the Handshake.
@@ -430,6 +519,7 @@ impl NetworkState {
                 .unwrap();
 
             // Save the fact that we are disconnecting to the PeerStore.
+            this.peer_store.set_reputation(&conn.peer_info.id, conn.reputation());
             let res = match reason {
                 ClosingReason::Ban(ban_reason) => {
                     this.peer_store.peer_ban(&clock, &conn.peer_info.id, ban_reason)
@@ -608,6 +698,21 @@ impl NetworkState {
         }
     }
 
+    /// Peer id of the node running `account_id`, looked up in the accounts data first
+    /// and in the account announcements otherwise.
+    pub fn account_peer_id(&self, account_id: &AccountId) -> Option<PeerId> {
+        let accounts_data = self.accounts_data.load();
+        accounts_data
+            .keys_by_id
+            .get(account_id)
+            .iter()
+            .flat_map(|keys| keys.iter())
+            .flat_map(|key| accounts_data.data.get(key))
+            .next()
+            .map(|data| data.peer_id.clone())
+            .or_else(|| self.account_announcements.get_account_owner(account_id))
+    }
+
     /// Send message to specific account.
     /// Return whether the message is sent or not.
     /// The message might be sent over TIER1 or TIER2 connection depending on the message type.
@@ -629,7 +734,9 @@ impl NetworkState {
                 RawRoutedMessage { target: PeerIdOrHash::PeerId(peer_id.clone()), body: msg },
             );
             actix::spawn(async move {
-                this.receive_routed_message(&clock, peer_id, msg.hash(), msg.msg.body).await;
+                let author = msg.msg.author.clone();
+                this.receive_routed_message(&clock, peer_id, &author, msg.hash(), msg.msg.body)
+                    .await;
             });
             return true;
         }
@@ -698,10 +805,13 @@ impl NetworkState {
         success
     }
 
+    /// Handles a routed message targeted to this node. `peer_id` is the peer which relayed
+    /// the message and `author` the peer which signed it.
     pub async fn receive_routed_message(
         self: &Arc<Self>,
         clock: &time::Clock,
         peer_id: PeerId,
+        author: &PeerId,
         msg_hash: CryptoHash,
         body: RoutedMessageBody,
     ) -> Option<RoutedMessageBody> {
@@ -741,6 +851,26 @@ impl NetworkState {
                 );
                 None
             }
+            RoutedMessageBody::PartialEncodedChunkRequestBatch(requests) => {
+                self.shards_manager_adapter.send(
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequestBatch {
+                        partial_encoded_chunk_requests: requests,
+                        route_back: msg_hash,
+                    },
+                );
+                None
+            }
+            RoutedMessageBody::PartialEncodedChunkResponseBatch(responses) => {
+                for response in responses {
+                    self.shards_manager_adapter.send(
+                        ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
+                            partial_encoded_chunk_response: response,
+                            received_time: clock.now().into(),
+                        },
+                    );
+                }
+                None
+            }
             RoutedMessageBody::PartialEncodedChunkResponse(response) => {
                 self.shards_manager_adapter.send(
                     ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
@@ -751,8 +881,12 @@ impl NetworkState {
                 None
             }
             RoutedMessageBody::VersionedPartialEncodedChunk(chunk) => {
-                self.shards_manager_adapter
-                    .send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(chunk));
+                self.shards_manager_adapter.send(
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
+                        partial_encoded_chunk: chunk,
+                        peer_id,
+                    },
+                );
                 None
             }
             RoutedMessageBody::PartialEncodedChunkForward(msg) => {
@@ -760,6 +894,17 @@ impl NetworkState {
                     .send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForward(msg));
                 None
             }
+            RoutedMessageBody::PartialEncodedChunkForwardAck(ack) => {
+                // The ack stops the pushes to its account, so only that account may send it.
+                if self.account_peer_id(&ack.account_id).as_ref() != Some(author) {
+                    tracing::debug!(target: "network", account_id = ?ack.account_id, ?author, "Drop chunk part forward ack not signed by its account");
+                    return None;
+                }
+                self.shards_manager_adapter.send(
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForwardAck(ack),
+                );
+                None
+            }
             RoutedMessageBody::ChunkStateWitnessAck(ack) => {
                 self.partial_witness_adapter.send(ChunkStateWitnessAckMessage(ack));
                 None
@@ -804,6 +949,10 @@ impl NetworkState {
                 self.partial_witness_adapter.send(PartialEncodedContractDeploysMessage(deploys));
                 None
             }
+            RoutedMessageBody::Challenge(challenge) => {
+                self.client.send_async(RecvChallenge(challenge)).await.ok();
+                None
+            }
             body => {
                 tracing::error!(target: "network", "Peer received unexpected message type: {:?}", body);
                 None
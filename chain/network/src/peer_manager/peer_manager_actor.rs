@@ -4,8 +4,8 @@ use crate::debug::{DebugStatus, GetDebugStatus};
 use crate::network_protocol;
 use crate::network_protocol::SyncSnapshotHosts;
 use crate::network_protocol::{
-    Disconnect, Edge, PeerIdOrHash, PeerMessage, Ping, Pong, RawRoutedMessage, RoutedMessageBody,
-    StatePartRequest,
+    Disconnect, Edge, PartialEncodedChunkRequestMsg, PeerIdOrHash, PeerMessage, Ping, Pong,
+    RawRoutedMessage, RoutedMessageBody, StatePartRequest,
 };
 use crate::peer::peer_actor::PeerActor;
 use crate::peer_manager::connection;
@@ -17,10 +17,10 @@ use crate::stats::metrics;
 use crate::store;
 use crate::tcp;
 use crate::types::{
-    ConnectedPeerInfo, HighestHeightPeerInfo, KnownProducer, NetworkInfo, NetworkRequests,
-    NetworkResponses, PeerInfo, PeerManagerMessageRequest, PeerManagerMessageResponse,
-    PeerManagerSenderForNetwork, PeerType, SetChainInfo, SnapshotHostInfo, StatePartRequestBody,
-    StateSyncEvent, Tier3Request, Tier3RequestBody,
+    AccountIdOrPeerTrackingShard, ConnectedPeerInfo, HighestHeightPeerInfo, KnownProducer,
+    NetworkInfo, NetworkRequests, NetworkResponses, PeerInfo, PeerManagerMessageRequest,
+    PeerManagerMessageResponse, PeerManagerSenderForNetwork, PeerType, SetChainInfo,
+    SnapshotHostInfo, StatePartRequestBody, StateSyncEvent, Tier3Request, Tier3RequestBody,
 };
 use ::time::ext::InstantExt as _;
 use actix::fut::future::wrap_future;
@@ -31,7 +31,8 @@ use near_async::time;
 use near_o11y::{handler_debug_span, handler_trace_span, WithSpanContext};
 use near_performance_metrics_macros::perf;
 use near_primitives::block::GenesisId;
-use near_primitives::network::{AnnounceAccount, PeerId};
+use near_primitives::network::{AnnounceAccount, PeerId, Reachability};
+use near_primitives::version::ProtocolFeature;
 use near_primitives::views::{
     ConnectionInfoView, EdgeView, KnownPeerStateView, NetworkGraphView, NetworkRoutesView,
     PeerStoreView, RecentOutboundConnectionsView, SnapshotHostInfoView, SnapshotHostsView,
@@ -44,6 +45,7 @@ use std::cmp::min;
 use std::collections::HashSet;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use strum::IntoEnumIterator as _;
 use tracing::Instrument as _;
 
 /// Ratio between consecutive attempts to establish connection with another peer.
@@ -221,8 +223,9 @@ impl PeerManagerActor {
     ) -> anyhow::Result<actix::Addr<Self>> {
         let config = config.verify().context("config")?;
         let store = store::Store::from(store);
-        let peer_store = peer_store::PeerStore::new(&clock, config.peer_store.clone())
-            .context("PeerStore::new")?;
+        let peer_store =
+            peer_store::PeerStore::new(&clock, config.peer_store.clone(), store.clone())
+                .context("PeerStore::new")?;
         tracing::debug!(target: "network",
                len = peer_store.len(),
                boot_nodes = config.peer_store.boot_nodes.len(),
@@ -273,6 +276,7 @@ impl PeerManagerActor {
                         async move {
                             loop {
                                 if let Ok(stream) = listener.accept().await {
+                                    state.last_inbound_stream.store(Some(clock.now()));
                                     // Always let the new peer to send a handshake message.
                                     // Only then we can decide whether we should accept a connection.
                                     // It is expected to be reasonably cheap: eventually, for TIER2 network
@@ -549,9 +553,13 @@ impl PeerManagerActor {
         }
 
         // Build valid candidate list to choose the peer to be removed. All peers outside the safe set.
-        let candidates = tier2.ready.values().filter(|p| !safe_set.contains(&p.peer_info.id));
-        if let Some(p) = candidates.choose(&mut rand::thread_rng()) {
+        // Among them, the one with the lowest reputation is removed, ties are broken randomly.
+        let mut candidates: Vec<_> =
+            tier2.ready.values().filter(|p| !safe_set.contains(&p.peer_info.id)).collect();
+        candidates.shuffle(&mut rand::thread_rng());
+        if let Some(p) = candidates.into_iter().min_by_key(|p| p.reputation()) {
             tracing::debug!(target: "network", id = ?p.peer_info.id,
+                reputation = p.reputation(),
                 tier2_len = tier2.ready.len(),
                 ideal_connections_hi = self.state.config.ideal_connections_hi,
                 "Stop active connection"
@@ -658,6 +666,9 @@ impl PeerManagerActor {
         metrics::PEER_UNRELIABLE.set(unreliable_peers.len() as i64);
         self.state.set_unreliable_peers(unreliable_peers);
 
+        // Check whether peers can connect to us, if none did recently.
+        self.state.probe_reachability(&self.clock);
+
         let new_interval = min(max_interval, interval * EXPONENTIAL_BACKOFF_RATIO);
 
         near_performance_metrics::actix::run_later(
@@ -690,6 +701,12 @@ impl PeerManagerActor {
     }
 
     pub(crate) fn get_network_info(&self) -> NetworkInfo {
+        let reachability = self.state.reachability(&self.clock);
+        for status in Reachability::iter() {
+            metrics::REACHABILITY
+                .with_label_values(&[status.into()])
+                .set((status == reachability) as i64);
+        }
         let tier1 = self.state.tier1.load();
         let tier2 = self.state.tier2.load();
         let now = self.clock.now();
@@ -706,10 +723,12 @@ impl PeerManagerActor {
                 Some(e) => e.nonce(),
                 None => 0,
             },
+            reputation: cp.reputation(),
         };
         NetworkInfo {
             connected_peers: tier2.ready.values().map(connected_peer).collect(),
             tier1_connections: tier1.ready.values().map(connected_peer).collect(),
+            reachability,
             num_connected_peers: tier2.ready.len(),
             peer_max_count: self.state.config.max_num_peers,
             highest_height_peers: self.highest_height_peers(),
@@ -767,6 +786,113 @@ impl PeerManagerActor {
         );
     }
 
+    /// Sends a chunk parts request to `target`. Makes two attempts to send the message:
+    /// first following the preference of `prefer_peer`, and if it fails, against the preference.
+    fn send_partial_encoded_chunk_request(
+        &self,
+        target: &AccountIdOrPeerTrackingShard,
+        body: RoutedMessageBody,
+    ) -> bool {
+        for prefer_peer in &[target.prefer_peer, !target.prefer_peer] {
+            if !prefer_peer {
+                if let Some(account_id) = target.account_id.as_ref() {
+                    if self.state.send_message_to_account(&self.clock, account_id, body.clone()) {
+                        return true;
+                    }
+                }
+            } else {
+                if let Some(matching_peer) = self.choose_peer_for_chunk_request(target, &body) {
+                    if self.state.send_message_to_peer(
+                        &self.clock,
+                        tcp::Tier::T2,
+                        self.state.sign_message(
+                            &self.clock,
+                            RawRoutedMessage {
+                                target: PeerIdOrHash::PeerId(matching_peer),
+                                body: body.clone(),
+                            },
+                        ),
+                    ) {
+                        return true;
+                    }
+                } else {
+                    tracing::debug!(target: "network", shard_id=?target.shard_id, "Failed to find any matching peer for chunk");
+                }
+            }
+        }
+        false
+    }
+
+    /// Sends a batch of chunk parts requests to `target`, following the preference of
+    /// `prefer_peer` like `send_partial_encoded_chunk_request`. Relays with older protocol
+    /// versions can't decode the batch, so it is only sent over a direct TIER2 connection
+    /// to a peer which supports it. Returns false if there is no such peer.
+    fn send_partial_encoded_chunk_request_batch(
+        &self,
+        target: &AccountIdOrPeerTrackingShard,
+        requests: Vec<PartialEncodedChunkRequestMsg>,
+    ) -> bool {
+        let body = RoutedMessageBody::PartialEncodedChunkRequestBatch(requests);
+        for prefer_peer in &[target.prefer_peer, !target.prefer_peer] {
+            let peer_id = if !prefer_peer {
+                target
+                    .account_id
+                    .as_ref()
+                    .and_then(|account_id| self.state.account_peer_id(account_id))
+                    .filter(|peer_id| {
+                        self.state
+                            .tier2
+                            .load()
+                            .ready
+                            .get(peer_id)
+                            .is_some_and(|peer| peer.supports_routed(&body))
+                    })
+            } else {
+                self.choose_peer_for_chunk_request(target, &body)
+            };
+            let Some(peer_id) = peer_id else {
+                continue;
+            };
+            if self.state.send_message_to_peer(
+                &self.clock,
+                tcp::Tier::T2,
+                self.state.sign_message(
+                    &self.clock,
+                    RawRoutedMessage { target: PeerIdOrHash::PeerId(peer_id), body: body.clone() },
+                ),
+            ) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Picks a random connected peer which tracks the shard of the requested chunk and is
+    /// able to decode `body`.
+    fn choose_peer_for_chunk_request(
+        &self,
+        target: &AccountIdOrPeerTrackingShard,
+        body: &RoutedMessageBody,
+    ) -> Option<PeerId> {
+        let mut matching_peers = vec![];
+        for (peer_id, peer) in &self.state.tier2.load().ready {
+            let last_block = peer.last_block.load();
+            if (peer.archival || !target.only_archival)
+                && last_block.is_some()
+                && last_block.as_ref().unwrap().height >= target.min_height
+                && peer.tracked_shards.contains(&target.shard_id)
+                && peer.supports_routed(body)
+            {
+                matching_peers.push((peer_id.clone(), peer.reputation()));
+            }
+        }
+        // Peers with negative reputation are only asked if there is no one else.
+        if matching_peers.iter().any(|(_, reputation)| *reputation >= 0) {
+            matching_peers.retain(|(_, reputation)| *reputation >= 0);
+        }
+        matching_peers.into_iter().map(|(peer_id, _)| peer_id).choose(&mut thread_rng())
+    }
+
     #[perf]
     fn handle_msg_network_requests(
         &mut self,
@@ -810,6 +936,17 @@ impl PeerManagerActor {
                     NetworkResponses::RouteNotFound
                 }
             }
+            NetworkRequests::BlockHeadersRangeRequest { request, peer_id } => {
+                if self
+                    .state
+                    .tier2
+                    .send_message(peer_id, Arc::new(PeerMessage::BlockHeadersRangeRequest(request)))
+                {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
             NetworkRequests::StateRequestHeader { shard_id, sync_hash, peer_id } => {
                 if self.state.tier2.send_message(
                     peer_id,
@@ -925,63 +1062,40 @@ impl PeerManagerActor {
                 metrics::PARTIAL_ENCODED_CHUNK_REQUEST_DELAY.observe(
                     (self.clock.now().signed_duration_since(create_time)).as_seconds_f64(),
                 );
-                let mut success = false;
-
-                // Make two attempts to send the message. First following the preference of `prefer_peer`,
-                // and if it fails, against the preference.
-                for prefer_peer in &[target.prefer_peer, !target.prefer_peer] {
-                    if !prefer_peer {
-                        if let Some(account_id) = target.account_id.as_ref() {
-                            if self.state.send_message_to_account(
-                                &self.clock,
-                                account_id,
-                                RoutedMessageBody::PartialEncodedChunkRequest(request.clone()),
-                            ) {
-                                success = true;
-                                break;
-                            }
-                        }
-                    } else {
-                        let mut matching_peers = vec![];
-                        for (peer_id, peer) in &self.state.tier2.load().ready {
-                            let last_block = peer.last_block.load();
-                            if (peer.archival || !target.only_archival)
-                                && last_block.is_some()
-                                && last_block.as_ref().unwrap().height >= target.min_height
-                                && peer.tracked_shards.contains(&target.shard_id)
-                            {
-                                matching_peers.push(peer_id.clone());
-                            }
-                        }
-
-                        if let Some(matching_peer) = matching_peers.iter().choose(&mut thread_rng())
-                        {
-                            if self.state.send_message_to_peer(
-                                &self.clock,
-                                tcp::Tier::T2,
-                                self.state.sign_message(
-                                    &self.clock,
-                                    RawRoutedMessage {
-                                        target: PeerIdOrHash::PeerId(matching_peer.clone()),
-                                        body: RoutedMessageBody::PartialEncodedChunkRequest(
-                                            request.clone(),
-                                        ),
-                                    },
-                                ),
-                            ) {
-                                success = true;
-                                break;
-                            }
-                        } else {
-                            tracing::debug!(target: "network", chunk_hash=?request.chunk_hash, "Failed to find any matching peer for chunk");
+                let chunk_hash = request.chunk_hash.clone();
+                if self.send_partial_encoded_chunk_request(
+                    &target,
+                    RoutedMessageBody::PartialEncodedChunkRequest(request),
+                ) {
+                    NetworkResponses::NoResponse
+                } else {
+                    tracing::debug!(target: "network", ?chunk_hash, "Failed to find a route for chunk");
+                    NetworkResponses::RouteNotFound
+                }
+            }
+            NetworkRequests::PartialEncodedChunkRequestBatch { target, requests, create_time } => {
+                metrics::PARTIAL_ENCODED_CHUNK_REQUEST_DELAY.observe(
+                    (self.clock.now().signed_duration_since(create_time)).as_seconds_f64(),
+                );
+                let mut success =
+                    self.send_partial_encoded_chunk_request_batch(&target, requests.clone());
+                if !success {
+                    // No peer able to decode the batch, fall back to one request per chunk.
+                    success = true;
+                    for request in requests {
+                        let chunk_hash = request.chunk_hash.clone();
+                        if !self.send_partial_encoded_chunk_request(
+                            &target,
+                            RoutedMessageBody::PartialEncodedChunkRequest(request),
+                        ) {
+                            tracing::debug!(target: "network", ?chunk_hash, "Failed to find a route for chunk");
+                            success = false;
                         }
                     }
                 }
-
                 if success {
                     NetworkResponses::NoResponse
                 } else {
-                    tracing::debug!(target: "network", chunk_hash=?request.chunk_hash, "Failed to find a route for chunk");
                     NetworkResponses::RouteNotFound
                 }
             }
@@ -1002,6 +1116,23 @@ impl PeerManagerActor {
                     NetworkResponses::RouteNotFound
                 }
             }
+            NetworkRequests::PartialEncodedChunkResponseBatch { route_back, responses } => {
+                if self.state.send_message_to_peer(
+                    &self.clock,
+                    tcp::Tier::T2,
+                    self.state.sign_message(
+                        &self.clock,
+                        RawRoutedMessage {
+                            target: PeerIdOrHash::Hash(route_back),
+                            body: RoutedMessageBody::PartialEncodedChunkResponseBatch(responses),
+                        },
+                    ),
+                ) {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
             NetworkRequests::PartialEncodedChunkMessage { account_id, partial_encoded_chunk } => {
                 if self.state.send_message_to_account(
                     &self.clock,
@@ -1024,6 +1155,17 @@ impl PeerManagerActor {
                     NetworkResponses::RouteNotFound
                 }
             }
+            NetworkRequests::PartialEncodedChunkForwardAck { account_id, ack } => {
+                if self.state.send_message_to_account(
+                    &self.clock,
+                    &account_id,
+                    RoutedMessageBody::PartialEncodedChunkForwardAck(ack),
+                ) {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
             NetworkRequests::ForwardTx(account_id, tx) => {
                 if self.state.send_message_to_account(
                     &self.clock,
@@ -1047,10 +1189,35 @@ impl PeerManagerActor {
                 }
             }
             NetworkRequests::Challenge(challenge) => {
-                // TODO(illia): smarter routing?
-                self.state
-                    .tier2
-                    .broadcast_message(Arc::new(PeerMessage::Challenge(Box::new(challenge))));
+                // Route the challenge directly to all known validators, so that it doesn't
+                // compete with block gossip. Fall back to broadcasting it if no validators
+                // are known, e.g. when the node is not connected to TIER1 yet. Peers which
+                // can't decode routed challenges receive them through gossip in any case.
+                let me = self.state.config.validator.account_id();
+                let validators: Vec<_> = self
+                    .state
+                    .accounts_data
+                    .load()
+                    .keys_by_id
+                    .keys()
+                    .filter(|account_id| me.as_ref() != Some(*account_id))
+                    .cloned()
+                    .collect();
+                let gossip = Arc::new(PeerMessage::Challenge(Box::new(challenge.clone())));
+                for conn in self.state.tier2.load().ready.values() {
+                    if validators.is_empty()
+                        || !ProtocolFeature::RoutedChallenge.enabled(conn.protocol_version)
+                    {
+                        conn.send_message(gossip.clone());
+                    }
+                }
+                for account_id in validators {
+                    self.state.send_message_to_account(
+                        &self.clock,
+                        &account_id,
+                        RoutedMessageBody::Challenge(challenge.clone()),
+                    );
+                }
                 NetworkResponses::NoResponse
             }
             NetworkRequests::ChunkStateWitnessAck(target, ack) => {
@@ -1320,6 +1487,21 @@ impl actix::Handler<WithSpanContext<Tier3Request>> for PeerManagerActor {
     }
 }
 
+#[cfg(feature = "test_features")]
+impl actix::Handler<crate::chaos::SetNetworkChaos> for PeerManagerActor {
+    type Result = Result<(), String>;
+    fn handle(
+        &mut self,
+        msg: crate::chaos::SetNetworkChaos,
+        _ctx: &mut actix::Context<Self>,
+    ) -> Self::Result {
+        msg.0.validate()?;
+        tracing::info!(target: "network", config = ?msg.0, "injecting network faults");
+        self.state.chaos.update(|_| ((), msg.0));
+        Ok(())
+    }
+}
+
 impl actix::Handler<GetDebugStatus> for PeerManagerActor {
     type Result = DebugStatus;
     #[perf]
@@ -1346,6 +1528,7 @@ impl actix::Handler<GetDebugStatus> for PeerManagerActor {
                                 (attempt_time.unix_timestamp(), foo)
                             },
                         ),
+                        reputation: known_peer_state.reputation,
                     })
                     .collect::<Vec<_>>();
 
@@ -4,11 +4,13 @@
 //!     - Respond to requests from other peers for known peers (see PeerStore::healthy_peers).
 //!     - Select peers to which we may try to connect directly (see PeerStore::unconnected_peer).
 //!
-//! Contents of the PeerStore are not persisted to the database. Upon starting a node,
-//! the PeerStore is initialized from the boot nodes in its config.
+//! Contents of the PeerStore are not persisted to the database, except for the reputation
+//! of the peers. Upon starting a node, the PeerStore is initialized from the boot nodes in
+//! its config.
 
 use crate::blacklist;
 use crate::network_protocol::PeerInfo;
+use crate::store;
 use crate::types::{KnownPeerState, KnownPeerStatus, ReasonForBan};
 use anyhow::bail;
 use im::hashmap::Entry;
@@ -85,6 +87,18 @@ struct Inner {
     // It can happens that some peers don't have known address, so
     // they will not be present in this list, otherwise they will be present.
     addr_peers: HashMap<SocketAddr, VerifiedPeer>,
+    store: store::Store,
+}
+
+/// Creates the state of a peer new to the PeerStore, with the reputation it earned
+/// before the node restarted.
+fn new_peer_state(store: &store::Store, peer_info: PeerInfo, now: time::Utc) -> KnownPeerState {
+    let mut peer_state = KnownPeerState::new(peer_info, now);
+    match store.get_peer_reputation(&peer_state.peer_info.id) {
+        Ok(reputation) => peer_state.reputation = reputation.unwrap_or(0),
+        Err(err) => tracing::error!(target: "network", ?err, "Failed to load peer reputation"),
+    }
+    peer_state
 }
 
 impl Inner {
@@ -134,9 +148,9 @@ impl Inner {
             // If doesn't have the address attached it is not verified and we add it
             // only if it is unknown to us.
             if !self.peer_states.contains(&peer_info.id) {
-                if let Some((_, popped_peer_state)) = self
-                    .peer_states
-                    .push(peer_info.id.clone(), KnownPeerState::new(peer_info, clock.now_utc()))
+                let peer_state = new_peer_state(&self.store, peer_info, clock.now_utc());
+                if let Some((_, popped_peer_state)) =
+                    self.peer_states.push(peer_state.peer_info.id.clone(), peer_state)
                 {
                     // If a peer was evicted from peer_states due to the bounded cache size
                     // and it has an address, remove the corresponding entry from addr_peers
@@ -210,10 +224,9 @@ impl Inner {
         if let Some(peer_state) = self.peer_states.peek_mut(&peer_info.id) {
             peer_state.peer_info.addr = Some(peer_addr);
         } else {
-            let now = clock.now_utc();
-            if let Some((_, popped_peer_state)) = self
-                .peer_states
-                .push(peer_info.id.clone(), KnownPeerState::new(peer_info.clone(), now))
+            let peer_state = new_peer_state(&self.store, peer_info.clone(), clock.now_utc());
+            if let Some((_, popped_peer_state)) =
+                self.peer_states.push(peer_info.id.clone(), peer_state)
             {
                 // If a peer was evicted from peer_states due to the bounded cache size
                 // and it has an address, remove the corresponding entry from addr_peers
@@ -288,7 +301,7 @@ impl Inner {
 pub(crate) struct PeerStore(Mutex<Inner>);
 
 impl PeerStore {
-    pub fn new(clock: &time::Clock, config: Config) -> anyhow::Result<Self> {
+    pub fn new(clock: &time::Clock, config: Config, store: store::Store) -> anyhow::Result<Self> {
         let boot_nodes: HashSet<_> = config.boot_nodes.iter().map(|p| p.id.clone()).collect();
         // A mapping from `PeerId` to `KnownPeerState`.
         let mut peerid_2_state =
@@ -326,7 +339,7 @@ impl PeerStore {
             entry.insert(VerifiedPeer::signed(peer_info.id.clone()));
 
             if let Some((_, popped_peer_state)) = peerid_2_state
-                .push(peer_info.id.clone(), KnownPeerState::new(peer_info.clone(), now))
+                .push(peer_info.id.clone(), new_peer_state(&store, peer_info.clone(), now))
             {
                 // If a peer was evicted from peer_states due to the bounded cache size
                 // and it has an address, remove the corresponding entry from addr_peers
@@ -336,8 +349,13 @@ impl PeerStore {
             }
         }
 
-        let inner =
-            Inner { config, boot_nodes, peer_states: peerid_2_state, addr_peers: addr_2_peer };
+        let inner = Inner {
+            config,
+            boot_nodes,
+            peer_states: peerid_2_state,
+            addr_peers: addr_2_peer,
+            store,
+        };
         Ok(PeerStore(Mutex::new(inner)))
     }
 
@@ -386,6 +404,18 @@ impl PeerStore {
         Ok(())
     }
 
+    /// Records the reputation of the peer at the end of a connection.
+    /// The reputation is persisted, so that it is not reset by a restart.
+    pub fn set_reputation(&self, peer_id: &PeerId, reputation: i64) {
+        let mut inner = self.0.lock();
+        if let Some(peer_state) = inner.peer_states.get_mut(peer_id) {
+            peer_state.reputation = reputation;
+        }
+        if let Err(err) = inner.store.set_peer_reputation(peer_id, reputation) {
+            tracing::error!(target: "network", ?err, "Failed to save peer reputation");
+        }
+    }
+
     /// Records the last attempt to connect to peer.
     pub fn peer_connection_attempt(
         &self,
@@ -441,6 +471,7 @@ impl PeerStore {
                 |p| {
                     (p.status == KnownPeerStatus::NotConnected)
                         && !ignore_fn(p)
+                        && p.reputation >= 0
                         && p.peer_info.addr.is_some()
                         // if we're connecting only to the boot nodes - filter out the nodes that are not bootnodes.
                         && (!inner.config.connect_only_to_boot_nodes || inner.boot_nodes.contains(&p.peer_info.id))
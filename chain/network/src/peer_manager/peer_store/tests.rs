@@ -25,6 +25,10 @@ fn gen_peer_info(port: u16) -> PeerInfo {
     }
 }
 
+fn make_store() -> store::Store {
+    store::Store::from(near_store::db::TestDB::new())
+}
+
 fn make_config(
     boot_nodes: &[PeerInfo],
     blacklist: blacklist::Blacklist,
@@ -47,9 +51,12 @@ fn ban_store() {
     let peer_info_to_ban = gen_peer_info(1);
     let boot_nodes = vec![peer_info_a, peer_info_to_ban.clone()];
 
-    let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
-            .unwrap();
+    let peer_store = PeerStore::new(
+        &clock.clock(),
+        make_config(&boot_nodes, Blacklist::default(), false),
+        make_store(),
+    )
+    .unwrap();
     assert_eq!(peer_store.healthy_peers(3).len(), 2);
     peer_store.peer_ban(&clock.clock(), &peer_info_to_ban.id, ReasonForBan::Abusive).unwrap();
     assert_eq!(peer_store.healthy_peers(3).len(), 1);
@@ -62,14 +69,78 @@ fn test_unconnected_peer() {
     let peer_info_to_ban = gen_peer_info(1);
     let boot_nodes = vec![peer_info_a, peer_info_to_ban];
 
-    let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
-            .unwrap();
+    let peer_store = PeerStore::new(
+        &clock.clock(),
+        make_config(&boot_nodes, Blacklist::default(), false),
+        make_store(),
+    )
+    .unwrap();
 
     assert!(peer_store.unconnected_peer(|_| false, false).is_some());
     assert!(peer_store.unconnected_peer(|_| true, false).is_none());
 }
 
+#[test]
+fn test_unconnected_peer_reputation() {
+    let clock = time::FakeClock::default();
+    let peer_info_a = gen_peer_info(0);
+    let peer_info_b = gen_peer_info(1);
+
+    let peer_store =
+        PeerStore::new(&clock.clock(), make_config(&[], Blacklist::default(), false), make_store())
+            .unwrap();
+    for peer_info in [&peer_info_a, &peer_info_b] {
+        peer_store.add_direct_peer(&clock.clock(), peer_info.clone());
+        peer_store.peer_connected(&clock.clock(), peer_info);
+    }
+    peer_store.set_reputation(&peer_info_a.id, -10);
+    peer_store.set_reputation(&peer_info_b.id, 5);
+    for peer_info in [&peer_info_a, &peer_info_b] {
+        peer_store.peer_disconnected(&clock.clock(), &peer_info.id).unwrap();
+    }
+    assert_eq!(peer_store.get_peer_state(&peer_info_a.id).unwrap().reputation, -10);
+
+    // Previously connected peers with a negative reputation are not preferred.
+    for _ in 0..10 {
+        assert_eq!(peer_store.unconnected_peer(|_| false, true).unwrap().id, peer_info_b.id);
+    }
+    // But they are still connected to if there is no one else.
+    assert_eq!(
+        peer_store.unconnected_peer(|p| p.peer_info.id == peer_info_b.id, true).unwrap().id,
+        peer_info_a.id
+    );
+}
+
+#[test]
+fn test_reputation_survives_restart() {
+    let clock = time::FakeClock::default();
+    let peer_info_a = gen_peer_info(0);
+    let peer_info_b = gen_peer_info(1);
+    let store = make_store();
+
+    let peer_store = PeerStore::new(
+        &clock.clock(),
+        make_config(&[], Blacklist::default(), false),
+        store.clone(),
+    )
+    .unwrap();
+    peer_store.add_direct_peer(&clock.clock(), peer_info_a.clone());
+    peer_store.peer_connected(&clock.clock(), &peer_info_a);
+    peer_store.set_reputation(&peer_info_a.id, -10);
+    drop(peer_store);
+
+    // The reputation is loaded from the DB once the peer is discovered again,
+    // peers which have never been connected to start from zero.
+    let peer_store =
+        PeerStore::new(&clock.clock(), make_config(&[], Blacklist::default(), false), store)
+            .unwrap();
+    for peer_info in [&peer_info_a, &peer_info_b] {
+        peer_store.add_indirect_peers(&clock.clock(), std::iter::once(peer_info.clone()));
+    }
+    assert_eq!(peer_store.get_peer_state(&peer_info_a.id).unwrap().reputation, -10);
+    assert_eq!(peer_store.get_peer_state(&peer_info_b.id).unwrap().reputation, 0);
+}
+
 #[test]
 fn test_unknown_vs_not_connected() {
     use KnownPeerStatus::{Connected, NotConnected, Unknown};
@@ -85,9 +156,12 @@ fn test_unknown_vs_not_connected() {
         nodes.map(|peer| peer_store.get_peer_state(&peer.id).map(|known_state| known_state.status))
     };
 
-    let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
-            .unwrap();
+    let peer_store = PeerStore::new(
+        &clock.clock(),
+        make_config(&boot_nodes, Blacklist::default(), false),
+        make_store(),
+    )
+    .unwrap();
 
     // Check the status of the in-memory store.
     // Boot node should be marked as not-connected, as we've verified it.
@@ -159,9 +233,12 @@ fn test_unconnected_peer_only_boot_nodes() {
     // 1 non-boot (peer_in_store) node peer that is in the store.
     // we should connect to peer_in_store
     {
-        let peer_store =
-            PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
-                .unwrap();
+        let peer_store = PeerStore::new(
+            &clock.clock(),
+            make_config(&boot_nodes, Blacklist::default(), false),
+            make_store(),
+        )
+        .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_in_store.clone());
         peer_store.peer_connected(&clock.clock(), &peer_info_a);
         assert_eq!(peer_store.unconnected_peer(|_| false, false), Some(peer_in_store.clone()));
@@ -171,9 +248,12 @@ fn test_unconnected_peer_only_boot_nodes() {
     // 1 non-boot (peer_in_store) node peer that is in the store.
     // connect to only boot nodes is enabled - we should not find any peer to connect to.
     {
-        let peer_store =
-            PeerStore::new(&clock.clock(), make_config(&boot_nodes, Default::default(), true))
-                .unwrap();
+        let peer_store = PeerStore::new(
+            &clock.clock(),
+            make_config(&boot_nodes, Default::default(), true),
+            make_store(),
+        )
+        .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_in_store);
         peer_store.peer_connected(&clock.clock(), &peer_info_a);
         assert_eq!(peer_store.unconnected_peer(|_| false, false), None);
@@ -185,6 +265,7 @@ fn test_unconnected_peer_only_boot_nodes() {
         let peer_store = PeerStore::new(
             &clock.clock(),
             make_config(&boot_nodes, Default::default(), connect_to_boot_nodes),
+            make_store(),
         )
         .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_info_a.clone());
@@ -234,7 +315,8 @@ fn check_integrity(peer_store: &PeerStore) -> bool {
 fn handle_peer_id_change() {
     let clock = time::FakeClock::default();
     let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false)).unwrap();
+        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false), make_store())
+            .unwrap();
 
     let peers_id = (0..2).map(|ix| get_peer_id(format!("node{}", ix))).collect::<Vec<_>>();
     let addr = get_addr(0);
@@ -258,7 +340,8 @@ fn handle_peer_id_change() {
 fn dont_handle_address_change() {
     let clock = time::FakeClock::default();
     let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false)).unwrap();
+        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false), make_store())
+            .unwrap();
 
     let peers_id = (0..1).map(|ix| get_peer_id(format!("node{}", ix))).collect::<Vec<_>>();
     let addrs = (0..2).map(get_addr).collect::<Vec<_>>();
@@ -277,7 +360,8 @@ fn dont_handle_address_change() {
 fn check_add_peers_overriding() {
     let clock = time::FakeClock::default();
     let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false)).unwrap();
+        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false), make_store())
+            .unwrap();
 
     // Five peers: A, B, C, D, X, T
     let peers_id = (0..6).map(|ix| get_peer_id(format!("node{}", ix))).collect::<Vec<_>>();
@@ -369,7 +453,8 @@ fn check_ignore_blacklisted_peers() {
     let blacklist: blacklist::Blacklist =
         ["127.0.0.1:1"].iter().map(|e| e.parse().unwrap()).collect();
 
-    let peer_store = PeerStore::new(&clock.clock(), make_config(&[], blacklist, false)).unwrap();
+    let peer_store =
+        PeerStore::new(&clock.clock(), make_config(&[], blacklist, false), make_store()).unwrap();
 
     peer_store.add_indirect_peers(
         &clock.clock(),
@@ -414,7 +499,8 @@ fn test_delete_peers() {
     let peer_addresses = peer_infos.iter().map(|info| info.addr.unwrap()).collect::<Vec<_>>();
 
     let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false)).unwrap();
+        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false), make_store())
+            .unwrap();
 
     peer_store.add_indirect_peers(&clock.clock(), peer_infos.into_iter());
     assert_peers_in_cache(&peer_store, &peer_ids, &peer_addresses);
@@ -428,7 +514,7 @@ fn test_lru_eviction() {
     let clock = time::FakeClock::default();
     let mut config = make_config(&[], Default::default(), false);
     config.peer_states_cache_size = 10;
-    let peer_store = PeerStore::new(&clock.clock(), config).unwrap();
+    let peer_store = PeerStore::new(&clock.clock(), config, make_store()).unwrap();
 
     let (peer_ids, peer_infos): (Vec<_>, Vec<_>) = (0..15)
         .map(|i| {
@@ -459,7 +545,7 @@ fn test_lru_ignore_duplicate_peers() {
     let clock = time::FakeClock::default();
     let mut config = make_config(&[], Default::default(), false);
     config.peer_states_cache_size = 10;
-    let peer_store = PeerStore::new(&clock.clock(), config).unwrap();
+    let peer_store = PeerStore::new(&clock.clock(), config, make_store()).unwrap();
 
     let (peer_ids, peer_infos): (Vec<_>, Vec<_>) = (0..15)
         .map(|i| {
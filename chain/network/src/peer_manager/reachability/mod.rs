@@ -0,0 +1,86 @@
+//! Active check of whether other nodes can connect to this node.
+//!
+//! While the node has no inbound connections, which would prove that it is reachable,
+//! it asks one of its peers from time to time to dial back: the peer opens a TCP
+//! connection to the address which the node advertised in the handshake and responds
+//! with whether it succeeded. `Prober` keeps track of the probe in progress and of the
+//! last result.
+use near_async::time;
+use near_primitives::network::PeerId;
+
+#[cfg(test)]
+mod tests;
+
+/// Minimal time between the starts of two probes.
+pub(crate) const PROBE_INTERVAL: time::Duration = time::Duration::minutes(5);
+/// How long to wait for the response of the peer, before another peer may be asked.
+pub(crate) const PROBE_TIMEOUT: time::Duration = time::Duration::seconds(30);
+/// How long the result of a probe is valid.
+pub(crate) const PROBE_RESULT_TTL: time::Duration = time::Duration::minutes(15);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ProbeState {
+    /// No probe is in progress.
+    Idle,
+    /// The peer was asked to dial back and hasn't responded yet.
+    Pending { peer_id: PeerId, started: time::Instant },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ProbeResult {
+    reachable: bool,
+    finished: time::Instant,
+}
+
+pub(crate) struct Prober {
+    state: ProbeState,
+    /// Start of the last probe, whether it was answered or not.
+    last_started: Option<time::Instant>,
+    result: Option<ProbeResult>,
+}
+
+impl Prober {
+    pub fn new() -> Self {
+        Self { state: ProbeState::Idle, last_started: None, result: None }
+    }
+
+    /// Whether a new probe should be started. A probe which the peer didn't answer
+    /// within `PROBE_TIMEOUT` is abandoned.
+    pub fn should_start(&mut self, now: time::Instant) -> bool {
+        if let ProbeState::Pending { started, .. } = &self.state {
+            if now - *started < PROBE_TIMEOUT {
+                return false;
+            }
+            self.state = ProbeState::Idle;
+        }
+        self.last_started.map_or(true, |last_started| now - last_started >= PROBE_INTERVAL)
+    }
+
+    /// Records that the peer was asked to dial back.
+    pub fn start(&mut self, peer_id: PeerId, now: time::Instant) {
+        self.state = ProbeState::Pending { peer_id, started: now };
+        self.last_started = Some(now);
+    }
+
+    /// Records the response of the peer. Returns false if the peer wasn't asked
+    /// to dial back, or responded too late.
+    pub fn finish(&mut self, peer_id: &PeerId, reachable: bool, now: time::Instant) -> bool {
+        match &self.state {
+            ProbeState::Pending { peer_id: pending, started }
+                if pending == peer_id && now - *started < PROBE_TIMEOUT =>
+            {
+                self.state = ProbeState::Idle;
+                self.result = Some(ProbeResult { reachable, finished: now });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Result of the last probe, unless it is older than `PROBE_RESULT_TTL`.
+    pub fn reachable(&self, now: time::Instant) -> Option<bool> {
+        self.result
+            .filter(|result| now - result.finished < PROBE_RESULT_TTL)
+            .map(|result| result.reachable)
+    }
+}
@@ -0,0 +1,49 @@
+use super::*;
+use crate::network_protocol::testonly as data;
+use crate::testonly::make_rng;
+
+#[test]
+fn probe_state_machine() {
+    let mut rng = make_rng(921853233);
+    let clock = time::FakeClock::default();
+    let peer_a = data::make_peer_id(&mut rng);
+    let peer_b = data::make_peer_id(&mut rng);
+    let mut prober = Prober::new();
+
+    assert!(prober.should_start(clock.now()));
+    assert_eq!(prober.reachable(clock.now()), None);
+    prober.start(peer_a.clone(), clock.now());
+    assert_eq!(prober.state, ProbeState::Pending { peer_id: peer_a.clone(), started: clock.now() });
+    assert!(!prober.should_start(clock.now()));
+
+    // Responses of peers which were not asked are ignored.
+    assert!(!prober.finish(&peer_b, false, clock.now()));
+    clock.advance(time::Duration::seconds(1));
+    assert!(prober.finish(&peer_a, true, clock.now()));
+    assert_eq!(prober.state, ProbeState::Idle);
+    assert_eq!(prober.reachable(clock.now()), Some(true));
+    // The probe is answered already.
+    assert!(!prober.finish(&peer_a, false, clock.now()));
+    assert_eq!(prober.reachable(clock.now()), Some(true));
+
+    // The next probe starts after PROBE_INTERVAL.
+    assert!(!prober.should_start(clock.now()));
+    clock.advance(PROBE_INTERVAL);
+    assert!(prober.should_start(clock.now()));
+    prober.start(peer_b.clone(), clock.now());
+
+    // A late response is ignored, and the probe is abandoned.
+    clock.advance(PROBE_TIMEOUT);
+    assert!(!prober.finish(&peer_b, false, clock.now()));
+    assert!(!prober.should_start(clock.now()));
+    assert_eq!(prober.state, ProbeState::Idle);
+    assert_eq!(prober.reachable(clock.now()), Some(true));
+
+    // The result expires.
+    clock.advance(PROBE_RESULT_TTL);
+    assert_eq!(prober.reachable(clock.now()), None);
+    assert!(prober.should_start(clock.now()));
+    prober.start(peer_b.clone(), clock.now());
+    assert!(prober.finish(&peer_b, false, clock.now()));
+    assert_eq!(prober.reachable(clock.now()), Some(false));
+}
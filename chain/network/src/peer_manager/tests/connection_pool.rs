@@ -107,6 +107,8 @@ async fn loop_connection() {
                 &pm.cfg.node_key,
             ),
             owned_account: None,
+            supports_zstd: false,
+            zstd_dictionary: None,
         }))
         .await;
     let reason = events
@@ -177,6 +179,8 @@ async fn owned_account_mismatch() {
                 }
                 .sign(&signer),
             ),
+            supports_zstd: false,
+            zstd_dictionary: None,
         }))
         .await;
     let reason = events
@@ -299,6 +303,8 @@ async fn invalid_edge() {
                     }
                     .sign(&signer),
                 ),
+                supports_zstd: false,
+                zstd_dictionary: None,
             };
             let handshake = match tier {
                 tcp::Tier::T1 => PeerMessage::Tier1Handshake(handshake),
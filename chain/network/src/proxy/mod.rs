@@ -0,0 +1,159 @@
+//! Tunnelling of the outbound connections through a SOCKS5 or HTTP CONNECT proxy.
+//!
+//! Only the handshakes needed to open a tunnel are implemented: the SOCKS5 CONNECT
+//! command with optional username/password authentication (RFC 1928, RFC 1929) and
+//! the HTTP CONNECT method with optional basic authentication (RFC 9110).
+use anyhow::{anyhow, bail, Context as _};
+use near_primitives::serialize::to_base64;
+use std::net::SocketAddr;
+use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
+
+#[cfg(test)]
+mod tests;
+
+/// Maximal size of the response headers of an HTTP proxy.
+const MAX_HTTP_RESPONSE_SIZE: usize = 8 * 1024;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    Socks5,
+    Http,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub protocol: Protocol,
+    /// Address of the proxy, `<domain/ip>:<port>`. Domain names are resolved locally.
+    pub addr: String,
+    #[serde(default)]
+    pub credentials: Option<Credentials>,
+}
+
+impl Config {
+    /// Resolves the address of the proxy.
+    pub(crate) async fn resolve(&self) -> anyhow::Result<SocketAddr> {
+        tokio::net::lookup_host(&self.addr)
+            .await
+            .with_context(|| format!("lookup_host({})", self.addr))?
+            .next()
+            .ok_or_else(|| anyhow!("proxy address {} didn't resolve to any IP", self.addr))
+    }
+
+    /// Asks the proxy, connected through `stream`, to open a tunnel to `target`.
+    /// Once it returns, the data written to `stream` is forwarded to `target`.
+    pub(crate) async fn handshake(
+        &self,
+        stream: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        target: SocketAddr,
+    ) -> anyhow::Result<()> {
+        match self.protocol {
+            Protocol::Socks5 => socks5_handshake(stream, self.credentials.as_ref(), target).await,
+            Protocol::Http => http_handshake(stream, self.credentials.as_ref(), target).await,
+        }
+    }
+}
+
+async fn socks5_handshake(
+    stream: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    credentials: Option<&Credentials>,
+    target: SocketAddr,
+) -> anyhow::Result<()> {
+    const VERSION: u8 = 5;
+    const NO_AUTHENTICATION: u8 = 0;
+    const USERNAME_PASSWORD: u8 = 2;
+    const CONNECT: u8 = 1;
+    const IPV4: u8 = 1;
+    const DOMAIN_NAME: u8 = 3;
+    const IPV6: u8 = 4;
+
+    let method = if credentials.is_some() { USERNAME_PASSWORD } else { NO_AUTHENTICATION };
+    stream.write_all(&[VERSION, 1, method]).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != VERSION {
+        bail!("not a SOCKS5 proxy");
+    }
+    if reply[1] != method {
+        bail!("SOCKS5 proxy rejected the authentication method");
+    }
+    if let Some(credentials) = credentials {
+        let mut auth = vec![1];
+        for field in [&credentials.username, &credentials.password] {
+            auth.push(u8::try_from(field.len()).context("SOCKS5 credentials too long")?);
+            auth.extend_from_slice(field.as_bytes());
+        }
+        stream.write_all(&auth).await?;
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0 {
+            bail!("SOCKS5 proxy rejected the credentials");
+        }
+    }
+
+    let mut request = vec![VERSION, CONNECT, 0];
+    match target {
+        SocketAddr::V4(addr) => {
+            request.push(IPV4);
+            request.extend_from_slice(&addr.ip().octets());
+        }
+        SocketAddr::V6(addr) => {
+            request.push(IPV6);
+            request.extend_from_slice(&addr.ip().octets());
+        }
+    }
+    request.extend_from_slice(&target.port().to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0 {
+        bail!("SOCKS5 proxy failed to connect to {target}: reply code {}", reply[1]);
+    }
+    // Skip the address the proxy bound to, we don't need it.
+    let addr_len = match reply[3] {
+        IPV4 => 4,
+        IPV6 => 16,
+        DOMAIN_NAME => stream.read_u8().await? as usize,
+        atyp => bail!("SOCKS5 proxy replied with unknown address type {atyp}"),
+    };
+    let mut bound_addr = vec![0u8; addr_len + 2];
+    stream.read_exact(&mut bound_addr).await?;
+    Ok(())
+}
+
+async fn http_handshake(
+    stream: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    credentials: Option<&Credentials>,
+    target: SocketAddr,
+) -> anyhow::Result<()> {
+    let mut request = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+    if let Some(credentials) = credentials {
+        let token =
+            to_base64(format!("{}:{}", credentials.username, credentials.password).as_bytes());
+        request += &format!("Proxy-Authorization: Basic {token}\r\n");
+    }
+    request += "\r\n";
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the response byte by byte, so that nothing sent by the target after the
+    // headers gets consumed.
+    let mut response = vec![];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_HTTP_RESPONSE_SIZE {
+            bail!("HTTP proxy response too long");
+        }
+        response.push(stream.read_u8().await?);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => bail!("HTTP proxy failed to connect to {target}: {status_line}"),
+    }
+}
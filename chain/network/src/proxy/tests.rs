@@ -0,0 +1,89 @@
+use crate::proxy::{Config, Credentials, Protocol};
+use near_o11y::testonly::init_test_logger;
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+fn credentials() -> Option<Credentials> {
+    Some(Credentials { username: "user".to_string(), password: "pass".to_string() })
+}
+
+#[tokio::test]
+async fn socks5_handshake() {
+    init_test_logger();
+    let target = "1.2.3.4:24567".parse().unwrap();
+    let cfg =
+        Config { protocol: Protocol::Socks5, addr: String::new(), credentials: credentials() };
+    let (mut client, mut proxy) = tokio::io::duplex(1024);
+    let proxy = tokio::spawn(async move {
+        let mut greeting = [0u8; 3];
+        proxy.read_exact(&mut greeting).await.unwrap();
+        assert_eq!([5, 1, 2], greeting);
+        proxy.write_all(&[5, 2]).await.unwrap();
+        let mut auth = [0u8; 11];
+        proxy.read_exact(&mut auth).await.unwrap();
+        assert_eq!(b"\x01\x04user\x04pass", &auth);
+        proxy.write_all(&[1, 0]).await.unwrap();
+        let mut request = [0u8; 10];
+        proxy.read_exact(&mut request).await.unwrap();
+        assert_eq!([5, 1, 0, 1, 1, 2, 3, 4, 0x5f, 0xf7], request);
+        proxy.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 80]).await.unwrap();
+        proxy.write_all(b"hello").await.unwrap();
+    });
+    cfg.handshake(&mut client, target).await.unwrap();
+    // Data sent by the target after the handshake is not consumed.
+    let mut data = [0u8; 5];
+    client.read_exact(&mut data).await.unwrap();
+    assert_eq!(b"hello", &data);
+    proxy.await.unwrap();
+}
+
+#[tokio::test]
+async fn socks5_handshake_connection_refused() {
+    init_test_logger();
+    let target = "1.2.3.4:24567".parse().unwrap();
+    let cfg = Config { protocol: Protocol::Socks5, addr: String::new(), credentials: None };
+    let (mut client, mut proxy) = tokio::io::duplex(1024);
+    tokio::spawn(async move {
+        let mut greeting = [0u8; 3];
+        proxy.read_exact(&mut greeting).await.unwrap();
+        proxy.write_all(&[5, 0]).await.unwrap();
+        let mut request = [0u8; 10];
+        proxy.read_exact(&mut request).await.unwrap();
+        proxy.write_all(&[5, 5, 0, 1, 0, 0, 0, 0, 0, 0]).await.unwrap();
+    });
+    assert!(cfg.handshake(&mut client, target).await.is_err());
+}
+
+#[tokio::test]
+async fn http_handshake() {
+    init_test_logger();
+    let target = "1.2.3.4:24567".parse().unwrap();
+    let cfg = Config { protocol: Protocol::Http, addr: String::new(), credentials: credentials() };
+    let (mut client, mut proxy) = tokio::io::duplex(1024);
+    let proxy = tokio::spawn(async move {
+        let want = "CONNECT 1.2.3.4:24567 HTTP/1.1\r\nHost: 1.2.3.4:24567\r\n\
+            Proxy-Authorization: Basic dXNlcjpwYXNz\r\n\r\n";
+        let mut request = vec![0u8; want.len()];
+        proxy.read_exact(&mut request).await.unwrap();
+        assert_eq!(want.as_bytes(), &request);
+        proxy.write_all(b"HTTP/1.1 200 Connection established\r\n\r\nhello").await.unwrap();
+    });
+    cfg.handshake(&mut client, target).await.unwrap();
+    let mut data = [0u8; 5];
+    client.read_exact(&mut data).await.unwrap();
+    assert_eq!(b"hello", &data);
+    proxy.await.unwrap();
+}
+
+#[tokio::test]
+async fn http_handshake_rejected() {
+    init_test_logger();
+    let target = "1.2.3.4:24567".parse().unwrap();
+    let cfg = Config { protocol: Protocol::Http, addr: String::new(), credentials: None };
+    let (mut client, mut proxy) = tokio::io::duplex(1024);
+    tokio::spawn(async move {
+        let mut request = [0u8; 1];
+        proxy.read_exact(&mut request).await.unwrap();
+        proxy.write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n").await.unwrap();
+    });
+    assert!(cfg.handshake(&mut client, target).await.is_err());
+}
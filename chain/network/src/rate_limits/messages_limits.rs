@@ -121,6 +121,18 @@ impl Config {
             RateLimitedPeerMessageKey::EpochSyncRequest,
             SingleMessageConfig::new(1, 1.0 / 30.0, None),
         );
+        // Challenges are rare and carry evidence of misbehavior, so allow a burst of challenges
+        // (e.g. a validator double signing several blocks) while still protecting the node from
+        // a peer flooding it with challenges which are expensive to verify.
+        config
+            .rate_limits
+            .insert(RateLimitedPeerMessageKey::Challenge, SingleMessageConfig::new(20, 1.0, None));
+        // Each probe makes the node open a TCP connection, while peers are expected to send
+        // one every few minutes.
+        config.rate_limits.insert(
+            RateLimitedPeerMessageKey::ReachabilityProbeRequest,
+            SingleMessageConfig::new(1, 1.0 / 60.0, None),
+        );
         config
     }
 
@@ -160,6 +172,8 @@ pub enum RateLimitedPeerMessageKey {
     PeersResponse,
     BlockHeadersRequest,
     BlockHeaders,
+    BlockHeadersRangeRequest,
+    BlockHeadersRange,
     BlockRequest,
     Block,
     Transaction,
@@ -176,6 +190,7 @@ pub enum RateLimitedPeerMessageKey {
     PartialEncodedChunkResponse,
     VersionedPartialEncodedChunk,
     PartialEncodedChunkForward,
+    PartialEncodedChunkForwardAck,
     ChunkEndorsement,
     ChunkStateWitnessAck,
     PartialEncodedStateWitness,
@@ -185,6 +200,8 @@ pub enum RateLimitedPeerMessageKey {
     ContractCodeResponse,
     PartialEncodedContractDeploys,
     EpochSyncRequest,
+    Challenge,
+    ReachabilityProbeRequest,
 }
 
 /// Given a `PeerMessage` returns a tuple containing the `RateLimitedPeerMessageKey`
@@ -204,6 +221,8 @@ fn get_key_and_token_cost(message: &PeerMessage) -> Option<(RateLimitedPeerMessa
         PeerMessage::PeersResponse(_) => Some((PeersResponse, 1)),
         PeerMessage::BlockHeadersRequest(_) => Some((BlockHeadersRequest, 1)),
         PeerMessage::BlockHeaders(_) => Some((BlockHeaders, 1)),
+        PeerMessage::BlockHeadersRangeRequest(_) => Some((BlockHeadersRangeRequest, 1)),
+        PeerMessage::BlockHeadersRange(_) => Some((BlockHeadersRange, 1)),
         PeerMessage::BlockRequest(_) => Some((BlockRequest, 1)),
         PeerMessage::Block(_) => Some((Block, 1)),
         PeerMessage::Transaction(_) => Some((Transaction, 1)),
@@ -218,12 +237,22 @@ fn get_key_and_token_cost(message: &PeerMessage) -> Option<(RateLimitedPeerMessa
             RoutedMessageBody::PartialEncodedChunkResponse(_) => {
                 Some((PartialEncodedChunkResponse, 1))
             }
+            // A batch costs as much as the requests and responses it carries.
+            RoutedMessageBody::PartialEncodedChunkRequestBatch(requests) => {
+                Some((PartialEncodedChunkRequest, requests.len().max(1) as u32))
+            }
+            RoutedMessageBody::PartialEncodedChunkResponseBatch(responses) => {
+                Some((PartialEncodedChunkResponse, responses.len().max(1) as u32))
+            }
             RoutedMessageBody::VersionedPartialEncodedChunk(_) => {
                 Some((VersionedPartialEncodedChunk, 1))
             }
             RoutedMessageBody::PartialEncodedChunkForward(_) => {
                 Some((PartialEncodedChunkForward, 1))
             }
+            RoutedMessageBody::PartialEncodedChunkForwardAck(_) => {
+                Some((PartialEncodedChunkForwardAck, 1))
+            }
             RoutedMessageBody::ChunkStateWitnessAck(_) => Some((ChunkStateWitnessAck, 1)),
             RoutedMessageBody::PartialEncodedStateWitness(_) => {
                 Some((PartialEncodedStateWitness, 1))
@@ -238,6 +267,7 @@ fn get_key_and_token_cost(message: &PeerMessage) -> Option<(RateLimitedPeerMessa
                 Some((PartialEncodedContractDeploys, 1))
             }
             RoutedMessageBody::VersionedChunkEndorsement(_) => Some((ChunkEndorsement, 1)),
+            RoutedMessageBody::Challenge(_) => Some((Challenge, 1)),
             RoutedMessageBody::_UnusedEpochSyncRequest => None,
             RoutedMessageBody::_UnusedEpochSyncResponse(_) => None,
             RoutedMessageBody::StatePartRequest(_) => None, // TODO
@@ -261,22 +291,31 @@ fn get_key_and_token_cost(message: &PeerMessage) -> Option<(RateLimitedPeerMessa
         PeerMessage::VersionedStateResponse(_) => Some((VersionedStateResponse, 1)),
         PeerMessage::EpochSyncRequest => Some((EpochSyncRequest, 1)),
         PeerMessage::EpochSyncResponse(_) => None,
+        PeerMessage::Challenge(_) => Some((Challenge, 1)),
+        PeerMessage::ReachabilityProbeRequest => Some((ReachabilityProbeRequest, 1)),
+        PeerMessage::ReachabilityProbeResponse(_) => None,
         PeerMessage::Tier1Handshake(_)
         | PeerMessage::Tier2Handshake(_)
         | PeerMessage::Tier3Handshake(_)
         | PeerMessage::HandshakeFailure(_, _)
         | PeerMessage::LastEdge(_)
-        | PeerMessage::Disconnect(_)
-        | PeerMessage::Challenge(_) => None,
+        | PeerMessage::Disconnect(_) => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use near_async::time::{Duration, FakeClock};
+    use near_primitives::challenge::{BlockDoubleSign, Challenge, ChallengeBody};
     use near_primitives::hash::CryptoHash;
+    use near_primitives::test_utils::create_test_signer;
+    use near_primitives::types::EpochId;
+
+    use near_primitives::sharding::ChunkHash;
 
-    use crate::network_protocol::{Disconnect, PeerMessage};
+    use crate::network_protocol::testonly::make_routed_message;
+    use crate::network_protocol::{Disconnect, PartialEncodedChunkRequestMsg, PeerMessage};
+    use crate::testonly::make_rng;
 
     use super::*;
 
@@ -482,4 +521,53 @@ mod tests {
         clock.advance(Duration::seconds(30));
         assert!(rate_limits.is_allowed(&PeerMessage::EpochSyncRequest, clock.now()));
     }
+
+    #[test]
+    fn test_challenge_rate_limit() {
+        let config = Config::standard_preset();
+        let clock = FakeClock::default();
+        let mut rate_limits = RateLimits::from_config(&config, clock.now());
+        let challenge = Challenge::produce(
+            ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+                left_block_header: vec![],
+                right_block_header: vec![],
+            }),
+            EpochId::default(),
+            0,
+            &create_test_signer("test"),
+        );
+        let message = PeerMessage::Challenge(Box::new(challenge));
+        for _ in 0..20 {
+            assert!(rate_limits.is_allowed(&message, clock.now()));
+        }
+        assert!(!rate_limits.is_allowed(&message, clock.now()));
+        clock.advance(Duration::seconds(1));
+        assert!(rate_limits.is_allowed(&message, clock.now()));
+    }
+
+    #[test]
+    fn request_batch_is_charged_per_request() {
+        let mut rng = make_rng(921853233);
+        let now = Instant::now();
+        let mut limits = RateLimits::default();
+        limits.buckets[RateLimitedPeerMessageKey::PartialEncodedChunkRequest] =
+            Some(TokenBucket::new(5, 5, 0.0, now).unwrap());
+        let request = PartialEncodedChunkRequestMsg {
+            chunk_hash: ChunkHash::default(),
+            part_ords: vec![0],
+            tracking_shards: Default::default(),
+        };
+        let mut batch = |len: usize| {
+            PeerMessage::Routed(Box::new(make_routed_message(
+                &mut rng,
+                RoutedMessageBody::PartialEncodedChunkRequestBatch(vec![request.clone(); len]),
+            )))
+        };
+        let (batch_of_three, batch_of_two) = (batch(3), batch(2));
+        assert!(limits.is_allowed(&batch_of_three, now));
+        // Only two tokens are left, a second batch of three must be rejected.
+        assert!(!limits.is_allowed(&batch_of_three, now));
+        assert!(limits.is_allowed(&batch_of_two, now));
+        assert!(!limits.is_allowed(&batch_of_two, now));
+    }
 }
@@ -1,2 +1,3 @@
 pub mod messages_limits;
+pub mod send_shaping;
 pub mod token_bucket;
@@ -0,0 +1,352 @@
+//! Prioritization and shaping of the messages sent to a single peer.
+//!
+//! Every sent message belongs to a [MessageClass]. The classes are served in
+//! strict priority order: a message is only written to the connection when no
+//! message of a more important class is waiting. Within a class, messages are
+//! sent in the order they were queued.
+//!
+//! Optionally, each class can be limited in the number of bytes per second it
+//! sends, and in the number of bytes it may keep queued. Messages which don't fit
+//! in the queue of their class are dropped.
+
+use crate::network_protocol::{PeerMessage, RoutedMessageBody};
+use crate::stats::metrics;
+use enum_map::EnumMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Class of a sent message, from the most to the least important.
+#[derive(
+    Clone,
+    Copy,
+    enum_map::Enum,
+    strum::IntoStaticStr,
+    strum::Display,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum MessageClass {
+    /// Handshakes and connection management.
+    Control,
+    /// Approvals, chunk endorsements, state witnesses and challenges.
+    Consensus,
+    /// Chunk parts, and the requests for them.
+    ChunkParts,
+    /// Blocks and block headers, and the requests for them.
+    Blocks,
+    /// Routing, peer discovery, accounts data and whatever isn't classified otherwise.
+    Other,
+    /// State sync and epoch sync.
+    StateSync,
+    /// Transactions and their status.
+    Transactions,
+}
+
+impl MessageClass {
+    pub fn of(message: &PeerMessage) -> Self {
+        match message {
+            PeerMessage::Tier1Handshake(_)
+            | PeerMessage::Tier2Handshake(_)
+            | PeerMessage::Tier3Handshake(_)
+            | PeerMessage::HandshakeFailure(_, _)
+            | PeerMessage::LastEdge(_)
+            | PeerMessage::Disconnect(_)
+            | PeerMessage::RequestUpdateNonce(_) => MessageClass::Control,
+            PeerMessage::Challenge(_) => MessageClass::Consensus,
+            PeerMessage::BlockHeadersRequest(_)
+            | PeerMessage::BlockHeaders(_)
+            | PeerMessage::BlockHeadersRangeRequest(_)
+            | PeerMessage::BlockHeadersRange(_)
+            | PeerMessage::BlockRequest(_)
+            | PeerMessage::Block(_) => MessageClass::Blocks,
+            PeerMessage::StateRequestHeader(_, _)
+            | PeerMessage::StateRequestPart(_, _, _)
+            | PeerMessage::VersionedStateResponse(_)
+            | PeerMessage::EpochSyncRequest
+            | PeerMessage::EpochSyncResponse(_) => MessageClass::StateSync,
+            PeerMessage::Transaction(_) => MessageClass::Transactions,
+            PeerMessage::SyncRoutingTable(_)
+            | PeerMessage::DistanceVector(_)
+            | PeerMessage::SyncAccountsData(_)
+            | PeerMessage::PeersRequest(_)
+            | PeerMessage::PeersResponse(_)
+            | PeerMessage::SyncSnapshotHosts(_)
+            | PeerMessage::ReachabilityProbeRequest
+            | PeerMessage::ReachabilityProbeResponse(_) => MessageClass::Other,
+            PeerMessage::Routed(msg) => match &msg.body {
+                RoutedMessageBody::BlockApproval(_)
+                | RoutedMessageBody::VersionedChunkEndorsement(_)
+                | RoutedMessageBody::PartialEncodedStateWitness(_)
+                | RoutedMessageBody::PartialEncodedStateWitnessForward(_)
+                | RoutedMessageBody::ChunkStateWitnessAck(_)
+                | RoutedMessageBody::ChunkContractAccesses(_)
+                | RoutedMessageBody::ContractCodeRequest(_)
+                | RoutedMessageBody::ContractCodeResponse(_)
+                | RoutedMessageBody::Challenge(_) => MessageClass::Consensus,
+                RoutedMessageBody::PartialEncodedChunkRequest(_)
+                | RoutedMessageBody::PartialEncodedChunkResponse(_)
+                | RoutedMessageBody::PartialEncodedChunkRequestBatch(_)
+                | RoutedMessageBody::PartialEncodedChunkResponseBatch(_)
+                | RoutedMessageBody::VersionedPartialEncodedChunk(_)
+                | RoutedMessageBody::PartialEncodedChunkForward(_)
+                | RoutedMessageBody::PartialEncodedChunkForwardAck(_) => MessageClass::ChunkParts,
+                RoutedMessageBody::StatePartRequest(_) => MessageClass::StateSync,
+                RoutedMessageBody::ForwardTx(_)
+                | RoutedMessageBody::TxStatusRequest(_, _)
+                | RoutedMessageBody::TxStatusResponse(_) => MessageClass::Transactions,
+                _ => MessageClass::Other,
+            },
+        }
+    }
+}
+
+/// Limits of a single message class.
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+pub struct ClassLimits {
+    /// Sustained number of bytes per second the class may send. Unlimited if absent.
+    pub bytes_per_second: Option<u64>,
+    /// Number of bytes the class may send at once above the sustained rate.
+    #[serde(default)]
+    pub burst_bytes: u64,
+    /// Number of bytes of messages the class may keep queued. Further messages of the class
+    /// are dropped. Unlimited if absent.
+    pub max_queued_bytes: Option<u64>,
+}
+
+/// Limits of the sent messages, by message class. Classes without limits are only prioritized.
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Config {
+    pub limits: HashMap<MessageClass, ClassLimits>,
+}
+
+impl Config {
+    /// Returns the classes whose limits are invalid.
+    pub fn validate(&self) -> Result<(), Vec<MessageClass>> {
+        let mut errors: Vec<_> = self
+            .limits
+            .iter()
+            .filter(|(_, limits)| limits.bytes_per_second == Some(0))
+            .map(|(class, _)| *class)
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            errors.sort_by_key(|class| *class as usize);
+            Err(errors)
+        }
+    }
+}
+
+/// Byte budget of a rate limited class. The budget may go negative, so that a message
+/// larger than the burst can still be sent: the class then waits until the debt is repaid.
+struct ByteBudget {
+    bytes_per_second: f64,
+    burst_bytes: f64,
+    available: f64,
+    last_refill: Instant,
+}
+
+impl ByteBudget {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.bytes_per_second).min(self.burst_bytes);
+        self.last_refill = now;
+    }
+
+    /// Returns how long to wait until the class may send again.
+    fn wait_time(&mut self, now: Instant) -> Option<Duration> {
+        self.refill(now);
+        if self.available >= 0. {
+            return None;
+        }
+        Some(Duration::from_secs_f64(-self.available / self.bytes_per_second))
+    }
+}
+
+#[derive(Default)]
+struct ClassQueue {
+    frames: VecDeque<Vec<u8>>,
+    queued_bytes: u64,
+    max_queued_bytes: Option<u64>,
+    budget: Option<ByteBudget>,
+}
+
+/// Result of [SendQueue::pop].
+#[derive(Debug, PartialEq)]
+pub(crate) enum Pop {
+    Frame(Vec<u8>),
+    /// Nothing can be sent now. Messages of rate limited classes can be sent after the
+    /// given time, if any; other messages once they are pushed.
+    Wait(Option<Duration>),
+    /// The queue is closed and empty.
+    Closed,
+}
+
+/// Queues of the messages waiting to be sent on a connection, by class.
+pub(crate) struct SendQueue {
+    inner: Mutex<SendQueueInner>,
+    /// Notified when a message is pushed or the queue is closed.
+    pub notify: tokio::sync::Notify,
+}
+
+struct SendQueueInner {
+    classes: EnumMap<MessageClass, ClassQueue>,
+    closed: bool,
+}
+
+impl SendQueue {
+    pub fn new(config: &Config, now: Instant) -> Self {
+        let mut classes: EnumMap<MessageClass, ClassQueue> = EnumMap::default();
+        for (class, limits) in &config.limits {
+            let queue = &mut classes[*class];
+            queue.max_queued_bytes = limits.max_queued_bytes;
+            queue.budget = limits.bytes_per_second.map(|bytes_per_second| ByteBudget {
+                bytes_per_second: bytes_per_second as f64,
+                burst_bytes: limits.burst_bytes as f64,
+                available: limits.burst_bytes as f64,
+                last_refill: now,
+            });
+        }
+        Self {
+            inner: Mutex::new(SendQueueInner { classes, closed: false }),
+            notify: tokio::sync::Notify::new(),
+        }
+    }
+
+    /// Queues `frame` for sending. Returns false if the frame was dropped because the queue
+    /// of its class is full.
+    pub fn push(&self, class: MessageClass, frame: Vec<u8>) -> bool {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            let queue = &mut inner.classes[class];
+            let queued_bytes = queue.queued_bytes + frame.len() as u64;
+            if queue.max_queued_bytes.is_some_and(|max| queued_bytes > max) {
+                return false;
+            }
+            queue.queued_bytes = queued_bytes;
+            queue.frames.push_back(frame);
+        }
+        metrics::PEER_SEND_QUEUE_MESSAGES.with_label_values(&[class.into()]).inc();
+        self.notify.notify_one();
+        true
+    }
+
+    /// Takes the next frame to send: the first one of the most important class which is not
+    /// rate limited. Once the queue is closed, the remaining frames are returned without
+    /// regard for the rate limits.
+    pub fn pop(&self, now: Instant) -> Pop {
+        let mut inner = self.inner.lock().unwrap();
+        let closed = inner.closed;
+        let mut wait: Option<Duration> = None;
+        for (class, queue) in inner.classes.iter_mut() {
+            if queue.frames.is_empty() {
+                continue;
+            }
+            if let (Some(budget), false) = (&mut queue.budget, closed) {
+                if let Some(class_wait) = budget.wait_time(now) {
+                    wait = Some(wait.map_or(class_wait, |wait| wait.min(class_wait)));
+                    continue;
+                }
+            }
+            let frame = queue.frames.pop_front().unwrap();
+            queue.queued_bytes -= frame.len() as u64;
+            if let Some(budget) = &mut queue.budget {
+                budget.available -= frame.len() as f64;
+            }
+            metrics::PEER_SEND_QUEUE_MESSAGES.with_label_values(&[class.into()]).dec();
+            return Pop::Frame(frame);
+        }
+        if closed {
+            return Pop::Closed;
+        }
+        Pop::Wait(wait)
+    }
+
+    /// Closes the queue: the frames already queued are still returned by `pop`.
+    pub fn close(&self) {
+        self.inner.lock().unwrap().closed = true;
+        self.notify.notify_one();
+    }
+}
+
+impl Drop for SendQueue {
+    fn drop(&mut self) {
+        let inner = self.inner.get_mut().unwrap();
+        for (class, queue) in inner.classes.iter() {
+            metrics::PEER_SEND_QUEUE_MESSAGES
+                .with_label_values(&[class.into()])
+                .sub(queue.frames.len() as i64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(len: usize) -> Vec<u8> {
+        vec![0; len]
+    }
+
+    #[test]
+    fn strict_priority() {
+        let now = Instant::now();
+        let queue = SendQueue::new(&Config::default(), now);
+        assert!(queue.push(MessageClass::Transactions, frame(1)));
+        assert!(queue.push(MessageClass::Blocks, frame(2)));
+        assert!(queue.push(MessageClass::Consensus, frame(3)));
+        assert!(queue.push(MessageClass::Blocks, frame(4)));
+        let popped: Vec<_> = (0..4)
+            .map(|_| match queue.pop(now) {
+                Pop::Frame(frame) => frame.len(),
+                pop => panic!("unexpected {pop:?}"),
+            })
+            .collect();
+        assert_eq!(popped, vec![3, 2, 4, 1]);
+        assert_eq!(queue.pop(now), Pop::Wait(None));
+        queue.close();
+        assert_eq!(queue.pop(now), Pop::Closed);
+    }
+
+    #[test]
+    fn rate_limited_class() {
+        let now = Instant::now();
+        let config = Config {
+            limits: [(
+                MessageClass::StateSync,
+                ClassLimits {
+                    bytes_per_second: Some(1000),
+                    burst_bytes: 100,
+                    max_queued_bytes: Some(1000),
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+        let queue = SendQueue::new(&config, now);
+        assert!(queue.push(MessageClass::StateSync, frame(600)));
+        assert!(queue.push(MessageClass::StateSync, frame(400)));
+        // The queue of the class is full.
+        assert!(!queue.push(MessageClass::StateSync, frame(1)));
+        assert!(queue.push(MessageClass::Other, frame(10)));
+
+        // The first message uses the burst and more, the second one waits for the debt.
+        assert_eq!(queue.pop(now), Pop::Frame(frame(600)));
+        assert_eq!(queue.pop(now), Pop::Frame(frame(10)));
+        assert_eq!(queue.pop(now), Pop::Wait(Some(Duration::from_millis(500))));
+        let later = now + Duration::from_millis(500);
+        assert_eq!(queue.pop(later), Pop::Frame(frame(400)));
+
+        // Once closed, the remaining messages are sent regardless of the rate.
+        assert!(queue.push(MessageClass::StateSync, frame(100)));
+        assert!(matches!(queue.pop(later), Pop::Wait(Some(_))));
+        queue.close();
+        assert_eq!(queue.pop(later), Pop::Frame(frame(100)));
+        assert_eq!(queue.pop(later), Pop::Closed);
+    }
+}
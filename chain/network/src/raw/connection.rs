@@ -218,6 +218,8 @@ fn new_handshake(
         },
         partial_edge_info: PartialEdgeInfo::new(my_peer_id, target_peer_id, nonce, secret_key),
         owned_account: None,
+        supports_zstd: false,
+        zstd_dictionary: None,
     })
 }
 
@@ -601,7 +603,7 @@ impl PeerStream {
         }
 
         self.buf.advance(4);
-        let msg = PeerMessage::deserialize(Encoding::Proto, &self.buf[..msg_length]);
+        let msg = PeerMessage::deserialize(Encoding::Proto, &self.buf[..msg_length], None);
         self.buf.advance(msg_length);
 
         // make sure we can probably read the next message in one syscall next time
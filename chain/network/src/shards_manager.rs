@@ -1,17 +1,24 @@
 use actix::Message;
 use near_async::time::Instant;
-use near_primitives::{hash::CryptoHash, sharding::PartialEncodedChunk};
+use near_primitives::{hash::CryptoHash, network::PeerId, sharding::PartialEncodedChunk};
 
 use crate::types::{
-    PartialEncodedChunkForwardMsg, PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg,
+    PartialEncodedChunkForwardAckMsg, PartialEncodedChunkForwardMsg, PartialEncodedChunkRequestMsg,
+    PartialEncodedChunkResponseMsg,
 };
 
 #[derive(Message, Debug, strum::IntoStaticStr, Clone, PartialEq, Eq)]
 #[rtype(result = "()")]
 #[allow(clippy::large_enum_variant)]
 pub enum ShardsManagerRequestFromNetwork {
-    ProcessPartialEncodedChunk(PartialEncodedChunk),
+    /// A partial encoded chunk and the peer which sent it, so that the peer
+    /// can be banned if the chunk is malformed.
+    ProcessPartialEncodedChunk {
+        partial_encoded_chunk: PartialEncodedChunk,
+        peer_id: PeerId,
+    },
     ProcessPartialEncodedChunkForward(PartialEncodedChunkForwardMsg),
+    ProcessPartialEncodedChunkForwardAck(PartialEncodedChunkForwardAckMsg),
     ProcessPartialEncodedChunkResponse {
         partial_encoded_chunk_response: PartialEncodedChunkResponseMsg,
         received_time: Instant,
@@ -20,4 +27,9 @@ pub enum ShardsManagerRequestFromNetwork {
         partial_encoded_chunk_request: PartialEncodedChunkRequestMsg,
         route_back: CryptoHash,
     },
+    /// Requests for several chunks, all answered in one response message.
+    ProcessPartialEncodedChunkRequestBatch {
+        partial_encoded_chunk_requests: Vec<PartialEncodedChunkRequestMsg>,
+        route_back: CryptoHash,
+    },
 }
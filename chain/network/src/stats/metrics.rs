@@ -161,6 +161,22 @@ pub(crate) static PEER_DATA_WRITE_BUFFER_SIZE: LazyLock<IntGaugeVec> = LazyLock:
     )
     .unwrap()
 });
+pub(crate) static REACHABILITY: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "near_peer_reachability",
+        "Whether other nodes can connect to this node: 1 for the current status, 0 for the others",
+        &["status"],
+    )
+    .unwrap()
+});
+pub(crate) static PEER_SEND_QUEUE_MESSAGES: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "near_peer_send_queue_messages",
+        "Number of messages waiting to be sent to peers, by message class",
+        &["class"],
+    )
+    .unwrap()
+});
 pub(crate) static PEER_MESSAGE_RECEIVED_BY_TYPE_BYTES: LazyLock<IntCounterVec> =
     LazyLock::new(|| {
         try_create_int_counter_vec(
@@ -179,6 +195,24 @@ pub(crate) static PEER_MESSAGE_RECEIVED_BY_TYPE_TOTAL: LazyLock<IntCounterVec> =
         )
         .unwrap()
     });
+pub(crate) static PEER_MESSAGE_COMPRESSION_RATIO: LazyLock<HistogramVec> = LazyLock::new(|| {
+    try_create_histogram_vec(
+        "near_peer_message_compression_ratio",
+        "Ratio between the compressed and the original size of the sent messages, by type",
+        &["type"],
+        Some(prometheus::linear_buckets(0.05, 0.05, 20).unwrap()),
+    )
+    .unwrap()
+});
+pub(crate) static PEER_MESSAGE_COMPRESSION_TIME: LazyLock<HistogramVec> = LazyLock::new(|| {
+    try_create_histogram_vec(
+        "near_peer_message_compression_time",
+        "Time spent compressing the sent messages, by type",
+        &["type"],
+        Some(exponential_buckets(0.0001, 2., 15).unwrap()),
+    )
+    .unwrap()
+});
 pub(crate) static PEER_MESSAGE_SENT_BY_TYPE_BYTES: LazyLock<IntCounterVec> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "near_peer_message_sent_by_type_bytes",
@@ -461,6 +495,7 @@ pub(crate) enum MessageDropped {
     MaxCapacityExceeded,
     TransactionsPerBlockExceeded,
     Duplicate,
+    SendQueueFull,
 }
 
 impl MessageDropped {
@@ -472,7 +507,7 @@ impl MessageDropped {
         self.inc_msg_type("unknown")
     }
 
-    fn inc_msg_type(self, msg_type: &str) {
+    pub fn inc_msg_type(self, msg_type: &str) {
         let reason = self.as_ref();
         DROPPED_MESSAGE_COUNT.with_label_values(&[msg_type, reason]).inc();
     }
@@ -2,7 +2,7 @@
 /// All transactions should be implemented within this module,
 /// in particular schema::StoreUpdate is not exported.
 use crate::types::ConnectionInfo;
-use near_primitives::network::AnnounceAccount;
+use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::types::AccountId;
 use std::sync::Arc;
 
@@ -78,6 +78,21 @@ impl Store {
     }
 }
 
+// PeerStore storage.
+impl Store {
+    /// Inserts (peer_id,reputation) to the PeerReputation column.
+    pub fn set_peer_reputation(&mut self, peer_id: &PeerId, reputation: i64) -> Result<(), Error> {
+        let mut update = self.0.new_update();
+        update.set::<schema::PeerReputation>(peer_id, &reputation);
+        self.0.commit(update).map_err(Error)
+    }
+
+    /// Fetches row with key peer_id from the PeerReputation column.
+    pub fn get_peer_reputation(&self, peer_id: &PeerId) -> Result<Option<i64>, Error> {
+        self.0.get::<schema::PeerReputation>(peer_id).map_err(Error)
+    }
+}
+
 impl From<Arc<dyn near_store::db::Database>> for Store {
     fn from(store: Arc<dyn near_store::db::Database>) -> Self {
         Self(schema::Store::from(store))
@@ -102,6 +102,13 @@ impl Column for RecentOutboundConnections {
     type Value = Vec<ConnectionInfoRepr>;
 }
 
+pub(super) struct PeerReputation;
+impl Column for PeerReputation {
+    const COL: DBCol = DBCol::PeerReputation;
+    type Key = Borsh<PeerId>;
+    type Value = Borsh<i64>;
+}
+
 ////////////////////////////////////////////////////
 // Storage
 
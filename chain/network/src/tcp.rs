@@ -8,6 +8,9 @@ use std::sync::Mutex;
 
 const LISTENER_BACKLOG: u32 = 128;
 
+/// Maximal time to open a tunnel through the proxy, once connected to it.
+const PROXY_HANDSHAKE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// TEST-ONLY: guards ensuring that OS considers the given TCP listener port to be in use until
 /// this OS process is terminated.
 pub(crate) static RESERVED_LISTENER_ADDRS: std::sync::LazyLock<
@@ -97,7 +100,13 @@ impl Stream {
             .addr
             .ok_or_else(|| anyhow!("Trying to connect to peer with no public address"))?;
 
-        let socket = match addr {
+        // With a proxy, the TCP connection is established to the proxy instead of the peer.
+        let connect_addr = match &socket_options.proxy {
+            Some(proxy) => proxy.resolve().await?,
+            None => addr,
+        };
+
+        let socket = match connect_addr {
             std::net::SocketAddr::V4(_) => tokio::net::TcpSocket::new_v4()?,
             std::net::SocketAddr::V6(_) => tokio::net::TcpSocket::new_v6()?,
         };
@@ -126,10 +135,20 @@ impl Stream {
         // Why exactly a second? It was hard-coded in a library we used
         // before, so we keep it to preserve behavior. Removing the timeout
         // completely was observed to break stuff for real on the testnet.
-        let stream = tokio::time::timeout(std::time::Duration::from_secs(1), socket.connect(addr))
-            .await?
-            .context("TcpStream::connect()")?;
-        Ok(Stream::new(stream, StreamType::Outbound { peer_id: peer_info.id.clone(), tier })?)
+        let mut stream =
+            tokio::time::timeout(std::time::Duration::from_secs(1), socket.connect(connect_addr))
+                .await?
+                .context("TcpStream::connect()")?;
+        if let Some(proxy) = &socket_options.proxy {
+            tokio::time::timeout(PROXY_HANDSHAKE_TIMEOUT, proxy.handshake(&mut stream, addr))
+                .await?
+                .context("proxy handshake")?;
+        }
+        let mut stream =
+            Stream::new(stream, StreamType::Outbound { peer_id: peer_info.id.clone(), tier })?;
+        // Through a proxy, the socket is connected to the proxy, not the peer.
+        stream.peer_addr = addr;
+        Ok(stream)
     }
 
     /// Establishes a loopback TCP connection to localhost with random ports.
@@ -2,9 +2,9 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use crate::client::{
-    BlockApproval, BlockHeadersRequest, BlockHeadersResponse, BlockRequest, BlockResponse,
-    ChunkEndorsementMessage, EpochSyncRequestMessage, EpochSyncResponseMessage, ProcessTxRequest,
-    ProcessTxResponse,
+    BlockApproval, BlockHeadersRangeRequestMessage, BlockHeadersRangeResponse, BlockHeadersRequest,
+    BlockHeadersResponse, BlockRequest, BlockResponse, ChunkEndorsementMessage,
+    EpochSyncRequestMessage, EpochSyncResponseMessage, ProcessTxRequest, ProcessTxResponse,
 };
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
 use crate::state_witness::{
@@ -32,6 +32,8 @@ use near_primitives::types::AccountId;
 pub struct ClientSenderForTestLoopNetwork {
     pub block: AsyncSender<BlockResponse, ()>,
     pub block_headers: AsyncSender<BlockHeadersResponse, ActixResult<BlockHeadersResponse>>,
+    pub block_headers_range:
+        AsyncSender<BlockHeadersRangeResponse, ActixResult<BlockHeadersRangeResponse>>,
     pub block_approval: AsyncSender<BlockApproval, ()>,
     pub transaction: AsyncSender<ProcessTxRequest, ProcessTxResponse>,
     pub chunk_endorsement: AsyncSender<ChunkEndorsementMessage, ()>,
@@ -42,6 +44,8 @@ pub struct ClientSenderForTestLoopNetwork {
 #[derive(Clone, MultiSend, MultiSenderFrom)]
 pub struct ViewClientSenderForTestLoopNetwork {
     pub block_headers_request: AsyncSender<BlockHeadersRequest, ActixResult<BlockHeadersRequest>>,
+    pub block_headers_range_request:
+        AsyncSender<BlockHeadersRangeRequestMessage, ActixResult<BlockHeadersRangeRequestMessage>>,
     pub block_request: AsyncSender<BlockRequest, ActixResult<BlockRequest>>,
 }
 
@@ -312,6 +316,25 @@ fn network_message_to_view_client_handler(
             });
             None
         }
+        NetworkRequests::BlockHeadersRangeRequest { request, peer_id } => {
+            let responder = shared_state.senders_for_account(&my_account_id).client_sender.clone();
+            let future = shared_state
+                .senders_for_peer(&peer_id)
+                .view_client_sender
+                .send_async(BlockHeadersRangeRequestMessage(request));
+            future_spawner.spawn(
+                "wait for ViewClient to handle BlockHeadersRangeRequest",
+                async move {
+                    let Some(range) = future.await.unwrap() else {
+                        return;
+                    };
+                    let future = responder
+                        .send_async(BlockHeadersRangeResponse { range, from_peer: peer_id });
+                    drop(future);
+                },
+            );
+            None
+        }
         NetworkRequests::BlockRequest { hash, peer_id } => {
             let responder = shared_state.senders_for_account(&my_account_id).client_sender.clone();
             let future = shared_state
@@ -439,12 +462,38 @@ fn network_message_to_shards_manager_handler(
             );
             None
         }
+        NetworkRequests::PartialEncodedChunkRequestBatch { target, requests, .. } => {
+            let my_peer_id = shared_state.account_to_peer_id.get(&my_account_id).unwrap();
+            let route_back = shared_state.generate_route_back(my_peer_id);
+            let target = target.account_id.unwrap();
+            assert!(target != my_account_id, "Sending message to self not supported.");
+            shared_state.senders_for_account(&target).shards_manager_sender.send(
+                ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequestBatch {
+                    partial_encoded_chunk_requests: requests,
+                    route_back,
+                },
+            );
+            None
+        }
+        NetworkRequests::PartialEncodedChunkResponseBatch { route_back, responses } => {
+            let senders = shared_state.senders_for_route_back(&route_back);
+            for response in responses {
+                senders.shards_manager_sender.send(
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
+                        partial_encoded_chunk_response: response,
+                        received_time: clock.now(),
+                    },
+                );
+            }
+            None
+        }
         NetworkRequests::PartialEncodedChunkMessage { account_id, partial_encoded_chunk } => {
             assert!(account_id != my_account_id, "Sending message to self not supported.");
             shared_state.senders_for_account(&account_id).shards_manager_sender.send(
-                ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(
-                    partial_encoded_chunk.into(),
-                ),
+                ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
+                    partial_encoded_chunk: partial_encoded_chunk.into(),
+                    peer_id: PeerId::random(),
+                },
             );
             None
         }
@@ -456,6 +505,14 @@ fn network_message_to_shards_manager_handler(
                 .send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForward(forward));
             None
         }
+        NetworkRequests::PartialEncodedChunkForwardAck { account_id, ack } => {
+            assert!(account_id != my_account_id, "Sending message to self not supported.");
+            shared_state
+                .senders_for_account(&account_id)
+                .shards_manager_sender
+                .send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForwardAck(ack));
+            None
+        }
         _ => Some(request),
     })
 }
@@ -34,7 +34,7 @@ impl Stream {
             buf.resize(n, 0);
             self.stream.stream.read_exact(&mut buf[..]).await?;
             for enc in [Encoding::Proto, Encoding::Borsh] {
-                if let Ok(msg) = PeerMessage::deserialize(enc, &buf[..]) {
+                if let Ok(msg) = PeerMessage::deserialize(enc, &buf[..], None) {
                     // If deserialize() succeeded but we expected different encoding, ignore the
                     // message.
                     if self.encoding().unwrap_or(enc) != enc {
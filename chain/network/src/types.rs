@@ -5,9 +5,9 @@ pub use crate::network_protocol::{
 };
 /// Exported types, which are part of network protocol.
 pub use crate::network_protocol::{
-    Edge, PartialEdgeInfo, PartialEncodedChunkForwardMsg, PartialEncodedChunkRequestMsg,
-    PartialEncodedChunkResponseMsg, PeerChainInfoV2, PeerInfo, SnapshotHostInfo, StateResponseInfo,
-    StateResponseInfoV1, StateResponseInfoV2,
+    Edge, PartialEdgeInfo, PartialEncodedChunkForwardAckMsg, PartialEncodedChunkForwardMsg,
+    PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg, PeerChainInfoV2, PeerInfo,
+    SnapshotHostInfo, StateResponseInfo, StateResponseInfoV1, StateResponseInfoV2,
 };
 use crate::routing::routing_table_view::RoutingTableInfo;
 pub use crate::state_sync::StateSyncResponse;
@@ -15,10 +15,11 @@ use near_async::messaging::{AsyncSender, Sender};
 use near_async::{time, MultiSend, MultiSendMessage, MultiSenderFrom};
 use near_crypto::PublicKey;
 use near_primitives::block::{ApprovalMessage, Block, GenesisId};
+use near_primitives::block_headers_range::BlockHeadersRangeRequest;
 use near_primitives::challenge::Challenge;
 use near_primitives::epoch_sync::CompressedEpochSyncProof;
 use near_primitives::hash::CryptoHash;
-use near_primitives::network::{AnnounceAccount, PeerId};
+use near_primitives::network::{AnnounceAccount, PeerId, Reachability};
 use near_primitives::sharding::PartialEncodedChunkWithArcReceipts;
 use near_primitives::stateless_validation::chunk_endorsement::ChunkEndorsement;
 use near_primitives::stateless_validation::contract_distribution::{
@@ -120,6 +121,9 @@ pub struct KnownPeerState {
     // Last time we tried to connect to this peer.
     // This data is not persisted in storage.
     pub last_outbound_attempt: Option<(time::Utc, Result<(), String>)>,
+    /// Reputation earned on the past connections with this peer,
+    /// see `connection::Connection::reputation`.
+    pub reputation: i64,
 }
 
 impl KnownPeerState {
@@ -130,6 +134,7 @@ impl KnownPeerState {
             first_seen: now,
             last_seen: now,
             last_outbound_attempt: None,
+            reputation: 0,
         }
     }
 }
@@ -246,6 +251,8 @@ pub enum NetworkRequests {
     BlockRequest { hash: CryptoHash, peer_id: PeerId },
     /// Request given block headers.
     BlockHeadersRequest { hashes: Vec<CryptoHash>, peer_id: PeerId },
+    /// Request a range of headers committed to by a later block.
+    BlockHeadersRangeRequest { request: BlockHeadersRangeRequest, peer_id: PeerId },
     /// Request state header for given shard at given state root.
     StateRequestHeader { shard_id: ShardId, sync_hash: CryptoHash, peer_id: PeerId },
     /// Request state part for given shard at given state root.
@@ -270,6 +277,17 @@ pub enum NetworkRequests {
     },
     /// Information about chunk such as its header, some subset of parts and/or incoming receipts
     PartialEncodedChunkResponse { route_back: CryptoHash, response: PartialEncodedChunkResponseMsg },
+    /// Request parts and/or receipts of several chunks from the same target in one message
+    PartialEncodedChunkRequestBatch {
+        target: AccountIdOrPeerTrackingShard,
+        requests: Vec<PartialEncodedChunkRequestMsg>,
+        create_time: time::Instant,
+    },
+    /// Responses to a `PartialEncodedChunkRequestBatch`, in one message
+    PartialEncodedChunkResponseBatch {
+        route_back: CryptoHash,
+        responses: Vec<PartialEncodedChunkResponseMsg>,
+    },
     /// Information about chunk such as its header, some subset of parts and/or incoming receipts
     PartialEncodedChunkMessage {
         account_id: AccountId,
@@ -277,6 +295,8 @@ pub enum NetworkRequests {
     },
     /// Forwarding a chunk part to a validator tracking the shard
     PartialEncodedChunkForward { account_id: AccountId, forward: PartialEncodedChunkForwardMsg },
+    /// Acknowledging pushed chunk parts to their owner
+    PartialEncodedChunkForwardAck { account_id: AccountId, ack: PartialEncodedChunkForwardAckMsg },
     /// Valid transaction but since we are not validators we send this transaction to current validators.
     ForwardTx(AccountId, SignedTransaction),
     /// Query transaction status
@@ -395,6 +415,8 @@ pub struct ConnectedPeerInfo {
     pub peer_type: PeerType,
     /// Nonce used for the connection with the peer.
     pub nonce: u64,
+    /// Reputation of the peer, see `connection::Connection::reputation`.
+    pub reputation: i64,
 }
 
 #[derive(Debug, Clone, actix::MessageResponse, PartialEq, Eq)]
@@ -413,6 +435,8 @@ pub struct NetworkInfo {
     pub tier1_accounts_data: Vec<Arc<SignedAccountData>>,
     /// TIER1 connections.
     pub tier1_connections: Vec<ConnectedPeerInfo>,
+    /// Whether other nodes can connect to us.
+    pub reachability: Reachability,
 }
 
 #[derive(Debug, actix::MessageResponse, PartialEq, Eq)]
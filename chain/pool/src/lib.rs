@@ -1,5 +1,6 @@
+use std::cmp::Reverse;
 use std::collections::btree_map::Entry;
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 
 use crate::types::{PoolKey, TransactionGroup, TransactionGroupIterator};
 
@@ -32,6 +33,9 @@ pub struct TransactionPool {
     transactions: BTreeMap<PoolKey, Vec<SignedTransaction>>,
     /// Set of all hashes to quickly check if the given transaction is in the pool.
     unique_transactions: HashSet<CryptoHash>,
+    /// Keys of the groups containing a transaction with a non-zero priority fee, ordered by the
+    /// highest priority fee in the group. These groups are pulled before the rest of the pool.
+    priority_keys: BTreeSet<(Reverse<u64>, PoolKey)>,
     /// A uniquely generated key seed to randomize PoolKey order.
     key_seed: RngSeed,
     /// The key after which the pool iterator starts. Doesn't have to be present in the pool.
@@ -63,6 +67,7 @@ impl TransactionPool {
             key_seed,
             transactions: BTreeMap::new(),
             unique_transactions: HashSet::new(),
+            priority_keys: BTreeSet::new(),
             last_used_key: CryptoHash::default(),
             total_transaction_size_limit,
             total_transaction_size: 0,
@@ -78,6 +83,17 @@ impl TransactionPool {
         hash(&v)
     }
 
+    /// Updates the position of the group in `priority_keys` after its transactions changed.
+    fn update_priority_key(&mut self, key: PoolKey, old_priority_fee: u64, new_priority_fee: u64) {
+        if old_priority_fee == new_priority_fee {
+            return;
+        }
+        self.priority_keys.remove(&(Reverse(old_priority_fee), key));
+        if new_priority_fee > 0 {
+            self.priority_keys.insert((Reverse(new_priority_fee), key));
+        }
+    }
+
     /// Inserts a signed transaction that passed validation into the pool.
     #[must_use]
     pub fn insert_transaction(
@@ -105,10 +121,12 @@ impl TransactionPool {
         self.total_transaction_size = new_total_transaction_size;
         let signer_id = signed_transaction.transaction.signer_id();
         let signer_public_key = signed_transaction.transaction.public_key();
-        self.transactions
-            .entry(self.key(signer_id, signer_public_key))
-            .or_insert_with(Vec::new)
-            .push(signed_transaction);
+        let key = self.key(signer_id, signer_public_key);
+        let priority_fee = tx_priority_fee(&signed_transaction);
+        let group = self.transactions.entry(key).or_insert_with(Vec::new);
+        let old_priority_fee = group_priority_fee(group);
+        group.push(signed_transaction);
+        self.update_priority_key(key, old_priority_fee, old_priority_fee.max(priority_fee));
 
         self.transaction_pool_count_metric.inc();
         self.transaction_pool_size_metric.set(self.total_transaction_size as i64);
@@ -143,6 +161,7 @@ impl TransactionPool {
         }
         for (key, hashes) in grouped_transactions {
             if let Entry::Occupied(mut entry) = self.transactions.entry(key) {
+                let old_priority_fee = group_priority_fee(entry.get());
                 entry.get_mut().retain(|tx| {
                     if !hashes.contains(&tx.get_hash()) {
                         return true;
@@ -155,9 +174,11 @@ impl TransactionPool {
                         .expect("Total transaction size dropped below zero");
                     false
                 });
+                let new_priority_fee = group_priority_fee(entry.get());
                 if entry.get().is_empty() {
                     entry.remove_entry();
                 }
+                self.update_priority_key(key, old_priority_fee, new_priority_fee);
             }
         }
 
@@ -186,23 +207,46 @@ pub struct PoolIteratorWrapper<'a> {
 
     /// Queue of transaction groups. Each group there is sorted by nonce.
     sorted_groups: VecDeque<TransactionGroup>,
+
+    /// Deferred transaction groups, which are not returned by the iterator anymore.
+    deferred_groups: Vec<TransactionGroup>,
 }
 
 impl<'a> PoolIteratorWrapper<'a> {
     pub fn new(pool: &'a mut TransactionPool) -> Self {
-        Self { pool, sorted_groups: Default::default() }
+        Self { pool, sorted_groups: Default::default(), deferred_groups: vec![] }
+    }
+
+    /// Sorts the group taken from the pool by nonce and adds it to the back of the queue.
+    fn push_sorted_group(
+        &mut self,
+        key: PoolKey,
+        mut transactions: Vec<SignedTransaction>,
+    ) -> &mut TransactionGroup {
+        transactions.sort_by_key(|st| Reverse(st.transaction.nonce()));
+        self.sorted_groups.push_back(TransactionGroup {
+            key,
+            transactions,
+            removed_transaction_hashes: vec![],
+            removed_transaction_size: 0,
+            deferred: false,
+        });
+        self.sorted_groups.back_mut().expect("just pushed")
     }
 }
 
 /// The iterator works with the following algorithm:
 /// On next(), the iterator tries to get a transaction group from the pool, sorts transactions in
 /// it, and add it to the back of the sorted groups queue.
+/// Groups containing transactions with a priority fee are taken first, in the order of the
+/// highest priority fee in the group. The remaining groups are taken in the order of their keys.
 /// Remembers the last used key, so it can continue from the next key.
 ///
 /// If the pool is empty, the iterator gets the group from the front of the sorted groups queue.
 ///
 /// If this group is empty (no transactions left inside), then the iterator discards it and
-/// updates `unique_transactions` in the pool. Then gets the next one.
+/// updates `unique_transactions` in the pool. Then gets the next one. If this group was deferred,
+/// the iterator sets it aside until it is dropped. Then gets the next one.
 ///
 /// Once a non-empty group is found, this group is pushed to the back of the sorted groups queue
 /// and the iterator returns a mutable reference to this group.
@@ -210,10 +254,15 @@ impl<'a> PoolIteratorWrapper<'a> {
 /// If the sorted groups queue is empty, the iterator returns None.
 ///
 /// When the iterator is dropped, `unique_transactions` in the pool is updated for every group.
-/// And all non-empty group from the sorted groups queue are inserted back into the pool.
+/// And all non-empty group from the sorted groups queue and the deferred groups are inserted back
+/// into the pool.
 impl<'a> TransactionGroupIterator for PoolIteratorWrapper<'a> {
     fn next(&mut self) -> Option<&mut TransactionGroup> {
-        if !self.pool.transactions.is_empty() {
+        if let Some((_, key)) = self.pool.priority_keys.pop_first() {
+            let transactions =
+                self.pool.transactions.remove(&key).expect("priority key must be in the pool");
+            Some(self.push_sorted_group(key, transactions))
+        } else if !self.pool.transactions.is_empty() {
             let key = *self
                 .pool
                 .transactions
@@ -228,19 +277,13 @@ impl<'a> TransactionGroupIterator for PoolIteratorWrapper<'a> {
                         .expect("we've just checked that the map is not empty")
                 });
             self.pool.last_used_key = key;
-            let mut transactions =
-                self.pool.transactions.remove(&key).expect("just checked existence");
-            transactions.sort_by_key(|st| std::cmp::Reverse(st.transaction.nonce()));
-            self.sorted_groups.push_back(TransactionGroup {
-                key,
-                transactions,
-                removed_transaction_hashes: vec![],
-                removed_transaction_size: 0,
-            });
-            Some(self.sorted_groups.back_mut().expect("just pushed"))
+            let transactions = self.pool.transactions.remove(&key).expect("just checked existence");
+            Some(self.push_sorted_group(key, transactions))
         } else {
             while let Some(sorted_group) = self.sorted_groups.pop_front() {
-                if sorted_group.transactions.is_empty() {
+                if sorted_group.deferred {
+                    self.deferred_groups.push(sorted_group);
+                } else if sorted_group.transactions.is_empty() {
                     for hash in sorted_group.removed_transaction_hashes {
                         self.pool.unique_transactions.remove(&hash);
                     }
@@ -267,11 +310,11 @@ impl<'a> TransactionGroupIterator for PoolIteratorWrapper<'a> {
 }
 
 /// When a pool iterator is dropped, all remaining non empty transaction groups from the sorted
-/// groups queue are inserted back into the pool. And removed transactions hashes from groups are
+/// groups queue and the deferred groups are inserted back into the pool. And removed transactions hashes from groups are
 /// removed from the pool's unique_transactions.
 impl<'a> Drop for PoolIteratorWrapper<'a> {
     fn drop(&mut self) {
-        for group in self.sorted_groups.drain(..) {
+        for group in self.sorted_groups.drain(..).chain(self.deferred_groups.drain(..)) {
             for hash in group.removed_transaction_hashes {
                 self.pool.unique_transactions.remove(&hash);
             }
@@ -284,6 +327,8 @@ impl<'a> Drop for PoolIteratorWrapper<'a> {
                 .expect("Total transaction size dropped below zero");
 
             if !group.transactions.is_empty() {
+                let priority_fee = group_priority_fee(&group.transactions);
+                self.pool.update_priority_key(group.key, 0, priority_fee);
                 self.pool.transactions.insert(group.key, group.transactions);
             }
         }
@@ -293,6 +338,15 @@ impl<'a> Drop for PoolIteratorWrapper<'a> {
     }
 }
 
+fn tx_priority_fee(signed_transaction: &SignedTransaction) -> u64 {
+    signed_transaction.transaction.priority_fee().unwrap_or_default()
+}
+
+/// Returns the highest priority fee of the transactions in the group.
+fn group_priority_fee(transactions: &[SignedTransaction]) -> u64 {
+    transactions.iter().map(tx_priority_fee).max().unwrap_or_default()
+}
+
 /// On creation we transform a list of transactions into a list of singleton transaction groups
 /// that we later can iterate through. This weird structure is motivated by `prepare_transactions`,
 /// where we take first valid transaction from each transaction group.
@@ -310,6 +364,7 @@ impl TransactionGroupIteratorWrapper {
                 transactions: vec![transaction.clone()],
                 removed_transaction_hashes: vec![],
                 removed_transaction_size: 0,
+                deferred: false,
             })
             .collect();
 
@@ -532,6 +587,34 @@ mod tests {
         assert_eq!(nonces, vec![1, 21, 3, 23, 25, 27, 29, 31]);
     }
 
+    /// Defer the group of alice after pulling a transaction from it. The iterator keeps returning
+    /// the group of bob, and the transactions of alice are back in the pool afterwards.
+    #[test]
+    fn test_pool_iterator_defer() {
+        let mut transactions = generate_transactions("alice.near", "alice.near", 1, 5);
+        transactions.extend(generate_transactions("bob.near", "bob.near", 1, 5));
+
+        let (nonces, mut pool) = process_txs_to_nonces(transactions, 0);
+        assert!(nonces.is_empty());
+        let mut signers = vec![];
+        let mut pool_iter = pool.pool_iterator();
+        while let Some(iter) = pool_iter.next() {
+            let tx = iter.next().unwrap();
+            let signer_id = tx.transaction.signer_id().to_string();
+            if signer_id == "alice.near" {
+                iter.defer();
+            }
+            signers.push(signer_id);
+        }
+        drop(pool_iter);
+        assert_eq!(signers.iter().filter(|signer_id| *signer_id == "alice.near").count(), 1);
+        assert_eq!(signers.iter().filter(|signer_id| *signer_id == "bob.near").count(), 5);
+        assert_eq!(pool.len(), 4);
+        let nonces: Vec<u64> =
+            prepare_transactions(&mut pool, 4).iter().map(|tx| tx.transaction.nonce()).collect();
+        assert_eq!(nonces, vec![2, 3, 4, 5]);
+    }
+
     /// Test pool iterator updates unique transactions.
     #[test]
     fn test_pool_iterator_removes_unique() {
@@ -625,4 +708,55 @@ mod tests {
             }
         }
     }
+
+    fn generate_priority_transaction(
+        signer_id: &str,
+        nonce: u64,
+        priority_fee: u64,
+    ) -> SignedTransaction {
+        let signer_id: AccountId = signer_id.parse().unwrap();
+        let signer = InMemorySigner::from_seed(signer_id.clone(), KeyType::ED25519, "seed");
+        SignedTransaction::from_actions_v1(
+            nonce,
+            signer_id,
+            "bob.near".parse().unwrap(),
+            &signer,
+            vec![],
+            CryptoHash::default(),
+            priority_fee,
+        )
+    }
+
+    /// Groups with a higher priority fee are pulled first, the rest follow in key order.
+    #[test]
+    fn test_pool_iterator_priority_fee() {
+        let mut transactions = generate_transactions("alice.near", "alice.near", 1, 2);
+        transactions.push(generate_priority_transaction("carol.near", 1, 5));
+        transactions.push(generate_priority_transaction("dave.near", 1, 10));
+        transactions.push(generate_priority_transaction("dave.near", 2, 0));
+        let mut pool = TransactionPool::new(TEST_SEED, None, "");
+        for tx in transactions {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+        let signers: Vec<_> = prepare_transactions(&mut pool, 2)
+            .iter()
+            .map(|tx| tx.transaction.signer_id().to_string())
+            .collect();
+        assert_eq!(signers, vec!["dave.near", "carol.near"]);
+        // The remaining dave.near transaction has no priority fee.
+        assert!(pool.priority_keys.is_empty());
+        assert_eq!(prepare_transactions(&mut pool, 3).len(), 3);
+        assert_eq!(pool.len(), 0);
+    }
+
+    /// Removing the only prioritized transaction of a group drops the priority of the group.
+    #[test]
+    fn test_remove_priority_transaction() {
+        let mut pool = TransactionPool::new(TEST_SEED, None, "");
+        let tx = generate_priority_transaction("carol.near", 1, 5);
+        assert_eq!(pool.insert_transaction(tx.clone()), InsertTransactionResult::Success);
+        assert_eq!(pool.priority_keys.len(), 1);
+        pool.remove_transactions(&[tx]);
+        assert!(pool.priority_keys.is_empty());
+    }
 }
@@ -22,6 +22,8 @@ pub struct TransactionGroup {
     pub(crate) removed_transaction_hashes: Vec<CryptoHash>,
     /// Total size of transactions that were pulled from the group using `.next()`.
     pub(crate) removed_transaction_size: u64,
+    /// Whether the group was deferred with `.defer()`.
+    pub(crate) deferred: bool,
 }
 
 impl TransactionGroup {
@@ -40,4 +42,10 @@ impl TransactionGroup {
     pub fn peek_next(&self) -> Option<&SignedTransaction> {
         self.transactions.last()
     }
+
+    /// Leaves the remaining transactions of the group in the pool. The pool iterator doesn't
+    /// return the group again.
+    pub fn defer(&mut self) {
+        self.deferred = true;
+    }
 }
@@ -369,6 +369,21 @@ impl From<NearActions> for Vec<crate::models::Operation> {
                     );
                 }
 
+                // Storage sponsorship deposit is held in a sponsorship record of the receiving
+                // account and doesn't show up in its balance.
+                near_primitives::transaction::Action::SponsorStorage(action) => {
+                    let sponsor_amount = crate::models::Amount::from_yoctonear(action.deposit);
+
+                    operations.push(
+                        validated_operations::TransferOperation {
+                            account: sender_account_identifier.clone(),
+                            amount: -sponsor_amount,
+                            predecessor_id: Some(sender_account_identifier.clone()),
+                        }
+                        .into_operation(crate::models::OperationIdentifier::new(&operations)),
+                    );
+                }
+
                 near_primitives::transaction::Action::Stake(action) => {
                     operations.push(
                         validated_operations::StakeOperation {
@@ -380,6 +395,18 @@ impl From<NearActions> for Vec<crate::models::Operation> {
                     );
                 }
 
+                // Validator metadata has no representation in Rosetta operations.
+                near_primitives::transaction::Action::StakeWithMetadata(action) => {
+                    operations.push(
+                        validated_operations::StakeOperation {
+                            account: receiver_account_identifier.clone(),
+                            amount: action.stake.stake,
+                            public_key: (&action.stake.public_key).into(),
+                        }
+                        .into_operation(crate::models::OperationIdentifier::new(&operations)),
+                    );
+                }
+
                 near_primitives::transaction::Action::DeployContract(action) => {
                     let initiate_deploy_contract_operation_id =
                         crate::models::OperationIdentifier::new(&operations);
@@ -402,6 +429,17 @@ impl From<NearActions> for Vec<crate::models::Operation> {
                     );
                 }
 
+                // Global contracts have no representation in Rosetta operations. The tokens burnt
+                // for their storage show up as a balance change of the deployer.
+                near_primitives::transaction::Action::DeployGlobalContract(_)
+                | near_primitives::transaction::Action::UseGlobalContract(_) => {}
+
+                // State migrations attach no deposit, so they don't move any tokens.
+                near_primitives::transaction::Action::MigrateState(_) => {}
+
+                // Native tokens other than NEAR aren't represented in Rosetta operations.
+                near_primitives::transaction::Action::TransferToken(_) => {}
+
                 near_primitives::transaction::Action::FunctionCall(action) => {
                     let attached_amount = crate::models::Amount::from_yoctonear(action.deposit);
 
@@ -36,7 +36,25 @@ pub const DEFAULT_STATE_SYNC_NUM_CONCURRENT_REQUESTS_ON_CATCHUP_EXTERNAL: u32 =
 /// before giving up and downloading it from external storage.
 pub const DEFAULT_EXTERNAL_STORAGE_FALLBACK_THRESHOLD: u64 = 3;
 
+/// How long a class of data is kept by garbage collection.
+#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GCRetention {
+    /// The data is garbage collected together with its block, after
+    /// `gc_num_epochs_to_keep` epochs.
+    #[default]
+    WithBlocks,
+    /// The data is kept for the given number of epochs, which is at least
+    /// `gc_num_epochs_to_keep`.
+    Epochs(u64),
+    /// The data is never garbage collected.
+    Forever,
+}
+
 /// Configuration for garbage collection.
+///
+/// Blocks, state and everything else not covered by a retention setting are
+/// kept for `gc_num_epochs_to_keep` epochs.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
 #[serde(default)]
 pub struct GCConfig {
@@ -54,6 +72,18 @@ pub struct GCConfig {
     /// How often gc should be run
     #[serde(with = "near_time::serde_duration_as_std")]
     pub gc_step_period: Duration,
+
+    /// How long chunks, with their transactions and receipts, are kept.
+    pub chunks_retention: GCRetention,
+
+    /// How long execution outcomes are kept.
+    pub outcomes_retention: GCRetention,
+
+    /// How long block headers are kept. Headers are only garbage collected
+    /// below the epoch of a stored epoch sync proof, which the node keeps
+    /// up to date on its own when they are not kept forever, so that it can
+    /// still serve epoch sync. Not supported on archival nodes.
+    pub headers_retention: GCRetention,
 }
 
 impl Default for GCConfig {
@@ -63,6 +93,164 @@ impl Default for GCConfig {
             gc_fork_clean_step: 100,
             gc_num_epochs_to_keep: DEFAULT_GC_NUM_EPOCHS_TO_KEEP,
             gc_step_period: Duration::seconds(1),
+            chunks_retention: GCRetention::WithBlocks,
+            outcomes_retention: GCRetention::WithBlocks,
+            headers_retention: GCRetention::Forever,
+        }
+    }
+}
+
+/// What to do with a block whose timestamp is further in the future than the
+/// allowed clock skew.
+#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum FutureBlockPolicy {
+    /// The block is rejected. The peer which sent it isn't banned, since its
+    /// clock may be correct and ours wrong.
+    #[default]
+    Reject,
+    /// The block is held until its timestamp is within the allowed clock skew
+    /// and processed then, as long as that is at most `max_future_block_delay`
+    /// away. Blocks further in the future are rejected.
+    Delay,
+}
+
+/// Validation of the timestamps of received blocks.
+///
+/// A node whose clock is behind, e.g. because of a bad NTP sync, sees the
+/// blocks of the others as coming from the future.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct BlockTimestampConfig {
+    /// How far in the future the timestamp of a block may be compared to the
+    /// clock of the node.
+    #[serde(with = "near_time::serde_duration_as_std")]
+    pub max_clock_skew: Duration,
+    /// What to do with blocks further in the future than `max_clock_skew`.
+    pub future_block_policy: FutureBlockPolicy,
+    /// Longest time a block is held with `FutureBlockPolicy::Delay`.
+    #[serde(with = "near_time::serde_duration_as_std")]
+    pub max_future_block_delay: Duration,
+}
+
+impl Default for BlockTimestampConfig {
+    fn default() -> Self {
+        Self {
+            max_clock_skew: Duration::seconds(120),
+            future_block_policy: FutureBlockPolicy::Reject,
+            max_future_block_delay: Duration::seconds(10),
+        }
+    }
+}
+
+/// How the owners of chunk parts get them to the nodes which need them.
+#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum ChunkPartDistributionMode {
+    /// Part owners forward their parts once, the nodes missing parts request
+    /// them from other peers.
+    #[default]
+    Pull,
+    /// Part owners push their parts and push again those which the receiver
+    /// did not acknowledge. The receivers only request parts from other peers
+    /// once the owners stopped pushing.
+    Push,
+}
+
+/// Accounts the owner of a chunk part forwards it to. The part is always
+/// forwarded to the producer of the next chunk of the shard.
+#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum ChunkPartForwardingPolicy {
+    /// Forward to all the block producers tracking the shard.
+    #[default]
+    AllTrackers,
+    /// Forward only to the producers of the next `num_block_producers` blocks
+    /// which track the shard, including those of the next epoch near its
+    /// start. The other trackers request the parts they miss.
+    NextBlockProducers { num_block_producers: u64 },
+}
+
+/// Distribution of chunk parts between validators. All validators of a
+/// network are expected to use the same mode.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ChunkPartDistributionConfig {
+    pub mode: ChunkPartDistributionMode,
+    /// With `ChunkPartDistributionMode::Push`, time after which the parts
+    /// which were not acknowledged are pushed again.
+    #[serde(with = "near_time::serde_duration_as_std")]
+    pub retransmit_period: Duration,
+    /// With `ChunkPartDistributionMode::Push`, how many times the parts which
+    /// were not acknowledged are pushed again.
+    pub max_retransmits: u32,
+    pub forwarding_policy: ChunkPartForwardingPolicy,
+    /// Maximum number of accounts a part is forwarded to, the producer of the
+    /// next chunk coming first. `None` means no limit.
+    pub max_forward_fanout: Option<usize>,
+}
+
+impl Default for ChunkPartDistributionConfig {
+    fn default() -> Self {
+        Self {
+            mode: ChunkPartDistributionMode::Pull,
+            retransmit_period: Duration::milliseconds(200),
+            max_retransmits: 3,
+            forwarding_policy: ChunkPartForwardingPolicy::AllTrackers,
+            max_forward_fanout: None,
+        }
+    }
+}
+
+impl ChunkPartDistributionConfig {
+    pub fn is_push(&self) -> bool {
+        self.mode == ChunkPartDistributionMode::Push
+    }
+
+    /// Time after which the owners of the parts stop pushing them.
+    pub fn push_duration(&self) -> Duration {
+        self.retransmit_period * (self.max_retransmits + 1)
+    }
+}
+
+/// Configuration of shadow validation, where the node validates the chunks of
+/// every block it processes as if it was their chunk validator.
+///
+/// This lets a node which isn't a validator audit the chain: the state
+/// witnesses are produced from the local state of the tracked shards and
+/// validated, and any divergence from the chunks included in the chain is
+/// reported.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ShadowValidationConfig {
+    pub enabled: bool,
+    /// URL receiving a JSON `POST` for each chunk which failed validation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+}
+
+/// Configuration of catchup, i.e. applying the blocks of the current epoch
+/// for the shards tracked in the next epoch after their state was synced.
+///
+/// Catchup runs concurrently with the processing of new blocks, the limits
+/// keep it from delaying the head, e.g. for validators switching shards.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct CatchupConfig {
+    /// Number of threads applying the chunks of the blocks being caught up.
+    /// They are separate from the threads applying the chunks of new blocks.
+    /// 0 means one thread per CPU.
+    pub apply_chunks_threads: usize,
+    /// Maximum number of blocks being caught up at the same time.
+    pub max_scheduled_blocks: usize,
+    /// Maximum number of blocks being caught up at the same time while new
+    /// blocks are being applied.
+    pub max_scheduled_blocks_during_block_processing: usize,
+}
+
+impl Default for CatchupConfig {
+    fn default() -> Self {
+        Self {
+            apply_chunks_threads: 2,
+            max_scheduled_blocks: 4,
+            max_scheduled_blocks_during_block_processing: 1,
         }
     }
 }
@@ -71,6 +259,18 @@ impl GCConfig {
     pub fn gc_num_epochs_to_keep(&self) -> u64 {
         max(MIN_GC_NUM_EPOCHS_TO_KEEP, self.gc_num_epochs_to_keep)
     }
+
+    /// Number of epochs for which data with the given retention is kept
+    /// beyond the blocks, `None` if it is kept forever.
+    pub fn extra_epochs_to_keep(&self, retention: GCRetention) -> Option<u64> {
+        match retention {
+            GCRetention::WithBlocks => Some(0),
+            GCRetention::Epochs(num_epochs) => {
+                Some(num_epochs.saturating_sub(self.gc_num_epochs_to_keep()))
+            }
+            GCRetention::Forever => None,
+        }
+    }
 }
 
 fn default_num_concurrent_requests() -> u32 {
@@ -139,6 +339,25 @@ pub struct DumpConfig {
     pub credentials_file: Option<PathBuf>,
 }
 
+/// Configures the export of the execution of every applied block, e.g. for
+/// provers or audit tooling.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct ExecutionArtifactsConfig {
+    /// Where to write the artifacts.
+    pub location: ExternalStorageLocation,
+    /// Location of a json file with credentials allowing write access to the bucket.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials_file: Option<PathBuf>,
+    /// Maximum number of artifacts waiting to be written. Artifacts are
+    /// dropped when the storage falls further behind.
+    #[serde(default = "default_execution_artifacts_queue_size")]
+    pub queue_size: usize,
+}
+
+fn default_execution_artifacts_queue_size() -> usize {
+    32
+}
+
 /// Configures how to fetch state parts during state sync.
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub enum SyncConfig {
@@ -337,6 +556,10 @@ pub fn default_header_sync_expected_height_per_second() -> u64 {
     10
 }
 
+pub fn default_header_sync_max_prefetched_ranges() -> u64 {
+    4
+}
+
 pub fn default_sync_check_period() -> Duration {
     Duration::seconds(10)
 }
@@ -365,6 +588,10 @@ pub fn default_view_client_threads() -> usize {
     4
 }
 
+pub fn default_apply_chunks_threads() -> usize {
+    8
+}
+
 pub fn default_log_summary_period() -> Duration {
     Duration::seconds(10)
 }
@@ -444,6 +671,9 @@ pub struct ClientConfig {
     pub max_block_production_delay: Duration,
     /// Maximum duration before skipping given height.
     pub max_block_wait_delay: Duration,
+    /// How much longer doomslug waits for approvals at each height skipped
+    /// since the last block, until `max_block_wait_delay` is reached.
+    pub doomslug_delay_step: Duration,
     /// Skip waiting for sync (for testing or single node testnet).
     pub skip_sync_wait: bool,
     /// How often to check that we are not out of sync.
@@ -462,6 +692,9 @@ pub struct ClientConfig {
     pub header_sync_stall_ban_timeout: Duration,
     /// Expected increase of header head height per second during header sync
     pub header_sync_expected_height_per_second: u64,
+    /// Maximum number of ranges of headers ahead of the header head requested
+    /// from other peers in parallel during header sync. 0 disables it.
+    pub header_sync_max_prefetched_ranges: u64,
     /// How long to wait for a response from centralized state sync
     pub state_sync_external_timeout: Duration,
     /// How long to wait for a response from p2p state sync
@@ -527,6 +760,26 @@ pub struct ClientConfig {
     pub enable_statistics_export: bool,
     /// Number of threads to execute background migration work in client.
     pub client_background_migration_threads: usize,
+    /// Maximum number of shards of a block applied in parallel. Each shard
+    /// being applied holds its state changes in memory, so this also bounds
+    /// the memory used by block processing. 0 means one thread per CPU.
+    pub apply_chunks_threads: usize,
+    /// Limits of the resources used by catchup.
+    pub catchup: CatchupConfig,
+    /// Validation of the timestamps of received blocks.
+    pub block_timestamp: BlockTimestampConfig,
+    /// Validation of the chunks of every processed block.
+    pub shadow_validation: ShadowValidationConfig,
+    /// Distribution of chunk parts between validators.
+    pub chunk_part_distribution: ChunkPartDistributionConfig,
+    /// Export of the execution of every applied block. `None` disables it.
+    pub execution_artifacts: Option<ExecutionArtifactsConfig>,
+    /// Whether to produce blocks on top of a block whose chunks are still being
+    /// applied, when the next block can't start a new epoch.
+    pub produce_optimistic_blocks: bool,
+    /// Whether to read the accounts and access keys used by the transactions
+    /// of a received chunk before applying it.
+    pub prefetch_chunk_state: bool,
     /// Whether to use the State Sync mechanism.
     /// If disabled, the node will do Block Sync instead of State Sync.
     pub state_sync_enabled: bool,
@@ -599,6 +852,7 @@ impl ClientConfig {
             min_block_production_delay: Duration::milliseconds(min_block_prod_time as i64),
             max_block_production_delay: Duration::milliseconds(max_block_prod_time as i64),
             max_block_wait_delay: Duration::milliseconds(3 * min_block_prod_time as i64),
+            doomslug_delay_step: Duration::milliseconds(max_block_prod_time as i64 / 10),
             skip_sync_wait,
             sync_check_period: Duration::milliseconds(100),
             sync_step_period: Duration::milliseconds(10),
@@ -612,6 +866,7 @@ impl ClientConfig {
             state_sync_retry_backoff: Duration::seconds(TEST_STATE_SYNC_TIMEOUT),
             state_sync_external_backoff: Duration::seconds(TEST_STATE_SYNC_TIMEOUT),
             header_sync_expected_height_per_second: 1,
+            header_sync_max_prefetched_ranges: default_header_sync_max_prefetched_ranges(),
             min_num_peers: 1,
             log_summary_period: Duration::seconds(10),
             produce_empty_blocks: true,
@@ -640,6 +895,14 @@ impl ClientConfig {
             max_gas_burnt_view: None,
             enable_statistics_export: true,
             client_background_migration_threads: 1,
+            apply_chunks_threads: 2,
+            catchup: CatchupConfig::default(),
+            block_timestamp: BlockTimestampConfig::default(),
+            shadow_validation: ShadowValidationConfig::default(),
+            chunk_part_distribution: ChunkPartDistributionConfig::default(),
+            execution_artifacts: None,
+            produce_optimistic_blocks: false,
+            prefetch_chunk_state: false,
             state_sync_enabled,
             state_sync: StateSyncConfig::default(),
             epoch_sync: EpochSyncConfig::default(),
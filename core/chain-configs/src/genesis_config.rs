@@ -240,6 +240,60 @@ pub struct GenesisConfig {
     /// if algorithm is able to choose assignment with better balance of
     /// number of chunk producers for shards.
     pub chunk_producer_assignment_changes_limit: NumSeats,
+    /// Protocol upgrades for which block producers start voting at fixed
+    /// heights instead of when they are ready for them. Meant for private
+    /// chains and testing upgrades on localnet.
+    ///
+    /// The new version is not activated at the scheduled height itself: the
+    /// protocol version only changes at epoch boundaries, so it's adopted
+    /// through the usual voting. See [`ScheduledProtocolUpgrade`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protocol_upgrade_heights: Vec<ScheduledProtocolUpgrade>,
+    /// Block production delays used by all the nodes of the chain instead of
+    /// the ones in their config. Only allowed on test and local networks,
+    /// where it lets the chain use sub-second block times.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_production_delays: Option<BlockProductionDelays>,
+}
+
+/// Doomslug timers of a test or local network, in milliseconds. See the
+/// `consensus` section of config.json for their meaning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BlockProductionDelays {
+    pub min_block_production_delay_ms: u64,
+    pub max_block_production_delay_ms: u64,
+    pub max_block_wait_delay_ms: u64,
+    /// Defaults to a tenth of `max_block_production_delay_ms`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doomslug_delay_step_ms: Option<u64>,
+}
+
+/// Protocol upgrade whose voting starts at a fixed height.
+///
+/// Starting at `height`, block producers vote for `protocol_version` and
+/// blocks voting for an older version are rejected. The votes are counted
+/// at the end of each epoch as usual, so if `height` is in epoch `T`, the
+/// chain runs `protocol_version` from epoch `T + 2` on. If the block
+/// producers holding `protocol_upgrade_stake_threshold` of the stake don't
+/// all produce a block between `height` and the end of `T`, e.g. because
+/// `height` is close to the end of the epoch, the upgrade slips by an epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ScheduledProtocolUpgrade {
+    pub height: BlockHeight,
+    pub protocol_version: ProtocolVersion,
+}
+
+/// Returns the protocol version which blocks at `height` must vote for at
+/// least, according to `schedule`.
+pub fn get_scheduled_protocol_version(
+    schedule: &[ScheduledProtocolUpgrade],
+    height: BlockHeight,
+) -> Option<ProtocolVersion> {
+    schedule
+        .iter()
+        .filter(|upgrade| upgrade.height <= height)
+        .map(|upgrade| upgrade.protocol_version)
+        .max()
 }
 
 impl GenesisConfig {
@@ -277,6 +331,7 @@ impl From<&GenesisConfig> for EpochConfig {
             chunk_producer_assignment_changes_limit: config.chunk_producer_assignment_changes_limit,
             shuffle_shard_assignment_for_chunk_producers: config
                 .shuffle_shard_assignment_for_chunk_producers,
+            slashing_fractions: Default::default(),
             validator_max_kickout_stake_perc: config.max_kickout_stake_perc,
         }
     }
@@ -947,7 +1002,9 @@ pub fn get_initial_supply(records: &[StateRecord]) -> Balance {
 
 #[cfg(test)]
 mod test {
-    use crate::genesis_config::RecordsProcessor;
+    use crate::genesis_config::{
+        get_scheduled_protocol_version, RecordsProcessor, ScheduledProtocolUpgrade,
+    };
     use crate::{Genesis, GenesisValidationMode};
     use near_primitives::state_record::StateRecord;
     use serde::Deserializer;
@@ -1305,4 +1362,17 @@ mod test {
         let _genesis =
             serde_json::from_str::<Genesis>(&genesis_str).expect("Failed to deserialize Genesis");
     }
+
+    #[test]
+    fn test_scheduled_protocol_version() {
+        let schedule = [
+            ScheduledProtocolUpgrade { height: 10, protocol_version: 70 },
+            ScheduledProtocolUpgrade { height: 20, protocol_version: 72 },
+        ];
+        assert_eq!(get_scheduled_protocol_version(&schedule, 9), None);
+        assert_eq!(get_scheduled_protocol_version(&schedule, 10), Some(70));
+        assert_eq!(get_scheduled_protocol_version(&schedule, 19), Some(70));
+        assert_eq!(get_scheduled_protocol_version(&schedule, 25), Some(72));
+        assert_eq!(get_scheduled_protocol_version(&[], 25), None);
+    }
 }
@@ -1,8 +1,9 @@
-use crate::genesis_config::{Genesis, GenesisConfig, GenesisContents};
+use crate::genesis_config::{Genesis, GenesisConfig, GenesisContents, ScheduledProtocolUpgrade};
 use near_config_utils::{ValidationError, ValidationErrors};
 use near_crypto::key_conversion::is_valid_staking_key;
 use near_primitives::state_record::StateRecord;
 use near_primitives::types::AccountId;
+use near_primitives::version::PROTOCOL_VERSION;
 use num_rational::Rational32;
 use std::collections::{HashMap, HashSet};
 
@@ -180,6 +181,46 @@ impl<'a> GenesisValidator<'a> {
             let error_message = format!("Epoch Length must be greater than 0");
             self.validation_errors.push_genesis_semantics_error(error_message)
         }
+
+        let mut prev_upgrade: Option<&ScheduledProtocolUpgrade> = None;
+        for upgrade in &self.genesis_config.protocol_upgrade_heights {
+            if upgrade.protocol_version <= self.genesis_config.protocol_version
+                || upgrade.protocol_version > PROTOCOL_VERSION
+            {
+                let error_message = format!(
+                    "Scheduled protocol version {} must be greater than the genesis protocol version {} and at most the supported protocol version {}",
+                    upgrade.protocol_version, self.genesis_config.protocol_version, PROTOCOL_VERSION
+                );
+                self.validation_errors.push_genesis_semantics_error(error_message)
+            }
+            if let Some(prev_upgrade) = prev_upgrade {
+                if upgrade.height <= prev_upgrade.height
+                    || upgrade.protocol_version <= prev_upgrade.protocol_version
+                {
+                    let error_message = "Protocol upgrade heights must be ordered by increasing height and protocol version".to_string();
+                    self.validation_errors.push_genesis_semantics_error(error_message)
+                }
+            }
+            prev_upgrade = Some(upgrade);
+        }
+
+        if let Some(delays) = &self.genesis_config.block_production_delays {
+            let chain_id = &self.genesis_config.chain_id;
+            if chain_id == near_primitives::chains::MAINNET
+                || chain_id == near_primitives::chains::TESTNET
+            {
+                let error_message = format!("Block production delays can't be set in the genesis of {chain_id}, only of test and local networks");
+                self.validation_errors.push_genesis_semantics_error(error_message)
+            }
+            if delays.min_block_production_delay_ms == 0
+                || delays.min_block_production_delay_ms > delays.max_block_production_delay_ms
+                || delays.max_block_production_delay_ms > delays.max_block_wait_delay_ms
+                || delays.doomslug_delay_step_ms == Some(0)
+            {
+                let error_message = format!("Block production delays must be positive with min_block_production_delay_ms <= max_block_production_delay_ms <= max_block_wait_delay_ms, got {delays:?}");
+                self.validation_errors.push_genesis_semantics_error(error_message)
+            }
+        }
     }
 
     fn result_with_full_error(&self) -> Result<(), ValidationError> {
@@ -196,11 +237,11 @@ impl<'a> GenesisValidator<'a> {
 mod test {
     use super::*;
 
+    use crate::genesis_config::BlockProductionDelays;
     use crate::GenesisRecords;
     use near_crypto::{KeyType, PublicKey};
     use near_primitives::account::{AccessKey, Account};
     use near_primitives::types::AccountInfo;
-    use near_primitives::version::PROTOCOL_VERSION;
 
     const VALID_ED25519_RISTRETTO_KEY: &str = "ed25519:KuTCtARNzxZQ3YvXDeLjx83FDqxv2SdQTSbiq876zR7";
 
@@ -244,6 +285,56 @@ mod test {
         validate_genesis(genesis).unwrap();
     }
 
+    #[test]
+    #[should_panic(expected = "Block production delays can't be set in the genesis of mainnet")]
+    fn test_block_production_delays_on_mainnet() {
+        let mut config = GenesisConfig::default();
+        config.epoch_length = 42;
+        config.total_supply = 110;
+        config.chain_id = near_primitives::chains::MAINNET.to_string();
+        config.block_production_delays = Some(BlockProductionDelays {
+            min_block_production_delay_ms: 300,
+            max_block_production_delay_ms: 600,
+            max_block_wait_delay_ms: 1800,
+            doomslug_delay_step_ms: None,
+        });
+        config.validators = vec![AccountInfo {
+            account_id: "test".parse().unwrap(),
+            public_key: VALID_ED25519_RISTRETTO_KEY.parse().unwrap(),
+            amount: 10,
+        }];
+        let records = GenesisRecords(vec![StateRecord::Account {
+            account_id: "test".parse().unwrap(),
+            account: create_account(),
+        }]);
+        let genesis = &Genesis::new(config, records).unwrap();
+        validate_genesis(genesis).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Protocol upgrade heights must be ordered")]
+    fn test_protocol_upgrade_heights_not_ordered() {
+        let mut config = GenesisConfig::default();
+        config.epoch_length = 42;
+        config.total_supply = 110;
+        config.protocol_version = PROTOCOL_VERSION - 2;
+        config.protocol_upgrade_heights = vec![
+            ScheduledProtocolUpgrade { height: 10, protocol_version: PROTOCOL_VERSION - 1 },
+            ScheduledProtocolUpgrade { height: 5, protocol_version: PROTOCOL_VERSION },
+        ];
+        config.validators = vec![AccountInfo {
+            account_id: "test".parse().unwrap(),
+            public_key: VALID_ED25519_RISTRETTO_KEY.parse().unwrap(),
+            amount: 10,
+        }];
+        let records = GenesisRecords(vec![StateRecord::Account {
+            account_id: "test".parse().unwrap(),
+            account: create_account(),
+        }]);
+        let genesis = &Genesis::new(config, records).unwrap();
+        validate_genesis(genesis).unwrap();
+    }
+
     #[test]
     #[should_panic(expected = "validator staking key is not valid")]
     fn test_invalid_staking_key() {
@@ -239,6 +239,7 @@ impl TestEpochConfigBuilder {
             chunk_producer_assignment_changes_limit: self.chunk_producer_assignment_changes_limit,
             shuffle_shard_assignment_for_chunk_producers: self
                 .shuffle_shard_assignment_for_chunk_producers,
+            slashing_fractions: Default::default(),
             num_block_producer_seats_per_shard: self.num_block_producer_seats_per_shard,
             num_chunk_only_producer_seats: self.num_chunk_only_producer_seats,
         };
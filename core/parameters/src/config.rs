@@ -197,6 +197,21 @@ pub struct CongestionControlConfig {
     /// accepting new transactions with the receiver set to the congested shard.
     pub reject_tx_congestion_threshold: f64,
 
+    /// The share of the gas a chunk spends on converting new transactions to
+    /// receipts that transactions signed by a single account can use.
+    ///
+    /// Once an account used its share, its remaining transactions stay in the
+    /// transaction pool until a later chunk, so that a single account cannot
+    /// monopolize the capacity of a shard.
+    pub max_account_tx_gas_share: f64,
+
+    /// The share of the size limit of the transactions in a chunk that
+    /// transactions signed by a single account can use.
+    ///
+    /// Transactions bigger than this share of the combined transactions size
+    /// limit can never be included and are rejected.
+    pub max_account_tx_size_share: f64,
+
     /// The standard size limit for outgoing receipts aimed at a single shard.
     /// This limit is pretty small to keep the size of source_receipt_proofs under control.
     /// It limits the total sum of outgoing receipts, not individual receipts.
@@ -215,6 +230,20 @@ pub struct CongestionControlConfig {
 impl Eq for CongestionControlConfig {}
 
 impl CongestionControlConfig {
+    /// Gas that transactions signed by a single account can burn in a chunk
+    /// where new transactions can burn `tx_gas_limit` in total.
+    pub fn account_tx_gas_quota(&self, tx_gas_limit: Gas) -> Gas {
+        (tx_gas_limit as f64 * self.max_account_tx_gas_share) as Gas
+    }
+
+    /// Total size of the transactions signed by a single account in a chunk
+    /// where transactions can take `tx_size_limit` bytes in total. It is never
+    /// below `max_transaction_size`, so that every valid transaction can be
+    /// included in a chunk on its own.
+    pub fn account_tx_size_quota(&self, tx_size_limit: u64, max_transaction_size: u64) -> u64 {
+        ((tx_size_limit as f64 * self.max_account_tx_size_share) as u64).max(max_transaction_size)
+    }
+
     /// Creates a config where congestion control is disabled. This config can
     /// be used for tests. It can be useful e.g. in tests with missing chunks
     /// where we still want to process all transactions.
@@ -231,6 +260,8 @@ impl CongestionControlConfig {
             max_tx_gas: max_value,
             min_tx_gas: max_value,
             reject_tx_congestion_threshold: 2.0,
+            max_account_tx_gas_share: 1.0,
+            max_account_tx_size_share: 1.0,
             outgoing_receipts_usual_size_limit: max_value,
             outgoing_receipts_big_size_limit: max_value,
         }
@@ -52,6 +52,30 @@ static CONFIG_DIFFS: &[(ProtocolVersion, &str)] = &[
     // Fix wasm_yield_resume_byte and relax congestion control.
     (73, include_config!("73.yaml")),
     (129, include_config!("129.yaml")),
+    // Expire receipts created from transactions.
+    (152, include_config!("152.yaml")),
+    // Enable the `p256_verify` host function.
+    (159, include_config!("159.yaml")),
+    // Allow contracts to choose the timeout of each yielded promise.
+    (160, include_config!("160.yaml")),
+    // Parse the multi-memory WebAssembly proposal.
+    (162, include_config!("162.yaml")),
+    // Enable the `block_header` host function.
+    (164, include_config!("164.yaml")),
+    // Enable the `storage_ns_*` host functions.
+    (165, include_config!("165.yaml")),
+    // Enable the `view_call` host function.
+    (167, include_config!("167.yaml")),
+    // Limits of the static analysis of contracts.
+    (168, include_config!("168.yaml")),
+    // Execute well-known contracts with their native implementation.
+    (170, include_config!("170.yaml")),
+    // Limit the share of a chunk's transactions signed by a single account.
+    (171, include_config!("171.yaml")),
+    // Enable the `random_seed_with_domain` host function.
+    (172, include_config!("172.yaml")),
+    // Burn part of the unused gas of receipts instead of refunding it.
+    (173, include_config!("173.yaml")),
 ];
 
 /// Testnet parameters for versions <= 29, which (incorrectly) differed from mainnet parameters
@@ -176,6 +200,16 @@ impl RuntimeConfigStore {
         }
     }
 
+    /// Replaces the limits of the contract sandbox set in `limits` for all
+    /// protocol versions. Only meant for sandbox and test networks.
+    pub fn with_limit_config_override(mut self, limits: &vm::LimitConfigOverride) -> Self {
+        for config in self.store.values_mut() {
+            let config = Arc::make_mut(config);
+            Arc::make_mut(&mut config.wasm_config).limit_config.apply_override(limits);
+        }
+        self
+    }
+
     /// Constructs test store.
     pub fn with_one_config(runtime_config: RuntimeConfig) -> Self {
         Self { store: BTreeMap::from_iter([(0, Arc::new(runtime_config))].iter().cloned()) }
@@ -382,6 +416,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_limit_config_override() {
+        let limits = vm::LimitConfigOverride {
+            max_stack_height: Some(1_000),
+            max_memory_pages: Some(4_096),
+            ..Default::default()
+        };
+        let base_store = RuntimeConfigStore::new(None);
+        let store = RuntimeConfigStore::new(None).with_limit_config_override(&limits);
+        for protocol_version in [0, LowerStorageKeyLimit.protocol_version(), PROTOCOL_VERSION] {
+            let base_limits = &base_store.get_config(protocol_version).wasm_config.limit_config;
+            let mut expected = base_limits.clone();
+            expected.max_stack_height = 1_000;
+            expected.max_memory_pages = 4_096;
+            assert_eq!(store.get_config(protocol_version).wasm_config.limit_config, expected);
+        }
+    }
+
     /// Use snapshot testing to check that the JSON representation of the configurations of each version is unchanged.
     /// If tests fail after an intended change, follow the steps below to update the config files:
     /// 1) Run the following to run tests with cargo insta so it generates all the file differences:
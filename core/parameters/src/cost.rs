@@ -151,6 +151,11 @@ impl ExtCostsConfig {
             ExtCosts::bls12381_p1_decompress_element => SAFETY_MULTIPLIER * 27_000_000_000,
             ExtCosts::bls12381_p2_decompress_base => SAFETY_MULTIPLIER * 500_000_000,
             ExtCosts::bls12381_p2_decompress_element => SAFETY_MULTIPLIER * 55_000_000_000,
+            ExtCosts::p256_verify_base => SAFETY_MULTIPLIER * 210_000_000_000,
+            ExtCosts::p256_verify_byte => SAFETY_MULTIPLIER * 3_000_000,
+            ExtCosts::block_header_base => SAFETY_MULTIPLIER * 3_000_000_000,
+            ExtCosts::storage_ns_base => SAFETY_MULTIPLIER * 21_398_912_000,
+            ExtCosts::view_call_base => SAFETY_MULTIPLIER * 50_000_000_000,
             // TODO(yield/resume): replicate fees here after estimation
             ExtCosts::yield_create_base => 300_000_000_000_000,
             ExtCosts::yield_create_byte => 300_000_000_000_000,
@@ -272,6 +277,11 @@ pub enum ExtCosts {
     bls12381_p2_decompress_element = 82,
     storage_large_read_overhead_base = 83,
     storage_large_read_overhead_byte = 84,
+    p256_verify_base = 85,
+    p256_verify_byte = 86,
+    block_header_base = 87,
+    storage_ns_base = 88,
+    view_call_base = 89,
 }
 
 // Type of an action, used in fees logic.
@@ -307,6 +317,9 @@ pub enum ActionCosts {
     new_data_receipt_base = 13,
     new_data_receipt_byte = 14,
     delegate = 15,
+    sponsor_storage = 16,
+    use_global_contract_base = 17,
+    use_global_contract_byte = 18,
 }
 
 impl ExtCosts {
@@ -409,6 +422,11 @@ impl ExtCosts {
             ExtCosts::bls12381_p1_decompress_element => Parameter::WasmBls12381P1DecompressElement,
             ExtCosts::bls12381_p2_decompress_base => Parameter::WasmBls12381P2DecompressBase,
             ExtCosts::bls12381_p2_decompress_element => Parameter::WasmBls12381P2DecompressElement,
+            ExtCosts::p256_verify_base => Parameter::WasmP256VerifyBase,
+            ExtCosts::p256_verify_byte => Parameter::WasmP256VerifyByte,
+            ExtCosts::block_header_base => Parameter::WasmBlockHeaderBase,
+            ExtCosts::storage_ns_base => Parameter::WasmStorageNsBase,
+            ExtCosts::view_call_base => Parameter::WasmViewCallBase,
         }
     }
 }
@@ -426,6 +444,14 @@ pub struct RuntimeFeesConfig {
 
     /// Pessimistic gas price inflation ratio.
     pub pessimistic_gas_price_inflation_ratio: Rational32,
+
+    /// Fraction of the unused gas of a receipt that is burnt instead of
+    /// refunded to the signer.
+    pub gas_refund_penalty: Rational32,
+
+    /// Minimum amount of the unused gas of a receipt that is burnt instead of
+    /// refunded to the signer, if there is any unused gas.
+    pub min_gas_refund_penalty: Gas,
 }
 
 /// Describes cost of storage per block
@@ -451,6 +477,8 @@ impl RuntimeFeesConfig {
             storage_usage_config: StorageUsageConfig::test(),
             burnt_gas_reward: Rational32::new(3, 10),
             pessimistic_gas_price_inflation_ratio: Rational32::new(103, 100),
+            gas_refund_penalty: Rational32::from_integer(0),
+            min_gas_refund_penalty: 0,
             action_fees: enum_map::enum_map! {
                 ActionCosts::create_account => Fee {
                     send_sir: 3_850_000_000_000,
@@ -532,6 +560,21 @@ impl RuntimeFeesConfig {
                     send_not_sir: 200_000_000_000,
                     execution: 200_000_000_000,
                 },
+                ActionCosts::sponsor_storage => Fee {
+                    send_sir: 115123062500,
+                    send_not_sir: 115123062500,
+                    execution: 115123062500,
+                },
+                ActionCosts::use_global_contract_base => Fee {
+                    send_sir: 184765750000,
+                    send_not_sir: 184765750000,
+                    execution: 184765750000,
+                },
+                ActionCosts::use_global_contract_byte => Fee {
+                    send_sir: 6812999,
+                    send_not_sir: 6812999,
+                    execution: 6812999,
+                },
             },
         }
     }
@@ -544,9 +587,19 @@ impl RuntimeFeesConfig {
             storage_usage_config: StorageUsageConfig::free(),
             burnt_gas_reward: Rational32::from_integer(0),
             pessimistic_gas_price_inflation_ratio: Rational32::from_integer(0),
+            gas_refund_penalty: Rational32::from_integer(0),
+            min_gas_refund_penalty: 0,
         }
     }
 
+    /// The part of `gas_refund`, the unused gas of a receipt, that is burnt
+    /// instead of refunded to the signer.
+    pub fn gas_refund_penalty(&self, gas_refund: Gas) -> Gas {
+        let penalty = (gas_refund as u128 * *self.gas_refund_penalty.numer() as u128
+            / *self.gas_refund_penalty.denom() as u128) as Gas;
+        penalty.max(self.min_gas_refund_penalty).min(gas_refund)
+    }
+
     /// The minimum amount of gas required to create and execute a new receipt with a function call
     /// action.
     /// This amount is used to determine how many receipts can be created, send and executed for
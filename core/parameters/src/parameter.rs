@@ -19,6 +19,8 @@ pub enum Parameter {
     // Gas economics config
     BurntGasReward,
     PessimisticGasPriceInflation,
+    GasRefundPenalty,
+    MinGasRefundPenalty,
 
     /// Stateless validation config
     /// Size limit for storage proof generated while executing receipts in a chunk.
@@ -70,6 +72,9 @@ pub enum Parameter {
     ActionAddFunctionCallKeyPerByte,
     ActionDeleteKey,
     ActionDelegate,
+    ActionSponsorStorage,
+    ActionUseGlobalContract,
+    ActionUseGlobalContractPerIdentifierByte,
 
     // Smart contract dynamic gas costs
     WasmRegularOpCost,
@@ -160,6 +165,11 @@ pub enum Parameter {
     WasmBls12381P1DecompressElement,
     WasmBls12381P2DecompressBase,
     WasmBls12381P2DecompressElement,
+    WasmP256VerifyBase,
+    WasmP256VerifyByte,
+    WasmBlockHeaderBase,
+    WasmStorageNsBase,
+    WasmViewCallBase,
 
     // Smart contract limits
     MaxGasBurnt,
@@ -168,6 +178,7 @@ pub enum Parameter {
     ContractPrepareVersion,
     InitialMemoryPages,
     MaxMemoryPages,
+    MaxMemoriesPerContract,
     RegistersMemoryLimit,
     MaxRegisterSize,
     MaxNumberRegisters,
@@ -184,14 +195,25 @@ pub enum Parameter {
     MaxReceiptSize,
     MaxLengthStorageKey,
     MaxLengthStorageValue,
+    MaxLengthStorageNamespace,
     MaxPromisesPerFunctionCallAction,
     MaxNumberInputDataDependencies,
+    MaxViewCallDepth,
     MaxFunctionsNumberPerContract,
     Wasmer2StackLimit,
+    WasmtimeStackLimit,
     MaxLocalsPerContract,
+    MaxLocalsPerFunction,
+    MaxTableElements,
+    MaxBlockNestingDepth,
     AccountIdValidityRulesVersion,
     YieldTimeoutLengthInBlocks,
     MaxYieldPayloadSize,
+    MaxYieldTimeoutLengthInBlocks,
+    MaxDelegateActionNestingDepth,
+    MaxMigrationGas,
+    MigrationGracePeriodLengthInBlocks,
+    ReceiptTtlInBlocks,
 
     // Contract runtime features
     #[strum(serialize = "disable_9393_fix")]
@@ -207,6 +229,13 @@ pub enum Parameter {
     EthImplicitAccounts,
     YieldResume,
     DiscardCustomSections,
+    P256Verify,
+    YieldTimeoutPerCall,
+    BlockHeader,
+    NamespacedStorage,
+    ViewCall,
+    PrecompiledContracts,
+    RandomSeedWithDomain,
 
     // Congestion Control
     MaxCongestionIncomingGas,
@@ -220,6 +249,8 @@ pub enum Parameter {
     MaxTxGas,
     MinTxGas,
     RejectTxCongestionThreshold,
+    MaxAccountTxGasShare,
+    MaxAccountTxSizeShare,
 
     // Use the StateStoredReceipt structure when storing receipts in State.
     UseStateStoredReceipt,
@@ -255,6 +286,9 @@ pub enum FeeParameter {
     ActionAddFunctionCallKeyPerByte,
     ActionDeleteKey,
     ActionDelegate,
+    ActionSponsorStorage,
+    ActionUseGlobalContract,
+    ActionUseGlobalContractPerIdentifierByte,
 }
 
 impl Parameter {
@@ -267,6 +301,7 @@ impl Parameter {
             Parameter::ContractPrepareVersion,
             Parameter::InitialMemoryPages,
             Parameter::MaxMemoryPages,
+            Parameter::MaxMemoriesPerContract,
             Parameter::RegistersMemoryLimit,
             Parameter::MaxRegisterSize,
             Parameter::MaxNumberRegisters,
@@ -283,14 +318,25 @@ impl Parameter {
             Parameter::MaxReceiptSize,
             Parameter::MaxLengthStorageKey,
             Parameter::MaxLengthStorageValue,
+            Parameter::MaxLengthStorageNamespace,
             Parameter::MaxPromisesPerFunctionCallAction,
             Parameter::MaxNumberInputDataDependencies,
+            Parameter::MaxViewCallDepth,
             Parameter::MaxFunctionsNumberPerContract,
             Parameter::Wasmer2StackLimit,
+            Parameter::WasmtimeStackLimit,
             Parameter::MaxLocalsPerContract,
+            Parameter::MaxLocalsPerFunction,
+            Parameter::MaxTableElements,
+            Parameter::MaxBlockNestingDepth,
             Parameter::AccountIdValidityRulesVersion,
             Parameter::YieldTimeoutLengthInBlocks,
             Parameter::MaxYieldPayloadSize,
+            Parameter::MaxYieldTimeoutLengthInBlocks,
+            Parameter::MaxDelegateActionNestingDepth,
+            Parameter::MaxMigrationGas,
+            Parameter::MigrationGracePeriodLengthInBlocks,
+            Parameter::ReceiptTtlInBlocks,
             Parameter::PerReceiptStorageProofSizeLimit,
         ]
         .iter()
@@ -315,6 +361,9 @@ impl From<ActionCosts> for FeeParameter {
             ActionCosts::add_function_call_key_base => Self::ActionAddFunctionCallKey,
             ActionCosts::add_function_call_key_byte => Self::ActionAddFunctionCallKeyPerByte,
             ActionCosts::delete_key => Self::ActionDeleteKey,
+            ActionCosts::sponsor_storage => Self::ActionSponsorStorage,
+            ActionCosts::use_global_contract_base => Self::ActionUseGlobalContract,
+            ActionCosts::use_global_contract_byte => Self::ActionUseGlobalContractPerIdentifierByte,
             ActionCosts::new_action_receipt => Self::ActionReceiptCreation,
             ActionCosts::new_data_receipt_base => Self::DataReceiptCreationBase,
             ActionCosts::new_data_receipt_byte => Self::DataReceiptCreationPerByte,
@@ -298,6 +298,8 @@ impl TryFrom<&ParameterTable> for RuntimeConfig {
                 burnt_gas_reward: params.get(Parameter::BurntGasReward)?,
                 pessimistic_gas_price_inflation_ratio: params
                     .get(Parameter::PessimisticGasPriceInflation)?,
+                gas_refund_penalty: params.get(Parameter::GasRefundPenalty)?,
+                min_gas_refund_penalty: params.get(Parameter::MinGasRefundPenalty)?,
                 storage_usage_config: StorageUsageConfig {
                     storage_amount_per_byte: params.get(Parameter::StorageAmountPerByte)?,
                     num_bytes_account: params.get(Parameter::StorageNumBytesAccount)?,
@@ -325,10 +327,17 @@ impl TryFrom<&ParameterTable> for RuntimeConfig {
                 implicit_account_creation: params.get(Parameter::ImplicitAccountCreation)?,
                 math_extension: params.get(Parameter::MathExtension)?,
                 ed25519_verify: params.get(Parameter::Ed25519Verify)?,
+                p256_verify: params.get(Parameter::P256Verify)?,
                 alt_bn128: params.get(Parameter::AltBn128)?,
                 function_call_weight: params.get(Parameter::FunctionCallWeight)?,
                 eth_implicit_accounts: params.get(Parameter::EthImplicitAccounts)?,
                 yield_resume_host_functions: params.get(Parameter::YieldResume)?,
+                yield_timeout_per_call: params.get(Parameter::YieldTimeoutPerCall)?,
+                block_header: params.get(Parameter::BlockHeader)?,
+                namespaced_storage: params.get(Parameter::NamespacedStorage)?,
+                view_call: params.get(Parameter::ViewCall)?,
+                precompiled_contracts: params.get(Parameter::PrecompiledContracts)?,
+                random_seed_with_domain: params.get(Parameter::RandomSeedWithDomain)?,
             }),
             account_creation_config: AccountCreationConfig {
                 min_allowed_top_level_account_length: params
@@ -366,6 +375,14 @@ fn get_congestion_control_config(
             let rational: Rational32 = params.get(Parameter::RejectTxCongestionThreshold)?;
             *rational.numer() as f64 / *rational.denom() as f64
         },
+        max_account_tx_gas_share: {
+            let rational: Rational32 = params.get(Parameter::MaxAccountTxGasShare)?;
+            *rational.numer() as f64 / *rational.denom() as f64
+        },
+        max_account_tx_size_share: {
+            let rational: Rational32 = params.get(Parameter::MaxAccountTxSizeShare)?;
+            *rational.numer() as f64 / *rational.denom() as f64
+        },
         outgoing_receipts_usual_size_limit: params
             .get(Parameter::OutgoingReceiptsUsualSizeLimit)?,
         outgoing_receipts_big_size_limit: params.get(Parameter::OutgoingReceiptsBigSizeLimit)?,
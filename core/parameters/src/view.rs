@@ -45,6 +45,12 @@ pub struct RuntimeFeesConfigView {
 
     /// Pessimistic gas price inflation ratio.
     pub pessimistic_gas_price_inflation_ratio: Rational32,
+
+    /// Fraction of the unused gas of a receipt that is burnt instead of refunded.
+    pub gas_refund_penalty: Rational32,
+
+    /// Minimum amount of the unused gas of a receipt that is burnt instead of refunded.
+    pub min_gas_refund_penalty: Gas,
 }
 
 /// The structure describes configuration for creation of new accounts.
@@ -184,6 +190,8 @@ impl From<crate::RuntimeConfig> for RuntimeConfigView {
                 pessimistic_gas_price_inflation_ratio: config
                     .fees
                     .pessimistic_gas_price_inflation_ratio,
+                gas_refund_penalty: config.fees.gas_refund_penalty,
+                min_gas_refund_penalty: config.fees.min_gas_refund_penalty,
             },
             wasm_config: VMConfigView::from(crate::vm::Config::clone(&config.wasm_config)),
             account_creation_config: AccountCreationConfigView {
@@ -227,6 +235,8 @@ pub struct VMConfigView {
     pub math_extension: bool,
     /// See [VMConfig::ed25519_verify](crate::vm::Config::ed25519_verify).
     pub ed25519_verify: bool,
+    /// See [VMConfig::p256_verify](crate::vm::Config::p256_verify).
+    pub p256_verify: bool,
     /// See [VMConfig::alt_bn128](crate::vm::Config::alt_bn128).
     pub alt_bn128: bool,
     /// See [VMConfig::function_call_weight](crate::vm::Config::function_call_weight).
@@ -235,6 +245,18 @@ pub struct VMConfigView {
     pub eth_implicit_accounts: bool,
     /// See [VMConfig::yield_resume_host_functions](`crate::vm::Config::yield_resume_host_functions).
     pub yield_resume_host_functions: bool,
+    /// See [VMConfig::yield_timeout_per_call](crate::vm::Config::yield_timeout_per_call).
+    pub yield_timeout_per_call: bool,
+    /// See [VMConfig::block_header](crate::vm::Config::block_header).
+    pub block_header: bool,
+    /// See [VMConfig::namespaced_storage](crate::vm::Config::namespaced_storage).
+    pub namespaced_storage: bool,
+    /// See [VMConfig::view_call](crate::vm::Config::view_call).
+    pub view_call: bool,
+    /// See [VMConfig::precompiled_contracts](crate::vm::Config::precompiled_contracts).
+    pub precompiled_contracts: bool,
+    /// See [VMConfig::random_seed_with_domain](crate::vm::Config::random_seed_with_domain).
+    pub random_seed_with_domain: bool,
 
     /// Describes limits for VM and Runtime.
     ///
@@ -257,11 +279,18 @@ impl From<crate::vm::Config> for VMConfigView {
             implicit_account_creation: config.implicit_account_creation,
             math_extension: config.math_extension,
             ed25519_verify: config.ed25519_verify,
+            p256_verify: config.p256_verify,
             alt_bn128: config.alt_bn128,
             function_call_weight: config.function_call_weight,
             vm_kind: config.vm_kind,
             eth_implicit_accounts: config.eth_implicit_accounts,
             yield_resume_host_functions: config.yield_resume_host_functions,
+            yield_timeout_per_call: config.yield_timeout_per_call,
+            block_header: config.block_header,
+            namespaced_storage: config.namespaced_storage,
+            view_call: config.view_call,
+            precompiled_contracts: config.precompiled_contracts,
+            random_seed_with_domain: config.random_seed_with_domain,
         }
     }
 }
@@ -280,11 +309,18 @@ impl From<VMConfigView> for crate::vm::Config {
             implicit_account_creation: view.implicit_account_creation,
             math_extension: view.math_extension,
             ed25519_verify: view.ed25519_verify,
+            p256_verify: view.p256_verify,
             alt_bn128: view.alt_bn128,
             function_call_weight: view.function_call_weight,
             vm_kind: view.vm_kind,
             eth_implicit_accounts: view.eth_implicit_accounts,
             yield_resume_host_functions: view.yield_resume_host_functions,
+            yield_timeout_per_call: view.yield_timeout_per_call,
+            block_header: view.block_header,
+            namespaced_storage: view.namespaced_storage,
+            view_call: view.view_call,
+            precompiled_contracts: view.precompiled_contracts,
+            random_seed_with_domain: view.random_seed_with_domain,
         }
     }
 }
@@ -487,6 +523,22 @@ pub struct ExtCostsConfigView {
     pub bls12381_p1_decompress_element: Gas,
     pub bls12381_p2_decompress_base: Gas,
     pub bls12381_p2_decompress_element: Gas,
+
+    /// Base cost for verifying a secp256r1 (P-256) signature.
+    pub p256_verify_base: Gas,
+    /// Cost per message byte for verifying a secp256r1 (P-256) signature.
+    pub p256_verify_byte: Gas,
+
+    /// Base cost for reading the header of a recent block.
+    pub block_header_base: Gas,
+
+    /// Base cost for accessing a record in a storage namespace, on top of the
+    /// regular storage costs. Covers the upkeep of the namespace usage record.
+    pub storage_ns_base: Gas,
+
+    /// Base cost for a synchronous view call into another contract. The gas
+    /// burnt by the called contract is charged on top.
+    pub view_call_base: Gas,
 }
 
 impl From<crate::ExtCostsConfig> for ExtCostsConfigView {
@@ -586,6 +638,11 @@ impl From<crate::ExtCostsConfig> for ExtCostsConfigView {
             bls12381_p2_decompress_base: config.gas_cost(ExtCosts::bls12381_p2_decompress_base),
             bls12381_p2_decompress_element: config
                 .gas_cost(ExtCosts::bls12381_p2_decompress_element),
+            p256_verify_base: config.gas_cost(ExtCosts::p256_verify_base),
+            p256_verify_byte: config.gas_cost(ExtCosts::p256_verify_byte),
+            block_header_base: config.gas_cost(ExtCosts::block_header_base),
+            storage_ns_base: config.gas_cost(ExtCosts::storage_ns_base),
+            view_call_base: config.gas_cost(ExtCosts::view_call_base),
             // removed parameters
             contract_compile_base: 0,
             contract_compile_bytes: 0,
@@ -681,6 +738,11 @@ impl From<ExtCostsConfigView> for crate::ExtCostsConfig {
                 ExtCosts::bls12381_p1_decompress_element => view.bls12381_p1_decompress_element,
                 ExtCosts::bls12381_p2_decompress_base => view.bls12381_p2_decompress_base,
                 ExtCosts::bls12381_p2_decompress_element => view.bls12381_p2_decompress_element,
+                ExtCosts::p256_verify_base => view.p256_verify_base,
+                ExtCosts::p256_verify_byte => view.p256_verify_byte,
+                ExtCosts::block_header_base => view.block_header_base,
+                ExtCosts::storage_ns_base => view.storage_ns_base,
+                ExtCosts::view_call_base => view.view_call_base,
         }
         .map(|_, value| ParameterCost { gas: value, compute: value });
         Self { costs }
@@ -766,6 +828,17 @@ pub struct CongestionControlConfigView {
     /// accepting new transactions with the receiver set to the congested shard.
     pub reject_tx_congestion_threshold: f64,
 
+    /// The share of the transaction gas of a chunk a single account can use.
+    ///
+    /// See [`CongestionControlConfig`] for more details.
+    pub max_account_tx_gas_share: f64,
+
+    /// The share of the transaction size limit of a chunk a single account can
+    /// use.
+    ///
+    /// See [`CongestionControlConfig`] for more details.
+    pub max_account_tx_size_share: f64,
+
     /// The standard size limit for outgoing receipts aimed at a single shard.
     /// This limit is pretty small to keep the size of source_receipt_proofs under control.
     /// It limits the total sum of outgoing receipts, not individual receipts.
@@ -790,6 +863,8 @@ impl From<CongestionControlConfig> for CongestionControlConfigView {
             max_tx_gas: other.max_tx_gas,
             min_tx_gas: other.min_tx_gas,
             reject_tx_congestion_threshold: other.reject_tx_congestion_threshold,
+            max_account_tx_gas_share: other.max_account_tx_gas_share,
+            max_account_tx_size_share: other.max_account_tx_size_share,
             outgoing_receipts_usual_size_limit: other.outgoing_receipts_usual_size_limit,
             outgoing_receipts_big_size_limit: other.outgoing_receipts_big_size_limit,
         }
@@ -809,6 +884,8 @@ impl From<CongestionControlConfigView> for CongestionControlConfig {
             max_tx_gas: other.max_tx_gas,
             min_tx_gas: other.min_tx_gas,
             reject_tx_congestion_threshold: other.reject_tx_congestion_threshold,
+            max_account_tx_gas_share: other.max_account_tx_gas_share,
+            max_account_tx_size_share: other.max_account_tx_size_share,
             outgoing_receipts_usual_size_limit: other.outgoing_receipts_usual_size_limit,
             outgoing_receipts_big_size_limit: other.outgoing_receipts_big_size_limit,
         }
@@ -76,6 +76,13 @@ pub struct LimitConfig {
     pub initial_memory_pages: u32,
     /// What is the maximal memory pages amount is allowed to have for a contract.
     pub max_memory_pages: u32,
+    /// Maximal number of memories a contract may have, including the main one
+    /// shared with the host. Only applies since [`ContractPrepareVersion::V3`].
+    ///
+    /// Memories other than the main one aren't charged for, so this must stay at
+    /// one until they are.
+    #[serde(default = "max_memories_per_contract_default")]
+    pub max_memories_per_contract: u32,
 
     /// Limit of memory used by registers.
     pub registers_memory_limit: u64,
@@ -117,10 +124,17 @@ pub struct LimitConfig {
     pub max_length_storage_key: u64,
     /// Max storage value size
     pub max_length_storage_value: u64,
+    /// Max storage namespace size. Can't exceed 255, since the namespace length
+    /// is stored as a single byte in the trie key.
+    #[serde(default = "max_length_storage_namespace_default")]
+    pub max_length_storage_namespace: u64,
     /// Max number of promises that a function call can create
     pub max_promises_per_function_call_action: u64,
     /// Max number of input data dependencies
     pub max_number_input_data_dependencies: u64,
+    /// Max number of synchronous view calls that can be nested into each other.
+    #[serde(default = "max_view_call_depth_default")]
+    pub max_view_call_depth: u64,
     /// If present, stores max number of functions in one contract
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_functions_number_per_contract: Option<u64>,
@@ -129,9 +143,24 @@ pub struct LimitConfig {
     /// This limit should never be hit normally.
     #[serde(default = "wasmer2_stack_limit_default")]
     pub wasmer2_stack_limit: i32,
+    /// Size in bytes of the native stack wasmtime lets contracts use.
+    ///
+    /// Stack usage is limited by `max_stack_height` through instrumentation,
+    /// so this limit should never be hit normally either.
+    #[serde(default = "wasmtime_stack_limit_default")]
+    pub wasmtime_stack_limit: u64,
     /// If present, stores max number of locals declared globally in one contract
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_locals_per_contract: Option<u64>,
+    /// If present, stores max number of locals declared in one function.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_locals_per_function: Option<u64>,
+    /// If present, stores max number of initial elements of a table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_table_elements: Option<u64>,
+    /// If present, stores max nesting depth of the blocks of a function.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_block_nesting_depth: Option<u64>,
     /// Whether to enforce account_id well-formedness where it wasn't enforced
     /// historically.
     #[serde(default = "AccountIdValidityRulesVersion::v0")]
@@ -140,10 +169,75 @@ pub struct LimitConfig {
     pub yield_timeout_length_in_blocks: u64,
     /// Maximum number of bytes for payload passed over a yield resume.
     pub max_yield_payload_size: u64,
+    /// Maximum number of blocks a contract may request as the timeout of a
+    /// single yielded promise.
+    #[serde(default = "max_yield_timeout_length_in_blocks_default")]
+    pub max_yield_timeout_length_in_blocks: u64,
+    /// Maximum number of delegate actions nested in each other, counting the
+    /// outermost one. Only applies once nested delegate actions are enabled.
+    #[serde(default = "max_delegate_action_nesting_depth_default")]
+    pub max_delegate_action_nesting_depth: u64,
+    /// Max gas a `MigrateState` action may attach and burn in its migration,
+    /// replacing `max_total_prepaid_gas` and `max_gas_burnt` for it.
+    #[serde(default = "max_migration_gas_default")]
+    pub max_migration_gas: Gas,
+    /// Number of blocks after a state migration during which the storage it
+    /// added doesn't need to be staked.
+    #[serde(default = "migration_grace_period_length_in_blocks_default")]
+    pub migration_grace_period_length_in_blocks: u64,
+    /// Number of blocks after which a receipt created from a transaction expires.
+    /// Expired receipts are not executed, their deposits and prepaid gas are refunded.
+    #[serde(default = "receipt_ttl_in_blocks_default")]
+    pub receipt_ttl_in_blocks: u64,
     /// Hard limit on the size of storage proof generated while executing a single receipt.
     pub per_receipt_storage_proof_size_limit: usize,
 }
 
+/// Limits of the contract sandbox replacing the ones of [`LimitConfig`] for
+/// every protocol version, see [`RuntimeConfigStore::with_limit_config_override`].
+///
+/// The limits change the outcome of contract execution, so they may only be
+/// overridden on chains where all nodes use the same values, such as sandbox
+/// and test networks.
+///
+/// [`RuntimeConfigStore::with_limit_config_override`]: crate::RuntimeConfigStore::with_limit_config_override
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitConfigOverride {
+    pub max_stack_height: Option<u32>,
+    pub initial_memory_pages: Option<u32>,
+    pub max_memory_pages: Option<u32>,
+    pub registers_memory_limit: Option<u64>,
+    pub max_register_size: Option<u64>,
+    pub max_number_registers: Option<u64>,
+    pub wasmer2_stack_limit: Option<i32>,
+    pub wasmtime_stack_limit: Option<u64>,
+}
+
+impl LimitConfig {
+    /// Replaces the limits set in `limits`.
+    pub fn apply_override(&mut self, limits: &LimitConfigOverride) {
+        let LimitConfigOverride {
+            max_stack_height,
+            initial_memory_pages,
+            max_memory_pages,
+            registers_memory_limit,
+            max_register_size,
+            max_number_registers,
+            wasmer2_stack_limit,
+            wasmtime_stack_limit,
+        } = *limits;
+        self.max_stack_height = max_stack_height.unwrap_or(self.max_stack_height);
+        self.initial_memory_pages = initial_memory_pages.unwrap_or(self.initial_memory_pages);
+        self.max_memory_pages = max_memory_pages.unwrap_or(self.max_memory_pages);
+        self.registers_memory_limit = registers_memory_limit.unwrap_or(self.registers_memory_limit);
+        self.max_register_size = max_register_size.unwrap_or(self.max_register_size);
+        self.max_number_registers = max_number_registers.unwrap_or(self.max_number_registers);
+        self.wasmer2_stack_limit = wasmer2_stack_limit.unwrap_or(self.wasmer2_stack_limit);
+        self.wasmtime_stack_limit = wasmtime_stack_limit.unwrap_or(self.wasmtime_stack_limit);
+    }
+}
+
 /// Dynamic configuration parameters required for the WASM runtime to
 /// execute a smart contract.
 ///
@@ -184,6 +278,9 @@ pub struct Config {
     /// Enable the host functions added by the `Ed25519Verify` protocol feature.
     pub ed25519_verify: bool,
 
+    /// Enable the `p256_verify` host function added by the `P256Verify` protocol feature.
+    pub p256_verify: bool,
+
     /// Enable the host functions added by the `AltBn128` protocol feature.
     pub alt_bn128: bool,
 
@@ -196,6 +293,29 @@ pub struct Config {
     /// Enable the `promise_yield_create` and `promise_yield_resume` host functions.
     pub yield_resume_host_functions: bool,
 
+    /// Enable the `promise_yield_create_with_timeout` host function.
+    pub yield_timeout_per_call: bool,
+
+    /// Enable the `block_header` host function added by the
+    /// `BlockHeaderHostFunction` protocol feature.
+    pub block_header: bool,
+
+    /// Enable the `storage_ns_*` host functions added by the
+    /// `NamespacedStorage` protocol feature.
+    pub namespaced_storage: bool,
+
+    /// Enable the `view_call` host function added by the
+    /// `SynchronousViewCall` protocol feature.
+    pub view_call: bool,
+
+    /// Execute the contracts registered in `near-vm-runner` with their native
+    /// implementation, added by the `PrecompiledContracts` protocol feature.
+    pub precompiled_contracts: bool,
+
+    /// Enable the `random_seed_with_domain` host function added by the
+    /// `RandomSeedWithDomain` protocol feature.
+    pub random_seed_with_domain: bool,
+
     /// Whether to discard custom sections.
     pub discard_custom_sections: bool,
 
@@ -225,10 +345,17 @@ impl Config {
 
     pub fn enable_all_features(&mut self) {
         self.yield_resume_host_functions = true;
+        self.yield_timeout_per_call = true;
         self.eth_implicit_accounts = true;
         self.function_call_weight = true;
         self.alt_bn128 = true;
         self.ed25519_verify = true;
+        self.p256_verify = true;
+        self.block_header = true;
+        self.namespaced_storage = true;
+        self.view_call = true;
+        self.precompiled_contracts = true;
+        self.random_seed_with_domain = true;
         self.math_extension = true;
         self.implicit_account_creation = true;
     }
@@ -238,6 +365,42 @@ fn wasmer2_stack_limit_default() -> i32 {
     100 * 1024
 }
 
+fn wasmtime_stack_limit_default() -> u64 {
+    1024 * 1024 * 1024
+}
+
+fn max_memories_per_contract_default() -> u32 {
+    1
+}
+
+fn max_length_storage_namespace_default() -> u64 {
+    64
+}
+
+fn max_view_call_depth_default() -> u64 {
+    3
+}
+
+fn max_yield_timeout_length_in_blocks_default() -> u64 {
+    200
+}
+
+fn max_delegate_action_nesting_depth_default() -> u64 {
+    2
+}
+
+fn max_migration_gas_default() -> Gas {
+    1_000_000_000_000_000
+}
+
+fn migration_grace_period_length_in_blocks_default() -> u64 {
+    43_200
+}
+
+fn receipt_ttl_in_blocks_default() -> u64 {
+    2_000
+}
+
 /// Our original code for limiting WASM stack was buggy. We fixed that, but we
 /// still have to use old (`V0`) limiter for old protocol versions.
 ///
@@ -267,6 +430,16 @@ pub enum ContractPrepareVersion {
     V1,
     /// finite-wasm 0.3.0 based contract preparation code.
     V2,
+    /// V2 with the multi-memory proposal enabled, subject to
+    /// `max_memories_per_contract`.
+    V3,
+    /// V3 with the fixed-width SIMD proposal enabled.
+    ///
+    /// No protocol version uses it yet: NearVM doesn't compile SIMD
+    /// instructions, so contracts using them must be rejected when preparing
+    /// them for all VMs until it does. SIMD operations have no cost of their
+    /// own yet and are charged `regular_op_cost` like any other operation.
+    V4,
 }
 
 impl ContractPrepareVersion {
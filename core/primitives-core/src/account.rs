@@ -2,7 +2,7 @@
 use crate::checked_feature;
 use crate::hash::CryptoHash;
 use crate::serialize::dec_format;
-use crate::types::{Balance, Nonce, ProtocolVersion, StorageUsage};
+use crate::types::{AccountId, Balance, BlockHeight, Nonce, ProtocolVersion, StorageUsage};
 use borsh::{BorshDeserialize, BorshSerialize};
 pub use near_account_id as id;
 use near_schema_checker_lib::ProtocolSchema;
@@ -476,6 +476,95 @@ pub struct FunctionCallPermission {
     pub method_names: Vec<String>,
 }
 
+/// Balance deposited by `sponsor_id` to cover the storage staking of another
+/// account. It is stored separately from the account under
+/// `TrieKey::StorageSponsorship` and counts towards the balance available for
+/// storage staking, but it can't be spent by the sponsored account. The
+/// deposit goes back to the sponsor when the sponsorship ends or the sponsored
+/// account is deleted.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    ProtocolSchema,
+)]
+pub struct StorageSponsorship {
+    pub sponsor_id: AccountId,
+    #[serde(with = "dec_format")]
+    pub deposit: Balance,
+}
+
+/// Grace period following a `MigrateState` action, during which the account
+/// only stakes for the storage it used before the migration. It is stored
+/// separately from the account under `TrieKey::MigrationGracePeriod` and gives
+/// the contract time to delete the state it migrated away from.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    ProtocolSchema,
+)]
+pub struct MigrationGracePeriod {
+    /// Storage usage of the account before the migration.
+    pub storage_usage: StorageUsage,
+    /// Last block height of the grace period.
+    pub end_height: BlockHeight,
+}
+
+/// Source metadata of a contract as defined by NEP-330, which the contract
+/// returns from its `contract_source_metadata` method. When it is also embedded
+/// in the `contract_source_metadata` custom section of the WASM code, it is
+/// stored under `TrieKey::ContractSourceMetadata` of the account the contract
+/// is deployed to.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    Default,
+    ProtocolSchema,
+)]
+pub struct ContractSourceMetadata {
+    /// Version of the contract, e.g. a semver version or a commit hash.
+    pub version: Option<String>,
+    /// Link to the source code of the contract.
+    pub link: Option<String>,
+    /// Standards implemented by the contract.
+    #[serde(default)]
+    pub standards: Vec<ContractStandard>,
+}
+
+/// A standard implemented by a contract, e.g. `nep141` version `1.0.0`.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    ProtocolSchema,
+)]
+pub struct ContractStandard {
+    pub standard: String,
+    pub version: String,
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -71,6 +71,26 @@ impl CryptoHash {
         CryptoHash(hasher.finalize().into())
     }
 
+    /// Calculates hash of given bytes for uses which are not part of the
+    /// protocol, e.g. cache keys or deduplication of network messages.
+    ///
+    /// With the `blake3` feature this uses blake3, which is several times
+    /// faster than sha256, so the result may differ between nodes and must
+    /// never be stored in blocks, chunks or anything else shared with them.
+    pub fn hash_bytes_non_consensus(bytes: &[u8]) -> CryptoHash {
+        let mut hasher = NonConsensusHasher::default();
+        hasher.write_all(bytes).unwrap();
+        hasher.finalize()
+    }
+
+    /// Same as [`Self::hash_borsh`], but with the hash function of
+    /// [`Self::hash_bytes_non_consensus`].
+    pub fn hash_borsh_non_consensus<T: BorshSerialize>(value: T) -> CryptoHash {
+        let mut hasher = NonConsensusHasher::default();
+        value.serialize(&mut hasher).unwrap();
+        hasher.finalize()
+    }
+
     pub const fn as_bytes(&self) -> &[u8; Self::LENGTH] {
         &self.0
     }
@@ -105,6 +125,30 @@ impl CryptoHash {
     }
 }
 
+#[derive(Default)]
+struct NonConsensusHasher {
+    #[cfg(feature = "blake3")]
+    inner: blake3::Hasher,
+    #[cfg(not(feature = "blake3"))]
+    inner: sha2::Sha256,
+}
+
+impl NonConsensusHasher {
+    fn finalize(self) -> CryptoHash {
+        CryptoHash(self.inner.finalize().into())
+    }
+}
+
+impl Write for NonConsensusHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Result of decoding base58-encoded crypto hash.
 enum Decode58Result {
     /// Decoding succeeded.
@@ -282,6 +326,17 @@ mod tests {
         slice("CuoNgQBWsXnTqup6FY3UXNz6RRufnYyQVxx8HKZLUaRt", &[b'f', b'o', b'o']);
     }
 
+    #[test]
+    fn test_hash_non_consensus() {
+        let hash = CryptoHash::hash_bytes_non_consensus(b"foo");
+        assert_eq!(hash, CryptoHash::hash_borsh_non_consensus(b"foo"));
+        assert_ne!(hash, CryptoHash::hash_bytes_non_consensus(b"bar"));
+        #[cfg(feature = "blake3")]
+        assert_eq!(hash.0, *blake3::hash(b"foo").as_bytes());
+        #[cfg(not(feature = "blake3"))]
+        assert_eq!(hash, CryptoHash::hash_bytes(b"foo"));
+    }
+
     #[test]
     fn test_base58_successes() {
         for (encoded, hash) in [
@@ -193,6 +193,137 @@ pub enum ProtocolFeature {
     ExcludeExistingCodeFromWitnessForCodeLen,
     /// Use the block height instead of the block hash to calculate the receipt ID.
     BlockHeightForReceiptId,
+    /// Chunk headers commit to the size of receipts sent by the previous chunk
+    /// to every other shard, so that chunk validators can verify bandwidth
+    /// scheduling. This feature introduces ShardChunkHeaderInnerV5.
+    ChunkBandwidthCommitment,
+    /// Allow `TransactionV1`, which carries a priority fee burnt on top of the
+    /// regular transaction fees. Transactions with a higher priority fee are
+    /// picked from the transaction pool first.
+    TransactionPriorityFee,
+    /// Receipts created from transactions are `ReceiptV2` with an expiry height.
+    /// Receipts which are not executed before the expiry height, e.g. because
+    /// they sit in the delayed receipts queue of a congested shard, are refunded
+    /// and dropped.
+    ReceiptExpiry,
+    /// Parts of partial encoded chunks are sent zstd-compressed, see
+    /// `PartialEncodedChunkV3`.
+    ChunkPartCompression,
+    /// Delegate actions may contain other delegate actions, up to the
+    /// `max_delegate_action_nesting_depth` runtime parameter.
+    NestedDelegateActions,
+    /// Chunk headers commit to the state root the previous chunk was applied
+    /// to, so that together with its post state root and outcome root the
+    /// header describes the full state transition. This feature introduces
+    /// ShardChunkHeaderInnerV6.
+    ExecutionProofs,
+    /// Allows an account to cover the storage staking of another account with
+    /// the `SponsorStorage` action, without holding keys on that account.
+    SponsoredStorage,
+    /// Validators may attach self-reported metadata (node version, p2p
+    /// endpoint) to their stake with the `StakeWithMetadata` action. It is
+    /// carried in `ValidatorStakeV2` proposals and exposed by the `validators`
+    /// RPC.
+    ValidatorMetadata,
+    /// Enables the `p256_verify` host function, which verifies secp256r1
+    /// (NIST P-256) signatures as produced by WebAuthn authenticators.
+    P256Verify,
+    /// Enables the `promise_yield_create_with_timeout` host function, with
+    /// which contracts choose the timeout of a yielded promise, up to the
+    /// `max_yield_timeout_length_in_blocks` runtime parameter.
+    YieldTimeoutPerCall,
+    /// Enables `DeployGlobalContract` and `UseGlobalContract` actions, with
+    /// which contract code is published once and distributed to all shards,
+    /// and accounts use it without storing a copy of their own.
+    GlobalContracts,
+    /// Switches to `ContractPrepareVersion::V3`, which parses the multi-memory
+    /// WebAssembly proposal. Contracts with 64-bit memories or with more memories
+    /// than `max_memories_per_contract` are still rejected when preparing them,
+    /// for all VMs, since NearVM can't compile the former and additional
+    /// memories aren't charged for yet.
+    WasmMemory64AndMultiMemory,
+    /// Enables the `block_header` host function, with which contracts read the
+    /// hash, timestamp and epoch id of the blocks preceding the current one.
+    BlockHeaderHostFunction,
+    /// Enables the `storage_ns_*` host functions, with which contracts keep
+    /// key-value records in separate namespaces whose storage usage is
+    /// tracked per namespace.
+    NamespacedStorage,
+    /// Allow `TransactionV2`, which carries the highest gas price the signer
+    /// agrees to pay. Such transactions are rejected when the gas price is
+    /// higher. Their prepaid gas is still bought at the pessimistic gas price.
+    TransactionMaxGasPrice,
+    /// Enables the `view_call` host function, with which a contract calls a
+    /// view method of another contract on the same shard and reads its result
+    /// within the same receipt execution.
+    SynchronousViewCall,
+    /// Reject contracts that are likely to be pathologically slow to compile,
+    /// based on a static analysis of their code done before preparing them.
+    ContractStaticAnalysis,
+    /// Enables the `MigrateState` action, which replaces the code of a contract
+    /// and runs its migration method with the `max_migration_gas` limit in a
+    /// single receipt, and exempts the storage added by the migration from
+    /// storage staking for `migration_grace_period_length_in_blocks`.
+    MigrateState,
+    /// Execute the methods of some well-known contracts, identified by their
+    /// code hash, with native implementations instead of the WASM runtime.
+    PrecompiledContracts,
+    /// Limit the share of the gas and size of the transactions in a chunk that
+    /// transactions signed by a single account can use, see
+    /// `max_account_tx_gas_share` and `max_account_tx_size_share`.
+    AccountTransactionQuota,
+    /// Enables the `random_seed_with_domain` host function, which derives
+    /// independent random values for different domains from the random seed
+    /// of the receipt.
+    RandomSeedWithDomain,
+    /// Burn `gas_refund_penalty` of the unused gas of a receipt, but at least
+    /// `min_gas_refund_penalty`, instead of refunding it to the signer.
+    ReducedGasRefunds,
+    /// Store the NEP-330 source metadata embedded in the `contract_source_metadata`
+    /// custom section of deployed contracts, so that it can be viewed without
+    /// calling the contract. Deploying a contract with malformed metadata fails.
+    ContractSourceMetadata,
+    /// Accounts hold balances of native tokens issued by other accounts and
+    /// move them with the `TransferToken` action, without registering storage
+    /// with a token contract. An account opts in to receiving a token by
+    /// sending it to itself.
+    NativeTokens,
+    /// The number of Reed-Solomon data parts of a chunk depends on the size
+    /// of the chunk: small chunks use fewer, larger parts, down to a single
+    /// data part for tiny chunks, while large chunks keep the data parts of
+    /// the epoch. The number is recorded in ShardChunkHeaderInnerV7.
+    AdaptiveChunkEncoding,
+    /// Accept state parts and base states of state witnesses in the compact
+    /// `PartialState::StatePartRange` format.
+    StatePartRange,
+    /// Validators slashed by a valid challenge record the kind of misbehavior
+    /// (double sign, invalid chunk or invalid witness) instead of only
+    /// whether it was a double sign.
+    SlashingSeverity,
+    /// Slashed validators record the account which submitted the challenge,
+    /// which is rewarded with a fraction of the slashed stake.
+    ChallengerReward,
+    /// Challenges carry the epoch and height of the challenged block or chunk
+    /// and are processed by the client: valid ones are added to the pool and
+    /// relayed, expired and duplicated ones are dropped.
+    ChallengeTargetEpoch,
+    /// Challenges are routed directly to validators with
+    /// `RoutedMessageBody::Challenge` instead of being gossiped. Peers with
+    /// older versions keep receiving them through gossip.
+    RoutedChallenge,
+    /// Challenges may be co-signed by several validators or fishermen. The
+    /// co-signers are part of the signed hash of the challenge.
+    ChallengeCoSignatures,
+    /// Chunk parts requests for the same target may be sent as one
+    /// `RoutedMessageBody::PartialEncodedChunkRequestBatch`, answered with one
+    /// `RoutedMessageBody::PartialEncodedChunkResponseBatch`.
+    PartialEncodedChunkRequestBatch,
+    /// Receivers of pushed chunk parts acknowledge them to their owner with
+    /// `RoutedMessageBody::PartialEncodedChunkForwardAck`.
+    PartialEncodedChunkForwardAck,
+    /// Peers can be asked to dial back with `PeerMessage::ReachabilityProbeRequest`,
+    /// to check whether the node accepts inbound connections.
+    ReachabilityProbe,
 }
 
 impl ProtocolFeature {
@@ -276,6 +407,40 @@ impl ProtocolFeature {
             ProtocolFeature::BandwidthScheduler => 148,
             ProtocolFeature::BlockHeightForReceiptId => 149,
             // Place features that are not yet in Nightly below this line.
+            ProtocolFeature::ChunkBandwidthCommitment => 150,
+            ProtocolFeature::TransactionPriorityFee => 151,
+            ProtocolFeature::ReceiptExpiry => 152,
+            ProtocolFeature::ChunkPartCompression => 153,
+            ProtocolFeature::NestedDelegateActions => 155,
+            ProtocolFeature::ExecutionProofs => 156,
+            ProtocolFeature::SponsoredStorage => 157,
+            ProtocolFeature::ValidatorMetadata => 158,
+            ProtocolFeature::P256Verify => 159,
+            ProtocolFeature::YieldTimeoutPerCall => 160,
+            ProtocolFeature::GlobalContracts => 161,
+            ProtocolFeature::WasmMemory64AndMultiMemory => 162,
+            ProtocolFeature::BlockHeaderHostFunction => 164,
+            ProtocolFeature::NamespacedStorage => 165,
+            ProtocolFeature::TransactionMaxGasPrice => 166,
+            ProtocolFeature::SynchronousViewCall => 167,
+            ProtocolFeature::ContractStaticAnalysis => 168,
+            ProtocolFeature::MigrateState => 169,
+            ProtocolFeature::PrecompiledContracts => 170,
+            ProtocolFeature::AccountTransactionQuota => 171,
+            ProtocolFeature::RandomSeedWithDomain => 172,
+            ProtocolFeature::ReducedGasRefunds => 173,
+            ProtocolFeature::ContractSourceMetadata => 174,
+            ProtocolFeature::NativeTokens => 175,
+            ProtocolFeature::AdaptiveChunkEncoding => 177,
+            ProtocolFeature::StatePartRange => 178,
+            ProtocolFeature::SlashingSeverity => 179,
+            ProtocolFeature::ChallengerReward => 180,
+            ProtocolFeature::ChallengeTargetEpoch => 181,
+            ProtocolFeature::RoutedChallenge => 182,
+            ProtocolFeature::ChallengeCoSignatures => 183,
+            ProtocolFeature::PartialEncodedChunkRequestBatch => 184,
+            ProtocolFeature::PartialEncodedChunkForwardAck => 185,
+            ProtocolFeature::ReachabilityProbe => 186,
         }
     }
 
@@ -288,7 +453,7 @@ impl ProtocolFeature {
 const STABLE_PROTOCOL_VERSION: ProtocolVersion = 74;
 
 // On nightly, pick big enough version to support all features.
-const NIGHTLY_PROTOCOL_VERSION: ProtocolVersion = 149;
+const NIGHTLY_PROTOCOL_VERSION: ProtocolVersion = 186;
 
 /// Largest protocol version supported by the current binary.
 pub const PROTOCOL_VERSION: ProtocolVersion = if cfg!(feature = "nightly_protocol") {
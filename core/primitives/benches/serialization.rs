@@ -149,6 +149,17 @@ fn combine_hash_bench(bench: &mut Bencher) {
     });
 }
 
+fn hash_bytes_bench(bench: &mut Bencher) {
+    let bytes = vec![1u8; 1 << 20];
+    bench.iter(|| black_box(CryptoHash::hash_bytes(black_box(&bytes))));
+}
+
+/// Uses blake3 when run with `--features blake3`.
+fn hash_bytes_non_consensus_bench(bench: &mut Bencher) {
+    let bytes = vec![1u8; 1 << 20];
+    bench.iter(|| black_box(CryptoHash::hash_bytes_non_consensus(black_box(&bytes))));
+}
+
 benchmark_group!(
     benches,
     serialize_tx,
@@ -158,5 +169,7 @@ benchmark_group!(
     serialize_account,
     deserialize_account,
     combine_hash_bench,
+    hash_bytes_bench,
+    hash_bytes_non_consensus_bench,
 );
 benchmark_main!(benches);
@@ -12,10 +12,16 @@ use near_primitives_core::types::BlockHeight;
 use near_primitives_core::types::{AccountId, Nonce};
 use near_schema_checker_lib::ProtocolSchema;
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 use std::io::{Error, ErrorKind, Read};
 
 /// This is an index number of Action::Delegate in Action enumeration
 const ACTION_DELEGATE_NUMBER: u8 = 8;
+/// Maximal number of delegate actions nested in another one which are accepted
+/// when decoding, so that a malicious payload can't overflow the stack. The
+/// runtime enforces the configurable `max_delegate_action_nesting_depth` limit
+/// on top of that.
+pub const MAX_NESTED_DELEGATE_ACTIONS_DECODING_DEPTH: usize = 8;
 /// This action allows to execute the inner actions behalf of the defined sender.
 #[derive(
     BorshSerialize,
@@ -36,7 +42,9 @@ pub struct DelegateAction {
     /// List of actions to be executed.
     ///
     /// With the meta transactions MVP defined in NEP-366, nested
-    /// DelegateActions are not allowed. A separate type is used to enforce it.
+    /// DelegateActions are not allowed. Since `NestedDelegateActions` they are
+    /// allowed up to the `max_delegate_action_nesting_depth` limit, but have
+    /// to be constructed explicitly with `NonDelegateAction::from_nested`.
     pub actions: Vec<NonDelegateAction>,
     /// Nonce to ensure that the same delegate action is not sent twice by a
     /// relayer and should match for given account's `public_key`.
@@ -85,6 +93,22 @@ impl DelegateAction {
         self.actions.iter().map(|a| a.clone().into()).collect()
     }
 
+    /// Number of delegate actions nested in each other, counting this one.
+    pub fn nesting_depth(&self) -> u64 {
+        let nested_depth = self
+            .actions
+            .iter()
+            .filter_map(|action| match &action.0 {
+                Action::Delegate(signed_delegate_action) => {
+                    Some(signed_delegate_action.delegate_action.nesting_depth())
+                }
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        1 + nested_depth
+    }
+
     /// Delegate action hash used for NEP-461 signature scheme which tags
     /// different messages before hashing
     ///
@@ -99,6 +123,9 @@ impl DelegateAction {
 /// This is Action which mustn't contain DelegateAction.
 ///
 /// This struct is needed to avoid the recursion when Action/DelegateAction is deserialized.
+/// With `NestedDelegateActions` it may contain a nested DelegateAction, but only
+/// if constructed with `NonDelegateAction::from_nested`, and decoding bounds the
+/// recursion by `MAX_NESTED_DELEGATE_ACTIONS_DECODING_DEPTH`.
 ///
 /// Important: Don't make the inner Action public, this must only be constructed
 /// through the correct interface that ensures the inner Action is actually not
@@ -119,10 +146,22 @@ mod private_non_delegate_action {
         }
     }
 
+    impl NonDelegateAction {
+        /// Wraps a delegate action to be nested in another one. Only valid
+        /// with the `NestedDelegateActions` protocol feature.
+        pub fn from_nested(signed_delegate_action: SignedDelegateAction) -> Self {
+            Self(Action::Delegate(Box::new(signed_delegate_action)))
+        }
+    }
+
     #[derive(Debug, thiserror::Error)]
     #[error("attempted to construct NonDelegateAction from Action::Delegate")]
     pub struct IsDelegateAction;
 
+    thread_local! {
+        static NESTED_DELEGATE_ACTIONS_DEPTH: Cell<usize> = const { Cell::new(0) };
+    }
+
     impl TryFrom<Action> for NonDelegateAction {
         type Error = IsDelegateAction;
 
@@ -138,10 +177,19 @@ mod private_non_delegate_action {
     impl borsh::de::BorshDeserialize for NonDelegateAction {
         fn deserialize_reader<R: Read>(rd: &mut R) -> ::core::result::Result<Self, Error> {
             match u8::deserialize_reader(rd)? {
-                ACTION_DELEGATE_NUMBER => Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    "DelegateAction mustn't contain a nested one",
-                )),
+                ACTION_DELEGATE_NUMBER => NESTED_DELEGATE_ACTIONS_DEPTH.with(|depth| {
+                    if depth.get() >= MAX_NESTED_DELEGATE_ACTIONS_DECODING_DEPTH {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            "DelegateAction contains too many nested ones",
+                        ));
+                    }
+                    depth.set(depth.get() + 1);
+                    let result =
+                        borsh::de::EnumExt::deserialize_variant(rd, ACTION_DELEGATE_NUMBER);
+                    depth.set(depth.get() - 1);
+                    result.map(Self)
+                }),
                 n => borsh::de::EnumExt::deserialize_variant(rd, n).map(Self),
             }
         }
@@ -198,12 +246,11 @@ mod tests {
         // Expected Action::Delegate has not been moved in enum Action
         assert_eq!(serialized_non_delegate_action[0], ACTION_DELEGATE_NUMBER);
 
-        // Expected a nested DelegateAction error
-        assert_eq!(
-            NonDelegateAction::try_from_slice(&serialized_non_delegate_action)
-                .map_err(|e| e.kind()),
-            Err(ErrorKind::InvalidInput)
-        );
+        // Nested DelegateAction can be decoded, but not constructed from an Action
+        let nested =
+            NonDelegateAction::try_from_slice(&serialized_non_delegate_action).expect("Expect ok");
+        assert_eq!(Action::from(nested), delegate_action);
+        assert!(NonDelegateAction::try_from(delegate_action).is_err());
 
         let delegate_action =
             create_delegate_action(vec![Action::CreateAccount(CreateAccountAction {})]);
@@ -216,6 +263,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_nested_delegate_action_deserialization() {
+        let mut action = create_delegate_action(vec![]);
+        for _ in 0..MAX_NESTED_DELEGATE_ACTIONS_DECODING_DEPTH {
+            let Action::Delegate(signed_delegate_action) = action else { unreachable!() };
+            action = create_delegate_action(vec![]);
+            let Action::Delegate(outer) = &mut action else { unreachable!() };
+            outer.delegate_action.actions =
+                vec![NonDelegateAction::from_nested(*signed_delegate_action)];
+        }
+        let Action::Delegate(signed_delegate_action) = &action else { unreachable!() };
+        assert_eq!(
+            signed_delegate_action.delegate_action.nesting_depth(),
+            MAX_NESTED_DELEGATE_ACTIONS_DECODING_DEPTH as u64 + 1
+        );
+        let serialized = borsh::to_vec(&action).expect("Expect ok");
+        assert_eq!(Action::try_from_slice(&serialized).expect("Expect ok"), action);
+
+        // One more level of nesting is rejected when decoding.
+        let mut outer = create_delegate_action(vec![]);
+        let Action::Delegate(signed_outer) = &mut outer else { unreachable!() };
+        let Action::Delegate(signed_delegate_action) = action else { unreachable!() };
+        signed_outer.delegate_action.actions =
+            vec![NonDelegateAction::from_nested(*signed_delegate_action)];
+        let serialized = borsh::to_vec(&outer).expect("Expect ok");
+        assert_eq!(
+            Action::try_from_slice(&serialized).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidInput)
+        );
+    }
+
     /// Check that the hard-coded delegate action is valid.
     #[test]
     fn test_delegate_action_deserialization_hard_coded() {
@@ -1,9 +1,11 @@
 pub mod delegate;
 
+use crate::types::ValidatorMetadata;
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::PublicKey;
 use near_primitives_core::{
     account::AccessKey,
+    hash::CryptoHash,
     serialize::dec_format,
     types::{AccountId, Balance, Gas},
 };
@@ -157,6 +159,24 @@ pub struct StakeAction {
     pub public_key: PublicKey,
 }
 
+/// Same as `StakeAction`, additionally reporting metadata of the validator
+/// node which ends up in its stake proposal.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+pub struct StakeWithMetadataAction {
+    pub stake: StakeAction,
+    pub metadata: ValidatorMetadata,
+}
+
 #[derive(
     BorshSerialize,
     BorshDeserialize,
@@ -190,6 +210,180 @@ pub struct NonrefundableStorageTransferAction {
     pub deposit: Balance,
 }
 
+/// Covers storage staking of the receiver with `deposit` taken from the
+/// predecessor. A repeated action from the same sponsor adds to the deposit,
+/// and a zero deposit ends the sponsorship and refunds the sponsor.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+pub struct SponsorStorageAction {
+    #[serde(with = "dec_format")]
+    pub deposit: Balance,
+}
+
+/// Transfers `amount` of the native token issued by the `token_id` account from
+/// the signer to the receiver. The issuer has an unlimited balance of its own
+/// token: a transfer from the issuer mints the tokens and a transfer to the
+/// issuer burns them.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+pub struct TransferTokenAction {
+    pub token_id: AccountId,
+    #[serde(with = "dec_format")]
+    pub amount: Balance,
+}
+
+/// How a global contract is identified once it is deployed.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+pub enum GlobalContractDeployMode {
+    /// The contract is identified by the hash of its code, so it can't be
+    /// changed.
+    CodeHash,
+    /// The contract is identified by the account that deployed it, which can
+    /// later replace the code by deploying again.
+    AccountId,
+}
+
+/// Publishes contract code for all shards, so that any account can use it
+/// with `UseGlobalContractAction` without paying for its own copy.
+#[serde_as]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    ProtocolSchema,
+)]
+pub struct DeployGlobalContractAction {
+    /// WebAssembly binary
+    #[serde_as(as = "Base64")]
+    pub code: Vec<u8>,
+    pub deploy_mode: GlobalContractDeployMode,
+}
+
+impl fmt::Debug for DeployGlobalContractAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeployGlobalContractAction")
+            .field("code", &format_args!("{}", base64(&self.code)))
+            .field("deploy_mode", &self.deploy_mode)
+            .finish()
+    }
+}
+
+/// Identifier of a global contract, see `GlobalContractDeployMode`.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+pub enum GlobalContractIdentifier {
+    CodeHash(CryptoHash),
+    AccountId(AccountId),
+}
+
+impl GlobalContractIdentifier {
+    /// Length of the identifier in bytes.
+    pub fn len(&self) -> usize {
+        match self {
+            GlobalContractIdentifier::CodeHash(hash) => hash.as_ref().len(),
+            GlobalContractIdentifier::AccountId(account_id) => account_id.len(),
+        }
+    }
+}
+
+/// Makes the receiver use a global contract instead of contract code of its
+/// own. The contract is resolved when the action is applied, so an account
+/// using a contract identified by `AccountId` keeps running the code it got
+/// until it uses the contract again.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+pub struct UseGlobalContractAction {
+    pub contract_identifier: GlobalContractIdentifier,
+}
+
+/// Replaces the code of the receiver and calls `method_name` of the new code
+/// to migrate the contract state, as a single action. The migration may use
+/// up to the `max_migration_gas` limit, more than a function call, and must be
+/// the only action of its receipt.
+#[serde_as]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    ProtocolSchema,
+)]
+pub struct MigrateStateAction {
+    /// WebAssembly binary
+    #[serde_as(as = "Base64")]
+    pub code: Vec<u8>,
+    pub method_name: String,
+    #[serde_as(as = "Base64")]
+    pub args: Vec<u8>,
+    pub gas: Gas,
+}
+
+impl fmt::Debug for MigrateStateAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MigrateStateAction")
+            .field("code", &format_args!("{}", base64(&self.code)))
+            .field("method_name", &format_args!("{}", &self.method_name))
+            .field("args", &format_args!("{}", base64(&self.args)))
+            .field("gas", &format_args!("{}", &self.gas))
+            .finish()
+    }
+}
+
 #[derive(
     BorshSerialize,
     BorshDeserialize,
@@ -216,6 +410,15 @@ pub enum Action {
     DeleteKey(Box<DeleteKeyAction>),
     DeleteAccount(DeleteAccountAction),
     Delegate(Box<delegate::SignedDelegateAction>),
+    /// Makes the predecessor a sponsor of the receiver's storage staking.
+    SponsorStorage(SponsorStorageAction),
+    StakeWithMetadata(Box<StakeWithMetadataAction>),
+    DeployGlobalContract(Box<DeployGlobalContractAction>),
+    UseGlobalContract(Box<UseGlobalContractAction>),
+    MigrateState(Box<MigrateStateAction>),
+    TransferToken(Box<TransferTokenAction>),
+    // Feature-gated variants must stay last, so that the borsh tags of the
+    // other variants don't depend on which features are enabled.
     #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
     /// Makes a non-refundable transfer for storage allowance.
     /// Only possible during new account creation.
@@ -235,6 +438,7 @@ impl Action {
     pub fn get_prepaid_gas(&self) -> Gas {
         match self {
             Action::FunctionCall(a) => a.gas,
+            Action::MigrateState(a) => a.gas,
             _ => 0,
         }
     }
@@ -244,6 +448,7 @@ impl Action {
             Action::Transfer(a) => a.deposit,
             #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
             Action::NonrefundableStorageTransfer(a) => a.deposit,
+            Action::SponsorStorage(a) => a.deposit,
             _ => 0,
         }
     }
@@ -280,12 +485,48 @@ impl From<NonrefundableStorageTransferAction> for Action {
     }
 }
 
+impl From<SponsorStorageAction> for Action {
+    fn from(sponsor_storage_action: SponsorStorageAction) -> Self {
+        Self::SponsorStorage(sponsor_storage_action)
+    }
+}
+
+impl From<TransferTokenAction> for Action {
+    fn from(transfer_token_action: TransferTokenAction) -> Self {
+        Self::TransferToken(Box::new(transfer_token_action))
+    }
+}
+
+impl From<DeployGlobalContractAction> for Action {
+    fn from(deploy_global_contract_action: DeployGlobalContractAction) -> Self {
+        Self::DeployGlobalContract(Box::new(deploy_global_contract_action))
+    }
+}
+
+impl From<UseGlobalContractAction> for Action {
+    fn from(use_global_contract_action: UseGlobalContractAction) -> Self {
+        Self::UseGlobalContract(Box::new(use_global_contract_action))
+    }
+}
+
+impl From<MigrateStateAction> for Action {
+    fn from(migrate_state_action: MigrateStateAction) -> Self {
+        Self::MigrateState(Box::new(migrate_state_action))
+    }
+}
+
 impl From<StakeAction> for Action {
     fn from(stake_action: StakeAction) -> Self {
         Self::Stake(Box::new(stake_action))
     }
 }
 
+impl From<StakeWithMetadataAction> for Action {
+    fn from(stake_with_metadata_action: StakeWithMetadataAction) -> Self {
+        Self::StakeWithMetadata(Box::new(stake_with_metadata_action))
+    }
+}
+
 impl From<AddKeyAction> for Action {
     fn from(add_key_action: AddKeyAction) -> Self {
         Self::AddKey(Box::new(add_key_action))
@@ -10,6 +10,9 @@ use near_primitives_core::types::{ProtocolVersion, ShardId};
 use near_primitives_core::version::ProtocolFeature;
 use near_schema_checker_lib::ProtocolSchema;
 
+use crate::receipt::Receipt;
+use crate::shard_layout::ShardLayout;
+
 /// Represents size of receipts, in the context of cross-shard bandwidth, in bytes.
 /// TODO(bandwidth_scheduler) - consider using ByteSize
 pub type Bandwidth = u64;
@@ -297,6 +300,60 @@ impl BlockBandwidthRequests {
     }
 }
 
+/// Size of receipts sent by a chunk to every receiver shard.
+/// The header of the next chunk commits to it, so that chunk validators can
+/// verify that the chunk producer didn't send more than it was granted.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Default,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    ProtocolSchema,
+)]
+pub struct OutgoingBandwidth {
+    /// Total size of receipts in bytes for every shard which receives at least
+    /// one receipt, sorted by shard id.
+    pub sizes: Vec<(ShardId, Bandwidth)>,
+}
+
+impl OutgoingBandwidth {
+    pub fn from_receipts(receipts: &[Receipt], shard_layout: &ShardLayout) -> OutgoingBandwidth {
+        let mut sizes: BTreeMap<ShardId, Bandwidth> = BTreeMap::new();
+        for receipt in receipts {
+            let receiver_shard = receipt.receiver_shard_id(shard_layout);
+            let receipt_size: Bandwidth = borsh::object_length(receipt)
+                .expect("Failed to serialize")
+                .try_into()
+                .expect("Receipt size doesn't fit into u64");
+            *sizes.entry(receiver_shard).or_default() += receipt_size;
+        }
+        OutgoingBandwidth { sizes: sizes.into_iter().collect() }
+    }
+
+    pub fn default_for_protocol_version(
+        protocol_version: ProtocolVersion,
+    ) -> Option<OutgoingBandwidth> {
+        if ProtocolFeature::ChunkBandwidthCommitment.enabled(protocol_version) {
+            Some(OutgoingBandwidth::default())
+        } else {
+            None
+        }
+    }
+
+    /// Size of receipts sent to the given shard.
+    pub fn get(&self, receiver_shard: ShardId) -> Bandwidth {
+        self.sizes
+            .iter()
+            .find(|(shard_id, _)| *shard_id == receiver_shard)
+            .map_or(0, |(_, size)| *size)
+    }
+}
+
 /// Persistent state used by the bandwidth scheduler.
 /// It is kept in the shard trie.
 /// The state should be the same on all shards. All shards start with the same state
@@ -386,10 +443,12 @@ mod tests {
     use rand::{Rng, SeedableRng};
 
     use crate::bandwidth_scheduler::{interpolate, BANDWIDTH_REQUEST_VALUES_NUM};
-    use crate::shard_layout::ShardUId;
+    use crate::receipt::{Receipt, ReceiptPriority};
+    use crate::shard_layout::{ShardLayout, ShardUId};
 
     use super::{
         BandwidthRequest, BandwidthRequestBitmap, BandwidthRequestValues, BandwidthSchedulerParams,
+        OutgoingBandwidth,
     };
     use rand_chacha::ChaCha20Rng;
 
@@ -678,4 +737,30 @@ mod tests {
         assert_eq!(interpolate(100, 200, 5, 10), 150);
         assert_eq!(interpolate(100, 200, 10, 10), 200);
     }
+
+    #[test]
+    fn test_outgoing_bandwidth_from_receipts() {
+        let shard_layout = ShardLayout::multi_shard_custom(vec!["b".parse().unwrap()], 3);
+        let alice = "alice".parse().unwrap();
+        let bob = "bob".parse().unwrap();
+        let receipts = vec![
+            Receipt::new_balance_refund(&alice, 1, ReceiptPriority::NoPriority),
+            Receipt::new_balance_refund(&bob, 2, ReceiptPriority::NoPriority),
+            Receipt::new_balance_refund(&alice, 3, ReceiptPriority::NoPriority),
+        ];
+        let receipt_size = borsh::object_length(&receipts[0]).unwrap() as u64;
+
+        let outgoing_bandwidth = OutgoingBandwidth::from_receipts(&receipts, &shard_layout);
+        let alice_shard = shard_layout.account_id_to_shard_id(&alice);
+        let bob_shard = shard_layout.account_id_to_shard_id(&bob);
+        assert_ne!(alice_shard, bob_shard);
+        assert_eq!(outgoing_bandwidth.sizes.len(), 2);
+        assert!(outgoing_bandwidth.sizes.is_sorted_by_key(|(shard_id, _)| *shard_id));
+        assert_eq!(outgoing_bandwidth.get(alice_shard), 2 * receipt_size);
+        assert_eq!(outgoing_bandwidth.get(bob_shard), receipt_size);
+
+        let empty = OutgoingBandwidth::from_receipts(&[], &shard_layout);
+        assert_eq!(empty, OutgoingBandwidth::default());
+        assert_eq!(empty.get(alice_shard), 0);
+    }
 }
@@ -1,18 +1,20 @@
 use crate::bandwidth_scheduler::BlockBandwidthRequests;
-use crate::block::BlockValidityError::{
-    InvalidChallengeRoot, InvalidChunkHeaderRoot, InvalidChunkMask, InvalidReceiptRoot,
-    InvalidStateRoot, InvalidTransactionRoot,
-};
 use crate::block_body::{BlockBody, BlockBodyV1, ChunkEndorsementSignatures};
 pub use crate::block_header::*;
 use crate::challenge::Challenges;
 use crate::checked_feature;
 use crate::congestion_info::{BlockCongestionInfo, ExtendedCongestionInfo};
+use crate::errors::PrimitiveValidationError;
+use crate::errors::PrimitiveValidationError::{
+    InvalidChallengeRoot, InvalidChunkHeaderRoot, InvalidChunkMask, InvalidReceiptRoot,
+    InvalidStateRoot, InvalidTransactionRoot,
+};
 use crate::hash::CryptoHash;
 use crate::merkle::{merklize, verify_path, MerklePath};
 use crate::num_rational::Rational32;
 use crate::sharding::{ChunkHashHeight, ShardChunkHeader, ShardChunkHeaderV1};
 use crate::types::{Balance, BlockHeight, EpochId, Gas};
+use crate::utils::DetailedDebug;
 use crate::version::{ProtocolVersion, SHARD_CHUNK_HEADER_UPGRADE_VERSION};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_primitives_core::types::ShardIndex;
@@ -31,16 +33,6 @@ pub struct GenesisId {
     pub hash: CryptoHash,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub enum BlockValidityError {
-    InvalidStateRoot,
-    InvalidReceiptRoot,
-    InvalidChunkHeaderRoot,
-    InvalidTransactionRoot,
-    InvalidChunkMask,
-    InvalidChallengeRoot,
-}
-
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Eq, PartialEq, ProtocolSchema)]
 pub struct BlockV1 {
     pub header: BlockHeader,
@@ -79,7 +71,8 @@ pub struct BlockV4 {
 
 /// Versioned Block data structure.
 /// For each next version, document what are the changes between versions.
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Eq, PartialEq, ProtocolSchema)]
+/// `Debug` only prints a summary, use `utils::detailed` to print all fields.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Eq, PartialEq, ProtocolSchema)]
 pub enum Block {
     BlockV1(Arc<BlockV1>),
     BlockV2(Arc<BlockV2>),
@@ -87,6 +80,29 @@ pub enum Block {
     BlockV4(Arc<BlockV4>),
 }
 
+impl std::fmt::Debug for Block {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Block")
+            .field("hash", self.hash())
+            .field("height", &self.header().height())
+            .field("prev_hash", self.header().prev_hash())
+            .field("num_chunks", &self.chunks().len())
+            .field("num_challenges", &self.challenges().len())
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for DetailedDebug<'_, Block> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Block::BlockV1(block) => f.debug_tuple("BlockV1").field(block).finish(),
+            Block::BlockV2(block) => f.debug_tuple("BlockV2").field(block).finish(),
+            Block::BlockV3(block) => f.debug_tuple("BlockV3").field(block).finish(),
+            Block::BlockV4(block) => f.debug_tuple("BlockV4").field(block).finish(),
+        }
+    }
+}
+
 #[cfg(feature = "solomon")]
 type ShardChunkReedSolomon = reed_solomon_erasure::galois_8::ReedSolomon;
 
@@ -146,7 +162,7 @@ fn genesis_chunk(
     state_root: CryptoHash,
     congestion_info: Option<crate::congestion_info::CongestionInfo>,
 ) -> crate::sharding::EncodedShardChunk {
-    use crate::bandwidth_scheduler::BandwidthRequests;
+    use crate::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 
     let (encoded_chunk, _) = crate::sharding::EncodedShardChunk::new(
         CryptoHash::default(),
@@ -165,6 +181,10 @@ fn genesis_chunk(
         CryptoHash::default(),
         congestion_info,
         BandwidthRequests::default_for_protocol_version(genesis_protocol_version),
+        OutgoingBandwidth::default_for_protocol_version(genesis_protocol_version),
+        crate::version::ProtocolFeature::ExecutionProofs
+            .enabled(genesis_protocol_version)
+            .then_some(state_root),
         &crate::validator_signer::EmptyValidatorSigner::default().into(),
         genesis_protocol_version,
     )
@@ -716,7 +736,7 @@ impl Block {
     }
 
     /// Checks that block content matches block hash, with the possible exception of chunk signatures
-    pub fn check_validity(&self) -> Result<(), BlockValidityError> {
+    pub fn check_validity(&self) -> Result<(), PrimitiveValidationError> {
         // Check that state root stored in the header matches the state root of the chunks
         let state_root = Block::compute_state_root(self.chunks().iter_deprecated());
         if self.header().prev_state_root() != &state_root {
@@ -895,3 +915,14 @@ impl Tip {
         }
     }
 }
+
+/// Block found invalid by the node, persisted in `DBCol::InvalidBlocks` so
+/// that it is not processed again after a restart.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq, ProtocolSchema)]
+pub struct StoredInvalidBlock {
+    /// Height of the block. The entry is garbage collected once this height
+    /// falls behind the GC stop height.
+    pub height: BlockHeight,
+    /// The error which made the block invalid.
+    pub reason: String,
+}
@@ -0,0 +1,75 @@
+//! Ranges of block headers served in header sync.
+//!
+//! A range is committed to by a later block, the anchor: the headers of the
+//! range form a chain, and the last of them is proven to be included in the
+//! block merkle root of the anchor. This lets several ranges be downloaded
+//! from different peers at the same time and applied once the header chain
+//! reaches them.
+//!
+//! The anchor is the highest block of the serving peer, which the requesting
+//! node hasn't validated yet, so a range that passes
+//! [`BlockHeadersRange::verify`] is only consistent with what the peer
+//! claims. It's not proven canonical or valid. Each header is still fully
+//! validated when it's added to the chain, and a range failing that gets its
+//! sender banned.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_schema_checker_lib::ProtocolSchema;
+
+use crate::block_header::BlockHeader;
+use crate::hash::CryptoHash;
+use crate::merkle::{verify_hash, MerklePath};
+use crate::types::BlockHeight;
+
+/// Request for the headers at heights `start_height..start_height + max_headers`
+/// of the chain ending at the block `anchor_hash`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq, ProtocolSchema)]
+pub struct BlockHeadersRangeRequest {
+    pub anchor_hash: CryptoHash,
+    pub start_height: BlockHeight,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq, ProtocolSchema)]
+pub struct BlockHeadersRange {
+    /// Header of the block the range is committed to.
+    pub anchor: BlockHeader,
+    /// Headers of the chain of `anchor` in the requested heights, by
+    /// increasing height.
+    pub headers: Vec<BlockHeader>,
+    /// Proof of the last header in the block merkle root of `anchor`, empty
+    /// if the last header is the anchor.
+    pub proof: MerklePath,
+}
+
+impl BlockHeadersRange {
+    /// Checks that the range answers `request` for ranges of `max_headers`
+    /// heights: the headers are in the requested heights, form a chain, and
+    /// the chain is committed to by the requested anchor.
+    ///
+    /// This only filters out malformed responses. Neither the anchor nor the
+    /// headers are validated, which is done when they are added to the chain.
+    pub fn verify(&self, request: &BlockHeadersRangeRequest, max_headers: u64) -> bool {
+        if self.anchor.hash() != &request.anchor_hash {
+            return false;
+        }
+        let (Some(first), Some(last)) = (self.headers.first(), self.headers.last()) else {
+            return false;
+        };
+        if first.height() < request.start_height
+            || last.height() >= request.start_height.saturating_add(max_headers)
+            || last.height() > self.anchor.height()
+        {
+            return false;
+        }
+        let is_chain = self.headers.windows(2).all(|pair| {
+            pair[1].prev_hash() == pair[0].hash() && pair[1].height() > pair[0].height()
+        });
+        if !is_chain {
+            return false;
+        }
+        if last.hash() == self.anchor.hash() {
+            return self.proof.is_empty();
+        }
+        verify_hash(*self.anchor.block_merkle_root(), &self.proof, *last.hash())
+    }
+}
@@ -1,22 +1,53 @@
 use crate::hash::CryptoHash;
 use crate::merkle::MerklePath;
 use crate::sharding::{EncodedShardChunk, ShardChunk, ShardChunkHeader};
-use crate::types::AccountId;
+use crate::stateless_validation::partial_witness::PartialEncodedStateWitness;
+use crate::types::{AccountId, BlockHeight, EpochHeight, EpochId};
 use crate::validator_signer::ValidatorSigner;
+use crate::version::{ProtocolFeature, ProtocolVersion};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::Signature;
+use near_primitives_core::serialize::{from_base64, to_base64};
 use near_schema_checker_lib::ProtocolSchema;
+use serde::de::Error as DecodeError;
+use serde::ser::Error as EncodeError;
 use std::fmt::{Debug, Formatter};
 
 /// Serialized TrieNodeWithSize or state value.
 pub type TrieValue = std::sync::Arc<[u8]>;
 
+/// Number of epochs after the epoch of the challenged misbehavior during which
+/// a challenge is still accepted and gossiped. Older challenges are dropped.
+pub const CHALLENGE_SLASHING_WINDOW_EPOCHS: EpochHeight = 2;
+
 #[derive(BorshSerialize, BorshDeserialize, Clone, Eq, PartialEq, ProtocolSchema)]
-/// TODO (#8984): consider supporting format containing trie values only for
-/// state part boundaries and storing state items for state part range.
+#[cfg_attr(feature = "test_features", derive(arbitrary::Arbitrary))]
 pub enum PartialState {
     /// State represented by the set of unique trie values (`RawTrieNodeWithSize`s and state values).
     TrieValues(Vec<TrieValue>),
+    /// State of a contiguous range of keys, represented by trie values proving
+    /// range boundaries and raw state items inside the range.
+    StatePartRange(StatePartRange),
+}
+
+/// Compact representation of a state part. Only trie values on the paths to
+/// the range boundaries are stored, while all trie nodes inside the range can
+/// be recomputed from the state items.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Eq, PartialEq, Default, ProtocolSchema)]
+#[cfg_attr(feature = "test_features", derive(arbitrary::Arbitrary))]
+pub struct StatePartRange {
+    /// Trie values on the paths from the state root to both boundaries of the
+    /// range, including left siblings of these paths.
+    pub boundary_values: Vec<TrieValue>,
+    /// State items inside the range as `(trie key, value)` pairs, sorted by key.
+    pub items: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl StatePartRange {
+    pub fn new(boundary_values: Vec<TrieValue>, mut items: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        items.sort();
+        Self { boundary_values, items }
+    }
 }
 
 impl Default for PartialState {
@@ -33,19 +64,27 @@ impl Debug for PartialState {
             PartialState::TrieValues(values) => {
                 f.write_str(&format!("{} trie values", values.len()))
             }
+            PartialState::StatePartRange(range) => f.write_str(&format!(
+                "{} boundary trie values, {} state items",
+                range.boundary_values.len(),
+                range.items.len()
+            )),
         }
     }
 }
 
 impl PartialState {
     pub fn len(&self) -> usize {
-        let Self::TrieValues(values) = self;
-        values.len()
+        match self {
+            Self::TrieValues(values) => values.len(),
+            Self::StatePartRange(range) => range.boundary_values.len() + range.items.len(),
+        }
     }
 }
 
 /// Double signed block.
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug, ProtocolSchema)]
+#[cfg_attr(feature = "test_features", derive(arbitrary::Arbitrary))]
 pub struct BlockDoubleSign {
     pub left_block_header: Vec<u8>,
     pub right_block_header: Vec<u8>,
@@ -57,6 +96,13 @@ impl std::fmt::Display for BlockDoubleSign {
     }
 }
 
+/// Two different chunks signed by the same chunk producer for the same height and shard.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug, ProtocolSchema)]
+pub struct ChunkDoubleSign {
+    pub left_chunk_header: ShardChunkHeader,
+    pub right_chunk_header: ShardChunkHeader,
+}
+
 /// Invalid chunk (body of the chunk doesn't match proofs or invalid encoding).
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug, ProtocolSchema)]
 pub struct ChunkProofs {
@@ -98,21 +144,78 @@ pub struct ChunkState {
     pub partial_state: PartialState,
 }
 
+/// State witness endorsed by chunk validators which doesn't lead to the post-state of the chunk
+/// it is produced for. Allows slashing both the chunk producer and the endorsing validators.
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug, ProtocolSchema)]
+pub struct InvalidStateWitness {
+    /// Parts of the offending state witness, as signed by the chunk producer.
+    /// Must contain enough parts to decode the witness, which is re-executed
+    /// with its own base state.
+    pub witness_parts: Vec<PartialEncodedStateWitness>,
+    /// Endorsement signatures of chunk validators over the witness chunk hash.
+    pub endorsements: Vec<(AccountId, Signature)>,
+}
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    strum::IntoStaticStr,
+    ProtocolSchema,
+)]
 // TODO(#1313): Use Box
 #[allow(clippy::large_enum_variant)]
 pub enum ChallengeBody {
     BlockDoubleSign(BlockDoubleSign),
     ChunkProofs(ChunkProofs),
     ChunkState(ChunkState),
+    InvalidStateWitness(InvalidStateWitness),
+    ChunkDoubleSign(ChunkDoubleSign),
+}
+
+impl ChallengeBody {
+    /// Kind of misbehavior of the validators slashed by a valid challenge.
+    /// Before `SlashingSeverity` only double signs are told apart.
+    pub fn slashing_severity(&self, protocol_version: ProtocolVersion) -> SlashingSeverity {
+        if !ProtocolFeature::SlashingSeverity.enabled(protocol_version) {
+            return match self {
+                ChallengeBody::BlockDoubleSign(_) | ChallengeBody::ChunkDoubleSign(_) => {
+                    SlashingSeverity::DoubleSign
+                }
+                _ => SlashingSeverity::Other,
+            };
+        }
+        match self {
+            ChallengeBody::BlockDoubleSign(_) | ChallengeBody::ChunkDoubleSign(_) => {
+                SlashingSeverity::DoubleSign
+            }
+            ChallengeBody::ChunkProofs(_) | ChallengeBody::ChunkState(_) => {
+                SlashingSeverity::InvalidChunk
+            }
+            ChallengeBody::InvalidStateWitness(_) => SlashingSeverity::InvalidWitness,
+        }
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug, ProtocolSchema)]
 #[borsh(init=init)]
 pub struct Challenge {
     pub body: ChallengeBody,
+    /// Epoch of the challenged block or chunk. Used to expire challenges
+    /// without decoding the body.
+    pub target_epoch_id: EpochId,
+    /// Height of the challenged block or chunk.
+    pub target_height: BlockHeight,
     pub account_id: AccountId,
     pub signature: Signature,
+    /// Signatures of other validators or fishermen over the same hash, so that
+    /// several accounts which detected the same misbehavior independently can
+    /// submit a single challenge. Rewards go to `account_id` only. The hash
+    /// covers the co-signers, so they can't be added or stripped by relayers.
+    pub co_signatures: Vec<(AccountId, Signature)>,
 
     #[borsh(skip)]
     pub hash: CryptoHash,
@@ -120,18 +223,145 @@ pub struct Challenge {
 
 impl Challenge {
     pub fn init(&mut self) {
-        self.hash = CryptoHash::hash_borsh(&self.body);
+        let co_signers: Vec<_> =
+            self.co_signatures.iter().map(|(account_id, _)| account_id).collect();
+        self.hash =
+            Self::compute_hash(&self.body, &self.target_epoch_id, self.target_height, &co_signers);
+    }
+
+    fn compute_hash(
+        body: &ChallengeBody,
+        target_epoch_id: &EpochId,
+        target_height: BlockHeight,
+        co_signers: &[&AccountId],
+    ) -> CryptoHash {
+        CryptoHash::hash_borsh((body, target_epoch_id, target_height, co_signers))
+    }
+
+    pub fn produce(
+        body: ChallengeBody,
+        target_epoch_id: EpochId,
+        target_height: BlockHeight,
+        signer: &ValidatorSigner,
+    ) -> Self {
+        Self::produce_co_signed(body, target_epoch_id, target_height, signer, &[])
+    }
+
+    /// Produces a challenge signed by `signer` as well as by all `co_signers`,
+    /// see `ProtocolFeature::ChallengeCoSignatures`.
+    pub fn produce_co_signed(
+        body: ChallengeBody,
+        target_epoch_id: EpochId,
+        target_height: BlockHeight,
+        signer: &ValidatorSigner,
+        co_signers: &[&ValidatorSigner],
+    ) -> Self {
+        let co_signer_ids: Vec<_> = co_signers.iter().map(|s| s.validator_id()).collect();
+        let hash = Self::compute_hash(&body, &target_epoch_id, target_height, &co_signer_ids);
+        let co_signatures = co_signers
+            .iter()
+            .map(|s| (s.validator_id().clone(), s.sign_bytes(hash.as_ref())))
+            .collect();
+        Self {
+            body,
+            target_epoch_id,
+            target_height,
+            account_id: signer.validator_id().clone(),
+            signature: signer.sign_bytes(hash.as_ref()),
+            co_signatures,
+            hash,
+        }
+    }
+
+    /// Returns all accounts which signed the challenge along with their
+    /// signatures, starting with the submitter.
+    pub fn signatures(&self) -> impl Iterator<Item = (&AccountId, &Signature)> {
+        std::iter::once((&self.account_id, &self.signature))
+            .chain(self.co_signatures.iter().map(|(account_id, signature)| (account_id, signature)))
     }
 
-    pub fn produce(body: ChallengeBody, signer: &ValidatorSigner) -> Self {
-        let hash = CryptoHash::hash_borsh(&body);
-        let signature = signer.sign_bytes(hash.as_ref());
-        Self { body, account_id: signer.validator_id().clone(), signature, hash }
+    /// Returns all accounts which signed the challenge, starting with the submitter.
+    pub fn signers(&self) -> impl Iterator<Item = &AccountId> {
+        self.signatures().map(|(account_id, _)| account_id)
+    }
+}
+
+/// Challenges are serialized to JSON as base64-encoded borsh, e.g. when
+/// submitted through the JSON-RPC.
+impl serde::Serialize for Challenge {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let challenge_borsh = borsh::to_vec(self).map_err(|err| {
+            S::Error::custom(&format!("the value could not be borsh encoded due to: {}", err))
+        })?;
+        serializer.serialize_str(&to_base64(&challenge_borsh))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Challenge {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let challenge_base64 = <String as serde::Deserialize>::deserialize(deserializer)?;
+        let challenge_borsh = from_base64(&challenge_base64).map_err(|err| {
+            D::Error::custom(&format!("the value could not decoded from base64 due to: {}", err))
+        })?;
+        borsh::from_slice::<Self>(&challenge_borsh).map_err(|err| {
+            D::Error::custom(&format!("the value could not decoded from borsh due to: {}", err))
+        })
     }
 }
 
 pub type Challenges = Vec<Challenge>;
 
+/// Challenge persisted by the client in `DBCol::Challenges` so that pending
+/// evidence of misbehavior survives a node restart.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug, ProtocolSchema)]
+pub struct StoredChallenge {
+    /// Height of the chain head when the challenge was received. The challenge
+    /// is garbage collected once this height falls behind the GC stop height.
+    pub received_height: BlockHeight,
+    pub challenge: Challenge,
+}
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+#[serde(untagged)]
+pub enum SlashedValidator {
+    V1(SlashedValidatorV1),
+    /// Records the challenger, since `ChallengerReward`.
+    V2(SlashedValidatorV2),
+}
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+#[serde(deny_unknown_fields)]
+pub struct SlashedValidatorV1 {
+    pub account_id: AccountId,
+    pub severity: SlashingSeverity,
+}
+
 #[derive(
     BorshSerialize,
     BorshDeserialize,
@@ -143,17 +373,198 @@ pub type Challenges = Vec<Challenge>;
     serde::Deserialize,
     ProtocolSchema,
 )]
-pub struct SlashedValidator {
+pub struct SlashedValidatorV2 {
     pub account_id: AccountId,
-    pub is_double_sign: bool,
+    pub severity: SlashingSeverity,
+    /// Account which submitted the challenge that led to slashing, if it is
+    /// eligible for the challenger reward.
+    pub challenger: Option<AccountId>,
 }
 
 impl SlashedValidator {
-    pub fn new(account_id: AccountId, is_double_sign: bool) -> Self {
-        SlashedValidator { account_id, is_double_sign }
+    pub fn new(account_id: AccountId, severity: SlashingSeverity) -> Self {
+        SlashedValidator::V1(SlashedValidatorV1 { account_id, severity })
+    }
+
+    /// Records the account which submitted the challenge if challengers are
+    /// rewarded in the given protocol version.
+    pub fn with_challenger(self, challenger: AccountId, protocol_version: ProtocolVersion) -> Self {
+        if !ProtocolFeature::ChallengerReward.enabled(protocol_version) {
+            return self;
+        }
+        let (account_id, severity) = match self {
+            SlashedValidator::V1(v1) => (v1.account_id, v1.severity),
+            SlashedValidator::V2(v2) => (v2.account_id, v2.severity),
+        };
+        SlashedValidator::V2(SlashedValidatorV2 {
+            account_id,
+            severity,
+            challenger: Some(challenger),
+        })
     }
+
+    #[inline]
+    pub fn account_id(&self) -> &AccountId {
+        match self {
+            SlashedValidator::V1(v1) => &v1.account_id,
+            SlashedValidator::V2(v2) => &v2.account_id,
+        }
+    }
+
+    #[inline]
+    pub fn severity(&self) -> SlashingSeverity {
+        match self {
+            SlashedValidator::V1(v1) => v1.severity,
+            SlashedValidator::V2(v2) => v2.severity,
+        }
+    }
+
+    #[inline]
+    pub fn challenger(&self) -> Option<&AccountId> {
+        match self {
+            SlashedValidator::V1(_) => None,
+            SlashedValidator::V2(v2) => v2.challenger.as_ref(),
+        }
+    }
+}
+
+/// Kind of misbehavior a validator is slashed for. Determines which fraction of
+/// the stake is burnt, see `SlashingFractions` in `EpochConfig`.
+///
+/// The first two variants are borsh-compatible with the `is_double_sign` flag
+/// which was used before.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Debug,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+pub enum SlashingSeverity {
+    /// All other cases, e.g. sending an invalid challenge. The whole stake is slashed.
+    Other,
+    /// Signing two different blocks at the same height.
+    DoubleSign,
+    /// Producing a chunk which is invalid or can't be decoded.
+    InvalidChunk,
+    /// Producing or endorsing an invalid state witness.
+    InvalidWitness,
 }
 
 /// Result of checking challenge, contains which accounts to slash.
 /// If challenge is invalid this is sender, otherwise author of chunk (and possibly other participants that signed invalid blocks).
 pub type ChallengesResult = Vec<SlashedValidator>;
+
+/// `Arbitrary` implementations used to fuzz decoding and validation of
+/// challenges. Chunks and chunk headers contained in challenges are built
+/// through their constructors so that the generated values are well-formed,
+/// while headers of blocks are arbitrary bytes. `InvalidStateWitness`
+/// challenges are not generated.
+#[cfg(feature = "test_features")]
+mod arbitrary_impls {
+    use super::*;
+    use crate::sharding::{ShardChunkHeaderV3, ShardChunkV2};
+    use crate::types::ShardId;
+    use crate::validator_signer::EmptyValidatorSigner;
+    use crate::version::PROTOCOL_VERSION;
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    /// Picks a signer from a small set of accounts, so that generated
+    /// challenges refer to the validators of test setups.
+    fn arbitrary_signer(u: &mut Unstructured<'_>) -> Result<ValidatorSigner> {
+        let account_id = format!("test{}", u.int_in_range(0..=3)?).parse().unwrap();
+        Ok(EmptyValidatorSigner::new(account_id))
+    }
+
+    fn arbitrary_chunk_header(u: &mut Unstructured<'_>) -> Result<ShardChunkHeader> {
+        let signer = arbitrary_signer(u)?;
+        Ok(ShardChunkHeader::V3(ShardChunkHeaderV3::new(
+            PROTOCOL_VERSION,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            ShardId::new(u.int_in_range(0..=3)?),
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            vec![],
+            None,
+            None,
+            None,
+            None,
+            None,
+            &signer,
+        )))
+    }
+
+    fn arbitrary_chunk(u: &mut Unstructured<'_>) -> Result<ShardChunk> {
+        let header = arbitrary_chunk_header(u)?;
+        Ok(ShardChunk::V2(ShardChunkV2 {
+            chunk_hash: header.chunk_hash(),
+            header,
+            transactions: vec![],
+            prev_outgoing_receipts: vec![],
+        }))
+    }
+
+    impl<'a> Arbitrary<'a> for ChunkProofs {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(ChunkProofs {
+                block_header: u.arbitrary()?,
+                merkle_proof: u.arbitrary()?,
+                chunk: Box::new(MaybeEncodedShardChunk::Decoded(arbitrary_chunk(u)?)),
+            })
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for ChunkState {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(ChunkState {
+                prev_block_header: u.arbitrary()?,
+                block_header: u.arbitrary()?,
+                prev_merkle_proof: u.arbitrary()?,
+                prev_chunk: arbitrary_chunk(u)?,
+                merkle_proof: u.arbitrary()?,
+                chunk_header: arbitrary_chunk_header(u)?,
+                partial_state: u.arbitrary()?,
+            })
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for ChallengeBody {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(match u.int_in_range(0..=3)? {
+                0 => ChallengeBody::BlockDoubleSign(u.arbitrary()?),
+                1 => ChallengeBody::ChunkProofs(u.arbitrary()?),
+                2 => ChallengeBody::ChunkState(u.arbitrary()?),
+                _ => ChallengeBody::ChunkDoubleSign(ChunkDoubleSign {
+                    left_chunk_header: arbitrary_chunk_header(u)?,
+                    right_chunk_header: arbitrary_chunk_header(u)?,
+                }),
+            })
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for Challenge {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let body = u.arbitrary()?;
+            let signer = arbitrary_signer(u)?;
+            let mut challenge = Challenge::produce(body, u.arbitrary()?, u.arbitrary()?, &signer);
+            for _ in 0..u.int_in_range(0..=2)? {
+                challenge.co_sign(&arbitrary_signer(u)?);
+            }
+            Ok(challenge)
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_crypto::{PublicKey, SecretKey, Signature};
+use near_primitives_core::types::BlockHeight;
+use near_schema_checker_lib::ProtocolSchema;
+
+use crate::block::Tip;
+use crate::hash::CryptoHash;
+use crate::shard_layout::ShardUId;
+
+/// Flat storage head of a shard at the time of a checkpoint.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq, ProtocolSchema)]
+pub struct FlatStorageHead {
+    pub shard_uid: ShardUId,
+    pub block_hash: CryptoHash,
+    pub height: BlockHeight,
+}
+
+/// State of the chain recorded periodically by the node, which allows to
+/// check on startup that the store is consistent with what the node had
+/// written before it stopped.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, ProtocolSchema)]
+pub struct ChainCheckpoint {
+    pub head: Tip,
+    pub final_head: Tip,
+    /// Heads of the flat storages which were ready.
+    pub flat_storage_heads: Vec<FlatStorageHead>,
+    /// Hash of the stored epoch sync proof, if any.
+    pub epoch_sync_proof_hash: Option<CryptoHash>,
+}
+
+/// Checkpoint signed with the node key of the operator. Stored in
+/// `DBCol::BlockMisc`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, ProtocolSchema)]
+pub struct SignedChainCheckpoint {
+    pub checkpoint: ChainCheckpoint,
+    pub public_key: PublicKey,
+    pub signature: Signature,
+}
+
+impl SignedChainCheckpoint {
+    pub fn new(checkpoint: ChainCheckpoint, secret_key: &SecretKey) -> Self {
+        let signature = secret_key.sign(CryptoHash::hash_borsh(&checkpoint).as_bytes());
+        Self { checkpoint, public_key: secret_key.public_key(), signature }
+    }
+
+    /// Checks that the checkpoint was signed by the owner of `public_key`.
+    pub fn verify(&self) -> bool {
+        self.signature.verify(CryptoHash::hash_borsh(&self.checkpoint).as_bytes(), &self.public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChainCheckpoint, SignedChainCheckpoint};
+    use crate::block::Tip;
+    use crate::hash::CryptoHash;
+    use crate::types::EpochId;
+    use near_crypto::{KeyType, SecretKey};
+
+    #[test]
+    fn test_signed_chain_checkpoint() {
+        let tip = Tip {
+            height: 10,
+            last_block_hash: CryptoHash::hash_bytes(b"block"),
+            prev_block_hash: CryptoHash::default(),
+            epoch_id: EpochId::default(),
+            next_epoch_id: EpochId::default(),
+        };
+        let checkpoint = ChainCheckpoint {
+            head: tip.clone(),
+            final_head: tip,
+            flat_storage_heads: vec![],
+            epoch_sync_proof_hash: None,
+        };
+        let secret_key = SecretKey::from_seed(KeyType::ED25519, "node");
+        let mut signed = SignedChainCheckpoint::new(checkpoint, &secret_key);
+        assert!(signed.verify());
+        signed.checkpoint.head.height += 1;
+        assert!(!signed.verify());
+    }
+}
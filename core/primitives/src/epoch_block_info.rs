@@ -1,5 +1,5 @@
 use crate::block_header::BlockHeader;
-use crate::challenge::SlashedValidator;
+use crate::challenge::{SlashedValidator, SlashingSeverity};
 use crate::stateless_validation::chunk_endorsements_bitmap::ChunkEndorsementsBitmap;
 use crate::types::validator_stake::{ValidatorStake, ValidatorStakeIter};
 use crate::types::{AccountId, EpochId, ValidatorStakeV1};
@@ -17,11 +17,12 @@ pub enum BlockInfo {
     V1(BlockInfoV1),
     V2(BlockInfoV2),
     V3(BlockInfoV3),
+    V4(BlockInfoV4),
 }
 
 impl Default for BlockInfo {
     fn default() -> Self {
-        Self::V3(BlockInfoV3::default())
+        Self::V4(BlockInfoV4::default())
     }
 }
 
@@ -40,60 +41,30 @@ impl BlockInfo {
         timestamp_nanosec: u64,
         chunk_endorsements: Option<ChunkEndorsementsBitmap>,
     ) -> Self {
-        if let Some(chunk_endorsements) = chunk_endorsements {
-            Self::V3(BlockInfoV3 {
-                hash,
-                height,
-                last_finalized_height,
-                last_final_block_hash,
-                prev_hash,
-                proposals,
-                chunk_mask: validator_mask,
-                latest_protocol_version,
-                slashed: slashed
-                    .into_iter()
-                    .map(|s| {
-                        let slash_state = if s.is_double_sign {
-                            SlashState::DoubleSign
-                        } else {
-                            SlashState::Other
-                        };
-                        (s.account_id, slash_state)
-                    })
-                    .collect(),
-                total_supply,
-                epoch_first_block: Default::default(),
-                epoch_id: Default::default(),
-                timestamp_nanosec,
-                chunk_endorsements,
-            })
-        } else {
-            Self::V2(BlockInfoV2 {
-                hash,
-                height,
-                last_finalized_height,
-                last_final_block_hash,
-                prev_hash,
-                proposals,
-                chunk_mask: validator_mask,
-                latest_protocol_version,
-                slashed: slashed
-                    .into_iter()
-                    .map(|s| {
-                        let slash_state = if s.is_double_sign {
-                            SlashState::DoubleSign
-                        } else {
-                            SlashState::Other
-                        };
-                        (s.account_id, slash_state)
-                    })
-                    .collect(),
-                total_supply,
-                epoch_first_block: Default::default(),
-                epoch_id: Default::default(),
-                timestamp_nanosec,
-            })
-        }
+        let challengers = slashed
+            .iter()
+            .filter_map(|s| Some((s.account_id().clone(), s.challenger()?.clone())))
+            .collect();
+        Self::V4(BlockInfoV4 {
+            hash,
+            height,
+            last_finalized_height,
+            last_final_block_hash,
+            prev_hash,
+            proposals,
+            chunk_mask: validator_mask,
+            latest_protocol_version,
+            slashed: slashed
+                .iter()
+                .map(|s| (s.account_id().clone(), s.severity().into()))
+                .collect(),
+            total_supply,
+            epoch_first_block: Default::default(),
+            epoch_id: Default::default(),
+            timestamp_nanosec,
+            chunk_endorsements,
+            challengers,
+        })
     }
 
     pub fn from_header(header: &BlockHeader, last_finalized_height: BlockHeight) -> Self {
@@ -141,6 +112,7 @@ impl BlockInfo {
             Self::V1(info) => ValidatorStakeIter::v1(&info.proposals),
             Self::V2(info) => ValidatorStakeIter::new(&info.proposals),
             Self::V3(info) => ValidatorStakeIter::new(&info.proposals),
+            Self::V4(info) => ValidatorStakeIter::new(&info.proposals),
         }
     }
 
@@ -150,6 +122,7 @@ impl BlockInfo {
             Self::V1(info) => &info.hash,
             Self::V2(info) => &info.hash,
             Self::V3(info) => &info.hash,
+            Self::V4(info) => &info.hash,
         }
     }
 
@@ -159,6 +132,7 @@ impl BlockInfo {
             Self::V1(info) => info.height,
             Self::V2(info) => info.height,
             Self::V3(info) => info.height,
+            Self::V4(info) => info.height,
         }
     }
 
@@ -168,6 +142,7 @@ impl BlockInfo {
             Self::V1(info) => info.last_finalized_height,
             Self::V2(info) => info.last_finalized_height,
             Self::V3(info) => info.last_finalized_height,
+            Self::V4(info) => info.last_finalized_height,
         }
     }
 
@@ -177,6 +152,7 @@ impl BlockInfo {
             Self::V1(info) => &info.last_final_block_hash,
             Self::V2(info) => &info.last_final_block_hash,
             Self::V3(info) => &info.last_final_block_hash,
+            Self::V4(info) => &info.last_final_block_hash,
         }
     }
 
@@ -186,6 +162,7 @@ impl BlockInfo {
             Self::V1(info) => &info.prev_hash,
             Self::V2(info) => &info.prev_hash,
             Self::V3(info) => &info.prev_hash,
+            Self::V4(info) => &info.prev_hash,
         }
     }
 
@@ -200,6 +177,7 @@ impl BlockInfo {
             Self::V1(info) => &info.epoch_first_block,
             Self::V2(info) => &info.epoch_first_block,
             Self::V3(info) => &info.epoch_first_block,
+            Self::V4(info) => &info.epoch_first_block,
         }
     }
 
@@ -209,6 +187,7 @@ impl BlockInfo {
             Self::V1(info) => &mut info.epoch_first_block,
             Self::V2(info) => &mut info.epoch_first_block,
             Self::V3(info) => &mut info.epoch_first_block,
+            Self::V4(info) => &mut info.epoch_first_block,
         }
     }
 
@@ -218,6 +197,7 @@ impl BlockInfo {
             Self::V1(info) => &info.epoch_id,
             Self::V2(info) => &info.epoch_id,
             Self::V3(info) => &info.epoch_id,
+            Self::V4(info) => &info.epoch_id,
         }
     }
 
@@ -227,6 +207,7 @@ impl BlockInfo {
             Self::V1(info) => &mut info.epoch_id,
             Self::V2(info) => &mut info.epoch_id,
             Self::V3(info) => &mut info.epoch_id,
+            Self::V4(info) => &mut info.epoch_id,
         }
     }
 
@@ -236,6 +217,7 @@ impl BlockInfo {
             Self::V1(info) => &info.chunk_mask,
             Self::V2(info) => &info.chunk_mask,
             Self::V3(info) => &info.chunk_mask,
+            Self::V4(info) => &info.chunk_mask,
         }
     }
 
@@ -245,6 +227,7 @@ impl BlockInfo {
             Self::V1(info) => &info.latest_protocol_version,
             Self::V2(info) => &info.latest_protocol_version,
             Self::V3(info) => &info.latest_protocol_version,
+            Self::V4(info) => &info.latest_protocol_version,
         }
     }
 
@@ -254,6 +237,7 @@ impl BlockInfo {
             Self::V1(info) => &info.slashed,
             Self::V2(info) => &info.slashed,
             Self::V3(info) => &info.slashed,
+            Self::V4(info) => &info.slashed,
         }
     }
 
@@ -263,6 +247,7 @@ impl BlockInfo {
             Self::V1(info) => &mut info.slashed,
             Self::V2(info) => &mut info.slashed,
             Self::V3(info) => &mut info.slashed,
+            Self::V4(info) => &mut info.slashed,
         }
     }
 
@@ -272,6 +257,7 @@ impl BlockInfo {
             Self::V1(info) => &info.total_supply,
             Self::V2(info) => &info.total_supply,
             Self::V3(info) => &info.total_supply,
+            Self::V4(info) => &info.total_supply,
         }
     }
 
@@ -281,6 +267,7 @@ impl BlockInfo {
             Self::V1(info) => &info.timestamp_nanosec,
             Self::V2(info) => &info.timestamp_nanosec,
             Self::V3(info) => &info.timestamp_nanosec,
+            Self::V4(info) => &info.timestamp_nanosec,
         }
     }
 
@@ -290,10 +277,64 @@ impl BlockInfo {
             Self::V1(_) => None,
             Self::V2(_) => None,
             Self::V3(info) => Some(&info.chunk_endorsements),
+            Self::V4(info) => info.chunk_endorsements.as_ref(),
+        }
+    }
+
+    /// Accounts whose challenges led to slashing of validators, keyed by the slashed account.
+    /// Only tracked since `BlockInfoV4`.
+    #[inline]
+    pub fn challengers(&self) -> Option<&HashMap<AccountId, AccountId>> {
+        match self {
+            Self::V1(_) | Self::V2(_) | Self::V3(_) => None,
+            Self::V4(info) => Some(&info.challengers),
+        }
+    }
+
+    #[inline]
+    pub fn challengers_mut(&mut self) -> Option<&mut HashMap<AccountId, AccountId>> {
+        match self {
+            Self::V1(_) | Self::V2(_) | Self::V3(_) => None,
+            Self::V4(info) => Some(&mut info.challengers),
         }
     }
 }
 
+// V3 -> V4: Make chunk_endorsements optional and add challengers
+#[derive(
+    Default,
+    BorshSerialize,
+    BorshDeserialize,
+    Eq,
+    PartialEq,
+    Clone,
+    Debug,
+    serde::Serialize,
+    ProtocolSchema,
+)]
+pub struct BlockInfoV4 {
+    pub hash: CryptoHash,
+    pub height: BlockHeight,
+    pub last_finalized_height: BlockHeight,
+    pub last_final_block_hash: CryptoHash,
+    pub prev_hash: CryptoHash,
+    pub epoch_first_block: CryptoHash,
+    pub epoch_id: EpochId,
+    pub proposals: Vec<ValidatorStake>,
+    pub chunk_mask: Vec<bool>,
+    /// Latest protocol version this validator observes.
+    pub latest_protocol_version: ProtocolVersion,
+    /// Validators slashed since the start of epoch or in previous epoch.
+    pub slashed: HashMap<AccountId, SlashState>,
+    /// Total supply at this block.
+    pub total_supply: Balance,
+    pub timestamp_nanosec: u64,
+    pub chunk_endorsements: Option<ChunkEndorsementsBitmap>,
+    /// Account which submitted the challenge for each validator slashed since the start of
+    /// epoch. Used to reward challengers at the end of the epoch.
+    pub challengers: HashMap<AccountId, AccountId>,
+}
+
 // V2 -> V3: Add chunk_endorsements bitmap
 #[derive(
     Default,
@@ -412,12 +453,8 @@ impl BlockInfoV1 {
             chunk_mask: validator_mask,
             latest_protocol_version,
             slashed: slashed
-                .into_iter()
-                .map(|s| {
-                    let slash_state =
-                        if s.is_double_sign { SlashState::DoubleSign } else { SlashState::Other };
-                    (s.account_id, slash_state)
-                })
+                .iter()
+                .map(|s| (s.account_id().clone(), s.severity().into()))
                 .collect(),
             total_supply,
             epoch_first_block: Default::default(),
@@ -438,4 +475,27 @@ pub enum SlashState {
     AlreadySlashed,
     /// All other cases (tokens should be entirely slashed),
     Other,
+    /// Invalid chunk, will be partially slashed at the end of the epoch.
+    InvalidChunk,
+    /// Invalid state witness, will be partially slashed at the end of the epoch.
+    InvalidWitness,
+}
+
+impl SlashState {
+    /// Whether the stake is slashed at the end of the epoch by a fraction
+    /// depending on the kind of misbehavior, rather than immediately in full.
+    pub fn is_partial(&self) -> bool {
+        matches!(self, Self::DoubleSign | Self::InvalidChunk | Self::InvalidWitness)
+    }
+}
+
+impl From<SlashingSeverity> for SlashState {
+    fn from(severity: SlashingSeverity) -> Self {
+        match severity {
+            SlashingSeverity::Other => Self::Other,
+            SlashingSeverity::DoubleSign => Self::DoubleSign,
+            SlashingSeverity::InvalidChunk => Self::InvalidChunk,
+            SlashingSeverity::InvalidWitness => Self::InvalidWitness,
+        }
+    }
 }
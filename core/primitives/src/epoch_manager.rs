@@ -1,3 +1,4 @@
+use crate::challenge::SlashingSeverity;
 use crate::num_rational::Rational32;
 use crate::shard_layout::ShardLayout;
 use crate::types::validator_stake::ValidatorStake;
@@ -75,6 +76,49 @@ pub struct EpochConfig {
     pub chunk_producer_assignment_changes_limit: NumSeats,
     // #[default(false)]
     pub shuffle_shard_assignment_for_chunk_producers: bool,
+    /// Fractions of stake slashed for each kind of misbehavior.
+    #[serde(default)]
+    pub slashing_fractions: SlashingFractions,
+}
+
+/// Fractions of stake slashed at the end of the epoch depending on the kind of
+/// misbehavior, see `SlashingSeverity`. Misbehavior which doesn't have a
+/// dedicated fraction is always slashed in full.
+#[derive(Clone, Eq, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SlashingFractions {
+    /// Multiplier applied to the double sign slashing amount, which itself
+    /// grows with the total stake slashed for double signing in the epoch.
+    pub double_sign: Rational32,
+    /// Fraction of stake slashed for producing an invalid chunk.
+    pub invalid_chunk: Rational32,
+    /// Fraction of stake slashed for producing or endorsing an invalid state witness.
+    pub invalid_witness: Rational32,
+    /// Fraction of the slashed stake credited to the account which submitted
+    /// the challenge, at the end of the epoch.
+    pub challenger_reward: Rational32,
+}
+
+impl Default for SlashingFractions {
+    fn default() -> Self {
+        Self {
+            double_sign: Rational32::from_integer(1),
+            invalid_chunk: Rational32::from_integer(1),
+            invalid_witness: Rational32::from_integer(1),
+            challenger_reward: Rational32::new(1, 10),
+        }
+    }
+}
+
+impl SlashingFractions {
+    /// Returns the fraction of stake slashed for given kind of misbehavior.
+    pub fn for_severity(&self, severity: SlashingSeverity) -> Rational32 {
+        match severity {
+            SlashingSeverity::Other => Rational32::from_integer(1),
+            SlashingSeverity::DoubleSign => self.double_sign,
+            SlashingSeverity::InvalidChunk => self.invalid_chunk,
+            SlashingSeverity::InvalidWitness => self.invalid_witness,
+        }
+    }
 }
 
 impl EpochConfig {
@@ -125,6 +169,7 @@ impl EpochConfig {
             minimum_stake_ratio: Rational32::new(160i32, 1_000_000i32),
             chunk_producer_assignment_changes_limit: 5,
             shuffle_shard_assignment_for_chunk_producers: false,
+            slashing_fractions: Default::default(),
         }
     }
 
@@ -153,6 +198,7 @@ impl EpochConfig {
             minimum_stake_ratio: Rational32::new(160i32, 1_000_000i32),
             chunk_producer_assignment_changes_limit: 5,
             shuffle_shard_assignment_for_chunk_producers: false,
+            slashing_fractions: Default::default(),
         }
     }
 
@@ -180,6 +226,7 @@ impl EpochConfig {
             minimum_stake_ratio: Rational32::new(160i32, 1_000_000i32),
             chunk_producer_assignment_changes_limit: 5,
             shuffle_shard_assignment_for_chunk_producers: false,
+            slashing_fractions: Default::default(),
         }
     }
 }
@@ -1,8 +1,9 @@
+use crate::action::GlobalContractIdentifier;
 use crate::hash::CryptoHash;
 use crate::serialize::dec_format;
 use crate::shard_layout::ShardLayoutError;
 use crate::sharding::ChunkHash;
-use crate::types::{AccountId, Balance, EpochId, Gas, Nonce};
+use crate::types::{AccountId, Balance, BlockHeight, EpochId, Gas, Nonce};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::PublicKey;
 use near_primitives_core::types::ProtocolVersion;
@@ -228,6 +229,13 @@ pub enum InvalidTxError {
     },
     /// Transaction version is invalid.
     InvalidTransactionVersion,
+    /// The gas price is higher than the maximum gas price set by the transaction.
+    GasPriceTooHigh {
+        #[serde(with = "dec_format")]
+        gas_price: Balance,
+        #[serde(with = "dec_format")]
+        max_gas_price: Balance,
+    },
     // Error occurred during storage access
     StorageError(StorageError),
     /// The receiver shard of the transaction is too congested to accept new
@@ -246,6 +254,16 @@ pub enum InvalidTxError {
         /// The number of blocks since the last included chunk of the shard.
         missed_chunks: u64,
     },
+    /// Signer account doesn't have enough balance of the token for the
+    /// TransferToken actions of the transaction.
+    NotEnoughTokenBalance {
+        signer_id: AccountId,
+        token_id: AccountId,
+        #[serde(with = "dec_format")]
+        balance: Balance,
+        #[serde(with = "dec_format")]
+        amount: Balance,
+    },
 }
 
 impl From<StorageError> for InvalidTxError {
@@ -335,6 +353,18 @@ pub enum ActionsValidationError {
     /// `ProtocolFeature` here because we don't want to leak the internals of
     /// that type into observable borsh serialization.
     UnsupportedProtocolFeature { protocol_feature: String, version: ProtocolVersion },
+    /// DelegateAction contains more nested DelegateActions than allowed.
+    DelegateActionNestingTooDeep { depth: u64, limit: u64 },
+    /// A field of the validator metadata in StakeWithMetadata action is too long.
+    ValidatorMetadataFieldTooLong { length: u64, limit: u64 },
+    /// MigrateState action is in a receipt with other actions.
+    MigrateStateMustBeOnlyAction,
+    /// The `contract_source_metadata` custom section of the contract code in a
+    /// DeployContract or MigrateState action is not valid NEP-330 metadata.
+    InvalidContractSourceMetadata { reason: String },
+    /// DelegateAction contains a TransferToken action. Token transfers are
+    /// debited from the signer of the transaction, so they can't be relayed.
+    TransferTokenInDelegateAction,
 }
 
 /// Describes the error for validating a receipt.
@@ -476,6 +506,25 @@ impl Display for ActionsValidationError {
                     protocol_feature,
                     version,
             ),
+            ActionsValidationError::DelegateActionNestingTooDeep { depth, limit } => write!(
+                f,
+                "DelegateAction nesting depth {} exceeds the limit {}",
+                depth, limit
+            ),
+            ActionsValidationError::ValidatorMetadataFieldTooLong { length, limit } => write!(
+                f,
+                "Validator metadata field of length {} exceeds the limit {}",
+                length, limit
+            ),
+            ActionsValidationError::MigrateStateMustBeOnlyAction => {
+                write!(f, "The MigrateState action must be the only action of the receipt")
+            }
+            ActionsValidationError::InvalidContractSourceMetadata { reason } => {
+                write!(f, "The contract source metadata embedded in the contract is not valid: {}", reason)
+            }
+            ActionsValidationError::TransferTokenInDelegateAction => {
+                write!(f, "DelegateAction can't contain TransferToken actions")
+            }
         }
     }
 }
@@ -593,6 +642,25 @@ pub enum ActionErrorKind {
     DelegateActionNonceTooLarge { delegate_nonce: Nonce, upper_bound: Nonce },
     /// Non-refundable storage transfer to an existing account is not allowed according to NEP-491.
     NonRefundableTransferToExistingAccount { account_id: AccountId },
+    /// The receipt wasn't executed before its expiry height. Deposits and prepaid gas are refunded.
+    ReceiptExpired { expiry_height: BlockHeight, block_height: BlockHeight },
+    /// Storage staking of the account is already sponsored by another account.
+    StorageSponsoredByAnotherAccount { account_id: AccountId, sponsor_id: AccountId },
+    /// The global contract used by the account isn't deployed (or hasn't reached the shard of
+    /// the account yet).
+    GlobalContractDoesNotExist { identifier: GlobalContractIdentifier },
+    /// The migration method called by a MigrateState action created receipts. Nothing may run
+    /// against the state of the account between the code replacement and the end of its
+    /// migration, so migrations can't call other contracts or schedule callbacks.
+    MigrationCreatedReceipts { account_id: AccountId },
+    /// The token balance of the receiver of a TransferToken action would overflow.
+    TokenBalanceOverflow { account_id: AccountId, token_id: AccountId },
+    /// The receiver of a TransferToken action doesn't hold a balance of the
+    /// token. Accounts opt in to a token by sending it to themselves.
+    TokenNotRegistered { account_id: AccountId, token_id: AccountId },
+    /// Account holds balances of more tokens than can be sent to the
+    /// beneficiary when it is deleted.
+    DeleteAccountWithTooManyTokens { account_id: AccountId, limit: u64 },
 }
 
 impl From<ActionErrorKind> for ActionError {
@@ -657,6 +725,11 @@ impl Display for InvalidTxError {
             InvalidTxError::InvalidTransactionVersion => {
                 write!(f, "Transaction version is invalid")
             }
+            InvalidTxError::GasPriceTooHigh { gas_price, max_gas_price } => write!(
+                f,
+                "Gas price {} is higher than the maximum gas price {} of the transaction",
+                gas_price, max_gas_price
+            ),
             InvalidTxError::StorageError(error) => {
                 write!(f, "Storage error: {}", error)
             }
@@ -669,6 +742,12 @@ impl Display for InvalidTxError {
                     "Shard {shard_id} missed {missed_chunks} chunks and rejects new transactions."
                 )
             }
+            InvalidTxError::NotEnoughTokenBalance { signer_id, token_id, balance, amount } => {
+                write!(
+                    f,
+                    "Sender {signer_id:?} does not have enough balance {balance} of token {token_id:?} to transfer {amount}"
+                )
+            }
         }
     }
 }
@@ -936,6 +1015,13 @@ impl Display for ActionErrorKind {
             ActionErrorKind::NonRefundableTransferToExistingAccount { account_id} => {
                 write!(f, "Can't make non-refundable storage transfer to {} because it already exists", account_id)
             }
+            ActionErrorKind::ReceiptExpired { expiry_height, block_height } => write!(f, "Receipt expired at height {} and can't be executed at height {}", expiry_height, block_height),
+            ActionErrorKind::StorageSponsoredByAnotherAccount { account_id, sponsor_id } => write!(f, "Storage of account {} is already sponsored by {}", account_id, sponsor_id),
+            ActionErrorKind::GlobalContractDoesNotExist { identifier } => write!(f, "Global contract {:?} does not exist", identifier),
+            ActionErrorKind::MigrationCreatedReceipts { account_id } => write!(f, "The migration of the state of {} created receipts", account_id),
+            ActionErrorKind::TokenBalanceOverflow { account_id, token_id } => write!(f, "The balance of token {} of account {} would overflow", token_id, account_id),
+            ActionErrorKind::TokenNotRegistered { account_id, token_id } => write!(f, "Account {} doesn't accept token {}, it must send the token to itself first", account_id, token_id),
+            ActionErrorKind::DeleteAccountWithTooManyTokens { account_id, limit } => write!(f, "Account {} holds balances of more than {} tokens and can't be deleted", account_id, limit),
         }
     }
 }
@@ -1076,6 +1162,29 @@ pub enum PrepareError {
     TooManyFunctions,
     /// Contract contains too many locals.
     TooManyLocals,
+    /// Contract contains too many memories.
+    TooManyMemories,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshDeserialize,
+    BorshSerialize,
+    serde::Deserialize,
+    serde::Serialize,
+    ProtocolSchema,
+)]
+/// Reason for which the static analysis rejected a contract as likely to be too slow to compile.
+pub enum StaticAnalysisError {
+    /// A function declares more locals than allowed.
+    TooManyLocalsPerFunction,
+    /// A table has more initial elements than allowed.
+    TableTooLarge,
+    /// Blocks of a function are nested deeper than allowed.
+    BlocksNestedTooDeep,
 }
 
 /// A kind of a trap happened during execution of a binary
@@ -1236,6 +1345,8 @@ pub enum CompilationError {
     WasmerCompileError {
         msg: String,
     },
+    /// The contract was rejected by the static analysis done before preparing it.
+    StaticAnalysisError(StaticAnalysisError),
 }
 
 /// Serializable version of `near-vm-runner::FunctionCallError`.
@@ -1289,3 +1400,94 @@ impl std::fmt::Display for ChunkAccessError {
 }
 
 impl std::error::Error for ChunkAccessError {}
+
+/// Reason why a block, chunk or challenge failed validation.
+///
+/// Serialized as a stable machine-readable code (e.g. `INVALID_SIGNATURE`), so
+/// that tools can tell different kinds of invalid data apart without parsing
+/// error messages. New variants may be added, existing ones must not be renamed.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    thiserror::Error,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PrimitiveValidationError {
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("invalid merkle proof")]
+    InvalidMerkleProof,
+    #[error("state root doesn't match the chunks")]
+    InvalidStateRoot,
+    #[error("outgoing receipts root doesn't match the chunks")]
+    InvalidReceiptRoot,
+    #[error("chunk headers root doesn't match the chunks")]
+    InvalidChunkHeaderRoot,
+    #[error("transaction or outcome root doesn't match the chunks")]
+    InvalidTransactionRoot,
+    #[error("chunk mask doesn't match the chunks")]
+    InvalidChunkMask,
+    #[error("challenges root doesn't match the challenges")]
+    InvalidChallengeRoot,
+    #[error("chunk header version is not valid for the protocol version")]
+    InvalidChunkHeaderVersion,
+    #[error("challenge doesn't prove misbehavior")]
+    InvalidChallenge,
+    #[error("challenge accuses validators which behaved correctly")]
+    MaliciousChallenge,
+    #[error("signer is not a validator")]
+    NotAValidator,
+    #[error("chunk is invalid")]
+    InvalidChunk,
+    #[error("chunk state witness is invalid")]
+    InvalidStateWitness,
+    #[error("invalid data")]
+    Other,
+}
+
+impl PrimitiveValidationError {
+    /// Machine-readable code of the error, the same as its serialized form.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidSignature => "INVALID_SIGNATURE",
+            Self::InvalidMerkleProof => "INVALID_MERKLE_PROOF",
+            Self::InvalidStateRoot => "INVALID_STATE_ROOT",
+            Self::InvalidReceiptRoot => "INVALID_RECEIPT_ROOT",
+            Self::InvalidChunkHeaderRoot => "INVALID_CHUNK_HEADER_ROOT",
+            Self::InvalidTransactionRoot => "INVALID_TRANSACTION_ROOT",
+            Self::InvalidChunkMask => "INVALID_CHUNK_MASK",
+            Self::InvalidChallengeRoot => "INVALID_CHALLENGE_ROOT",
+            Self::InvalidChunkHeaderVersion => "INVALID_CHUNK_HEADER_VERSION",
+            Self::InvalidChallenge => "INVALID_CHALLENGE",
+            Self::MaliciousChallenge => "MALICIOUS_CHALLENGE",
+            Self::NotAValidator => "NOT_A_VALIDATOR",
+            Self::InvalidChunk => "INVALID_CHUNK",
+            Self::InvalidStateWitness => "INVALID_STATE_WITNESS",
+            Self::Other => "OTHER",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrimitiveValidationError;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_validation_error_code_matches_serde_name() {
+        for error in PrimitiveValidationError::iter() {
+            let serialized = serde_json::to_value(error).unwrap();
+            assert_eq!(serialized, serde_json::Value::from(error.code()));
+            assert_eq!(
+                serde_json::from_value::<PrimitiveValidationError>(serialized).unwrap(),
+                error
+            );
+        }
+    }
+}
@@ -13,7 +13,9 @@ pub mod bandwidth_scheduler;
 pub mod block;
 pub mod block_body;
 pub mod block_header;
+pub mod block_headers_range;
 pub mod challenge;
+pub mod checkpoint;
 pub mod congestion_info;
 pub mod epoch_block_info;
 pub mod epoch_info;
@@ -37,12 +39,14 @@ pub mod state;
 pub mod state_part;
 pub mod state_record;
 pub mod state_sync;
+pub mod state_transition_proof;
 pub mod stateless_validation;
 pub mod telemetry;
 #[cfg(feature = "test_utils")]
 pub mod test_utils;
 pub mod transaction;
 pub mod trie_key;
+pub mod trie_proof;
 pub mod types;
 pub mod upgrade_schedule;
 pub mod utils;
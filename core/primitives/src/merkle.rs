@@ -14,6 +14,7 @@ use near_schema_checker_lib::ProtocolSchema;
     serde::Deserialize,
     ProtocolSchema,
 )]
+#[cfg_attr(feature = "test_features", derive(arbitrary::Arbitrary))]
 pub struct MerklePathItem {
     pub hash: MerkleHash,
     pub direction: Direction,
@@ -32,6 +33,7 @@ pub type MerklePath = Vec<MerklePathItem>;
     serde::Deserialize,
     ProtocolSchema,
 )]
+#[cfg_attr(feature = "test_features", derive(arbitrary::Arbitrary))]
 pub enum Direction {
     Left,
     Right,
@@ -138,6 +140,131 @@ pub fn compute_root_from_path_and_item<T: BorshSerialize>(
     compute_root_from_path(path, CryptoHash::hash_borsh(item))
 }
 
+/// Proof of inclusion of several leaves in a merkle tree built by `merklize`.
+/// Unlike a set of `MerklePath`s, hashes of subtrees shared by the paths of
+/// the proven leaves are included only once, and subtrees which can be
+/// computed from the proven leaves are omitted.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Default,
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct MerkleMultiProof {
+    /// Number of leaves in the tree.
+    pub num_leaves: u64,
+    /// Hashes of the sibling subtrees required to compute the root, level by
+    /// level from the bottom and from left to right within each level.
+    pub hashes: Vec<MerkleHash>,
+}
+
+/// Merklize an array of items and build a single proof of inclusion for the
+/// items at the given indices. Duplicated indices are ignored.
+pub fn merklize_multi<T: BorshSerialize>(
+    arr: &[T],
+    indices: &[usize],
+) -> (MerkleHash, MerkleMultiProof) {
+    let mut known = indices.to_vec();
+    known.sort_unstable();
+    known.dedup();
+    assert!(known.last().map_or(true, |index| *index < arr.len()), "leaf index out of bounds");
+
+    let mut level = arr.iter().map(CryptoHash::hash_borsh).collect::<Vec<_>>();
+    let mut hashes = vec![];
+    while level.len() > 1 {
+        let mut next_known = Vec::with_capacity(known.len());
+        let mut known_iter = known.into_iter().peekable();
+        while let Some(index) = known_iter.next() {
+            if index % 2 == 1 {
+                // Otherwise the left sibling would have been known and
+                // consumed this node already.
+                hashes.push(level[index - 1]);
+            } else if index + 1 < level.len() && known_iter.next_if_eq(&(index + 1)).is_none() {
+                hashes.push(level[index + 1]);
+            }
+            next_known.push(index / 2);
+        }
+        known = next_known;
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => combine_hash(left, right),
+                [hash] => *hash,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    let root = level.first().copied().unwrap_or_default();
+    (root, MerkleMultiProof { num_leaves: arr.len() as u64, hashes })
+}
+
+/// Verify merkle multiproof for given items and their indices in the tree.
+pub fn verify_multi_path<T: BorshSerialize>(
+    root: MerkleHash,
+    proof: &MerkleMultiProof,
+    items: &[(usize, T)],
+) -> bool {
+    let item_hashes = items
+        .iter()
+        .map(|(index, item)| (*index, CryptoHash::hash_borsh(item)))
+        .collect::<Vec<_>>();
+    verify_multi_hash(root, proof, &item_hashes)
+}
+
+pub fn verify_multi_hash(
+    root: MerkleHash,
+    proof: &MerkleMultiProof,
+    item_hashes: &[(usize, MerkleHash)],
+) -> bool {
+    compute_root_from_multi_proof(proof, item_hashes) == Some(root)
+}
+
+/// Computes the root of the tree from the proof and hashes of the proven
+/// items. Returns `None` if there are no items, if any index is duplicated or
+/// out of bounds, or if the number of hashes in the proof doesn't match.
+pub fn compute_root_from_multi_proof(
+    proof: &MerkleMultiProof,
+    item_hashes: &[(usize, MerkleHash)],
+) -> Option<MerkleHash> {
+    let mut level = item_hashes.to_vec();
+    level.sort_unstable_by_key(|(index, _)| *index);
+    let (last_index, _) = level.last()?;
+    if *last_index as u64 >= proof.num_leaves || level.windows(2).any(|pair| pair[0].0 == pair[1].0)
+    {
+        return None;
+    }
+
+    let mut proof_hashes = proof.hashes.iter();
+    let mut level_len = proof.num_leaves as usize;
+    while level_len > 1 {
+        let mut next_level = Vec::with_capacity(level.len());
+        let mut nodes = level.into_iter().peekable();
+        while let Some((index, hash)) = nodes.next() {
+            let parent_hash = if index % 2 == 1 {
+                combine_hash(proof_hashes.next()?, &hash)
+            } else if index + 1 == level_len {
+                hash
+            } else if let Some((_, right_hash)) = nodes.next_if(|(next, _)| *next == index + 1) {
+                combine_hash(&hash, &right_hash)
+            } else {
+                combine_hash(&hash, proof_hashes.next()?)
+            };
+            next_level.push((index / 2, parent_hash));
+        }
+        level = next_level;
+        level_len = (level_len + 1) / 2;
+    }
+    if proof_hashes.next().is_some() {
+        return None;
+    }
+    Some(level[0].1)
+}
+
 /// Merkle tree that only maintains the path for the next leaf, i.e,
 /// when a new leaf is inserted, the existing `path` is its proof.
 /// The root can be computed by folding `path` from right but is not explicitly
@@ -259,6 +386,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_merkle_multi_proof() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(1);
+        for _ in 0..20 {
+            let len = rng.gen_range(1..100);
+            let arr: Vec<u32> = (0..len).map(|_| rng.gen_range(0..1000)).collect();
+            let indices: Vec<usize> =
+                (0..rng.gen_range(1..=len)).map(|_| rng.gen_range(0..len)).collect();
+            let (root, proof) = merklize_multi(&arr, &indices);
+            let (expected_root, paths) = merklize(&arr);
+            assert_eq!(root, expected_root);
+            // Shared subtrees are not repeated, unlike in separate paths.
+            let paths_len: usize = indices.iter().map(|index| paths[*index].len()).sum();
+            assert!(proof.hashes.len() <= paths_len);
+
+            let mut items: Vec<_> = indices.iter().map(|index| (*index, arr[*index])).collect();
+            items.sort();
+            items.dedup();
+            assert!(verify_multi_path(root, &proof, &items));
+
+            // Changing any of the proven items breaks the proof.
+            let mut wrong_items = items.clone();
+            wrong_items[0].1 += 1;
+            assert!(!verify_multi_path(root, &proof, &wrong_items));
+        }
+    }
+
+    #[test]
+    fn test_incorrect_multi_proof() {
+        let items = vec![1, 2, 3, 4, 5];
+        let (root, proof) = merklize_multi(&items, &[1, 4]);
+        assert!(verify_multi_path(root, &proof, &[(4, 5), (1, 2)]));
+        // All proven items are required, and only once.
+        assert!(!verify_multi_path(root, &proof, &[(1, 2)]));
+        assert!(!verify_multi_path(root, &proof, &[(1, 2), (1, 2), (4, 5)]));
+        assert!(!verify_multi_path::<u32>(root, &proof, &[]));
+        // Extra hashes in the proof are rejected.
+        let mut extra_proof = proof.clone();
+        extra_proof.hashes.push(root);
+        assert!(!verify_multi_path(root, &extra_proof, &[(1, 2), (4, 5)]));
+        // A proof of all items doesn't contain any hashes.
+        let all_indices: Vec<usize> = (0..items.len()).collect();
+        let (_, full_proof) = merklize_multi(&items, &all_indices);
+        assert!(full_proof.hashes.is_empty());
+    }
+
     #[test]
     fn test_elements_order() {
         let items = vec![1, 2];
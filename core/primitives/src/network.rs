@@ -91,3 +91,29 @@ impl AnnounceAccount {
         CryptoHash::hash_borsh((account_id, peer_id, epoch_id))
     }
 }
+
+/// Whether other nodes are able to connect to this node, as far as it can tell.
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum::IntoStaticStr,
+    strum::EnumIter,
+)]
+pub enum Reachability {
+    /// The node doesn't accept inbound connections.
+    NotListening,
+    /// The node hasn't been on the network long enough to tell.
+    #[default]
+    Unknown,
+    /// Peers connected to the node recently, or a peer managed to dial back.
+    Reachable,
+    /// A peer failed to dial back, or the node is connected to peers but none of them
+    /// connected to it for a while. Most likely its address is behind a NAT or a firewall.
+    Unreachable,
+}
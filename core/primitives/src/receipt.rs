@@ -1,6 +1,8 @@
+use crate::action::GlobalContractIdentifier;
 use crate::hash::CryptoHash;
 use crate::serialize::dec_format;
-use crate::transaction::{Action, TransferAction};
+use crate::shard_layout::ShardLayout;
+use crate::transaction::{Action, TransferAction, TransferTokenAction};
 use crate::types::{AccountId, Balance, BlockHeight, ShardId};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::{KeyType, PublicKey};
@@ -85,11 +87,42 @@ pub struct ReceiptV1 {
     pub priority: u64,
 }
 
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+pub struct ReceiptV2 {
+    /// An issuer account_id of a particular receipt.
+    /// `predecessor_id` could be either `Transaction` `signer_id` or intermediate contract's `account_id`.
+    pub predecessor_id: AccountId,
+    /// `receiver_id` is a receipt destination.
+    pub receiver_id: AccountId,
+    /// An unique id for the receipt
+    pub receipt_id: CryptoHash,
+    /// A receipt type
+    pub receipt: ReceiptEnum,
+    /// Priority of a receipt
+    pub priority: u64,
+    /// The last block height at which the receipt can be executed.
+    pub expiry_height: BlockHeight,
+}
+
+/// Receipts are encoded in JSON without the version. When decoding, serde tries
+/// the variants in order and ignores unknown fields, so the newest version, with
+/// the most fields, must come first or its extra fields would be dropped.
 #[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize, ProtocolSchema)]
 #[serde(untagged)]
 pub enum Receipt {
-    V0(ReceiptV0),
+    V2(ReceiptV2),
     V1(ReceiptV1),
+    V0(ReceiptV0),
 }
 
 /// A receipt that is stored in the state with added metadata. A receipt may be
@@ -251,13 +284,17 @@ impl BorshSerialize for Receipt {
                 BorshSerialize::serialize(&1_u8, writer)?;
                 receipt.serialize(writer)
             }
+            Receipt::V2(receipt) => {
+                BorshSerialize::serialize(&2_u8, writer)?;
+                receipt.serialize(writer)
+            }
         }
     }
 }
 
 impl BorshDeserialize for Receipt {
     /// Deserialize based on the first and second bytes of the stream. For V0, we do backward compatible deserialization by deserializing
-    /// the entire stream into V0. For V1, we consume the first byte and then deserialize the rest.
+    /// the entire stream into V0. For V1 and V2, we consume the first byte (the version) and then deserialize the rest.
     fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
         // This is a ridiculous hackery: because the first field in `ReceiptV0` is an `AccountId`
         // and an account id is at most 64 bytes, for all valid `ReceiptV0` the second byte must be 0
@@ -273,10 +310,16 @@ impl BorshDeserialize for Receipt {
         let prefix = if is_v0 { vec![u1, u2] } else { vec![u2] };
         let mut reader = prefix.chain(reader);
 
-        let receipt = if is_v0 {
-            Receipt::V0(ReceiptV0::deserialize_reader(&mut reader)?)
-        } else {
-            Receipt::V1(ReceiptV1::deserialize_reader(&mut reader)?)
+        let receipt = match (is_v0, u1) {
+            (true, _) => Receipt::V0(ReceiptV0::deserialize_reader(&mut reader)?),
+            (false, 1) => Receipt::V1(ReceiptV1::deserialize_reader(&mut reader)?),
+            (false, 2) => Receipt::V2(ReceiptV2::deserialize_reader(&mut reader)?),
+            (false, version) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid receipt version {}", version),
+                ))
+            }
         };
         Ok(receipt)
     }
@@ -366,6 +409,7 @@ impl BorshDeserialize for ReceiptOrStateStoredReceipt<'_> {
         // The StateStored receipt has the tag as the first two bytes.
         // The Receipt::V0 has 0 as the second byte.
         // The Receipt::V1 has 1 as the first byte.
+        // The Receipt::V2 has 2 as the first byte.
         let u1 = u8::deserialize_reader(reader)?;
         let u2 = u8::deserialize_reader(reader)?;
 
@@ -385,7 +429,7 @@ impl BorshDeserialize for ReceiptOrStateStoredReceipt<'_> {
 }
 
 pub enum ReceiptPriority {
-    /// Used in ReceiptV1
+    /// Used in ReceiptV1 and ReceiptV2
     Priority(u64),
     /// Used in ReceiptV0
     NoPriority,
@@ -405,6 +449,7 @@ impl Borrow<CryptoHash> for Receipt {
         match self {
             Receipt::V0(receipt) => &receipt.receipt_id,
             Receipt::V1(receipt) => &receipt.receipt_id,
+            Receipt::V2(receipt) => &receipt.receipt_id,
         }
     }
 }
@@ -414,6 +459,7 @@ impl Receipt {
         match self {
             Receipt::V0(receipt) => &receipt.receiver_id,
             Receipt::V1(receipt) => &receipt.receiver_id,
+            Receipt::V2(receipt) => &receipt.receiver_id,
         }
     }
 
@@ -421,6 +467,7 @@ impl Receipt {
         match self {
             Receipt::V0(receipt) => receipt.receiver_id = receiver_id,
             Receipt::V1(receipt) => receipt.receiver_id = receiver_id,
+            Receipt::V2(receipt) => receipt.receiver_id = receiver_id,
         }
     }
 
@@ -428,6 +475,7 @@ impl Receipt {
         match self {
             Receipt::V0(receipt) => &receipt.predecessor_id,
             Receipt::V1(receipt) => &receipt.predecessor_id,
+            Receipt::V2(receipt) => &receipt.predecessor_id,
         }
     }
 
@@ -435,6 +483,7 @@ impl Receipt {
         match self {
             Receipt::V0(receipt) => receipt.predecessor_id = predecessor_id,
             Receipt::V1(receipt) => receipt.predecessor_id = predecessor_id,
+            Receipt::V2(receipt) => receipt.predecessor_id = predecessor_id,
         }
     }
 
@@ -442,6 +491,7 @@ impl Receipt {
         match self {
             Receipt::V0(receipt) => &receipt.receipt,
             Receipt::V1(receipt) => &receipt.receipt,
+            Receipt::V2(receipt) => &receipt.receipt,
         }
     }
 
@@ -449,6 +499,7 @@ impl Receipt {
         match self {
             Receipt::V0(receipt) => &mut receipt.receipt,
             Receipt::V1(receipt) => &mut receipt.receipt,
+            Receipt::V2(receipt) => &mut receipt.receipt,
         }
     }
 
@@ -456,6 +507,7 @@ impl Receipt {
         match self {
             Receipt::V0(receipt) => receipt.receipt,
             Receipt::V1(receipt) => receipt.receipt,
+            Receipt::V2(receipt) => receipt.receipt,
         }
     }
 
@@ -463,6 +515,7 @@ impl Receipt {
         match self {
             Receipt::V0(receipt) => &receipt.receipt_id,
             Receipt::V1(receipt) => &receipt.receipt_id,
+            Receipt::V2(receipt) => &receipt.receipt_id,
         }
     }
 
@@ -470,6 +523,7 @@ impl Receipt {
         match self {
             Receipt::V0(receipt) => receipt.receipt_id = receipt_id,
             Receipt::V1(receipt) => receipt.receipt_id = receipt_id,
+            Receipt::V2(receipt) => receipt.receipt_id = receipt_id,
         }
     }
 
@@ -477,6 +531,36 @@ impl Receipt {
         match self {
             Receipt::V0(_) => ReceiptPriority::NoPriority,
             Receipt::V1(receipt) => ReceiptPriority::Priority(receipt.priority),
+            Receipt::V2(receipt) => ReceiptPriority::Priority(receipt.priority),
+        }
+    }
+
+    /// The last block height at which the receipt can be executed, if the receipt expires.
+    pub fn expiry_height(&self) -> Option<BlockHeight> {
+        match self {
+            Receipt::V0(_) | Receipt::V1(_) => None,
+            Receipt::V2(receipt) => Some(receipt.expiry_height),
+        }
+    }
+
+    /// Whether the receipt can no longer be executed at the given block height.
+    pub fn is_expired(&self, block_height: BlockHeight) -> bool {
+        self.expiry_height().is_some_and(|expiry_height| expiry_height < block_height)
+    }
+
+    /// The shard the receipt has to be applied on. This is the shard of the
+    /// receiver, except for global contract distribution receipts, which are
+    /// sent to every shard in turn.
+    pub fn receiver_shard_id(&self, shard_layout: &ShardLayout) -> ShardId {
+        match self.receipt() {
+            // The target shard may no longer exist after resharding, the
+            // receipt then continues from the shard of its receiver.
+            ReceiptEnum::GlobalContractDistribution(receipt)
+                if shard_layout.shard_ids().any(|shard_id| shard_id == receipt.target_shard) =>
+            {
+                receipt.target_shard
+            }
+            _ => shard_layout.account_id_to_shard_id(self.receiver_id()),
         }
     }
 
@@ -493,35 +577,58 @@ impl Receipt {
         refund: Balance,
         priority: ReceiptPriority,
     ) -> Self {
+        Self::new_system_refund(
+            receiver_id,
+            Action::Transfer(TransferAction { deposit: refund }),
+            priority,
+        )
+    }
+
+    /// Generates a receipt from system giving back `refund` of the native token
+    /// `token_id` to `receiver_id`, without a receipt_id. It inherits priority from the
+    /// parent receipt.
+    pub fn new_token_refund(
+        receiver_id: &AccountId,
+        token_id: &AccountId,
+        refund: Balance,
+        priority: ReceiptPriority,
+    ) -> Self {
+        Self::new_system_refund(
+            receiver_id,
+            Action::TransferToken(Box::new(TransferTokenAction {
+                token_id: token_id.clone(),
+                amount: refund,
+            })),
+            priority,
+        )
+    }
+
+    fn new_system_refund(
+        receiver_id: &AccountId,
+        action: Action,
+        priority: ReceiptPriority,
+    ) -> Self {
+        let receipt = ReceiptEnum::Action(ActionReceipt {
+            signer_id: "system".parse().unwrap(),
+            signer_public_key: PublicKey::empty(KeyType::ED25519),
+            gas_price: 0,
+            output_data_receivers: vec![],
+            input_data_ids: vec![],
+            actions: vec![action],
+        });
         match priority {
             ReceiptPriority::Priority(priority) => Receipt::V1(ReceiptV1 {
                 predecessor_id: "system".parse().unwrap(),
                 receiver_id: receiver_id.clone(),
                 receipt_id: CryptoHash::default(),
-
-                receipt: ReceiptEnum::Action(ActionReceipt {
-                    signer_id: "system".parse().unwrap(),
-                    signer_public_key: PublicKey::empty(KeyType::ED25519),
-                    gas_price: 0,
-                    output_data_receivers: vec![],
-                    input_data_ids: vec![],
-                    actions: vec![Action::Transfer(TransferAction { deposit: refund })],
-                }),
+                receipt,
                 priority,
             }),
             ReceiptPriority::NoPriority => Receipt::V0(ReceiptV0 {
                 predecessor_id: "system".parse().unwrap(),
                 receiver_id: receiver_id.clone(),
                 receipt_id: CryptoHash::default(),
-
-                receipt: ReceiptEnum::Action(ActionReceipt {
-                    signer_id: "system".parse().unwrap(),
-                    signer_public_key: PublicKey::empty(KeyType::ED25519),
-                    gas_price: 0,
-                    output_data_receivers: vec![],
-                    input_data_ids: vec![],
-                    actions: vec![Action::Transfer(TransferAction { deposit: refund })],
-                }),
+                receipt,
             }),
         }
     }
@@ -590,6 +697,7 @@ pub enum ReceiptEnum {
     Data(DataReceipt),
     PromiseYield(ActionReceipt),
     PromiseResume(DataReceipt),
+    GlobalContractDistribution(GlobalContractDistributionReceipt),
 }
 
 /// ActionReceipt is derived from an Action from `Transaction or from Receipt`
@@ -653,6 +761,39 @@ impl fmt::Debug for DataReceipt {
     }
 }
 
+/// Carries the code of a global contract to the shards. The receipt is applied
+/// on `target_shard`, which stores the code and forwards the receipt to the
+/// next shard that hasn't received it yet.
+#[serde_as]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    serde::Serialize,
+    serde::Deserialize,
+    ProtocolSchema,
+)]
+pub struct GlobalContractDistributionReceipt {
+    pub id: GlobalContractIdentifier,
+    pub target_shard: ShardId,
+    pub already_delivered_shards: Vec<ShardId>,
+    #[serde_as(as = "Base64")]
+    pub code: Vec<u8>,
+}
+
+impl fmt::Debug for GlobalContractDistributionReceipt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlobalContractDistributionReceipt")
+            .field("id", &self.id)
+            .field("target_shard", &self.target_shard)
+            .field("already_delivered_shards", &self.already_delivered_shards)
+            .field("code", &format_args!("{}", AbbrBytes(&self.code)))
+            .finish()
+    }
+}
+
 /// A temporary data which is created by processing of DataReceipt
 /// stored in a state trie with a key = `account_id` + `data_id` until
 /// `input_data_ids` of all incoming Receipts are satisfied
@@ -796,6 +937,24 @@ mod tests {
         receipt_v1
     }
 
+    fn get_receipt_v2() -> Receipt {
+        Receipt::V2(ReceiptV2 {
+            predecessor_id: "predecessor_id".parse().unwrap(),
+            receiver_id: "receiver_id".parse().unwrap(),
+            receipt_id: CryptoHash::default(),
+            receipt: ReceiptEnum::Action(ActionReceipt {
+                signer_id: "signer_id".parse().unwrap(),
+                signer_public_key: PublicKey::empty(KeyType::ED25519),
+                gas_price: 0,
+                output_data_receivers: vec![],
+                input_data_ids: vec![],
+                actions: vec![Action::Transfer(TransferAction { deposit: 0 })],
+            }),
+            priority: 0,
+            expiry_height: 100,
+        })
+    }
+
     #[test]
     fn test_receipt_v0_serialization() {
         let receipt_v0 = get_receipt_v0();
@@ -812,6 +971,37 @@ mod tests {
         assert_eq!(receipt_v1, receipt2);
     }
 
+    #[test]
+    fn test_receipt_v2_serialization() {
+        let receipt_v2 = get_receipt_v2();
+        let serialized_receipt = borsh::to_vec(&receipt_v2).unwrap();
+        let receipt2 = Receipt::try_from_slice(&serialized_receipt).unwrap();
+        assert_eq!(receipt_v2, receipt2);
+
+        let receipt = Cow::Owned(receipt_v2);
+        let serialized_receipt = borsh::to_vec(&receipt).unwrap();
+        let deserialized_receipt =
+            ReceiptOrStateStoredReceipt::try_from_slice(&serialized_receipt).unwrap();
+        assert_eq!(ReceiptOrStateStoredReceipt::Receipt(receipt), deserialized_receipt);
+    }
+
+    #[test]
+    fn test_receipt_json_serialization() {
+        for receipt in [get_receipt_v0(), get_receipt_v1(), get_receipt_v2()] {
+            let json = serde_json::to_string(&receipt).unwrap();
+            assert_eq!(receipt, serde_json::from_str::<Receipt>(&json).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_receipt_expiry() {
+        assert!(!get_receipt_v1().is_expired(BlockHeight::MAX));
+        let receipt_v2 = get_receipt_v2();
+        assert_eq!(receipt_v2.expiry_height(), Some(100));
+        assert!(!receipt_v2.is_expired(100));
+        assert!(receipt_v2.is_expired(101));
+    }
+
     fn test_state_stored_receipt_serialization_impl(receipt: Receipt) {
         let metadata = StateStoredReceiptMetadata { congestion_gas: 42, congestion_size: 43 };
         let receipt = StateStoredReceipt::new_owned(receipt, metadata, PROTOCOL_VERSION);
@@ -1,6 +1,7 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use itertools::Itertools;
 use reed_solomon_erasure::galois_8::ReedSolomon;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::Error;
 use std::sync::Arc;
@@ -67,6 +68,33 @@ pub fn reed_solomon_num_data_parts(total_parts: usize, ratio_data_parts: f64) ->
     std::cmp::max((total_parts as f64 * ratio_data_parts) as usize, 1)
 }
 
+/// Smallest data part of a chunk encoded with adaptive encoding. Splitting a
+/// small chunk into many tiny parts only adds the overhead of the merkle
+/// proofs and of the messages, so such chunks use fewer data parts.
+pub const ADAPTIVE_MIN_PART_SIZE: usize = 4 * 1024;
+
+/// Number of data parts of a chunk of `encoded_length` bytes with adaptive
+/// encoding. Chunks smaller than `ADAPTIVE_MIN_PART_SIZE` have a single data
+/// part, i.e. every part is enough to reconstruct the chunk, and large chunks
+/// keep `max_data_parts`.
+pub fn reed_solomon_adaptive_num_data_parts(encoded_length: usize, max_data_parts: usize) -> usize {
+    encoded_length.div_ceil(ADAPTIVE_MIN_PART_SIZE).clamp(1, max_data_parts.max(1))
+}
+
+/// Returns a ReedSolomon with the same total number of parts as `rs` and
+/// `num_data_parts` data parts, reusing `rs` if it already has them.
+pub fn reed_solomon_with_data_parts(
+    rs: &ReedSolomon,
+    num_data_parts: usize,
+) -> Cow<'_, ReedSolomon> {
+    if rs.data_shard_count() == num_data_parts {
+        Cow::Borrowed(rs)
+    } else {
+        let total_parts = rs.total_shard_count();
+        Cow::Owned(ReedSolomon::new(num_data_parts, total_parts - num_data_parts).unwrap())
+    }
+}
+
 pub struct ReedSolomonEncoder {
     /// ReedSolomon does not support having exactly 1 total part count and
     /// no parity parts, so we use None for that
@@ -245,3 +273,32 @@ impl<T: ReedSolomonEncoderDeserialize> ReedSolomonPartsTracker<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_num_data_parts() {
+        assert_eq!(reed_solomon_adaptive_num_data_parts(0, 33), 1);
+        assert_eq!(reed_solomon_adaptive_num_data_parts(ADAPTIVE_MIN_PART_SIZE, 33), 1);
+        assert_eq!(reed_solomon_adaptive_num_data_parts(ADAPTIVE_MIN_PART_SIZE + 1, 33), 2);
+        assert_eq!(reed_solomon_adaptive_num_data_parts(100 * ADAPTIVE_MIN_PART_SIZE, 33), 33);
+    }
+
+    #[test]
+    fn test_single_data_part_decodes_from_any_part() {
+        let rs = ReedSolomon::new(33, 67).unwrap();
+        let data = vec![7u8; 1000];
+        let data_parts = reed_solomon_adaptive_num_data_parts(data.len(), rs.data_shard_count());
+        let rs = reed_solomon_with_data_parts(&rs, data_parts);
+        assert_eq!(rs.total_shard_count(), 100);
+        let (parts, encoded_length) = reed_solomon_encode(&rs, &data);
+        for part_ord in [0, 50, 99] {
+            let mut received = vec![None; parts.len()];
+            received[part_ord] = parts[part_ord].clone();
+            let decoded: Vec<u8> = reed_solomon_decode(&rs, &mut received, encoded_length).unwrap();
+            assert_eq!(decoded, data);
+        }
+    }
+}
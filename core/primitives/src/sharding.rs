@@ -1,18 +1,23 @@
-use crate::bandwidth_scheduler::BandwidthRequests;
+use crate::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 use crate::congestion_info::CongestionInfo;
 use crate::hash::{hash, CryptoHash};
 use crate::merkle::{combine_hash, merklize, verify_path, MerklePath};
 use crate::receipt::Receipt;
+use crate::state_transition_proof::StateTransitionCommitment;
 use crate::transaction::SignedTransaction;
 use crate::types::validator_stake::{ValidatorStake, ValidatorStakeIter, ValidatorStakeV1};
 use crate::types::{Balance, BlockHeight, Gas, MerkleHash, ShardId, StateRoot};
+use crate::utils::DetailedDebug;
 use crate::validator_signer::ValidatorSigner;
 use crate::version::{ProtocolFeature, ProtocolVersion, SHARD_CHUNK_HEADER_UPGRADE_VERSION};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::Signature;
 use near_fmt::AbbrBytes;
 use near_schema_checker_lib::ProtocolSchema;
-use shard_chunk_header_inner::ShardChunkHeaderInnerV4;
+use shard_chunk_header_inner::{
+    ShardChunkHeaderInnerV4, ShardChunkHeaderInnerV5, ShardChunkHeaderInnerV6,
+    ShardChunkHeaderInnerV7,
+};
 use std::cmp::Ordering;
 use std::sync::Arc;
 use tracing::debug_span;
@@ -264,9 +269,86 @@ impl ShardChunkHeaderV3 {
         prev_validator_proposals: Vec<ValidatorStake>,
         congestion_info: Option<CongestionInfo>,
         bandwidth_requests: Option<BandwidthRequests>,
+        prev_outgoing_bandwidth: Option<OutgoingBandwidth>,
+        prev_pre_state_root: Option<StateRoot>,
+        num_data_parts: Option<u64>,
         signer: &ValidatorSigner,
     ) -> Self {
-        let inner = if let Some(bandwidth_requests) = bandwidth_requests {
+        let inner = if let (Some(bandwidth_requests), Some(prev_outgoing_bandwidth)) =
+            (&bandwidth_requests, prev_outgoing_bandwidth)
+        {
+            // `prev_outgoing_bandwidth` can only be `Some` when chunk bandwidth commitment is enabled.
+            assert!(ProtocolFeature::ChunkBandwidthCommitment.enabled(protocol_version));
+            let congestion_info = congestion_info
+                .expect("Congestion info must exist when bandwidth scheduler is enabled");
+            if let Some(prev_pre_state_root) = prev_pre_state_root {
+                // `prev_pre_state_root` can only be `Some` when execution proofs are enabled.
+                assert!(ProtocolFeature::ExecutionProofs.enabled(protocol_version));
+                if let Some(num_data_parts) = num_data_parts {
+                    // `num_data_parts` can only be `Some` when adaptive chunk encoding is enabled.
+                    assert!(ProtocolFeature::AdaptiveChunkEncoding.enabled(protocol_version));
+                    ShardChunkHeaderInner::V7(ShardChunkHeaderInnerV7 {
+                        prev_block_hash,
+                        prev_state_root,
+                        prev_pre_state_root,
+                        prev_outcome_root,
+                        encoded_merkle_root,
+                        encoded_length,
+                        height_created: height,
+                        shard_id,
+                        prev_gas_used,
+                        gas_limit,
+                        prev_balance_burnt,
+                        prev_outgoing_receipts_root,
+                        tx_root,
+                        prev_validator_proposals,
+                        congestion_info,
+                        bandwidth_requests: bandwidth_requests.clone(),
+                        prev_outgoing_bandwidth,
+                        num_data_parts,
+                    })
+                } else {
+                    ShardChunkHeaderInner::V6(ShardChunkHeaderInnerV6 {
+                        prev_block_hash,
+                        prev_state_root,
+                        prev_pre_state_root,
+                        prev_outcome_root,
+                        encoded_merkle_root,
+                        encoded_length,
+                        height_created: height,
+                        shard_id,
+                        prev_gas_used,
+                        gas_limit,
+                        prev_balance_burnt,
+                        prev_outgoing_receipts_root,
+                        tx_root,
+                        prev_validator_proposals,
+                        congestion_info,
+                        bandwidth_requests: bandwidth_requests.clone(),
+                        prev_outgoing_bandwidth,
+                    })
+                }
+            } else {
+                ShardChunkHeaderInner::V5(ShardChunkHeaderInnerV5 {
+                    prev_block_hash,
+                    prev_state_root,
+                    prev_outcome_root,
+                    encoded_merkle_root,
+                    encoded_length,
+                    height_created: height,
+                    shard_id,
+                    prev_gas_used,
+                    gas_limit,
+                    prev_balance_burnt,
+                    prev_outgoing_receipts_root,
+                    tx_root,
+                    prev_validator_proposals,
+                    congestion_info,
+                    bandwidth_requests: bandwidth_requests.clone(),
+                    prev_outgoing_bandwidth,
+                })
+            }
+        } else if let Some(bandwidth_requests) = bandwidth_requests {
             // `bandwidth_requests` can only be `Some` when bandwidth scheduler is enabled.
             assert!(ProtocolFeature::BandwidthScheduler.enabled(protocol_version));
 
@@ -542,6 +624,69 @@ impl ShardChunkHeader {
         }
     }
 
+    /// Size of the previous chunk's outgoing receipts for every receiver shard,
+    /// if the header commits to it, `None` otherwise.
+    #[inline]
+    pub fn prev_outgoing_bandwidth(&self) -> Option<&OutgoingBandwidth> {
+        match self {
+            ShardChunkHeader::V1(_) | ShardChunkHeader::V2(_) => None,
+            ShardChunkHeader::V3(header) => header.inner.prev_outgoing_bandwidth(),
+        }
+    }
+
+    /// State root the previous chunk was applied to, if the header commits to
+    /// it, `None` otherwise.
+    #[inline]
+    pub fn prev_pre_state_root(&self) -> Option<&StateRoot> {
+        match self {
+            ShardChunkHeader::V1(_) | ShardChunkHeader::V2(_) => None,
+            ShardChunkHeader::V3(header) => header.inner.prev_pre_state_root(),
+        }
+    }
+
+    /// Number of data parts the chunk is encoded with, if recorded in the
+    /// header. Use [`ShardChunkHeader::num_data_parts_or`] to fall back to the
+    /// fixed number of data parts of the epoch.
+    #[inline]
+    pub fn num_data_parts(&self) -> Option<usize> {
+        match self {
+            ShardChunkHeader::V1(_) | ShardChunkHeader::V2(_) => None,
+            ShardChunkHeader::V3(header) => header.inner.num_data_parts(),
+        }
+    }
+
+    #[inline]
+    pub fn num_data_parts_or(&self, epoch_num_data_parts: usize) -> usize {
+        self.num_data_parts().unwrap_or(epoch_num_data_parts)
+    }
+
+    /// Whether the number of data parts recorded in the header, if any, is the
+    /// one adaptive encoding picks for the encoded length of the chunk. Any
+    /// other value would let the producer choose how many parts are needed to
+    /// reconstruct the chunk.
+    pub fn has_valid_num_data_parts(&self, epoch_num_data_parts: usize) -> bool {
+        self.num_data_parts().map_or(true, |num_data_parts| {
+            let encoded_length = usize::try_from(self.encoded_length()).unwrap_or(usize::MAX);
+            num_data_parts
+                == crate::reed_solomon::reed_solomon_adaptive_num_data_parts(
+                    encoded_length,
+                    epoch_num_data_parts,
+                )
+        })
+    }
+
+    /// State transition of the previous chunk committed to in this header, if
+    /// the header commits to the state root the previous chunk was applied to.
+    pub fn prev_state_transition(&self) -> Option<StateTransitionCommitment> {
+        let pre_state_root = *self.prev_pre_state_root()?;
+        Some(StateTransitionCommitment {
+            shard_id: self.shard_id(),
+            pre_state_root,
+            post_state_root: self.prev_state_root(),
+            outcome_root: self.prev_outcome_root(),
+        })
+    }
+
     /// Returns whether the header is valid for given `ProtocolVersion`.
     pub fn validate_version(
         &self,
@@ -553,6 +698,12 @@ impl ShardChunkHeader {
             ProtocolFeature::CongestionControl.protocol_version();
         const BANDWIDTH_SCHEDULER_VERSION: ProtocolVersion =
             ProtocolFeature::BandwidthScheduler.protocol_version();
+        const CHUNK_BANDWIDTH_COMMITMENT_VERSION: ProtocolVersion =
+            ProtocolFeature::ChunkBandwidthCommitment.protocol_version();
+        const EXECUTION_PROOFS_VERSION: ProtocolVersion =
+            ProtocolFeature::ExecutionProofs.protocol_version();
+        const ADAPTIVE_CHUNK_ENCODING_VERSION: ProtocolVersion =
+            ProtocolFeature::AdaptiveChunkEncoding.protocol_version();
 
         let is_valid = match &self {
             ShardChunkHeader::V1(_) => version < SHARD_CHUNK_HEADER_UPGRADE_VERSION,
@@ -574,7 +725,26 @@ impl ShardChunkHeader {
                 // latest version. TODO(#12328) - don't allow InnerV2 in bandwidth scheduler version.
                 ShardChunkHeaderInner::V2(_) => version >= BLOCK_HEADER_V3_VERSION,
                 ShardChunkHeaderInner::V3(_) => version >= CONGESTION_CONTROL_VERSION,
-                ShardChunkHeaderInner::V4(_) => version >= BANDWIDTH_SCHEDULER_VERSION,
+                // Once chunks commit to the outgoing bandwidth, v4 is no longer
+                // allowed. Outgoing bandwidth is computed from the outgoing receipts
+                // of the previous chunk, so it's always available to the chunk producer.
+                ShardChunkHeaderInner::V4(_) => {
+                    version >= BANDWIDTH_SCHEDULER_VERSION
+                        && version < CHUNK_BANDWIDTH_COMMITMENT_VERSION
+                }
+                // The state root the previous chunk was applied to is known to the
+                // chunk producer from the last chunk header, so v5 is not allowed
+                // once execution proofs are enabled.
+                ShardChunkHeaderInner::V5(_) => {
+                    version >= CHUNK_BANDWIDTH_COMMITMENT_VERSION
+                        && version < EXECUTION_PROOFS_VERSION
+                }
+                // The number of data parts is chosen by the chunk producer when
+                // encoding the chunk, so v6 is not allowed once it is recorded.
+                ShardChunkHeaderInner::V6(_) => {
+                    version >= EXECUTION_PROOFS_VERSION && version < ADAPTIVE_CHUNK_ENCODING_VERSION
+                }
+                ShardChunkHeaderInner::V7(_) => version >= ADAPTIVE_CHUNK_ENCODING_VERSION,
             },
         };
 
@@ -699,6 +869,8 @@ impl ShardChunkHeaderV1 {
 pub enum PartialEncodedChunk {
     V1(PartialEncodedChunkV1),
     V2(PartialEncodedChunkV2),
+    /// Only used on the wire, see `PartialEncodedChunkV3`.
+    V3(PartialEncodedChunkV3),
 }
 
 impl PartialEncodedChunk {
@@ -719,6 +891,7 @@ impl PartialEncodedChunk {
         match self {
             Self::V1(chunk) => ShardChunkHeader::V1(chunk.header.clone()),
             Self::V2(chunk) => chunk.header.clone(),
+            Self::V3(chunk) => chunk.header.clone(),
         }
     }
 
@@ -726,6 +899,7 @@ impl PartialEncodedChunk {
         match self {
             Self::V1(chunk) => chunk.header.hash.clone(),
             Self::V2(chunk) => chunk.header.chunk_hash(),
+            Self::V3(chunk) => chunk.header.chunk_hash(),
         }
     }
 
@@ -733,14 +907,17 @@ impl PartialEncodedChunk {
         match self {
             Self::V1(chunk) => chunk.header.height_included,
             Self::V2(chunk) => chunk.header.height_included(),
+            Self::V3(chunk) => chunk.header.height_included(),
         }
     }
 
+    /// Note that parts of a `V3` chunk may be compressed.
     #[inline]
     pub fn parts(&self) -> &[PartialEncodedChunkPart] {
         match self {
             Self::V1(chunk) => &chunk.parts,
             Self::V2(chunk) => &chunk.parts,
+            Self::V3(chunk) => &chunk.parts,
         }
     }
 
@@ -749,6 +926,7 @@ impl PartialEncodedChunk {
         match self {
             Self::V1(chunk) => &chunk.prev_outgoing_receipts,
             Self::V2(chunk) => &chunk.prev_outgoing_receipts,
+            Self::V3(chunk) => &chunk.prev_outgoing_receipts,
         }
     }
 
@@ -757,6 +935,7 @@ impl PartialEncodedChunk {
         match &self {
             PartialEncodedChunk::V1(chunk) => &chunk.header.inner.prev_block_hash,
             PartialEncodedChunk::V2(chunk) => chunk.header.prev_block_hash(),
+            PartialEncodedChunk::V3(chunk) => chunk.header.prev_block_hash(),
         }
     }
 
@@ -765,6 +944,7 @@ impl PartialEncodedChunk {
         match &self {
             PartialEncodedChunk::V1(_) => version < SHARD_CHUNK_HEADER_UPGRADE_VERSION,
             PartialEncodedChunk::V2(_) => SHARD_CHUNK_HEADER_UPGRADE_VERSION <= version,
+            PartialEncodedChunk::V3(_) => ProtocolFeature::ChunkPartCompression.enabled(version),
         }
     }
 
@@ -772,12 +952,14 @@ impl PartialEncodedChunk {
         match self {
             Self::V1(chunk) => chunk.header.inner.height_created,
             Self::V2(chunk) => chunk.header.height_created(),
+            Self::V3(chunk) => chunk.header.height_created(),
         }
     }
     pub fn shard_id(&self) -> ShardId {
         match self {
             Self::V1(chunk) => chunk.header.inner.shard_id,
             Self::V2(chunk) => chunk.header.shard_id(),
+            Self::V3(chunk) => chunk.header.shard_id(),
         }
     }
 }
@@ -789,19 +971,136 @@ pub struct PartialEncodedChunkV2 {
     pub prev_outgoing_receipts: Vec<ReceiptProof>,
 }
 
-impl From<PartialEncodedChunk> for PartialEncodedChunkV2 {
-    fn from(pec: PartialEncodedChunk) -> Self {
-        match pec {
-            PartialEncodedChunk::V1(chunk) => PartialEncodedChunkV2 {
+impl PartialEncodedChunk {
+    /// Converts the chunk into `PartialEncodedChunkV2`, decompressing the parts
+    /// of a `V3` chunk, see [`PartialEncodedChunkV3::decompress`].
+    pub fn into_v2(
+        self,
+        epoch_num_data_parts: usize,
+        num_total_parts: usize,
+    ) -> Result<PartialEncodedChunkV2, ChunkPartsDecompressionError> {
+        match self {
+            PartialEncodedChunk::V1(chunk) => Ok(PartialEncodedChunkV2 {
                 header: ShardChunkHeader::V1(chunk.header),
                 parts: chunk.parts,
                 prev_outgoing_receipts: chunk.prev_outgoing_receipts,
-            },
-            PartialEncodedChunk::V2(chunk) => chunk,
+            }),
+            PartialEncodedChunk::V2(chunk) => Ok(chunk),
+            PartialEncodedChunk::V3(chunk) => {
+                chunk.decompress(epoch_num_data_parts, num_total_parts)
+            }
         }
     }
 }
 
+/// Compression applied to the parts of a `PartialEncodedChunkV3`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Eq, PartialEq, ProtocolSchema)]
+pub enum ChunkPartsCompression {
+    None,
+    Zstd,
+}
+
+/// Partial encoded chunk as sent over the network once
+/// `ProtocolFeature::ChunkPartCompression` is enabled.  Parts are compressed
+/// individually, so that merkle proofs still refer to the uncompressed parts.
+/// The chunk is decompressed into `PartialEncodedChunkV2` as soon as it is
+/// received and is never stored in this form.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Eq, PartialEq, ProtocolSchema)]
+pub struct PartialEncodedChunkV3 {
+    pub header: ShardChunkHeader,
+    pub compression: ChunkPartsCompression,
+    pub parts: Vec<PartialEncodedChunkPart>,
+    pub prev_outgoing_receipts: Vec<ReceiptProof>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ChunkPartsDecompressionError {
+    #[error("chunk has {num_parts} parts, but is encoded into {num_total_parts} parts")]
+    TooManyParts { num_parts: usize, num_total_parts: usize },
+    #[error("part {part_ord} doesn't decompress into a part of {part_length} bytes")]
+    InvalidPart { part_ord: u64, part_length: usize },
+}
+
+impl PartialEncodedChunkV3 {
+    const COMPRESSION_LEVEL: i32 = 3;
+    /// Upper bound on the size of a decompressed part, whatever the encoded
+    /// length claimed by the header.
+    const MAX_DECOMPRESSED_PART_SIZE: usize = 16 * 1024 * 1024;
+
+    pub fn compress(chunk: PartialEncodedChunkV2) -> Self {
+        let parts = chunk
+            .parts
+            .into_iter()
+            .map(|part| PartialEncodedChunkPart {
+                part: zstd::bulk::compress(&part.part, Self::COMPRESSION_LEVEL)
+                    .expect("compressing into memory can't fail")
+                    .into_boxed_slice(),
+                ..part
+            })
+            .collect();
+        Self {
+            header: chunk.header,
+            compression: ChunkPartsCompression::Zstd,
+            parts,
+            prev_outgoing_receipts: chunk.prev_outgoing_receipts,
+        }
+    }
+
+    /// Every part of the chunk has the same length, derived from the encoded
+    /// length and the number of data parts in the header. A part which doesn't
+    /// decompress into exactly that many bytes, or more parts than the chunk is
+    /// encoded into, make the whole message invalid, so that a malicious peer
+    /// can't make us allocate more memory than the chunk itself takes.
+    pub fn decompress(
+        self,
+        epoch_num_data_parts: usize,
+        num_total_parts: usize,
+    ) -> Result<PartialEncodedChunkV2, ChunkPartsDecompressionError> {
+        if self.parts.len() > num_total_parts {
+            return Err(ChunkPartsDecompressionError::TooManyParts {
+                num_parts: self.parts.len(),
+                num_total_parts,
+            });
+        }
+        let parts = match self.compression {
+            ChunkPartsCompression::None => self.parts,
+            ChunkPartsCompression::Zstd => {
+                let num_data_parts = self.header.num_data_parts_or(epoch_num_data_parts).max(1);
+                // Same as `reed_solomon_part_length`, without overflowing on a
+                // bogus encoded length.
+                let part_length = self.header.encoded_length().div_ceil(num_data_parts as u64);
+                let part_length = usize::try_from(part_length).unwrap_or(usize::MAX);
+                self.parts
+                    .into_iter()
+                    .map(|part| {
+                        let invalid_part = ChunkPartsDecompressionError::InvalidPart {
+                            part_ord: part.part_ord,
+                            part_length,
+                        };
+                        if part_length > Self::MAX_DECOMPRESSED_PART_SIZE {
+                            return Err(invalid_part);
+                        }
+                        let decompressed = zstd::bulk::decompress(&part.part, part_length)
+                            .map_err(|_| invalid_part.clone())?;
+                        if decompressed.len() != part_length {
+                            return Err(invalid_part);
+                        }
+                        Ok(PartialEncodedChunkPart {
+                            part: decompressed.into_boxed_slice(),
+                            ..part
+                        })
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        Ok(PartialEncodedChunkV2 {
+            header: self.header,
+            parts,
+            prev_outgoing_receipts: self.prev_outgoing_receipts,
+        })
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Eq, PartialEq, ProtocolSchema)]
 pub struct PartialEncodedChunkV1 {
     pub header: ShardChunkHeaderV1,
@@ -814,11 +1113,13 @@ pub struct PartialEncodedChunkWithArcReceipts {
     pub header: ShardChunkHeader,
     pub parts: Vec<PartialEncodedChunkPart>,
     pub prev_outgoing_receipts: Vec<Arc<ReceiptProof>>,
+    /// Whether the parts should be compressed when the chunk is sent.
+    pub compression: ChunkPartsCompression,
 }
 
 impl From<PartialEncodedChunkWithArcReceipts> for PartialEncodedChunk {
     fn from(pec: PartialEncodedChunkWithArcReceipts) -> Self {
-        Self::V2(PartialEncodedChunkV2 {
+        let chunk = PartialEncodedChunkV2 {
             header: pec.header,
             parts: pec.parts,
             prev_outgoing_receipts: pec
@@ -826,7 +1127,11 @@ impl From<PartialEncodedChunkWithArcReceipts> for PartialEncodedChunk {
                 .into_iter()
                 .map(|r| ReceiptProof::clone(&r))
                 .collect(),
-        })
+        };
+        match pec.compression {
+            ChunkPartsCompression::None => Self::V2(chunk),
+            ChunkPartsCompression::Zstd => Self::V3(PartialEncodedChunkV3::compress(chunk)),
+        }
     }
 }
 
@@ -916,12 +1221,34 @@ pub struct ShardChunkV2 {
     pub prev_outgoing_receipts: Vec<Receipt>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Eq, PartialEq, ProtocolSchema)]
+/// `Debug` only prints a summary, use `utils::detailed` to print all fields.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Eq, PartialEq, ProtocolSchema)]
 pub enum ShardChunk {
     V1(ShardChunkV1),
     V2(ShardChunkV2),
 }
 
+impl std::fmt::Debug for ShardChunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShardChunk")
+            .field("chunk_hash", &self.chunk_hash())
+            .field("shard_id", &self.shard_id())
+            .field("height_created", &self.height_created())
+            .field("num_transactions", &self.transactions().len())
+            .field("num_prev_outgoing_receipts", &self.prev_outgoing_receipts().len())
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for DetailedDebug<'_, ShardChunk> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            ShardChunk::V1(chunk) => f.debug_tuple("V1").field(chunk).finish(),
+            ShardChunk::V2(chunk) => f.debug_tuple("V2").field(chunk).finish(),
+        }
+    }
+}
+
 impl ShardChunk {
     pub fn with_header(chunk: ShardChunk, header: ShardChunkHeader) -> Option<ShardChunk> {
         match chunk {
@@ -1007,6 +1334,14 @@ impl ShardChunk {
         }
     }
 
+    #[inline]
+    pub fn height_created(&self) -> BlockHeight {
+        match self {
+            Self::V1(chunk) => chunk.header.inner.height_created,
+            Self::V2(chunk) => chunk.header.height_created(),
+        }
+    }
+
     #[inline]
     pub fn chunk_hash(&self) -> ChunkHash {
         match self {
@@ -1115,12 +1450,35 @@ pub struct EncodedShardChunkV2 {
     pub content: EncodedShardChunkBody,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq, ProtocolSchema)]
+/// `Debug` only prints a summary, use `utils::detailed` to print all fields.
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, ProtocolSchema)]
 pub enum EncodedShardChunk {
     V1(EncodedShardChunkV1),
     V2(EncodedShardChunkV2),
 }
 
+impl std::fmt::Debug for EncodedShardChunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncodedShardChunk")
+            .field("chunk_hash", &self.chunk_hash())
+            .field("shard_id", &self.shard_id())
+            .field("height_created", &self.height_created())
+            .field("encoded_length", &self.encoded_length())
+            .field("num_parts", &self.content().parts.len())
+            .field("num_fetched_parts", &self.content().num_fetched_parts())
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for DetailedDebug<'_, EncodedShardChunk> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            EncodedShardChunk::V1(chunk) => f.debug_tuple("V1").field(chunk).finish(),
+            EncodedShardChunk::V2(chunk) => f.debug_tuple("V2").field(chunk).finish(),
+        }
+    }
+}
+
 impl EncodedShardChunk {
     pub fn cloned_header(&self) -> ShardChunkHeader {
         match self {
@@ -1225,13 +1583,30 @@ impl EncodedShardChunk {
         prev_outgoing_receipts_root: CryptoHash,
         congestion_info: Option<CongestionInfo>,
         bandwidth_requests: Option<BandwidthRequests>,
+        prev_outgoing_bandwidth: Option<OutgoingBandwidth>,
+        prev_pre_state_root: Option<StateRoot>,
         signer: &ValidatorSigner,
         protocol_version: ProtocolVersion,
     ) -> Result<(Self, Vec<MerklePath>), std::io::Error> {
-        let (transaction_receipts_parts, encoded_length) = crate::reed_solomon::reed_solomon_encode(
+        let transaction_receipts =
+            TransactionReceipt(transactions, prev_outgoing_receipts.to_vec());
+        // With adaptive encoding the data parts depend on the size of the chunk,
+        // while the total number of parts, and so the part owners, stay the same.
+        let num_data_parts = if ProtocolFeature::AdaptiveChunkEncoding.enabled(protocol_version) {
+            let length = borsh::object_length(&transaction_receipts)?;
+            Some(crate::reed_solomon::reed_solomon_adaptive_num_data_parts(
+                length,
+                rs.data_shard_count(),
+            ))
+        } else {
+            None
+        };
+        let rs = crate::reed_solomon::reed_solomon_with_data_parts(
             rs,
-            TransactionReceipt(transactions, prev_outgoing_receipts.to_vec()),
+            num_data_parts.unwrap_or(rs.data_shard_count()),
         );
+        let (transaction_receipts_parts, encoded_length) =
+            crate::reed_solomon::reed_solomon_encode(&rs, transaction_receipts);
         let content = EncodedShardChunkBody { parts: transaction_receipts_parts };
         let (encoded_merkle_root, merkle_paths) = content.get_merkle_hash_and_paths();
 
@@ -1299,6 +1674,9 @@ impl EncodedShardChunk {
                 prev_validator_proposals,
                 congestion_info,
                 bandwidth_requests,
+                prev_outgoing_bandwidth,
+                prev_pre_state_root,
+                num_data_parts.map(|num_data_parts| num_data_parts as u64),
                 signer,
             );
             let chunk = EncodedShardChunkV2 { header: ShardChunkHeader::V3(header), content };
@@ -1370,7 +1748,12 @@ impl EncodedShardChunk {
             Self::V1(chunk) => ShardChunkHeader::V1(chunk.header.clone()),
             Self::V2(chunk) => chunk.header.clone(),
         };
-        PartialEncodedChunkWithArcReceipts { header, parts, prev_outgoing_receipts }
+        PartialEncodedChunkWithArcReceipts {
+            header,
+            parts,
+            prev_outgoing_receipts,
+            compression: ChunkPartsCompression::None,
+        }
     }
 
     pub fn decode_chunk(&self, data_parts: usize) -> Result<ShardChunk, std::io::Error> {
@@ -1402,3 +1785,51 @@ impl EncodedShardChunk {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reed_solomon::ADAPTIVE_MIN_PART_SIZE;
+    use crate::validator_signer::InMemoryValidatorSigner;
+    use near_crypto::KeyType;
+
+    fn chunk_header(encoded_length: u64, num_data_parts: u64) -> ShardChunkHeader {
+        let protocol_version = ProtocolFeature::AdaptiveChunkEncoding.protocol_version();
+        ShardChunkHeader::V3(ShardChunkHeaderV3::new(
+            protocol_version,
+            CryptoHash::default(),
+            CryptoHash::default(),
+            CryptoHash::default(),
+            CryptoHash::default(),
+            encoded_length,
+            1,
+            ShardId::new(0),
+            0,
+            0,
+            0,
+            CryptoHash::default(),
+            CryptoHash::default(),
+            vec![],
+            Some(CongestionInfo::default()),
+            BandwidthRequests::default_for_protocol_version(protocol_version),
+            OutgoingBandwidth::default_for_protocol_version(protocol_version),
+            Some(CryptoHash::default()),
+            Some(num_data_parts),
+            &InMemoryValidatorSigner::from_seed("test".parse().unwrap(), KeyType::ED25519, "test"),
+        ))
+    }
+
+    #[test]
+    fn test_has_valid_num_data_parts() {
+        let epoch_num_data_parts = 4;
+        let encoded_length = 2 * ADAPTIVE_MIN_PART_SIZE as u64;
+        assert!(chunk_header(encoded_length, 2).has_valid_num_data_parts(epoch_num_data_parts));
+        // In range, but not what adaptive encoding picks for this length.
+        assert!(!chunk_header(encoded_length, 1).has_valid_num_data_parts(epoch_num_data_parts));
+        assert!(!chunk_header(encoded_length, 4).has_valid_num_data_parts(epoch_num_data_parts));
+        // Large chunks are capped by the data parts of the epoch.
+        let encoded_length = 100 * ADAPTIVE_MIN_PART_SIZE as u64;
+        assert!(chunk_header(encoded_length, 4).has_valid_num_data_parts(epoch_num_data_parts));
+        assert!(!chunk_header(encoded_length, 5).has_valid_num_data_parts(epoch_num_data_parts));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::bandwidth_scheduler::BandwidthRequests;
+use crate::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 use crate::congestion_info::CongestionInfo;
 use crate::types::validator_stake::{ValidatorStake, ValidatorStakeIter, ValidatorStakeV1};
 use crate::types::StateRoot;
@@ -13,6 +13,9 @@ pub enum ShardChunkHeaderInner {
     V2(ShardChunkHeaderInnerV2),
     V3(ShardChunkHeaderInnerV3),
     V4(ShardChunkHeaderInnerV4),
+    V5(ShardChunkHeaderInnerV5),
+    V6(ShardChunkHeaderInnerV6),
+    V7(ShardChunkHeaderInnerV7),
 }
 
 impl ShardChunkHeaderInner {
@@ -23,6 +26,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => &inner.prev_state_root,
             Self::V3(inner) => &inner.prev_state_root,
             Self::V4(inner) => &inner.prev_state_root,
+            Self::V5(inner) => &inner.prev_state_root,
+            Self::V6(inner) => &inner.prev_state_root,
+            Self::V7(inner) => &inner.prev_state_root,
         }
     }
 
@@ -33,6 +39,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => &inner.prev_block_hash,
             Self::V3(inner) => &inner.prev_block_hash,
             Self::V4(inner) => &inner.prev_block_hash,
+            Self::V5(inner) => &inner.prev_block_hash,
+            Self::V6(inner) => &inner.prev_block_hash,
+            Self::V7(inner) => &inner.prev_block_hash,
         }
     }
 
@@ -43,6 +52,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => inner.gas_limit,
             Self::V3(inner) => inner.gas_limit,
             Self::V4(inner) => inner.gas_limit,
+            Self::V5(inner) => inner.gas_limit,
+            Self::V6(inner) => inner.gas_limit,
+            Self::V7(inner) => inner.gas_limit,
         }
     }
 
@@ -53,6 +65,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => inner.prev_gas_used,
             Self::V3(inner) => inner.prev_gas_used,
             Self::V4(inner) => inner.prev_gas_used,
+            Self::V5(inner) => inner.prev_gas_used,
+            Self::V6(inner) => inner.prev_gas_used,
+            Self::V7(inner) => inner.prev_gas_used,
         }
     }
 
@@ -63,6 +78,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => ValidatorStakeIter::new(&inner.prev_validator_proposals),
             Self::V3(inner) => ValidatorStakeIter::new(&inner.prev_validator_proposals),
             Self::V4(inner) => ValidatorStakeIter::new(&inner.prev_validator_proposals),
+            Self::V5(inner) => ValidatorStakeIter::new(&inner.prev_validator_proposals),
+            Self::V6(inner) => ValidatorStakeIter::new(&inner.prev_validator_proposals),
+            Self::V7(inner) => ValidatorStakeIter::new(&inner.prev_validator_proposals),
         }
     }
 
@@ -73,6 +91,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => inner.height_created,
             Self::V3(inner) => inner.height_created,
             Self::V4(inner) => inner.height_created,
+            Self::V5(inner) => inner.height_created,
+            Self::V6(inner) => inner.height_created,
+            Self::V7(inner) => inner.height_created,
         }
     }
 
@@ -83,6 +104,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => inner.shard_id,
             Self::V3(inner) => inner.shard_id,
             Self::V4(inner) => inner.shard_id,
+            Self::V5(inner) => inner.shard_id,
+            Self::V6(inner) => inner.shard_id,
+            Self::V7(inner) => inner.shard_id,
         }
     }
 
@@ -93,6 +117,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => &inner.prev_outcome_root,
             Self::V3(inner) => &inner.prev_outcome_root,
             Self::V4(inner) => &inner.prev_outcome_root,
+            Self::V5(inner) => &inner.prev_outcome_root,
+            Self::V6(inner) => &inner.prev_outcome_root,
+            Self::V7(inner) => &inner.prev_outcome_root,
         }
     }
 
@@ -103,6 +130,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => &inner.encoded_merkle_root,
             Self::V3(inner) => &inner.encoded_merkle_root,
             Self::V4(inner) => &inner.encoded_merkle_root,
+            Self::V5(inner) => &inner.encoded_merkle_root,
+            Self::V6(inner) => &inner.encoded_merkle_root,
+            Self::V7(inner) => &inner.encoded_merkle_root,
         }
     }
 
@@ -113,6 +143,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => inner.encoded_length,
             Self::V3(inner) => inner.encoded_length,
             Self::V4(inner) => inner.encoded_length,
+            Self::V5(inner) => inner.encoded_length,
+            Self::V6(inner) => inner.encoded_length,
+            Self::V7(inner) => inner.encoded_length,
         }
     }
 
@@ -123,6 +156,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => inner.prev_balance_burnt,
             Self::V3(inner) => inner.prev_balance_burnt,
             Self::V4(inner) => inner.prev_balance_burnt,
+            Self::V5(inner) => inner.prev_balance_burnt,
+            Self::V6(inner) => inner.prev_balance_burnt,
+            Self::V7(inner) => inner.prev_balance_burnt,
         }
     }
 
@@ -133,6 +169,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => &inner.prev_outgoing_receipts_root,
             Self::V3(inner) => &inner.prev_outgoing_receipts_root,
             Self::V4(inner) => &inner.prev_outgoing_receipts_root,
+            Self::V5(inner) => &inner.prev_outgoing_receipts_root,
+            Self::V6(inner) => &inner.prev_outgoing_receipts_root,
+            Self::V7(inner) => &inner.prev_outgoing_receipts_root,
         }
     }
 
@@ -143,6 +182,9 @@ impl ShardChunkHeaderInner {
             Self::V2(inner) => &inner.tx_root,
             Self::V3(inner) => &inner.tx_root,
             Self::V4(inner) => &inner.tx_root,
+            Self::V5(inner) => &inner.tx_root,
+            Self::V6(inner) => &inner.tx_root,
+            Self::V7(inner) => &inner.tx_root,
         }
     }
 
@@ -154,6 +196,9 @@ impl ShardChunkHeaderInner {
             Self::V2(_) => None,
             Self::V3(v3) => Some(v3.congestion_info),
             Self::V4(v4) => Some(v4.congestion_info),
+            Self::V5(v5) => Some(v5.congestion_info),
+            Self::V6(v6) => Some(v6.congestion_info),
+            Self::V7(v7) => Some(v7.congestion_info),
         }
     }
 
@@ -162,6 +207,41 @@ impl ShardChunkHeaderInner {
         match self {
             Self::V1(_) | Self::V2(_) | Self::V3(_) => None,
             Self::V4(inner) => Some(&inner.bandwidth_requests),
+            Self::V5(inner) => Some(&inner.bandwidth_requests),
+            Self::V6(inner) => Some(&inner.bandwidth_requests),
+            Self::V7(inner) => Some(&inner.bandwidth_requests),
+        }
+    }
+
+    #[inline]
+    pub fn prev_outgoing_bandwidth(&self) -> Option<&OutgoingBandwidth> {
+        match self {
+            Self::V1(_) | Self::V2(_) | Self::V3(_) | Self::V4(_) => None,
+            Self::V5(inner) => Some(&inner.prev_outgoing_bandwidth),
+            Self::V6(inner) => Some(&inner.prev_outgoing_bandwidth),
+            Self::V7(inner) => Some(&inner.prev_outgoing_bandwidth),
+        }
+    }
+
+    #[inline]
+    pub fn prev_pre_state_root(&self) -> Option<&StateRoot> {
+        match self {
+            Self::V1(_) | Self::V2(_) | Self::V3(_) | Self::V4(_) | Self::V5(_) => None,
+            Self::V6(inner) => Some(&inner.prev_pre_state_root),
+            Self::V7(inner) => Some(&inner.prev_pre_state_root),
+        }
+    }
+
+    /// Number of data parts the chunk was encoded with, if it is recorded in
+    /// the header. Otherwise the chunk uses the fixed number of data parts of
+    /// the epoch.
+    #[inline]
+    pub fn num_data_parts(&self) -> Option<usize> {
+        match self {
+            Self::V1(_) | Self::V2(_) | Self::V3(_) | Self::V4(_) | Self::V5(_) | Self::V6(_) => {
+                None
+            }
+            Self::V7(inner) => Some(inner.num_data_parts as usize),
         }
     }
 
@@ -173,6 +253,9 @@ impl ShardChunkHeaderInner {
             Self::V2(_) => 2,
             Self::V3(_) => 3,
             Self::V4(_) => 4,
+            Self::V5(_) => 5,
+            Self::V6(_) => 6,
+            Self::V7(_) => 7,
         }
     }
 }
@@ -289,3 +372,113 @@ pub struct ShardChunkHeaderInnerV4 {
     /// Requests for bandwidth to send receipts to other shards.
     pub bandwidth_requests: BandwidthRequests,
 }
+
+// V4 -> V5: Add outgoing bandwidth of the previous chunk.
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug, ProtocolSchema)]
+pub struct ShardChunkHeaderInnerV5 {
+    /// Previous block hash.
+    pub prev_block_hash: CryptoHash,
+    pub prev_state_root: StateRoot,
+    /// Root of the outcomes from execution transactions and results of the previous chunk.
+    pub prev_outcome_root: CryptoHash,
+    pub encoded_merkle_root: CryptoHash,
+    pub encoded_length: u64,
+    pub height_created: BlockHeight,
+    /// Shard index.
+    pub shard_id: ShardId,
+    /// Gas used in the previous chunk.
+    pub prev_gas_used: Gas,
+    /// Gas limit voted by validators.
+    pub gas_limit: Gas,
+    /// Total balance burnt in the previous chunk.
+    pub prev_balance_burnt: Balance,
+    /// Previous chunk's outgoing receipts merkle root.
+    pub prev_outgoing_receipts_root: CryptoHash,
+    /// Tx merkle root.
+    pub tx_root: CryptoHash,
+    /// Validator proposals from the previous chunk.
+    pub prev_validator_proposals: Vec<ValidatorStake>,
+    /// Congestion info about this shard after the previous chunk was applied.
+    pub congestion_info: CongestionInfo,
+    /// Requests for bandwidth to send receipts to other shards.
+    pub bandwidth_requests: BandwidthRequests,
+    /// Size of the previous chunk's outgoing receipts for every receiver shard.
+    pub prev_outgoing_bandwidth: OutgoingBandwidth,
+}
+
+// V5 -> V6: Add the state root the previous chunk was applied to.
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug, ProtocolSchema)]
+pub struct ShardChunkHeaderInnerV6 {
+    /// Previous block hash.
+    pub prev_block_hash: CryptoHash,
+    pub prev_state_root: StateRoot,
+    /// State root the previous chunk was applied to. Together with
+    /// `prev_state_root` and `prev_outcome_root` it commits to the full state
+    /// transition of the previous chunk.
+    pub prev_pre_state_root: StateRoot,
+    /// Root of the outcomes from execution transactions and results of the previous chunk.
+    pub prev_outcome_root: CryptoHash,
+    pub encoded_merkle_root: CryptoHash,
+    pub encoded_length: u64,
+    pub height_created: BlockHeight,
+    /// Shard index.
+    pub shard_id: ShardId,
+    /// Gas used in the previous chunk.
+    pub prev_gas_used: Gas,
+    /// Gas limit voted by validators.
+    pub gas_limit: Gas,
+    /// Total balance burnt in the previous chunk.
+    pub prev_balance_burnt: Balance,
+    /// Previous chunk's outgoing receipts merkle root.
+    pub prev_outgoing_receipts_root: CryptoHash,
+    /// Tx merkle root.
+    pub tx_root: CryptoHash,
+    /// Validator proposals from the previous chunk.
+    pub prev_validator_proposals: Vec<ValidatorStake>,
+    /// Congestion info about this shard after the previous chunk was applied.
+    pub congestion_info: CongestionInfo,
+    /// Requests for bandwidth to send receipts to other shards.
+    pub bandwidth_requests: BandwidthRequests,
+    /// Size of the previous chunk's outgoing receipts for every receiver shard.
+    pub prev_outgoing_bandwidth: OutgoingBandwidth,
+}
+
+// V6 -> V7: Record the number of data parts the chunk is encoded with.
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug, ProtocolSchema)]
+pub struct ShardChunkHeaderInnerV7 {
+    /// Previous block hash.
+    pub prev_block_hash: CryptoHash,
+    pub prev_state_root: StateRoot,
+    /// State root the previous chunk was applied to. Together with
+    /// `prev_state_root` and `prev_outcome_root` it commits to the full state
+    /// transition of the previous chunk.
+    pub prev_pre_state_root: StateRoot,
+    /// Root of the outcomes from execution transactions and results of the previous chunk.
+    pub prev_outcome_root: CryptoHash,
+    pub encoded_merkle_root: CryptoHash,
+    pub encoded_length: u64,
+    pub height_created: BlockHeight,
+    /// Shard index.
+    pub shard_id: ShardId,
+    /// Gas used in the previous chunk.
+    pub prev_gas_used: Gas,
+    /// Gas limit voted by validators.
+    pub gas_limit: Gas,
+    /// Total balance burnt in the previous chunk.
+    pub prev_balance_burnt: Balance,
+    /// Previous chunk's outgoing receipts merkle root.
+    pub prev_outgoing_receipts_root: CryptoHash,
+    /// Tx merkle root.
+    pub tx_root: CryptoHash,
+    /// Validator proposals from the previous chunk.
+    pub prev_validator_proposals: Vec<ValidatorStake>,
+    /// Congestion info about this shard after the previous chunk was applied.
+    pub congestion_info: CongestionInfo,
+    /// Requests for bandwidth to send receipts to other shards.
+    pub bandwidth_requests: BandwidthRequests,
+    /// Size of the previous chunk's outgoing receipts for every receiver shard.
+    pub prev_outgoing_bandwidth: OutgoingBandwidth,
+    /// Number of data parts the chunk is encoded with. The rest of the parts
+    /// are parity parts.
+    pub num_data_parts: u64,
+}
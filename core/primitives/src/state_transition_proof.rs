@@ -0,0 +1,187 @@
+//! Proofs of the effect of a chunk on the state of its shard.
+//!
+//! Outcome proofs only show that a transaction or a receipt was executed.
+//! Since `ProtocolFeature::ExecutionProofs`, chunk headers also commit to the
+//! state root the previous chunk was applied to, so a header describes the
+//! whole state transition of the previous chunk: the state root before and
+//! after applying it and the root of its execution outcomes. An
+//! `ExecutionProof` ties an execution outcome to such a transition and the
+//! transition to the chunk headers root of the block which included the header,
+//! which lets light clients check which state the outcome was executed against
+//! and which state it resulted in.
+
+use crate::block::Block;
+use crate::block_header::BlockHeader;
+use crate::hash::CryptoHash;
+use crate::merkle::{verify_path, MerklePath};
+use crate::sharding::ShardChunkHeader;
+use crate::transaction::ExecutionOutcomeWithId;
+use crate::types::{ShardId, StateRoot};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// State transition of a single chunk.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct StateTransitionCommitment {
+    pub shard_id: ShardId,
+    /// State root the chunk was applied to.
+    pub pre_state_root: StateRoot,
+    /// State root after the chunk was applied.
+    pub post_state_root: StateRoot,
+    /// Root of the execution outcomes of the chunk.
+    pub outcome_root: CryptoHash,
+}
+
+impl StateTransitionCommitment {
+    pub fn hash(&self) -> CryptoHash {
+        CryptoHash::hash_borsh(self)
+    }
+}
+
+/// Proof that an execution outcome was produced by a state transition
+/// committed to in a block.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct ExecutionProof {
+    /// Header of the chunk following the one which produced the outcome. Its
+    /// hash commits to the state transition of the previous chunk.
+    pub chunk_header: ShardChunkHeader,
+    /// Path from the outcome to the outcome root of the transition.
+    pub outcome_proof: MerklePath,
+    /// Path from `chunk_header` to the chunk headers root of the block which
+    /// included it, see `Block::compute_chunk_headers_root`.
+    pub chunk_header_proof: MerklePath,
+}
+
+impl ExecutionProof {
+    /// State transition committed to in `chunk_header`, if any.
+    pub fn transition(&self) -> Option<StateTransitionCommitment> {
+        self.chunk_header.prev_state_transition()
+    }
+
+    /// Checks that `outcome` is included in the outcome root of the transition.
+    pub fn verify_outcome(&self, outcome: &ExecutionOutcomeWithId) -> bool {
+        let Some(transition) = self.transition() else {
+            return false;
+        };
+        verify_path(transition.outcome_root, &self.outcome_proof, outcome.to_hashes())
+    }
+
+    /// Checks that `outcome` was produced by the state transition committed to
+    /// in a chunk header included in the block with `chunk_headers_root`.
+    pub fn verify(
+        &self,
+        outcome: &ExecutionOutcomeWithId,
+        chunk_headers_root: &CryptoHash,
+    ) -> bool {
+        Block::validate_chunk_header_proof(
+            &self.chunk_header,
+            chunk_headers_root,
+            &self.chunk_header_proof,
+        ) && self.verify_outcome(outcome)
+    }
+
+    /// Same as `verify`, against the chunk headers root of `block_header`.
+    pub fn verify_with_block_header(
+        &self,
+        outcome: &ExecutionOutcomeWithId,
+        block_header: &BlockHeader,
+    ) -> bool {
+        self.verify(outcome, block_header.chunk_headers_root())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
+    use crate::congestion_info::CongestionInfo;
+    use crate::hash::hash;
+    use crate::merkle::merklize;
+    use crate::sharding::{ChunkHashHeight, ShardChunkHeaderV3};
+    use crate::transaction::ExecutionOutcome;
+    use crate::validator_signer::InMemoryValidatorSigner;
+    use crate::version::ProtocolFeature;
+    use near_crypto::KeyType;
+
+    fn outcome(id: u8) -> ExecutionOutcomeWithId {
+        ExecutionOutcomeWithId {
+            id: hash(&[id]),
+            outcome: ExecutionOutcome { logs: vec![format!("log {}", id)], ..Default::default() },
+        }
+    }
+
+    fn chunk_header(shard: u64, outcome_root: CryptoHash) -> ShardChunkHeader {
+        let protocol_version = ProtocolFeature::ExecutionProofs.protocol_version();
+        let mut header = ShardChunkHeader::V3(ShardChunkHeaderV3::new(
+            protocol_version,
+            CryptoHash::default(),
+            hash(&[shard as u8, 1]),
+            outcome_root,
+            CryptoHash::default(),
+            0,
+            1,
+            ShardId::new(shard),
+            0,
+            0,
+            0,
+            CryptoHash::default(),
+            CryptoHash::default(),
+            vec![],
+            Some(CongestionInfo::default()),
+            BandwidthRequests::default_for_protocol_version(protocol_version),
+            OutgoingBandwidth::default_for_protocol_version(protocol_version),
+            Some(hash(&[shard as u8, 0])),
+            None,
+            &InMemoryValidatorSigner::from_seed("test".parse().unwrap(), KeyType::ED25519, "test"),
+        ));
+        *header.height_included_mut() = 1;
+        header
+    }
+
+    #[test]
+    fn test_execution_proof() {
+        let outcomes: Vec<_> = (0..3).map(outcome).collect();
+        let (outcome_root, outcome_paths) =
+            merklize(&outcomes.iter().map(|o| o.to_hashes()).collect::<Vec<_>>());
+        let headers = vec![chunk_header(0, CryptoHash::default()), chunk_header(1, outcome_root)];
+        let (root, header_paths) = merklize(
+            &headers
+                .iter()
+                .map(|h| ChunkHashHeight(h.chunk_hash(), h.height_included()))
+                .collect::<Vec<_>>(),
+        );
+
+        let proof = ExecutionProof {
+            chunk_header: headers[1].clone(),
+            outcome_proof: outcome_paths[2].clone(),
+            chunk_header_proof: header_paths[1].clone(),
+        };
+        assert!(proof.verify(&outcomes[2], &root));
+        assert!(!proof.verify(&outcomes[1], &root));
+        assert!(!proof.verify(&outcomes[2], &CryptoHash::default()));
+
+        // A header which is not included in the block doesn't verify, even
+        // though it commits to the same outcome root.
+        let mut wrong_header = proof.clone();
+        wrong_header.chunk_header = chunk_header(2, outcome_root);
+        assert!(wrong_header.verify_outcome(&outcomes[2]));
+        assert!(!wrong_header.verify(&outcomes[2], &root));
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_primitives_core::types::{BlockHeight, ShardId};
+use near_schema_checker_lib::ProtocolSchema;
+
+use crate::sharding::ChunkHash;
+use crate::types::ValidatorStats;
+
+/// Maximum number of missed endorsements remembered per validator and epoch.
+pub const MAX_MISSED_ENDORSEMENTS: usize = 32;
+
+/// Endorsements of a chunk validator in an epoch, recorded from the final
+/// blocks of the epoch. Stored in `DBCol::ChunkEndorsementStats`.
+#[derive(
+    BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq, ProtocolSchema,
+)]
+pub struct ValidatorEndorsementStats {
+    /// Produced and expected endorsements in each shard.
+    pub shards: BTreeMap<ShardId, ValidatorStats>,
+    /// The latest chunks the validator was expected to endorse, but whose
+    /// block doesn't include its endorsement, from the oldest.
+    pub missed: Vec<MissedEndorsement>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq, ProtocolSchema)]
+pub struct MissedEndorsement {
+    pub shard_id: ShardId,
+    pub height_created: BlockHeight,
+    pub chunk_hash: ChunkHash,
+}
+
+impl ValidatorEndorsementStats {
+    /// Records whether the validator endorsed the chunk `chunk_hash`.
+    pub fn record(
+        &mut self,
+        shard_id: ShardId,
+        height_created: BlockHeight,
+        chunk_hash: &ChunkHash,
+        endorsed: bool,
+    ) {
+        let stats = self.shards.entry(shard_id).or_default();
+        stats.expected += 1;
+        if endorsed {
+            stats.produced += 1;
+            return;
+        }
+        if self.missed.len() == MAX_MISSED_ENDORSEMENTS {
+            self.missed.remove(0);
+        }
+        self.missed.push(MissedEndorsement {
+            shard_id,
+            height_created,
+            chunk_hash: chunk_hash.clone(),
+        });
+    }
+
+    /// Produced and expected endorsements in all shards.
+    pub fn total(&self) -> ValidatorStats {
+        let mut total = ValidatorStats::default();
+        for stats in self.shards.values() {
+            total.produced += stats.produced;
+            total.expected += stats.expected;
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ValidatorEndorsementStats, MAX_MISSED_ENDORSEMENTS};
+    use crate::hash::hash;
+    use crate::sharding::ChunkHash;
+    use near_primitives_core::types::ShardId;
+
+    #[test]
+    fn test_record_keeps_latest_missed_endorsements() {
+        let mut stats = ValidatorEndorsementStats::default();
+        let num_chunks = MAX_MISSED_ENDORSEMENTS as u64 + 10;
+        for height in 0..num_chunks {
+            let chunk_hash = ChunkHash(hash(&height.to_le_bytes()));
+            stats.record(ShardId::new(height % 2), height, &chunk_hash, height % 3 == 0);
+        }
+        let total = stats.total();
+        assert_eq!(total.expected, num_chunks);
+        assert_eq!(total.produced, (0..num_chunks).filter(|height| height % 3 == 0).count() as u64);
+        assert_eq!(stats.missed.len(), MAX_MISSED_ENDORSEMENTS);
+        assert_eq!(stats.missed.last().unwrap().height_created, num_chunks - 1);
+        assert!(stats.missed.iter().all(|missed| missed.height_created % 3 != 0));
+    }
+}
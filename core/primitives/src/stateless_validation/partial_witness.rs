@@ -18,6 +18,11 @@ use near_schema_checker_lib::ProtocolSchema;
 pub const MAX_COMPRESSED_STATE_WITNESS_SIZE: ByteSize =
     ByteSize::mib(if cfg!(feature = "test_features") { 512 } else { 48 });
 
+/// Ratio of the number of data parts to total parts in the Reed Solomon encoding.
+/// The tradeoff here is having a higher ratio is better for handling missing parts and network errors
+/// but increases the size of the encoded state witness and the total network bandwidth requirements.
+pub const WITNESS_RATIO_DATA_PARTS: f64 = 0.6;
+
 /// Represents the Reed Solomon erasure encoded parts of the `EncodedChunkStateWitness`.
 /// These are created and signed by the chunk producer and sent to the chunk validators.
 /// Note that the chunk validators do not require all the parts of the state witness to
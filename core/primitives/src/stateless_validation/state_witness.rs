@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 
 use super::{ChunkProductionKey, SignatureDifferentiator};
-use crate::bandwidth_scheduler::BandwidthRequests;
+use crate::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 use crate::challenge::PartialState;
 use crate::congestion_info::CongestionInfo;
 #[cfg(feature = "solomon")]
@@ -11,6 +11,7 @@ use crate::sharding::{ChunkHash, ReceiptProof, ShardChunkHeader, ShardChunkHeade
 use crate::transaction::SignedTransaction;
 use crate::types::EpochId;
 use crate::utils::compression::CompressedData;
+use crate::utils::DetailedDebug;
 use crate::validator_signer::EmptyValidatorSigner;
 use borsh::{BorshDeserialize, BorshSerialize};
 use bytesize::ByteSize;
@@ -87,7 +88,8 @@ impl ChunkStateWitnessAck {
 
 /// The state witness for a chunk; proves the state transition that the
 /// chunk attests to.
-#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, ProtocolSchema)]
+/// `Debug` only prints a summary, use `utils::detailed` to print all fields.
+#[derive(Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, ProtocolSchema)]
 pub struct ChunkStateWitness {
     // TODO(stateless_validation): Deprecate this field in the next version of the state witness.
     pub chunk_producer: AccountId,
@@ -162,6 +164,46 @@ pub struct ChunkStateWitness {
     signature_differentiator: SignatureDifferentiator,
 }
 
+impl Debug for ChunkStateWitness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkStateWitness")
+            .field("chunk_producer", &self.chunk_producer)
+            .field("epoch_id", &self.epoch_id)
+            .field("chunk_hash", &self.chunk_header.chunk_hash())
+            .field("shard_id", &self.chunk_header.shard_id())
+            .field("height_created", &self.chunk_header.height_created())
+            .field("main_state_transition", &self.main_state_transition)
+            .field("num_source_receipt_proofs", &self.source_receipt_proofs.len())
+            .field("applied_receipts_hash", &self.applied_receipts_hash)
+            .field("num_transactions", &self.transactions.len())
+            .field("num_implicit_transitions", &self.implicit_transitions.len())
+            .field("num_new_transactions", &self.new_transactions.len())
+            .field("new_transactions_validation_state", &self.new_transactions_validation_state)
+            .finish()
+    }
+}
+
+impl Debug for DetailedDebug<'_, ChunkStateWitness> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Sort receipt proofs so that the output doesn't depend on the order
+        // of the hash map.
+        let source_receipt_proofs: BTreeMap<_, _> = self.0.source_receipt_proofs.iter().collect();
+        f.debug_struct("ChunkStateWitness")
+            .field("chunk_producer", &self.0.chunk_producer)
+            .field("epoch_id", &self.0.epoch_id)
+            .field("chunk_header", &self.0.chunk_header)
+            .field("main_state_transition", &self.0.main_state_transition)
+            .field("source_receipt_proofs", &source_receipt_proofs)
+            .field("applied_receipts_hash", &self.0.applied_receipts_hash)
+            .field("transactions", &self.0.transactions)
+            .field("implicit_transitions", &self.0.implicit_transitions)
+            .field("new_transactions", &self.0.new_transactions)
+            .field("new_transactions_validation_state", &self.0.new_transactions_validation_state)
+            .field("signature_differentiator", &self.0.signature_differentiator)
+            .finish()
+    }
+}
+
 impl ChunkStateWitness {
     pub fn new(
         chunk_producer: AccountId,
@@ -220,6 +262,9 @@ impl ChunkStateWitness {
             Default::default(),
             congestion_info,
             BandwidthRequests::default_for_protocol_version(PROTOCOL_VERSION),
+            OutgoingBandwidth::default_for_protocol_version(PROTOCOL_VERSION),
+            None,
+            None,
             &EmptyValidatorSigner::default().into(),
         ));
         Self::new(
@@ -12,7 +12,7 @@ use crate::stateless_validation::chunk_endorsements_bitmap::ChunkEndorsementsBit
 use crate::transaction::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
     DeployContractAction, FunctionCallAction, SignedTransaction, StakeAction, Transaction,
-    TransactionV0, TransactionV1, TransferAction,
+    TransactionV0, TransactionV1, TransactionV2, TransferAction,
 };
 use crate::types::validator_stake::ValidatorStake;
 use crate::types::{AccountId, Balance, EpochId, EpochInfoProvider, Gas, Nonce};
@@ -70,6 +70,7 @@ impl Transaction {
         match self {
             Transaction::V0(tx) => &mut tx.actions,
             Transaction::V1(tx) => &mut tx.actions,
+            Transaction::V2(tx) => &mut tx.actions,
         }
     }
 
@@ -77,6 +78,7 @@ impl Transaction {
         match self {
             Transaction::V0(tx) => &mut tx.nonce,
             Transaction::V1(tx) => &mut tx.nonce,
+            Transaction::V2(tx) => &mut tx.nonce,
         }
     }
 
@@ -182,6 +184,30 @@ impl SignedTransaction {
         .sign(signer)
     }
 
+    /// Explicitly create v2 transaction, which caps the gas price.
+    pub fn from_actions_v2(
+        nonce: Nonce,
+        signer_id: AccountId,
+        receiver_id: AccountId,
+        signer: &Signer,
+        actions: Vec<Action>,
+        block_hash: CryptoHash,
+        priority_fee: u64,
+        max_gas_price: Balance,
+    ) -> Self {
+        Transaction::V2(TransactionV2 {
+            nonce,
+            signer_id,
+            public_key: signer.public_key(),
+            receiver_id,
+            block_hash,
+            actions,
+            priority_fee,
+            max_gas_price,
+        })
+        .sign(signer)
+    }
+
     pub fn send_money(
         nonce: Nonce,
         signer_id: AccountId,
@@ -2,7 +2,9 @@
 pub use crate::action::NonrefundableStorageTransferAction;
 pub use crate::action::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
-    DeployContractAction, FunctionCallAction, StakeAction, TransferAction,
+    DeployContractAction, DeployGlobalContractAction, FunctionCallAction, GlobalContractDeployMode,
+    GlobalContractIdentifier, MigrateStateAction, SponsorStorageAction, StakeAction,
+    StakeWithMetadataAction, TransferAction, TransferTokenAction, UseGlobalContractAction,
 };
 use crate::errors::TxExecutionError;
 use crate::hash::{hash, CryptoHash};
@@ -44,7 +46,9 @@ pub struct TransactionV0 {
     pub actions: Vec<Action>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug, Clone, ProtocolSchema)]
+#[derive(
+    BorshSerialize, BorshDeserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, ProtocolSchema,
+)]
 pub struct TransactionV1 {
     /// An account on which behalf transaction is signed
     pub signer_id: AccountId,
@@ -64,6 +68,35 @@ pub struct TransactionV1 {
     pub priority_fee: u64,
 }
 
+#[derive(
+    BorshSerialize, BorshDeserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, ProtocolSchema,
+)]
+pub struct TransactionV2 {
+    /// An account on which behalf transaction is signed
+    pub signer_id: AccountId,
+    /// A public key of the access key which was used to sign an account.
+    /// Access key holds permissions for calling certain kinds of actions.
+    pub public_key: PublicKey,
+    /// Nonce is used to determine order of transaction in the pool.
+    /// It increments for a combination of `signer_id` and `public_key`
+    pub nonce: Nonce,
+    /// Receiver account for this transaction
+    pub receiver_id: AccountId,
+    /// The hash of the block in the blockchain on top of which the given transaction is valid
+    pub block_hash: CryptoHash,
+    /// A list of actions to be applied
+    pub actions: Vec<Action>,
+    /// Priority fee. Unit is 10^12 yotcoNEAR
+    pub priority_fee: u64,
+    /// The highest gas price the signer agrees to pay. The transaction is rejected
+    /// if the gas price at conversion is higher. It does not change the pessimistic
+    /// gas price at which the prepaid gas is bought.
+    pub max_gas_price: Balance,
+}
+
+/// Amount of yoctoNEAR in one unit of `TransactionV1::priority_fee`.
+pub const PRIORITY_FEE_UNIT: Balance = 1_000_000_000_000;
+
 impl Transaction {
     /// Computes a hash of the transaction for signing and size of serialized transaction
     pub fn get_hash_and_size(&self) -> (CryptoHash, u64) {
@@ -76,6 +109,7 @@ impl Transaction {
 pub enum Transaction {
     V0(TransactionV0),
     V1(TransactionV1),
+    V2(TransactionV2),
 }
 
 impl Transaction {
@@ -83,6 +117,7 @@ impl Transaction {
         match self {
             Transaction::V0(tx) => &tx.signer_id,
             Transaction::V1(tx) => &tx.signer_id,
+            Transaction::V2(tx) => &tx.signer_id,
         }
     }
 
@@ -90,6 +125,7 @@ impl Transaction {
         match self {
             Transaction::V0(tx) => &tx.receiver_id,
             Transaction::V1(tx) => &tx.receiver_id,
+            Transaction::V2(tx) => &tx.receiver_id,
         }
     }
 
@@ -97,6 +133,7 @@ impl Transaction {
         match self {
             Transaction::V0(tx) => &tx.public_key,
             Transaction::V1(tx) => &tx.public_key,
+            Transaction::V2(tx) => &tx.public_key,
         }
     }
 
@@ -104,6 +141,7 @@ impl Transaction {
         match self {
             Transaction::V0(tx) => tx.nonce,
             Transaction::V1(tx) => tx.nonce,
+            Transaction::V2(tx) => tx.nonce,
         }
     }
 
@@ -111,6 +149,7 @@ impl Transaction {
         match self {
             Transaction::V0(tx) => &tx.actions,
             Transaction::V1(tx) => &tx.actions,
+            Transaction::V2(tx) => &tx.actions,
         }
     }
 
@@ -118,6 +157,7 @@ impl Transaction {
         match self {
             Transaction::V0(tx) => tx.actions,
             Transaction::V1(tx) => tx.actions,
+            Transaction::V2(tx) => tx.actions,
         }
     }
 
@@ -125,6 +165,7 @@ impl Transaction {
         match self {
             Transaction::V0(tx) => &tx.block_hash,
             Transaction::V1(tx) => &tx.block_hash,
+            Transaction::V2(tx) => &tx.block_hash,
         }
     }
 
@@ -132,8 +173,22 @@ impl Transaction {
         match self {
             Transaction::V0(_) => None,
             Transaction::V1(tx) => Some(tx.priority_fee),
+            Transaction::V2(tx) => Some(tx.priority_fee),
+        }
+    }
+
+    /// The highest gas price the signer agrees to pay, if the transaction sets one.
+    pub fn max_gas_price(&self) -> Option<Balance> {
+        match self {
+            Transaction::V0(_) | Transaction::V1(_) => None,
+            Transaction::V2(tx) => Some(tx.max_gas_price),
         }
     }
+
+    /// Amount of tokens burnt on top of the regular fees to prioritize the transaction.
+    pub fn priority_fee_amount(&self) -> Balance {
+        Balance::from(self.priority_fee().unwrap_or_default()) * PRIORITY_FEE_UNIT
+    }
 }
 
 impl BorshSerialize for Transaction {
@@ -144,6 +199,10 @@ impl BorshSerialize for Transaction {
                 BorshSerialize::serialize(&1_u8, writer)?;
                 tx.serialize(writer)?;
             }
+            Transaction::V2(tx) => {
+                BorshSerialize::serialize(&2_u8, writer)?;
+                tx.serialize(writer)?;
+            }
         }
         Ok(())
     }
@@ -151,7 +210,7 @@ impl BorshSerialize for Transaction {
 
 impl BorshDeserialize for Transaction {
     /// Deserialize based on the first and second bytes of the stream. For V0, we do backward compatible deserialization by deserializing
-    /// the entire stream into V0. For V1, we consume the first byte and then deserialize the rest.
+    /// the entire stream into V0. For V1 and V2, we consume the first byte and then deserialize the rest.
     fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
         let u1 = u8::deserialize_reader(reader)?;
         let u2 = u8::deserialize_reader(reader)?;
@@ -200,15 +259,31 @@ impl BorshDeserialize for Transaction {
             let block_hash = CryptoHash::deserialize_reader(reader)?;
             let actions = Vec::<Action>::deserialize_reader(reader)?;
             let priority_fee = u64::deserialize_reader(reader)?;
-            Ok(Transaction::V1(TransactionV1 {
-                signer_id,
-                public_key,
-                nonce,
-                receiver_id,
-                block_hash,
-                actions,
-                priority_fee,
-            }))
+            match u1 {
+                1 => Ok(Transaction::V1(TransactionV1 {
+                    signer_id,
+                    public_key,
+                    nonce,
+                    receiver_id,
+                    block_hash,
+                    actions,
+                    priority_fee,
+                })),
+                2 => {
+                    let max_gas_price = Balance::deserialize_reader(reader)?;
+                    Ok(Transaction::V2(TransactionV2 {
+                        signer_id,
+                        public_key,
+                        nonce,
+                        receiver_id,
+                        block_hash,
+                        actions,
+                        priority_fee,
+                        max_gas_price,
+                    }))
+                }
+                _ => Err(Error::new(ErrorKind::InvalidData, "Unknown transaction version")),
+            }
         }
     }
 }
@@ -416,6 +491,29 @@ pub enum ExecutionMetadata {
     V2(crate::profile_data_v2::ProfileDataV2),
     /// V3: With ProfileData by gas parameters
     V3(Box<ProfileDataV3>),
+    /// V4: With ProfileData by gas parameters and the split of the unused gas
+    /// between the refund and the refund penalty
+    V4(Box<ProfileDataV3>, GasRefund),
+}
+
+impl ExecutionMetadata {
+    pub fn gas_refund(&self) -> Option<&GasRefund> {
+        match self {
+            ExecutionMetadata::V4(_, gas_refund) => Some(gas_refund),
+            ExecutionMetadata::V1 | ExecutionMetadata::V2(_) | ExecutionMetadata::V3(_) => None,
+        }
+    }
+}
+
+/// What happened to the unused gas of a receipt.
+#[derive(
+    BorshSerialize, BorshDeserialize, PartialEq, Clone, Eq, Debug, Default, ProtocolSchema,
+)]
+pub struct GasRefund {
+    /// Gas refunded to the signer.
+    pub gas_refunded: Gas,
+    /// Gas burnt instead of refunded, see `RuntimeFeesConfig::gas_refund_penalty`.
+    pub gas_refund_penalty: Gas,
 }
 
 impl fmt::Debug for ExecutionOutcome {
@@ -602,6 +700,28 @@ mod tests {
         }
     }
 
+    fn create_transaction_v2() -> TransactionV2 {
+        let TransactionV1 {
+            signer_id,
+            public_key,
+            nonce,
+            receiver_id,
+            block_hash,
+            actions,
+            priority_fee,
+        } = create_transaction_v1();
+        TransactionV2 {
+            signer_id,
+            public_key,
+            nonce,
+            receiver_id,
+            block_hash,
+            actions,
+            priority_fee,
+            max_gas_price: 1_000_000_000,
+        }
+    }
+
     /// This test is change checker for a reason - we don't expect transaction format to change.
     /// If it does - you MUST update all of the dependencies: like nearlib and other clients.
     #[test]
@@ -628,6 +748,15 @@ mod tests {
         let serialized_tx_v1 = borsh::to_vec(&transaction_v1).unwrap();
         let deserialized_tx_v1 = Transaction::try_from_slice(&serialized_tx_v1).unwrap();
         assert_eq!(transaction_v1, deserialized_tx_v1);
+
+        let transaction_v2 = Transaction::V2(create_transaction_v2());
+        let serialized_tx_v2 = borsh::to_vec(&transaction_v2).unwrap();
+        let deserialized_tx_v2 = Transaction::try_from_slice(&serialized_tx_v2).unwrap();
+        assert_eq!(transaction_v2, deserialized_tx_v2);
+
+        let mut serialized_tx_unknown = serialized_tx_v2;
+        serialized_tx_unknown[0] = 3;
+        assert!(Transaction::try_from_slice(&serialized_tx_unknown).is_err());
     }
 
     #[test]
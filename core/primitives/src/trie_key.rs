@@ -1,5 +1,6 @@
+use crate::action::GlobalContractIdentifier;
 use crate::hash::CryptoHash;
-use crate::types::AccountId;
+use crate::types::{AccountId, BlockHeight};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::PublicKey;
 use near_primitives_core::types::ShardId;
@@ -62,10 +63,37 @@ pub mod col {
     pub const BUFFERED_RECEIPT_GROUPS_QUEUE_DATA: u8 = 16;
     /// A single item of `ReceiptGroupsQueue`. Values are of type `ReceiptGroup`.
     pub const BUFFERED_RECEIPT_GROUPS_QUEUE_ITEM: u8 = 17;
+    /// This column id is used when storing `primitives::account::StorageSponsorship`
+    /// for a given `account_id`.
+    pub const STORAGE_SPONSORSHIP: u8 = 18;
+    /// This column id is used when storing the code of global contracts. Every shard
+    /// stores all global contracts.
+    pub const GLOBAL_CONTRACT_CODE: u8 = 19;
+    /// This column id is used when storing Key-Value data from a contract on an `account_id`
+    /// in one of its storage namespaces.
+    pub const NAMESPACED_CONTRACT_DATA: u8 = 20;
+    /// This column id is used when storing the storage usage `u64` of a storage namespace
+    /// of an `account_id`.
+    pub const STORAGE_NAMESPACE_USAGE: u8 = 21;
+    /// This column id is used when storing `primitives::account::MigrationGracePeriod`
+    /// for a given `account_id`.
+    pub const MIGRATION_GRACE_PERIOD: u8 = 22;
+    /// This column id is used when storing `primitives::account::ContractSourceMetadata`
+    /// of the contract deployed on a given `account_id`.
+    pub const CONTRACT_SOURCE_METADATA: u8 = 23;
+    /// This column id is used when storing the `Balance` an `account_id` holds of the
+    /// native token issued by a `token_id` account.
+    pub const TOKEN_BALANCE: u8 = 24;
+    /// This column id is used when storing the PromiseYield timeouts which don't expire
+    /// after the default number of blocks, keyed by the height they expire at.
+    pub const PROMISE_YIELD_TIMEOUT_AT: u8 = 25;
+    /// This column id is used when storing the `AccountId` of the deployer of the global
+    /// contract a given `account_id` uses.
+    pub const GLOBAL_CONTRACT_DEPLOYER: u8 = 26;
 
     /// All columns except those used for the delayed receipts queue, the yielded promises
     /// queue, and the outgoing receipts buffer, which are global state for the shard.
-    pub const COLUMNS_WITH_ACCOUNT_ID_IN_KEY: [(u8, &str); 9] = [
+    pub const COLUMNS_WITH_ACCOUNT_ID_IN_KEY: [(u8, &str); 16] = [
         (ACCOUNT, "Account"),
         (CONTRACT_CODE, "ContractCode"),
         (ACCESS_KEY, "AccessKey"),
@@ -75,9 +103,16 @@ pub mod col {
         (POSTPONED_RECEIPT, "PostponedReceipt"),
         (CONTRACT_DATA, "ContractData"),
         (PROMISE_YIELD_RECEIPT, "PromiseYieldReceipt"),
+        (STORAGE_SPONSORSHIP, "StorageSponsorship"),
+        (NAMESPACED_CONTRACT_DATA, "NamespacedContractData"),
+        (STORAGE_NAMESPACE_USAGE, "StorageNamespaceUsage"),
+        (MIGRATION_GRACE_PERIOD, "MigrationGracePeriod"),
+        (CONTRACT_SOURCE_METADATA, "ContractSourceMetadata"),
+        (TOKEN_BALANCE, "TokenBalance"),
+        (GLOBAL_CONTRACT_DEPLOYER, "GlobalContractDeployer"),
     ];
 
-    pub const ALL_COLUMNS_WITH_NAMES: [(u8, &'static str); 17] = [
+    pub const ALL_COLUMNS_WITH_NAMES: [(u8, &'static str); 26] = [
         (ACCOUNT, "Account"),
         (CONTRACT_CODE, "ContractCode"),
         (ACCESS_KEY, "AccessKey"),
@@ -95,9 +130,59 @@ pub mod col {
         (BANDWIDTH_SCHEDULER_STATE, "BandwidthSchedulerState"),
         (BUFFERED_RECEIPT_GROUPS_QUEUE_DATA, "BufferedReceiptGroupsQueueData"),
         (BUFFERED_RECEIPT_GROUPS_QUEUE_ITEM, "BufferedReceiptGroupsQueueItem"),
+        (STORAGE_SPONSORSHIP, "StorageSponsorship"),
+        (GLOBAL_CONTRACT_CODE, "GlobalContractCode"),
+        (NAMESPACED_CONTRACT_DATA, "NamespacedContractData"),
+        (STORAGE_NAMESPACE_USAGE, "StorageNamespaceUsage"),
+        (MIGRATION_GRACE_PERIOD, "MigrationGracePeriod"),
+        (CONTRACT_SOURCE_METADATA, "ContractSourceMetadata"),
+        (TOKEN_BALANCE, "TokenBalance"),
+        (PROMISE_YIELD_TIMEOUT_AT, "PromiseYieldTimeoutAt"),
+        (GLOBAL_CONTRACT_DEPLOYER, "GlobalContractDeployer"),
     ];
 }
 
+/// The first byte of the `GlobalContractIdentifier` in `TrieKey::GlobalContractCode`.
+const GLOBAL_CONTRACT_CODE_HASH_TAG: u8 = 0;
+const GLOBAL_CONTRACT_ACCOUNT_ID_TAG: u8 = 1;
+
+/// Name of a storage namespace in `TrieKey::NamespacedContractData`.
+///
+/// The namespace is prefixed by its length as `u8` in the raw key, so it can't be longer than
+/// 255 bytes, which is checked when creating it.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, ProtocolSchema)]
+pub struct StorageNamespace(Vec<u8>);
+
+impl StorageNamespace {
+    pub const MAX_LEN: usize = u8::MAX as usize;
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<u8>> for StorageNamespace {
+    type Error = std::io::Error;
+
+    fn try_from(namespace: Vec<u8>) -> Result<Self, Self::Error> {
+        if namespace.len() > Self::MAX_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("storage namespace is longer than {} bytes", Self::MAX_LEN),
+            ));
+        }
+        Ok(Self(namespace))
+    }
+}
+
+impl BorshDeserialize for StorageNamespace {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let namespace = Vec::<u8>::deserialize_reader(reader)?;
+        Self::try_from(namespace)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+}
+
 /// Describes the key of a specific key-value record in a state trie.
 #[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize, ProtocolSchema)]
 pub enum TrieKey {
@@ -193,6 +278,59 @@ pub enum TrieKey {
         receiving_shard: ShardId,
         index: u64,
     },
+    /// Used to store `primitives::account::StorageSponsorship` of a given `AccountId`.
+    StorageSponsorship {
+        account_id: AccountId,
+    },
+    /// Used to store `Vec<u8>` code of a global contract with the given identifier.
+    /// NOTE: Global contracts are stored on all shards, so the key doesn't belong to any account.
+    GlobalContractCode {
+        identifier: GlobalContractIdentifier,
+    },
+    /// Used to store a key-value record `Vec<u8>` within a storage namespace of the contract
+    /// deployed on a given `AccountId`.
+    NamespacedContractData {
+        account_id: AccountId,
+        namespace: StorageNamespace,
+        key: Vec<u8>,
+    },
+    /// Used to store the storage usage `u64` of all records in a storage namespace of a
+    /// given `AccountId`.
+    StorageNamespaceUsage {
+        account_id: AccountId,
+        namespace: Vec<u8>,
+    },
+    /// Used to store `primitives::account::MigrationGracePeriod` of a given `AccountId`.
+    MigrationGracePeriod {
+        account_id: AccountId,
+    },
+    /// Used to store `primitives::account::ContractSourceMetadata` of the contract
+    /// deployed on a given `AccountId`.
+    ContractSourceMetadata {
+        account_id: AccountId,
+    },
+    /// Used to store the `Balance` a given `AccountId` holds of the native token
+    /// issued by `token_id`.
+    TokenBalance {
+        account_id: AccountId,
+        token_id: AccountId,
+    },
+    /// Used to store a PromiseYield timeout which expires at the given height, for the
+    /// yielded promise with the given `data_id`. Unlike the `PromiseYieldTimeout` queue,
+    /// which only holds timeouts of the default length, these are ordered by `expires_at`.
+    /// NOTE: The height is stored in big-endian in the raw key, so that iterating over the
+    /// column yields the timeouts in the order they expire.
+    PromiseYieldTimeoutAt {
+        expires_at: BlockHeight,
+        data_id: CryptoHash,
+    },
+    /// Used to store the `AccountId` of the deployer of the global contract a given
+    /// `AccountId` uses, if it uses it by the deployer's account id. The code of such
+    /// contracts is resolved when it's called, so that the account follows the deployer's
+    /// updates.
+    GlobalContractDeployer {
+        account_id: AccountId,
+    },
 }
 
 /// Provides `len` function.
@@ -272,11 +410,53 @@ impl TrieKey {
             TrieKey::BufferedReceiptGroupsQueueData { .. } => {
                 col::BUFFERED_RECEIPT_GROUPS_QUEUE_DATA.len() + std::mem::size_of::<u64>()
             }
+            TrieKey::StorageSponsorship { account_id } => {
+                col::STORAGE_SPONSORSHIP.len() + account_id.len()
+            }
             TrieKey::BufferedReceiptGroupsQueueItem { index, .. } => {
                 col::BUFFERED_RECEIPT_GROUPS_QUEUE_ITEM.len()
                     + std::mem::size_of::<u64>()
                     + std::mem::size_of_val(index)
             }
+            TrieKey::GlobalContractCode { identifier } => {
+                col::GLOBAL_CONTRACT_CODE.len()
+                    + GLOBAL_CONTRACT_CODE_HASH_TAG.len()
+                    + identifier.len()
+            }
+            TrieKey::NamespacedContractData { account_id, namespace, key } => {
+                col::NAMESPACED_CONTRACT_DATA.len()
+                    + account_id.len()
+                    + ACCOUNT_DATA_SEPARATOR.len()
+                    + size_of::<u8>()
+                    + namespace.as_bytes().len()
+                    + key.len()
+            }
+            TrieKey::StorageNamespaceUsage { account_id, namespace } => {
+                col::STORAGE_NAMESPACE_USAGE.len()
+                    + account_id.len()
+                    + ACCOUNT_DATA_SEPARATOR.len()
+                    + namespace.len()
+            }
+            TrieKey::MigrationGracePeriod { account_id } => {
+                col::MIGRATION_GRACE_PERIOD.len() + account_id.len()
+            }
+            TrieKey::ContractSourceMetadata { account_id } => {
+                col::CONTRACT_SOURCE_METADATA.len() + account_id.len()
+            }
+            TrieKey::TokenBalance { account_id, token_id } => {
+                col::TOKEN_BALANCE.len()
+                    + account_id.len()
+                    + ACCOUNT_DATA_SEPARATOR.len()
+                    + token_id.len()
+            }
+            TrieKey::PromiseYieldTimeoutAt { data_id, .. } => {
+                col::PROMISE_YIELD_TIMEOUT_AT.len()
+                    + size_of::<BlockHeight>()
+                    + data_id.as_ref().len()
+            }
+            TrieKey::GlobalContractDeployer { account_id } => {
+                col::GLOBAL_CONTRACT_DEPLOYER.len() + account_id.len()
+            }
         }
     }
 
@@ -370,6 +550,62 @@ impl TrieKey {
                 buf.extend(&receiving_shard.to_le_bytes());
                 buf.extend(&index.to_le_bytes());
             }
+            TrieKey::StorageSponsorship { account_id } => {
+                buf.push(col::STORAGE_SPONSORSHIP);
+                buf.extend(account_id.as_bytes());
+            }
+            TrieKey::GlobalContractCode { identifier } => {
+                buf.push(col::GLOBAL_CONTRACT_CODE);
+                match identifier {
+                    GlobalContractIdentifier::CodeHash(hash) => {
+                        buf.push(GLOBAL_CONTRACT_CODE_HASH_TAG);
+                        buf.extend(hash.as_ref());
+                    }
+                    GlobalContractIdentifier::AccountId(account_id) => {
+                        buf.push(GLOBAL_CONTRACT_ACCOUNT_ID_TAG);
+                        buf.extend(account_id.as_bytes());
+                    }
+                }
+            }
+            TrieKey::NamespacedContractData { account_id, namespace, key } => {
+                buf.push(col::NAMESPACED_CONTRACT_DATA);
+                buf.extend(account_id.as_bytes());
+                buf.push(ACCOUNT_DATA_SEPARATOR);
+                let namespace = namespace.as_bytes();
+                // The length of a `StorageNamespace` fits in a byte by construction.
+                buf.push(namespace.len() as u8);
+                buf.extend(namespace);
+                buf.extend(key);
+            }
+            TrieKey::StorageNamespaceUsage { account_id, namespace } => {
+                buf.push(col::STORAGE_NAMESPACE_USAGE);
+                buf.extend(account_id.as_bytes());
+                buf.push(ACCOUNT_DATA_SEPARATOR);
+                buf.extend(namespace);
+            }
+            TrieKey::MigrationGracePeriod { account_id } => {
+                buf.push(col::MIGRATION_GRACE_PERIOD);
+                buf.extend(account_id.as_bytes());
+            }
+            TrieKey::ContractSourceMetadata { account_id } => {
+                buf.push(col::CONTRACT_SOURCE_METADATA);
+                buf.extend(account_id.as_bytes());
+            }
+            TrieKey::TokenBalance { account_id, token_id } => {
+                buf.push(col::TOKEN_BALANCE);
+                buf.extend(account_id.as_bytes());
+                buf.push(ACCOUNT_DATA_SEPARATOR);
+                buf.extend(token_id.as_bytes());
+            }
+            TrieKey::PromiseYieldTimeoutAt { expires_at, data_id } => {
+                buf.push(col::PROMISE_YIELD_TIMEOUT_AT);
+                buf.extend(&expires_at.to_be_bytes());
+                buf.extend(data_id.as_ref());
+            }
+            TrieKey::GlobalContractDeployer { account_id } => {
+                buf.push(col::GLOBAL_CONTRACT_DEPLOYER);
+                buf.extend(account_id.as_bytes());
+            }
         };
         debug_assert_eq!(expected_len, buf.len() - start_len);
     }
@@ -401,6 +637,15 @@ impl TrieKey {
             TrieKey::BandwidthSchedulerState => None,
             TrieKey::BufferedReceiptGroupsQueueData { .. } => None,
             TrieKey::BufferedReceiptGroupsQueueItem { .. } => None,
+            TrieKey::StorageSponsorship { account_id } => Some(account_id.clone()),
+            TrieKey::GlobalContractCode { .. } => None,
+            TrieKey::NamespacedContractData { account_id, .. } => Some(account_id.clone()),
+            TrieKey::StorageNamespaceUsage { account_id, .. } => Some(account_id.clone()),
+            TrieKey::MigrationGracePeriod { account_id } => Some(account_id.clone()),
+            TrieKey::ContractSourceMetadata { account_id } => Some(account_id.clone()),
+            TrieKey::TokenBalance { account_id, .. } => Some(account_id.clone()),
+            TrieKey::PromiseYieldTimeoutAt { .. } => None,
+            TrieKey::GlobalContractDeployer { account_id } => Some(account_id.clone()),
         }
     }
 }
@@ -437,6 +682,80 @@ pub mod trie_key_parsers {
         Ok(&raw_key[prefix_len..])
     }
 
+    /// Splits a raw `TrieKey::NamespacedContractData` key into the namespace and the key.
+    pub fn parse_namespace_and_key_from_namespaced_contract_data_key<'a>(
+        raw_key: &'a [u8],
+        account_id: &AccountId,
+    ) -> Result<(&'a [u8], &'a [u8]), std::io::Error> {
+        let prefix_len =
+            col::NAMESPACED_CONTRACT_DATA.len() + account_id.len() + ACCOUNT_DATA_SEPARATOR.len();
+        let Some((&namespace_len, tail)) =
+            raw_key.get(prefix_len..).and_then(|tail| tail.split_first())
+        else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "raw key is too short for TrieKey::NamespacedContractData",
+            ));
+        };
+        if tail.len() < namespace_len as usize {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "raw key is too short for the namespace of TrieKey::NamespacedContractData",
+            ));
+        }
+        Ok(tail.split_at(namespace_len as usize))
+    }
+
+    /// Returns the namespace of a raw `TrieKey::StorageNamespaceUsage` key.
+    pub fn parse_namespace_from_storage_namespace_usage_key<'a>(
+        raw_key: &'a [u8],
+        account_id: &AccountId,
+    ) -> Result<&'a [u8], std::io::Error> {
+        let prefix_len =
+            col::STORAGE_NAMESPACE_USAGE.len() + account_id.len() + ACCOUNT_DATA_SEPARATOR.len();
+        if raw_key.len() < prefix_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "raw key is too short for TrieKey::StorageNamespaceUsage",
+            ));
+        }
+        Ok(&raw_key[prefix_len..])
+    }
+
+    /// Returns the token id of a raw `TrieKey::TokenBalance` key.
+    pub fn parse_token_id_from_token_balance_key(
+        raw_key: &[u8],
+        account_id: &AccountId,
+    ) -> Result<AccountId, std::io::Error> {
+        let prefix_len = col::TOKEN_BALANCE.len() + account_id.len() + ACCOUNT_DATA_SEPARATOR.len();
+        if raw_key.len() < prefix_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "raw key is too short for TrieKey::TokenBalance",
+            ));
+        }
+        parse_account_id_from_slice(&raw_key[prefix_len..], "TokenBalance")
+    }
+
+    pub fn parse_promise_yield_timeout_at_key(
+        raw_key: &[u8],
+    ) -> Result<(BlockHeight, CryptoHash), std::io::Error> {
+        let invalid = || {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "raw key has invalid length for TrieKey::PromiseYieldTimeoutAt",
+            )
+        };
+        let key = raw_key.strip_prefix(&[col::PROMISE_YIELD_TIMEOUT_AT]).ok_or_else(invalid)?;
+        if key.len() != size_of::<BlockHeight>() + size_of::<CryptoHash>() {
+            return Err(invalid());
+        }
+        let (expires_at, data_id) = key.split_at(size_of::<BlockHeight>());
+        let expires_at = BlockHeight::from_be_bytes(expires_at.try_into().unwrap());
+        let data_id = CryptoHash::try_from(data_id).map_err(|_| invalid())?;
+        Ok((expires_at, data_id))
+    }
+
     pub fn parse_account_id_prefix<'a>(
         column: u8,
         raw_key: &'a [u8],
@@ -503,6 +822,34 @@ pub mod trie_key_parsers {
         parse_account_id_from_slice(account_id, "Account")
     }
 
+    pub fn parse_account_id_from_storage_sponsorship_key(
+        raw_key: &[u8],
+    ) -> Result<AccountId, std::io::Error> {
+        let account_id = parse_account_id_prefix(col::STORAGE_SPONSORSHIP, raw_key)?;
+        parse_account_id_from_slice(account_id, "StorageSponsorship")
+    }
+
+    pub fn parse_account_id_from_migration_grace_period_key(
+        raw_key: &[u8],
+    ) -> Result<AccountId, std::io::Error> {
+        let account_id = parse_account_id_prefix(col::MIGRATION_GRACE_PERIOD, raw_key)?;
+        parse_account_id_from_slice(account_id, "MigrationGracePeriod")
+    }
+
+    pub fn parse_account_id_from_contract_source_metadata_key(
+        raw_key: &[u8],
+    ) -> Result<AccountId, std::io::Error> {
+        let account_id = parse_account_id_prefix(col::CONTRACT_SOURCE_METADATA, raw_key)?;
+        parse_account_id_from_slice(account_id, "ContractSourceMetadata")
+    }
+
+    pub fn parse_account_id_from_global_contract_deployer_key(
+        raw_key: &[u8],
+    ) -> Result<AccountId, std::io::Error> {
+        let account_id = parse_account_id_prefix(col::GLOBAL_CONTRACT_DEPLOYER, raw_key)?;
+        parse_account_id_from_slice(account_id, "GlobalContractDeployer")
+    }
+
     pub fn parse_account_id_from_access_key_key(
         raw_key: &[u8],
     ) -> Result<AccountId, std::io::Error> {
@@ -543,6 +890,16 @@ pub mod trie_key_parsers {
                 col::ACCOUNT => parse_account_id_from_account_key(raw_key)?,
                 col::CONTRACT_CODE => parse_account_id_from_contract_code_key(raw_key)?,
                 col::ACCESS_KEY => parse_account_id_from_access_key_key(raw_key)?,
+                col::STORAGE_SPONSORSHIP => parse_account_id_from_storage_sponsorship_key(raw_key)?,
+                col::MIGRATION_GRACE_PERIOD => {
+                    parse_account_id_from_migration_grace_period_key(raw_key)?
+                }
+                col::CONTRACT_SOURCE_METADATA => {
+                    parse_account_id_from_contract_source_metadata_key(raw_key)?
+                }
+                col::GLOBAL_CONTRACT_DEPLOYER => {
+                    parse_account_id_from_global_contract_deployer_key(raw_key)?
+                }
                 _ => parse_account_id_from_trie_key_with_separator(col, raw_key, col_name)?,
             };
             return Ok(Some(account_id));
@@ -612,6 +969,17 @@ pub mod trie_key_parsers {
         res.extend(prefix);
         res
     }
+
+    /// Prefix of all keys of the given column which belong to the account, for
+    /// columns separating the account id with `ACCOUNT_DATA_SEPARATOR`.
+    pub fn get_raw_prefix_for_account_data(column: u8, account_id: &AccountId) -> Vec<u8> {
+        let mut res =
+            Vec::with_capacity(column.len() + account_id.len() + ACCOUNT_DATA_SEPARATOR.len());
+        res.push(column);
+        res.extend(account_id.as_bytes());
+        res.push(ACCOUNT_DATA_SEPARATOR);
+        res
+    }
 }
 
 #[cfg(test)]
@@ -721,6 +1089,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_key_for_token_balance_consistency() {
+        let token_id: AccountId = "usdc.near".parse().unwrap();
+        for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
+            let key = TrieKey::TokenBalance {
+                account_id: account_id.clone(),
+                token_id: token_id.clone(),
+            };
+            let raw_key = key.to_vec();
+            assert_eq!(raw_key.len(), key.len());
+            assert_eq!(
+                trie_key_parsers::parse_token_id_from_token_balance_key(&raw_key, &account_id)
+                    .unwrap(),
+                token_id
+            );
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().unwrap(),
+                account_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_for_namespaced_data_consistency() {
+        let namespace = b"ns" as &[u8];
+        let data_key = b"0123456789" as &[u8];
+        for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
+            let key = TrieKey::NamespacedContractData {
+                account_id: account_id.clone(),
+                namespace: namespace.to_vec().try_into().unwrap(),
+                key: data_key.to_vec(),
+            };
+            let raw_key = key.to_vec();
+            assert_eq!(raw_key.len(), key.len());
+            assert_eq!(
+                trie_key_parsers::parse_namespace_and_key_from_namespaced_contract_data_key(
+                    &raw_key,
+                    &account_id
+                )
+                .unwrap(),
+                (namespace, data_key)
+            );
+            assert!(raw_key.starts_with(&trie_key_parsers::get_raw_prefix_for_account_data(
+                col::NAMESPACED_CONTRACT_DATA,
+                &account_id
+            )));
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().unwrap(),
+                account_id
+            );
+
+            let key = TrieKey::StorageNamespaceUsage {
+                account_id: account_id.clone(),
+                namespace: namespace.to_vec(),
+            };
+            let raw_key = key.to_vec();
+            assert_eq!(raw_key.len(), key.len());
+            assert_eq!(
+                trie_key_parsers::parse_namespace_from_storage_namespace_usage_key(
+                    &raw_key,
+                    &account_id
+                )
+                .unwrap(),
+                namespace
+            );
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().unwrap(),
+                account_id
+            );
+        }
+    }
+
     #[test]
     fn test_key_for_code_consistency() {
         for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
@@ -736,6 +1176,79 @@ mod tests {
                 account_id
             );
         }
+        assert!(StorageNamespace::try_from(vec![0; StorageNamespace::MAX_LEN]).is_ok());
+        assert!(StorageNamespace::try_from(vec![0; StorageNamespace::MAX_LEN + 1]).is_err());
+    }
+
+    #[test]
+    fn test_key_for_storage_sponsorship_consistency() {
+        for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
+            let key = TrieKey::StorageSponsorship { account_id: account_id.clone() };
+            let raw_key = key.to_vec();
+            assert_eq!(raw_key.len(), key.len());
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_storage_sponsorship_key(&raw_key).unwrap(),
+                account_id
+            );
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().unwrap(),
+                account_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_for_global_contract_deployer_consistency() {
+        for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
+            let key = TrieKey::GlobalContractDeployer { account_id: account_id.clone() };
+            let raw_key = key.to_vec();
+            assert_eq!(raw_key.len(), key.len());
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_global_contract_deployer_key(&raw_key)
+                    .unwrap(),
+                account_id
+            );
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().unwrap(),
+                account_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_for_migration_grace_period_consistency() {
+        for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
+            let key = TrieKey::MigrationGracePeriod { account_id: account_id.clone() };
+            let raw_key = key.to_vec();
+            assert_eq!(raw_key.len(), key.len());
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_migration_grace_period_key(&raw_key)
+                    .unwrap(),
+                account_id
+            );
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().unwrap(),
+                account_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_for_contract_source_metadata_consistency() {
+        for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
+            let key = TrieKey::ContractSourceMetadata { account_id: account_id.clone() };
+            let raw_key = key.to_vec();
+            assert_eq!(raw_key.len(), key.len());
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_contract_source_metadata_key(&raw_key)
+                    .unwrap(),
+                account_id
+            );
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().unwrap(),
+                account_id
+            );
+        }
     }
 
     #[test]
@@ -829,6 +1342,19 @@ mod tests {
         let key = TrieKey::PromiseYieldTimeout { index: 0 };
         let raw_key = key.to_vec();
         assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
+        let data_id = CryptoHash::hash_bytes(b"data");
+        let key = TrieKey::PromiseYieldTimeoutAt { expires_at: 1 << 8, data_id };
+        let raw_key = key.to_vec();
+        assert_eq!(raw_key.len(), key.len());
+        assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
+        assert_eq!(
+            trie_key_parsers::parse_promise_yield_timeout_at_key(&raw_key).unwrap(),
+            (1 << 8, data_id)
+        );
+        // Raw keys are ordered by the height the timeouts expire at.
+        let earlier_key =
+            TrieKey::PromiseYieldTimeoutAt { expires_at: 2, data_id: CryptoHash([u8::MAX; 32]) };
+        assert!(earlier_key.to_vec() < raw_key);
         for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
             let key = TrieKey::PromiseYieldReceipt {
                 receiver_id: account_id.clone(),
@@ -843,6 +1369,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_key_for_global_contract_code_consistency() {
+        let key = TrieKey::GlobalContractCode {
+            identifier: GlobalContractIdentifier::CodeHash(CryptoHash::hash_bytes(b"code")),
+        };
+        let raw_key = key.to_vec();
+        assert_eq!(raw_key.len(), key.len());
+        assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
+        for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
+            let key = TrieKey::GlobalContractCode {
+                identifier: GlobalContractIdentifier::AccountId(account_id),
+            };
+            let raw_key = key.to_vec();
+            assert_eq!(raw_key.len(), key.len());
+            assert!(key.get_account_id().is_none());
+            assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
+        }
+    }
+
     #[test]
     fn test_account_id_from_trie_key() {
         for account_id_str in OK_ACCOUNT_IDS {
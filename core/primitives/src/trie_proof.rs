@@ -0,0 +1,204 @@
+//! Proofs of the contents of a range of trie keys.
+//!
+//! A `TrieRangeProof` contains the trie nodes and values visited while
+//! iterating over `[start_key, end_key)`, including the nodes on the paths to
+//! both boundaries. Walking the trie from the state root over these nodes
+//! yields every key-value pair of the range, so the verifier learns not only
+//! that the returned pairs are in the state but also that no other key of the
+//! range is. Proofs are generated by `Trie::prove_range` in near-store.
+//!
+//! Nodes are decoded here instead of using `RawTrieNodeWithSize` because the
+//! trie node types live in near-store, which depends on this crate.
+
+use crate::challenge::PartialState;
+use crate::hash::CryptoHash;
+use crate::state::ValueRef;
+use crate::types::StateRoot;
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::collections::HashMap;
+use std::io;
+
+/// Trie nodes and values proving all key-value pairs of `[start_key, end_key)`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TrieRangeProof {
+    pub start_key: Vec<u8>,
+    /// Exclusive end of the range.
+    pub end_key: Vec<u8>,
+    pub nodes: PartialState,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum TrieRangeProofError {
+    #[error("trie node or value {0} is missing from the proof")]
+    MissingNode(CryptoHash),
+    #[error("trie node {0} can't be decoded")]
+    InvalidNode(CryptoHash),
+    #[error("value {0} doesn't match the length of its reference")]
+    InvalidValue(CryptoHash),
+    #[error("range proofs must be given as trie values")]
+    UnsupportedPartialState,
+}
+
+pub type TrieRangeItems = Vec<(Vec<u8>, Vec<u8>)>;
+
+enum ProofNode {
+    Leaf(Vec<u8>, ValueRef),
+    Branch(Option<ValueRef>, [Option<CryptoHash>; 16]),
+    Extension(Vec<u8>, CryptoHash),
+}
+
+impl ProofNode {
+    /// Decodes borsh-serialized `RawTrieNodeWithSize`.
+    fn decode(mut bytes: &[u8]) -> io::Result<Self> {
+        let rd = &mut bytes;
+        let node = match u8::deserialize_reader(rd)? {
+            0 => Self::Leaf(Vec::deserialize_reader(rd)?, ValueRef::deserialize_reader(rd)?),
+            1 => Self::Branch(None, Self::decode_children(rd)?),
+            2 => {
+                let value = ValueRef::deserialize_reader(rd)?;
+                Self::Branch(Some(value), Self::decode_children(rd)?)
+            }
+            3 => Self::Extension(Vec::deserialize_reader(rd)?, CryptoHash::deserialize_reader(rd)?),
+            tag => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected trie node tag: {}", tag),
+                ))
+            }
+        };
+        // Memory usage of the subtree isn't needed to read the range.
+        u64::deserialize_reader(rd)?;
+        if !rd.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "trailing bytes"));
+        }
+        Ok(node)
+    }
+
+    fn decode_children(rd: &mut &[u8]) -> io::Result<[Option<CryptoHash>; 16]> {
+        let bitmap = u16::deserialize_reader(rd)?;
+        let mut children = [None; 16];
+        for (idx, child) in children.iter_mut().enumerate() {
+            if bitmap & (1 << idx) != 0 {
+                *child = Some(CryptoHash::deserialize_reader(rd)?);
+            }
+        }
+        Ok(children)
+    }
+}
+
+fn bytes_to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|byte| [byte >> 4, byte & 0xf]).collect()
+}
+
+fn nibbles_to_bytes(nibbles: &[u8]) -> Vec<u8> {
+    nibbles.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect()
+}
+
+/// Decodes the nibbles of a leaf or extension key in the hex-prefix encoding
+/// used by the trie.
+fn decode_key_nibbles(encoded: &[u8]) -> Option<Vec<u8>> {
+    let (&first, rest) = encoded.split_first()?;
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if first & 0x10 != 0 {
+        nibbles.push(first & 0xf);
+    }
+    nibbles.extend(bytes_to_nibbles(rest));
+    Some(nibbles)
+}
+
+struct RangeVerifier<'a> {
+    values: HashMap<CryptoHash, &'a [u8]>,
+    start: Vec<u8>,
+    end: Vec<u8>,
+    items: TrieRangeItems,
+}
+
+impl RangeVerifier<'_> {
+    /// Whether any key starting with `prefix` is inside the range.
+    fn intersects(&self, prefix: &[u8]) -> bool {
+        prefix < self.end.as_slice() && prefix >= &self.start[..prefix.len().min(self.start.len())]
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        key.len() % 2 == 0 && key >= self.start.as_slice() && key < self.end.as_slice()
+    }
+
+    fn get(&self, hash: &CryptoHash) -> Result<&[u8], TrieRangeProofError> {
+        self.values.get(hash).copied().ok_or(TrieRangeProofError::MissingNode(*hash))
+    }
+
+    fn push_value(&mut self, key: &[u8], value_ref: &ValueRef) -> Result<(), TrieRangeProofError> {
+        let value = self.get(&value_ref.hash)?;
+        if value.len() != value_ref.len() {
+            return Err(TrieRangeProofError::InvalidValue(value_ref.hash));
+        }
+        self.items.push((nibbles_to_bytes(key), value.to_vec()));
+        Ok(())
+    }
+
+    fn visit(&mut self, hash: &CryptoHash, key: &mut Vec<u8>) -> Result<(), TrieRangeProofError> {
+        let node = ProofNode::decode(self.get(hash)?)
+            .map_err(|_| TrieRangeProofError::InvalidNode(*hash))?;
+        let len = key.len();
+        match node {
+            ProofNode::Leaf(encoded_key, value_ref) => {
+                key.extend(
+                    decode_key_nibbles(&encoded_key)
+                        .ok_or(TrieRangeProofError::InvalidNode(*hash))?,
+                );
+                if self.contains(key) {
+                    self.push_value(key, &value_ref)?;
+                }
+            }
+            ProofNode::Branch(value_ref, children) => {
+                if let Some(value_ref) = value_ref {
+                    if self.contains(key) {
+                        self.push_value(key, &value_ref)?;
+                    }
+                }
+                for (idx, child) in children.iter().enumerate() {
+                    let Some(child) = child else { continue };
+                    key.push(idx as u8);
+                    if self.intersects(key) {
+                        self.visit(child, key)?;
+                    }
+                    key.truncate(len);
+                }
+            }
+            ProofNode::Extension(encoded_key, child) => {
+                key.extend(
+                    decode_key_nibbles(&encoded_key)
+                        .ok_or(TrieRangeProofError::InvalidNode(*hash))?,
+                );
+                if self.intersects(key) {
+                    self.visit(&child, key)?;
+                }
+            }
+        }
+        key.truncate(len);
+        Ok(())
+    }
+}
+
+impl TrieRangeProof {
+    /// Checks the proof against `state_root` and returns all key-value pairs
+    /// of the range, sorted by key.
+    pub fn verify(&self, state_root: &StateRoot) -> Result<TrieRangeItems, TrieRangeProofError> {
+        let PartialState::TrieValues(values) = &self.nodes else {
+            return Err(TrieRangeProofError::UnsupportedPartialState);
+        };
+        let mut verifier = RangeVerifier {
+            values: values
+                .iter()
+                .map(|value| (CryptoHash::hash_bytes(value), &value[..]))
+                .collect(),
+            start: bytes_to_nibbles(&self.start_key),
+            end: bytes_to_nibbles(&self.end_key),
+            items: vec![],
+        };
+        if *state_root != CryptoHash::default() && self.start_key < self.end_key {
+            verifier.visit(state_root, &mut vec![])?;
+        }
+        Ok(verifier.items)
+    }
+}
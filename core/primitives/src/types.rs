@@ -354,12 +354,21 @@ impl StateChanges {
                 TrieKey::DelayedReceipt { .. } => {}
                 TrieKey::PromiseYieldIndices => {}
                 TrieKey::PromiseYieldTimeout { .. } => {}
+                TrieKey::PromiseYieldTimeoutAt { .. } => {}
+                TrieKey::GlobalContractDeployer { .. } => {}
                 TrieKey::PromiseYieldReceipt { .. } => {}
                 TrieKey::BufferedReceiptIndices => {}
                 TrieKey::BufferedReceipt { .. } => {}
                 TrieKey::BandwidthSchedulerState => {}
                 TrieKey::BufferedReceiptGroupsQueueData { .. } => {}
                 TrieKey::BufferedReceiptGroupsQueueItem { .. } => {}
+                TrieKey::StorageSponsorship { .. } => {}
+                TrieKey::GlobalContractCode { .. } => {}
+                TrieKey::NamespacedContractData { .. } => {}
+                TrieKey::StorageNamespaceUsage { .. } => {}
+                TrieKey::MigrationGracePeriod { .. } => {}
+                TrieKey::ContractSourceMetadata { .. } => {}
+                TrieKey::TokenBalance { .. } => {}
             }
         }
 
@@ -509,13 +518,15 @@ pub mod validator_stake {
     use near_primitives_core::types::{AccountId, Balance};
     use serde::Serialize;
 
-    pub use super::ValidatorStakeV1;
+    pub use super::{ValidatorMetadata, ValidatorStakeV1, ValidatorStakeV2};
 
     /// Stores validator and its stake.
     #[derive(BorshSerialize, BorshDeserialize, Serialize, Debug, Clone, PartialEq, Eq)]
     #[serde(tag = "validator_stake_struct_version")]
     pub enum ValidatorStake {
         V1(ValidatorStakeV1),
+        /// V1 -> V2: add metadata reported by the validator when staking.
+        V2(ValidatorStakeV2),
         // Warning: if you're adding a new version, make sure that the borsh encoding of
         // any `ValidatorStake` cannot be equal to the borsh encoding of any `ValidatorStakeV1`.
         // See `EpochSyncProofEpochData::use_versioned_bp_hash_format` for an explanation.
@@ -588,13 +599,28 @@ pub mod validator_stake {
             Self::new_v1(account_id, public_key, stake)
         }
 
+        pub fn new_with_metadata(
+            account_id: AccountId,
+            public_key: PublicKey,
+            stake: Balance,
+            metadata: ValidatorMetadata,
+        ) -> Self {
+            Self::V2(ValidatorStakeV2 { account_id, public_key, stake, metadata })
+        }
+
         pub fn test(account_id: AccountId) -> Self {
             Self::new_v1(account_id, PublicKey::empty(KeyType::ED25519), 0)
         }
 
+        /// Drops the metadata of `V2`.
         pub fn into_v1(self) -> ValidatorStakeV1 {
             match self {
                 Self::V1(v1) => v1,
+                Self::V2(v2) => ValidatorStakeV1 {
+                    account_id: v2.account_id,
+                    public_key: v2.public_key,
+                    stake: v2.stake,
+                },
             }
         }
 
@@ -602,6 +628,7 @@ pub mod validator_stake {
         pub fn account_and_stake(self) -> (AccountId, Balance) {
             match self {
                 Self::V1(v1) => (v1.account_id, v1.stake),
+                Self::V2(v2) => (v2.account_id, v2.stake),
             }
         }
 
@@ -609,6 +636,7 @@ pub mod validator_stake {
         pub fn destructure(self) -> (AccountId, PublicKey, Balance) {
             match self {
                 Self::V1(v1) => (v1.account_id, v1.public_key, v1.stake),
+                Self::V2(v2) => (v2.account_id, v2.public_key, v2.stake),
             }
         }
 
@@ -616,6 +644,7 @@ pub mod validator_stake {
         pub fn take_account_id(self) -> AccountId {
             match self {
                 Self::V1(v1) => v1.account_id,
+                Self::V2(v2) => v2.account_id,
             }
         }
 
@@ -623,6 +652,7 @@ pub mod validator_stake {
         pub fn account_id(&self) -> &AccountId {
             match self {
                 Self::V1(v1) => &v1.account_id,
+                Self::V2(v2) => &v2.account_id,
             }
         }
 
@@ -630,6 +660,7 @@ pub mod validator_stake {
         pub fn take_public_key(self) -> PublicKey {
             match self {
                 Self::V1(v1) => v1.public_key,
+                Self::V2(v2) => v2.public_key,
             }
         }
 
@@ -637,6 +668,7 @@ pub mod validator_stake {
         pub fn public_key(&self) -> &PublicKey {
             match self {
                 Self::V1(v1) => &v1.public_key,
+                Self::V2(v2) => &v2.public_key,
             }
         }
 
@@ -644,6 +676,7 @@ pub mod validator_stake {
         pub fn stake(&self) -> Balance {
             match self {
                 Self::V1(v1) => v1.stake,
+                Self::V2(v2) => v2.stake,
             }
         }
 
@@ -651,6 +684,15 @@ pub mod validator_stake {
         pub fn stake_mut(&mut self) -> &mut Balance {
             match self {
                 Self::V1(v1) => &mut v1.stake,
+                Self::V2(v2) => &mut v2.stake,
+            }
+        }
+
+        #[inline]
+        pub fn metadata(&self) -> Option<&ValidatorMetadata> {
+            match self {
+                Self::V1(_) => None,
+                Self::V2(v2) => Some(&v2.metadata),
             }
         }
 
@@ -722,6 +764,49 @@ pub struct ValidatorStakeV1 {
     pub stake: Balance,
 }
 
+/// Information about the node of a validator, reported by the validator itself
+/// when staking. It is not verified in any way and only helps to coordinate
+/// upgrades and connectivity between validators.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    ProtocolSchema,
+)]
+pub struct ValidatorMetadata {
+    /// Version of the node software, e.g. `2.4.0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_version: Option<String>,
+    /// Address other validators can try to reach the node at, e.g. `1.2.3.4:24567`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p2p_endpoint: Option<String>,
+}
+
+impl ValidatorMetadata {
+    /// Maximum length of each metadata field in bytes.
+    pub const MAX_FIELD_LEN: usize = 64;
+}
+
+/// Stores validator, its stake and metadata.
+#[derive(
+    BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, PartialEq, Eq, ProtocolSchema,
+)]
+pub struct ValidatorStakeV2 {
+    /// Account that stakes money.
+    pub account_id: AccountId,
+    /// Public key of the proposed validator.
+    pub public_key: PublicKey,
+    /// Stake / weight of the validator.
+    pub stake: Balance,
+    pub metadata: ValidatorMetadata,
+}
+
 /// Information after block was processed.
 #[derive(Debug, PartialEq, BorshSerialize, BorshDeserialize, Clone, Eq, ProtocolSchema)]
 pub struct BlockExtra {
@@ -201,6 +201,24 @@ pub fn get_block_shard_id_rev(
     Ok((block_hash, shard_id))
 }
 
+/// Key of `DBCol::Challenges`. The height goes first so that the column is
+/// ordered by height, which makes garbage collection cheap.
+pub fn get_challenge_key(received_height: BlockHeight, challenge_hash: &CryptoHash) -> Vec<u8> {
+    let mut res = Vec::with_capacity(40);
+    res.extend_from_slice(&received_height.to_be_bytes());
+    res.extend_from_slice(challenge_hash.as_ref());
+    res
+}
+
+/// Key of `DBCol::InvalidBlocks`. The height goes first so that the invalid
+/// blocks are garbage collected with a range delete.
+pub fn get_invalid_block_key(height: BlockHeight, block_hash: &CryptoHash) -> Vec<u8> {
+    let mut res = Vec::with_capacity(40);
+    res.extend_from_slice(&height.to_be_bytes());
+    res.extend_from_slice(block_hash.as_ref());
+    res
+}
+
 pub fn get_outcome_id_block_hash(outcome_id: &CryptoHash, block_hash: &CryptoHash) -> Vec<u8> {
     let mut res = Vec::with_capacity(64);
     res.extend_from_slice(outcome_id.as_ref());
@@ -486,6 +504,19 @@ where
     Serializable(object)
 }
 
+/// Wrapper printing the full contents of objects whose `Debug` only prints
+/// a summary, such as blocks, chunks and state witnesses.
+pub struct DetailedDebug<'a, T>(pub(crate) &'a T);
+
+/// Wrap an object whose `Debug` output is summarized into another object
+/// whose `Debug` output contains all of its fields. Full dumps of blocks or
+/// state witnesses can take megabytes, so use it only for diagnostics.
+///
+/// tracing::debug!(target: "diagnostic", block=?detailed(&block));
+pub fn detailed<T>(object: &T) -> DetailedDebug<'_, T> {
+    DetailedDebug(object)
+}
+
 /// From `near-account-id` version `1.0.0-alpha.2`, `is_implicit` returns true for ETH-implicit accounts.
 /// This function is a wrapper for `is_implicit` method so that we can easily differentiate its behavior
 /// based on whether ETH-implicit accounts are enabled.
@@ -686,4 +717,23 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_detailed_debug() {
+        let block = crate::block::Block::genesis(
+            crate::version::PROTOCOL_VERSION,
+            vec![],
+            near_time::Utc::UNIX_EPOCH,
+            0,
+            1_000,
+            1_000,
+            Default::default(),
+        );
+        let summary = format!("{:?}", block);
+        assert!(summary.contains(&format!("hash: {:?}", block.hash())));
+        assert!(!summary.contains("vrf_proof"));
+        let detailed = format!("{:?}", detailed(&block));
+        assert!(detailed.contains("vrf_proof"));
+        assert!(detailed.len() > summary.len());
+    }
 }
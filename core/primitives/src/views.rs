@@ -3,38 +3,55 @@
 //! These types should only change when we cannot avoid this. Thus, when the counterpart internal
 //! type gets changed, the view should preserve the old shape and only re-map the necessary bits
 //! from the source structure in the relevant `From<SourceStruct>` impl.
-use crate::account::{AccessKey, AccessKeyPermission, Account, FunctionCallPermission};
+use crate::account::{
+    AccessKey, AccessKeyPermission, Account, ContractSourceMetadata, ContractStandard,
+    FunctionCallPermission, StorageSponsorship,
+};
 use crate::action::delegate::{DelegateAction, SignedDelegateAction};
-use crate::bandwidth_scheduler::BandwidthRequests;
+use crate::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 use crate::block::{Block, BlockHeader, Tip};
 use crate::block_header::BlockHeaderInnerLite;
-use crate::challenge::{Challenge, ChallengesResult};
+use crate::challenge::{
+    Challenge, ChallengeBody, ChallengesResult, MaybeEncodedShardChunk, PartialState,
+};
 use crate::congestion_info::{CongestionInfo, CongestionInfoV1};
-use crate::errors::TxExecutionError;
+use crate::errors::{ActionErrorKind, PrimitiveValidationError, TxExecutionError};
 use crate::hash::{hash, CryptoHash};
 use crate::merkle::{combine_hash, MerklePath};
-use crate::network::PeerId;
-use crate::receipt::{ActionReceipt, DataReceipt, DataReceiver, Receipt, ReceiptEnum, ReceiptV1};
+use crate::network::{PeerId, Reachability};
+use crate::receipt::{
+    ActionReceipt, DataReceipt, DataReceiver, GlobalContractDistributionReceipt, Receipt,
+    ReceiptEnum, ReceiptV1, ReceiptV2,
+};
 use crate::serialize::dec_format;
-use crate::sharding::shard_chunk_header_inner::ShardChunkHeaderInnerV4;
+use crate::sharding::shard_chunk_header_inner::{
+    ShardChunkHeaderInnerV4, ShardChunkHeaderInnerV5, ShardChunkHeaderInnerV6,
+    ShardChunkHeaderInnerV7,
+};
 use crate::sharding::{
-    ChunkHash, ShardChunk, ShardChunkHeader, ShardChunkHeaderInner, ShardChunkHeaderInnerV2,
-    ShardChunkHeaderInnerV3, ShardChunkHeaderV3,
+    ChunkHash, EncodedShardChunk, ShardChunk, ShardChunkHeader, ShardChunkHeaderInner,
+    ShardChunkHeaderInnerV2, ShardChunkHeaderInnerV3, ShardChunkHeaderV3,
 };
 use crate::stateless_validation::chunk_endorsements_bitmap::ChunkEndorsementsBitmap;
+use crate::stateless_validation::endorsement_stats::ValidatorEndorsementStats;
 #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
 use crate::transaction::NonrefundableStorageTransferAction;
 use crate::transaction::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
     DeployContractAction, ExecutionMetadata, ExecutionOutcome, ExecutionOutcomeWithIdAndProof,
-    ExecutionStatus, FunctionCallAction, PartialExecutionOutcome, PartialExecutionStatus,
-    SignedTransaction, StakeAction, TransferAction,
+    ExecutionStatus, FunctionCallAction, GasRefund, PartialExecutionOutcome,
+    PartialExecutionStatus, SignedTransaction, SponsorStorageAction, StakeAction,
+    StakeWithMetadataAction, TransferAction, TransferTokenAction,
+};
+use crate::transaction::{
+    DeployGlobalContractAction, GlobalContractDeployMode, GlobalContractIdentifier,
+    MigrateStateAction, UseGlobalContractAction,
 };
 use crate::types::{
     AccountId, AccountWithPublicKey, Balance, BlockHeight, EpochHeight, EpochId, FunctionArgs, Gas,
     Nonce, NumBlocks, ShardId, StateChangeCause, StateChangeKind, StateChangeValue,
     StateChangeWithCause, StateChangesRequest, StateRoot, StorageUsage, StoreKey, StoreValue,
-    ValidatorKickoutReason,
+    ValidatorKickoutReason, ValidatorMetadata,
 };
 use crate::version::{ProtocolVersion, Version};
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -245,6 +262,66 @@ impl FromIterator<AccessKeyInfoView> for AccessKeyList {
     }
 }
 
+/// A view of the storage sponsorship of an account.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct StorageSponsorshipView {
+    /// `None` if the storage staking of the account is not sponsored.
+    pub sponsor_id: Option<AccountId>,
+    #[serde(with = "dec_format")]
+    pub deposit: Balance,
+}
+
+impl From<Option<StorageSponsorship>> for StorageSponsorshipView {
+    fn from(sponsorship: Option<StorageSponsorship>) -> Self {
+        match sponsorship {
+            Some(StorageSponsorship { sponsor_id, deposit }) => {
+                Self { sponsor_id: Some(sponsor_id), deposit }
+            }
+            None => Self { sponsor_id: None, deposit: 0 },
+        }
+    }
+}
+
+/// A view of the balance an account holds of a native token.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct TokenBalanceView {
+    pub token_id: AccountId,
+    #[serde(with = "dec_format")]
+    pub balance: Balance,
+}
+
+/// A view of the NEP-330 source metadata embedded in the contract of an account.
+/// All fields are empty if the contract has no embedded metadata.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ContractSourceMetadataView {
+    pub version: Option<String>,
+    pub link: Option<String>,
+    pub standards: Vec<ContractStandardView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ContractStandardView {
+    pub standard: String,
+    pub version: String,
+}
+
+impl From<Option<ContractSourceMetadata>> for ContractSourceMetadataView {
+    fn from(metadata: Option<ContractSourceMetadata>) -> Self {
+        let ContractSourceMetadata { version, link, standards } = metadata.unwrap_or_default();
+        Self {
+            version,
+            link,
+            standards: standards
+                .into_iter()
+                .map(|ContractStandard { standard, version }| ContractStandardView {
+                    standard,
+                    version,
+                })
+                .collect(),
+        }
+    }
+}
+
 #[cfg_attr(feature = "deepsize_feature", derive(deepsize::DeepSizeOf))]
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct KnownPeerStateView {
@@ -254,6 +331,8 @@ pub struct KnownPeerStateView {
     pub first_seen: i64,
     pub last_seen: i64,
     pub last_attempt: Option<(i64, String)>,
+    #[serde(default)]
+    pub reputation: i64,
 }
 
 #[cfg_attr(feature = "deepsize_feature", derive(deepsize::DeepSizeOf))]
@@ -283,6 +362,9 @@ pub enum QueryResponseKind {
     CallResult(CallResult),
     AccessKey(AccessKeyView),
     AccessKeyList(AccessKeyList),
+    StorageSponsorship(StorageSponsorshipView),
+    ContractSourceMetadata(ContractSourceMetadataView),
+    TokenBalance(TokenBalanceView),
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -314,6 +396,16 @@ pub enum QueryRequest {
         #[serde(rename = "args_base64")]
         args: FunctionArgs,
     },
+    ViewStorageSponsorship {
+        account_id: AccountId,
+    },
+    ViewContractSourceMetadata {
+        account_id: AccountId,
+    },
+    ViewTokenBalance {
+        account_id: AccountId,
+        token_id: AccountId,
+    },
 }
 
 fn is_false(v: &bool) -> bool {
@@ -368,6 +460,9 @@ pub struct PeerInfoView {
     pub is_outbound_peer: bool,
     /// Connection nonce.
     pub nonce: u64,
+    /// Higher for peers which served more useful data and sent fewer invalid messages.
+    #[serde(default)]
+    pub reputation: i64,
 }
 
 /// Information about a Producer: its account name, peer_id and a list of connected peers that
@@ -403,6 +498,8 @@ pub struct NetworkInfoView {
     pub tier1_accounts_keys: Vec<PublicKey>,
     pub tier1_accounts_data: Vec<AccountDataView>,
     pub tier1_connections: Vec<PeerInfoView>,
+    #[serde(default)]
+    pub reachability: Reachability,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
@@ -520,6 +617,15 @@ pub struct CatchupStatusView {
     pub shard_sync_status: HashMap<ShardId, String>,
     // Blocks that we need to catchup, if it is empty, it means catching up is done
     pub blocks_to_catchup: Vec<BlockStatusView>,
+    // Number of blocks already caught up
+    #[serde(default)]
+    pub num_done_blocks: usize,
+    // Number of blocks being applied
+    #[serde(default)]
+    pub num_scheduled_blocks: usize,
+    // Number of blocks waiting to be applied, e.g. because catchup is throttled
+    #[serde(default)]
+    pub num_pending_blocks: usize,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
@@ -673,6 +779,60 @@ pub struct PartCollectionInfo {
     pub chunk_received_time: Option<Utc>,
 }
 
+/// What the ShardsManager of the node saw of the parts of a chunk.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ChunkPartsStatusView {
+    pub chunk_hash: ChunkHash,
+    /// None if the header of the chunk is not known yet.
+    pub height_created: Option<BlockHeight>,
+    pub shard_id: Option<ShardId>,
+    /// Time when the chunk was first heard of, through its header or any of its parts.
+    #[serde(with = "near_time::serde_utc_as_iso")]
+    pub first_seen_timestamp: Utc,
+    #[serde(with = "near_time::serde_opt_utc_as_iso")]
+    pub completed_timestamp: Option<Utc>,
+    /// All the parts of the chunk if their owners are known, else only the received ones.
+    pub parts: Vec<ChunkPartInfo>,
+    /// Requests sent for the parts of the chunk.
+    pub requests: Vec<ChunkPartsRequestInfo>,
+    /// Parts of the chunk forwarded by this node.
+    pub forwards: Vec<ChunkPartsForwardInfo>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ChunkPartInfo {
+    pub part_ord: u64,
+    pub part_owner: Option<AccountId>,
+    // Time when the part is first received through any message
+    #[serde(with = "near_time::serde_opt_utc_as_iso")]
+    pub received_time: Option<Utc>,
+    // Time when we first receive a PartialEncodedChunkForward containing this part
+    #[serde(with = "near_time::serde_opt_utc_as_iso")]
+    pub forwarded_received_time: Option<Utc>,
+    // Time when we receive the PartialEncodedChunk message containing this part
+    #[serde(with = "near_time::serde_opt_utc_as_iso")]
+    pub chunk_received_time: Option<Utc>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ChunkPartsRequestInfo {
+    /// Account the request was sent to, or the peers tracking the shard.
+    pub target: String,
+    pub part_ords: Vec<u64>,
+    #[serde(with = "near_time::serde_utc_as_iso")]
+    pub timestamp: Utc,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ChunkPartsForwardInfo {
+    pub account_id: AccountId,
+    pub part_ords: Vec<u64>,
+    #[serde(with = "near_time::serde_utc_as_iso")]
+    pub timestamp: Utc,
+    /// Whether the parts were pushed again because they were not acknowledged.
+    pub retransmit: bool,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub enum ChunkProcessingStatus {
     NeedToRequest,
@@ -727,12 +887,216 @@ pub struct StatusResponse {
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct ChallengeView {
-    // TODO: decide how to represent challenges in json.
+    pub hash: CryptoHash,
+    pub body: ChallengeBodyView,
+    pub target_epoch_id: CryptoHash,
+    pub target_height: BlockHeight,
+    pub account_id: AccountId,
+    pub signature: Signature,
+    pub co_signatures: Vec<AccountSignatureView>,
 }
 
 impl From<Challenge> for ChallengeView {
-    fn from(_challenge: Challenge) -> Self {
-        Self {}
+    fn from(challenge: Challenge) -> Self {
+        Self {
+            hash: challenge.hash,
+            body: challenge.body.into(),
+            target_epoch_id: challenge.target_epoch_id.0,
+            target_height: challenge.target_height,
+            account_id: challenge.account_id,
+            signature: challenge.signature,
+            co_signatures: challenge.co_signatures.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountSignatureView {
+    pub account_id: AccountId,
+    pub signature: Signature,
+}
+
+impl From<(AccountId, Signature)> for AccountSignatureView {
+    fn from((account_id, signature): (AccountId, Signature)) -> Self {
+        Self { account_id, signature }
+    }
+}
+
+/// Challenged block headers are included in challenges as borsh-encoded bytes.
+/// They are `None` in the view if they can't be decoded, in which case the
+/// challenge is invalid anyway.
+fn decode_block_header_view(bytes: &[u8]) -> Option<BlockHeaderView> {
+    BlockHeader::try_from_slice(bytes).ok().map(Into::into)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub enum ChallengeBodyView {
+    BlockDoubleSign {
+        left_block_header: Option<BlockHeaderView>,
+        right_block_header: Option<BlockHeaderView>,
+    },
+    ChunkProofs {
+        block_header: Option<BlockHeaderView>,
+        merkle_proof: MerklePath,
+        chunk: MaybeEncodedShardChunkView,
+    },
+    ChunkState {
+        prev_block_header: Option<BlockHeaderView>,
+        block_header: Option<BlockHeaderView>,
+        prev_merkle_proof: MerklePath,
+        prev_chunk: ShardChunkView,
+        merkle_proof: MerklePath,
+        chunk_header: ChunkHeaderView,
+        partial_state: PartialStateView,
+    },
+    /// Only the chunk the state witness parts are for is included, the state
+    /// transitions they contain are too large to be useful in json.
+    /// The chunk is unset if the challenge has no parts.
+    InvalidStateWitness {
+        epoch_id: Option<CryptoHash>,
+        shard_id: Option<ShardId>,
+        height_created: Option<BlockHeight>,
+        num_witness_parts: usize,
+        endorsements: Vec<AccountSignatureView>,
+    },
+    ChunkDoubleSign {
+        left_chunk_header: ChunkHeaderView,
+        right_chunk_header: ChunkHeaderView,
+    },
+}
+
+impl From<ChallengeBody> for ChallengeBodyView {
+    fn from(body: ChallengeBody) -> Self {
+        match body {
+            ChallengeBody::BlockDoubleSign(body) => Self::BlockDoubleSign {
+                left_block_header: decode_block_header_view(&body.left_block_header),
+                right_block_header: decode_block_header_view(&body.right_block_header),
+            },
+            ChallengeBody::ChunkProofs(body) => Self::ChunkProofs {
+                block_header: decode_block_header_view(&body.block_header),
+                merkle_proof: body.merkle_proof,
+                chunk: (*body.chunk).into(),
+            },
+            ChallengeBody::ChunkState(body) => Self::ChunkState {
+                prev_block_header: decode_block_header_view(&body.prev_block_header),
+                block_header: decode_block_header_view(&body.block_header),
+                prev_merkle_proof: body.prev_merkle_proof,
+                prev_chunk: body.prev_chunk.into(),
+                merkle_proof: body.merkle_proof,
+                chunk_header: body.chunk_header.into(),
+                partial_state: body.partial_state.into(),
+            },
+            ChallengeBody::InvalidStateWitness(body) => {
+                let key = body.witness_parts.first().map(|part| part.chunk_production_key());
+                Self::InvalidStateWitness {
+                    epoch_id: key.as_ref().map(|key| key.epoch_id.0),
+                    shard_id: key.as_ref().map(|key| key.shard_id),
+                    height_created: key.map(|key| key.height_created),
+                    num_witness_parts: body.witness_parts.len(),
+                    endorsements: body.endorsements.into_iter().map(Into::into).collect(),
+                }
+            }
+            ChallengeBody::ChunkDoubleSign(body) => Self::ChunkDoubleSign {
+                left_chunk_header: body.left_chunk_header.into(),
+                right_chunk_header: body.right_chunk_header.into(),
+            },
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub enum MaybeEncodedShardChunkView {
+    Encoded(EncodedShardChunkView),
+    Decoded(ShardChunkView),
+}
+
+impl From<MaybeEncodedShardChunk> for MaybeEncodedShardChunkView {
+    fn from(chunk: MaybeEncodedShardChunk) -> Self {
+        match chunk {
+            MaybeEncodedShardChunk::Encoded(chunk) => Self::Encoded(chunk.into()),
+            MaybeEncodedShardChunk::Decoded(chunk) => Self::Decoded(chunk.into()),
+        }
+    }
+}
+
+/// Erasure-coded chunk, parts which are not available are `null`.
+#[serde_as]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct EncodedShardChunkView {
+    pub header: ChunkHeaderView,
+    #[serde_as(as = "Vec<Option<Base64>>")]
+    pub parts: Vec<Option<Vec<u8>>>,
+}
+
+impl From<EncodedShardChunk> for EncodedShardChunkView {
+    fn from(chunk: EncodedShardChunk) -> Self {
+        Self {
+            header: chunk.cloned_header().into(),
+            parts: chunk
+                .content()
+                .parts
+                .iter()
+                .map(|part| part.as_ref().map(|part| part.to_vec()))
+                .collect(),
+        }
+    }
+}
+
+/// Same as `ChunkView`, but without the author, which can only be found with
+/// the epoch manager.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ShardChunkView {
+    pub header: ChunkHeaderView,
+    pub transactions: Vec<SignedTransactionView>,
+    pub receipts: Vec<ReceiptView>,
+}
+
+impl From<ShardChunk> for ShardChunkView {
+    fn from(chunk: ShardChunk) -> Self {
+        let header = chunk.cloned_header().into();
+        match chunk {
+            ShardChunk::V1(chunk) => Self {
+                header,
+                transactions: chunk.transactions.into_iter().map(Into::into).collect(),
+                receipts: chunk.prev_outgoing_receipts.into_iter().map(Into::into).collect(),
+            },
+            ShardChunk::V2(chunk) => Self {
+                header,
+                transactions: chunk.transactions.into_iter().map(Into::into).collect(),
+                receipts: chunk.prev_outgoing_receipts.into_iter().map(Into::into).collect(),
+            },
+        }
+    }
+}
+
+/// Trie values are serialized in base64, state items of a state part range are
+/// listed the same way as in the `view_state` query.
+#[serde_as]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum PartialStateView {
+    TrieValues(#[serde_as(as = "Vec<Base64>")] Vec<Vec<u8>>),
+    StatePartRange {
+        #[serde_as(as = "Vec<Base64>")]
+        boundary_values: Vec<Vec<u8>>,
+        items: Vec<StateItem>,
+    },
+}
+
+impl From<PartialState> for PartialStateView {
+    fn from(partial_state: PartialState) -> Self {
+        match partial_state {
+            PartialState::TrieValues(values) => {
+                Self::TrieValues(values.iter().map(|value| value.to_vec()).collect())
+            }
+            PartialState::StatePartRange(range) => Self::StatePartRange {
+                boundary_values: range.boundary_values.iter().map(|value| value.to_vec()).collect(),
+                items: range
+                    .items
+                    .into_iter()
+                    .map(|(key, value)| StateItem { key: key.into(), value: value.into() })
+                    .collect(),
+            },
+        }
     }
 }
 
@@ -946,6 +1310,14 @@ pub struct ChunkHeaderView {
     pub validator_proposals: Vec<ValidatorStakeView>,
     pub congestion_info: Option<CongestionInfoView>,
     pub bandwidth_requests: Option<BandwidthRequests>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_outgoing_bandwidth: Option<OutgoingBandwidth>,
+    /// State root the previous chunk was applied to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_pre_state_root: Option<StateRoot>,
+    /// Number of data parts the chunk is encoded with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_data_parts: Option<u64>,
     pub signature: Signature,
 }
 
@@ -975,6 +1347,9 @@ impl From<ShardChunkHeader> for ChunkHeaderView {
             validator_proposals: inner.prev_validator_proposals().map(Into::into).collect(),
             congestion_info: inner.congestion_info().map(Into::into),
             bandwidth_requests: inner.bandwidth_requests().cloned(),
+            prev_outgoing_bandwidth: inner.prev_outgoing_bandwidth().cloned(),
+            prev_pre_state_root: inner.prev_pre_state_root().copied(),
+            num_data_parts: inner.num_data_parts().map(|num_data_parts| num_data_parts as u64),
             signature,
         }
     }
@@ -982,8 +1357,83 @@ impl From<ShardChunkHeader> for ChunkHeaderView {
 
 impl From<ChunkHeaderView> for ShardChunkHeader {
     fn from(view: ChunkHeaderView) -> Self {
-        match (view.bandwidth_requests, view.congestion_info) {
-            (Some(bandwidth_requests), Some(congestion_info)) => {
+        match (view.bandwidth_requests, view.congestion_info, view.prev_outgoing_bandwidth) {
+            (Some(bandwidth_requests), Some(congestion_info), Some(prev_outgoing_bandwidth)) => {
+                let prev_validator_proposals =
+                    view.validator_proposals.into_iter().map(Into::into).collect();
+                let inner = match (view.prev_pre_state_root, view.num_data_parts) {
+                    (Some(prev_pre_state_root), Some(num_data_parts)) => {
+                        ShardChunkHeaderInner::V7(ShardChunkHeaderInnerV7 {
+                            prev_block_hash: view.prev_block_hash,
+                            prev_state_root: view.prev_state_root,
+                            prev_pre_state_root,
+                            prev_outcome_root: view.outcome_root,
+                            encoded_merkle_root: view.encoded_merkle_root,
+                            encoded_length: view.encoded_length,
+                            height_created: view.height_created,
+                            shard_id: view.shard_id,
+                            prev_gas_used: view.gas_used,
+                            gas_limit: view.gas_limit,
+                            prev_balance_burnt: view.balance_burnt,
+                            prev_outgoing_receipts_root: view.outgoing_receipts_root,
+                            tx_root: view.tx_root,
+                            prev_validator_proposals,
+                            congestion_info: congestion_info.into(),
+                            bandwidth_requests,
+                            prev_outgoing_bandwidth,
+                            num_data_parts,
+                        })
+                    }
+                    (Some(prev_pre_state_root), None) => {
+                        ShardChunkHeaderInner::V6(ShardChunkHeaderInnerV6 {
+                            prev_block_hash: view.prev_block_hash,
+                            prev_state_root: view.prev_state_root,
+                            prev_pre_state_root,
+                            prev_outcome_root: view.outcome_root,
+                            encoded_merkle_root: view.encoded_merkle_root,
+                            encoded_length: view.encoded_length,
+                            height_created: view.height_created,
+                            shard_id: view.shard_id,
+                            prev_gas_used: view.gas_used,
+                            gas_limit: view.gas_limit,
+                            prev_balance_burnt: view.balance_burnt,
+                            prev_outgoing_receipts_root: view.outgoing_receipts_root,
+                            tx_root: view.tx_root,
+                            prev_validator_proposals,
+                            congestion_info: congestion_info.into(),
+                            bandwidth_requests,
+                            prev_outgoing_bandwidth,
+                        })
+                    }
+                    (None, _) => ShardChunkHeaderInner::V5(ShardChunkHeaderInnerV5 {
+                        prev_block_hash: view.prev_block_hash,
+                        prev_state_root: view.prev_state_root,
+                        prev_outcome_root: view.outcome_root,
+                        encoded_merkle_root: view.encoded_merkle_root,
+                        encoded_length: view.encoded_length,
+                        height_created: view.height_created,
+                        shard_id: view.shard_id,
+                        prev_gas_used: view.gas_used,
+                        gas_limit: view.gas_limit,
+                        prev_balance_burnt: view.balance_burnt,
+                        prev_outgoing_receipts_root: view.outgoing_receipts_root,
+                        tx_root: view.tx_root,
+                        prev_validator_proposals,
+                        congestion_info: congestion_info.into(),
+                        bandwidth_requests,
+                        prev_outgoing_bandwidth,
+                    }),
+                };
+                let mut header = ShardChunkHeaderV3 {
+                    inner,
+                    height_included: view.height_included,
+                    signature: view.signature,
+                    hash: ChunkHash::default(),
+                };
+                header.init();
+                ShardChunkHeader::V3(header)
+            }
+            (Some(bandwidth_requests), Some(congestion_info), None) => {
                 let mut header = ShardChunkHeaderV3 {
                     inner: ShardChunkHeaderInner::V4(ShardChunkHeaderInnerV4 {
                         prev_block_hash: view.prev_block_hash,
@@ -1013,7 +1463,7 @@ impl From<ChunkHeaderView> for ShardChunkHeader {
                 header.init();
                 ShardChunkHeader::V3(header)
             }
-            (None, Some(congestion_info)) => {
+            (None, Some(congestion_info), _) => {
                 let mut header = ShardChunkHeaderV3 {
                     inner: ShardChunkHeaderInner::V3(ShardChunkHeaderInnerV3 {
                         prev_block_hash: view.prev_block_hash,
@@ -1170,6 +1620,36 @@ pub enum ActionView {
         delegate_action: DelegateAction,
         signature: Signature,
     },
+    SponsorStorage {
+        #[serde(with = "dec_format")]
+        deposit: Balance,
+    },
+    StakeWithMetadata {
+        #[serde(with = "dec_format")]
+        stake: Balance,
+        public_key: PublicKey,
+        metadata: ValidatorMetadata,
+    },
+    DeployGlobalContract {
+        #[serde_as(as = "Base64")]
+        code: Vec<u8>,
+        deploy_mode: GlobalContractDeployMode,
+    },
+    UseGlobalContract {
+        contract_identifier: GlobalContractIdentifier,
+    },
+    MigrateState {
+        #[serde_as(as = "Base64")]
+        code: Vec<u8>,
+        method_name: String,
+        args: FunctionArgs,
+        gas: Gas,
+    },
+    TransferToken {
+        token_id: AccountId,
+        #[serde(with = "dec_format")]
+        amount: Balance,
+    },
 }
 
 impl From<Action> for ActionView {
@@ -1206,6 +1686,30 @@ impl From<Action> for ActionView {
                 delegate_action: action.delegate_action,
                 signature: action.signature,
             },
+            Action::SponsorStorage(action) => {
+                ActionView::SponsorStorage { deposit: action.deposit }
+            }
+            Action::StakeWithMetadata(action) => ActionView::StakeWithMetadata {
+                stake: action.stake.stake,
+                public_key: action.stake.public_key,
+                metadata: action.metadata,
+            },
+            Action::DeployGlobalContract(action) => {
+                let code = hash(&action.code).as_ref().to_vec();
+                ActionView::DeployGlobalContract { code, deploy_mode: action.deploy_mode }
+            }
+            Action::UseGlobalContract(action) => {
+                ActionView::UseGlobalContract { contract_identifier: action.contract_identifier }
+            }
+            Action::MigrateState(action) => ActionView::MigrateState {
+                code: hash(&action.code).as_ref().to_vec(),
+                method_name: action.method_name,
+                args: action.args.into(),
+                gas: action.gas,
+            },
+            Action::TransferToken(action) => {
+                ActionView::TransferToken { token_id: action.token_id, amount: action.amount }
+            }
         }
     }
 }
@@ -1247,6 +1751,35 @@ impl TryFrom<ActionView> for Action {
             ActionView::Delegate { delegate_action, signature } => {
                 Action::Delegate(Box::new(SignedDelegateAction { delegate_action, signature }))
             }
+            ActionView::SponsorStorage { deposit } => {
+                Action::SponsorStorage(SponsorStorageAction { deposit })
+            }
+            ActionView::StakeWithMetadata { stake, public_key, metadata } => {
+                Action::StakeWithMetadata(Box::new(StakeWithMetadataAction {
+                    stake: StakeAction { stake, public_key },
+                    metadata,
+                }))
+            }
+            ActionView::DeployGlobalContract { code, deploy_mode } => {
+                Action::DeployGlobalContract(Box::new(DeployGlobalContractAction {
+                    code,
+                    deploy_mode,
+                }))
+            }
+            ActionView::UseGlobalContract { contract_identifier } => {
+                Action::UseGlobalContract(Box::new(UseGlobalContractAction { contract_identifier }))
+            }
+            ActionView::MigrateState { code, method_name, args, gas } => {
+                Action::MigrateState(Box::new(MigrateStateAction {
+                    code,
+                    method_name,
+                    args: args.into(),
+                    gas,
+                }))
+            }
+            ActionView::TransferToken { token_id, amount } => {
+                Action::TransferToken(Box::new(TransferTokenAction { token_id, amount }))
+            }
         })
     }
 }
@@ -1272,6 +1805,10 @@ pub struct SignedTransactionView {
     // priority_fee for Transaction::V0 => None, SignedTransactionView => 0
     #[serde(default)]
     pub priority_fee: u64,
+    /// The highest gas price the signer agrees to pay. Only set by transactions
+    /// which carry one.
+    #[serde(default, with = "dec_format", skip_serializing_if = "Option::is_none")]
+    pub max_gas_price: Option<Balance>,
     pub signature: Signature,
     pub hash: CryptoHash,
 }
@@ -1281,6 +1818,7 @@ impl From<SignedTransaction> for SignedTransactionView {
         let hash = signed_tx.get_hash();
         let transaction = signed_tx.transaction;
         let priority_fee = transaction.priority_fee().unwrap_or_default();
+        let max_gas_price = transaction.max_gas_price();
         SignedTransactionView {
             signer_id: transaction.signer_id().clone(),
             public_key: transaction.public_key().clone(),
@@ -1290,6 +1828,7 @@ impl From<SignedTransaction> for SignedTransactionView {
             signature: signed_tx.signature,
             hash,
             priority_fee,
+            max_gas_price,
         }
     }
 }
@@ -1434,6 +1973,7 @@ impl From<ExecutionMetadata> for ExecutionMetadataView {
             ExecutionMetadata::V1 => 1,
             ExecutionMetadata::V2(_) => 2,
             ExecutionMetadata::V3(_) => 3,
+            ExecutionMetadata::V4(..) => 4,
         };
         let mut gas_profile = match metadata {
             ExecutionMetadata::V1 => None,
@@ -1466,7 +2006,7 @@ impl From<ExecutionMetadata> for ExecutionMetadataView {
 
                 Some(costs)
             }
-            ExecutionMetadata::V3(profile) => {
+            ExecutionMetadata::V3(profile) | ExecutionMetadata::V4(profile, _) => {
                 // Add actions, wasm op, and ext costs in groups.
                 // actions costs are 1-to-1
                 let mut costs: Vec<CostGasUsed> = ActionCosts::iter()
@@ -1559,6 +2099,11 @@ pub struct ExecutionOutcomeView {
     /// Execution metadata, versioned
     #[serde(default)]
     pub metadata: ExecutionMetadataView,
+    /// Split of the unused gas of a receipt between the refund to the signer and the penalty,
+    /// which is included in `tokens_burnt`. Only set for receipts executed with reduced gas
+    /// refunds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_refund: Option<GasRefundView>,
 }
 
 impl From<ExecutionOutcome> for ExecutionOutcomeView {
@@ -1570,11 +2115,38 @@ impl From<ExecutionOutcome> for ExecutionOutcomeView {
             tokens_burnt: outcome.tokens_burnt,
             executor_id: outcome.executor_id,
             status: outcome.status.into(),
+            gas_refund: outcome.metadata.gas_refund().map(GasRefundView::from),
             metadata: outcome.metadata.into(),
         }
     }
 }
 
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct GasRefundView {
+    /// Unused gas refunded to the signer.
+    pub gas_refunded: Gas,
+    /// Unused gas burnt instead of refunded.
+    pub gas_refund_penalty: Gas,
+}
+
+impl From<&GasRefund> for GasRefundView {
+    fn from(gas_refund: &GasRefund) -> Self {
+        Self {
+            gas_refunded: gas_refund.gas_refunded,
+            gas_refund_penalty: gas_refund.gas_refund_penalty,
+        }
+    }
+}
+
 impl From<&ExecutionOutcomeView> for PartialExecutionOutcome {
     fn from(outcome: &ExecutionOutcomeView) -> Self {
         Self {
@@ -1599,6 +2171,115 @@ impl From<ExecutionStatusView> for PartialExecutionStatus {
     }
 }
 
+/// Event emitted while the runtime applies a receipt, in the order of execution.
+/// Collected for debugging contracts, never part of consensus.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ExecutionTraceEventView {
+    ReceiptStart {
+        receipt_id: CryptoHash,
+        predecessor_id: AccountId,
+        receiver_id: AccountId,
+    },
+    ActionStart {
+        receipt_id: CryptoHash,
+        action_index: u64,
+        /// Kind of the action, for example `FunctionCall`.
+        action: String,
+    },
+    /// A contract called a host function, within the last started action.
+    HostFunctionCall {
+        receipt_id: CryptoHash,
+        name: String,
+    },
+    ActionEnd {
+        receipt_id: CryptoHash,
+        action_index: u64,
+        gas_burnt: Gas,
+        /// Error of the action, if it failed.
+        error: Option<ActionErrorKind>,
+    },
+    ReceiptEnd {
+        receipt_id: CryptoHash,
+        gas_burnt: Gas,
+        status: ExecutionStatusView,
+    },
+}
+
+impl ExecutionTraceEventView {
+    /// Receipt during the execution of which the event was emitted.
+    pub fn receipt_id(&self) -> &CryptoHash {
+        match self {
+            Self::ReceiptStart { receipt_id, .. }
+            | Self::ActionStart { receipt_id, .. }
+            | Self::HostFunctionCall { receipt_id, .. }
+            | Self::ActionEnd { receipt_id, .. }
+            | Self::ReceiptEnd { receipt_id, .. } => receipt_id,
+        }
+    }
+}
+
+/// Block which the node found invalid and refuses to process again.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvalidBlockView {
+    pub block_hash: CryptoHash,
+    pub height: BlockHeight,
+    /// The error which made the block invalid.
+    pub reason: String,
+}
+
+/// Result of re-applying a past block on a shard and comparing it with the
+/// result stored when the block was processed.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BlockReplayView {
+    pub block_hash: CryptoHash,
+    pub height: BlockHeight,
+    pub shard_id: ShardId,
+    /// Whether the block contains a new chunk for the shard.
+    pub is_new_chunk: bool,
+    /// State root after the replay.
+    pub state_root: CryptoHash,
+    pub num_outcomes: u64,
+    /// Differences between the replay and the stored result, empty if the
+    /// replay is consistent with it.
+    pub mismatches: Vec<BlockReplayMismatchView>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BlockReplayMismatchView {
+    StateRoot {
+        stored: CryptoHash,
+        replayed: CryptoHash,
+    },
+    OutcomeRoot {
+        stored: CryptoHash,
+        replayed: CryptoHash,
+    },
+    GasUsed {
+        stored: Gas,
+        replayed: Gas,
+    },
+    BalanceBurnt {
+        #[serde(with = "dec_format")]
+        stored: Balance,
+        #[serde(with = "dec_format")]
+        replayed: Balance,
+    },
+    /// Hash of the outgoing receipts of the chunk.
+    OutgoingReceipts {
+        stored: CryptoHash,
+        replayed: CryptoHash,
+    },
+    /// Hash of the outcome of the transaction or receipt `id`, `None` if it
+    /// is only present on one side.
+    Outcome {
+        id: CryptoHash,
+        stored: Option<CryptoHash>,
+        replayed: Option<CryptoHash>,
+    },
+}
+
 impl ExecutionOutcomeView {
     // Same behavior as ExecutionOutcomeWithId's to_hashes.
     pub fn to_hashes(&self, id: CryptoHash) -> Vec<CryptoHash> {
@@ -1764,7 +2445,7 @@ pub struct FinalExecutionOutcomeWithReceiptView {
 }
 
 pub mod validator_stake_view {
-    pub use super::ValidatorStakeViewV1;
+    pub use super::{ValidatorStakeViewV1, ValidatorStakeViewV2};
     use crate::types::validator_stake::ValidatorStake;
     use borsh::{BorshDeserialize, BorshSerialize};
     use near_primitives_core::types::AccountId;
@@ -1776,6 +2457,7 @@ pub mod validator_stake_view {
     #[serde(tag = "validator_stake_struct_version")]
     pub enum ValidatorStakeView {
         V1(ValidatorStakeViewV1),
+        V2(ValidatorStakeViewV2),
     }
 
     impl ValidatorStakeView {
@@ -1787,6 +2469,7 @@ pub mod validator_stake_view {
         pub fn take_account_id(self) -> AccountId {
             match self {
                 Self::V1(v1) => v1.account_id,
+                Self::V2(v2) => v2.account_id,
             }
         }
 
@@ -1794,6 +2477,7 @@ pub mod validator_stake_view {
         pub fn account_id(&self) -> &AccountId {
             match self {
                 Self::V1(v1) => &v1.account_id,
+                Self::V2(v2) => &v2.account_id,
             }
         }
     }
@@ -1806,6 +2490,12 @@ pub mod validator_stake_view {
                     public_key: v1.public_key,
                     stake: v1.stake,
                 }),
+                ValidatorStake::V2(v2) => Self::V2(ValidatorStakeViewV2 {
+                    account_id: v2.account_id,
+                    public_key: v2.public_key,
+                    stake: v2.stake,
+                    metadata: v2.metadata,
+                }),
             }
         }
     }
@@ -1814,6 +2504,9 @@ pub mod validator_stake_view {
         fn from(view: ValidatorStakeView) -> Self {
             match view {
                 ValidatorStakeView::V1(v1) => Self::new_v1(v1.account_id, v1.public_key, v1.stake),
+                ValidatorStakeView::V2(v2) => {
+                    Self::new_with_metadata(v2.account_id, v2.public_key, v2.stake, v2.metadata)
+                }
             }
         }
     }
@@ -1836,6 +2529,24 @@ pub struct ValidatorStakeViewV1 {
     pub stake: Balance,
 }
 
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Eq,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct ValidatorStakeViewV2 {
+    pub account_id: AccountId,
+    pub public_key: PublicKey,
+    #[serde(with = "dec_format")]
+    pub stake: Balance,
+    pub metadata: ValidatorMetadata,
+}
+
 #[derive(
     BorshSerialize,
     BorshDeserialize,
@@ -1857,6 +2568,9 @@ pub struct ReceiptView {
     // For ReceiptV0 ReceiptPriority::NoPriority => 0
     #[serde(default)]
     pub priority: u64,
+    /// The last block height at which the receipt can be executed. Only set for `ReceiptV2`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry_height: Option<BlockHeight>,
 }
 
 #[derive(
@@ -1904,6 +2618,13 @@ pub enum ReceiptEnumView {
         #[serde(default = "default_is_promise")]
         is_promise_resume: bool,
     },
+    GlobalContractDistribution {
+        id: GlobalContractIdentifier,
+        target_shard: ShardId,
+        already_delivered_shards: Vec<ShardId>,
+        #[serde_as(as = "Base64")]
+        code: Vec<u8>,
+    },
 }
 
 // Default value used when deserializing ReceiptEnumViews which are missing either the
@@ -1918,6 +2639,7 @@ impl From<Receipt> for ReceiptView {
         let is_promise_yield = matches!(receipt.receipt(), ReceiptEnum::PromiseYield(_));
         let is_promise_resume = matches!(receipt.receipt(), ReceiptEnum::PromiseResume(_));
         let priority = receipt.priority().value();
+        let expiry_height = receipt.expiry_height();
 
         ReceiptView {
             predecessor_id: receipt.predecessor_id().clone(),
@@ -1953,8 +2675,17 @@ impl From<Receipt> for ReceiptView {
                         is_promise_resume,
                     }
                 }
+                ReceiptEnum::GlobalContractDistribution(distribution_receipt) => {
+                    ReceiptEnumView::GlobalContractDistribution {
+                        id: distribution_receipt.id,
+                        target_shard: distribution_receipt.target_shard,
+                        already_delivered_shards: distribution_receipt.already_delivered_shards,
+                        code: distribution_receipt.code,
+                    }
+                }
             },
             priority,
+            expiry_height,
         }
     }
 }
@@ -1963,7 +2694,7 @@ impl TryFrom<ReceiptView> for Receipt {
     type Error = Box<dyn std::error::Error + Send + Sync>;
 
     fn try_from(receipt_view: ReceiptView) -> Result<Self, Self::Error> {
-        Ok(Receipt::V1(ReceiptV1 {
+        let receipt = ReceiptV1 {
             predecessor_id: receipt_view.predecessor_id,
             receiver_id: receipt_view.receiver_id,
             receipt_id: receipt_view.receipt_id,
@@ -2010,9 +2741,31 @@ impl TryFrom<ReceiptView> for Receipt {
                         ReceiptEnum::Data(data_receipt)
                     }
                 }
+                ReceiptEnumView::GlobalContractDistribution {
+                    id,
+                    target_shard,
+                    already_delivered_shards,
+                    code,
+                } => ReceiptEnum::GlobalContractDistribution(GlobalContractDistributionReceipt {
+                    id,
+                    target_shard,
+                    already_delivered_shards,
+                    code,
+                }),
             },
             priority: receipt_view.priority,
-        }))
+        };
+        Ok(match receipt_view.expiry_height {
+            Some(expiry_height) => Receipt::V2(ReceiptV2 {
+                predecessor_id: receipt.predecessor_id,
+                receiver_id: receipt.receiver_id,
+                receipt_id: receipt.receipt_id,
+                receipt: receipt.receipt,
+                priority: receipt.priority,
+                expiry_height,
+            }),
+            None => Receipt::V1(receipt),
+        })
     }
 }
 
@@ -2088,6 +2841,9 @@ pub struct CurrentEpochValidatorInfo {
     pub num_expected_endorsements_per_shard: Vec<NumBlocks>,
     /// Shards this validator is assigned to as chunk validator in the current epoch.
     pub shards_endorsed: Vec<ShardId>,
+    /// Metadata reported by the validator in its stake proposal, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ValidatorMetadata>,
 }
 
 #[derive(
@@ -2107,6 +2863,9 @@ pub struct NextEpochValidatorInfo {
     #[serde(with = "dec_format")]
     pub stake: Balance,
     pub shards: Vec<ShardId>,
+    /// Metadata reported by the validator in its stake proposal, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ValidatorMetadata>,
 }
 
 #[derive(
@@ -2382,6 +3141,90 @@ pub type StateChangesView = Vec<StateChangeWithCauseView>;
 /// Maintenance windows view are a vector of maintenance window.
 pub type MaintenanceWindowsView = Vec<Range<BlockHeight>>;
 
+/// Status of a challenge as seen by the node.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChallengeStatusView {
+    /// Challenge is valid and accounts it accuses can be slashed.
+    Accepted,
+    /// Challenge is invalid, e.g. it is not signed by a validator or fisherman
+    /// or its body doesn't prove misbehavior.
+    Rejected {
+        reason: String,
+        /// Machine-readable reason of the rejection.
+        code: PrimitiveValidationError,
+    },
+    /// Challenge is valid, but all accounts it accuses are already slashed.
+    AlreadySlashed,
+}
+
+/// Chunk endorsements of a chunk validator in an epoch, as recorded from the
+/// final blocks seen by the node.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorEndorsementStatsView {
+    pub account_id: AccountId,
+    pub epoch_id: EpochId,
+    pub num_produced_endorsements: NumBlocks,
+    pub num_expected_endorsements: NumBlocks,
+    /// Percentage of the expected endorsements below which a validator which
+    /// is only a chunk validator is kicked out at the end of the epoch.
+    pub kickout_threshold: u8,
+    pub shards: Vec<ShardEndorsementStatsView>,
+    /// The latest chunks which were included in blocks without an endorsement
+    /// of the validator, from the oldest.
+    pub missed_chunks: Vec<MissedEndorsementView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ShardEndorsementStatsView {
+    pub shard_id: ShardId,
+    pub num_produced_endorsements: NumBlocks,
+    pub num_expected_endorsements: NumBlocks,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MissedEndorsementView {
+    pub shard_id: ShardId,
+    pub height_created: BlockHeight,
+    pub chunk_hash: CryptoHash,
+}
+
+impl ValidatorEndorsementStatsView {
+    pub fn new(
+        account_id: AccountId,
+        epoch_id: EpochId,
+        kickout_threshold: u8,
+        stats: ValidatorEndorsementStats,
+    ) -> Self {
+        let total = stats.total();
+        Self {
+            account_id,
+            epoch_id,
+            num_produced_endorsements: total.produced,
+            num_expected_endorsements: total.expected,
+            kickout_threshold,
+            shards: stats
+                .shards
+                .into_iter()
+                .map(|(shard_id, stats)| ShardEndorsementStatsView {
+                    shard_id,
+                    num_produced_endorsements: stats.produced,
+                    num_expected_endorsements: stats.expected,
+                })
+                .collect(),
+            missed_chunks: stats
+                .missed
+                .into_iter()
+                .map(|missed| MissedEndorsementView {
+                    shard_id: missed.shard_id,
+                    height_created: missed.height_created,
+                    chunk_hash: missed.chunk_hash.0,
+                })
+                .collect(),
+        }
+    }
+}
+
 /// Contains the split storage information.
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct SplitStorageInfoView {
@@ -2510,4 +3353,50 @@ mod tests {
         let view: FinalExecutionOutcomeViewEnum = serde_json::from_str(json).unwrap();
         assert!(matches!(view, FinalExecutionOutcomeViewEnum::FinalExecutionOutcome(_)));
     }
+
+    #[test]
+    fn test_challenge_view() {
+        use super::{ChallengeBodyView, ChallengeView, PartialStateView};
+        use crate::challenge::{
+            BlockDoubleSign, Challenge, ChallengeBody, PartialState, StatePartRange,
+        };
+        use crate::types::EpochId;
+        use crate::validator_signer::InMemoryValidatorSigner;
+        use near_crypto::KeyType;
+
+        let signer =
+            InMemoryValidatorSigner::from_seed("test".parse().unwrap(), KeyType::ED25519, "test");
+        let challenge = Challenge::produce(
+            ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+                left_block_header: vec![1, 2, 3],
+                right_block_header: vec![4, 5, 6],
+            }),
+            EpochId::default(),
+            10,
+            &signer,
+        );
+        let view = ChallengeView::from(challenge.clone());
+        assert_eq!(view.hash, challenge.hash);
+        assert_eq!(view.target_height, 10);
+        assert!(matches!(
+            view.body,
+            ChallengeBodyView::BlockDoubleSign {
+                left_block_header: None,
+                right_block_header: None
+            }
+        ));
+
+        let partial_state = PartialState::StatePartRange(StatePartRange::new(
+            vec![vec![1, 2].into()],
+            vec![(b"key".to_vec(), b"value".to_vec())],
+        ));
+        let json = serde_json::to_value(PartialStateView::from(partial_state)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"StatePartRange": {
+                "boundary_values": ["AQI="],
+                "items": [{"key": "a2V5", "value": "dmFsdWU="}],
+            }})
+        );
+    }
 }
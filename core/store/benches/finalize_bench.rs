@@ -19,7 +19,7 @@ use borsh::BorshSerialize;
 use near_chain::Chain;
 use near_chunks::shards_manager_actor::ShardsManagerActor;
 use near_crypto::{InMemorySigner, KeyType};
-use near_primitives::bandwidth_scheduler::BandwidthRequests;
+use near_primitives::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 use near_primitives::congestion_info::CongestionInfo;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{merklize, MerklePathItem};
@@ -138,6 +138,9 @@ fn create_chunk_header(height: u64, shard_id: ShardId) -> ShardChunkHeader {
         vec![],
         congestion_info,
         BandwidthRequests::default_for_protocol_version(PROTOCOL_VERSION),
+        OutgoingBandwidth::default_for_protocol_version(PROTOCOL_VERSION),
+        ProtocolFeature::ExecutionProofs.enabled(PROTOCOL_VERSION).then(CryptoHash::default),
+        ProtocolFeature::AdaptiveChunkEncoding.enabled(PROTOCOL_VERSION).then_some(1),
         &validator_signer(),
     ))
 }
@@ -211,6 +214,8 @@ fn create_encoded_shard_chunk(
         Default::default(),
         congestion_info,
         BandwidthRequests::default_for_protocol_version(PROTOCOL_VERSION),
+        OutgoingBandwidth::default_for_protocol_version(PROTOCOL_VERSION),
+        ProtocolFeature::ExecutionProofs.enabled(PROTOCOL_VERSION).then(CryptoHash::default),
         &validator_signer(),
         &rs,
         100,
@@ -1,7 +1,14 @@
+use std::io;
 use std::sync::Arc;
 
+use borsh::{BorshDeserialize, BorshSerialize};
+
 use near_primitives::errors::ChunkAccessError;
-use near_primitives::sharding::{ChunkHash, PartialEncodedChunk, ShardChunk};
+use near_primitives::sharding::{
+    ChunkHash, PartialEncodedChunk, PartialEncodedChunkPart, ReceiptProof, ShardChunk,
+    ShardChunkHeader,
+};
+use near_primitives::types::BlockHeight;
 
 use crate::{DBCol, Store};
 
@@ -39,4 +46,135 @@ impl ChunkStoreAdapter {
             .expect("Borsh should not have failed here")
             .ok_or_else(|| ChunkAccessError::ChunkMissing(chunk_hash.clone()))
     }
+
+    /// Saves new items of a chunk which is not complete yet, in a single commit.
+    /// Each item has its own key, so the items saved before are not written again.
+    pub fn save_pending_partial_chunk_items(
+        &self,
+        height_created: BlockHeight,
+        chunk_hash: &ChunkHash,
+        items: &[PendingPartialChunkItem],
+    ) -> io::Result<()> {
+        let mut store_update = self.store.store_update();
+        for item in items {
+            store_update.set_ser(
+                DBCol::PendingPartialChunks,
+                &get_pending_partial_chunk_item_key(height_created, chunk_hash, item),
+                item,
+            )?;
+        }
+        store_update.commit()
+    }
+
+    pub fn delete_pending_partial_chunk(
+        &self,
+        height_created: BlockHeight,
+        chunk_hash: &ChunkHash,
+    ) -> io::Result<()> {
+        let from = get_pending_partial_chunk_key(height_created, chunk_hash);
+        let mut to = from.clone();
+        to.push(u8::MAX);
+        let mut store_update = self.store.store_update();
+        store_update.delete_range(DBCol::PendingPartialChunks, &from, &to);
+        store_update.commit()
+    }
+
+    /// Returns the pending partial chunks created at `min_height` or above, ordered by height.
+    pub fn get_pending_partial_chunks(
+        &self,
+        min_height: BlockHeight,
+    ) -> io::Result<Vec<PartialEncodedChunk>> {
+        let mut chunks = vec![];
+        // Key prefix, header, parts and receipts of the chunk being read.
+        let mut current: Option<(Box<[u8]>, ShardChunkHeader, Vec<_>, Vec<_>)> = None;
+        for item in self.store.iter_range(
+            DBCol::PendingPartialChunks,
+            Some(&min_height.to_be_bytes()),
+            None,
+        ) {
+            let (key, value) = item?;
+            let prefix = &key[..key.len().min(PENDING_PARTIAL_CHUNK_KEY_LEN)];
+            if current
+                .as_ref()
+                .is_some_and(|(current_prefix, ..)| current_prefix.as_ref() != prefix)
+            {
+                let (_, header, parts, receipts) = current.take().unwrap();
+                chunks.push(PartialEncodedChunk::new(header, parts, receipts));
+            }
+            let item = PendingPartialChunkItem::try_from_slice(&value)?;
+            if let PendingPartialChunkItem::Header(header) = item {
+                if current.is_some() {
+                    return Err(unexpected_pending_partial_chunk_item(&key));
+                }
+                current = Some((prefix.into(), header, vec![], vec![]));
+                continue;
+            }
+            // The header is the first item of a chunk, see `get_pending_partial_chunk_item_key`.
+            let Some((_, _, parts, receipts)) = current.as_mut() else {
+                return Err(unexpected_pending_partial_chunk_item(&key));
+            };
+            match item {
+                PendingPartialChunkItem::Part(part) => parts.push(part),
+                PendingPartialChunkItem::Receipt(receipt) => receipts.push(receipt),
+                PendingPartialChunkItem::Header(_) => unreachable!(),
+            }
+        }
+        if let Some((_, header, parts, receipts)) = current {
+            chunks.push(PartialEncodedChunk::new(header, parts, receipts));
+        }
+        Ok(chunks)
+    }
+}
+
+/// Value of `DBCol::PendingPartialChunks`: the header, a part or the receipts
+/// of a chunk which is not complete yet.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum PendingPartialChunkItem {
+    Header(ShardChunkHeader),
+    Part(PartialEncodedChunkPart),
+    Receipt(ReceiptProof),
+}
+
+const PENDING_PARTIAL_CHUNK_KEY_LEN: usize = std::mem::size_of::<BlockHeight>() + 32;
+
+fn unexpected_pending_partial_chunk_item(key: &[u8]) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unexpected pending partial chunk item at {key:?}"),
+    )
+}
+
+/// Prefix of the keys of a chunk in `DBCol::PendingPartialChunks`. The height goes
+/// first so that the column is ordered by height, which makes garbage collection cheap.
+pub fn get_pending_partial_chunk_key(
+    height_created: BlockHeight,
+    chunk_hash: &ChunkHash,
+) -> Vec<u8> {
+    let mut key = Vec::with_capacity(PENDING_PARTIAL_CHUNK_KEY_LEN + 9);
+    key.extend_from_slice(&height_created.to_be_bytes());
+    key.extend_from_slice(chunk_hash.as_ref());
+    key
+}
+
+/// Key of an item of a chunk in `DBCol::PendingPartialChunks`: the chunk prefix
+/// followed by 0 for the header, 1 and the part ordinal for a part, or 2 and the
+/// target shard for receipts. The header therefore comes before the other items.
+pub(crate) fn get_pending_partial_chunk_item_key(
+    height_created: BlockHeight,
+    chunk_hash: &ChunkHash,
+    item: &PendingPartialChunkItem,
+) -> Vec<u8> {
+    let mut key = get_pending_partial_chunk_key(height_created, chunk_hash);
+    match item {
+        PendingPartialChunkItem::Header(_) => key.push(0),
+        PendingPartialChunkItem::Part(part) => {
+            key.push(1);
+            key.extend_from_slice(&part.part_ord.to_be_bytes());
+        }
+        PendingPartialChunkItem::Receipt(receipt) => {
+            key.push(2);
+            key.extend_from_slice(&receipt.1.to_shard_id.to_be_bytes());
+        }
+    }
+    key
 }
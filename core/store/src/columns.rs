@@ -317,6 +317,38 @@ pub enum DBCol {
     /// - *Rows*: `CryptoHash`
     /// - *Column type*: `Vec<u8>`
     StateSyncNewChunks,
+    /// Challenges received or produced by this node which have not been included
+    /// in a block yet. Persisted so that pending evidence of misbehavior is not lost
+    /// on restart, and garbage collected together with the blocks.
+    /// - *Rows*: BlockHeight ++ challenge hash (CryptoHash)
+    /// - *Column type*: `StoredChallenge`
+    Challenges,
+    /// Chunk endorsements of each chunk validator in an epoch, recorded from
+    /// the final blocks, so that validators can check why they were kicked
+    /// out. Garbage collected together with the last block of the epoch.
+    /// - *Rows*: EpochId ++ AccountId
+    /// - *Column type*: `ValidatorEndorsementStats`
+    ChunkEndorsementStats,
+    /// Blocks which failed validation, with the reason, so that they are
+    /// rejected without being processed again, also after a restart.
+    /// Only blocks up to a bounded height above the head are stored, and they
+    /// are garbage collected together with the blocks at their height.
+    /// - *Rows*: BlockHeight ++ BlockHash (CryptoHash)
+    /// - *Column type*: `StoredInvalidBlock`
+    InvalidBlocks,
+    /// Parts and receipts received so far for chunks which are not complete
+    /// yet, so that a restarting node does not fetch them again. Each part is
+    /// stored once, under its own key.
+    /// Garbage collected once the final head passes their height.
+    /// - *Rows*: BlockHeight ++ ChunkHash ++ 0 for the header, or 1 ++ part
+    ///   ordinal (u64) for a part, or 2 ++ target ShardId for receipts
+    /// - *Column type*: `PendingPartialChunkItem`
+    PendingPartialChunks,
+    /// Reputation which the node recorded for a peer at the end of the last
+    /// connection to it, so that it survives restarts.
+    /// - *Rows*: PeerId
+    /// - *Column type*: i64
+    PeerReputation,
 }
 
 /// Defines different logical parts of a db key.
@@ -352,6 +384,7 @@ pub enum DBKeyType {
     ColumnId,
     LatestWitnessesKey,
     LatestWitnessIndex,
+    ChallengeHash,
 }
 
 impl DBCol {
@@ -522,7 +555,12 @@ impl DBCol {
             | DBCol::FlatStorageStatus
             | DBCol::EpochSyncProof
             | DBCol::StateSyncHashes
-            | DBCol::StateSyncNewChunks => false,
+            | DBCol::StateSyncNewChunks
+            | DBCol::Challenges
+            | DBCol::ChunkEndorsementStats
+            | DBCol::InvalidBlocks
+            | DBCol::PendingPartialChunks
+            | DBCol::PeerReputation => false,
         }
     }
 
@@ -598,6 +636,13 @@ impl DBCol {
             DBCol::StateShardUIdMapping => &[DBKeyType::ShardUId],
             DBCol::StateSyncHashes => &[DBKeyType::EpochId],
             DBCol::StateSyncNewChunks => &[DBKeyType::BlockHash],
+            DBCol::Challenges => &[DBKeyType::BlockHeight, DBKeyType::ChallengeHash],
+            DBCol::ChunkEndorsementStats => &[DBKeyType::EpochId, DBKeyType::AccountId],
+            DBCol::InvalidBlocks => &[DBKeyType::BlockHeight, DBKeyType::BlockHash],
+            DBCol::PendingPartialChunks => {
+                &[DBKeyType::BlockHeight, DBKeyType::ChunkHash, DBKeyType::PartId]
+            }
+            DBCol::PeerReputation => &[DBKeyType::PeerId],
         }
     }
 }
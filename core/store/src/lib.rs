@@ -18,14 +18,18 @@ pub use columns::DBCol;
 use config::ArchivalConfig;
 use db::{SplitDB, GENESIS_CONGESTION_INFO_KEY};
 pub use db::{
-    CHUNK_TAIL_KEY, COLD_HEAD_KEY, FINAL_HEAD_KEY, FORK_TAIL_KEY, GENESIS_JSON_HASH_KEY,
-    GENESIS_STATE_ROOTS_KEY, HEADER_HEAD_KEY, HEAD_KEY, LARGEST_TARGET_HEIGHT_KEY,
-    LATEST_KNOWN_KEY, STATE_SNAPSHOT_KEY, STATE_SYNC_DUMP_KEY, TAIL_KEY,
+    CHAIN_CHECKPOINT_KEY, CHUNK_TAIL_KEY, COLD_HEAD_KEY, FINAL_HEAD_KEY, FORK_TAIL_KEY,
+    GENESIS_JSON_HASH_KEY, GENESIS_STATE_ROOTS_KEY, HEADER_HEAD_KEY, HEADER_TAIL_KEY, HEAD_KEY,
+    LARGEST_TARGET_HEIGHT_KEY, LATEST_KNOWN_KEY, OUTCOMES_TAIL_KEY, STATE_SNAPSHOT_KEY,
+    STATE_SYNC_DUMP_KEY, TAIL_KEY,
 };
 use metadata::{DbKind, DbVersion, KIND_KEY, VERSION_KEY};
 use near_crypto::PublicKey;
 use near_fmt::{AbbrBytes, StorageKey};
-use near_primitives::account::{AccessKey, Account};
+use near_primitives::account::{
+    AccessKey, Account, ContractSourceMetadata, MigrationGracePeriod, StorageSponsorship,
+};
+use near_primitives::action::GlobalContractIdentifier;
 use near_primitives::bandwidth_scheduler::BandwidthSchedulerState;
 use near_primitives::congestion_info::CongestionInfo;
 pub use near_primitives::errors::{MissingTrieValueContext, StorageError};
@@ -35,8 +39,8 @@ use near_primitives::receipt::{
     Receipt, ReceiptEnum, ReceivedData,
 };
 pub use near_primitives::shard_layout::ShardUId;
-use near_primitives::trie_key::{trie_key_parsers, TrieKey};
-use near_primitives::types::{AccountId, BlockHeight, StateRoot};
+use near_primitives::trie_key::{col, trie_key_parsers, StorageNamespace, TrieKey};
+use near_primitives::types::{AccountId, Balance, BlockHeight, StateRoot};
 use near_vm_runner::{CompiledContractInfo, ContractRuntimeCache};
 use std::fs::File;
 use std::path::Path;
@@ -824,6 +828,149 @@ pub fn get_account(
     get(trie, &TrieKey::Account { account_id: account_id.clone() })
 }
 
+pub fn set_storage_sponsorship(
+    state_update: &mut TrieUpdate,
+    account_id: AccountId,
+    sponsorship: &StorageSponsorship,
+) {
+    set(state_update, TrieKey::StorageSponsorship { account_id }, sponsorship)
+}
+
+pub fn get_storage_sponsorship(
+    trie: &dyn TrieAccess,
+    account_id: &AccountId,
+) -> Result<Option<StorageSponsorship>, StorageError> {
+    get(trie, &TrieKey::StorageSponsorship { account_id: account_id.clone() })
+}
+
+pub fn remove_storage_sponsorship(state_update: &mut TrieUpdate, account_id: AccountId) {
+    state_update.remove(TrieKey::StorageSponsorship { account_id });
+}
+
+pub fn set_migration_grace_period(
+    state_update: &mut TrieUpdate,
+    account_id: AccountId,
+    grace_period: &MigrationGracePeriod,
+) {
+    set(state_update, TrieKey::MigrationGracePeriod { account_id }, grace_period)
+}
+
+pub fn get_migration_grace_period(
+    trie: &dyn TrieAccess,
+    account_id: &AccountId,
+) -> Result<Option<MigrationGracePeriod>, StorageError> {
+    get(trie, &TrieKey::MigrationGracePeriod { account_id: account_id.clone() })
+}
+
+pub fn remove_migration_grace_period(state_update: &mut TrieUpdate, account_id: AccountId) {
+    state_update.remove(TrieKey::MigrationGracePeriod { account_id });
+}
+
+pub fn set_global_contract_deployer(
+    state_update: &mut TrieUpdate,
+    account_id: AccountId,
+    deployer_id: &AccountId,
+) {
+    set(state_update, TrieKey::GlobalContractDeployer { account_id }, deployer_id)
+}
+
+pub fn get_global_contract_deployer(
+    trie: &dyn TrieAccess,
+    account_id: &AccountId,
+) -> Result<Option<AccountId>, StorageError> {
+    get(trie, &TrieKey::GlobalContractDeployer { account_id: account_id.clone() })
+}
+
+pub fn remove_global_contract_deployer(state_update: &mut TrieUpdate, account_id: AccountId) {
+    state_update.remove(TrieKey::GlobalContractDeployer { account_id });
+}
+
+/// Returns the hash of the code to run when calling the contract of `account_id`.
+///
+/// That's the code hash of the account, unless it uses a global contract by the account id
+/// of its deployer, in which case it's the hash of the code currently deployed by them.
+pub fn get_contract_code_hash(
+    state_update: &TrieUpdate,
+    account_id: &AccountId,
+    account: &Account,
+) -> Result<CryptoHash, StorageError> {
+    let Some(deployer_id) = get_global_contract_deployer(state_update, account_id)? else {
+        return Ok(account.code_hash());
+    };
+    let key = TrieKey::GlobalContractCode {
+        identifier: GlobalContractIdentifier::AccountId(deployer_id.clone()),
+    };
+    let code_ref = state_update.get_ref(&key, KeyLookupMode::FlatStorage)?.ok_or_else(|| {
+        StorageError::StorageInconsistentState(format!(
+            "Global contract of {} used by {} should be in the state",
+            deployer_id, account_id
+        ))
+    })?;
+    Ok(code_ref.value_hash())
+}
+
+pub fn set_contract_source_metadata(
+    state_update: &mut TrieUpdate,
+    account_id: AccountId,
+    metadata: &ContractSourceMetadata,
+) {
+    set(state_update, TrieKey::ContractSourceMetadata { account_id }, metadata)
+}
+
+pub fn get_contract_source_metadata(
+    trie: &dyn TrieAccess,
+    account_id: &AccountId,
+) -> Result<Option<ContractSourceMetadata>, StorageError> {
+    get(trie, &TrieKey::ContractSourceMetadata { account_id: account_id.clone() })
+}
+
+pub fn remove_contract_source_metadata(state_update: &mut TrieUpdate, account_id: AccountId) {
+    state_update.remove(TrieKey::ContractSourceMetadata { account_id });
+}
+
+pub fn set_token_balance(
+    state_update: &mut TrieUpdate,
+    account_id: AccountId,
+    token_id: AccountId,
+    balance: Balance,
+) {
+    set(state_update, TrieKey::TokenBalance { account_id, token_id }, &balance)
+}
+
+pub fn get_token_balance(
+    trie: &dyn TrieAccess,
+    account_id: &AccountId,
+    token_id: &AccountId,
+) -> Result<Option<Balance>, StorageError> {
+    get(trie, &TrieKey::TokenBalance { account_id: account_id.clone(), token_id: token_id.clone() })
+}
+
+/// Returns the ids of at most `limit` of the tokens `account_id` holds a balance of.
+pub fn get_token_ids(
+    state_update: &TrieUpdate,
+    account_id: &AccountId,
+    limit: usize,
+) -> Result<Vec<AccountId>, StorageError> {
+    let lock = state_update.trie().lock_for_iter();
+    let token_ids = state_update
+        .locked_iter(
+            &trie_key_parsers::get_raw_prefix_for_account_data(col::TOKEN_BALANCE, account_id),
+            &lock,
+        )?
+        .map(|raw_key| {
+            trie_key_parsers::parse_token_id_from_token_balance_key(&raw_key?, account_id).map_err(
+                |_e| {
+                    StorageError::StorageInconsistentState(
+                        "Can't parse token id from raw key for TokenBalance".to_string(),
+                    )
+                },
+            )
+        })
+        .take(limit)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(token_ids)
+}
+
 pub fn set_received_data(
     state_update: &mut TrieUpdate,
     receiver_id: AccountId,
@@ -929,6 +1076,40 @@ pub fn enqueue_promise_yield_timeout(
         .expect("Next available index for PromiseYield timeout queue exceeded the integer limit");
 }
 
+/// Stores a PromiseYield timeout which doesn't expire after the default number of blocks,
+/// and so can't be enqueued to the PromiseYield timeout queue, keyed by its expiry height.
+pub fn set_promise_yield_timeout_at(state_update: &mut TrieUpdate, timeout: &PromiseYieldTimeout) {
+    set(
+        state_update,
+        TrieKey::PromiseYieldTimeoutAt { expires_at: timeout.expires_at, data_id: timeout.data_id },
+        timeout,
+    );
+}
+
+/// Returns the keys of at most `limit` timeouts stored by `set_promise_yield_timeout_at`
+/// which expire at `block_height` or earlier, in the order they expire.
+pub fn get_expired_promise_yield_timeouts_at(
+    state_update: &TrieUpdate,
+    block_height: BlockHeight,
+    limit: usize,
+) -> Result<Vec<TrieKey>, StorageError> {
+    let lock = state_update.trie().lock_for_iter();
+    let mut keys = vec![];
+    for raw_key in state_update.locked_iter(&[col::PROMISE_YIELD_TIMEOUT_AT], &lock)? {
+        let (expires_at, data_id) = trie_key_parsers::parse_promise_yield_timeout_at_key(&raw_key?)
+            .map_err(|_e| {
+                StorageError::StorageInconsistentState(
+                    "Can't parse raw key for PromiseYieldTimeoutAt".to_string(),
+                )
+            })?;
+        if expires_at > block_height || keys.len() >= limit {
+            break;
+        }
+        keys.push(TrieKey::PromiseYieldTimeoutAt { expires_at, data_id });
+    }
+    Ok(keys)
+}
+
 pub fn set_promise_yield_receipt(state_update: &mut TrieUpdate, receipt: &Receipt) {
     match receipt.receipt() {
         ReceiptEnum::PromiseYield(ref action_receipt) => {
@@ -1072,6 +1253,75 @@ pub fn remove_account(
     for key in data_keys {
         state_update.remove(TrieKey::ContractData { account_id: account_id.clone(), key });
     }
+
+    // Removing namespaced contract data
+    let lock = state_update.trie().lock_for_iter();
+    let namespaced_data_keys = state_update
+        .locked_iter(
+            &trie_key_parsers::get_raw_prefix_for_account_data(
+                col::NAMESPACED_CONTRACT_DATA,
+                account_id,
+            ),
+            &lock,
+        )?
+        .map(|raw_key| {
+            trie_key_parsers::parse_namespace_and_key_from_namespaced_contract_data_key(
+                &raw_key?, account_id,
+            )
+            .and_then(|(namespace, key)| {
+                Ok((StorageNamespace::try_from(namespace.to_vec())?, key.to_vec()))
+            })
+            .map_err(|_e| {
+                StorageError::StorageInconsistentState(
+                    "Can't parse namespace and key from raw key for NamespacedContractData"
+                        .to_string(),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(lock);
+
+    for (namespace, key) in namespaced_data_keys {
+        state_update.remove(TrieKey::NamespacedContractData {
+            account_id: account_id.clone(),
+            namespace,
+            key,
+        });
+    }
+
+    // Removing storage namespace usage records
+    let lock = state_update.trie().lock_for_iter();
+    let namespaces = state_update
+        .locked_iter(
+            &trie_key_parsers::get_raw_prefix_for_account_data(
+                col::STORAGE_NAMESPACE_USAGE,
+                account_id,
+            ),
+            &lock,
+        )?
+        .map(|raw_key| {
+            trie_key_parsers::parse_namespace_from_storage_namespace_usage_key(
+                &raw_key?, account_id,
+            )
+            .map_err(|_e| {
+                StorageError::StorageInconsistentState(
+                    "Can't parse namespace from raw key for StorageNamespaceUsage".to_string(),
+                )
+            })
+            .map(Vec::from)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(lock);
+
+    for namespace in namespaces {
+        state_update
+            .remove(TrieKey::StorageNamespaceUsage { account_id: account_id.clone(), namespace });
+    }
+
+    // Removing token balances
+    for token_id in get_token_ids(state_update, account_id, usize::MAX)? {
+        state_update.remove(TrieKey::TokenBalance { account_id: account_id.clone(), token_id });
+    }
     Ok(())
 }
 
@@ -1,12 +1,14 @@
+use crate::adapter::chunk_store::{get_pending_partial_chunk_item_key, PendingPartialChunkItem};
 use crate::metadata::DbKind;
 use crate::{DBCol, Store, StoreUpdate};
 use anyhow::{anyhow, Context};
 use borsh::{BorshDeserialize, BorshSerialize};
-use near_primitives::challenge::PartialState;
+use near_primitives::block::StoredInvalidBlock;
+use near_primitives::challenge::{PartialState, StoredChallenge};
 use near_primitives::epoch_manager::EpochSummary;
 use near_primitives::epoch_manager::AGGREGATOR_KEY;
 use near_primitives::hash::CryptoHash;
-use near_primitives::sharding::{ChunkHash, StateSyncInfo, StateSyncInfoV0};
+use near_primitives::sharding::{ChunkHash, PartialEncodedChunk, StateSyncInfo, StateSyncInfoV0};
 use near_primitives::state::FlatStateValue;
 use near_primitives::stateless_validation::contract_distribution::{CodeBytes, CodeHash};
 use near_primitives::stateless_validation::stored_chunk_state_transition_data::{
@@ -18,7 +20,9 @@ use near_primitives::types::{
     ValidatorKickoutReason, ValidatorStats,
 };
 use near_primitives::types::{BlockChunkValidatorStats, ChunkStats};
-use near_primitives::utils::{get_block_shard_id_rev, get_outcome_id_block_hash};
+use near_primitives::utils::{
+    get_block_shard_id_rev, get_challenge_key, get_invalid_block_key, get_outcome_id_block_hash,
+};
 use near_primitives::version::ProtocolVersion;
 use std::collections::{BTreeMap, HashMap};
 use tracing::info;
@@ -516,3 +520,84 @@ pub fn migrate_42_to_43(store: &Store) -> anyhow::Result<()> {
     update.commit()?;
     Ok(())
 }
+
+/// Migrates `DBCol::Challenges` from keys by challenge hash to keys by height
+/// and challenge hash, so that challenges are garbage collected with a range scan.
+pub fn migrate_43_to_44(store: &Store) -> anyhow::Result<()> {
+    let _span =
+        tracing::info_span!(target: "migrations", "Keying DBCol::Challenges by height").entered();
+    let mut update = store.store_update();
+    for row in store.iter_ser::<StoredChallenge>(DBCol::Challenges) {
+        let (key, stored_challenge) = row.context("failed deserializing StoredChallenge")?;
+        update.delete(DBCol::Challenges, &key);
+        update.set_ser(
+            DBCol::Challenges,
+            &get_challenge_key(stored_challenge.received_height, &stored_challenge.challenge.hash),
+            &stored_challenge,
+        )?;
+    }
+    update.commit()?;
+    Ok(())
+}
+
+/// Migrates the database from version 44 to 45.
+///
+/// Adds `DBCol::ChunkEndorsementStats`. Nothing to convert, the stats are
+/// recorded from the blocks which become final after the upgrade.
+pub fn migrate_44_to_45(_store: &Store) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// Migrates `DBCol::InvalidBlocks` from keys by block hash to keys by height
+/// and block hash, so that invalid blocks are garbage collected with a range
+/// delete.
+pub fn migrate_45_to_46(store: &Store) -> anyhow::Result<()> {
+    let _span = tracing::info_span!(target: "migrations", "Keying DBCol::InvalidBlocks by height")
+        .entered();
+    let mut update = store.store_update();
+    for row in store.iter_ser::<StoredInvalidBlock>(DBCol::InvalidBlocks) {
+        let (key, invalid_block) = row.context("failed deserializing StoredInvalidBlock")?;
+        let block_hash =
+            CryptoHash::try_from(key.as_ref()).map_err(|_| anyhow!("wrong key length: {key:?}"))?;
+        update.delete(DBCol::InvalidBlocks, &key);
+        update.set_ser(
+            DBCol::InvalidBlocks,
+            &get_invalid_block_key(invalid_block.height, &block_hash),
+            &invalid_block,
+        )?;
+    }
+    update.commit()?;
+    Ok(())
+}
+
+/// Migrates `DBCol::PendingPartialChunks` from one row per chunk to one row per
+/// header, part and receipts of the chunk, so that each part is written once.
+pub fn migrate_46_to_47(store: &Store) -> anyhow::Result<()> {
+    let _span = tracing::info_span!(target: "migrations", "Splitting DBCol::PendingPartialChunks")
+        .entered();
+    let mut update = store.store_update();
+    for row in store.iter_ser::<PartialEncodedChunk>(DBCol::PendingPartialChunks) {
+        let (key, partial_chunk) = row.context("failed deserializing PartialEncodedChunk")?;
+        update.delete(DBCol::PendingPartialChunks, &key);
+        let height_created = partial_chunk.height_created();
+        let chunk_hash = partial_chunk.chunk_hash();
+        let items = std::iter::once(PendingPartialChunkItem::Header(partial_chunk.cloned_header()))
+            .chain(partial_chunk.parts().iter().cloned().map(PendingPartialChunkItem::Part))
+            .chain(
+                partial_chunk
+                    .prev_outgoing_receipts()
+                    .iter()
+                    .cloned()
+                    .map(PendingPartialChunkItem::Receipt),
+            );
+        for item in items {
+            update.set_ser(
+                DBCol::PendingPartialChunks,
+                &get_pending_partial_chunk_item_key(height_created, &chunk_hash, &item),
+                &item,
+            )?;
+        }
+    }
+    update.commit()?;
+    Ok(())
+}
@@ -57,6 +57,7 @@ mod nibble_slice;
 pub mod ops;
 pub mod outgoing_metadata;
 mod prefetching_trie_storage;
+mod range_proof;
 mod raw_node;
 pub mod receipts_column_helper;
 pub mod resharding_v2;
@@ -763,13 +764,17 @@ impl Trie {
         partial_storage: PartialStorage,
         root: StateRoot,
         flat_storage_used: bool,
-    ) -> Self {
-        let PartialState::TrieValues(nodes) = partial_storage.nodes;
-        let recorded_storage = nodes.into_iter().map(|value| (hash(&value), value)).collect();
+    ) -> Result<Self, StorageError> {
+        let recorded_storage = match partial_storage.nodes {
+            PartialState::TrieValues(nodes) => {
+                nodes.into_iter().map(|value| (hash(&value), value)).collect()
+            }
+            PartialState::StatePartRange(range) => Self::state_part_range_values(range)?,
+        };
         let storage = Arc::new(TrieMemoryPartialStorage::new(recorded_storage));
         let mut trie = Self::new(storage, root, None);
         trie.charge_gas_for_trie_node_access = !flat_storage_used;
-        trie
+        Ok(trie)
     }
 
     /// Get statistics about the recorded trie. Useful for observability and debugging.
@@ -2229,7 +2234,7 @@ mod tests {
         trie2.get(b"horse").unwrap();
         let partial_storage = trie2.recorded_storage();
 
-        let trie3 = Trie::from_recorded_storage(partial_storage.unwrap(), root, false);
+        let trie3 = Trie::from_recorded_storage(partial_storage.unwrap(), root, false).unwrap();
 
         assert_eq!(trie3.get(b"dog"), Ok(Some(b"puppy".to_vec())));
         assert_eq!(trie3.get(b"horse"), Ok(Some(b"stallion".to_vec())));
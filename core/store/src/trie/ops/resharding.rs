@@ -53,7 +53,9 @@ fn boundary_account_to_intervals(
             col::DELAYED_RECEIPT_OR_INDICES
             | col::PROMISE_YIELD_INDICES
             | col::PROMISE_YIELD_TIMEOUT
-            | col::BANDWIDTH_SCHEDULER_STATE => {
+            | col::PROMISE_YIELD_TIMEOUT_AT
+            | col::BANDWIDTH_SCHEDULER_STATE
+            | col::GLOBAL_CONTRACT_CODE => {
                 // This section contains the keys that we need to copy to both shards.
                 intervals.push(get_interval_for_copy_to_both_children(prefix))
             }
@@ -361,6 +363,20 @@ mod tests {
                 ..vec![col::BUFFERED_RECEIPT_GROUPS_QUEUE_DATA + 1],
             vec![col::BUFFERED_RECEIPT_GROUPS_QUEUE_ITEM]
                 ..vec![col::BUFFERED_RECEIPT_GROUPS_QUEUE_ITEM + 1],
+            vec![col::STORAGE_SPONSORSHIP]..append_key(col::STORAGE_SPONSORSHIP, &alice_account),
+            vec![col::GLOBAL_CONTRACT_CODE]..vec![col::GLOBAL_CONTRACT_CODE + 1],
+            vec![col::NAMESPACED_CONTRACT_DATA]
+                ..append_key(col::NAMESPACED_CONTRACT_DATA, &alice_account),
+            vec![col::STORAGE_NAMESPACE_USAGE]
+                ..append_key(col::STORAGE_NAMESPACE_USAGE, &alice_account),
+            vec![col::MIGRATION_GRACE_PERIOD]
+                ..append_key(col::MIGRATION_GRACE_PERIOD, &alice_account),
+            vec![col::CONTRACT_SOURCE_METADATA]
+                ..append_key(col::CONTRACT_SOURCE_METADATA, &alice_account),
+            vec![col::TOKEN_BALANCE]..append_key(col::TOKEN_BALANCE, &alice_account),
+            vec![col::PROMISE_YIELD_TIMEOUT_AT]..vec![col::PROMISE_YIELD_TIMEOUT_AT + 1],
+            vec![col::GLOBAL_CONTRACT_DEPLOYER]
+                ..append_key(col::GLOBAL_CONTRACT_DEPLOYER, &alice_account),
         ];
         assert!(left_intervals.iter().all(|range| range.start < range.end));
         for (actual, expected) in left_intervals.iter().zip_eq(expected_left_intervals.iter()) {
@@ -385,6 +401,21 @@ mod tests {
             append_key(col::PROMISE_YIELD_RECEIPT, &alice_account)
                 ..vec![col::PROMISE_YIELD_RECEIPT + 1],
             vec![col::BANDWIDTH_SCHEDULER_STATE]..vec![col::BANDWIDTH_SCHEDULER_STATE + 1],
+            append_key(col::STORAGE_SPONSORSHIP, &alice_account)
+                ..vec![col::STORAGE_SPONSORSHIP + 1],
+            vec![col::GLOBAL_CONTRACT_CODE]..vec![col::GLOBAL_CONTRACT_CODE + 1],
+            append_key(col::NAMESPACED_CONTRACT_DATA, &alice_account)
+                ..vec![col::NAMESPACED_CONTRACT_DATA + 1],
+            append_key(col::STORAGE_NAMESPACE_USAGE, &alice_account)
+                ..vec![col::STORAGE_NAMESPACE_USAGE + 1],
+            append_key(col::MIGRATION_GRACE_PERIOD, &alice_account)
+                ..vec![col::MIGRATION_GRACE_PERIOD + 1],
+            append_key(col::CONTRACT_SOURCE_METADATA, &alice_account)
+                ..vec![col::CONTRACT_SOURCE_METADATA + 1],
+            append_key(col::TOKEN_BALANCE, &alice_account)..vec![col::TOKEN_BALANCE + 1],
+            vec![col::PROMISE_YIELD_TIMEOUT_AT]..vec![col::PROMISE_YIELD_TIMEOUT_AT + 1],
+            append_key(col::GLOBAL_CONTRACT_DEPLOYER, &alice_account)
+                ..vec![col::GLOBAL_CONTRACT_DEPLOYER + 1],
         ];
         assert!(right_intervals.iter().all(|range| range.start < range.end));
         for (actual, expected) in right_intervals.iter().zip_eq(expected_right_intervals.iter()) {
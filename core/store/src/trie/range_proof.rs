@@ -0,0 +1,99 @@
+use crate::trie::nibble_slice::NibbleSlice;
+use crate::{StorageError, Trie};
+use near_primitives::challenge::PartialState;
+use near_primitives::trie_proof::TrieRangeProof;
+
+impl Trie {
+    /// Generates a proof of all key-value pairs in `[start_key, end_key)`,
+    /// which can be checked against the state root with
+    /// `TrieRangeProof::verify`.
+    ///
+    /// The proof consists of all nodes and values inside the range together
+    /// with the paths from the root to both boundaries of the range.
+    pub fn prove_range(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+    ) -> Result<TrieRangeProof, StorageError> {
+        let nodes = if self.root == Trie::EMPTY_ROOT || start_key >= end_key {
+            PartialState::default()
+        } else {
+            let path_begin: Vec<u8> = NibbleSlice::new(start_key).iter().collect();
+            let path_end: Vec<u8> = NibbleSlice::new(end_key).iter().collect();
+            let recording_trie = self.recording_reads();
+            recording_trie.disk_iter()?.visit_nodes_interval(&path_begin, &path_end)?;
+            recording_trie.recorded_storage().unwrap().nodes
+        };
+        Ok(TrieRangeProof { start_key: start_key.to_vec(), end_key: end_key.to_vec(), nodes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{gen_changes, simplify_changes, test_populate_trie, TestTriesBuilder};
+    use crate::Trie;
+    use near_primitives::challenge::PartialState;
+    use near_primitives::shard_layout::ShardUId;
+    use near_primitives::trie_proof::TrieRangeProofError;
+    use rand::Rng;
+
+    #[test]
+    fn test_prove_range() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let tries = TestTriesBuilder::new().build();
+            let trie_changes = gen_changes(&mut rng, 50);
+            let state_root = test_populate_trie(
+                &tries,
+                &Trie::EMPTY_ROOT,
+                ShardUId::single_shard(),
+                trie_changes.clone(),
+            );
+            let trie = tries.get_trie_for_shard(ShardUId::single_shard(), state_root);
+            let items: Vec<_> = simplify_changes(&trie_changes)
+                .into_iter()
+                .filter_map(|(key, value)| Some((key, value?)))
+                .collect();
+
+            for _ in 0..10 {
+                let mut bounds = [gen_key(&mut rng), gen_key(&mut rng)];
+                bounds.sort();
+                let [start_key, end_key] = bounds;
+                let proof = trie.prove_range(&start_key, &end_key).unwrap();
+                let expected: Vec<_> = items
+                    .iter()
+                    .filter(|(key, _)| *key >= start_key && *key < end_key)
+                    .cloned()
+                    .collect();
+                assert_eq!(proof.verify(&state_root).unwrap(), expected);
+
+                let PartialState::TrieValues(values) = &proof.nodes else { unreachable!() };
+                if !values.is_empty() {
+                    let mut incomplete = proof.clone();
+                    incomplete.nodes = PartialState::TrieValues(values[1..].to_vec());
+                    assert!(incomplete.verify(&state_root).is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_prove_range_wrong_root() {
+        let tries = TestTriesBuilder::new().build();
+        let changes = vec![(b"alice".to_vec(), Some(b"1".to_vec()))];
+        let state_root =
+            test_populate_trie(&tries, &Trie::EMPTY_ROOT, ShardUId::single_shard(), changes);
+        let trie = tries.get_trie_for_shard(ShardUId::single_shard(), state_root);
+        let proof = trie.prove_range(b"a", b"b").unwrap();
+        assert_eq!(proof.verify(&state_root).unwrap(), vec![(b"alice".to_vec(), b"1".to_vec())]);
+
+        let wrong_root = near_primitives::hash::hash(b"wrong");
+        assert_eq!(proof.verify(&wrong_root), Err(TrieRangeProofError::MissingNode(wrong_root)));
+    }
+
+    /// Generates short keys over the same alphabet as `gen_changes`, so that
+    /// ranges between them contain some of the generated keys.
+    fn gen_key(rng: &mut impl Rng) -> Vec<u8> {
+        (0..rng.gen_range(0..4)).map(|_| rng.gen_range(b'a'..=b'z')).collect()
+    }
+}
@@ -25,7 +25,7 @@ use crate::trie::{
 };
 use crate::{metrics, PartialStorage, StorageError, Trie, TrieChanges};
 use borsh::BorshDeserialize;
-use near_primitives::challenge::PartialState;
+use near_primitives::challenge::{PartialState, StatePartRange};
 use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::state::FlatStateValue;
 use near_primitives::state_part::PartId;
@@ -200,7 +200,10 @@ impl Trie {
             .start_timer();
         // TODO(nikurt): Simplify. This is a long function with complex logic.
 
-        let PartialState::TrieValues(path_boundary_nodes) = partial_state;
+        let path_boundary_nodes = match partial_state {
+            PartialState::TrieValues(values) => values,
+            PartialState::StatePartRange(range) => range.boundary_values,
+        };
 
         // 1. Extract all key-value pairs in state part from flat storage.
         let values_read_timer = metrics::GET_STATE_PART_READ_FS_ELAPSED
@@ -269,7 +272,9 @@ impl Trie {
         final_trie.visit_nodes_for_state_part(part_id)?;
         let final_trie_storage = final_trie.storage.as_partial_storage().unwrap();
         let final_state_part_nodes = final_trie_storage.partial_state();
-        let PartialState::TrieValues(trie_values) = &final_state_part_nodes;
+        let PartialState::TrieValues(trie_values) = &final_state_part_nodes else {
+            unreachable!("partial storage always records trie values");
+        };
         let final_part_creation_duration = final_part_creation_timer.stop_and_record();
 
         // Compute how many nodes were recreated from memory.
@@ -412,6 +417,88 @@ impl Trie {
         Ok(key_nibbles)
     }
 
+    /// Returns trie values needed to read state from `StatePartRange`.
+    /// Boundary values are taken as is, and nodes inside the range are
+    /// recomputed from the state items. Each node whose subtree is fully
+    /// inside the range has the same hash in the trie built only from range
+    /// items, so all these nodes are reachable from the original state root.
+    pub(crate) fn state_part_range_values(
+        range: StatePartRange,
+    ) -> Result<HashMap<CryptoHash, Arc<[u8]>>, StorageError> {
+        let StatePartRange { boundary_values, items } = range;
+        let local_trie =
+            Trie::new(Arc::new(TrieMemoryPartialStorage::default()), StateRoot::new(), None);
+        let local_nodes =
+            local_trie.update(items.into_iter().map(|(key, value)| (key, Some(value))))?.insertions;
+        let mut values: HashMap<CryptoHash, Arc<[u8]>> =
+            boundary_values.into_iter().map(|value| (hash(&value), value)).collect();
+        values.extend(
+            local_nodes.iter().map(|entry| (*entry.hash(), entry.payload().to_vec().into())),
+        );
+        Ok(values)
+    }
+
+    /// Converts state part into `PartialState::TrieValues` containing exactly
+    /// the trie values of the state part.
+    pub fn state_part_to_trie_values(
+        state_root: &StateRoot,
+        part_id: PartId,
+        partial_state: PartialState,
+    ) -> Result<PartialState, StorageError> {
+        if let PartialState::TrieValues(_) = partial_state {
+            return Ok(partial_state);
+        }
+        if state_root == &Trie::EMPTY_ROOT {
+            return Ok(PartialState::default());
+        }
+        let trie = Trie::from_recorded_storage(
+            PartialStorage { nodes: partial_state },
+            *state_root,
+            false,
+        )?;
+        trie.visit_nodes_for_state_part(part_id)?;
+        let storage = trie.storage.as_partial_storage().unwrap();
+        Ok(storage.partial_state())
+    }
+
+    /// Converts state part into compact `StatePartRange` format.
+    pub fn state_part_to_range(
+        state_root: &StateRoot,
+        part_id: PartId,
+        partial_state: PartialState,
+    ) -> Result<StatePartRange, StorageError> {
+        if let PartialState::StatePartRange(range) = partial_state {
+            return Ok(range);
+        }
+        if state_root == &Trie::EMPTY_ROOT {
+            return Ok(StatePartRange::default());
+        }
+        let trie = Trie::from_recorded_storage(
+            PartialStorage { nodes: partial_state },
+            *state_root,
+            false,
+        )?;
+        let recording_trie = trie.recording_reads();
+        let path_begin = recording_trie.find_state_part_boundary(part_id.idx, part_id.total)?;
+        let path_end = recording_trie.find_state_part_boundary(part_id.idx + 1, part_id.total)?;
+        let PartialState::TrieValues(boundary_values) =
+            recording_trie.recorded_storage().unwrap().nodes
+        else {
+            unreachable!("trie recorder always records trie values");
+        };
+
+        let mut iterator = trie.disk_iter()?;
+        let mut items = vec![];
+        for TrieTraversalItem { hash, key } in
+            iterator.visit_nodes_interval(&path_begin, &path_end)?
+        {
+            if let Some(key) = key {
+                items.push((key, trie.retrieve_value(&hash)?.to_vec()));
+            }
+        }
+        Ok(StatePartRange { boundary_values, items })
+    }
+
     /// Validates state part for given state root.
     /// Returns error if state part is invalid and Ok otherwise.
     pub fn validate_state_part(
@@ -419,13 +506,21 @@ impl Trie {
         part_id: PartId,
         partial_state: PartialState,
     ) -> Result<(), StorageError> {
-        let PartialState::TrieValues(nodes) = &partial_state;
+        let partial_state = match partial_state {
+            PartialState::TrieValues(_) => partial_state,
+            PartialState::StatePartRange(range) => {
+                Self::validate_state_part_range(state_root, part_id, range)?
+            }
+        };
+        let PartialState::TrieValues(nodes) = &partial_state else {
+            unreachable!("state part range is converted to trie values");
+        };
         let num_nodes = nodes.len();
         let trie = Trie::from_recorded_storage(
             PartialStorage { nodes: partial_state },
             *state_root,
             false,
-        );
+        )?;
 
         trie.visit_nodes_for_state_part(part_id)?;
         let storage = trie.storage.as_partial_storage().unwrap();
@@ -438,6 +533,34 @@ impl Trie {
         Ok(())
     }
 
+    /// Checks that state part range contains exactly the state items and
+    /// boundary values of the state part, and returns it as trie values.
+    fn validate_state_part_range(
+        state_root: &StateRoot,
+        part_id: PartId,
+        range: StatePartRange,
+    ) -> Result<PartialState, StorageError> {
+        let boundary_values: HashSet<_> = range.boundary_values.iter().cloned().collect();
+        let items = range.items.clone();
+        let trie_values = Self::state_part_to_trie_values(
+            state_root,
+            part_id,
+            PartialState::StatePartRange(range),
+        )?;
+        let PartialState::TrieValues(nodes) = &trie_values else {
+            unreachable!("partial storage always records trie values");
+        };
+        let nodes: HashSet<_> = nodes.iter().collect();
+        if !boundary_values.iter().all(|value| nodes.contains(&value)) {
+            return Err(StorageError::UnexpectedTrieValue);
+        }
+        let expected_range = Self::state_part_to_range(state_root, part_id, trie_values.clone())?;
+        if expected_range.items != items {
+            return Err(StorageError::UnexpectedTrieValue);
+        }
+        Ok(trie_values)
+    }
+
     fn apply_state_part_impl(
         state_root: &StateRoot,
         part_id: PartId,
@@ -450,7 +573,7 @@ impl Trie {
                 contract_codes: vec![],
             });
         }
-        let trie = Trie::from_recorded_storage(PartialStorage { nodes: part }, *state_root, false);
+        let trie = Trie::from_recorded_storage(PartialStorage { nodes: part }, *state_root, false)?;
         let path_begin = trie.find_state_part_boundary(part_id.idx, part_id.total)?;
         let path_end = trie.find_state_part_boundary(part_id.idx + 1, part_id.total)?;
         let mut iterator = trie.disk_iter()?;
@@ -614,11 +737,17 @@ mod tests {
             let nodes = PartialState::TrieValues(
                 parts
                     .iter()
-                    .flat_map(|PartialState::TrieValues(nodes)| nodes.iter())
+                    .flat_map(|part| {
+                        let PartialState::TrieValues(nodes) = part else {
+                            panic!("expected trie values in state part");
+                        };
+                        nodes.iter()
+                    })
                     .cloned()
                     .collect(),
             );
-            let trie = Trie::from_recorded_storage(PartialStorage { nodes }, *state_root, false);
+            let trie =
+                Trie::from_recorded_storage(PartialStorage { nodes }, *state_root, false).unwrap();
             let mut insertions = <HashMap<CryptoHash, (Vec<u8>, u32)>>::new();
             trie.traverse_all_nodes(|hash| {
                 if let Some((_bytes, rc)) = insertions.get_mut(hash) {
@@ -834,7 +963,10 @@ mod tests {
                     assert_matches!(trie.get(&left_key_boundary), Ok(Some(_)));
                 }
                 let PartialState::TrieValues(proof_nodes) =
-                    trie_recording.recorded_storage().unwrap().nodes;
+                    trie_recording.recorded_storage().unwrap().nodes
+                else {
+                    panic!("expected trie values in recorded storage");
+                };
                 let proof_size = proof_nodes.iter().map(|node| node.len()).sum::<usize>() as u64;
                 assert!(
                     proof_size <= max_proof_overhead,
@@ -847,7 +979,10 @@ mod tests {
 
                 let PartialState::TrieValues(part_nodes) = trie
                     .get_trie_nodes_for_part_without_flat_storage(PartId::new(part_id, num_parts))
-                    .unwrap();
+                    .unwrap()
+                else {
+                    panic!("expected trie values in state part");
+                };
                 // TODO (#8997): it's a bit weird that raw lengths are compared to
                 // config values. Consider better defined assertion.
                 let total_size = part_nodes.iter().map(|node| node.len()).sum::<usize>() as u64;
@@ -935,13 +1070,18 @@ mod tests {
                 let mut nodes = <HashMap<CryptoHash, Arc<[u8]>>>::new();
                 let sizes_vec = parts
                     .iter()
-                    .map(|PartialState::TrieValues(nodes)| {
+                    .map(|part| {
+                        let PartialState::TrieValues(nodes) = part else {
+                            panic!("expected trie values in state part");
+                        };
                         nodes.iter().map(|node| node.len()).sum::<usize>()
                     })
                     .collect::<Vec<_>>();
 
                 for part in parts {
-                    let PartialState::TrieValues(part_nodes) = part;
+                    let PartialState::TrieValues(part_nodes) = part else {
+                        panic!("expected trie values in state part");
+                    };
                     for node in part_nodes {
                         nodes.insert(hash(&node), node);
                     }
@@ -1053,7 +1193,10 @@ mod tests {
         let trie = tries.get_view_trie_for_shard(shard_uid, root);
         let PartialState::TrieValues(trie_values) = trie
             .get_trie_nodes_for_part_without_flat_storage(part_id)
-            .expect("State part generation using Trie must work");
+            .expect("State part generation using Trie must work")
+        else {
+            panic!("expected trie values in state part");
+        };
         let num_trie_values = trie_values.len();
         assert!(num_trie_values >= 2);
 
@@ -1100,6 +1243,69 @@ mod tests {
         );
     }
 
+    /// Checks that state part in `StatePartRange` format can be converted to and
+    /// from trie values, validated and applied, and that inconsistent ranges
+    /// fail validation.
+    #[test]
+    fn state_part_range() {
+        let tries = TestTriesBuilder::new().build();
+        let shard_uid = ShardUId::single_shard();
+        let part_id = PartId::new(1, 2);
+        let trie = tries.get_trie_for_shard(shard_uid, Trie::EMPTY_ROOT);
+
+        let state_items = vec![
+            (b"a".to_vec(), vec![1]),
+            (b"aa".to_vec(), vec![2]),
+            (b"ab".to_vec(), vec![3]),
+            (b"b".to_vec(), vec![4]),
+            (b"ba".to_vec(), vec![5]),
+        ];
+        let changes_for_trie = state_items.iter().cloned().map(|(k, v)| (k, Some(v)));
+        let trie_changes = trie.update(changes_for_trie).unwrap();
+        let mut store_update = tries.store_update();
+        let root = tries.apply_all(&trie_changes, shard_uid, &mut store_update);
+        store_update.commit().unwrap();
+
+        let trie = tries.get_view_trie_for_shard(shard_uid, root);
+        let trie_values = trie.get_trie_nodes_for_part_without_flat_storage(part_id).unwrap();
+        let range = Trie::state_part_to_range(&root, part_id, trie_values.clone()).unwrap();
+        assert!(!range.items.is_empty());
+        assert!(range.items.iter().all(|item| state_items.contains(item)));
+
+        let range_state = PartialState::StatePartRange(range.clone());
+        assert_eq!(Trie::validate_state_part(&root, part_id, range_state.clone()), Ok(()));
+        assert_eq!(
+            Trie::state_part_to_trie_values(&root, part_id, range_state.clone()).unwrap(),
+            trie_values
+        );
+        let insertions = |state_part| -> HashSet<_> {
+            Trie::apply_state_part(&root, part_id, state_part)
+                .trie_changes
+                .insertions
+                .into_iter()
+                .collect()
+        };
+        assert_eq!(insertions(range_state), insertions(trie_values));
+
+        // Changed value in the range, check that validation fails.
+        let mut wrong_range = range.clone();
+        wrong_range.items[0].1.push(42);
+        assert!(Trie::validate_state_part(
+            &root,
+            part_id,
+            PartialState::StatePartRange(wrong_range)
+        )
+        .is_err());
+
+        // Extra boundary value, check that validation fails.
+        let mut wrong_range = range;
+        wrong_range.boundary_values.push(vec![11].into());
+        assert_eq!(
+            Trie::validate_state_part(&root, part_id, PartialState::StatePartRange(wrong_range)),
+            Err(StorageError::UnexpectedTrieValue)
+        );
+    }
+
     /// Check on random samples that state parts can be validated independently
     /// from the entire trie.
     #[test]
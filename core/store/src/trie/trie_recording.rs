@@ -475,8 +475,12 @@ mod trie_recording_tests {
 
     /// Assert equality of partial storages with human-readable output.
     fn assert_partial_storage(storage: &PartialStorage, other_storage: &PartialStorage) {
-        let PartialState::TrieValues(nodes) = &storage.nodes;
-        let PartialState::TrieValues(other_nodes) = &other_storage.nodes;
+        let PartialState::TrieValues(nodes) = &storage.nodes else {
+            panic!("expected trie values in partial storage");
+        };
+        let PartialState::TrieValues(other_nodes) = &other_storage.nodes else {
+            panic!("expected trie values in partial storage");
+        };
         let nodes: HashSet<Vec<u8>> = HashSet::from_iter(nodes.into_iter().map(|key| key.to_vec()));
         let other_nodes: HashSet<Vec<u8>> =
             HashSet::from_iter(other_nodes.into_iter().map(|key| key.to_vec()));
@@ -601,7 +605,8 @@ mod trie_recording_tests {
                 data_in_trie.len()
             );
             let trie =
-                Trie::from_recorded_storage(partial_storage.clone(), state_root, use_flat_storage);
+                Trie::from_recorded_storage(partial_storage.clone(), state_root, use_flat_storage)
+                    .unwrap();
             trie.accounting_cache.borrow().enable_switch().set(enable_accounting_cache);
             for key in &keys_to_get {
                 assert_eq!(trie.get(key).unwrap(), data_in_trie.get(key).cloned());
@@ -619,6 +624,7 @@ mod trie_recording_tests {
 
             // Build a Trie using recorded storage and enable recording_reads on this Trie
             let trie = Trie::from_recorded_storage(partial_storage, state_root, use_flat_storage)
+                .unwrap()
                 .recording_reads();
             trie.accounting_cache.borrow().enable_switch().set(enable_accounting_cache);
             for key in &keys_to_get {
@@ -22,7 +22,9 @@ pub struct IncompletePartialStorage {
 
 impl IncompletePartialStorage {
     pub fn new(partial_storage: PartialStorage, nodes_count_to_fail_at: usize) -> Self {
-        let PartialState::TrieValues(nodes) = partial_storage.nodes;
+        let PartialState::TrieValues(nodes) = partial_storage.nodes else {
+            panic!("expected trie values in partial storage");
+        };
         let recorded_storage = nodes.into_iter().map(|value| (hash(&value), value)).collect();
         Self {
             recorded_storage,
@@ -529,6 +531,7 @@ mod trie_storage_tests {
 
         let partial_recorded = {
             let trie = Trie::from_recorded_storage(memtrie_iter_recorded, state_root, true)
+                .unwrap()
                 .recording_reads();
             let mut disk_iter = trie.disk_iter().unwrap();
             disk_iter.seek_prefix(&iter_prefix).unwrap();
@@ -688,7 +688,7 @@ impl TestLoopBuilder {
         )
         .unwrap();
 
-        let shards_manager = ShardsManagerActor::new(
+        let mut shards_manager = ShardsManagerActor::new(
             self.test_loop.clock(),
             validator_signer.clone(),
             epoch_manager.clone(),
@@ -701,6 +701,7 @@ impl TestLoopBuilder {
             client.chain.header_head().unwrap(),
             Duration::milliseconds(100),
         );
+        shards_manager.set_chunk_part_distribution(client_config.chunk_part_distribution.clone());
 
         let client_actor = ClientActorInner::new(
             self.test_loop.clock(),
@@ -733,6 +734,7 @@ impl TestLoopBuilder {
             epoch_manager.clone(),
             client_config.gc.clone(),
             client_config.archive,
+            chain_genesis.transaction_validity_period,
         );
         // We don't send messages to `GCActor` so adapter is not needed.
         self.test_loop.register_actor_for_index(idx, gc_actor, None);
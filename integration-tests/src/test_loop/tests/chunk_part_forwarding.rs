@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use near_async::messaging::Handler;
+use near_async::time::Duration;
+use near_chain_configs::test_genesis::{
+    build_genesis_and_epoch_config_store, GenesisAndEpochConfigParams, ValidatorsSpec,
+};
+use near_chain_configs::ChunkPartForwardingPolicy;
+use near_chunks::debug::GetChunkPartsStatus;
+use near_o11y::testonly::init_test_logger;
+use near_primitives::shard_layout::ShardLayout;
+use near_primitives::types::AccountId;
+use near_primitives::version::PROTOCOL_VERSION;
+
+use crate::test_loop::builder::TestLoopBuilder;
+use crate::test_loop::env::TestLoopEnv;
+
+const NUM_VALIDATORS: usize = 8;
+const MAX_FORWARD_FANOUT: usize = 3;
+
+/// Every validator tracks all shards, but the owners of the parts only forward them to the
+/// producers of the next blocks, and to no more than `MAX_FORWARD_FANOUT` accounts. The other
+/// validators request the parts they miss, so the chain keeps including all the chunks, also
+/// across epoch boundaries.
+#[test]
+fn test_forward_chunk_parts_to_next_block_producers() {
+    init_test_logger();
+    let accounts = (0..NUM_VALIDATORS)
+        .map(|i| format!("account{}", i).parse().unwrap())
+        .collect::<Vec<AccountId>>();
+    let epoch_length = 10;
+    let shard_layout = ShardLayout::simple_v1(&["account3", "account5"]);
+    let validators_spec =
+        ValidatorsSpec::desired_roles(&accounts.iter().map(|a| a.as_str()).collect_vec(), &[]);
+    let (genesis, epoch_config_store) = build_genesis_and_epoch_config_store(
+        GenesisAndEpochConfigParams {
+            epoch_length,
+            protocol_version: PROTOCOL_VERSION,
+            shard_layout,
+            validators_spec,
+            accounts: &accounts,
+        },
+        |genesis_builder| genesis_builder,
+        |epoch_config_builder| epoch_config_builder,
+    );
+    let TestLoopEnv { mut test_loop, datas: node_datas, tempdir } = TestLoopBuilder::new()
+        .genesis(genesis)
+        .epoch_config_store(epoch_config_store)
+        .clients(accounts)
+        .track_all_shards()
+        .config_modifier(|config, _| {
+            config.chunk_part_distribution.forwarding_policy =
+                ChunkPartForwardingPolicy::NextBlockProducers { num_block_producers: 2 };
+            config.chunk_part_distribution.max_forward_fanout = Some(MAX_FORWARD_FANOUT);
+        })
+        .build();
+
+    let client_handle = node_datas[0].client_sender.actor_handle();
+    let initial_height = test_loop.data.get(&client_handle).client.chain.head().unwrap().height;
+    let mut last_checked_height = initial_height;
+    test_loop.run_until(
+        |test_loop_data| {
+            let chain = &test_loop_data.get(&client_handle).client.chain;
+            let head = chain.head().unwrap();
+            if head.height > last_checked_height {
+                let header = chain.get_block_header(&head.last_block_hash).unwrap();
+                assert!(
+                    header.chunk_mask().iter().all(|included| *included),
+                    "missing chunk at height {}",
+                    head.height
+                );
+                last_checked_height = head.height;
+            }
+            head.height > initial_height + 3 * epoch_length
+        },
+        Duration::seconds(60),
+    );
+
+    let chain = &test_loop.data.get(&client_handle).client.chain;
+    let head = chain.head().unwrap();
+    let chunk_hashes = chain
+        .get_block(&head.last_block_hash)
+        .unwrap()
+        .chunks()
+        .iter_raw()
+        .map(|chunk_header| chunk_header.chunk_hash())
+        .collect_vec();
+    let mut num_forwards = 0;
+    for node_data in &node_datas {
+        let shards_manager =
+            test_loop.data.get_mut(&node_data.shards_manager_sender.actor_handle());
+        for chunk_hash in &chunk_hashes {
+            let Some(status) =
+                shards_manager.handle(GetChunkPartsStatus { chunk_hash: chunk_hash.clone() })
+            else {
+                continue;
+            };
+            let recipients =
+                status.forwards.iter().map(|forward| &forward.account_id).collect::<HashSet<_>>();
+            assert!(recipients.len() <= MAX_FORWARD_FANOUT, "{:?}", recipients);
+            num_forwards += status.forwards.len();
+        }
+    }
+    assert!(num_forwards > 0);
+
+    TestLoopEnv { test_loop, datas: node_datas, tempdir }
+        .shutdown_and_drain_remaining_events(Duration::seconds(20));
+}
@@ -1,5 +1,6 @@
 mod bandwidth_scheduler;
 mod bandwidth_scheduler_protocol_upgrade;
+mod chunk_part_forwarding;
 mod chunk_validator_kickout;
 mod congestion_control;
 mod congestion_control_genesis_bootstrap;
@@ -1,29 +1,41 @@
 use assert_matches::assert_matches;
 use near_async::time::Clock;
-use near_chain::validate::validate_challenge;
+use near_chain::validate::{
+    challenge_target, validate_challenge, validate_challenge_signature, validate_challenges_batch,
+};
 use near_chain::{Block, ChainStoreAccess, Error, Provenance};
 use near_chain_configs::Genesis;
 use near_chunks::shards_manager_actor::ShardsManagerActor;
 use near_client::test_utils::{create_chunk, create_chunk_with_transactions, TestEnv};
 use near_client::{Client, ProcessTxResponse, ProduceChunkResult};
+use near_client_primitives::debug::{ChallengeCheckResult, ChallengeVerdict};
 use near_crypto::InMemorySigner;
 use near_network::types::NetworkRequests;
-use near_primitives::bandwidth_scheduler::BandwidthRequests;
+use near_primitives::bandwidth_scheduler::{BandwidthRequests, OutgoingBandwidth};
 use near_primitives::challenge::{
-    BlockDoubleSign, Challenge, ChallengeBody, ChunkProofs, MaybeEncodedShardChunk, PartialState,
-    TrieValue,
+    BlockDoubleSign, Challenge, ChallengeBody, ChunkDoubleSign, ChunkProofs, InvalidStateWitness,
+    MaybeEncodedShardChunk, PartialState, SlashedValidator, SlashingSeverity, TrieValue,
 };
 use near_primitives::congestion_info::CongestionInfo;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::PartialMerkleTree;
 use near_primitives::num_rational::Ratio;
+use near_primitives::reed_solomon::ReedSolomonEncoder;
 use near_primitives::shard_layout::ShardUId;
 use near_primitives::sharding::EncodedShardChunk;
 use near_primitives::stateless_validation::chunk_endorsement::ChunkEndorsement;
+use near_primitives::stateless_validation::partial_witness::{
+    PartialEncodedStateWitness, WITNESS_RATIO_DATA_PARTS,
+};
+use near_primitives::stateless_validation::state_witness::{
+    ChunkStateWitness, EncodedChunkStateWitness,
+};
 use near_primitives::test_utils::create_test_signer;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{AccountId, EpochId, ShardId};
+use near_primitives::utils::compression::CompressedData;
+use near_primitives::validator_signer::ValidatorSigner;
 use near_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
 use near_store::Trie;
 use nearcore::test_utils::TestEnvNightshadeSetupExt;
@@ -44,7 +56,7 @@ fn test_block_with_challenges() {
             left_block_header: borsh::to_vec(&genesis.header()).unwrap(),
             right_block_header: borsh::to_vec(&genesis.header()).unwrap(),
         });
-        let challenge = Challenge::produce(challenge_body, &*signer);
+        let challenge = env.clients[0].produce_challenge(challenge_body, &*signer).unwrap();
         let challenges = vec![challenge];
         block.set_challenges(challenges.clone());
         let block_body_hash = block.compute_block_body_hash().unwrap();
@@ -120,13 +132,15 @@ fn test_verify_block_double_sign_challenge() {
         None,
     );
     let epoch_id = *b1.header().epoch_id();
-    let valid_challenge = Challenge::produce(
-        ChallengeBody::BlockDoubleSign(BlockDoubleSign {
-            left_block_header: borsh::to_vec(&b2.header()).unwrap(),
-            right_block_header: borsh::to_vec(&b1.header()).unwrap(),
-        }),
-        &signer,
-    );
+    let valid_challenge = env.clients[1]
+        .produce_challenge(
+            ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+                left_block_header: borsh::to_vec(&b2.header()).unwrap(),
+                right_block_header: borsh::to_vec(&b1.header()).unwrap(),
+            }),
+            &signer,
+        )
+        .unwrap();
     assert_eq!(
         &validate_challenge(
             env.clients[1].chain.epoch_manager.as_ref(),
@@ -139,13 +153,15 @@ fn test_verify_block_double_sign_challenge() {
         .0,
         if b1.hash() > b2.hash() { b1.hash() } else { b2.hash() }
     );
-    let invalid_challenge = Challenge::produce(
-        ChallengeBody::BlockDoubleSign(BlockDoubleSign {
-            left_block_header: borsh::to_vec(&b1.header()).unwrap(),
-            right_block_header: borsh::to_vec(&b1.header()).unwrap(),
-        }),
-        &signer,
-    );
+    let invalid_challenge = env.clients[1]
+        .produce_challenge(
+            ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+                left_block_header: borsh::to_vec(&b1.header()).unwrap(),
+                right_block_header: borsh::to_vec(&b1.header()).unwrap(),
+            }),
+            &signer,
+        )
+        .unwrap();
     assert!(validate_challenge(
         env.clients[1].chain.epoch_manager.as_ref(),
         env.clients[1].chain.runtime_adapter.as_ref(),
@@ -155,13 +171,15 @@ fn test_verify_block_double_sign_challenge() {
     )
     .is_err());
     let b3 = env.clients[0].produce_block(3).unwrap().unwrap();
-    let invalid_challenge = Challenge::produce(
-        ChallengeBody::BlockDoubleSign(BlockDoubleSign {
-            left_block_header: borsh::to_vec(&b1.header()).unwrap(),
-            right_block_header: borsh::to_vec(&b3.header()).unwrap(),
-        }),
-        &signer,
-    );
+    let invalid_challenge = env.clients[1]
+        .produce_challenge(
+            ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+                left_block_header: borsh::to_vec(&b1.header()).unwrap(),
+                right_block_header: borsh::to_vec(&b3.header()).unwrap(),
+            }),
+            &signer,
+        )
+        .unwrap();
     assert!(validate_challenge(
         env.clients[1].chain.epoch_manager.as_ref(),
         env.clients[1].chain.runtime_adapter.as_ref(),
@@ -171,6 +189,48 @@ fn test_verify_block_double_sign_challenge() {
     )
     .is_err());
 
+    // Batch validation gives the same results as validating one by one.
+    let batch_results = validate_challenges_batch(
+        env.clients[1].chain.epoch_manager.as_ref(),
+        env.clients[1].chain.runtime_adapter.as_ref(),
+        &epoch_id,
+        genesis.hash(),
+        &[valid_challenge.clone(), invalid_challenge],
+    );
+    assert_eq!(batch_results.len(), 2);
+    assert_eq!(
+        &batch_results[0].as_ref().unwrap().0,
+        if b1.hash() > b2.hash() { b1.hash() } else { b2.hash() }
+    );
+    assert!(batch_results[1].is_err());
+
+    // Dry-run validation reports the slashed validators without broadcasting the challenge.
+    let report = env.clients[1].validate_challenge(&valid_challenge);
+    assert!(report.is_valid());
+    assert_eq!(report.state_reexecution, ChallengeCheckResult::Skipped);
+    assert_eq!(
+        report.slashed_validators,
+        vec![SlashedValidator::new("test0".parse().unwrap(), SlashingSeverity::DoubleSign)
+            .with_challenger("test0".parse().unwrap(), PROTOCOL_VERSION)]
+    );
+    let malicious_challenge = env.clients[1]
+        .produce_challenge(
+            ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+                left_block_header: borsh::to_vec(&b1.header()).unwrap(),
+                right_block_header: borsh::to_vec(&b1.header()).unwrap(),
+            }),
+            &signer,
+        )
+        .unwrap();
+    let report = env.clients[1].validate_challenge(&malicious_challenge);
+    assert!(!report.is_valid());
+    assert_eq!(report.signature, ChallengeCheckResult::Passed);
+    assert_matches!(report.proofs, ChallengeCheckResult::Failed { .. });
+    assert_eq!(
+        report.slashed_validators,
+        vec![SlashedValidator::new("test0".parse().unwrap(), SlashingSeverity::Other)]
+    );
+
     let result = env.clients[0].process_block_test(b2.into(), Provenance::SYNC);
     assert!(result.is_ok());
 
@@ -185,6 +245,243 @@ fn test_verify_block_double_sign_challenge() {
     assert!(seen_challenge);
 }
 
+#[test]
+fn test_verify_co_signed_challenge() {
+    let mut env = TestEnv::default_builder().clients_count(2).mock_epoch_managers().build();
+    env.produce_block(0, 1);
+    let genesis = env.clients[0].chain.get_block_by_height(0).unwrap();
+    let b1 = env.clients[0].chain.get_block_by_height(1).unwrap();
+    let epoch_id = *genesis.header().epoch_id();
+    let signer0 = create_test_signer("test0");
+    let signer1 = create_test_signer("test1");
+    let body = ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+        left_block_header: borsh::to_vec(&b1.header()).unwrap(),
+        right_block_header: borsh::to_vec(&b1.header()).unwrap(),
+    });
+    let epoch_manager = env.clients[0].chain.epoch_manager.as_ref();
+    let (target_epoch_id, target_height) = challenge_target(epoch_manager, &body).unwrap();
+    let challenge = Challenge::produce_co_signed(
+        body.clone(),
+        target_epoch_id,
+        target_height,
+        &signer0,
+        &[&signer1],
+    );
+    assert_matches!(
+        validate_challenge_signature(epoch_manager, &epoch_id, genesis.hash(), &challenge),
+        Ok(())
+    );
+
+    // Co-signers are part of the signed hash, so they can't be stripped.
+    let mut stripped_challenge = challenge.clone();
+    stripped_challenge.co_signatures.clear();
+    stripped_challenge.init();
+    assert_matches!(
+        validate_challenge_signature(epoch_manager, &epoch_id, genesis.hash(), &stripped_challenge),
+        Err(Error::InvalidChallenge)
+    );
+
+    // Each account can sign the challenge only once.
+    let duplicated_challenge = Challenge::produce_co_signed(
+        body,
+        target_epoch_id,
+        target_height,
+        &signer0,
+        &[&signer1, &signer1],
+    );
+    assert_matches!(
+        validate_challenge_signature(
+            epoch_manager,
+            &epoch_id,
+            genesis.hash(),
+            &duplicated_challenge
+        ),
+        Err(Error::InvalidChallenge)
+    );
+    let mut forged_challenge = challenge.clone();
+    forged_challenge.co_signatures[0].1 = signer1.sign_bytes(b"not a challenge");
+    assert_matches!(
+        validate_challenge_signature(epoch_manager, &epoch_id, genesis.hash(), &forged_challenge),
+        Err(Error::InvalidChallenge)
+    );
+
+    // Batch validation checks co-signatures as well.
+    let batch_results = validate_challenges_batch(
+        epoch_manager,
+        env.clients[0].chain.runtime_adapter.as_ref(),
+        &epoch_id,
+        genesis.hash(),
+        &[challenge.clone(), forged_challenge],
+    );
+    assert_matches!(batch_results[0], Err(Error::MaliciousChallenge));
+    assert_matches!(batch_results[1], Err(Error::InvalidChallenge));
+
+    // All signers of a malicious challenge are slashed.
+    let report = env.clients[0].validate_challenge(&challenge);
+    assert_eq!(report.signature, ChallengeCheckResult::Passed);
+    assert_eq!(
+        report.slashed_validators,
+        vec![
+            SlashedValidator::new("test0".parse().unwrap(), SlashingSeverity::Other),
+            SlashedValidator::new("test1".parse().unwrap(), SlashingSeverity::Other),
+        ]
+    );
+}
+
+#[test]
+fn test_verify_chunk_double_sign_challenge() {
+    let mut env = TestEnv::default_builder().mock_epoch_managers().build();
+    env.produce_block(0, 1);
+    let genesis = env.clients[0].chain.get_block_by_height(0).unwrap();
+    let epoch_id = *genesis.header().epoch_id();
+    let (ProduceChunkResult { chunk: left_chunk, .. }, _) =
+        create_chunk(&mut env.clients[0], None, None);
+    let (ProduceChunkResult { chunk: right_chunk, .. }, _) =
+        create_invalid_proofs_chunk(&mut env.clients[0]);
+    let left_chunk_header = left_chunk.cloned_header();
+    let right_chunk_header = right_chunk.cloned_header();
+    assert_ne!(left_chunk_header.chunk_hash(), right_chunk_header.chunk_hash());
+
+    let signer = create_test_signer("test0");
+    let valid_challenge = env.clients[0]
+        .produce_challenge(
+            ChallengeBody::ChunkDoubleSign(ChunkDoubleSign {
+                left_chunk_header: left_chunk_header.clone(),
+                right_chunk_header: right_chunk_header.clone(),
+            }),
+            &signer,
+        )
+        .unwrap();
+    assert_eq!(
+        validate_challenge(
+            env.clients[0].chain.epoch_manager.as_ref(),
+            env.clients[0].chain.runtime_adapter.as_ref(),
+            &epoch_id,
+            genesis.hash(),
+            &valid_challenge
+        )
+        .unwrap(),
+        (
+            std::cmp::max(left_chunk_header.chunk_hash().0, right_chunk_header.chunk_hash().0),
+            vec!["test0".parse().unwrap()]
+        )
+    );
+
+    // The same chunk signed once is not a double sign.
+    let invalid_challenge = env.clients[0]
+        .produce_challenge(
+            ChallengeBody::ChunkDoubleSign(ChunkDoubleSign {
+                left_chunk_header: left_chunk_header.clone(),
+                right_chunk_header: left_chunk_header,
+            }),
+            &signer,
+        )
+        .unwrap();
+    assert_matches!(
+        validate_challenge(
+            env.clients[0].chain.epoch_manager.as_ref(),
+            env.clients[0].chain.runtime_adapter.as_ref(),
+            &epoch_id,
+            genesis.hash(),
+            &invalid_challenge
+        ),
+        Err(Error::MaliciousChallenge)
+    );
+}
+
+#[test]
+fn test_process_challenge_replay_protection() {
+    let mut env = TestEnv::default_builder().mock_epoch_managers().build();
+    env.produce_block(0, 1);
+    let genesis = env.clients[0].chain.get_block_by_height(0).unwrap();
+    let (ProduceChunkResult { chunk: left_chunk, .. }, _) =
+        create_chunk(&mut env.clients[0], None, None);
+    let (ProduceChunkResult { chunk: right_chunk, .. }, _) =
+        create_invalid_proofs_chunk(&mut env.clients[0]);
+    let body = ChallengeBody::ChunkDoubleSign(ChunkDoubleSign {
+        left_chunk_header: left_chunk.cloned_header(),
+        right_chunk_header: right_chunk.cloned_header(),
+    });
+    let signer = create_test_signer("test0");
+    let challenge = env.clients[0].produce_challenge(body.clone(), &signer).unwrap();
+
+    // Target epoch and height are covered by the signature and must match the body.
+    let mismatched_challenge =
+        Challenge::produce(body, challenge.target_epoch_id, challenge.target_height + 1, &signer);
+    assert_ne!(mismatched_challenge.hash, challenge.hash);
+    assert_matches!(
+        validate_challenge(
+            env.clients[0].chain.epoch_manager.as_ref(),
+            env.clients[0].chain.runtime_adapter.as_ref(),
+            genesis.header().epoch_id(),
+            genesis.hash(),
+            &mismatched_challenge
+        ),
+        Err(Error::InvalidChallenge)
+    );
+
+    while env.network_adapters[0].pop().is_some() {}
+    let count_gossiped = |env: &TestEnv| {
+        let mut count = 0;
+        while let Some(message) = env.network_adapters[0].pop() {
+            if let NetworkRequests::Challenge(_) = message.as_network_requests() {
+                count += 1;
+            }
+        }
+        count
+    };
+    env.clients[0].process_challenge(mismatched_challenge).unwrap();
+    assert_eq!(count_gossiped(&env), 0);
+    env.clients[0].process_challenge(challenge.clone()).unwrap();
+    assert!(env.clients[0].challenges.contains_key(&challenge.hash));
+    assert_eq!(count_gossiped(&env), 1);
+    // Duplicated challenge is neither reprocessed nor gossiped again.
+    env.clients[0].process_challenge(challenge.clone()).unwrap();
+    assert_eq!(count_gossiped(&env), 0);
+
+    let verdicts: Vec<_> = env.clients[0]
+        .recent_challenges
+        .iter()
+        .map(|info| (info.challenge_hash, info.verdict.clone()))
+        .collect();
+    assert_eq!(verdicts.len(), 2);
+    assert_eq!(verdicts[0], (challenge.hash, ChallengeVerdict::Accepted));
+    assert_matches!(verdicts[1].1, ChallengeVerdict::Rejected { .. });
+    assert_eq!(env.clients[0].recent_challenges[0].slashed_validators.len(), 1);
+}
+
+#[test]
+fn test_challenge_slashing_window() {
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap()], 1);
+    genesis.config.epoch_length = 5;
+    let mut env = TestEnv::builder(&genesis.config).nightshade_runtimes(&genesis).build();
+    let signer = create_test_signer("test0");
+    let make_challenge = |target_epoch_id| {
+        Challenge::produce(
+            ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+                left_block_header: vec![],
+                right_block_header: vec![],
+            }),
+            target_epoch_id,
+            1,
+            &signer,
+        )
+    };
+    let genesis_epoch_challenge = make_challenge(EpochId::default());
+    assert!(env.clients[0].is_challenge_within_slashing_window(&genesis_epoch_challenge).unwrap());
+
+    for height in 1..=20 {
+        env.produce_block(0, height);
+    }
+    let head = env.clients[0].chain.head().unwrap();
+    assert!(env.clients[0]
+        .is_challenge_within_slashing_window(&make_challenge(head.epoch_id))
+        .unwrap());
+    assert!(!env.clients[0].is_challenge_within_slashing_window(&genesis_epoch_challenge).unwrap());
+    let unknown_epoch_challenge = make_challenge(EpochId(CryptoHash::hash_bytes(b"unknown")));
+    assert!(!env.clients[0].is_challenge_within_slashing_window(&unknown_epoch_challenge).unwrap());
+}
+
 fn create_invalid_proofs_chunk(client: &mut Client) -> (ProduceChunkResult, Block) {
     create_chunk(
         client,
@@ -306,6 +603,104 @@ fn test_verify_chunk_proofs_challenge_transaction_order() {
     assert_eq!(challenge_result.unwrap(), (*block.hash(), vec!["test0".parse().unwrap()]));
 }
 
+#[test]
+fn test_verify_invalid_state_witness_challenge_signatures() {
+    let mut env = TestEnv::default_builder().mock_epoch_managers().build();
+    env.produce_block(0, 1);
+    let (ProduceChunkResult { chunk, .. }, block) = create_chunk(&mut env.clients[0], None, None);
+    let chunk_header = chunk.cloned_header();
+    let signer = env.clients[0].validator_signer.get().unwrap();
+    let state_witness = ChunkStateWitness::new(
+        signer.validator_id().clone(),
+        *block.header().epoch_id(),
+        chunk_header.clone(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    );
+    let epoch_id = *block.header().epoch_id();
+    // Splits the witness into parts signed by `part_signer`, as the chunk producer does.
+    let make_witness_parts = |part_signer: &ValidatorSigner| {
+        let num_parts = env.clients[0]
+            .epoch_manager
+            .get_chunk_validator_assignments(
+                &epoch_id,
+                chunk_header.shard_id(),
+                chunk_header.height_created(),
+            )
+            .unwrap()
+            .len();
+        let (encoded_witness, _) = EncodedChunkStateWitness::encode(&state_witness).unwrap();
+        let encoder = ReedSolomonEncoder::new(num_parts, WITNESS_RATIO_DATA_PARTS);
+        let (parts, encoded_length) = encoder.encode(&encoded_witness);
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(part_ord, part)| {
+                PartialEncodedStateWitness::new(
+                    epoch_id,
+                    chunk_header.clone(),
+                    part_ord,
+                    part.unwrap().to_vec(),
+                    encoded_length,
+                    part_signer,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let make_challenge = |witness_parts, endorsements| {
+        env.clients[0]
+            .produce_challenge(
+                ChallengeBody::InvalidStateWitness(InvalidStateWitness {
+                    witness_parts,
+                    endorsements,
+                }),
+                &signer,
+            )
+            .unwrap()
+    };
+    let validate = |challenge: &Challenge| {
+        validate_challenge(
+            env.clients[0].chain.epoch_manager.as_ref(),
+            env.clients[0].chain.runtime_adapter.as_ref(),
+            block.header().epoch_id(),
+            block.header().prev_hash(),
+            challenge,
+        )
+    };
+
+    let witness_parts = make_witness_parts(signer.as_ref());
+    let endorsement = ChunkEndorsement::new(epoch_id, &chunk_header, &signer);
+    let endorsements = vec![(signer.validator_id().clone(), endorsement.signature())];
+
+    // Witness that nobody endorsed can't be challenged.
+    let challenge = make_challenge(witness_parts.clone(), vec![]);
+    assert_matches!(validate(&challenge).unwrap_err(), Error::InvalidChallenge);
+
+    // Endorsement signature must be over the chunk hash.
+    let bad_signature = signer.sign_bytes(b"not an endorsement");
+    let challenge =
+        make_challenge(witness_parts.clone(), vec![(signer.validator_id().clone(), bad_signature)]);
+    assert_matches!(validate(&challenge).unwrap_err(), Error::InvalidChallenge);
+
+    // The witness must be included, as distributed by the chunk producer.
+    let challenge = make_challenge(vec![], endorsements.clone());
+    assert_matches!(validate(&challenge).unwrap_err(), Error::InvalidChallenge);
+    let other_signer = create_test_signer("test1");
+    let challenge = make_challenge(make_witness_parts(&other_signer), endorsements.clone());
+    assert_matches!(validate(&challenge).unwrap_err(), Error::InvalidChallenge);
+
+    let challenge = make_challenge(witness_parts, endorsements);
+    assert_eq!(
+        validate(&challenge).unwrap(),
+        (chunk_header.chunk_hash().0, vec![signer.validator_id().clone()])
+    );
+}
+
 fn challenge(
     env: TestEnv,
     shard_id: ShardId,
@@ -317,14 +712,16 @@ fn challenge(
     let shard_index = shard_layout.get_shard_index(shard_id)?;
 
     let merkle_paths = Block::compute_chunk_headers_root(block.chunks().iter_deprecated()).1;
-    let valid_challenge = Challenge::produce(
-        ChallengeBody::ChunkProofs(ChunkProofs {
-            block_header: borsh::to_vec(&block.header()).unwrap(),
-            chunk,
-            merkle_proof: merkle_paths[shard_index].clone(),
-        }),
-        &*env.clients[0].validator_signer.get().unwrap(),
-    );
+    let valid_challenge = env.clients[0]
+        .produce_challenge(
+            ChallengeBody::ChunkProofs(ChunkProofs {
+                block_header: borsh::to_vec(&block.header()).unwrap(),
+                chunk,
+                merkle_proof: merkle_paths[shard_index].clone(),
+            }),
+            &*env.clients[0].validator_signer.get().unwrap(),
+        )
+        .unwrap();
     validate_challenge(
         env.clients[0].chain.epoch_manager.as_ref(),
         env.clients[0].chain.runtime_adapter.as_ref(),
@@ -387,6 +784,8 @@ fn test_verify_chunk_invalid_state_challenge() {
         CryptoHash::default(),
         congestion_info,
         BandwidthRequests::default_for_protocol_version(PROTOCOL_VERSION),
+        OutgoingBandwidth::default_for_protocol_version(PROTOCOL_VERSION),
+        ProtocolFeature::ExecutionProofs.enabled(PROTOCOL_VERSION).then(CryptoHash::default),
         &validator_signer,
         &rs,
         PROTOCOL_VERSION,
@@ -475,8 +874,9 @@ fn test_verify_chunk_invalid_state_challenge() {
         //     ],
         // );
     }
-    let challenge =
-        Challenge::produce(ChallengeBody::ChunkState(challenge_body), &validator_signer);
+    let challenge = client
+        .produce_challenge(ChallengeBody::ChunkState(challenge_body), &validator_signer)
+        .unwrap();
     // Invalidate chunk state challenges because they are not supported yet.
     // TODO (#2445): Enable challenges when they are working correctly.
     assert_matches!(
@@ -14,6 +14,7 @@ use near_o11y::testonly::init_test_logger;
 use near_parameters::RuntimeConfigStore;
 use near_primitives::account::AccessKey;
 use near_primitives::errors::InvalidTxError;
+use near_primitives::network::PeerId;
 use near_primitives::shard_layout::ShardLayout;
 use near_primitives::sharding::ChunkHash;
 use near_primitives::transaction::SignedTransaction;
@@ -655,9 +656,10 @@ impl ChunkForwardingOptimizationTestData {
                 self.num_part_ords_sent_as_partial_encoded_chunk +=
                     partial_encoded_chunk.parts.len();
                 self.env.shards_manager(&account_id).send(
-                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(
-                        partial_encoded_chunk.into(),
-                    ),
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
+                        partial_encoded_chunk: partial_encoded_chunk.into(),
+                        peer_id: PeerId::random(),
+                    },
                 );
                 None
             }
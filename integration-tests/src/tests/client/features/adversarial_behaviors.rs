@@ -17,6 +17,7 @@ use near_network::{
 };
 use near_o11y::testonly::init_test_logger;
 use near_primitives::{
+    network::PeerId,
     shard_layout::ShardLayout,
     types::{AccountId, EpochId, ShardId},
 };
@@ -87,9 +88,10 @@ impl AdversarialBehaviorTestData {
             }
             NetworkRequests::PartialEncodedChunkMessage { account_id, partial_encoded_chunk } => {
                 self.env.shards_manager(&account_id).send(
-                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(
-                        partial_encoded_chunk.into(),
-                    ),
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk {
+                        partial_encoded_chunk: partial_encoded_chunk.into(),
+                        peer_id: PeerId::random(),
+                    },
                 );
                 None
             }
@@ -1,5 +1,7 @@
 mod benchmarks;
 mod block_corruption;
+// Challenges are processed since `ProtocolFeature::ChallengeTargetEpoch`.
+#[cfg(feature = "nightly")]
 mod challenges;
 mod chunks_management;
 mod cold_storage;
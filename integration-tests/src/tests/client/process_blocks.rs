@@ -962,6 +962,7 @@ fn client_sync_headers() {
                     connection_established_time: near_async::time::Instant::now(),
                     peer_type: PeerType::Outbound,
                     nonce: 1,
+                    reputation: 0,
                 }],
                 num_connected_peers: 1,
                 peer_max_count: 1,
@@ -977,6 +978,7 @@ fn client_sync_headers() {
                 received_bytes_per_sec: 0,
                 known_producers: vec![],
                 tier1_connections: vec![],
+                reachability: Default::default(),
                 tier1_accounts_keys: vec![],
                 tier1_accounts_data: vec![],
             })
@@ -1142,6 +1144,9 @@ fn test_bad_orphan() {
                 ShardChunkHeaderInner::V2(inner) => inner.prev_outcome_root = CryptoHash([1; 32]),
                 ShardChunkHeaderInner::V3(inner) => inner.prev_outcome_root = CryptoHash([1; 32]),
                 ShardChunkHeaderInner::V4(inner) => inner.prev_outcome_root = CryptoHash([1; 32]),
+                ShardChunkHeaderInner::V5(inner) => inner.prev_outcome_root = CryptoHash([1; 32]),
+                ShardChunkHeaderInner::V6(inner) => inner.prev_outcome_root = CryptoHash([1; 32]),
+                ShardChunkHeaderInner::V7(inner) => inner.prev_outcome_root = CryptoHash([1; 32]),
             }
             chunk.hash = ShardChunkHeaderV3::compute_hash(&chunk.inner);
         }
@@ -2383,7 +2388,7 @@ fn test_validate_chunk_extra() {
         client.epoch_manager.as_ref(),
         block1.hash(),
         &chunk_extra,
-        block1.chunks()[0].height_included(),
+        &block1.chunks()[0],
         &chunk_header,
     )
     .is_ok());
@@ -2784,7 +2789,9 @@ fn test_execution_metadata() {
         near_vm_runner::logic::ContractPrepareVersion::V1 => 2,
         // We spend two wasm instructions (call & drop), plus 8 ops for initializing function
         // operand stack (8 bytes worth to hold the return value.)
-        near_vm_runner::logic::ContractPrepareVersion::V2 => 10,
+        near_vm_runner::logic::ContractPrepareVersion::V2
+        | near_vm_runner::logic::ContractPrepareVersion::V3
+        | near_vm_runner::logic::ContractPrepareVersion::V4 => 10,
     };
 
     // Profile for what's happening *inside* wasm vm during function call.
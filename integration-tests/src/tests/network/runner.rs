@@ -139,6 +139,7 @@ fn setup_network_node(
         validator_signer.clone(),
         runtime.store().clone(),
         client_config.chunk_request_retry_period,
+        client_config.chunk_part_distribution.clone(),
     );
     let (partial_witness_actor, _) = spawn_actix_actor(PartialWitnessActor::new(
         Clock::real(),
@@ -205,6 +205,8 @@ impl RuntimeUser {
             migration_flags: MigrationFlags::default(),
             congestion_info,
             bandwidth_requests: BlockBandwidthRequests::empty(),
+            recent_block_headers: Default::default(),
+            execution_tracer: None,
         }
     }
 
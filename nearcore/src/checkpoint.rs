@@ -0,0 +1,53 @@
+use actix_rt::ArbiterHandle;
+use near_async::time::Duration;
+use near_chain::checkpoint::{get_checkpoint, save_checkpoint, validate_checkpoint};
+use near_crypto::{PublicKey, SecretKey};
+use near_store::Store;
+
+/// Checks the store against the latest chain checkpoint written by the node,
+/// if any, and reports the result.
+pub(crate) fn verify_chain_checkpoint(store: &Store, public_key: &PublicKey) -> anyhow::Result<()> {
+    let Some(checkpoint) = get_checkpoint(store)? else {
+        tracing::debug!(target: "checkpoint", "No chain checkpoint to verify");
+        return Ok(());
+    };
+    let height = checkpoint.checkpoint.final_head.height;
+    let problems = validate_checkpoint(store, &checkpoint, public_key)?;
+    if problems.is_empty() {
+        tracing::info!(target: "checkpoint", height, "Store is consistent with the chain checkpoint");
+    } else {
+        tracing::warn!(target: "checkpoint", height, ?problems, "Store is inconsistent with the chain checkpoint, consider running `neard database validate-checkpoint`");
+    }
+    Ok(())
+}
+
+/// Spawns a loop writing a chain checkpoint signed with `secret_key` every
+/// `period`.
+pub(crate) fn spawn_chain_checkpoint_loop(
+    store: Store,
+    secret_key: SecretKey,
+    period: Duration,
+) -> ArbiterHandle {
+    tracing::debug!(target: "checkpoint", ?period, "Spawning the chain checkpoint loop");
+    let arbiter = actix_rt::Arbiter::new();
+
+    let start = tokio::time::Instant::now() + period.unsigned_abs();
+    let mut interval = actix_rt::time::interval_at(start, period.unsigned_abs());
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    arbiter.spawn(async move {
+        loop {
+            interval.tick().await;
+            match save_checkpoint(&store, &secret_key) {
+                Ok(checkpoint) => {
+                    tracing::debug!(target: "checkpoint", height = checkpoint.final_head.height, "Saved chain checkpoint");
+                }
+                Err(err) => {
+                    tracing::error!(target: "checkpoint", ?err, "Failed to save chain checkpoint");
+                }
+            }
+        }
+    });
+
+    arbiter.handle()
+}
@@ -9,25 +9,28 @@ use near_chain_configs::test_utils::{
     TESTING_INIT_STAKE,
 };
 use near_chain_configs::{
-    default_enable_multiline_logging, default_epoch_sync,
+    default_apply_chunks_threads, default_enable_multiline_logging, default_epoch_sync,
     default_header_sync_expected_height_per_second, default_header_sync_initial_timeout,
-    default_header_sync_progress_timeout, default_header_sync_stall_ban_timeout,
-    default_log_summary_period, default_orphan_state_witness_max_size,
-    default_orphan_state_witness_pool_size, default_produce_chunk_add_transactions_time_limit,
-    default_state_sync_enabled, default_state_sync_external_backoff,
-    default_state_sync_external_timeout, default_state_sync_p2p_timeout,
-    default_state_sync_retry_backoff, default_sync_check_period, default_sync_height_threshold,
-    default_sync_max_block_requests, default_sync_step_period, default_transaction_pool_size_limit,
-    default_trie_viewer_state_size_limit, default_tx_routing_height_horizon,
-    default_view_client_threads, default_view_client_throttle_period, get_initial_supply,
-    ChunkDistributionNetworkConfig, ClientConfig, EpochSyncConfig, GCConfig, Genesis,
-    GenesisConfig, GenesisValidationMode, LogSummaryStyle, MutableConfigValue,
-    MutableValidatorSigner, ReshardingConfig, StateSyncConfig, BLOCK_PRODUCER_KICKOUT_THRESHOLD,
-    CHUNK_PRODUCER_KICKOUT_THRESHOLD, CHUNK_VALIDATOR_ONLY_KICKOUT_THRESHOLD,
-    EXPECTED_EPOCH_LENGTH, FAST_EPOCH_LENGTH, FISHERMEN_THRESHOLD, GAS_PRICE_ADJUSTMENT_RATE,
-    GENESIS_CONFIG_FILENAME, INITIAL_GAS_LIMIT, MAX_INFLATION_RATE, MIN_BLOCK_PRODUCTION_DELAY,
-    MIN_GAS_PRICE, NEAR_BASE, NUM_BLOCKS_PER_YEAR, NUM_BLOCK_PRODUCER_SEATS, PROTOCOL_REWARD_RATE,
-    PROTOCOL_UPGRADE_STAKE_THRESHOLD, TRANSACTION_VALIDITY_PERIOD,
+    default_header_sync_max_prefetched_ranges, default_header_sync_progress_timeout,
+    default_header_sync_stall_ban_timeout, default_log_summary_period,
+    default_orphan_state_witness_max_size, default_orphan_state_witness_pool_size,
+    default_produce_chunk_add_transactions_time_limit, default_state_sync_enabled,
+    default_state_sync_external_backoff, default_state_sync_external_timeout,
+    default_state_sync_p2p_timeout, default_state_sync_retry_backoff, default_sync_check_period,
+    default_sync_height_threshold, default_sync_max_block_requests, default_sync_step_period,
+    default_transaction_pool_size_limit, default_trie_viewer_state_size_limit,
+    default_tx_routing_height_horizon, default_view_client_threads,
+    default_view_client_throttle_period, get_initial_supply, BlockProductionDelays,
+    BlockTimestampConfig, CatchupConfig, ChunkDistributionNetworkConfig,
+    ChunkPartDistributionConfig, ClientConfig, EpochSyncConfig, ExecutionArtifactsConfig, GCConfig,
+    Genesis, GenesisConfig, GenesisValidationMode, LogSummaryStyle, MutableConfigValue,
+    MutableValidatorSigner, ReshardingConfig, ShadowValidationConfig, StateSyncConfig,
+    BLOCK_PRODUCER_KICKOUT_THRESHOLD, CHUNK_PRODUCER_KICKOUT_THRESHOLD,
+    CHUNK_VALIDATOR_ONLY_KICKOUT_THRESHOLD, EXPECTED_EPOCH_LENGTH, FAST_EPOCH_LENGTH,
+    FISHERMEN_THRESHOLD, GAS_PRICE_ADJUSTMENT_RATE, GENESIS_CONFIG_FILENAME, INITIAL_GAS_LIMIT,
+    MAX_INFLATION_RATE, MIN_BLOCK_PRODUCTION_DELAY, MIN_GAS_PRICE, NEAR_BASE, NUM_BLOCKS_PER_YEAR,
+    NUM_BLOCK_PRODUCER_SEATS, PROTOCOL_REWARD_RATE, PROTOCOL_UPGRADE_STAKE_THRESHOLD,
+    TRANSACTION_VALIDITY_PERIOD,
 };
 use near_config_utils::{DownloadConfigType, ValidationError, ValidationErrors};
 use near_crypto::{InMemorySigner, KeyFile, KeyType, PublicKey, Signer};
@@ -37,6 +40,8 @@ use near_jsonrpc::RpcConfig;
 use near_network::config::NetworkConfig;
 use near_network::tcp;
 use near_o11y::log_config::LogConfig;
+use near_parameters::vm::LimitConfigOverride;
+use near_parameters::RuntimeConfigStore;
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::ShardLayout;
 use near_primitives::test_utils::create_test_signer;
@@ -85,6 +90,10 @@ pub const TESTNET_MAX_BLOCK_PRODUCTION_DELAY: i64 = 2_500;
 /// Maximum time until skipping the previous block is ms.
 pub const MAX_BLOCK_WAIT_DELAY: i64 = 6_000;
 
+/// Lowest minimum block production delay allowed on mainnet and testnet, in ms.
+/// Sub-second block times are only allowed on test and local networks.
+pub const PUBLIC_NETWORK_MIN_BLOCK_PRODUCTION_DELAY: i64 = 1_000;
+
 /// Horizon at which instead of fetching block, fetch full state.
 const BLOCK_FETCH_HORIZON: BlockHeightDelta = 50;
 
@@ -101,6 +110,10 @@ const CHUNK_REQUEST_RETRY_PERIOD: i64 = 400;
 pub const FAST_MIN_BLOCK_PRODUCTION_DELAY: i64 = 120;
 pub const FAST_MAX_BLOCK_PRODUCTION_DELAY: i64 = 500;
 
+/// Block production delays of the chains created by `create_localnet_configs`.
+const LOCALNET_MIN_BLOCK_PRODUCTION_DELAY: i64 = 600;
+const LOCALNET_MAX_BLOCK_PRODUCTION_DELAY: i64 = 2_000;
+
 /// The minimum stake required for staking is last seat price divided by this number.
 pub const MINIMUM_STAKE_DIVISOR: u64 = 10;
 
@@ -131,6 +144,10 @@ pub struct Consensus {
     /// Maximum duration before skipping given height.
     #[serde(with = "near_async::time::serde_duration_as_std")]
     pub max_block_wait_delay: Duration,
+    /// How much longer to wait for approvals at each height skipped since the
+    /// last block. Defaults to a tenth of `max_block_production_delay`.
+    #[serde(default, with = "near_async::time::serde_opt_duration_as_std")]
+    pub doomslug_delay_step: Option<Duration>,
     /// Produce empty blocks, use `false` for testing.
     pub produce_empty_blocks: bool,
     /// Horizon at which instead of fetching block, fetch full state.
@@ -171,6 +188,9 @@ pub struct Consensus {
     /// Expected increase of header head weight per second during header sync
     #[serde(default = "default_header_sync_expected_height_per_second")]
     pub header_sync_expected_height_per_second: u64,
+    /// Maximum number of ranges of headers requested in parallel during header sync
+    #[serde(default = "default_header_sync_max_prefetched_ranges")]
+    pub header_sync_max_prefetched_ranges: u64,
     /// How frequently we check whether we need to sync
     #[serde(default = "default_sync_check_period")]
     #[serde(with = "near_async::time::serde_duration_as_std")]
@@ -190,6 +210,12 @@ pub struct Consensus {
     pub sync_max_block_requests: usize,
 }
 
+impl Consensus {
+    pub fn doomslug_delay_step(&self) -> Duration {
+        self.doomslug_delay_step.unwrap_or(self.max_block_production_delay / 10)
+    }
+}
+
 impl Default for Consensus {
     fn default() -> Self {
         Consensus {
@@ -200,6 +226,7 @@ impl Default for Consensus {
             min_block_production_delay: Duration::milliseconds(MIN_BLOCK_PRODUCTION_DELAY),
             max_block_production_delay: Duration::milliseconds(MAX_BLOCK_PRODUCTION_DELAY),
             max_block_wait_delay: Duration::milliseconds(MAX_BLOCK_WAIT_DELAY),
+            doomslug_delay_step: None,
             produce_empty_blocks: true,
             block_fetch_horizon: BLOCK_FETCH_HORIZON,
             block_header_fetch_horizon: BLOCK_HEADER_FETCH_HORIZON,
@@ -214,6 +241,7 @@ impl Default for Consensus {
             state_sync_external_backoff: default_state_sync_external_backoff(),
             header_sync_expected_height_per_second: default_header_sync_expected_height_per_second(
             ),
+            header_sync_max_prefetched_ranges: default_header_sync_max_prefetched_ranges(),
             sync_check_period: default_sync_check_period(),
             sync_step_period: default_sync_step_period(),
             doomslug_step_period: default_doomslug_step_period(),
@@ -263,6 +291,24 @@ pub struct Config {
     #[serde(flatten)]
     pub gc: GCConfig,
     pub view_client_threads: usize,
+    /// Maximum number of shards of a block applied in parallel.
+    pub apply_chunks_threads: usize,
+    /// Limits of the resources used by catchup.
+    pub catchup: CatchupConfig,
+    /// Validation of the timestamps of received blocks.
+    pub block_timestamp: BlockTimestampConfig,
+    /// Validation of the chunks of every processed block.
+    pub shadow_validation: ShadowValidationConfig,
+    /// Distribution of chunk parts between validators.
+    pub chunk_part_distribution: ChunkPartDistributionConfig,
+    /// Export of the execution of every applied block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_artifacts: Option<ExecutionArtifactsConfig>,
+    /// Whether to produce blocks on top of a block still being applied.
+    pub produce_optimistic_blocks: bool,
+    /// Whether to read the state used by the transactions of a received chunk
+    /// before applying it. Disabled by default.
+    pub prefetch_chunk_state: bool,
     #[serde(with = "near_async::time::serde_duration_as_std")]
     pub view_client_throttle_period: Duration,
     pub trie_viewer_state_size_limit: Option<u64>,
@@ -339,6 +385,23 @@ pub struct Config {
     /// which can cause extra load on the database. This option is not recommended for production use,
     /// as a large number of incoming witnesses could cause denial of service.
     pub save_latest_witnesses: bool,
+    /// Overrides the limits of the contract sandbox, such as the stack height
+    /// and the number of memory pages, for all protocol versions.
+    ///
+    /// The limits change the outcome of contract execution, so they must only
+    /// be overridden on sandbox and test networks where all nodes use the same
+    /// values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_limits_override: Option<LimitConfigOverride>,
+    /// If set, the node writes a checkpoint of the chain signed with its node
+    /// key this often. The latest checkpoint is verified against the store on
+    /// startup, see `neard database validate-checkpoint`.
+    #[serde(
+        default,
+        with = "near_async::time::serde_opt_duration_as_std",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub chain_checkpoint_period: Option<Duration>,
 }
 
 fn is_false(value: &bool) -> bool {
@@ -368,6 +431,14 @@ impl Default for Config {
             log_summary_period: default_log_summary_period(),
             gc: GCConfig::default(),
             view_client_threads: default_view_client_threads(),
+            apply_chunks_threads: default_apply_chunks_threads(),
+            catchup: CatchupConfig::default(),
+            block_timestamp: BlockTimestampConfig::default(),
+            shadow_validation: ShadowValidationConfig::default(),
+            chunk_part_distribution: ChunkPartDistributionConfig::default(),
+            execution_artifacts: None,
+            produce_optimistic_blocks: false,
+            prefetch_chunk_state: false,
             view_client_throttle_period: default_view_client_throttle_period(),
             trie_viewer_state_size_limit: default_trie_viewer_state_size_limit(),
             max_gas_burnt_view: None,
@@ -390,6 +461,8 @@ impl Default for Config {
             orphan_state_witness_max_size: default_orphan_state_witness_max_size(),
             max_loaded_contracts: 256,
             save_latest_witnesses: false,
+            vm_limits_override: None,
+            chain_checkpoint_period: None,
         }
     }
 }
@@ -513,6 +586,7 @@ impl NearConfig {
                 min_block_production_delay: config.consensus.min_block_production_delay,
                 max_block_production_delay: config.consensus.max_block_production_delay,
                 max_block_wait_delay: config.consensus.max_block_wait_delay,
+                doomslug_delay_step: config.consensus.doomslug_delay_step(),
                 skip_sync_wait: config.network.skip_sync_wait,
                 sync_check_period: config.consensus.sync_check_period,
                 sync_step_period: config.consensus.sync_step_period,
@@ -524,6 +598,9 @@ impl NearConfig {
                 header_sync_expected_height_per_second: config
                     .consensus
                     .header_sync_expected_height_per_second,
+                header_sync_max_prefetched_ranges: config
+                    .consensus
+                    .header_sync_max_prefetched_ranges,
                 state_sync_external_timeout: config.consensus.state_sync_external_timeout,
                 state_sync_p2p_timeout: config.consensus.state_sync_p2p_timeout,
                 state_sync_retry_backoff: config.consensus.state_sync_retry_backoff,
@@ -554,6 +631,14 @@ impl NearConfig {
                 max_gas_burnt_view: config.max_gas_burnt_view,
                 enable_statistics_export: config.store.enable_statistics_export,
                 client_background_migration_threads: 8,
+                apply_chunks_threads: config.apply_chunks_threads,
+                catchup: config.catchup,
+                block_timestamp: config.block_timestamp,
+                shadow_validation: config.shadow_validation,
+                chunk_part_distribution: config.chunk_part_distribution,
+                execution_artifacts: config.execution_artifacts,
+                produce_optimistic_blocks: config.produce_optimistic_blocks,
+                prefetch_chunk_state: config.prefetch_chunk_state,
                 state_sync_enabled: config.state_sync_enabled,
                 state_sync: config.state_sync.unwrap_or_default(),
                 epoch_sync: config.epoch_sync.unwrap_or_default(),
@@ -664,7 +749,11 @@ impl NightshadeRuntime {
             epoch_manager,
             config.client_config.trie_viewer_state_size_limit,
             config.client_config.max_gas_burnt_view,
-            None,
+            config.config.vm_limits_override.as_ref().map(|limits| {
+                warn!(target: "neard", ?limits, "Overriding the limits of the contract runtime");
+                RuntimeConfigStore::for_chain_id(&config.genesis.config.chain_id)
+                    .with_limit_config_override(limits)
+            }),
             config.config.gc.gc_num_epochs_to_keep(),
             TrieConfig::from_store_config(&config.config.store),
             state_snapshot_config,
@@ -767,6 +856,48 @@ fn set_block_production_delay(chain_id: &str, fast: bool, config: &mut Config) {
     }
 }
 
+/// Applies the block production delays of the chain to the config: the
+/// schedule set in the genesis of a test or local network replaces the
+/// configured one, while on mainnet and testnet sub-second block times are
+/// raised to `PUBLIC_NETWORK_MIN_BLOCK_PRODUCTION_DELAY`.
+fn apply_chain_block_production_delays(config: &mut Config, genesis_config: &GenesisConfig) {
+    let chain_id = genesis_config.chain_id.as_str();
+    if chain_id == near_primitives::chains::MAINNET || chain_id == near_primitives::chains::TESTNET
+    {
+        let min_delay = Duration::milliseconds(PUBLIC_NETWORK_MIN_BLOCK_PRODUCTION_DELAY);
+        if config.consensus.min_block_production_delay < min_delay {
+            warn!(
+                target: "neard",
+                configured = ?config.consensus.min_block_production_delay,
+                ?min_delay,
+                chain_id,
+                "min_block_production_delay is only allowed below {PUBLIC_NETWORK_MIN_BLOCK_PRODUCTION_DELAY}ms on test and local networks, raising it"
+            );
+            config.consensus.min_block_production_delay = min_delay;
+            config.consensus.max_block_production_delay =
+                config.consensus.max_block_production_delay.max(min_delay);
+            config.consensus.max_block_wait_delay =
+                config.consensus.max_block_wait_delay.max(min_delay);
+        }
+        return;
+    }
+    let Some(delays) = genesis_config.block_production_delays else {
+        return;
+    };
+    let to_duration = |ms: u64| Duration::milliseconds(ms as i64);
+    config.consensus.min_block_production_delay = to_duration(delays.min_block_production_delay_ms);
+    config.consensus.max_block_production_delay = to_duration(delays.max_block_production_delay_ms);
+    config.consensus.max_block_wait_delay = to_duration(delays.max_block_wait_delay_ms);
+    config.consensus.doomslug_delay_step = delays.doomslug_delay_step_ms.map(to_duration);
+    // The doomslug timer has to fire at least once per block.
+    config.consensus.doomslug_step_period =
+        config.consensus.doomslug_step_period.min(config.consensus.min_block_production_delay);
+    config.consensus.block_production_tracking_delay = config
+        .consensus
+        .block_production_tracking_delay
+        .min(config.consensus.min_block_production_delay);
+}
+
 /// Initializes Genesis, client Config, node and validator keys, and stores in the specified folder.
 ///
 /// This method supports the following use cases:
@@ -976,6 +1107,12 @@ pub fn init_configs(
                 fishermen_threshold: FISHERMEN_THRESHOLD,
                 shard_layout: shards,
                 min_gas_price: MIN_GAS_PRICE,
+                block_production_delays: fast.then(|| BlockProductionDelays {
+                    min_block_production_delay_ms: FAST_MIN_BLOCK_PRODUCTION_DELAY as u64,
+                    max_block_production_delay_ms: FAST_MAX_BLOCK_PRODUCTION_DELAY as u64,
+                    max_block_wait_delay_ms: MAX_BLOCK_WAIT_DELAY as u64,
+                    doomslug_delay_step_ms: None,
+                }),
                 ..Default::default()
             };
             let genesis = Genesis::new(genesis_config, records.into())?;
@@ -1054,13 +1191,19 @@ pub fn create_localnet_configs_from_seeds(
     let accounts_to_add_to_genesis: Vec<AccountId> =
         seeds.iter().map(|s| s.parse().unwrap()).collect();
 
-    let genesis = Genesis::test_with_seeds(
+    let mut genesis = Genesis::test_with_seeds(
         Clock::real(),
         accounts_to_add_to_genesis,
         num_validators,
         get_num_seats_per_shard(num_shards, num_validators),
         shard_layout,
     );
+    genesis.config.block_production_delays = Some(BlockProductionDelays {
+        min_block_production_delay_ms: LOCALNET_MIN_BLOCK_PRODUCTION_DELAY as u64,
+        max_block_production_delay_ms: LOCALNET_MAX_BLOCK_PRODUCTION_DELAY as u64,
+        max_block_wait_delay_ms: MAX_BLOCK_WAIT_DELAY as u64,
+        doomslug_delay_step_ms: None,
+    });
     let mut configs = vec![];
 
     // We assign the seeds to the nodes in the following order:
@@ -1134,8 +1277,10 @@ fn create_localnet_config(
     let mut config = Config::default();
 
     // Configure consensus protocol.
-    config.consensus.min_block_production_delay = Duration::milliseconds(600);
-    config.consensus.max_block_production_delay = Duration::milliseconds(2000);
+    config.consensus.min_block_production_delay =
+        Duration::milliseconds(LOCALNET_MIN_BLOCK_PRODUCTION_DELAY);
+    config.consensus.max_block_production_delay =
+        Duration::milliseconds(LOCALNET_MAX_BLOCK_PRODUCTION_DELAY);
     config.consensus.min_num_peers =
         std::cmp::min(num_validators as usize - 1, config.consensus.min_num_peers);
 
@@ -1382,7 +1527,7 @@ pub fn load_config(
     let mut validation_errors = ValidationErrors::new();
 
     // if config.json has file issues, the program will directly panic
-    let config = Config::from_file_skip_validation(&dir.join(CONFIG_FILENAME))?;
+    let mut config = Config::from_file_skip_validation(&dir.join(CONFIG_FILENAME))?;
     // do config.json validation later so that genesis_file, validator_file and genesis_file can be validated before program panic
     if let Err(e) = config.validate() {
         validation_errors.push_errors(e)
@@ -1426,6 +1571,12 @@ pub fn load_config(
             if let Err(e) = genesis.validate(genesis_validation) {
                 validation_errors.push_errors(e)
             };
+            apply_chain_block_production_delays(&mut config, &genesis.config);
+            if let Err(e) =
+                crate::config_validate::validate_config_for_chain(&config, &genesis.config.chain_id)
+            {
+                validation_errors.push_errors(e)
+            };
             Some(genesis)
         }
         Err(error) => {
@@ -1633,6 +1784,7 @@ mod tests {
                     gc_fork_clean_step: 420,
                     gc_num_epochs_to_keep: 24,
                     gc_step_period: Duration::seconds(1),
+                    ..GCConfig::default()
                 }
             } else {
                 GCConfig {
@@ -1640,6 +1792,7 @@ mod tests {
                     gc_fork_clean_step: 100,
                     gc_num_epochs_to_keep: 5,
                     gc_step_period: Duration::seconds(1),
+                    ..GCConfig::default()
                 }
             };
             assert_eq!(want_gc, config.gc);
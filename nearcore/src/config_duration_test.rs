@@ -65,6 +65,8 @@ fn test_config_duration_all_std() {
                     received_messages_rate_limits: Some(
                         near_network::MessagesLimitsOverrideConfig::default(),
                     ),
+                    sent_messages_shaping: Some(Default::default()),
+                    routed_message_compression: Some(Default::default()),
                 },
                 ..Default::default()
             },
@@ -1,10 +1,13 @@
-use near_chain_configs::{ExternalStorageLocation, SyncConfig};
+use near_chain_configs::{ExternalStorageLocation, GCRetention, SyncConfig};
 use near_config_utils::{ValidationError, ValidationErrors};
 use std::collections::HashSet;
 use std::path::Path;
 
 use crate::config::Config;
 
+/// Largest `block_timestamp.max_clock_skew` accepted.
+const MAX_BLOCK_CLOCK_SKEW: near_async::time::Duration = near_async::time::Duration::minutes(10);
+
 /// Validate Config extracted from config.json.
 /// This function does not panic. It returns the error if any validation fails.
 pub fn validate_config(config: &Config) -> Result<(), ValidationError> {
@@ -14,6 +17,14 @@ pub fn validate_config(config: &Config) -> Result<(), ValidationError> {
     config_validator.validate()
 }
 
+/// Validate the parts of Config which depend on the chain the node runs.
+pub fn validate_config_for_chain(config: &Config, chain_id: &str) -> Result<(), ValidationError> {
+    let mut validation_errors = ValidationErrors::new();
+    let mut config_validator = ConfigValidator::new(config, &mut validation_errors);
+    config_validator.validate_chain_conditions(chain_id);
+    config_validator.result_with_full_error()
+}
+
 struct ConfigValidator<'a> {
     config: &'a Config,
     validation_errors: &'a mut ValidationErrors,
@@ -36,6 +47,11 @@ impl<'a> ConfigValidator<'a> {
             self.validation_errors.push_config_semantics_error(error_message);
         }
 
+        if self.config.archive && self.config.gc.headers_retention != GCRetention::Forever {
+            let error_message = format!("Configuration with archive = true and headers_retention = {:?} is not supported because archival nodes must keep all block headers.", self.config.gc.headers_retention);
+            self.validation_errors.push_config_semantics_error(error_message);
+        }
+
         // Checking that if cold storage is configured, trie changes are definitely saved.
         // Unlike in the previous case, None is not a valid option here.
         if self.config.cold_store.is_some() && self.config.save_trie_changes != Some(true) {
@@ -65,6 +81,68 @@ impl<'a> ConfigValidator<'a> {
             self.validation_errors.push_config_semantics_error(error_message);
         }
 
+        if self.config.consensus.max_block_production_delay
+            > self.config.consensus.max_block_wait_delay
+        {
+            let error_message = format!(
+                "max_block_production_delay: {:?} is greater than max_block_wait_delay: {:?}",
+                self.config.consensus.max_block_production_delay,
+                self.config.consensus.max_block_wait_delay
+            );
+            self.validation_errors.push_config_semantics_error(error_message);
+        }
+
+        if !self.config.consensus.doomslug_delay_step().is_positive() {
+            let error_message = format!(
+                "doomslug_delay_step: {:?} should be greater than 0",
+                self.config.consensus.doomslug_delay_step()
+            );
+            self.validation_errors.push_config_semantics_error(error_message);
+        }
+
+        // The timers driving block production must fire at least once per
+        // block, otherwise blocks are produced later than configured.
+        for (name, period) in [
+            (
+                "block_production_tracking_delay",
+                self.config.consensus.block_production_tracking_delay,
+            ),
+            ("doomslug_step_period", self.config.consensus.doomslug_step_period),
+        ] {
+            if !period.is_positive() || period > self.config.consensus.min_block_production_delay {
+                let error_message = format!(
+                    "{name}: {period:?} should be greater than 0 and at most min_block_production_delay: {:?}",
+                    self.config.consensus.min_block_production_delay
+                );
+                self.validation_errors.push_config_semantics_error(error_message);
+            }
+        }
+
+        // Receivers of pushed parts only request the missing ones once the pushes are over,
+        // which must happen before the block is produced without the chunk.
+        let chunk_part_distribution = &self.config.chunk_part_distribution;
+        if chunk_part_distribution.is_push()
+            && chunk_part_distribution.push_duration()
+                > self.config.consensus.max_block_production_delay
+        {
+            let error_message = format!(
+                "chunk_part_distribution: retransmit_period * (max_retransmits + 1) = {:?} should be at most max_block_production_delay: {:?}",
+                chunk_part_distribution.push_duration(),
+                self.config.consensus.max_block_production_delay
+            );
+            self.validation_errors.push_config_semantics_error(error_message);
+        }
+
+        // A large skew lets block producers with a clock ahead pick timestamps
+        // far in the future, which then skew the block times of the chain.
+        let max_clock_skew = self.config.block_timestamp.max_clock_skew;
+        if !max_clock_skew.is_positive() || max_clock_skew > MAX_BLOCK_CLOCK_SKEW {
+            let error_message = format!(
+                "block_timestamp.max_clock_skew: {max_clock_skew:?} should be greater than 0 and at most {MAX_BLOCK_CLOCK_SKEW:?}"
+            );
+            self.validation_errors.push_config_semantics_error(error_message);
+        }
+
         if self.config.consensus.header_sync_expected_height_per_second == 0 {
             let error_message =
                 "consensus.header_sync_expected_height_per_second should not be 0".to_string();
@@ -159,6 +237,15 @@ impl<'a> ConfigValidator<'a> {
         }
     }
 
+    fn validate_chain_conditions(&mut self, chain_id: &str) {
+        let is_public_network = chain_id == near_primitives::chains::MAINNET
+            || chain_id == near_primitives::chains::TESTNET;
+        if is_public_network && self.config.vm_limits_override.is_some() {
+            let error_message = format!("vm_limits_override changes the outcome of contract execution, so it is only allowed on sandbox and test networks, not on {chain_id}.");
+            self.validation_errors.push_config_semantics_error(error_message);
+        }
+    }
+
     fn result_with_full_error(&self) -> Result<(), ValidationError> {
         if self.validation_errors.is_empty() {
             Ok(())
@@ -171,6 +258,8 @@ impl<'a> ConfigValidator<'a> {
 
 #[cfg(test)]
 mod tests {
+    use near_async::time::Duration;
+    use near_chain_configs::ChunkPartDistributionMode;
     use near_primitives::types::ShardId;
 
     use super::*;
@@ -198,6 +287,19 @@ mod tests {
         validate_config(&config).unwrap();
     }
 
+    #[test]
+    #[should_panic(
+        expected = "Configuration with archive = true and headers_retention = WithBlocks is not supported"
+    )]
+    fn test_archive_true_headers_retention() {
+        let mut config = Config::default();
+        config.archive = true;
+        config.gc.headers_retention = GCRetention::WithBlocks;
+        // set tracked_shards to be non-empty
+        config.tracked_shards.push(ShardId::new(20));
+        validate_config(&config).unwrap();
+    }
+
     #[test]
     #[should_panic(
         expected = "\\nconfig.json semantic issue: Configuration with archive = false and save_trie_changes = false is not supported because non-archival nodes must save trie changes in order to do garbage collection.\\nconfig.json semantic issue: gc config values should all be greater than 0"
@@ -252,4 +354,63 @@ mod tests {
         config.tx_routing_height_horizon = 1_000_000_000;
         validate_config(&config).unwrap();
     }
+
+    #[test]
+    #[should_panic(expected = "doomslug_step_period: ")]
+    fn test_doomslug_step_period_longer_than_block_time() {
+        let mut config = Config::default();
+        config.consensus.min_block_production_delay = Duration::milliseconds(150);
+        config.consensus.doomslug_step_period = Duration::milliseconds(200);
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "doomslug_delay_step: ")]
+    fn test_doomslug_delay_step_zero() {
+        let mut config = Config::default();
+        config.consensus.doomslug_delay_step = Some(Duration::ZERO);
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "block_timestamp.max_clock_skew: ")]
+    fn test_max_clock_skew_too_large() {
+        let mut config = Config::default();
+        config.block_timestamp.max_clock_skew = Duration::hours(1);
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk_part_distribution: ")]
+    fn test_chunk_part_push_longer_than_block_time() {
+        let mut config = Config::default();
+        config.chunk_part_distribution.mode = ChunkPartDistributionMode::Push;
+        config.chunk_part_distribution.retransmit_period = Duration::seconds(1);
+        config.chunk_part_distribution.max_retransmits = 3;
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    fn test_sub_second_block_time() {
+        let mut config = Config::default();
+        config.consensus.min_block_production_delay = Duration::milliseconds(300);
+        config.consensus.max_block_production_delay = Duration::milliseconds(600);
+        config.consensus.max_block_wait_delay = Duration::milliseconds(1800);
+        config.consensus.block_production_tracking_delay = Duration::milliseconds(50);
+        config.consensus.doomslug_step_period = Duration::milliseconds(50);
+        validate_config(&config).unwrap();
+        validate_config_for_chain(&config, "localnet").unwrap();
+    }
+
+    #[test]
+    fn test_vm_limits_override_on_public_network() {
+        let mut config = Config::default();
+        config.vm_limits_override = Some(near_parameters::vm::LimitConfigOverride {
+            max_stack_height: Some(1),
+            ..Default::default()
+        });
+        validate_config_for_chain(&config, "localnet").unwrap();
+        validate_config_for_chain(&config, near_primitives::chains::MAINNET).unwrap_err();
+        validate_config_for_chain(&config, near_primitives::chains::TESTNET).unwrap_err();
+    }
 }
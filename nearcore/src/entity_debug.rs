@@ -645,7 +645,12 @@ impl PartialStateParser {
     /// Takes the flattened partial trie nodes and turn them into a hierarchical view,
     /// automatically finding the root. Only used for debugging.
     pub fn parse_and_serialize_partial_state(partial_state: PartialState) -> EntityDataValue {
-        let PartialState::TrieValues(nodes) = partial_state;
+        // Only trie values can be turned into a hierarchical view; state items of
+        // a state part range don't carry any trie structure.
+        let nodes = match partial_state {
+            PartialState::TrieValues(nodes) => nodes,
+            PartialState::StatePartRange(range) => range.boundary_values,
+        };
         let parser = Self::new(&nodes);
         let root = parser.find_root();
         match root {
@@ -1,3 +1,4 @@
+use crate::checkpoint::{spawn_chain_checkpoint_loop, verify_chain_checkpoint};
 pub use crate::config::NightshadeRuntimeExt;
 pub use crate::config::{init_configs, load_config, load_test_config, NearConfig};
 #[cfg(feature = "json_rpc")]
@@ -47,6 +48,7 @@ use std::sync::Arc;
 use tokio::sync::broadcast;
 
 pub mod append_only_map;
+mod checkpoint;
 pub mod cold_storage;
 pub mod config;
 #[cfg(test)]
@@ -271,6 +273,17 @@ pub fn start_with_config_and_synchronization(
         &genesis_epoch_config,
         Some(home_dir),
     );
+    verify_chain_checkpoint(
+        &storage.get_hot_store(),
+        &config.network_config.node_key.public_key(),
+    )?;
+    let chain_checkpoint_arbiter = config.config.chain_checkpoint_period.map(|period| {
+        spawn_chain_checkpoint_loop(
+            storage.get_hot_store(),
+            config.network_config.node_key.clone(),
+            period,
+        )
+    });
 
     let shard_tracker =
         ShardTracker::new(TrackedConfig::from_config(&config.client_config), epoch_manager.clone());
@@ -381,6 +394,7 @@ pub fn start_with_config_and_synchronization(
         epoch_manager.clone(),
         config.client_config.gc.clone(),
         config.client_config.archive,
+        config.genesis.config.transaction_validity_period,
     ));
 
     let (resharding_sender_addr, _) =
@@ -422,8 +436,9 @@ pub fn start_with_config_and_synchronization(
         config.validator_signer.clone(),
         split_store.unwrap_or_else(|| storage.get_hot_store()),
         config.client_config.chunk_request_retry_period,
+        config.client_config.chunk_part_distribution.clone(),
     );
-    shards_manager_adapter.bind(shards_manager_actor.with_auto_span_context());
+    shards_manager_adapter.bind(shards_manager_actor.clone().with_auto_span_context());
 
     let mut state_sync_dumper = StateSyncDumper {
         clock: Clock::real(),
@@ -455,7 +470,12 @@ pub fn start_with_config_and_synchronization(
     .context("PeerManager::spawn()")?;
     network_adapter.bind(network_actor.clone().with_auto_span_context());
     #[cfg(feature = "json_rpc")]
-    if let Some(rpc_config) = config.rpc_config {
+    if let Some(mut rpc_config) = config.rpc_config {
+        if let Some(eth_relayer) = &mut rpc_config.eth_relayer {
+            eth_relayer.key_file = home_dir.join(&eth_relayer.key_file);
+            eth_relayer.relayed_transactions_file =
+                home_dir.join(&eth_relayer.relayed_transactions_file);
+        }
         let entity_debug_handler = EntityDebugHandlerImpl {
             epoch_manager: view_epoch_manager,
             runtime: view_runtime,
@@ -467,9 +487,12 @@ pub fn start_with_config_and_synchronization(
             config.genesis.config.clone(),
             client_actor.clone().with_auto_span_context().into_multi_sender(),
             view_client_addr.clone().with_auto_span_context().into_multi_sender(),
-            network_actor.into_multi_sender(),
+            network_actor.clone().into_multi_sender(),
+            shards_manager_actor.with_auto_span_context().into_multi_sender(),
             #[cfg(feature = "test_features")]
             _gc_actor.with_auto_span_context().into_multi_sender(),
+            #[cfg(feature = "test_features")]
+            network_actor.into_multi_sender(),
             Arc::new(entity_debug_handler),
         ));
     }
@@ -503,6 +526,9 @@ pub fn start_with_config_and_synchronization(
     if let Some(db_metrics_arbiter) = db_metrics_arbiter {
         arbiters.push(db_metrics_arbiter);
     }
+    if let Some(chain_checkpoint_arbiter) = chain_checkpoint_arbiter {
+        arbiters.push(chain_checkpoint_arbiter);
+    }
 
     Ok(NearNode {
         client: client_actor,
@@ -89,6 +89,10 @@ impl<'a> near_store::StoreMigrator for Migrator<'a> {
             40 => near_store::migrations::migrate_40_to_41(store),
             41 => near_store::migrations::migrate_41_to_42(store),
             42 => near_store::migrations::migrate_42_to_43(store),
+            43 => near_store::migrations::migrate_43_to_44(store),
+            44 => near_store::migrations::migrate_44_to_45(store),
+            45 => near_store::migrations::migrate_45_to_46(store),
+            46 => near_store::migrations::migrate_46_to_47(store),
             DB_VERSION.. => unreachable!(),
         }
     }
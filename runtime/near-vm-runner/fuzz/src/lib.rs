@@ -46,6 +46,7 @@ pub fn create_context(input: Vec<u8>) -> VMContext {
         attached_deposit: 2u128,
         prepaid_gas: 10_u64.pow(14),
         random_seed: vec![0, 1, 2],
+        recent_block_headers: Default::default(),
         view_config: None,
         output_data_receivers: vec![],
     }
@@ -0,0 +1,107 @@
+;; Reference implementation of the ed25519 batch verification precompiled
+;; contract.
+;;
+;; The input of `batch_verify` is a sequence of records, each made of a 64 byte
+;; signature, the 32 byte public key, the length of the message as a 4 byte
+;; little endian integer and the message. It returns a single byte, 1 if all the
+;; signatures are valid and 0 otherwise. Malformed input traps.
+(module
+  (type (;0;) (func (param i64)))
+  (type (;1;) (func (param i64) (result i64)))
+  (type (;2;) (func (param i64 i64)))
+  (type (;3;) (func (param i64 i64 i64 i64 i64 i64) (result i64)))
+  (type (;4;) (func))
+  (import "env" "input" (func (;0;) (type 0)))
+  (import "env" "register_len" (func (;1;) (type 1)))
+  (import "env" "read_register" (func (;2;) (type 2)))
+  (import "env" "ed25519_verify" (func (;3;) (type 3)))
+  (import "env" "value_return" (func (;4;) (type 2)))
+  ;; Locals: input length, record offset, message length, all valid.
+  (func (;5;) (type 4) (local i64 i64 i64 i64)
+    i64.const 0
+    call 0
+    i64.const 0
+    call 1
+    local.set 0
+    ;; Grow the memory to fit the input and the two bytes after it.
+    local.get 0
+    i64.const 65537
+    i64.add
+    i64.const 16
+    i64.shr_u
+    i32.wrap_i64
+    memory.size
+    i32.sub
+    memory.grow
+    drop
+    i64.const 0
+    i64.const 0
+    call 2
+    i64.const 1
+    local.set 3
+    block
+      loop
+        local.get 1
+        local.get 0
+        i64.ge_u
+        br_if 1
+        ;; The signature, public key and message length must fit.
+        local.get 1
+        i64.const 100
+        i64.add
+        local.get 0
+        i64.gt_u
+        if
+          unreachable
+        end
+        local.get 1
+        i32.wrap_i64
+        i64.load32_u offset=96
+        local.set 2
+        ;; The message must fit.
+        local.get 1
+        i64.const 100
+        i64.add
+        local.get 2
+        i64.add
+        local.get 0
+        i64.gt_u
+        if
+          unreachable
+        end
+        i64.const 64
+        local.get 1
+        local.get 2
+        local.get 1
+        i64.const 100
+        i64.add
+        i64.const 32
+        local.get 1
+        i64.const 64
+        i64.add
+        call 3
+        local.get 3
+        i64.and
+        local.set 3
+        local.get 1
+        i64.const 100
+        i64.add
+        local.get 2
+        i64.add
+        local.set 1
+        br 0
+      end
+    end
+    ;; Return the byte at `input length + all valid` after storing 0 and 1
+    ;; right after the input.
+    local.get 0
+    i32.wrap_i64
+    i32.const 256
+    i32.store16
+    i64.const 1
+    local.get 0
+    local.get 3
+    i64.add
+    call 4)
+  (memory (;0;) 1)
+  (export "batch_verify" (func 5)))
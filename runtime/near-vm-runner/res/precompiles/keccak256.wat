@@ -0,0 +1,26 @@
+;; Reference implementation of the keccak256 precompiled contract.
+;;
+;; `keccak256` returns the keccak256 hash of its input.
+(module
+  (type (;0;) (func (param i64)))
+  (type (;1;) (func (param i64 i64 i64)))
+  (type (;2;) (func (param i64 i64)))
+  (type (;3;) (func))
+  (import "env" "input" (func (;0;) (type 0)))
+  (import "env" "keccak256" (func (;1;) (type 1)))
+  (import "env" "value_return" (func (;2;) (type 2)))
+  (func (;3;) (type 3)
+    ;; input(0)
+    i64.const 0
+    call 0
+    ;; keccak256(register 0, 1)
+    i64.const -1
+    i64.const 0
+    i64.const 1
+    call 1
+    ;; value_return(register 1)
+    i64.const -1
+    i64.const 1
+    call 2)
+  (memory (;0;) 1)
+  (export "keccak256" (func 3)))
@@ -23,6 +23,8 @@ use opts::*;
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) struct WasmFeatures {
     sign_extension: bool,
+    pub(crate) multi_memory: bool,
+    pub(crate) simd: bool,
 }
 
 impl From<crate::logic::ContractPrepareVersion> for WasmFeatures {
@@ -30,9 +32,16 @@ impl From<crate::logic::ContractPrepareVersion> for WasmFeatures {
         let sign_extension = match version {
             crate::logic::ContractPrepareVersion::V0 => false,
             crate::logic::ContractPrepareVersion::V1 => false,
-            crate::logic::ContractPrepareVersion::V2 => true,
+            crate::logic::ContractPrepareVersion::V2
+            | crate::logic::ContractPrepareVersion::V3
+            | crate::logic::ContractPrepareVersion::V4 => true,
         };
-        WasmFeatures { sign_extension }
+        let v3 = matches!(
+            version,
+            crate::logic::ContractPrepareVersion::V3 | crate::logic::ContractPrepareVersion::V4
+        );
+        let v4 = version == crate::logic::ContractPrepareVersion::V4;
+        WasmFeatures { sign_extension, multi_memory: v3, simd: v4 }
     }
 }
 
@@ -48,10 +57,10 @@ impl From<WasmFeatures> for finite_wasm::wasmparser::WasmFeatures {
             // wasmer singlepass compiler requires multi_value return values to be disabled.
             multi_value: MULTI_VALUE,
             bulk_memory: BULK_MEMORY,
-            simd: SIMD,
+            simd: f.simd,
             threads: THREADS,
             tail_call: TAIL_CALL,
-            multi_memory: MULTI_MEMORY,
+            multi_memory: f.multi_memory,
             exceptions: EXCEPTIONS,
             memory64: MEMORY64,
             saturating_float_to_int: SATURATING_FLOAT_TO_INT,
@@ -67,7 +76,7 @@ impl From<WasmFeatures> for finite_wasm::wasmparser::WasmFeatures {
 
 #[cfg(feature = "wasmparser")]
 impl From<WasmFeatures> for wasmparser::WasmFeatures {
-    fn from(_: WasmFeatures) -> Self {
+    fn from(f: WasmFeatures) -> Self {
         // /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\
         //
         // There are features that this version of wasmparser enables by default, but pwasm
@@ -88,10 +97,10 @@ impl From<WasmFeatures> for wasmparser::WasmFeatures {
             reference_types: REFERENCE_TYPES,
             multi_value: MULTI_VALUE,
             bulk_memory: BULK_MEMORY,
-            simd: SIMD,
+            simd: f.simd,
             threads: THREADS,
             tail_call: TAIL_CALL,
-            multi_memory: MULTI_MEMORY,
+            multi_memory: f.multi_memory,
             exceptions: EXCEPTIONS,
             memory64: MEMORY64,
         }
@@ -107,11 +116,11 @@ impl From<WasmFeatures> for near_vm_types::Features {
 
             threads: THREADS,
             reference_types: REFERENCE_TYPES,
-            simd: SIMD,
+            simd: f.simd,
             bulk_memory: BULK_MEMORY,
             multi_value: MULTI_VALUE,
             tail_call: TAIL_CALL,
-            multi_memory: MULTI_MEMORY,
+            multi_memory: f.multi_memory,
             memory64: MEMORY64,
             exceptions: EXCEPTIONS,
             saturating_float_to_int: SATURATING_FLOAT_TO_INT,
@@ -152,9 +161,13 @@ impl From<WasmFeatures> for wasmer_types::Features {
 
 #[cfg(feature = "wasmtime_vm")]
 impl From<WasmFeatures> for wasmtime::Config {
-    fn from(_: WasmFeatures) -> Self {
+    fn from(f: WasmFeatures) -> Self {
         // preparation code did all the filtering necessary already. Default configuration supports
         // all the necessary features (and, yes, enables more of them.)
-        wasmtime::Config::default()
+        let mut config = wasmtime::Config::default();
+        config.wasm_memory64(MEMORY64);
+        config.wasm_multi_memory(f.multi_memory);
+        config.wasm_simd(f.simd);
+        config
     }
 }
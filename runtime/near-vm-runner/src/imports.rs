@@ -108,6 +108,7 @@ imports! {
     block_index<[] -> [u64]>,
     block_timestamp<[] -> [u64]>,
     epoch_height<[] -> [u64]>,
+    #[block_header] block_header<[height: u64, register_id: u64] -> [u64]>,
     storage_usage<[] -> [u64]>,
     // #################
     // # Economics API #
@@ -121,6 +122,7 @@ imports! {
     // # Math API #
     // ############
     random_seed<[register_id: u64] -> []>,
+    #[random_seed_with_domain] random_seed_with_domain<[domain_len: u64, domain_ptr: u64, register_id: u64] -> []>,
     sha256<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     keccak256<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     keccak512<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
@@ -131,6 +133,13 @@ imports! {
         pub_key_len: u64,
         pub_key_ptr: u64
     ] -> [u64]>,
+    #[p256_verify] p256_verify<[sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64
+    ] -> [u64]>,
     #[math_extension] ripemd160<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     #[math_extension] ecrecover<[hash_len: u64, hash_ptr: u64, sign_len: u64, sig_ptr: u64, v: u64, malleability_flag: u64, register_id: u64] -> [u64]>,
     // #####################
@@ -239,6 +248,16 @@ imports! {
         gas_weight: u64,
         register_id: u64
     ] -> [u64]>,
+    #[yield_timeout_per_call] promise_yield_create_with_timeout<[
+        method_name_len: u64,
+        method_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: u64,
+        gas_weight: u64,
+        timeout_length_in_blocks: u64,
+        register_id: u64
+    ] -> [u64]>,
     #[yield_resume_host_functions] promise_yield_resume<[
         data_id_len: u64,
         data_id_ptr: u64,
@@ -261,6 +280,18 @@ imports! {
     storage_iter_prefix<[prefix_len: u64, prefix_ptr: u64] -> [u64]>,
     storage_iter_range<[start_len: u64, start_ptr: u64, end_len: u64, end_ptr: u64] -> [u64]>,
     storage_iter_next<[iterator_id: u64, key_register_id: u64, value_register_id: u64] -> [u64]>,
+    #[namespaced_storage] storage_ns_write<[
+        namespace_len: u64,
+        namespace_ptr: u64,
+        key_len: u64,
+        key_ptr: u64,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64
+    ] -> [u64]>,
+    #[namespaced_storage] storage_ns_read<[namespace_len: u64, namespace_ptr: u64, key_len: u64, key_ptr: u64, register_id: u64] -> [u64]>,
+    #[namespaced_storage] storage_ns_remove<[namespace_len: u64, namespace_ptr: u64, key_len: u64, key_ptr: u64, register_id: u64] -> [u64]>,
+    #[namespaced_storage] storage_ns_usage<[namespace_len: u64, namespace_ptr: u64] -> [u64]>,
     // Function for the injected gas counter. Automatically called by the gas meter.
     @as gas: gas_seen_from_wasm<[opcodes: u32] -> []>,
     // ###############
@@ -269,6 +300,19 @@ imports! {
     validator_stake<[account_id_len: u64, account_id_ptr: u64, stake_ptr: u64] -> []>,
     validator_total_stake<[stake_ptr: u64] -> []>,
     // #############
+    // # View call #
+    // #############
+    #[view_call] view_call<[
+        account_id_len: u64,
+        account_id_ptr: u64,
+        method_name_len: u64,
+        method_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: u64,
+        register_id: u64
+    ] -> [u64]>,
+    // #############
     // # Alt BN128 #
     // #############
     #[alt_bn128] alt_bn128_g1_multiexp<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
@@ -306,6 +350,51 @@ imports! {
 
 pub(crate) use {call_with_name, for_each_available_import};
 
+/// Signature of a host function, as listed in the `imports!` invocation above.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostFunctionSignature {
+    pub module: &'static str,
+    pub name: &'static str,
+    /// Wasm types of the parameters, `i32` or `i64`.
+    pub params: Vec<&'static str>,
+    /// Wasm types of the results, `i32` or `i64`.
+    pub results: Vec<&'static str>,
+}
+
+/// Lists the signatures of the host functions available to contracts with the
+/// given `config`.
+///
+/// Tools that generate contracts calling host functions, like the parameter
+/// estimator, use this to stay in sync with the host function interface.
+pub fn host_functions(config: &near_parameters::vm::Config) -> Vec<HostFunctionSignature> {
+    let mut functions = Vec::new();
+    macro_rules! add_import {
+        (
+          $mod:ident / $name:ident : $func:ident <
+            [ $( $arg_name:ident : $arg_type:ident ),* ]
+            -> [ $( $returns:ident ),* ]
+          >
+        ) => {
+            functions.push(HostFunctionSignature {
+                module: stringify!($mod),
+                name: stringify!($name),
+                params: vec![$(wasm_type(stringify!($arg_type))),*],
+                results: vec![$(wasm_type(stringify!($returns))),*],
+            });
+        };
+    }
+    for_each_available_import!(config, add_import);
+    functions
+}
+
+fn wasm_type(rust_type: &str) -> &'static str {
+    match rust_type {
+        "u32" | "i32" => "i32",
+        "u64" | "i64" => "i64",
+        _ => panic!("host function uses an unexpected type {rust_type}"),
+    }
+}
+
 pub(crate) const fn should_trace_host_function(host_function: &str) -> bool {
     match host_function {
         _ if str_eq(host_function, "gas") => false,
@@ -12,6 +12,7 @@ pub mod logic;
 mod metrics;
 #[cfg(all(feature = "near_vm", target_arch = "x86_64"))]
 mod near_vm_runner;
+mod precompiles;
 #[cfg(feature = "prepare")]
 pub mod prepare;
 mod profile;
@@ -42,6 +43,13 @@ pub use runner::{prepare, run, Contract, PreparedContract, VM};
 /// implementation detail of `near-vm-runner`.
 #[doc(hidden)]
 pub mod internal {
+    #[cfg(any(
+        feature = "wasmer0_vm",
+        feature = "wasmer2_vm",
+        feature = "near_vm",
+        feature = "wasmtime_vm"
+    ))]
+    pub use crate::imports::{host_functions, HostFunctionSignature};
     pub use crate::runner::VMKindExt;
     #[cfg(feature = "prepare")]
     pub use wasmparser;
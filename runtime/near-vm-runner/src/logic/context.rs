@@ -1,8 +1,24 @@
 use super::types::{PromiseResult, PublicKey};
 use near_primitives_core::config::ViewConfig;
+use near_primitives_core::hash::CryptoHash;
 use near_primitives_core::types::{
     AccountId, Balance, BlockHeight, EpochHeight, Gas, StorageUsage,
 };
+use std::sync::Arc;
+
+/// Number of blocks preceding the current one whose headers contracts can read
+/// with the `block_header` host function.
+pub const NUM_RECENT_BLOCK_HEADERS: usize = 32;
+
+/// Fields of a block header exposed to contracts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecentBlockHeader {
+    pub height: BlockHeight,
+    pub hash: CryptoHash,
+    /// Number of non-leap-nanoseconds since January 1, 1970 0:00:00 UTC.
+    pub timestamp: u64,
+    pub epoch_id: CryptoHash,
+}
 
 #[derive(Clone)]
 /// Context for the contract execution.
@@ -47,6 +63,9 @@ pub struct VMContext {
     pub prepaid_gas: Gas,
     /// Initial seed for randomness
     pub random_seed: Vec<u8>,
+    /// Headers of the blocks preceding the current one, most recent first.
+    /// Empty for view calls and before the `BlockHeaderHostFunction` protocol feature.
+    pub recent_block_headers: Arc<[RecentBlockHeader]>,
     /// If Some, it means that execution is made in a view mode and defines its configuration.
     /// View mode means that only read-only operations are allowed.
     /// See <https://nomicon.io/Proposals/0018-view-change-method.html> for more details.
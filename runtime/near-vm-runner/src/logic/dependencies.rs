@@ -1,10 +1,12 @@
 //! External dependencies of the near-vm-logic.
-use super::types::ReceiptIndex;
+use super::types::{ReceiptIndex, ViewCallOutcome};
 use super::VMLogicError;
 use near_crypto::PublicKey;
 use near_parameters::vm::StorageGetMode;
 use near_primitives_core::hash::CryptoHash;
-use near_primitives_core::types::{AccountId, Balance, Gas, GasWeight, Nonce};
+use near_primitives_core::types::{
+    AccountId, Balance, BlockHeightDelta, Gas, GasWeight, Nonce, StorageUsage,
+};
 use std::borrow::Cow;
 
 /// Representation of the address slice of guest memory.
@@ -255,6 +257,62 @@ pub trait External {
     /// ```
     fn storage_has_key(&mut self, key: &[u8], mode: StorageGetMode) -> Result<bool>;
 
+    /// Write `value` to the `key` of the storage `namespace` associated with the current
+    /// account.
+    ///
+    /// Records of a namespace live in a separate trie subtree from the records written with
+    /// [`External::storage_set`], so the same `key` may hold different values in each of them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use near_vm_runner::logic::mocks::mock_external::MockedExternal;
+    /// # use near_vm_runner::logic::{External, StorageGetMode};
+    ///
+    /// # let mut external = MockedExternal::new();
+    /// assert_eq!(external.storage_ns_set(b"ns", b"key42", b"value1337"), Ok(()));
+    /// assert!(!external.storage_has_key(b"key42", StorageGetMode::Trie).unwrap());
+    /// ```
+    fn storage_ns_set(&mut self, namespace: &[u8], key: &[u8], value: &[u8]) -> Result<()>;
+
+    /// Read `key` from the storage `namespace` associated with the current account.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - the namespace to read from
+    /// * `key` - the key to read
+    /// * `mode`- whether the lookup will be performed through flat storage or trie
+    fn storage_ns_get<'a>(
+        &'a self,
+        namespace: &[u8],
+        key: &[u8],
+        mode: StorageGetMode,
+    ) -> Result<Option<Box<dyn ValuePtr + 'a>>>;
+
+    /// Removes the `key` from the storage `namespace` associated with the current account.
+    ///
+    /// The operation will succeed even if the `key` does not exist.
+    fn storage_ns_remove(&mut self, namespace: &[u8], key: &[u8]) -> Result<()>;
+
+    /// Returns the number of bytes used by the records of the storage `namespace` of the
+    /// current account, or 0 if the namespace holds no records.
+    ///
+    /// # Example
+    /// ```
+    /// # use near_vm_runner::logic::mocks::mock_external::MockedExternal;
+    /// # use near_vm_runner::logic::External;
+    ///
+    /// # let mut external = MockedExternal::new();
+    /// external.set_storage_ns_usage(b"ns", 42).unwrap();
+    /// assert_eq!(external.storage_ns_usage(b"ns"), Ok(42));
+    /// assert_eq!(external.storage_ns_usage(b"other_ns"), Ok(0));
+    /// ```
+    fn storage_ns_usage(&self, namespace: &[u8]) -> Result<StorageUsage>;
+
+    /// Sets the number of bytes used by the records of the storage `namespace` of the
+    /// current account. The usage record is removed once it drops to 0.
+    fn set_storage_ns_usage(&mut self, namespace: &[u8], usage: StorageUsage) -> Result<()>;
+
     fn generate_data_id(&mut self) -> CryptoHash;
 
     /// Returns amount of touched trie nodes by storage operations
@@ -270,6 +328,33 @@ pub trait External {
     /// Returns total stake of validators in the current epoch.
     fn validator_total_stake(&self) -> Result<Balance>;
 
+    /// Calls the view method `method_name` of the contract deployed on `receiver_id` with
+    /// `args`, burning at most `gas`. The called contract can't modify state or create
+    /// promises, and only sees the state of the current shard.
+    ///
+    /// A call to a missing account or contract, or a call that fails, yields an outcome
+    /// without return data. A call to an account of another shard fails with
+    /// [`HostError::ViewCallCrossShard`](super::HostError::ViewCallCrossShard).
+    fn view_call(
+        &mut self,
+        receiver_id: &AccountId,
+        method_name: &str,
+        args: &[u8],
+        gas: Gas,
+    ) -> Result<ViewCallOutcome>;
+
+    /// Number of view calls the current execution is nested in, 0 if the current
+    /// execution isn't a view call.
+    fn view_call_depth(&self) -> u64;
+
+    /// Whether [`External::trace_host_function`] should be called for the host
+    /// function calls of the contract. Queried once, before the execution.
+    fn traces_host_functions(&self) -> bool;
+
+    /// Called before each host function call of the contract, if
+    /// [`External::traces_host_functions`] returned true.
+    fn trace_host_function(&mut self, name: &'static str);
+
     /// Create an action receipt which will be executed after all the receipts identified by
     /// `receipt_indices` are complete.
     ///
@@ -293,9 +378,12 @@ pub trait External {
     /// # Arguments
     ///
     /// * `receiver_id` - account id of the receiver of the receipt created
+    /// * `timeout_length_in_blocks` - number of blocks after which the receipt times out if its
+    ///   data dependency hasn't been resolved
     fn create_promise_yield_receipt(
         &mut self,
         receiver_id: AccountId,
+        timeout_length_in_blocks: BlockHeightDelta,
     ) -> Result<(ReceiptIndex, CryptoHash), VMLogicError>;
 
     /// Creates a receipt under the specified `data_id` containing given `data`.
@@ -116,6 +116,8 @@ pub enum CompilationError {
     WasmtimeCompileError {
         msg: String,
     },
+    /// The contract was rejected by the static analysis done before preparing it.
+    StaticAnalysisError(StaticAnalysisError),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
@@ -146,6 +148,19 @@ pub enum PrepareError {
     TooManyFunctions,
     /// Contract contains too many locals.
     TooManyLocals,
+    /// Contract contains too many memories.
+    TooManyMemories,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+/// Reason for which the static analysis rejected a contract as likely to be too slow to compile.
+pub enum StaticAnalysisError {
+    /// A function declares more locals than allowed.
+    TooManyLocalsPerFunction,
+    /// A table has more initial elements than allowed.
+    TableTooLarge,
+    /// Blocks of a function are nested deeper than allowed.
+    BlocksNestedTooDeep,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, strum::IntoStaticStr)]
@@ -218,6 +233,19 @@ pub enum HostError {
         length: u64,
         limit: u64,
     },
+    /// The storage namespace length exceeded the limit.
+    NamespaceLengthExceeded {
+        length: u64,
+        limit: u64,
+    },
+    /// `view_call` was called from a contract that is already nested `limit` view calls deep.
+    ViewCallDepthExceeded {
+        limit: u64,
+    },
+    /// `view_call` was called with an account of another shard, whose state isn't available.
+    ViewCallCrossShard {
+        receiver_id: String,
+    },
     /// The total log length exceeded the limit.
     TotalLogLengthExceeded {
         length: u64,
@@ -265,6 +293,11 @@ pub enum HostError {
     BLS12381InvalidInput {
         msg: String,
     },
+    /// Invalid input to p256 signature verification function (e.g. wrong
+    /// length of the signature or the public key).
+    P256VerifyInvalidInput {
+        msg: String,
+    },
     /// Yield payload length exceeds the maximum permitted.
     YieldPayloadLength {
         length: u64,
@@ -272,6 +305,11 @@ pub enum HostError {
     },
     /// Yield resumption data id is malformed.
     DataIdMalformed,
+    /// Requested yield timeout is zero or exceeds the maximum permitted.
+    YieldTimeoutLength {
+        length: u64,
+        limit: u64,
+    },
     /// Size of the recorded trie storage proof has exceeded the allowed limit.
     RecordedStorageExceeded {
         limit: ByteSize,
@@ -356,6 +394,18 @@ impl fmt::Display for PrepareError {
             Memory => "Error creating memory.",
             TooManyFunctions => "Too many functions in contract.",
             TooManyLocals => "Too many locals declared in the contract.",
+            TooManyMemories => "Too many memories declared in the contract.",
+        })
+    }
+}
+
+impl fmt::Display for StaticAnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        use StaticAnalysisError::*;
+        f.write_str(match self {
+            TooManyLocalsPerFunction => "Too many locals declared in a function.",
+            TableTooLarge => "Too many elements in a table.",
+            BlocksNestedTooDeep => "Blocks nested too deep in a function.",
         })
     }
 }
@@ -405,6 +455,7 @@ impl fmt::Display for CompilationError {
             CompilationError::WasmtimeCompileError { msg } => {
                 write!(f, "Wasmtime compilation error: {}", msg)
             }
+            CompilationError::StaticAnalysisError(e) => write!(f, "StaticAnalysisError: {}", e),
         }
     }
 }
@@ -477,6 +528,19 @@ impl std::fmt::Display for HostError {
             ValueLengthExceeded { length, limit } => {
                 write!(f, "The length of a storage value {} exceeds the limit {}", length, limit)
             }
+            ViewCallDepthExceeded { limit } => {
+                write!(f, "Exceeded the maximum depth of nested view calls {}", limit)
+            }
+            ViewCallCrossShard { receiver_id } => {
+                write!(f, "View call to {} which belongs to another shard", receiver_id)
+            }
+            NamespaceLengthExceeded { length, limit } => {
+                write!(
+                    f,
+                    "The length of a storage namespace {} exceeds the limit {}",
+                    length, limit
+                )
+            }
             TotalLogLengthExceeded { length, limit } => {
                 write!(f, "The length of a log message {} exceeds the limit {}", length, limit)
             }
@@ -509,11 +573,18 @@ impl std::fmt::Display for HostError {
                 write!(f, "ED25519 signature verification error: {}", msg)
             }
             BLS12381InvalidInput { msg } => write!(f, "BLS12-381 invalid input: {}", msg),
+            P256VerifyInvalidInput { msg } => {
+                write!(f, "P256 signature verification error: {}", msg)
+            }
             YieldPayloadLength { length, limit } => write!(
                 f,
                 "Yield resume payload is {length} bytes which exceeds the {limit} byte limit"
             ),
             DataIdMalformed => write!(f, "yield resumption token is malformed"),
+            YieldTimeoutLength { length, limit } => write!(
+                f,
+                "Yield timeout of {length} blocks is not between 1 and the {limit} block limit"
+            ),
             RecordedStorageExceeded { limit } => write!(
                 f,
                 "Size of the recorded trie storage proof has exceeded the allowed limit ({})",
@@ -331,6 +331,11 @@ impl GasCounter {
         self.fast_counter.burnt_gas
     }
 
+    /// Amount of gas that can still be burnt before hitting the limit.
+    pub(crate) fn remaining_gas(&self) -> Gas {
+        self.fast_counter.gas_limit.saturating_sub(self.fast_counter.burnt_gas)
+    }
+
     /// Amount of gas used through promises and amount burned.
     pub(crate) fn used_gas(&self) -> Gas {
         self.promises_gas + self.fast_counter.burnt_gas
@@ -50,6 +50,9 @@ pub struct ExecutionResultState {
     current_account_balance: Balance,
     /// Storage usage of the current account at the moment
     current_storage_usage: StorageUsage,
+    /// Compute usage of the contracts called through `view_call` beyond the gas they burnt,
+    /// which is already accounted for as wasm execution of this contract.
+    view_calls_excess_compute: Compute,
 }
 
 impl ExecutionResultState {
@@ -73,6 +76,7 @@ impl ExecutionResultState {
             return_data: ReturnData::None,
             current_account_balance,
             current_storage_usage,
+            view_calls_excess_compute: 0,
         }
     }
 
@@ -129,7 +133,9 @@ impl ExecutionResultState {
 
         let mut profile = self.gas_counter.profile_data();
         profile.compute_wasm_instruction_cost(burnt_gas);
-        let compute_usage = profile.total_compute_usage(&self.config.ext_costs);
+        let compute_usage = profile
+            .total_compute_usage(&self.config.ext_costs)
+            .saturating_add(self.view_calls_excess_compute);
 
         VMOutcome {
             balance: self.current_account_balance,
@@ -175,6 +181,9 @@ pub struct VMLogic<'a> {
     /// Tracks size of the recorded trie storage proof.
     recorded_storage_counter: RecordedStorageCounter,
 
+    /// Whether host function calls are reported to `ext`.
+    trace_host_functions: bool,
+
     pub(crate) result_state: ExecutionResultState,
 }
 
@@ -248,6 +257,7 @@ impl<'a> VMLogic<'a> {
             config.limit_config.per_receipt_storage_proof_size_limit,
         );
         let remaining_stack = u64::from(config.limit_config.max_stack_height);
+        let trace_host_functions = ext.traces_host_functions();
         Self {
             ext,
             context,
@@ -259,10 +269,20 @@ impl<'a> VMLogic<'a> {
             registers: Default::default(),
             promises: vec![],
             remaining_stack,
+            trace_host_functions,
             result_state,
         }
     }
 
+    /// Reports the call of the host function `name` to the external, if it
+    /// asked for it. Called by the runners before each host function call.
+    #[inline]
+    pub(crate) fn trace_host_function(&mut self, name: &'static str) {
+        if self.trace_host_functions {
+            self.ext.trace_host_function(name);
+        }
+    }
+
     /// Returns reference to logs that have been created so far.
     pub fn logs(&self) -> &[String] {
         &self.result_state.logs
@@ -706,6 +726,43 @@ impl<'a> VMLogic<'a> {
         Ok(self.context.epoch_height)
     }
 
+    /// Reads the header of the block at `height` if it is one of the
+    /// `NUM_RECENT_BLOCK_HEADERS` blocks preceding the current one and writes
+    /// its hash, timestamp and epoch id into the register, as the concatenation
+    /// of the 32-byte hash, the little-endian `u64` timestamp and the 32-byte
+    /// epoch id.
+    ///
+    /// Returns 1 if the header was written and 0 if there is no such block,
+    /// either because it is too old or because the height was skipped.
+    ///
+    /// # Errors
+    ///
+    /// If the size of the registers exceed the set limit `MemoryAccessViolation`.
+    ///
+    /// # Cost
+    ///
+    /// `base + block_header_base + write_register_base + write_register_byte * 72`.
+    pub fn block_header(&mut self, height: u64, register_id: u64) -> Result<u64> {
+        self.result_state.gas_counter.pay_base(base)?;
+        self.result_state.gas_counter.pay_base(block_header_base)?;
+        let Some(header) =
+            self.context.recent_block_headers.iter().find(|header| header.height == height)
+        else {
+            return Ok(0);
+        };
+        let mut data = Vec::with_capacity(72);
+        data.extend_from_slice(header.hash.as_ref());
+        data.extend_from_slice(&header.timestamp.to_le_bytes());
+        data.extend_from_slice(header.epoch_id.as_ref());
+        self.registers.set(
+            &mut self.result_state.gas_counter,
+            &self.config.limit_config,
+            register_id,
+            data,
+        )?;
+        Ok(1)
+    }
+
     /// Get the stake of an account, if the account is currently a validator. Otherwise returns 0.
     /// writes the value into the` u128` variable pointed by `stake_ptr`.
     ///
@@ -1409,6 +1466,46 @@ bls12381_p2_decompress_base + bls12381_p2_decompress_element * num_elements`
         )
     }
 
+    /// Writes the random value of the receipt for the given domain into the register, the sha256
+    /// hash of the random seed followed by the domain.
+    ///
+    /// The random seed is derived from the VRF output of the block and the id of the receipt, so
+    /// the value is the same for all calls with the same domain in a receipt, and values for
+    /// different domains are independent. Contracts use it to draw several random values in a
+    /// call, e.g. with the index of the value as the domain.
+    ///
+    /// # Errors
+    ///
+    /// If `domain_len + domain_ptr` points outside the memory or the registers use more memory
+    /// than the limit with `MemoryAccessViolation`.
+    ///
+    /// # Cost
+    ///
+    /// `base + sha256_base + sha256_byte * (32 + domain_len) + write_register_base +
+    /// write_register_byte * 32`
+    pub fn random_seed_with_domain(
+        &mut self,
+        domain_len: u64,
+        domain_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.result_state.gas_counter.pay_base(base)?;
+        self.result_state.gas_counter.pay_base(sha256_base)?;
+        let domain = get_memory_or_register!(self, domain_ptr, domain_len)?;
+        let seed = &self.context.random_seed;
+        self.result_state.gas_counter.pay_per(sha256_byte, (seed.len() + domain.len()) as u64)?;
+
+        use sha2::Digest;
+
+        let value = sha2::Sha256::new().chain_update(seed).chain_update(&domain).finalize();
+        self.registers.set(
+            &mut self.result_state.gas_counter,
+            &self.config.limit_config,
+            register_id,
+            value.as_slice(),
+        )
+    }
+
     /// Hashes the given value using sha256 and returns it into `register_id`.
     ///
     /// # Errors
@@ -1697,6 +1794,81 @@ bls12381_p2_decompress_base + bls12381_p2_decompress_element * num_elements`
         }
     }
 
+    /// Verify a secp256r1 (NIST P-256) ECDSA signature of the SHA-256 hash of
+    /// a message given a public key. This is the signature scheme used by
+    /// WebAuthn authenticators such as passkeys.
+    ///
+    /// The signature is the 64 byte concatenation of `r` and `s`, and the
+    /// public key is a SEC1 encoded point, either compressed (33 bytes) or
+    /// uncompressed (65 bytes).
+    ///
+    /// Returns a bool indicating success (1) or failure (0) as a `u64`.
+    ///
+    /// # Errors
+    ///
+    /// * If the signature size is not equal to 64, or the public key size is
+    ///   neither 33 nor 65, returns [HostError::P256VerifyInvalidInput].
+    /// * If any of the signature, message or public key arguments are out of
+    ///   memory bounds, returns [`HostError::MemoryAccessViolation`]
+    ///
+    /// # Cost
+    ///
+    /// Inputs are read the same way as in [`Self::ed25519_verify`].
+    ///
+    /// `input_cost(num_bytes_signature) + input_cost(num_bytes_message) +
+    ///  input_cost(num_bytes_public_key) + p256_verify_base +
+    ///  p256_verify_byte * num_bytes_message`
+    pub fn p256_verify(
+        &mut self,
+        signature_len: u64,
+        signature_ptr: u64,
+        message_len: u64,
+        message_ptr: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+    ) -> Result<u64> {
+        use p256::ecdsa::signature::Verifier;
+
+        self.result_state.gas_counter.pay_base(p256_verify_base)?;
+
+        let signature = {
+            let vec = get_memory_or_register!(self, signature_ptr, signature_len)?;
+            if vec.len() != 64 {
+                return Err(HostError::P256VerifyInvalidInput {
+                    msg: "invalid signature length".to_string(),
+                }
+                .into());
+            }
+            // Fails if either of `r` and `s` is zero or not below the curve order.
+            match p256::ecdsa::Signature::from_slice(&vec) {
+                Ok(signature) => signature,
+                Err(_) => return Ok(false as u64),
+            }
+        };
+
+        let message = get_memory_or_register!(self, message_ptr, message_len)?;
+        self.result_state.gas_counter.pay_per(p256_verify_byte, message.len() as u64)?;
+
+        let public_key = {
+            let vec = get_memory_or_register!(self, public_key_ptr, public_key_len)?;
+            if vec.len() != 33 && vec.len() != 65 {
+                return Err(HostError::P256VerifyInvalidInput {
+                    msg: "invalid public key length".to_string(),
+                }
+                .into());
+            }
+            match p256::ecdsa::VerifyingKey::from_sec1_bytes(&vec) {
+                Ok(public_key) => public_key,
+                Err(_) => return Ok(false as u64),
+            }
+        };
+
+        match public_key.verify(&message, &signature) {
+            Err(_) => Ok(false as u64),
+            Ok(()) => Ok(true as u64),
+        }
+    }
+
     /// Consume gas. Counts both towards `burnt_gas` and `used_gas`.
     ///
     /// # Errors
@@ -2587,6 +2759,79 @@ bls12381_p2_decompress_base + bls12381_p2_decompress_element * num_elements`
             }
             .into());
         }
+        self.promise_yield_create_impl(
+            method_name_len,
+            method_name_ptr,
+            arguments_len,
+            arguments_ptr,
+            gas,
+            gas_weight,
+            self.config.limit_config.yield_timeout_length_in_blocks,
+            register_id,
+        )
+    }
+
+    /// Same as [`Self::promise_yield_create`], except that the created promise times out after
+    /// `timeout_length_in_blocks` blocks instead of the `yield_timeout_length_in_blocks`
+    /// parameter.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`Self::promise_yield_create`]:
+    ///
+    /// * If `timeout_length_in_blocks` is zero or exceeds the
+    /// `max_yield_timeout_length_in_blocks` parameter returns `YieldTimeoutLength`.
+    ///
+    /// # Cost
+    ///
+    /// Same as [`Self::promise_yield_create`].
+    pub fn promise_yield_create_with_timeout(
+        &mut self,
+        method_name_len: u64,
+        method_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: Gas,
+        gas_weight: u64,
+        timeout_length_in_blocks: u64,
+        register_id: u64,
+    ) -> Result<u64> {
+        self.result_state.gas_counter.pay_base(base)?;
+        if self.context.is_view() {
+            return Err(HostError::ProhibitedInView {
+                method_name: "promise_yield_create_with_timeout".to_string(),
+            }
+            .into());
+        }
+        let limit = self.config.limit_config.max_yield_timeout_length_in_blocks;
+        if timeout_length_in_blocks == 0 || timeout_length_in_blocks > limit {
+            return Err(
+                HostError::YieldTimeoutLength { length: timeout_length_in_blocks, limit }.into()
+            );
+        }
+        self.promise_yield_create_impl(
+            method_name_len,
+            method_name_ptr,
+            arguments_len,
+            arguments_ptr,
+            gas,
+            gas_weight,
+            timeout_length_in_blocks,
+            register_id,
+        )
+    }
+
+    fn promise_yield_create_impl(
+        &mut self,
+        method_name_len: u64,
+        method_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: Gas,
+        gas_weight: u64,
+        timeout_length_in_blocks: u64,
+        register_id: u64,
+    ) -> Result<u64> {
         self.result_state.gas_counter.pay_base(yield_create_base)?;
 
         let method_name = get_memory_or_register!(self, method_name_ptr, method_name_len)?;
@@ -2606,8 +2851,10 @@ bls12381_p2_decompress_base + bls12381_p2_decompress_element * num_elements`
         // Here we are creating a receipt with a single data dependency which will then be
         // resolved by the resume call.
         self.pay_gas_for_new_receipt(true, &[true])?;
-        let (new_receipt_idx, data_id) =
-            self.ext.create_promise_yield_receipt(self.context.current_account_id.clone())?;
+        let (new_receipt_idx, data_id) = self.ext.create_promise_yield_receipt(
+            self.context.current_account_id.clone(),
+            timeout_length_in_blocks,
+        )?;
 
         let new_promise_idx = self.checked_push_promise(Promise::Receipt(new_receipt_idx))?;
         self.pay_action_base(ActionCosts::function_call_base, true)?;
@@ -2796,6 +3043,79 @@ bls12381_p2_decompress_base + bls12381_p2_decompress_element * num_elements`
         }
     }
 
+    /// Synchronously calls the view method `method_name` of the contract deployed on
+    /// `account_id` with the given arguments and writes the data it returns into the register.
+    /// The called contract runs as a view call within the current receipt execution: it sees
+    /// the state of the current shard as modified so far, but can't modify it or create
+    /// promises. The gas it burns, at most `gas`, is burnt by the current contract, which is
+    /// charged its compute usage as well.
+    ///
+    /// Returns 1 if the call succeeded and its result was written into the register, and 0 if
+    /// it failed, including when `account_id` has no contract.
+    ///
+    /// # Errors
+    ///
+    /// * If `account_id_len + account_id_ptr`, `method_name_len + method_name_ptr` or
+    /// `arguments_len + arguments_ptr` points outside the memory of the guest or host returns
+    /// `MemoryAccessViolation`.
+    /// * If account is not UTF-8 encoded then returns `BadUtf8`.
+    /// * If `method_name` is empty returns `EmptyMethodName`.
+    /// * If the current execution is already nested `max_view_call_depth` view calls deep
+    /// returns `ViewCallDepthExceeded`.
+    /// * If `account_id` belongs to another shard than the current account returns
+    /// `ViewCallCrossShard`.
+    /// * If the size of the registers exceed the set limit `MemoryAccessViolation`.
+    ///
+    /// # Cost
+    ///
+    /// `base + view_call_base + cost of reading the account id, method name and arguments from
+    /// memory or registers + the gas burnt by the called contract + cost of writing the
+    /// returned data into the register`
+    pub fn view_call(
+        &mut self,
+        account_id_len: u64,
+        account_id_ptr: u64,
+        method_name_len: u64,
+        method_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: Gas,
+        register_id: u64,
+    ) -> Result<u64> {
+        self.result_state.gas_counter.pay_base(base)?;
+        self.result_state.gas_counter.pay_base(view_call_base)?;
+        let account_id = self.read_and_parse_account_id(account_id_ptr, account_id_len)?;
+        let method_name = get_memory_or_register!(self, method_name_ptr, method_name_len)?;
+        if method_name.is_empty() {
+            return Err(HostError::EmptyMethodName.into());
+        }
+        let method_name =
+            String::from_utf8(method_name.into_owned()).map_err(|_| HostError::BadUTF8)?;
+        let arguments = get_memory_or_register!(self, arguments_ptr, arguments_len)?.into_owned();
+
+        let limit = self.config.limit_config.max_view_call_depth;
+        if self.ext.view_call_depth() >= limit {
+            return Err(HostError::ViewCallDepthExceeded { limit }.into());
+        }
+        let gas = gas.min(self.result_state.gas_counter.remaining_gas());
+        let outcome = self.ext.view_call(&account_id, &method_name, &arguments, gas)?;
+        self.result_state.gas_counter.burn_gas(outcome.burnt_gas)?;
+        self.result_state.view_calls_excess_compute = self
+            .result_state
+            .view_calls_excess_compute
+            .saturating_add(outcome.compute_usage.saturating_sub(outcome.burnt_gas));
+        let Some(data) = outcome.return_data else {
+            return Ok(0);
+        };
+        self.registers.set(
+            &mut self.result_state.gas_counter,
+            &self.config.limit_config,
+            register_id,
+            data,
+        )?;
+        Ok(1)
+    }
+
     // #####################
     // # Miscellaneous API #
     // #####################
@@ -3353,6 +3673,360 @@ bls12381_p2_decompress_base + bls12381_p2_decompress_element * num_elements`
         Ok(res? as u64)
     }
 
+    fn check_storage_ns_lengths(&self, namespace_len: usize, key_len: usize) -> Result<()> {
+        let limit_config = &self.config.limit_config;
+        if namespace_len as u64 > limit_config.max_length_storage_namespace {
+            return Err(HostError::NamespaceLengthExceeded {
+                length: namespace_len as u64,
+                limit: limit_config.max_length_storage_namespace,
+            }
+            .into());
+        }
+        if key_len as u64 > limit_config.max_length_storage_key {
+            return Err(HostError::KeyLengthExceeded {
+                length: key_len as u64,
+                limit: limit_config.max_length_storage_key,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Replaces a record of `old_len` bytes in the storage namespace by one of
+    /// `new_len` bytes, where a length of 0 means no record. The change applies
+    /// both to the namespace usage and to the storage usage of the account.
+    ///
+    /// The namespace usage record itself is charged to the account while the
+    /// namespace holds any records. Reading and writing it costs gas like a
+    /// `storage_read` and a `storage_write` of a `u64` under the namespace.
+    fn update_storage_ns_usage(
+        &mut self,
+        namespace: &[u8],
+        old_len: StorageUsage,
+        new_len: StorageUsage,
+    ) -> Result<()> {
+        let gas_counter = &mut self.result_state.gas_counter;
+        let usage_value_len = size_of::<StorageUsage>() as u64;
+        gas_counter.pay_base(storage_read_base)?;
+        gas_counter.pay_per(storage_read_key_byte, namespace.len() as u64)?;
+        gas_counter.pay_per(storage_read_value_byte, usage_value_len)?;
+        gas_counter.pay_base(storage_write_base)?;
+        gas_counter.pay_per(storage_write_key_byte, namespace.len() as u64)?;
+        gas_counter.pay_per(storage_write_value_byte, usage_value_len)?;
+        let nodes_before = self.ext.get_trie_nodes_count();
+        let usage_before = self.ext.storage_ns_usage(namespace)?;
+        let usage_after = usage_before
+            .checked_sub(old_len)
+            .and_then(|usage| usage.checked_add(new_len))
+            .ok_or(InconsistentStateError::IntegerOverflow)?;
+        self.ext.set_storage_ns_usage(namespace, usage_after)?;
+        let nodes_delta = self
+            .ext
+            .get_trie_nodes_count()
+            .checked_sub(&nodes_before)
+            .ok_or(InconsistentStateError::IntegerOverflow)?;
+        self.result_state.gas_counter.add_trie_fees(&nodes_delta)?;
+        self.recorded_storage_counter.observe_size(self.ext.get_recorded_storage_size())?;
+
+        let usage_record_len = namespace.len() as u64
+            + size_of::<StorageUsage>() as u64
+            + self.fees_config.storage_usage_config.num_extra_bytes_record;
+        let account_usage = self.result_state.current_storage_usage;
+        let account_usage = match (usage_before, usage_after) {
+            (0, 0) => Some(account_usage),
+            (0, _) => account_usage.checked_add(usage_record_len),
+            (_, 0) => account_usage.checked_sub(usage_record_len),
+            _ => Some(account_usage),
+        };
+        self.result_state.current_storage_usage = account_usage
+            .and_then(|usage| usage.checked_sub(old_len))
+            .and_then(|usage| usage.checked_add(new_len))
+            .ok_or(InconsistentStateError::IntegerOverflow)?;
+        Ok(())
+    }
+
+    /// Writes key-value into the given storage namespace of the current account.
+    /// * If key is not in use in the namespace it inserts the key-value pair and does not modify
+    ///   the register. Returns `0`;
+    /// * If key is in use it inserts the key-value and copies the old value into the
+    ///   `register_id`. Returns `1`.
+    ///
+    /// The bytes of the record are counted both in the storage usage of the account and in the
+    /// usage of the namespace, see `storage_ns_usage`.
+    ///
+    /// # Errors
+    ///
+    /// * If `namespace_len + namespace_ptr`, `key_len + key_ptr` or `value_len + value_ptr` exceeds
+    ///   the memory container or points to an unused register it returns `MemoryAccessViolation`;
+    /// * If the length of the namespace exceeds `max_length_storage_namespace` returns
+    ///   `NamespaceLengthExceeded`.
+    /// * If the length of the key exceeds `max_length_storage_key` returns `KeyLengthExceeded`.
+    /// * If the length of the value exceeds `max_length_storage_value` returns
+    ///   `ValueLengthExceeded`.
+    /// * If called as view function returns `ProhibitedInView`.
+    ///
+    /// # Cost
+    ///
+    /// Same as `storage_write`, plus `storage_ns_base`. The namespace bytes are charged as key
+    /// bytes. Updating the usage of the namespace is charged like reading and writing a `u64`
+    /// under the namespace.
+    pub fn storage_ns_write(
+        &mut self,
+        namespace_len: u64,
+        namespace_ptr: u64,
+        key_len: u64,
+        key_ptr: u64,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> Result<u64> {
+        self.result_state.gas_counter.pay_base(base)?;
+        if self.context.is_view() {
+            return Err(HostError::ProhibitedInView {
+                method_name: "storage_ns_write".to_string(),
+            }
+            .into());
+        }
+        self.result_state.gas_counter.pay_base(storage_write_base)?;
+        self.result_state.gas_counter.pay_base(storage_ns_base)?;
+        let namespace = get_memory_or_register!(self, namespace_ptr, namespace_len)?.into_owned();
+        let key = get_memory_or_register!(self, key_ptr, key_len)?;
+        self.check_storage_ns_lengths(namespace.len(), key.len())?;
+        let value = get_memory_or_register!(self, value_ptr, value_len)?;
+        if value.len() as u64 > self.config.limit_config.max_length_storage_value {
+            return Err(HostError::ValueLengthExceeded {
+                length: value.len() as u64,
+                limit: self.config.limit_config.max_length_storage_value,
+            }
+            .into());
+        }
+        self.result_state
+            .gas_counter
+            .pay_per(storage_write_key_byte, (namespace.len() + key.len()) as u64)?;
+        self.result_state.gas_counter.pay_per(storage_write_value_byte, value.len() as u64)?;
+        let nodes_before = self.ext.get_trie_nodes_count();
+        // As for `storage_write`, read the key through trie first to calculate the TTN cost.
+        let evicted_ptr = self.ext.storage_ns_get(&namespace, &key, StorageGetMode::Trie)?;
+        let evicted = Self::deref_value(
+            &mut self.result_state.gas_counter,
+            storage_write_evicted_byte,
+            evicted_ptr,
+        )?;
+        let nodes_delta = self
+            .ext
+            .get_trie_nodes_count()
+            .checked_sub(&nodes_before)
+            .ok_or(InconsistentStateError::IntegerOverflow)?;
+        self.result_state.gas_counter.add_trie_fees(&nodes_delta)?;
+        self.ext.storage_ns_set(&namespace, &key, &value)?;
+        self.recorded_storage_counter.observe_size(self.ext.get_recorded_storage_size())?;
+
+        // Inner values can't overflow, because the namespace/key/value length is limited.
+        let record_len = namespace.len() as u64
+            + key.len() as u64
+            + self.fees_config.storage_usage_config.num_extra_bytes_record;
+        let old_len = evicted.as_ref().map_or(0, |old_value| record_len + old_value.len() as u64);
+        let new_len = record_len + value.len() as u64;
+        self.update_storage_ns_usage(&namespace, old_len, new_len)?;
+        match evicted {
+            Some(old_value) => {
+                self.registers.set(
+                    &mut self.result_state.gas_counter,
+                    &self.config.limit_config,
+                    register_id,
+                    old_value,
+                )?;
+                Ok(1)
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Reads the value stored under the given key in the given storage namespace of the current
+    /// account.
+    /// * If key is used copies the content of the value into the `register_id`, even if the content
+    ///   is zero bytes. Returns `1`;
+    /// * If key is not present then does not modify the register. Returns `0`;
+    ///
+    /// # Errors
+    ///
+    /// * If `namespace_len + namespace_ptr` or `key_len + key_ptr` exceeds the memory container or
+    ///   points to an unused register it returns `MemoryAccessViolation`;
+    /// * If the length of the namespace exceeds `max_length_storage_namespace` returns
+    ///   `NamespaceLengthExceeded`.
+    /// * If the length of the key exceeds `max_length_storage_key` returns `KeyLengthExceeded`.
+    ///
+    /// # Cost
+    ///
+    /// Same as `storage_read`, plus `storage_ns_base`. The namespace bytes are charged as key
+    /// bytes.
+    pub fn storage_ns_read(
+        &mut self,
+        namespace_len: u64,
+        namespace_ptr: u64,
+        key_len: u64,
+        key_ptr: u64,
+        register_id: u64,
+    ) -> Result<u64> {
+        self.result_state.gas_counter.pay_base(base)?;
+        self.result_state.gas_counter.pay_base(storage_read_base)?;
+        self.result_state.gas_counter.pay_base(storage_ns_base)?;
+        let namespace = get_memory_or_register!(self, namespace_ptr, namespace_len)?;
+        let key = get_memory_or_register!(self, key_ptr, key_len)?;
+        self.check_storage_ns_lengths(namespace.len(), key.len())?;
+        self.result_state
+            .gas_counter
+            .pay_per(storage_read_key_byte, (namespace.len() + key.len()) as u64)?;
+        let nodes_before = self.ext.get_trie_nodes_count();
+        let read = self.ext.storage_ns_get(&namespace, &key, self.config.storage_get_mode);
+        let nodes_delta = self
+            .ext
+            .get_trie_nodes_count()
+            .checked_sub(&nodes_before)
+            .ok_or(InconsistentStateError::IntegerOverflow)?;
+        self.result_state.gas_counter.add_trie_fees(&nodes_delta)?;
+        let read = match read? {
+            Some(read) => {
+                let read_len = read.len() as usize;
+                self.result_state.gas_counter.pay_per(storage_read_value_byte, read_len as u64)?;
+                if read_len > INLINE_DISK_VALUE_THRESHOLD {
+                    self.result_state.gas_counter.pay_base(storage_large_read_overhead_base)?;
+                    self.result_state
+                        .gas_counter
+                        .pay_per(storage_large_read_overhead_byte, read_len as u64)?;
+                }
+                Some(read.deref()?)
+            }
+            None => None,
+        };
+        self.recorded_storage_counter.observe_size(self.ext.get_recorded_storage_size())?;
+        match read {
+            Some(value) => {
+                self.registers.set(
+                    &mut self.result_state.gas_counter,
+                    &self.config.limit_config,
+                    register_id,
+                    value,
+                )?;
+                Ok(1)
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Removes the value stored under the given key in the given storage namespace of the current
+    /// account.
+    /// * If key is used, removes the key-value from the namespace and copies the content of the
+    ///   value into the `register_id`, even if the content is zero bytes. Returns `1`;
+    /// * If key is not present then does not modify the register. Returns `0`.
+    ///
+    /// # Errors
+    ///
+    /// * If `namespace_len + namespace_ptr` or `key_len + key_ptr` exceeds the memory container or
+    ///   points to an unused register it returns `MemoryAccessViolation`;
+    /// * If the length of the namespace exceeds `max_length_storage_namespace` returns
+    ///   `NamespaceLengthExceeded`.
+    /// * If the length of the key exceeds `max_length_storage_key` returns `KeyLengthExceeded`.
+    /// * If called as view function returns `ProhibitedInView`.
+    ///
+    /// # Cost
+    ///
+    /// Same as `storage_remove`, plus `storage_ns_base`. The namespace bytes are charged as key
+    /// bytes. If a record is removed, updating the usage of the namespace is charged like reading
+    /// and writing a `u64` under the namespace.
+    pub fn storage_ns_remove(
+        &mut self,
+        namespace_len: u64,
+        namespace_ptr: u64,
+        key_len: u64,
+        key_ptr: u64,
+        register_id: u64,
+    ) -> Result<u64> {
+        self.result_state.gas_counter.pay_base(base)?;
+        if self.context.is_view() {
+            return Err(HostError::ProhibitedInView {
+                method_name: "storage_ns_remove".to_string(),
+            }
+            .into());
+        }
+        self.result_state.gas_counter.pay_base(storage_remove_base)?;
+        self.result_state.gas_counter.pay_base(storage_ns_base)?;
+        let namespace = get_memory_or_register!(self, namespace_ptr, namespace_len)?.into_owned();
+        let key = get_memory_or_register!(self, key_ptr, key_len)?;
+        self.check_storage_ns_lengths(namespace.len(), key.len())?;
+        self.result_state
+            .gas_counter
+            .pay_per(storage_remove_key_byte, (namespace.len() + key.len()) as u64)?;
+        let nodes_before = self.ext.get_trie_nodes_count();
+        // As for `storage_remove`, read the key through trie first to calculate the TTN cost.
+        let removed_ptr = self.ext.storage_ns_get(&namespace, &key, StorageGetMode::Trie)?;
+        let removed = Self::deref_value(
+            &mut self.result_state.gas_counter,
+            storage_remove_ret_value_byte,
+            removed_ptr,
+        )?;
+        self.ext.storage_ns_remove(&namespace, &key)?;
+        let nodes_delta = self
+            .ext
+            .get_trie_nodes_count()
+            .checked_sub(&nodes_before)
+            .ok_or(InconsistentStateError::IntegerOverflow)?;
+        self.result_state.gas_counter.add_trie_fees(&nodes_delta)?;
+        self.recorded_storage_counter.observe_size(self.ext.get_recorded_storage_size())?;
+        match removed {
+            Some(value) => {
+                // Inner value can't overflow, because the namespace/key/value length is limited.
+                let old_len = namespace.len() as u64
+                    + key.len() as u64
+                    + value.len() as u64
+                    + self.fees_config.storage_usage_config.num_extra_bytes_record;
+                self.update_storage_ns_usage(&namespace, old_len, 0)?;
+                self.registers.set(
+                    &mut self.result_state.gas_counter,
+                    &self.config.limit_config,
+                    register_id,
+                    value,
+                )?;
+                Ok(1)
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Returns the number of bytes used by the records of the given storage namespace of the
+    /// current account, counted the same way as the storage usage of the account.
+    /// Returns `0` if the namespace holds no records.
+    ///
+    /// # Errors
+    ///
+    /// * If `namespace_len + namespace_ptr` exceeds the memory container or points to an unused
+    ///   register it returns `MemoryAccessViolation`;
+    /// * If the length of the namespace exceeds `max_length_storage_namespace` returns
+    ///   `NamespaceLengthExceeded`.
+    ///
+    /// # Cost
+    ///
+    /// `base + storage_ns_base + storage_read_base + storage_read_key_byte * num_namespace_bytes
+    /// + cost of reading namespace`
+    pub fn storage_ns_usage(&mut self, namespace_len: u64, namespace_ptr: u64) -> Result<u64> {
+        self.result_state.gas_counter.pay_base(base)?;
+        self.result_state.gas_counter.pay_base(storage_ns_base)?;
+        self.result_state.gas_counter.pay_base(storage_read_base)?;
+        let namespace = get_memory_or_register!(self, namespace_ptr, namespace_len)?;
+        self.check_storage_ns_lengths(namespace.len(), 0)?;
+        self.result_state.gas_counter.pay_per(storage_read_key_byte, namespace.len() as u64)?;
+        let nodes_before = self.ext.get_trie_nodes_count();
+        let usage = self.ext.storage_ns_usage(&namespace);
+        let nodes_delta = self
+            .ext
+            .get_trie_nodes_count()
+            .checked_sub(&nodes_before)
+            .ok_or(InconsistentStateError::IntegerOverflow)?;
+        self.result_state.gas_counter.add_trie_fees(&nodes_delta)?;
+        self.recorded_storage_counter.observe_size(self.ext.get_recorded_storage_size())?;
+        usage
+    }
+
     /// Debug print given utf-8 string to node log. It's only available in Sandbox node
     ///
     /// # Errors
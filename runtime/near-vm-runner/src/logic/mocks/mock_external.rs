@@ -1,9 +1,11 @@
 use crate::logic::dependencies::{Result, TrieNodesCount};
-use crate::logic::types::ReceiptIndex;
+use crate::logic::types::{ReceiptIndex, ViewCallOutcome};
 use crate::logic::{External, StorageGetMode, ValuePtr};
 use crate::ContractCode;
 use near_primitives_core::hash::{hash, CryptoHash};
-use near_primitives_core::types::{AccountId, Balance, Gas, GasWeight};
+use near_primitives_core::types::{
+    AccountId, Balance, BlockHeightDelta, Gas, GasWeight, StorageUsage,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -67,6 +69,7 @@ pub enum MockAction {
     YieldCreate {
         data_id: CryptoHash,
         receiver_id: AccountId,
+        timeout_length_in_blocks: BlockHeightDelta,
     },
     YieldResume {
         data_id: CryptoHash,
@@ -78,7 +81,16 @@ pub enum MockAction {
 /// Emulates the trie and the mock handling code.
 pub struct MockedExternal {
     pub fake_trie: HashMap<Vec<u8>, Vec<u8>>,
+    /// Records of storage namespaces, keyed by namespace and key.
+    pub fake_ns_trie: HashMap<(Vec<u8>, Vec<u8>), Vec<u8>>,
+    pub ns_usage: HashMap<Vec<u8>, StorageUsage>,
     pub validators: HashMap<AccountId, Balance>,
+    /// Outcomes of view calls, keyed by receiver and method name. Calls to
+    /// anything else fail without burning gas.
+    pub view_calls: HashMap<(AccountId, String), ViewCallOutcome>,
+    pub view_call_depth: u64,
+    /// Host functions called by the contract, recorded only when set.
+    pub traced_host_functions: Option<Vec<&'static str>>,
     pub action_log: Vec<MockAction>,
     pub code: Option<std::sync::Arc<ContractCode>>,
     pub code_hash: CryptoHash,
@@ -149,6 +161,41 @@ impl External for MockedExternal {
         Ok(self.fake_trie.contains_key(key))
     }
 
+    fn storage_ns_set(&mut self, namespace: &[u8], key: &[u8], value: &[u8]) -> Result<()> {
+        self.fake_ns_trie.insert((namespace.to_vec(), key.to_vec()), value.to_vec());
+        Ok(())
+    }
+
+    fn storage_ns_get(
+        &self,
+        namespace: &[u8],
+        key: &[u8],
+        _mode: StorageGetMode,
+    ) -> Result<Option<Box<dyn ValuePtr>>> {
+        Ok(self
+            .fake_ns_trie
+            .get(&(namespace.to_vec(), key.to_vec()))
+            .map(|value| Box::new(MockedValuePtr { value: value.clone() }) as Box<_>))
+    }
+
+    fn storage_ns_remove(&mut self, namespace: &[u8], key: &[u8]) -> Result<()> {
+        self.fake_ns_trie.remove(&(namespace.to_vec(), key.to_vec()));
+        Ok(())
+    }
+
+    fn storage_ns_usage(&self, namespace: &[u8]) -> Result<StorageUsage> {
+        Ok(self.ns_usage.get(namespace).copied().unwrap_or(0))
+    }
+
+    fn set_storage_ns_usage(&mut self, namespace: &[u8], usage: StorageUsage) -> Result<()> {
+        if usage == 0 {
+            self.ns_usage.remove(namespace);
+        } else {
+            self.ns_usage.insert(namespace.to_vec(), usage);
+        }
+        Ok(())
+    }
+
     fn generate_data_id(&mut self) -> CryptoHash {
         // Generates some hash for the data ID to receive data. This hash should not be functionally
         // used in any mocked contexts.
@@ -173,6 +220,36 @@ impl External for MockedExternal {
         Ok(self.validators.values().sum())
     }
 
+    fn view_call(
+        &mut self,
+        receiver_id: &AccountId,
+        method_name: &str,
+        _args: &[u8],
+        gas: Gas,
+    ) -> Result<ViewCallOutcome> {
+        match self.view_calls.get(&(receiver_id.clone(), method_name.to_string())) {
+            Some(outcome) if outcome.burnt_gas <= gas => Ok(outcome.clone()),
+            Some(_) => {
+                Ok(ViewCallOutcome { return_data: None, burnt_gas: gas, compute_usage: gas })
+            }
+            None => Ok(ViewCallOutcome { return_data: None, burnt_gas: 0, compute_usage: 0 }),
+        }
+    }
+
+    fn view_call_depth(&self) -> u64 {
+        self.view_call_depth
+    }
+
+    fn traces_host_functions(&self) -> bool {
+        self.traced_host_functions.is_some()
+    }
+
+    fn trace_host_function(&mut self, name: &'static str) {
+        if let Some(traced) = &mut self.traced_host_functions {
+            traced.push(name);
+        }
+    }
+
     fn create_action_receipt(
         &mut self,
         receipt_indices: Vec<ReceiptIndex>,
@@ -186,10 +263,15 @@ impl External for MockedExternal {
     fn create_promise_yield_receipt(
         &mut self,
         receiver_id: AccountId,
+        timeout_length_in_blocks: BlockHeightDelta,
     ) -> Result<(ReceiptIndex, CryptoHash), crate::logic::VMLogicError> {
         let index = self.action_log.len();
         let data_id = self.generate_data_id();
-        self.action_log.push(MockAction::YieldCreate { data_id, receiver_id });
+        self.action_log.push(MockAction::YieldCreate {
+            data_id,
+            receiver_id,
+            timeout_length_in_blocks,
+        });
         Ok((index as u64, data_id))
     }
 
@@ -14,11 +14,11 @@ pub mod types;
 mod utils;
 mod vmstate;
 
-pub use context::VMContext;
+pub use context::{RecentBlockHeader, VMContext, NUM_RECENT_BLOCK_HEADERS};
 pub use dependencies::{External, MemSlice, MemoryLike, TrieNodesCount, ValuePtr};
 pub use errors::{HostError, VMLogicError};
 pub use gas_counter::{with_ext_cost_counter, GasCounter};
 pub use logic::{ExecutionResultState, VMLogic, VMOutcome};
 pub use near_parameters::vm::{Config, ContractPrepareVersion, LimitConfig, StorageGetMode};
 pub use near_primitives_core::types::ProtocolVersion;
-pub use types::ReturnData;
+pub use types::{ReturnData, ViewCallOutcome};
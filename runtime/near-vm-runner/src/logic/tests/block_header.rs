@@ -0,0 +1,50 @@
+use crate::logic::tests::helpers::*;
+use crate::logic::tests::vm_logic_builder::VMLogicBuilder;
+use crate::logic::RecentBlockHeader;
+use crate::map;
+use near_parameters::ExtCosts;
+use near_primitives_core::hash::hash;
+
+fn recent_block_header(height: u64) -> RecentBlockHeader {
+    RecentBlockHeader {
+        height,
+        hash: hash(&height.to_le_bytes()),
+        timestamp: height * 1_000_000_000,
+        epoch_id: hash(b"epoch"),
+    }
+}
+
+#[test]
+fn test_block_header() {
+    let mut logic_builder = VMLogicBuilder::default();
+    // Height 8 was skipped.
+    logic_builder.context.recent_block_headers =
+        [9, 7, 6].into_iter().map(recent_block_header).collect();
+    let mut logic = logic_builder.build();
+
+    assert_eq!(logic.block_header(7, 0), Ok(1));
+    let header = recent_block_header(7);
+    let mut want = header.hash.as_ref().to_vec();
+    want.extend_from_slice(&header.timestamp.to_le_bytes());
+    want.extend_from_slice(header.epoch_id.as_ref());
+    logic.assert_read_register(&want, 0);
+
+    assert_costs(map! {
+        ExtCosts::base: 2,
+        ExtCosts::block_header_base: 1,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: 72,
+        ExtCosts::read_register_base: 1,
+        ExtCosts::read_register_byte: 72,
+        ExtCosts::write_memory_base: 1,
+        ExtCosts::write_memory_byte: 72,
+    });
+
+    for height in [8, 5, 10] {
+        assert_eq!(logic.block_header(height, 1), Ok(0));
+        assert_costs(map! {
+            ExtCosts::base: 1,
+            ExtCosts::block_header_base: 1,
+        });
+    }
+}
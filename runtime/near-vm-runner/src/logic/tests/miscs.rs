@@ -37,6 +37,42 @@ fn test_sha256() {
     });
 }
 
+#[test]
+fn test_random_seed_with_domain() {
+    use sha2::Digest;
+
+    let mut logic_builder = VMLogicBuilder::default();
+    let seed = logic_builder.context.random_seed.clone();
+    let mut logic = logic_builder.build();
+
+    let domain = logic.internal_mem_write(b"lottery");
+    logic.random_seed_with_domain(domain.len, domain.ptr, 0).unwrap();
+    let value = sha2::Sha256::digest([seed.as_slice(), b"lottery"].concat());
+    logic.assert_read_register(value.as_slice(), 0);
+    assert_costs(map! {
+        ExtCosts::base: 2,
+        ExtCosts::read_memory_base: 1,
+        ExtCosts::read_memory_byte: domain.len,
+        ExtCosts::write_memory_base: 1,
+        ExtCosts::write_memory_byte: 32,
+        ExtCosts::read_register_base: 1,
+        ExtCosts::read_register_byte: 32,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: 32,
+        ExtCosts::sha256_base: 1,
+        ExtCosts::sha256_byte: seed.len() as u64 + domain.len,
+    });
+
+    // The same domain gives the same value, other domains give independent values.
+    logic.random_seed_with_domain(domain.len, domain.ptr, 1).unwrap();
+    logic.assert_read_register(value.as_slice(), 1);
+    let other_domain = logic.internal_mem_write(b"raffle");
+    logic.random_seed_with_domain(other_domain.len, other_domain.ptr, 2).unwrap();
+    let other_value = sha2::Sha256::digest([seed.as_slice(), b"raffle"].concat());
+    logic.assert_read_register(other_value.as_slice(), 2);
+    assert_ne!(value, other_value);
+}
+
 #[test]
 fn test_keccak256() {
     let mut logic_builder = VMLogicBuilder::default();
@@ -1,4 +1,5 @@
 mod alt_bn128;
+mod block_header;
 mod bls12381;
 mod context;
 mod ed25519_verify;
@@ -7,10 +8,13 @@ pub(crate) mod helpers;
 mod iterators;
 mod logs;
 mod miscs;
+mod p256_verify;
 mod promises;
 mod registers;
+mod storage_ns;
 mod storage_read_write;
 mod storage_usage;
+mod view_call;
 mod view_method;
 mod vm_logic_builder;
 
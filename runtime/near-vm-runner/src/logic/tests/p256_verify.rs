@@ -0,0 +1,139 @@
+use crate::logic::tests::helpers::*;
+use crate::logic::tests::vm_logic_builder::VMLogicBuilder;
+use crate::logic::HostError;
+use crate::logic::VMLogicError;
+use crate::map;
+use near_parameters::ExtCosts;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use std::collections::HashMap;
+
+const MESSAGE: &[u8] = b"authenticator data and client data hash";
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_slice(&[7; 32]).unwrap()
+}
+
+fn signature() -> Vec<u8> {
+    let signature: Signature = signing_key().sign(MESSAGE);
+    signature.to_bytes().to_vec()
+}
+
+fn public_key(compress: bool) -> Vec<u8> {
+    VerifyingKey::from(&signing_key()).to_encoded_point(compress).as_bytes().to_vec()
+}
+
+#[track_caller]
+fn check_p256_verify(
+    signature: &[u8],
+    message: &[u8],
+    public_key: &[u8],
+    want: Result<u64, HostError>,
+    want_costs: HashMap<ExtCosts, u64>,
+) {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+
+    let signature_ptr = logic.internal_mem_write(signature).ptr;
+    let message_ptr = logic.internal_mem_write(message).ptr;
+    let public_key_ptr = logic.internal_mem_write(public_key).ptr;
+
+    let result = logic.p256_verify(
+        signature.len() as u64,
+        signature_ptr,
+        message.len() as u64,
+        message_ptr,
+        public_key.len() as u64,
+        public_key_ptr,
+    );
+
+    let want = want.map_err(VMLogicError::HostError);
+    assert_eq!(want, result);
+    assert_costs(want_costs);
+}
+
+#[test]
+fn test_p256_verify_behavior_and_errors() {
+    let message_len = MESSAGE.len() as u64;
+    for public_key in [public_key(true), public_key(false)] {
+        check_p256_verify(
+            &signature(),
+            MESSAGE,
+            &public_key,
+            Ok(1),
+            map! {
+                ExtCosts::read_memory_byte: 64 + message_len + public_key.len() as u64,
+                ExtCosts::read_memory_base: 3,
+                ExtCosts::p256_verify_base: 1,
+                ExtCosts::p256_verify_byte: message_len,
+            },
+        );
+    }
+
+    let public_key = public_key(true);
+    check_p256_verify(
+        &signature(),
+        b"another message",
+        &public_key,
+        Ok(0),
+        map! {
+            ExtCosts::read_memory_byte: 64 + 15 + 33,
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::p256_verify_base: 1,
+            ExtCosts::p256_verify_byte: 15,
+        },
+    );
+
+    // `r` and `s` must both be non-zero.
+    check_p256_verify(
+        &[0; 64],
+        MESSAGE,
+        &public_key,
+        Ok(0),
+        map! {
+            ExtCosts::read_memory_byte: 64,
+            ExtCosts::read_memory_base: 1,
+            ExtCosts::p256_verify_base: 1,
+        },
+    );
+
+    // Not a point on the curve.
+    let mut forged_public_key = public_key.clone();
+    forged_public_key[0] = 5;
+    check_p256_verify(
+        &signature(),
+        MESSAGE,
+        &forged_public_key,
+        Ok(0),
+        map! {
+            ExtCosts::read_memory_byte: 64 + message_len + 33,
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::p256_verify_base: 1,
+            ExtCosts::p256_verify_byte: message_len,
+        },
+    );
+
+    check_p256_verify(
+        &signature()[1..],
+        MESSAGE,
+        &public_key,
+        Err(HostError::P256VerifyInvalidInput { msg: "invalid signature length".to_string() }),
+        map! {
+            ExtCosts::read_memory_byte: 63,
+            ExtCosts::read_memory_base: 1,
+            ExtCosts::p256_verify_base: 1,
+        },
+    );
+    check_p256_verify(
+        &signature(),
+        MESSAGE,
+        &public_key[1..],
+        Err(HostError::P256VerifyInvalidInput { msg: "invalid public key length".to_string() }),
+        map! {
+            ExtCosts::read_memory_byte: 64 + message_len + 32,
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::p256_verify_base: 1,
+            ExtCosts::p256_verify_byte: message_len,
+        },
+    );
+}
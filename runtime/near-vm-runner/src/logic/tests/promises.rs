@@ -1,7 +1,8 @@
-use crate::logic::mocks::mock_external::MockedExternal;
+use crate::logic::mocks::mock_external::{MockAction, MockedExternal};
 use crate::logic::tests::helpers::*;
 use crate::logic::tests::vm_logic_builder::VMLogicBuilder;
 use crate::logic::types::PromiseResult;
+use crate::logic::HostError;
 
 use near_crypto::PublicKey;
 use serde_json;
@@ -630,3 +631,48 @@ fn test_promise_batch_then() {
         ]"#]]
     .assert_eq(&serde_json::to_string_pretty(&vm_receipts(&logic_builder.ext)).unwrap());
 }
+
+#[test]
+fn test_promise_yield_create_with_timeout() {
+    let mut logic_builder = VMLogicBuilder::default();
+    logic_builder.config.limit_config.yield_timeout_length_in_blocks = 200;
+    logic_builder.config.limit_config.max_yield_timeout_length_in_blocks = 1_000;
+    let mut logic = logic_builder.build();
+    let method_name = logic.internal_mem_write(b"callback");
+
+    logic
+        .promise_yield_create(method_name.len, method_name.ptr, 0, 0, 0, 1, 0)
+        .expect("should create a yield with the default timeout");
+    logic
+        .promise_yield_create_with_timeout(method_name.len, method_name.ptr, 0, 0, 0, 1, 1_000, 0)
+        .expect("should create a yield with the maximum timeout");
+    for timeout in [0, 1_001] {
+        assert_eq!(
+            logic.promise_yield_create_with_timeout(
+                method_name.len,
+                method_name.ptr,
+                0,
+                0,
+                0,
+                1,
+                timeout,
+                0
+            ),
+            Err(HostError::YieldTimeoutLength { length: timeout, limit: 1_000 }.into())
+        );
+    }
+    drop(logic);
+
+    let timeouts: Vec<_> = logic_builder
+        .ext
+        .action_log
+        .iter()
+        .filter_map(|action| match action {
+            MockAction::YieldCreate { timeout_length_in_blocks, .. } => {
+                Some(*timeout_length_in_blocks)
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(timeouts, vec![200, 1_000]);
+}
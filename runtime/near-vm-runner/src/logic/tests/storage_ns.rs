@@ -0,0 +1,150 @@
+use crate::logic::tests::helpers::reset_costs_counter;
+use crate::logic::tests::vm_logic_builder::VMLogicBuilder;
+use crate::logic::{with_ext_cost_counter, External, HostError, StorageGetMode, VMLogicError};
+use near_parameters::ExtCosts;
+
+#[test]
+fn test_storage_ns_write_read_remove() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+    let ns = logic.internal_mem_write(b"ns");
+    let key = logic.internal_mem_write(b"foo");
+    let val = logic.internal_mem_write(b"bar");
+
+    assert_eq!(
+        logic.storage_ns_write(ns.len, ns.ptr, key.len, key.ptr, val.len, val.ptr, 0),
+        Ok(0)
+    );
+    // Namespaced records don't show up in the regular storage of the account.
+    assert_eq!(logic.storage_read(key.len, key.ptr, 0), Ok(0));
+
+    assert_eq!(logic.storage_ns_read(ns.len, ns.ptr, key.len, key.ptr, 0), Ok(1));
+    logic.assert_read_register(b"bar", 0);
+
+    let other_ns = logic.internal_mem_write(b"other");
+    assert_eq!(logic.storage_ns_read(other_ns.len, other_ns.ptr, key.len, key.ptr, 0), Ok(0));
+
+    assert_eq!(logic.storage_ns_remove(ns.len, ns.ptr, key.len, key.ptr, 1), Ok(1));
+    logic.assert_read_register(b"bar", 1);
+    assert_eq!(logic.storage_ns_read(ns.len, ns.ptr, key.len, key.ptr, 0), Ok(0));
+    assert_eq!(logic.storage_ns_remove(ns.len, ns.ptr, key.len, key.ptr, 1), Ok(0));
+}
+
+#[test]
+fn test_storage_ns_usage() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let data_record_cost = logic_builder.fees_config.storage_usage_config.num_extra_bytes_record;
+    let mut logic = logic_builder.build();
+    let ns = logic.internal_mem_write(b"ns");
+    let key = logic.internal_mem_write(b"foo");
+    let val = logic.internal_mem_write(b"bar");
+    let long_val = logic.internal_mem_write(b"barbaz");
+
+    logic
+        .storage_ns_write(ns.len, ns.ptr, key.len, key.ptr, val.len, val.ptr, 0)
+        .expect("storage write ok");
+    let record_cost = data_record_cost + ns.len + key.len + val.len;
+    let usage_record_cost = data_record_cost + ns.len + 8;
+    assert_eq!(logic.storage_ns_usage(ns.len, ns.ptr), Ok(record_cost));
+    assert_eq!(logic.storage_usage(), Ok(record_cost + usage_record_cost));
+
+    logic
+        .storage_ns_write(ns.len, ns.ptr, key.len, key.ptr, long_val.len, long_val.ptr, 0)
+        .expect("storage write ok");
+    let record_cost = record_cost + long_val.len - val.len;
+    assert_eq!(logic.storage_ns_usage(ns.len, ns.ptr), Ok(record_cost));
+    assert_eq!(logic.storage_usage(), Ok(record_cost + usage_record_cost));
+
+    logic.storage_ns_remove(ns.len, ns.ptr, key.len, key.ptr, 0).expect("storage remove ok");
+    assert_eq!(logic.storage_ns_usage(ns.len, ns.ptr), Ok(0));
+    assert_eq!(logic.storage_usage(), Ok(0));
+    assert_eq!(logic_builder.ext.storage_ns_usage(b"ns"), Ok(0));
+    assert!(logic_builder.ext.ns_usage.is_empty());
+}
+
+#[test]
+fn test_storage_ns_read_with_register() {
+    let mut logic_builder = VMLogicBuilder::default();
+    logic_builder.ext.storage_ns_set(b"ns", b"foo", b"bar").unwrap();
+    let mut logic = logic_builder.build();
+
+    logic.wrapped_internal_write_register(1, b"ns").unwrap();
+    logic.wrapped_internal_write_register(2, b"foo").unwrap();
+
+    assert_eq!(logic.storage_ns_read(u64::MAX, 1, u64::MAX, 2, 0), Ok(1));
+    logic.assert_read_register(b"bar", 0);
+    assert!(!logic_builder.ext.storage_has_key(b"foo", StorageGetMode::Trie).unwrap());
+}
+
+#[test]
+fn test_storage_ns_namespace_length_exceeded() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let limit = 8;
+    logic_builder.config.limit_config.max_length_storage_namespace = limit;
+    let mut logic = logic_builder.build();
+    let ns = logic.internal_mem_write(&[b'n'; 9]);
+    let key = logic.internal_mem_write(b"foo");
+    let val = logic.internal_mem_write(b"bar");
+
+    let want =
+        Err(VMLogicError::HostError(HostError::NamespaceLengthExceeded { length: 9, limit }));
+    assert_eq!(logic.storage_ns_write(ns.len, ns.ptr, key.len, key.ptr, val.len, val.ptr, 0), want);
+    assert_eq!(logic.storage_ns_read(ns.len, ns.ptr, key.len, key.ptr, 0), want);
+    assert_eq!(logic.storage_ns_remove(ns.len, ns.ptr, key.len, key.ptr, 0), want);
+    assert_eq!(logic.storage_ns_usage(ns.len, ns.ptr), want);
+}
+
+#[test]
+fn test_storage_ns_prohibited_in_view() {
+    let mut logic_builder = VMLogicBuilder::view();
+    let mut logic = logic_builder.build();
+    let ns = logic.internal_mem_write(b"ns");
+    let key = logic.internal_mem_write(b"foo");
+    let val = logic.internal_mem_write(b"bar");
+
+    assert_eq!(
+        logic.storage_ns_write(ns.len, ns.ptr, key.len, key.ptr, val.len, val.ptr, 0),
+        Err(VMLogicError::HostError(HostError::ProhibitedInView {
+            method_name: "storage_ns_write".to_string()
+        }))
+    );
+    assert_eq!(
+        logic.storage_ns_remove(ns.len, ns.ptr, key.len, key.ptr, 0),
+        Err(VMLogicError::HostError(HostError::ProhibitedInView {
+            method_name: "storage_ns_remove".to_string()
+        }))
+    );
+    assert_eq!(logic.storage_ns_read(ns.len, ns.ptr, key.len, key.ptr, 0), Ok(0));
+}
+
+#[test]
+fn test_storage_ns_usage_update_is_charged() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+    let ns = logic.internal_mem_write(b"ns");
+    let key = logic.internal_mem_write(b"foo");
+    let val = logic.internal_mem_write(b"bar");
+
+    reset_costs_counter();
+    logic
+        .storage_ns_write(ns.len, ns.ptr, key.len, key.ptr, val.len, val.ptr, 0)
+        .expect("storage write ok");
+    // The record and the usage record of the namespace are both written, and the usage record is
+    // read before being updated.
+    with_ext_cost_counter(|cc| {
+        assert_eq!(cc[&ExtCosts::storage_write_base], 2);
+        assert_eq!(cc[&ExtCosts::storage_write_key_byte], 2 * ns.len + key.len);
+        assert_eq!(cc[&ExtCosts::storage_write_value_byte], val.len + 8);
+        assert_eq!(cc[&ExtCosts::storage_read_base], 1);
+        assert_eq!(cc[&ExtCosts::storage_read_key_byte], ns.len);
+        assert_eq!(cc[&ExtCosts::storage_read_value_byte], 8);
+    });
+
+    reset_costs_counter();
+    logic.storage_ns_remove(ns.len, ns.ptr, key.len, key.ptr, 0).expect("storage remove ok");
+    with_ext_cost_counter(|cc| {
+        assert_eq!(cc[&ExtCosts::storage_remove_base], 1);
+        assert_eq!(cc[&ExtCosts::storage_write_base], 1);
+        assert_eq!(cc[&ExtCosts::storage_read_base], 1);
+    });
+}
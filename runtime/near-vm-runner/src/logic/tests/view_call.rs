@@ -0,0 +1,137 @@
+use crate::logic::tests::vm_logic_builder::VMLogicBuilder;
+use crate::logic::{HostError, VMLogicError, ViewCallOutcome};
+
+fn set_view_call(builder: &mut VMLogicBuilder, method_name: &str, outcome: ViewCallOutcome) {
+    builder.ext.view_calls.insert(("callee.near".parse().unwrap(), method_name.into()), outcome);
+}
+
+#[test]
+fn test_view_call() {
+    let mut logic_builder = VMLogicBuilder::free();
+    set_view_call(
+        &mut logic_builder,
+        "get_value",
+        ViewCallOutcome {
+            return_data: Some(b"42".to_vec()),
+            burnt_gas: 1_000,
+            compute_usage: 1_000,
+        },
+    );
+    let mut logic = logic_builder.build();
+    let account_id = logic.internal_mem_write(b"callee.near");
+    let method_name = logic.internal_mem_write(b"get_value");
+    let args = logic.internal_mem_write(b"{}");
+
+    let res = logic.view_call(
+        account_id.len,
+        account_id.ptr,
+        method_name.len,
+        method_name.ptr,
+        args.len,
+        args.ptr,
+        10_000,
+        0,
+    );
+    assert_eq!(res, Ok(1));
+    logic.assert_read_register(b"42", 0);
+    // The gas burnt by the called contract is burnt by the caller.
+    assert_eq!(logic.compute_outcome().burnt_gas, 1_000);
+}
+
+#[test]
+fn test_view_call_failed() {
+    let mut logic_builder = VMLogicBuilder::free();
+    set_view_call(
+        &mut logic_builder,
+        "panic",
+        ViewCallOutcome { return_data: None, burnt_gas: 1_000, compute_usage: 1_000 },
+    );
+    let mut logic = logic_builder.build();
+    let account_id = logic.internal_mem_write(b"callee.near");
+    let method_name = logic.internal_mem_write(b"panic");
+    let args = logic.internal_mem_write(b"");
+
+    let res = logic.view_call(
+        account_id.len,
+        account_id.ptr,
+        method_name.len,
+        method_name.ptr,
+        args.len,
+        args.ptr,
+        10_000,
+        0,
+    );
+    assert_eq!(res, Ok(0));
+    assert_eq!(logic.compute_outcome().burnt_gas, 1_000);
+}
+
+#[test]
+fn test_view_call_compute_usage() {
+    let mut logic_builder = VMLogicBuilder::free();
+    set_view_call(
+        &mut logic_builder,
+        "read_storage",
+        ViewCallOutcome { return_data: Some(vec![]), burnt_gas: 1_000, compute_usage: 5_000 },
+    );
+    let mut logic = logic_builder.build();
+    let account_id = logic.internal_mem_write(b"callee.near");
+    let method_name = logic.internal_mem_write(b"read_storage");
+
+    let res = logic.view_call(
+        account_id.len,
+        account_id.ptr,
+        method_name.len,
+        method_name.ptr,
+        0,
+        0,
+        10_000,
+        0,
+    );
+    assert_eq!(res, Ok(1));
+    // The compute usage of the called contract is charged in full, not only its gas.
+    let outcome = logic.compute_outcome();
+    assert_eq!(outcome.burnt_gas, 1_000);
+    assert_eq!(outcome.compute_usage, 5_000);
+}
+
+#[test]
+fn test_view_call_empty_method_name() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+    let account_id = logic.internal_mem_write(b"callee.near");
+    let method_name = logic.internal_mem_write(b"");
+
+    let res = logic.view_call(
+        account_id.len,
+        account_id.ptr,
+        method_name.len,
+        method_name.ptr,
+        0,
+        0,
+        10_000,
+        0,
+    );
+    assert_eq!(res, Err(VMLogicError::HostError(HostError::EmptyMethodName)));
+}
+
+#[test]
+fn test_view_call_depth_exceeded() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let limit = logic_builder.config.limit_config.max_view_call_depth;
+    logic_builder.ext.view_call_depth = limit;
+    let mut logic = logic_builder.build();
+    let account_id = logic.internal_mem_write(b"callee.near");
+    let method_name = logic.internal_mem_write(b"get_value");
+
+    let res = logic.view_call(
+        account_id.len,
+        account_id.ptr,
+        method_name.len,
+        method_name.ptr,
+        0,
+        0,
+        10_000,
+        0,
+    );
+    assert_eq!(res, Err(VMLogicError::HostError(HostError::ViewCallDepthExceeded { limit })));
+}
@@ -81,6 +81,7 @@ fn get_context() -> VMContext {
         attached_deposit: 10,
         prepaid_gas: 10u64.pow(14),
         random_seed: vec![0, 1, 2],
+        recent_block_headers: Default::default(),
         view_config: None,
         output_data_receivers: vec![],
     }
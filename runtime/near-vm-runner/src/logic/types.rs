@@ -37,3 +37,14 @@ pub enum PromiseResult {
     Successful(Vec<u8>),
     Failed,
 }
+
+/// Outcome of a synchronous view call into another contract.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ViewCallOutcome {
+    /// Data returned by the called method, or `None` if the call failed.
+    pub return_data: Option<Vec<u8>>,
+    /// Gas burnt by the called contract.
+    pub burnt_gas: Gas,
+    /// Compute usage of the called contract, which can exceed `burnt_gas`.
+    pub compute_usage: Compute,
+}
@@ -164,6 +164,8 @@ impl NearVM {
     ) -> Result<UniversalExecutable, CompilationError> {
         let _span = tracing::debug_span!(target: "vm", "NearVM::compile_uncached").entered();
         let start = std::time::Instant::now();
+        prepare::analyze_contract(code.code(), &self.config.limit_config)
+            .map_err(CompilationError::StaticAnalysisError)?;
         let prepared_code = prepare::prepare_contract(code.code(), &self.config, VMKind::NearVm)
             .map_err(CompilationError::PrepareError)?;
 
@@ -772,7 +774,12 @@ impl<'e, 'l, 'lr> Resolver for NearVmImports<'e, 'l, 'lr> {
                             // lifetime and so it is safe to dereference the `env` pointer which is
                             // known to be derived from a valid `&'vmlogic mut VMLogic<'_>` in the
                             // first place.
-                            unsafe { (*env).$func( $( $arg_name, )* ) }
+                            unsafe {
+                                if TRACE {
+                                    (*env).trace_host_function(stringify!($name));
+                                }
+                                (*env).$func( $( $arg_name, )* )
+                            }
                         }));
                         // We want to ensure that the only kind of error that host function calls
                         // return are VMLogicError. This is important because we later attempt to
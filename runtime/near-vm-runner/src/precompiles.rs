@@ -0,0 +1,218 @@
+//! Native implementations of well-known contracts.
+//!
+//! Some contracts are deployed to many accounts and spend most of their gas on
+//! a few host functions, e.g. to verify signatures or hash data. When the
+//! `precompiled_contracts` runtime feature is enabled, the methods of the
+//! contracts whose code hash is registered here are executed by Rust code
+//! rather than by the WASM runtime.
+//!
+//! The native implementation calls the same host functions with the same
+//! arguments as the WASM contract, so both return the same value and burn the
+//! same gas for host functions. Instead of loading the contract and paying for
+//! its WASM instructions, a call to a precompiled contract only pays the fixed
+//! `contract_loading_base` cost.
+//!
+//! The WASM contracts are in `res/precompiles`, and the tests run both
+//! implementations on the same inputs to check that they agree.
+
+use crate::logic::errors::{FunctionCallError, MethodResolveError, VMLogicError, WasmTrap};
+use crate::logic::{
+    ExecutionResultState, External, GasCounter, MemSlice, MemoryLike, VMContext, VMLogic, VMOutcome,
+};
+use crate::runner::VMResult;
+use near_parameters::vm::Config;
+use near_parameters::{ExtCosts, RuntimeFeesConfig};
+use near_primitives_core::hash::CryptoHash;
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock};
+
+/// A contract executed natively.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::EnumIter)]
+pub(crate) enum Precompile {
+    /// `batch_verify` takes a sequence of records, each made of a 64 byte
+    /// signature, a 32 byte public key, the length of the message as a 4 byte
+    /// little endian integer and the message. It returns 1 if all signatures
+    /// are valid and 0 otherwise.
+    Ed25519BatchVerify,
+    /// `keccak256` returns the keccak256 hash of its input.
+    Keccak256,
+}
+
+static PRECOMPILES: LazyLock<Vec<(CryptoHash, Precompile)>> = LazyLock::new(|| {
+    <Precompile as strum::IntoEnumIterator>::iter()
+        .map(|precompile| (CryptoHash::from_str(precompile.code_hash()).unwrap(), precompile))
+        .collect()
+});
+
+impl Precompile {
+    /// Returns the precompiled contract with the given code hash, if any.
+    pub(crate) fn find(code_hash: &CryptoHash) -> Option<Self> {
+        PRECOMPILES.iter().find(|(hash, _)| hash == code_hash).map(|&(_, precompile)| precompile)
+    }
+
+    /// Hash of the WASM code of the contract.
+    pub(crate) fn code_hash(self) -> &'static str {
+        match self {
+            Self::Ed25519BatchVerify => "E7ZAJG7SoRk7MKEFrr7xJpXsAUvpJAyoqRBZpHxSVBXF",
+            Self::Keccak256 => "FL3UWZyufbN3Ys6iyYokdSdaf1YbwYojtfrtieLJMHT9",
+        }
+    }
+
+    pub(crate) fn method_name(self) -> &'static str {
+        match self {
+            Self::Ed25519BatchVerify => "batch_verify",
+            Self::Keccak256 => "keccak256",
+        }
+    }
+
+    fn execute(self, logic: &mut VMLogic, input: &[u8]) -> Result<(), Abort> {
+        match self {
+            Self::Ed25519BatchVerify => ed25519_batch_verify(logic, input),
+            Self::Keccak256 => {
+                logic.input(0)?;
+                logic.keccak256(u64::MAX, 0, 1)?;
+                logic.value_return(u64::MAX, 1)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Length of the fixed size part of a record of `batch_verify`.
+const ED25519_RECORD_HEADER_LEN: usize = 100;
+
+fn ed25519_batch_verify(logic: &mut VMLogic, input: &[u8]) -> Result<(), Abort> {
+    // Read the input into the memory like the WASM contract does, so that the
+    // host functions are charged the same.
+    logic.input(0)?;
+    let input_len = logic.register_len(0)?;
+    logic.read_register(0, 0)?;
+    let mut all_valid = 1;
+    let mut offset = 0;
+    while offset < input.len() {
+        let Some(header) = input.get(offset..offset + ED25519_RECORD_HEADER_LEN) else {
+            return Err(Abort::Trap(WasmTrap::Unreachable));
+        };
+        let message_len = u32::from_le_bytes(header[96..].try_into().unwrap()) as usize;
+        let message_offset = offset + ED25519_RECORD_HEADER_LEN;
+        if input.len() - message_offset < message_len {
+            return Err(Abort::Trap(WasmTrap::Unreachable));
+        }
+        all_valid &= logic.ed25519_verify(
+            64,
+            offset as u64,
+            message_len as u64,
+            message_offset as u64,
+            32,
+            (offset + 64) as u64,
+        )?;
+        offset = message_offset + message_len;
+    }
+    // The memory holds the bytes 0 and 1 right after the input.
+    logic.value_return(1, input_len + all_valid)?;
+    Ok(())
+}
+
+enum Abort {
+    Logic(VMLogicError),
+    Trap(WasmTrap),
+}
+
+impl From<VMLogicError> for Abort {
+    fn from(err: VMLogicError) -> Self {
+        Self::Logic(err)
+    }
+}
+
+/// Memory of a precompiled contract, holding its input followed by the bytes 0
+/// and 1.
+struct PrecompileMemory(Vec<u8>);
+
+impl PrecompileMemory {
+    fn new(input_len: usize) -> Self {
+        let mut memory = vec![0; input_len + 2];
+        memory[input_len + 1] = 1;
+        Self(memory)
+    }
+
+    fn range(&self, ptr: u64, len: usize) -> Result<std::ops::Range<usize>, ()> {
+        let start = usize::try_from(ptr).map_err(|_| ())?;
+        let end = start.checked_add(len).ok_or(())?;
+        if end <= self.0.len() {
+            Ok(start..end)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl MemoryLike for PrecompileMemory {
+    fn fits_memory(&self, slice: MemSlice) -> Result<(), ()> {
+        self.range(slice.ptr, slice.len()?).map(|_| ())
+    }
+
+    fn view_memory(&self, slice: MemSlice) -> Result<Cow<[u8]>, ()> {
+        Ok(Cow::Borrowed(&self.0[self.range(slice.ptr, slice.len()?)?]))
+    }
+
+    fn read_memory(&self, ptr: u64, buffer: &mut [u8]) -> Result<(), ()> {
+        buffer.copy_from_slice(&self.0[self.range(ptr, buffer.len())?]);
+        Ok(())
+    }
+
+    fn write_memory(&mut self, ptr: u64, buffer: &[u8]) -> Result<(), ()> {
+        let range = self.range(ptr, buffer.len())?;
+        self.0[range].copy_from_slice(buffer);
+        Ok(())
+    }
+}
+
+pub(crate) fn prepare(
+    precompile: Precompile,
+    config: Arc<Config>,
+    gas_counter: GasCounter,
+    method: &str,
+) -> Box<dyn crate::PreparedContract> {
+    Box::new(PreparedPrecompile { precompile, config, gas_counter, method: method.to_string() })
+}
+
+struct PreparedPrecompile {
+    precompile: Precompile,
+    config: Arc<Config>,
+    gas_counter: GasCounter,
+    method: String,
+}
+
+impl crate::PreparedContract for PreparedPrecompile {
+    fn run(
+        self: Box<Self>,
+        ext: &mut dyn External,
+        context: &VMContext,
+        fees_config: Arc<RuntimeFeesConfig>,
+    ) -> VMResult {
+        let PreparedPrecompile { precompile, config, mut gas_counter, method } = *self;
+        let loading = if method.is_empty() {
+            Err(FunctionCallError::MethodResolveError(MethodResolveError::MethodEmptyName))
+        } else if gas_counter.pay_base(ExtCosts::contract_loading_base).is_err() {
+            Err(FunctionCallError::HostError(crate::logic::HostError::GasExceeded))
+        } else if method != precompile.method_name() {
+            Err(FunctionCallError::MethodResolveError(MethodResolveError::MethodNotFound))
+        } else {
+            Ok(())
+        };
+        let result_state = ExecutionResultState::new(context, gas_counter, config);
+        if let Err(err) = loading {
+            return Ok(VMOutcome::abort(result_state, err));
+        }
+        let mut memory = PrecompileMemory::new(context.input.len());
+        let mut logic = VMLogic::new(ext, context, fees_config, result_state, &mut memory);
+        match precompile.execute(&mut logic, &context.input) {
+            Ok(()) => Ok(VMOutcome::ok(logic.result_state)),
+            Err(Abort::Trap(trap)) => {
+                Ok(VMOutcome::abort(logic.result_state, FunctionCallError::WasmTrap(trap)))
+            }
+            Err(Abort::Logic(err)) => Ok(VMOutcome::abort(logic.result_state, err.try_into()?)),
+        }
+    }
+}
@@ -7,6 +7,9 @@ use near_parameters::vm::{Config, VMKind};
 mod prepare_v0;
 mod prepare_v1;
 mod prepare_v2;
+mod static_analysis;
+
+pub use static_analysis::analyze_contract;
 
 /// Loads the given module given in `original_code`, performs some checks on it and
 /// does some preprocessing.
@@ -25,10 +28,16 @@ pub fn prepare_contract(
     kind: VMKind,
 ) -> Result<Vec<u8>, PrepareError> {
     let prepare = config.limit_config.contract_prepare_version;
-    // NearVM => ContractPrepareVersion::V2
+    // NearVM => ContractPrepareVersion::V2 or later
     assert!(
-        (kind != VMKind::NearVm) || (prepare == crate::logic::ContractPrepareVersion::V2),
-        "NearVM only works with contract prepare version V2",
+        (kind != VMKind::NearVm)
+            || matches!(
+                prepare,
+                crate::logic::ContractPrepareVersion::V2
+                    | crate::logic::ContractPrepareVersion::V3
+                    | crate::logic::ContractPrepareVersion::V4
+            ),
+        "NearVM only works with contract prepare version V2 or later",
     );
     let features = crate::features::WasmFeatures::from(prepare);
     match prepare {
@@ -41,12 +50,32 @@ pub fn prepare_contract(
             prepare_v1::validate_contract(original_code, features, config)?;
             prepare_v1::prepare_contract(original_code, config)
         }
-        crate::logic::ContractPrepareVersion::V2 => {
+        crate::logic::ContractPrepareVersion::V2
+        | crate::logic::ContractPrepareVersion::V3
+        | crate::logic::ContractPrepareVersion::V4 => {
             prepare_v2::prepare_contract(original_code, features, config, kind)
         }
     }
 }
 
+/// Returns the contents of the first custom section of `code` named `name`.
+///
+/// Malformed code is treated as having no such section, it is rejected when the
+/// contract is prepared anyway.
+pub fn find_custom_section<'a>(code: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    use finite_wasm::wasmparser as wp;
+    for payload in wp::Parser::new(0).parse_all(code) {
+        match payload {
+            Ok(wp::Payload::CustomSection(reader)) if reader.name() == name => {
+                return Some(reader.data());
+            }
+            Ok(_) => {}
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,6 +172,48 @@ mod tests {
         })
     }
 
+    #[test]
+    fn memory64_and_multi_memory() {
+        let mut config = test_vm_config();
+        config.limit_config.contract_prepare_version = crate::logic::ContractPrepareVersion::V3;
+        // Only the early preparation step handles memories, which is shared by all VMs.
+        let kind = VMKind::NearVm;
+
+        // 64-bit memories are never accepted.
+        let r = parse_and_prepare_wat(&config, kind, r#"(module (memory i64 1))"#);
+        assert_matches!(r, Err(PrepareError::Deserialization));
+        let r = parse_and_prepare_wat(&config, kind, r#"(module (memory 1) (memory i64 1))"#);
+        assert_matches!(r, Err(_));
+
+        // Additional memories are rejected with the default limit of one memory.
+        let r = parse_and_prepare_wat(&config, kind, r#"(module (memory 1) (memory 1))"#);
+        assert_matches!(r, Err(PrepareError::TooManyMemories));
+
+        config.limit_config.max_memories_per_contract = 2;
+        let r = parse_and_prepare_wat(&config, kind, r#"(module (memory 1) (memory 1))"#);
+        assert_matches!(r, Ok(_));
+        let r =
+            parse_and_prepare_wat(&config, kind, r#"(module (memory 1) (memory 1) (memory 1))"#);
+        assert_matches!(r, Err(PrepareError::TooManyMemories));
+
+        // requested initial size exceeds configured maximum
+        let r = parse_and_prepare_wat(&config, kind, r#"(module (memory 1) (memory 2049))"#);
+        assert_matches!(r, Err(PrepareError::Memory));
+    }
+
+    #[test]
+    fn simd() {
+        let simd = r#"(module (func (result i32) i32.const 42 i32x4.splat i32x4.extract_lane 0))"#;
+        let mut config = test_vm_config();
+        config.limit_config.contract_prepare_version = crate::logic::ContractPrepareVersion::V3;
+        let r = parse_and_prepare_wat(&config, VMKind::Wasmtime, simd);
+        assert_matches!(r, Err(PrepareError::Deserialization));
+
+        config.limit_config.contract_prepare_version = crate::logic::ContractPrepareVersion::V4;
+        let r = parse_and_prepare_wat(&config, VMKind::Wasmtime, simd);
+        assert_matches!(r, Ok(_));
+    }
+
     #[test]
     fn imports() {
         let config = test_vm_config();
@@ -174,4 +245,16 @@ mod tests {
             */
         })
     }
+
+    #[test]
+    fn test_find_custom_section() {
+        let wasm = wat::parse_str(
+            r#"(module (@custom "a" "first") (@custom "b" "second") (@custom "a" "third"))"#,
+        )
+        .unwrap();
+        assert_eq!(find_custom_section(&wasm, "a"), Some(&b"first"[..]));
+        assert_eq!(find_custom_section(&wasm, "b"), Some(&b"second"[..]));
+        assert_eq!(find_custom_section(&wasm, "c"), None);
+        assert_eq!(find_custom_section(b"not wasm", "a"), None);
+    }
 }
@@ -92,6 +92,12 @@ impl<'a> PrepareContext<'a> {
                     self.validator
                         .memory_section(&reader)
                         .map_err(|_| PrepareError::Deserialization)?;
+                    if reader.count() > self.config.limit_config.max_memories_per_contract {
+                        return Err(PrepareError::TooManyMemories);
+                    }
+                    if reader.count() > 1 {
+                        self.transform_memory_section(&reader)?;
+                    }
                 }
                 wp::Payload::GlobalSection(reader) => {
                     self.ensure_import_section();
@@ -241,6 +247,31 @@ impl<'a> PrepareContext<'a> {
         }
     }
 
+    /// Keeps the memories following the first one, which is replaced by the imported memory, with
+    /// their maximum size limited by the config. This only happens with multi-memory enabled.
+    fn transform_memory_section(
+        &mut self,
+        reader: &wp::MemorySectionReader,
+    ) -> Result<(), PrepareError> {
+        let max_pages = u64::from(self.config.limit_config.max_memory_pages);
+        let mut new_section = wasm_encoder::MemorySection::new();
+        for memory in reader.clone().into_iter().skip(1) {
+            let memory = memory.map_err(|_| PrepareError::Deserialization)?;
+            if memory.initial > max_pages {
+                return Err(PrepareError::Memory);
+            }
+            new_section.memory(wasm_encoder::MemoryType {
+                minimum: memory.initial,
+                maximum: Some(memory.maximum.map_or(max_pages, |maximum| maximum.min(max_pages))),
+                memory64: false,
+                shared: false,
+                page_size_log2: None,
+            });
+        }
+        new_section.append_to(&mut self.output_code);
+        Ok(())
+    }
+
     fn memory_import(&self) -> wasm_encoder::EntityType {
         wasm_encoder::EntityType::Memory(wasm_encoder::MemoryType {
             minimum: u64::from(self.config.limit_config.initial_memory_pages),
@@ -0,0 +1,139 @@
+//! Static analysis rejecting contracts that are likely to be pathologically
+//! slow to compile, before any preparation work is done on them.
+
+use crate::logic::errors::StaticAnalysisError;
+use crate::logic::LimitConfig;
+use finite_wasm::wasmparser as wp;
+
+/// Checks the contract against the limits of the static analysis that are set
+/// in `limits`:
+///
+/// - number of locals declared by each function, `max_locals_per_function`,
+/// - initial number of elements of each table, `max_table_elements`,
+/// - nesting depth of the blocks of each function, which bounds the control
+///   stack the compiler has to keep, `max_block_nesting_depth`.
+///
+/// The number of functions is limited by `max_functions_number_per_contract`
+/// during preparation. Malformed contracts are let through, so that they are
+/// rejected with the preparation error they have always been rejected with.
+pub fn analyze_contract(code: &[u8], limits: &LimitConfig) -> Result<(), StaticAnalysisError> {
+    if limits.max_locals_per_function.is_none()
+        && limits.max_table_elements.is_none()
+        && limits.max_block_nesting_depth.is_none()
+    {
+        return Ok(());
+    }
+    match analyze(code, limits) {
+        Ok(result) => result,
+        Err(err) => {
+            tracing::trace!(?err, "static analysis was not able to parse the input module");
+            Ok(())
+        }
+    }
+}
+
+fn analyze(
+    code: &[u8],
+    limits: &LimitConfig,
+) -> Result<Result<(), StaticAnalysisError>, wp::BinaryReaderError> {
+    for payload in wp::Parser::new(0).parse_all(code) {
+        match payload? {
+            wp::Payload::TableSection(reader) => {
+                let Some(max_elements) = limits.max_table_elements else { continue };
+                for table in reader {
+                    if u64::from(table?.ty.initial) > max_elements {
+                        return Ok(Err(StaticAnalysisError::TableTooLarge));
+                    }
+                }
+            }
+            wp::Payload::CodeSectionEntry(body) => {
+                if let Some(max_locals) = limits.max_locals_per_function {
+                    let mut locals = 0_u64;
+                    for local in body.get_locals_reader()? {
+                        let (count, _ty) = local?;
+                        locals += u64::from(count);
+                        if locals > max_locals {
+                            return Ok(Err(StaticAnalysisError::TooManyLocalsPerFunction));
+                        }
+                    }
+                }
+                if let Some(max_depth) = limits.max_block_nesting_depth {
+                    let mut depth = 0_u64;
+                    let mut operators = body.get_operators_reader()?;
+                    while !operators.eof() {
+                        match operators.read()? {
+                            wp::Operator::Block { .. }
+                            | wp::Operator::Loop { .. }
+                            | wp::Operator::If { .. } => {
+                                depth += 1;
+                                if depth > max_depth {
+                                    return Ok(Err(StaticAnalysisError::BlocksNestedTooDeep));
+                                }
+                            }
+                            // The last `end` closes the function body itself.
+                            wp::Operator::End => depth = depth.saturating_sub(1),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_vm_config;
+    use assert_matches::assert_matches;
+
+    fn analyze_wat(limits: &LimitConfig, wat: &str) -> Result<(), StaticAnalysisError> {
+        let wasm = wat::parse_str(wat).unwrap();
+        analyze_contract(&wasm, limits)
+    }
+
+    #[test]
+    fn locals_per_function() {
+        let mut limits = test_vm_config().limit_config;
+        limits.max_locals_per_function = Some(3);
+        let r = analyze_wat(&limits, r#"(module (func (local i32 i64 i32)))"#);
+        assert_matches!(r, Ok(()));
+        let r = analyze_wat(&limits, r#"(module (func (local i32 i64 i32 i64)))"#);
+        assert_matches!(r, Err(StaticAnalysisError::TooManyLocalsPerFunction));
+        // The limit applies to each function separately.
+        let r = analyze_wat(&limits, r#"(module (func (local i32 i64)) (func (local i32 i64)))"#);
+        assert_matches!(r, Ok(()));
+    }
+
+    #[test]
+    fn table_elements() {
+        let mut limits = test_vm_config().limit_config;
+        limits.max_table_elements = Some(100);
+        let r = analyze_wat(&limits, r#"(module (table 100 funcref))"#);
+        assert_matches!(r, Ok(()));
+        let r = analyze_wat(&limits, r#"(module (table 101 funcref))"#);
+        assert_matches!(r, Err(StaticAnalysisError::TableTooLarge));
+    }
+
+    #[test]
+    fn block_nesting_depth() {
+        let mut limits = test_vm_config().limit_config;
+        limits.max_block_nesting_depth = Some(2);
+        let r = analyze_wat(&limits, r#"(module (func (block (loop)) (block (block))))"#);
+        assert_matches!(r, Ok(()));
+        let r = analyze_wat(&limits, r#"(module (func (block (loop (block)))))"#);
+        assert_matches!(r, Err(StaticAnalysisError::BlocksNestedTooDeep));
+    }
+
+    #[test]
+    fn no_limits() {
+        let mut limits = test_vm_config().limit_config;
+        limits.max_locals_per_function = None;
+        limits.max_table_elements = None;
+        limits.max_block_nesting_depth = None;
+        let r = analyze_wat(&limits, r#"(module (table 1000000 funcref) (func (block (block))))"#);
+        assert_matches!(r, Ok(()));
+    }
+}
@@ -31,6 +31,9 @@ pub(crate) type VMResult<T = VMOutcome> = Result<T, VMRunnerError>;
 /// module.
 ///
 /// Contract preparation and execution need not to be executed on the same thread.
+///
+/// Contracts with a native implementation are not prepared by the VM, see the
+/// `precompiles` module.
 #[tracing::instrument(target = "vm", level = "debug", "prepare", skip_all, fields(
     code.hash = %contract.hash(),
     method_name,
@@ -45,6 +48,11 @@ pub fn prepare(
     gas_counter: crate::logic::GasCounter,
     method: &str,
 ) -> Box<dyn crate::PreparedContract> {
+    if wasm_config.precompiled_contracts {
+        if let Some(precompile) = crate::precompiles::Precompile::find(&contract.hash()) {
+            return crate::precompiles::prepare(precompile, wasm_config, gas_counter, method);
+        }
+    }
     let vm_kind = wasm_config.vm_kind;
     let runtime = vm_kind
         .runtime(wasm_config)
@@ -1,6 +1,7 @@
 mod cache;
 mod compile_errors;
 mod fuzzers;
+mod precompiles;
 mod regression_tests;
 mod rs_contract;
 mod runtime_errors;
@@ -46,8 +47,12 @@ pub(crate) fn with_vm_variants(
     run(VMKind::Wasmer2);
 
     #[cfg(all(feature = "near_vm", target_arch = "x86_64"))]
-    if cfg.limit_config.contract_prepare_version == near_parameters::vm::ContractPrepareVersion::V2
-    {
+    if matches!(
+        cfg.limit_config.contract_prepare_version,
+        near_parameters::vm::ContractPrepareVersion::V2
+            | near_parameters::vm::ContractPrepareVersion::V3
+            | near_parameters::vm::ContractPrepareVersion::V4
+    ) {
         run(VMKind::NearVm);
     }
 }
@@ -69,6 +74,7 @@ fn create_context(input: Vec<u8>) -> VMContext {
         attached_deposit: 2u128,
         prepaid_gas: 10_u64.pow(14),
         random_seed: vec![0, 1, 2],
+        recent_block_headers: Default::default(),
         view_config: None,
         output_data_receivers: vec![],
     }
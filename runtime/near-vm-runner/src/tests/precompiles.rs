@@ -0,0 +1,99 @@
+use crate::logic::errors::{FunctionCallError, MethodResolveError, WasmTrap};
+use crate::logic::mocks::mock_external::MockedExternal;
+use crate::logic::types::ReturnData;
+use crate::logic::VMOutcome;
+use crate::precompiles::Precompile;
+use crate::tests::{create_context, test_vm_config};
+use crate::ContractCode;
+use ed25519_dalek::Signer;
+use near_parameters::RuntimeFeesConfig;
+use std::sync::Arc;
+
+fn reference_contract(precompile: Precompile) -> ContractCode {
+    let wat = match precompile {
+        Precompile::Ed25519BatchVerify => {
+            include_str!("../../res/precompiles/ed25519_batch_verify.wat")
+        }
+        Precompile::Keccak256 => include_str!("../../res/precompiles/keccak256.wat"),
+    };
+    ContractCode::new(wat::parse_str(wat).unwrap(), None)
+}
+
+fn run(precompile: Precompile, method: &str, input: Vec<u8>, precompiled: bool) -> VMOutcome {
+    let mut config = test_vm_config();
+    config.precompiled_contracts = precompiled;
+    let config = Arc::new(config);
+    let fees = Arc::new(RuntimeFeesConfig::test());
+    let mut ext = MockedExternal::with_code(reference_contract(precompile));
+    let context = create_context(input);
+    let gas_counter = context.make_gas_counter(&config);
+    crate::prepare(&ext, config, None, gas_counter, method)
+        .run(&mut ext, &context, fees)
+        .expect("execution failed")
+}
+
+/// Runs the WASM and the native implementation of `precompile` and checks that
+/// they agree. Returns the outcome of the native implementation.
+#[track_caller]
+fn run_both(precompile: Precompile, method: &str, input: Vec<u8>) -> VMOutcome {
+    let wasm = run(precompile, method, input.clone(), false);
+    let native = run(precompile, method, input, true);
+    assert_eq!(native.return_data, wasm.return_data);
+    assert_eq!(native.aborted, wasm.aborted);
+    assert!(native.burnt_gas <= wasm.burnt_gas, "{} > {}", native.burnt_gas, wasm.burnt_gas);
+    native
+}
+
+fn ed25519_record(seed: u8, message: &[u8], valid: bool) -> Vec<u8> {
+    let signing_key = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+    let signed = if valid { message.to_vec() } else { [message, b"!"].concat() };
+    let mut record = signing_key.sign(&signed).to_bytes().to_vec();
+    record.extend(signing_key.verifying_key().to_bytes());
+    record.extend((message.len() as u32).to_le_bytes());
+    record.extend(message);
+    record
+}
+
+#[test]
+fn test_precompile_code_hashes() {
+    for precompile in <Precompile as strum::IntoEnumIterator>::iter() {
+        let code = reference_contract(precompile);
+        assert_eq!(Precompile::find(code.hash()), Some(precompile), "{precompile:?}");
+    }
+}
+
+#[test]
+fn test_keccak256_precompile() {
+    use sha3::Digest;
+    for input in [vec![], b"hello".to_vec(), vec![7; 10_000]] {
+        let outcome = run_both(Precompile::Keccak256, "keccak256", input.clone());
+        let expected = sha3::Keccak256::digest(&input).to_vec();
+        assert_eq!(outcome.return_data, ReturnData::Value(expected));
+    }
+}
+
+#[test]
+fn test_ed25519_batch_verify_precompile() {
+    let valid = [ed25519_record(1, b"hello", true), ed25519_record(2, &[3; 200], true)].concat();
+    let invalid = [ed25519_record(1, b"hello", true), ed25519_record(2, b"world", false)].concat();
+    for (input, expected) in [(vec![], 1), (valid.clone(), 1), (invalid, 0)] {
+        let outcome = run_both(Precompile::Ed25519BatchVerify, "batch_verify", input);
+        assert_eq!(outcome.return_data, ReturnData::Value(vec![expected]));
+    }
+
+    // Records cut in the middle of the header or of the message.
+    for len in [valid.len() - 1, 50] {
+        let outcome =
+            run_both(Precompile::Ed25519BatchVerify, "batch_verify", valid[..len].to_vec());
+        assert_eq!(outcome.aborted, Some(FunctionCallError::WasmTrap(WasmTrap::Unreachable)));
+    }
+}
+
+#[test]
+fn test_precompile_unknown_method() {
+    let outcome = run_both(Precompile::Keccak256, "sha256", vec![]);
+    assert_eq!(
+        outcome.aborted,
+        Some(FunctionCallError::MethodResolveError(MethodResolveError::MethodNotFound))
+    );
+}
@@ -78,6 +78,32 @@ pub fn test_read_write() {
     });
 }
 
+#[test]
+pub fn test_trace_host_functions() {
+    let config = Arc::new(test_vm_config());
+    let fees = Arc::new(RuntimeFeesConfig::test());
+    with_vm_variants(&config, |vm_kind: VMKind| {
+        let code = test_contract(vm_kind);
+        let mut fake_external = MockedExternal::with_code(code);
+        fake_external.traced_host_functions = Some(vec![]);
+        let context = create_context(encode(&[10u64, 20u64]));
+
+        let runtime = vm_kind.runtime(config.clone()).expect("runtime has not been compiled");
+        let gas_counter = context.make_gas_counter(&config);
+        let result = runtime.prepare(&fake_external, None, gas_counter, "write_key_value").run(
+            &mut fake_external,
+            &context,
+            Arc::clone(&fees),
+        );
+        assert_run_result(result, 0);
+
+        let traced = fake_external.traced_host_functions.unwrap();
+        assert!(traced.contains(&"input"), "{traced:?}");
+        assert!(traced.contains(&"storage_write"), "{traced:?}");
+        assert!(!traced.contains(&"gas"), "{traced:?}");
+    });
+}
+
 macro_rules! def_test_ext {
     ($name:ident, $method:expr, $expected:expr, $input:expr, $validator:expr) => {
         #[test]
@@ -164,7 +190,9 @@ pub fn ext_used_gas() {
         let expected = match config.limit_config.contract_prepare_version {
             crate::logic::ContractPrepareVersion::V0 => [111, 10, 200, 15, 0, 0, 0, 0],
             crate::logic::ContractPrepareVersion::V1 => [111, 10, 200, 15, 0, 0, 0, 0],
-            crate::logic::ContractPrepareVersion::V2 => [27, 180, 237, 15, 0, 0, 0, 0],
+            crate::logic::ContractPrepareVersion::V2
+            | crate::logic::ContractPrepareVersion::V3
+            | crate::logic::ContractPrepareVersion::V4 => [27, 180, 237, 15, 0, 0, 0, 0],
         };
         run_test_ext(Arc::clone(&config), "ext_used_gas", &expected, &[], vec![], vm_kind)
     })
@@ -26,6 +26,7 @@ pub(crate) fn test_builder() -> TestBuilder {
         attached_deposit: 2u128,
         prepaid_gas: 10_u64.pow(14),
         random_seed: vec![0, 1, 2],
+        recent_block_headers: Default::default(),
         view_config: None,
         output_data_receivers: vec![],
     };
@@ -207,8 +208,12 @@ impl TestBuilder {
 
                 // NearVM includes a different contract preparation algorithm, that is not supported on old protocol versions
                 if vm_kind == VMKind::NearVm
-                    && runtime_config.wasm_config.limit_config.contract_prepare_version
-                        != ContractPrepareVersion::V2
+                    && !matches!(
+                        runtime_config.wasm_config.limit_config.contract_prepare_version,
+                        ContractPrepareVersion::V2
+                            | ContractPrepareVersion::V3
+                            | ContractPrepareVersion::V4
+                    )
                 {
                     continue;
                 }
@@ -312,6 +312,8 @@ impl Wasmer0VM {
         code: &ContractCode,
     ) -> Result<wasmer_runtime::Module, CompilationError> {
         let _span = tracing::debug_span!(target: "vm", "Wasmer0VM::compile_uncached").entered();
+        prepare::analyze_contract(code.code(), &self.config.limit_config)
+            .map_err(CompilationError::StaticAnalysisError)?;
         let prepared_code = prepare::prepare_contract(code.code(), &self.config, VMKind::Wasmer0)
             .map_err(CompilationError::PrepareError)?;
         wasmer_runtime::compile(&prepared_code).map_err(|err| match err {
@@ -586,6 +588,9 @@ pub(crate) fn build_imports(
                         tracing::trace_span!(target: "vm::host_function", stringify!($name)).entered()
                     });
                     let logic: &mut VMLogic<'_> = unsafe { &mut *(ctx.data as *mut VMLogic<'_>) };
+                    if TRACE {
+                        logic.trace_host_function(stringify!($name));
+                    }
                     logic.$func( $( $arg_name, )* )
                 }
 
@@ -132,8 +132,10 @@ pub fn get_engine(config: &wasmtime::Config) -> Engine {
 pub(crate) fn default_wasmtime_config(config: &Config) -> wasmtime::Config {
     let features =
         crate::features::WasmFeatures::from(config.limit_config.contract_prepare_version);
+    let stack_limit = config.limit_config.wasmtime_stack_limit;
     let mut config = wasmtime::Config::from(features);
-    config.max_wasm_stack(1024 * 1024 * 1024); // wasm stack metering is implemented by instrumentation, we don't want wasmtime to trap before that
+    // wasm stack metering is implemented by instrumentation, we don't want wasmtime to trap before that
+    config.max_wasm_stack(usize::try_from(stack_limit).unwrap_or(usize::MAX));
     config
 }
 
@@ -155,6 +157,8 @@ impl WasmtimeVM {
     #[tracing::instrument(target = "vm", level = "debug", "WasmtimeVM::compile_uncached", skip_all)]
     fn compile_uncached(&self, code: &ContractCode) -> Result<Vec<u8>, CompilationError> {
         let start = std::time::Instant::now();
+        prepare::analyze_contract(code.code(), &self.config.limit_config)
+            .map_err(CompilationError::StaticAnalysisError)?;
         let prepared_code = prepare::prepare_contract(code.code(), &self.config, VMKind::Wasmtime)
             .map_err(CompilationError::PrepareError)?;
         let serialized = self.engine.precompile_module(&prepared_code).map_err(|err| {
@@ -476,6 +480,9 @@ fn link<'a, 'b>(
                     crate::wasmtime_runner::CALLER.with(|runner_caller| *runner_caller.borrow_mut() = std::mem::transmute(caller));
                 }
                 let logic: &mut VMLogic<'_> = unsafe { &mut *(data as *mut VMLogic<'_>) };
+                if TRACE {
+                    logic.trace_host_function(stringify!($name));
+                }
                 match logic.$func( $( $arg_name as $arg_type, )* ) {
                     Ok(result) => Ok(result as ($( $returns ),* ) ),
                     Err(err) => {
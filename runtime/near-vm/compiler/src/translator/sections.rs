@@ -106,7 +106,9 @@ pub fn parse_import_section<'data>(
                 )?;
             }
             TypeRef::Memory(mem) => {
-                assert!(!mem.memory64, "64bit memory not implemented yet");
+                if mem.memory64 {
+                    return Err(wasm_unsupported!("64-bit memories are not supported yet"));
+                }
                 environ.declare_memory_import(
                     MemoryType {
                         minimum: Pages(mem.initial.try_into().unwrap()),
@@ -195,7 +197,9 @@ pub fn parse_memory_section(
 
     for entry in memories {
         let mem = entry?;
-        assert!(!mem.memory64, "64bit memory not implemented yet");
+        if mem.memory64 {
+            return Err(wasm_unsupported!("64-bit memories are not supported yet"));
+        }
 
         environ.declare_memory(MemoryType {
             minimum: Pages(mem.initial.try_into().unwrap()),
@@ -32,6 +32,11 @@ pub struct Config {
     pub vm_kind: VMKind,
     /// When non-none, only the specified costs will be measured.
     pub costs_to_measure: Option<Vec<Cost>>,
+    /// Directory from which the scenarios are loaded.
+    pub scenarios_dir: PathBuf,
+    /// When non-none, only the specified scenarios will be measured. When both
+    /// this and `costs_to_measure` are none, all scenarios are measured.
+    pub scenarios_to_measure: Option<Vec<String>>,
     /// Configuration specific to raw RocksDB tests. Does NOT affect normal tests that use RocksDB through the nearcore interface.
     pub rocksdb_test_config: RocksDBTestConfig,
     /// Print extra details on estimations.
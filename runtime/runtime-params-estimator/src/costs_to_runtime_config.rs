@@ -69,6 +69,12 @@ fn runtime_fees_config(cost_table: &CostTable) -> anyhow::Result<RuntimeFeesConf
             ActionCosts::add_function_call_key_base => fee(Cost::ActionAddFunctionAccessKeyBase)?,
             ActionCosts::add_function_call_key_byte => fee(Cost::ActionAddFunctionAccessKeyPerByte)?,
             ActionCosts::delete_key => fee(Cost::ActionDeleteKey)?,
+            // Sponsoring storage does the same amount of work as a transfer.
+            ActionCosts::sponsor_storage => fee(Cost::ActionTransfer)?,
+            // Using a global contract only records the identifier, which is cheaper than a
+            // deployment but not measured separately yet.
+            ActionCosts::use_global_contract_base => fee(Cost::ActionDeployContractBase)?,
+            ActionCosts::use_global_contract_byte => fee(Cost::ActionDeployContractPerByte)?,
             ActionCosts::new_action_receipt => fee(Cost::ActionReceiptCreation)?,
             ActionCosts::new_data_receipt_base => fee(Cost::DataReceiptCreationBase)?,
             ActionCosts::new_data_receipt_byte => fee(Cost::DataReceiptCreationPerByte)?,
@@ -192,6 +192,8 @@ impl<'c> EstimatorContext<'c> {
             migration_flags: MigrationFlags::default(),
             congestion_info,
             bandwidth_requests: BlockBandwidthRequests::empty(),
+            recent_block_headers: Default::default(),
+            execution_tracer: None,
         }
     }
 
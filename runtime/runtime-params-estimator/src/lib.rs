@@ -81,6 +81,8 @@ pub mod vm_estimator;
 pub mod config;
 mod function_call;
 mod gas_metering;
+// Data-driven host function estimations described by TOML files.
+pub mod scenario;
 mod trie;
 
 use crate::config::Config;
@@ -307,6 +309,8 @@ pub fn run(config: Config) -> CostTable {
             if !costs.contains(&cost) {
                 continue;
             }
+        } else if ctx.config.scenarios_to_measure.is_some() {
+            continue;
         }
 
         let start = Instant::now();
@@ -336,11 +340,70 @@ pub fn run(config: Config) -> CostTable {
             println!("{json}");
         }
     }
+    run_scenarios(&mut ctx, &config);
     eprintln!();
 
     res
 }
 
+/// Runs the scenarios selected by `config`.
+///
+/// Scenario results are reported like the other estimations but are not part
+/// of the `CostTable`, which only covers the costs of `ALL_COSTS`.
+fn run_scenarios(ctx: &mut EstimatorContext, config: &Config) {
+    if config.scenarios_to_measure.is_none() && config.costs_to_measure.is_some() {
+        return;
+    }
+    let scenarios = scenario::load_scenarios(&config.scenarios_dir).unwrap_or_else(|err| {
+        panic!("failed to load scenarios from {}: {err}", config.scenarios_dir.display())
+    });
+    let config_store = RuntimeConfigStore::new(None);
+    let vm_config = config_store.get_config(PROTOCOL_VERSION).wasm_config.clone();
+    for scenario in &scenarios {
+        if let Some(names) = &config.scenarios_to_measure {
+            if !names.contains(&scenario.name) {
+                continue;
+            }
+        }
+
+        let start = Instant::now();
+        let measurement = match scenario::run_scenario(ctx, scenario, &vm_config) {
+            Ok(Some(measurement)) => measurement,
+            Ok(None) => {
+                eprintln!(
+                    "{:<40} skipped, `{}` is not available at protocol version {}",
+                    scenario.name, scenario.host_function, PROTOCOL_VERSION
+                );
+                continue;
+            }
+            Err(err) => panic!("scenario {} failed: {err}", scenario.name),
+        };
+        let time = start.elapsed();
+        let uncertain = if measurement.is_uncertain() { "UNCERTAIN " } else { "" };
+        let gas = measurement.to_gas();
+
+        eprintln!(
+            "{:<40} {:>25} gas [{:>25}] {:<10}(computed in {:.2?}) {}",
+            scenario.name,
+            format_gas(gas),
+            format!("{:?}", measurement),
+            uncertain,
+            time,
+            measurement.uncertain_message().unwrap_or_default(),
+        );
+
+        if config.json_output {
+            let json = json! ({
+                "name": scenario.name,
+                "ext_cost": scenario.ext_cost().map(|cost| cost.to_string()).ok(),
+                "result": measurement.to_json(),
+                "computed_in": time,
+            });
+            println!("{json}");
+        }
+    }
+}
+
 fn action_receipt_creation(ctx: &mut EstimatorContext) -> GasCost {
     if let Some(cached) = ctx.cached.action_receipt_creation.clone() {
         return cached;
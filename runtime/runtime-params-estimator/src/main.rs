@@ -7,6 +7,7 @@ use near_parameters::vm::VMKind;
 use near_parameters::RuntimeConfigView;
 use replay::ReplayCmd;
 use runtime_params_estimator::config::{Config, GasMetric};
+use runtime_params_estimator::scenario::DEFAULT_SCENARIOS_DIR;
 use runtime_params_estimator::{
     costs_to_runtime_config, Cost, CostTable, QemuCommandBuilder, RocksDBTestConfig,
 };
@@ -72,6 +73,14 @@ struct CliArgs {
     /// Coma-separated lists of a subset of costs to estimate.
     #[clap(long, use_value_delimiter = true)]
     costs: Option<Vec<Cost>>,
+    /// Coma-separated lists of scenarios to estimate, by name. If neither
+    /// `--costs` nor `--scenarios` is given, all scenarios are estimated.
+    #[clap(long, use_value_delimiter = true)]
+    scenarios: Option<Vec<String>>,
+    /// Directory with the TOML scenario descriptions. Defaults to the
+    /// scenarios shipped with the estimator.
+    #[clap(long)]
+    scenarios_dir: Option<PathBuf>,
     /// Build and run the estimator inside a docker container via QEMU.
     #[clap(long)]
     containerize: bool,
@@ -295,6 +304,10 @@ fn run_estimation(cli_args: CliArgs) -> anyhow::Result<Option<CostTable>> {
         metric,
         vm_kind: cli_args.vm_kind,
         costs_to_measure: cli_args.costs,
+        scenarios_dir: cli_args
+            .scenarios_dir
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_SCENARIOS_DIR)),
+        scenarios_to_measure: cli_args.scenarios,
         rocksdb_test_config,
         debug: cli_args.debug,
         json_output: cli_args.json_output,
@@ -541,6 +554,8 @@ mod tests {
             costs_file: None,
             compare_to: None,
             costs: Some(costs),
+            scenarios: None,
+            scenarios_dir: None,
             containerize: false,
             container_shell: false,
             drop_os_cache: false,
@@ -0,0 +1,211 @@
+//! Data-driven estimations of host function costs.
+//!
+//! Instead of a hand-written estimation function in `ALL_COSTS`, a scenario is
+//! a TOML file in `res/scenarios` describing which host function to call, with
+//! which arguments and which memory contents. A generic executor turns the
+//! description into a contract that calls the host function in a loop and
+//! measures it against the same loop without the call.
+//!
+//! ```toml
+//! name = "BlockHeaderBase"
+//! host_function = "block_header"
+//! # Defaults to `<host_function>_base`.
+//! ext_cost = "block_header_base"
+//! # Defaults to zeros for all arguments of the host function.
+//! args = [1, 0]
+//! calls = 1000
+//!
+//! [[data]]
+//! offset = 0
+//! value = "some bytes"
+//! ```
+//!
+//! The signature of the host function comes from the import definitions of the
+//! VM runner, so every host function available at the estimated protocol
+//! version can be estimated without any extra Rust code. Scenarios for host
+//! functions that are not available are skipped.
+
+use crate::estimator_context::EstimatorContext;
+use crate::gas_cost::{GasCost, NonNegativeTolerance};
+use crate::utils::fn_cost_in_contract;
+use near_parameters::vm::Config as VMConfig;
+use near_parameters::ExtCosts;
+use near_vm_runner::internal::{host_functions, HostFunctionSignature};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
+
+/// Directory with the scenarios shipped with the estimator, relative to the
+/// crate root.
+pub const DEFAULT_SCENARIOS_DIR: &str = "res/scenarios";
+
+/// Number of times the generated method is called in a single transaction.
+const ACTIONS_PER_TRANSACTION: usize = 5;
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Name under which the estimation is reported.
+    pub name: String,
+    /// Host function to call, as imported by contracts.
+    pub host_function: String,
+    /// Parameter estimated by the scenario, `<host_function>_base` if omitted.
+    pub ext_cost: Option<String>,
+    /// Arguments of each call, zeros if omitted.
+    pub args: Option<Vec<u64>>,
+    /// Data written to the memory of the contract before the calls.
+    #[serde(default)]
+    pub data: Vec<ScenarioData>,
+    /// Number of calls to the host function per function call action.
+    pub calls: u64,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioData {
+    pub offset: u32,
+    pub value: String,
+}
+
+impl Scenario {
+    pub fn ext_cost(&self) -> anyhow::Result<ExtCosts> {
+        let name = match &self.ext_cost {
+            Some(name) => name.clone(),
+            None => format!("{}_base", self.host_function),
+        };
+        ExtCosts::iter()
+            .find(|cost| cost.to_string() == name)
+            .ok_or_else(|| anyhow::anyhow!("scenario {}: unknown ext cost {name}", self.name))
+    }
+
+    /// Builds the contract executing the scenario.
+    ///
+    /// The contract exports `run`, calling the host function `calls` times, and
+    /// `run_empty`, running the same loop without the call.
+    fn contract(&self, signature: &HostFunctionSignature) -> anyhow::Result<Vec<u8>> {
+        let args = match &self.args {
+            Some(args) => args.clone(),
+            None => vec![0; signature.params.len()],
+        };
+        anyhow::ensure!(
+            args.len() == signature.params.len(),
+            "scenario {}: {} takes {} arguments, {} given",
+            self.name,
+            self.host_function,
+            signature.params.len(),
+            args.len()
+        );
+        let mut func_type = String::new();
+        if !signature.params.is_empty() {
+            write!(func_type, "(param {})", signature.params.join(" "))?;
+        }
+        if !signature.results.is_empty() {
+            write!(func_type, " (result {})", signature.results.join(" "))?;
+        }
+        let mut call = String::new();
+        for (ty, arg) in signature.params.iter().zip(&args) {
+            write!(call, "({ty}.const {arg}) ")?;
+        }
+        write!(call, "(call $host_function)")?;
+        for _ in &signature.results {
+            write!(call, " (drop)")?;
+        }
+        let mut data = String::new();
+        for segment in &self.data {
+            write!(data, "(data (i32.const {}) \"", segment.offset)?;
+            for byte in segment.value.as_bytes() {
+                write!(data, "\\{byte:02x}")?;
+            }
+            write!(data, "\") ")?;
+        }
+        let calls = self.calls;
+        let wat = format!(
+            r#"
+            (module
+              (import "{module}" "{name}" (func $host_function {func_type}))
+              (memory 1)
+              {data}
+              (func (export "run")
+                (local $i i64)
+                (loop $loop
+                  {call}
+                  (local.set $i (i64.add (local.get $i) (i64.const 1)))
+                  (br_if $loop (i64.lt_u (local.get $i) (i64.const {calls})))))
+              (func (export "run_empty")
+                (local $i i64)
+                (loop $loop
+                  (local.set $i (i64.add (local.get $i) (i64.const 1)))
+                  (br_if $loop (i64.lt_u (local.get $i) (i64.const {calls}))))))
+            "#,
+            module = signature.module,
+            name = signature.name,
+        );
+        Ok(wat::parse_str(&wat)?)
+    }
+}
+
+/// Loads all scenarios of a directory, ordered by file name.
+pub fn load_scenarios(dir: &Path) -> anyhow::Result<Vec<Scenario>> {
+    let mut paths = std::fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<std::io::Result<Vec<PathBuf>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "toml"));
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            let content = std::fs::read_to_string(path)?;
+            toml::from_str(&content)
+                .map_err(|err| anyhow::anyhow!("failed to parse {}: {err}", path.display()))
+        })
+        .collect()
+}
+
+/// Runs the scenario, returning the cost of a single call to the host function
+/// or `None` if the host function is not available with `vm_config`.
+pub(crate) fn run_scenario(
+    ctx: &mut EstimatorContext,
+    scenario: &Scenario,
+    vm_config: &VMConfig,
+) -> anyhow::Result<Option<GasCost>> {
+    // Catch typos before doing any measurement.
+    scenario.ext_cost()?;
+    let Some(signature) =
+        host_functions(vm_config).into_iter().find(|f| f.name == scenario.host_function)
+    else {
+        return Ok(None);
+    };
+    anyhow::ensure!(scenario.calls > 0, "scenario {}: calls must be positive", scenario.name);
+    let code = scenario.contract(&signature)?;
+    let total_cost = fn_cost_in_contract(ctx, "run", &code, ACTIONS_PER_TRANSACTION);
+    let base_cost = fn_cost_in_contract(ctx, "run_empty", &code, ACTIONS_PER_TRANSACTION);
+    Ok(Some(
+        total_cost.saturating_sub(&base_cost, &NonNegativeTolerance::PER_MILLE) / scenario.calls,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_parameters::RuntimeConfigStore;
+    use near_primitives::version::PROTOCOL_VERSION;
+
+    #[test]
+    fn shipped_scenarios_are_valid() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_SCENARIOS_DIR);
+        let scenarios = load_scenarios(&dir).unwrap();
+        assert!(!scenarios.is_empty());
+        let mut vm_config =
+            RuntimeConfigStore::test().get_config(PROTOCOL_VERSION).wasm_config.as_ref().clone();
+        vm_config.enable_all_features();
+        let functions = host_functions(&vm_config);
+        for scenario in &scenarios {
+            scenario.ext_cost().unwrap();
+            let signature = functions
+                .iter()
+                .find(|f| f.name == scenario.host_function)
+                .unwrap_or_else(|| panic!("no host function {}", scenario.host_function));
+            scenario.contract(signature).unwrap();
+        }
+    }
+}
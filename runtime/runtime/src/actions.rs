@@ -1,28 +1,37 @@
 use crate::config::{
-    safe_add_compute, safe_add_gas, total_prepaid_exec_fees, total_prepaid_gas,
+    safe_add_compute, safe_add_gas, safe_sub_gas, total_prepaid_exec_fees, total_prepaid_gas,
     total_prepaid_send_fees,
 };
-use crate::ext::{ExternalError, RuntimeExt};
+use crate::ext::{ExternalError, RuntimeExt, ViewCallEnv};
 use crate::receipt_manager::ReceiptManager;
 use crate::{metrics, ActionResult, ApplyState};
 use near_crypto::PublicKey;
 use near_parameters::{AccountCreationConfig, ActionCosts, RuntimeConfig, RuntimeFeesConfig};
-use near_primitives::account::{AccessKey, AccessKeyPermission, Account};
+use near_primitives::account::{
+    AccessKey, AccessKeyPermission, Account, ContractSourceMetadata, MigrationGracePeriod,
+    StorageSponsorship,
+};
 use near_primitives::action::delegate::{DelegateAction, SignedDelegateAction};
 use near_primitives::checked_feature;
 use near_primitives::config::ViewConfig;
 use near_primitives::errors::{ActionError, ActionErrorKind, InvalidAccessKeyError, RuntimeError};
 use near_primitives::hash::CryptoHash;
 use near_primitives::receipt::{
-    ActionReceipt, DataReceipt, Receipt, ReceiptEnum, ReceiptPriority, ReceiptV0,
+    ActionReceipt, DataReceipt, GlobalContractDistributionReceipt, PromiseYieldTimeout, Receipt,
+    ReceiptEnum, ReceiptPriority, ReceiptV0,
 };
+use near_primitives::shard_layout::ShardLayout;
 use near_primitives::transaction::{
     Action, AddKeyAction, DeleteAccountAction, DeleteKeyAction, DeployContractAction,
-    FunctionCallAction, StakeAction,
+    DeployGlobalContractAction, FunctionCallAction, GlobalContractDeployMode,
+    GlobalContractIdentifier, MigrateStateAction, SponsorStorageAction, StakeAction,
+    TransferTokenAction, UseGlobalContractAction,
 };
+use near_primitives::trie_key::TrieKey;
 use near_primitives::types::validator_stake::ValidatorStake;
 use near_primitives::types::{
-    AccountId, Balance, BlockHeight, EpochInfoProvider, Gas, StorageUsage, TrieCacheMode,
+    AccountId, Balance, BlockHeight, EpochInfoProvider, Gas, ShardId, StorageUsage, TrieCacheMode,
+    ValidatorMetadata,
 };
 use near_primitives::utils::account_is_implicit;
 use near_primitives::version::{
@@ -30,8 +39,14 @@ use near_primitives::version::{
 };
 use near_primitives_core::account::id::AccountType;
 use near_store::{
-    enqueue_promise_yield_timeout, get_access_key, get_promise_yield_indices, remove_access_key,
-    remove_account, set_access_key, set_promise_yield_indices, StorageError, TrieUpdate,
+    enqueue_promise_yield_timeout, get_access_key, get_contract_code_hash,
+    get_migration_grace_period, get_promise_yield_indices, get_storage_sponsorship,
+    get_token_balance, get_token_ids, remove_access_key, remove_account,
+    remove_contract_source_metadata, remove_global_contract_deployer,
+    remove_migration_grace_period, remove_storage_sponsorship, set_access_key,
+    set_contract_source_metadata, set_global_contract_deployer, set_migration_grace_period,
+    set_promise_yield_indices, set_promise_yield_timeout_at, set_storage_sponsorship,
+    set_token_balance, KeyLookupMode, StorageError, TrieUpdate,
 };
 use near_vm_runner::logic::errors::{
     CompilationError, FunctionCallError, InconsistentStateError, VMRunnerError,
@@ -86,6 +101,7 @@ pub(crate) fn execute_function_call(
         attached_deposit: function_call.deposit,
         prepaid_gas: function_call.gas,
         random_seed,
+        recent_block_headers: apply_state.recent_block_headers.clone(),
         view_config,
         output_data_receivers,
     };
@@ -205,6 +221,10 @@ pub(crate) fn action_function_call(
         epoch_info_provider,
         apply_state.current_protocol_version,
     );
+    runtime_ext.set_view_call_env(ViewCallEnv { apply_state, action_receipt, depth: 0 });
+    if let Some(tracer) = &apply_state.execution_tracer {
+        runtime_ext.set_execution_tracer(tracer.as_ref(), *receipt.receipt_id());
+    }
     let outcome = execute_function_call(
         contract,
         apply_state,
@@ -283,16 +303,36 @@ pub(crate) fn action_function_call(
             .action_receipts
             .into_iter()
             .map(|receipt| {
-                // If the newly created receipt is a PromiseYield, enqueue a timeout for it
+                // If the newly created receipt is a PromiseYield, enqueue a timeout for it.
+                // The queue is ordered by expiry height only as long as all timeouts have the
+                // same length, so timeouts of other lengths are keyed by their expiry height.
                 if receipt.is_promise_yield {
-                    enqueue_promise_yield_timeout(
-                        state_update,
-                        &mut promise_yield_indices,
-                        account_id.clone(),
-                        receipt.input_data_ids[0],
-                        apply_state.block_height
-                            + config.wasm_config.limit_config.yield_timeout_length_in_blocks,
-                    );
+                    let expires_at =
+                        apply_state.block_height + receipt.yield_timeout_length_in_blocks;
+                    if receipt.yield_timeout_length_in_blocks
+                        == apply_state
+                            .config
+                            .wasm_config
+                            .limit_config
+                            .yield_timeout_length_in_blocks
+                    {
+                        enqueue_promise_yield_timeout(
+                            state_update,
+                            &mut promise_yield_indices,
+                            account_id.clone(),
+                            receipt.input_data_ids[0],
+                            expires_at,
+                        );
+                    } else {
+                        set_promise_yield_timeout_at(
+                            state_update,
+                            &PromiseYieldTimeout {
+                                account_id: account_id.clone(),
+                                data_id: receipt.input_data_ids[0],
+                                expires_at,
+                            },
+                        );
+                    }
                 }
 
                 let new_action_receipt = ActionReceipt {
@@ -351,11 +391,88 @@ pub(crate) fn action_function_call(
     Ok(())
 }
 
+/// Runs the migration method of the contract deployed by `MigrateStateAction`.
+///
+/// The migration must not create any receipts, so that nothing else touches the
+/// account's state before the migration completes. Storage added by a successful
+/// migration is rent-free until the grace period ends.
+pub(crate) fn action_migrate_state(
+    state_update: &mut TrieUpdate,
+    apply_state: &ApplyState,
+    account: &mut Account,
+    receipt: &Receipt,
+    action_receipt: &ActionReceipt,
+    promise_results: Arc<[near_vm_runner::logic::types::PromiseResult]>,
+    result: &mut ActionResult,
+    account_id: &AccountId,
+    migrate_state: &MigrateStateAction,
+    action_hash: &CryptoHash,
+    epoch_info_provider: &dyn EpochInfoProvider,
+    storage_usage_before: StorageUsage,
+    contract: Box<dyn PreparedContract>,
+) -> Result<(), RuntimeError> {
+    let function_call = FunctionCallAction {
+        method_name: migrate_state.method_name.clone(),
+        args: migrate_state.args.clone(),
+        gas: migrate_state.gas,
+        deposit: 0,
+    };
+    let code_hash = account.code_hash();
+    let new_receipts_before = result.new_receipts.len();
+    action_function_call(
+        state_update,
+        apply_state,
+        account,
+        receipt,
+        action_receipt,
+        promise_results,
+        result,
+        account_id,
+        &function_call,
+        action_hash,
+        code_hash,
+        &apply_state.config,
+        true,
+        epoch_info_provider,
+        contract,
+    )?;
+    if result.result.is_err() {
+        return Ok(());
+    }
+    if result.new_receipts.len() != new_receipts_before {
+        result.new_receipts.truncate(new_receipts_before);
+        result.result = Err(ActionErrorKind::MigrationCreatedReceipts {
+            account_id: account_id.clone(),
+        }
+        .into());
+        return Ok(());
+    }
+
+    // Repeated migrations within a grace period keep the storage usage from
+    // before the first one as the baseline.
+    let storage_usage = match get_migration_grace_period(state_update, account_id)? {
+        Some(grace_period) if apply_state.block_height <= grace_period.end_height => {
+            grace_period.storage_usage.min(storage_usage_before)
+        }
+        _ => storage_usage_before,
+    };
+    let end_height = apply_state.block_height.saturating_add(
+        apply_state.config.wasm_config.limit_config.migration_grace_period_length_in_blocks,
+    );
+    set_migration_grace_period(
+        state_update,
+        account_id.clone(),
+        &MigrationGracePeriod { storage_usage, end_height },
+    );
+    Ok(())
+}
+
 pub(crate) fn action_stake(
     account: &mut Account,
     result: &mut ActionResult,
     account_id: &AccountId,
     stake: &StakeAction,
+    metadata: Option<&ValidatorMetadata>,
     last_block_hash: &CryptoHash,
     epoch_info_provider: &dyn EpochInfoProvider,
 ) -> Result<(), RuntimeError> {
@@ -382,11 +499,16 @@ pub(crate) fn action_stake(
             }
         }
 
-        result.validator_proposals.push(ValidatorStake::new(
-            account_id.clone(),
-            stake.public_key.clone(),
-            stake.stake,
-        ));
+        let proposal = match metadata {
+            Some(metadata) => ValidatorStake::new_with_metadata(
+                account_id.clone(),
+                stake.public_key.clone(),
+                stake.stake,
+                metadata.clone(),
+            ),
+            None => ValidatorStake::new(account_id.clone(), stake.public_key.clone(), stake.stake),
+        };
+        result.validator_proposals.push(proposal);
         if stake.stake > account.locked() {
             // We've checked above `account.amount >= increment`
             account.set_amount(account.amount() - increment);
@@ -438,6 +560,77 @@ pub(crate) fn action_transfer(account: &mut Account, deposit: Balance) -> Result
     Ok(())
 }
 
+/// Storage used by the record holding the balance of `token_id` of `account_id`.
+pub(crate) fn token_balance_storage_usage(
+    fee_config: &RuntimeFeesConfig,
+    account_id: &AccountId,
+    token_id: &AccountId,
+) -> StorageUsage {
+    let key_len =
+        TrieKey::TokenBalance { account_id: account_id.clone(), token_id: token_id.clone() }.len()
+            as u64;
+    key_len
+        + borsh::object_length(&Balance::default()).unwrap() as u64
+        + fee_config.storage_usage_config.num_extra_bytes_record
+}
+
+/// Highest number of token balances an account can hold when it is deleted, as
+/// each of them is sent to the beneficiary.
+const MAX_TOKENS_OF_DELETED_ACCOUNT: usize = 100;
+
+/// Credits the amount of a TransferToken action to `account_id`. The amount
+/// was debited from the signer when the transaction was converted to a receipt,
+/// or comes from a refund. Tokens sent back to their issuer are burnt.
+///
+/// The record of the balance is charged to the storage of `account_id`, so
+/// only the account itself can create it, by sending the token to itself.
+/// Transfers to an account that didn't opt in to the token fail.
+pub(crate) fn action_transfer_token(
+    state_update: &mut TrieUpdate,
+    fee_config: &RuntimeFeesConfig,
+    account: &mut Account,
+    account_id: &AccountId,
+    predecessor_id: &AccountId,
+    transfer: &TransferTokenAction,
+    result: &mut ActionResult,
+) -> Result<(), StorageError> {
+    if account_id == &transfer.token_id {
+        return Ok(());
+    }
+    let new_balance = match get_token_balance(state_update, account_id, &transfer.token_id)? {
+        Some(balance) => {
+            let Some(new_balance) = balance.checked_add(transfer.amount) else {
+                result.result = Err(ActionErrorKind::TokenBalanceOverflow {
+                    account_id: account_id.clone(),
+                    token_id: transfer.token_id.clone(),
+                }
+                .into());
+                return Ok(());
+            };
+            if new_balance == balance {
+                return Ok(());
+            }
+            new_balance
+        }
+        None if predecessor_id == account_id => {
+            account.set_storage_usage(account.storage_usage().saturating_add(
+                token_balance_storage_usage(fee_config, account_id, &transfer.token_id),
+            ));
+            transfer.amount
+        }
+        None => {
+            result.result = Err(ActionErrorKind::TokenNotRegistered {
+                account_id: account_id.clone(),
+                token_id: transfer.token_id.clone(),
+            }
+            .into());
+            return Ok(());
+        }
+    };
+    set_token_balance(state_update, account_id.clone(), transfer.token_id.clone(), new_balance);
+    Ok(())
+}
+
 #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
 pub(crate) fn action_nonrefundable_storage_transfer(
     account: &mut Account,
@@ -457,6 +650,52 @@ pub(crate) fn action_nonrefundable_storage_transfer(
     Ok(())
 }
 
+/// Adds the deposit to the storage sponsorship of `account_id` by
+/// `predecessor_id`, or ends the sponsorship and refunds the sponsor if the
+/// deposit is zero. The storage stake check after the receipt makes sure the
+/// account can still cover its storage without the refunded deposit.
+pub(crate) fn action_sponsor_storage(
+    state_update: &mut TrieUpdate,
+    result: &mut ActionResult,
+    account_id: &AccountId,
+    predecessor_id: &AccountId,
+    sponsor_storage: &SponsorStorageAction,
+) -> Result<(), StorageError> {
+    let deposit = sponsor_storage.deposit;
+    match get_storage_sponsorship(state_update, account_id)? {
+        Some(sponsorship) if &sponsorship.sponsor_id != predecessor_id => {
+            result.result = Err(ActionErrorKind::StorageSponsoredByAnotherAccount {
+                account_id: account_id.clone(),
+                sponsor_id: sponsorship.sponsor_id,
+            }
+            .into());
+        }
+        Some(sponsorship) if deposit == 0 => {
+            remove_storage_sponsorship(state_update, account_id.clone());
+            result.new_receipts.push(Receipt::new_balance_refund(
+                predecessor_id,
+                sponsorship.deposit,
+                ReceiptPriority::NoPriority,
+            ));
+        }
+        Some(mut sponsorship) => {
+            sponsorship.deposit = sponsorship.deposit.checked_add(deposit).ok_or_else(|| {
+                StorageError::StorageInconsistentState(
+                    "Storage sponsorship deposit integer overflow".to_string(),
+                )
+            })?;
+            set_storage_sponsorship(state_update, account_id.clone(), &sponsorship);
+        }
+        None if deposit == 0 => {}
+        None => set_storage_sponsorship(
+            state_update,
+            account_id.clone(),
+            &StorageSponsorship { sponsor_id: predecessor_id.clone(), deposit },
+        ),
+    }
+    Ok(())
+}
+
 pub(crate) fn action_create_account(
     fee_config: &RuntimeFeesConfig,
     account_creation_config: &AccountCreationConfig,
@@ -641,6 +880,22 @@ pub(crate) fn action_deploy_contract(
     // contracts into the storage as part of the commit routine, however no code should be relying
     // that the contracts are written to The State.
     state_update.set_code(account_id.clone(), &code);
+    // The metadata is a part of the code the account already stakes for, so its record doesn't
+    // add to the storage usage.
+    // The account no longer follows the global contract it may have used.
+    if ProtocolFeature::GlobalContracts.enabled(current_protocol_version) {
+        remove_global_contract_deployer(state_update, account_id.clone());
+    }
+    if ProtocolFeature::ContractSourceMetadata.enabled(current_protocol_version) {
+        // Receipts validated before the feature was enabled may deploy contracts with malformed
+        // metadata, those are deployed without it.
+        match parse_contract_source_metadata(code.code()).ok().flatten() {
+            Some(metadata) => {
+                set_contract_source_metadata(state_update, account_id.clone(), &metadata)
+            }
+            None => remove_contract_source_metadata(state_update, account_id.clone()),
+        }
+    }
     // Precompile the contract and store result (compiled code or error) in the contract runtime
     // cache.
     // Note, that contract compilation costs are already accounted in deploy cost using special
@@ -653,6 +908,186 @@ pub(crate) fn action_deploy_contract(
     Ok(())
 }
 
+/// Name of the custom section of the contract code holding its NEP-330 source metadata.
+const CONTRACT_SOURCE_METADATA_SECTION: &str = "contract_source_metadata";
+
+/// Parses the NEP-330 source metadata embedded in the contract code, if there is any.
+pub(crate) fn parse_contract_source_metadata(
+    code: &[u8],
+) -> Result<Option<ContractSourceMetadata>, serde_json::Error> {
+    near_vm_runner::prepare::find_custom_section(code, CONTRACT_SOURCE_METADATA_SECTION)
+        .map(serde_json::from_slice)
+        .transpose()
+}
+
+/// Tokens burnt for storing the code of a global contract. Nobody stakes for the storage of
+/// global contracts, so the account deploying one pays for it upfront.
+pub(crate) fn global_contract_storage_cost(
+    config: &RuntimeConfig,
+    deploy_global_contract: &DeployGlobalContractAction,
+) -> Option<Balance> {
+    config.storage_amount_per_byte().checked_mul(deploy_global_contract.code.len() as Balance)
+}
+
+pub(crate) fn action_deploy_global_contract(
+    state_update: &mut TrieUpdate,
+    apply_state: &ApplyState,
+    account: &mut Account,
+    account_id: &AccountId,
+    deploy_global_contract: &DeployGlobalContractAction,
+    result: &mut ActionResult,
+    epoch_info_provider: &dyn EpochInfoProvider,
+) -> Result<(), RuntimeError> {
+    let _span = tracing::debug_span!(target: "runtime", "action_deploy_global_contract").entered();
+    let code = &deploy_global_contract.code;
+    let identifier = match deploy_global_contract.deploy_mode {
+        GlobalContractDeployMode::CodeHash => {
+            GlobalContractIdentifier::CodeHash(CryptoHash::hash_bytes(code))
+        }
+        GlobalContractDeployMode::AccountId => {
+            GlobalContractIdentifier::AccountId(account_id.clone())
+        }
+    };
+    let key = TrieKey::GlobalContractCode { identifier: identifier.clone() };
+    let storage_cost = global_contract_storage_cost(&apply_state.config, deploy_global_contract)
+        .ok_or_else(|| {
+            RuntimeError::UnexpectedIntegerOverflow("action_deploy_global_contract".into())
+        })?;
+    let Some(amount) = account.amount().checked_sub(storage_cost) else {
+        result.result = Err(ActionErrorKind::LackBalanceForState {
+            account_id: account_id.clone(),
+            amount: storage_cost,
+        }
+        .into());
+        return Ok(());
+    };
+    account.set_amount(amount);
+
+    set_global_contract_code(state_update, apply_state, key, code);
+    let shard_layout = epoch_info_provider.shard_layout(&apply_state.epoch_id)?;
+    result.new_receipts.extend(global_contract_distribution_receipt(
+        account_id,
+        identifier,
+        vec![apply_state.shard_id],
+        code,
+        &shard_layout,
+    ));
+    Ok(())
+}
+
+/// Stores the code of a global contract distributed to this shard and forwards it to the next
+/// shard.
+pub(crate) fn apply_global_contract_distribution_receipt(
+    state_update: &mut TrieUpdate,
+    apply_state: &ApplyState,
+    receipt: &Receipt,
+    distribution_receipt: &GlobalContractDistributionReceipt,
+    epoch_info_provider: &dyn EpochInfoProvider,
+) -> Result<Option<Receipt>, RuntimeError> {
+    let _span =
+        tracing::debug_span!(target: "runtime", "apply_global_contract_distribution_receipt")
+            .entered();
+    let key = TrieKey::GlobalContractCode { identifier: distribution_receipt.id.clone() };
+    set_global_contract_code(state_update, apply_state, key, &distribution_receipt.code);
+
+    let mut already_delivered_shards = distribution_receipt.already_delivered_shards.clone();
+    if !already_delivered_shards.contains(&apply_state.shard_id) {
+        already_delivered_shards.push(apply_state.shard_id);
+    }
+    let shard_layout = epoch_info_provider.shard_layout(&apply_state.epoch_id)?;
+    Ok(global_contract_distribution_receipt(
+        receipt.predecessor_id(),
+        distribution_receipt.id.clone(),
+        already_delivered_shards,
+        &distribution_receipt.code,
+        &shard_layout,
+    ))
+}
+
+fn set_global_contract_code(
+    state_update: &mut TrieUpdate,
+    apply_state: &ApplyState,
+    key: TrieKey,
+    code: &[u8],
+) {
+    state_update.set(key, code.to_vec());
+    let code = ContractCode::new(code.to_vec(), None);
+    precompile_contract(
+        &code,
+        Arc::clone(&apply_state.config.wasm_config),
+        apply_state.cache.as_deref(),
+    )
+    .ok();
+    state_update.record_contract_deploy(code);
+}
+
+/// Creates the receipt which delivers a global contract to the first shard of the layout that
+/// hasn't received it yet, if any.
+fn global_contract_distribution_receipt(
+    predecessor_id: &AccountId,
+    id: GlobalContractIdentifier,
+    already_delivered_shards: Vec<ShardId>,
+    code: &[u8],
+    shard_layout: &ShardLayout,
+) -> Option<Receipt> {
+    let target_shard =
+        shard_layout.shard_ids().find(|shard_id| !already_delivered_shards.contains(shard_id))?;
+    Some(Receipt::V0(ReceiptV0 {
+        predecessor_id: predecessor_id.clone(),
+        receiver_id: predecessor_id.clone(),
+        receipt_id: CryptoHash::default(),
+        receipt: ReceiptEnum::GlobalContractDistribution(GlobalContractDistributionReceipt {
+            id,
+            target_shard,
+            already_delivered_shards,
+            code: code.to_vec(),
+        }),
+    }))
+}
+
+pub(crate) fn action_use_global_contract(
+    state_update: &mut TrieUpdate,
+    account: &mut Account,
+    account_id: &AccountId,
+    use_global_contract: &UseGlobalContractAction,
+    result: &mut ActionResult,
+    current_protocol_version: ProtocolVersion,
+) -> Result<(), StorageError> {
+    let _span = tracing::debug_span!(target: "runtime", "action_use_global_contract").entered();
+    let identifier = &use_global_contract.contract_identifier;
+    let key = TrieKey::GlobalContractCode { identifier: identifier.clone() };
+    let Some(code_ref) = state_update.get_ref(&key, KeyLookupMode::FlatStorage)? else {
+        result.result =
+            Err(ActionErrorKind::GlobalContractDoesNotExist { identifier: identifier.clone() }
+                .into());
+        return Ok(());
+    };
+    let prev_code_len = get_code_len_or_default(
+        state_update,
+        account_id.clone(),
+        account.code_hash(),
+        current_protocol_version,
+    )?;
+    // The code is stored once per shard, the account only keeps its hash.
+    account.set_storage_usage(account.storage_usage().saturating_sub(prev_code_len));
+    account.set_code_hash(code_ref.value_hash());
+    state_update.remove(TrieKey::ContractCode { account_id: account_id.clone() });
+    // A contract used by the account id of its deployer follows the deployer's updates, so
+    // the code to run is resolved from the deployer when the contract is called.
+    match identifier {
+        GlobalContractIdentifier::CodeHash(_) => {
+            remove_global_contract_deployer(state_update, account_id.clone())
+        }
+        GlobalContractIdentifier::AccountId(deployer_id) => {
+            set_global_contract_deployer(state_update, account_id.clone(), deployer_id)
+        }
+    }
+    if ProtocolFeature::ContractSourceMetadata.enabled(current_protocol_version) {
+        remove_contract_source_metadata(state_update, account_id.clone());
+    }
+    Ok(())
+}
+
 pub(crate) fn action_delete_account(
     state_update: &mut TrieUpdate,
     account: &mut Option<Account>,
@@ -684,6 +1119,20 @@ pub(crate) fn action_delete_account(
             return Ok(());
         }
     }
+    let token_ids = if ProtocolFeature::NativeTokens.enabled(current_protocol_version) {
+        let token_ids = get_token_ids(state_update, account_id, MAX_TOKENS_OF_DELETED_ACCOUNT + 1)?;
+        if token_ids.len() > MAX_TOKENS_OF_DELETED_ACCOUNT {
+            result.result = Err(ActionErrorKind::DeleteAccountWithTooManyTokens {
+                account_id: account_id.clone(),
+                limit: MAX_TOKENS_OF_DELETED_ACCOUNT as u64,
+            }
+            .into());
+            return Ok(());
+        }
+        Some(token_ids)
+    } else {
+        None
+    };
     // We use current amount as a pay out to beneficiary.
     let account_balance = account.as_ref().unwrap().amount();
     if account_balance > 0 {
@@ -693,12 +1142,64 @@ pub(crate) fn action_delete_account(
             ReceiptPriority::NoPriority,
         ));
     }
+    // Sponsored storage deposit goes back to the sponsor rather than to the beneficiary.
+    if ProtocolFeature::SponsoredStorage.enabled(current_protocol_version) {
+        if let Some(sponsorship) = get_storage_sponsorship(state_update, account_id)? {
+            result.new_receipts.push(Receipt::new_balance_refund(
+                &sponsorship.sponsor_id,
+                sponsorship.deposit,
+                ReceiptPriority::NoPriority,
+            ));
+            remove_storage_sponsorship(state_update, account_id.clone());
+        }
+    }
+    if ProtocolFeature::MigrateState.enabled(current_protocol_version)
+        && get_migration_grace_period(state_update, account_id)?.is_some()
+    {
+        remove_migration_grace_period(state_update, account_id.clone());
+    }
+    if ProtocolFeature::ContractSourceMetadata.enabled(current_protocol_version) {
+        remove_contract_source_metadata(state_update, account_id.clone());
+    }
+    if ProtocolFeature::GlobalContracts.enabled(current_protocol_version) {
+        remove_global_contract_deployer(state_update, account_id.clone());
+    }
+    // Token balances go to the beneficiary like the NEAR balance, unless it
+    // didn't opt in to the token. The records are removed together with the
+    // account.
+    if let Some(token_ids) = token_ids {
+        for token_id in token_ids {
+            let balance = get_token_balance(state_update, account_id, &token_id)?.unwrap_or(0);
+            if balance > 0 {
+                result.new_receipts.push(Receipt::new_token_refund(
+                    &delete_account.beneficiary_id,
+                    &token_id,
+                    balance,
+                    ReceiptPriority::NoPriority,
+                ));
+            }
+        }
+    }
     remove_account(state_update, account_id)?;
     *actor_id = receipt.predecessor_id().clone();
     *account = None;
     Ok(())
 }
 
+/// Returns the hash of the code to run when calling the contract deployed to `account_id`.
+/// See `get_contract_code_hash` for accounts using global contracts.
+pub(crate) fn contract_code_hash(
+    state_update: &TrieUpdate,
+    account_id: &AccountId,
+    account: &Account,
+    current_protocol_version: ProtocolVersion,
+) -> Result<CryptoHash, StorageError> {
+    if !ProtocolFeature::GlobalContracts.enabled(current_protocol_version) {
+        return Ok(account.code_hash());
+    }
+    get_contract_code_hash(state_update, account_id, account)
+}
+
 /// Returns the storage usage for the contract code with the given `code_hash` and deployed to the given `account_id`.
 /// If no contract was deployed to the account, returns `0`.
 ///
@@ -717,11 +1218,7 @@ fn get_code_len_or_default(
         } else {
             state_update.get_code(account_id, code_hash)?.map(|contract| contract.code().len())
         };
-    debug_assert!(
-        code_len.is_some() || code_hash == CryptoHash::default(),
-        "Non-default code hash for account with no contract deployed: {:?}",
-        code_hash
-    );
+    // Accounts using a global contract have a non-default code hash, but no code of their own.
     Ok(code_len.unwrap_or_default().try_into().unwrap())
 }
 
@@ -865,7 +1362,12 @@ pub(crate) fn apply_delegate_action(
     // Some contracts refund the deposit. Usually they refund the deposit to the predecessor and this is sender_id/Sender from DelegateAction.
     // Therefore Relayer should verify DelegateAction before submitting it because it spends the attached deposit.
 
-    let prepaid_send_fees = total_prepaid_send_fees(&apply_state.config, &action_receipt.actions)?;
+    // Send fees of the actions of a nested DelegateAction are passed on with
+    // the new receipt and burnt when the nested DelegateAction is applied.
+    let prepaid_send_fees = safe_sub_gas(
+        total_prepaid_send_fees(&apply_state.config, &action_receipt.actions)?,
+        total_prepaid_send_fees(&apply_state.config, &delegate_action.get_actions())?,
+    )?;
     let required_gas = receipt_required_gas(apply_state, &new_receipt)?;
     // This gas will be burnt by the receiver of the created receipt,
     result.gas_used = safe_add_gas(result.gas_used, required_gas)?;
@@ -896,10 +1398,16 @@ fn receipt_required_gas(apply_state: &ApplyState, receipt: &Receipt) -> Result<G
                 required_gas,
                 apply_state.config.fees.fee(ActionCosts::new_action_receipt).exec_fee(),
             )?;
+            required_gas = safe_add_gas(
+                required_gas,
+                total_prepaid_send_fees(&apply_state.config, &action_receipt.actions)?,
+            )?;
 
             required_gas
         }
-        ReceiptEnum::Data(_) | ReceiptEnum::PromiseResume(_) => 0,
+        ReceiptEnum::Data(_)
+        | ReceiptEnum::PromiseResume(_)
+        | ReceiptEnum::GlobalContractDistribution(_) => 0,
     })
 }
 
@@ -1025,7 +1533,14 @@ pub(crate) fn check_actor_permissions(
     account_id: &AccountId,
 ) -> Result<(), ActionError> {
     match action {
-        Action::DeployContract(_) | Action::Stake(_) | Action::AddKey(_) | Action::DeleteKey(_) => {
+        Action::DeployContract(_)
+        | Action::Stake(_)
+        | Action::StakeWithMetadata(_)
+        | Action::AddKey(_)
+        | Action::DeleteKey(_)
+        | Action::DeployGlobalContract(_)
+        | Action::UseGlobalContract(_)
+        | Action::MigrateState(_) => {
             if actor_id != account_id {
                 return Err(ActionErrorKind::ActorNoPermission {
                     account_id: account_id.clone(),
@@ -1050,7 +1565,11 @@ pub(crate) fn check_actor_permissions(
                 .into());
             }
         }
-        Action::CreateAccount(_) | Action::FunctionCall(_) | Action::Transfer(_) => (),
+        Action::CreateAccount(_)
+        | Action::FunctionCall(_)
+        | Action::Transfer(_)
+        | Action::SponsorStorage(_)
+        | Action::TransferToken(_) => (),
         Action::Delegate(_) => (),
         #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
         Action::NonrefundableStorageTransfer(_) => (),
@@ -1137,7 +1656,13 @@ pub(crate) fn check_account_existence(
         | Action::AddKey(_)
         | Action::DeleteKey(_)
         | Action::DeleteAccount(_)
-        | Action::Delegate(_) => {
+        | Action::Delegate(_)
+        | Action::SponsorStorage(_)
+        | Action::StakeWithMetadata(_)
+        | Action::DeployGlobalContract(_)
+        | Action::UseGlobalContract(_)
+        | Action::MigrateState(_)
+        | Action::TransferToken(_) => {
             if account.is_none() {
                 return Err(ActionErrorKind::AccountDoesNotExist {
                     account_id: account_id.clone(),
@@ -1201,6 +1726,7 @@ mod tests {
     use near_primitives::congestion_info::BlockCongestionInfo;
     use near_primitives::errors::InvalidAccessKeyError;
     use near_primitives::runtime::migration_data::MigrationFlags;
+    use near_primitives::test_utils::MockEpochInfoProvider;
     use near_primitives::transaction::CreateAccountAction;
     use near_primitives::types::{EpochId, StateChangeCause};
     use near_primitives_core::version::PROTOCOL_VERSION;
@@ -1358,6 +1884,47 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_delete_account_with_too_many_tokens() {
+        let tries = TestTriesBuilder::new().build();
+        let mut state_update =
+            tries.new_trie_update(ShardUId::single_shard(), CryptoHash::default());
+        let account_id: AccountId = "alice".parse().unwrap();
+        for i in 0..=MAX_TOKENS_OF_DELETED_ACCOUNT {
+            let token_id = format!("token{i}").parse().unwrap();
+            set_token_balance(&mut state_update, account_id.clone(), token_id, 1);
+        }
+        let mut account = Some(Account::new(100, 0, 0, CryptoHash::default(), 0, PROTOCOL_VERSION));
+        let mut actor_id = account_id.clone();
+        let mut action_result = ActionResult::default();
+        let receipt = Receipt::new_balance_refund(
+            &"alice.near".parse().unwrap(),
+            0,
+            ReceiptPriority::NoPriority,
+        );
+        action_delete_account(
+            &mut state_update,
+            &mut account,
+            &mut actor_id,
+            &receipt,
+            &mut action_result,
+            &account_id,
+            &DeleteAccountAction { beneficiary_id: "bob".parse().unwrap() },
+            ProtocolFeature::NativeTokens.protocol_version(),
+        )
+        .unwrap();
+        assert_eq!(
+            action_result.result,
+            Err(ActionErrorKind::DeleteAccountWithTooManyTokens {
+                account_id: account_id.clone(),
+                limit: MAX_TOKENS_OF_DELETED_ACCOUNT as u64,
+            }
+            .into())
+        );
+        assert!(action_result.new_receipts.is_empty());
+        assert!(account.is_some());
+    }
+
     fn test_delete_account_with_contract(storage_usage: u64) -> ActionResult {
         let tries = TestTriesBuilder::new().build();
         let mut state_update =
@@ -1407,6 +1974,268 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_deploy_contract_source_metadata() {
+        let tries = TestTriesBuilder::new().build();
+        let mut state_update =
+            tries.new_trie_update(ShardUId::single_shard(), CryptoHash::default());
+        let account_id: AccountId = "alice".parse().unwrap();
+        let mut account = Account::new(100, 0, 0, CryptoHash::default(), 0, PROTOCOL_VERSION);
+        let apply_state = create_apply_state(0);
+        let mut deploy = |code: Vec<u8>| {
+            action_deploy_contract(
+                &mut state_update,
+                &mut account,
+                &account_id,
+                &DeployContractAction { code },
+                Arc::clone(&apply_state.config.wasm_config),
+                None,
+                ProtocolFeature::ContractSourceMetadata.protocol_version(),
+            )
+            .unwrap();
+            near_store::get_contract_source_metadata(&state_update, &account_id).unwrap()
+        };
+
+        let metadata = deploy(crate::tests::contract_with_source_metadata(
+            r#"{"version":"1.0.0","standards":[{"standard":"nep141","version":"1.0.0"}]}"#,
+        ));
+        assert_eq!(
+            metadata,
+            Some(ContractSourceMetadata {
+                version: Some("1.0.0".to_string()),
+                link: None,
+                standards: vec![near_primitives::account::ContractStandard {
+                    standard: "nep141".to_string(),
+                    version: "1.0.0".to_string(),
+                }],
+            })
+        );
+        // Deploying a contract without metadata removes the metadata of the previous one.
+        assert_eq!(deploy(b"\0asm\x01\0\0\0".to_vec()), None);
+    }
+
+    #[test]
+    fn test_sponsor_storage() {
+        let tries = TestTriesBuilder::new().build();
+        let mut state_update =
+            tries.new_trie_update(ShardUId::single_shard(), CryptoHash::default());
+        let account_id: AccountId = "alice".parse().unwrap();
+        let sponsor_id: AccountId = "wallet".parse().unwrap();
+        let mut sponsor = |predecessor_id: &AccountId, deposit: Balance| {
+            let mut result = ActionResult::default();
+            action_sponsor_storage(
+                &mut state_update,
+                &mut result,
+                &account_id,
+                predecessor_id,
+                &SponsorStorageAction { deposit },
+            )
+            .unwrap();
+            let sponsorship = get_storage_sponsorship(&state_update, &account_id).unwrap();
+            (result, sponsorship)
+        };
+
+        let (result, sponsorship) = sponsor(&sponsor_id, 100);
+        assert!(result.result.is_ok());
+        assert_eq!(
+            sponsorship,
+            Some(StorageSponsorship { sponsor_id: sponsor_id.clone(), deposit: 100 })
+        );
+        let (result, sponsorship) = sponsor(&sponsor_id, 50);
+        assert!(result.result.is_ok());
+        assert_eq!(sponsorship.unwrap().deposit, 150);
+
+        let other_id: AccountId = "other".parse().unwrap();
+        let (result, sponsorship) = sponsor(&other_id, 10);
+        assert_eq!(
+            result.result,
+            Err(ActionErrorKind::StorageSponsoredByAnotherAccount {
+                account_id: account_id.clone(),
+                sponsor_id: sponsor_id.clone()
+            }
+            .into())
+        );
+        assert_eq!(sponsorship.unwrap().deposit, 150);
+
+        let (result, sponsorship) = sponsor(&sponsor_id, 0);
+        assert!(result.result.is_ok());
+        assert_eq!(sponsorship, None);
+        assert_eq!(
+            result.new_receipts,
+            vec![Receipt::new_balance_refund(&sponsor_id, 150, ReceiptPriority::NoPriority)]
+        );
+    }
+
+    #[test]
+    fn test_transfer_token() {
+        let tries = TestTriesBuilder::new().build();
+        let mut state_update =
+            tries.new_trie_update(ShardUId::single_shard(), CryptoHash::default());
+        let fee_config = RuntimeFeesConfig::test();
+        let account_id: AccountId = "alice".parse().unwrap();
+        let token_id: AccountId = "token".parse().unwrap();
+        let mut account = Account::new(100, 0, 0, CryptoHash::default(), 0, PROTOCOL_VERSION);
+        let sender_id: AccountId = "bob".parse().unwrap();
+        let mut transfer = |predecessor_id: &AccountId,
+                            account_id: &AccountId,
+                            account: &mut Account,
+                            amount: Balance| {
+            let mut result = ActionResult::default();
+            action_transfer_token(
+                &mut state_update,
+                &fee_config,
+                account,
+                account_id,
+                predecessor_id,
+                &TransferTokenAction { token_id: token_id.clone(), amount },
+                &mut result,
+            )
+            .unwrap();
+            (result, get_token_balance(&state_update, account_id, &token_id).unwrap())
+        };
+
+        // Transfers fail until the receiver opts in to the token.
+        let (result, balance) = transfer(&sender_id, &account_id, &mut account, 10);
+        assert_eq!(
+            result.result,
+            Err(ActionErrorKind::TokenNotRegistered {
+                account_id: account_id.clone(),
+                token_id: token_id.clone()
+            }
+            .into())
+        );
+        assert_eq!(balance, None);
+        assert_eq!(account.storage_usage(), 0);
+
+        let (result, balance) = transfer(&account_id, &account_id, &mut account, 0);
+        assert!(result.result.is_ok());
+        assert_eq!(balance, Some(0));
+        let storage_usage = token_balance_storage_usage(&fee_config, &account_id, &token_id);
+        assert_eq!(account.storage_usage(), storage_usage);
+
+        let (result, balance) = transfer(&sender_id, &account_id, &mut account, 10);
+        assert!(result.result.is_ok());
+        assert_eq!(balance, Some(10));
+        assert_eq!(account.storage_usage(), storage_usage);
+
+        let (result, balance) = transfer(&sender_id, &account_id, &mut account, Balance::MAX);
+        assert_eq!(
+            result.result,
+            Err(ActionErrorKind::TokenBalanceOverflow {
+                account_id: account_id.clone(),
+                token_id: token_id.clone()
+            }
+            .into())
+        );
+        assert_eq!(balance, Some(10));
+        assert_eq!(account.storage_usage(), storage_usage);
+
+        // Tokens sent to the issuer are burnt.
+        let mut issuer = Account::new(100, 0, 0, CryptoHash::default(), 0, PROTOCOL_VERSION);
+        let (result, balance) = transfer(&account_id, &token_id, &mut issuer, 10);
+        assert!(result.result.is_ok());
+        assert_eq!(balance, None);
+        assert_eq!(issuer.storage_usage(), 0);
+    }
+
+    #[test]
+    fn test_global_contract() {
+        let tries = TestTriesBuilder::new().build();
+        let mut state_update =
+            tries.new_trie_update(ShardUId::single_shard(), CryptoHash::default());
+        let apply_state = create_apply_state(0);
+        let shard_layout = ShardLayout::multi_shard(2, 0);
+        let epoch_info_provider = MockEpochInfoProvider::new(shard_layout.clone());
+        let deployer_id: AccountId = "alice".parse().unwrap();
+        let code = vec![1; 100];
+        let storage_cost = apply_state.config.storage_amount_per_byte() * code.len() as Balance;
+        let mut deployer =
+            Account::new(storage_cost + 1, 0, 0, CryptoHash::default(), 100, PROTOCOL_VERSION);
+        let deploy = DeployGlobalContractAction {
+            code: code.clone(),
+            deploy_mode: GlobalContractDeployMode::AccountId,
+        };
+        let mut result = ActionResult::default();
+        action_deploy_global_contract(
+            &mut state_update,
+            &apply_state,
+            &mut deployer,
+            &deployer_id,
+            &deploy,
+            &mut result,
+            &epoch_info_provider,
+        )
+        .unwrap();
+        assert!(result.result.is_ok());
+        assert_eq!(deployer.amount(), 1);
+        let identifier = GlobalContractIdentifier::AccountId(deployer_id.clone());
+        // The code is distributed to the other shard of the layout.
+        let [receipt] = &result.new_receipts[..] else { panic!("expected one receipt") };
+        let ReceiptEnum::GlobalContractDistribution(distribution) = receipt.receipt() else {
+            panic!("expected a distribution receipt")
+        };
+        assert_eq!(distribution.id, identifier);
+        assert_eq!(
+            Some(distribution.target_shard),
+            shard_layout.shard_ids().find(|shard_id| *shard_id != apply_state.shard_id)
+        );
+        assert_eq!(distribution.already_delivered_shards, vec![apply_state.shard_id]);
+
+        let account_id: AccountId = "bob".parse().unwrap();
+        let mut account = Account::new(100, 0, 0, CryptoHash::default(), 100, PROTOCOL_VERSION);
+        let mut use_global_contract = |identifier: GlobalContractIdentifier| {
+            let mut result = ActionResult::default();
+            action_use_global_contract(
+                &mut state_update,
+                &mut account,
+                &account_id,
+                &UseGlobalContractAction { contract_identifier: identifier },
+                &mut result,
+                PROTOCOL_VERSION,
+            )
+            .unwrap();
+            result
+        };
+        let missing = GlobalContractIdentifier::CodeHash(CryptoHash::hash_bytes(&[2; 100]));
+        assert_eq!(
+            use_global_contract(missing.clone()).result,
+            Err(ActionErrorKind::GlobalContractDoesNotExist { identifier: missing }.into())
+        );
+        assert!(use_global_contract(identifier).result.is_ok());
+        assert_eq!(account.code_hash(), CryptoHash::hash_bytes(&code));
+        assert_eq!(account.storage_usage(), 100);
+        // The code is resolved through the deployer when it is called.
+        assert_eq!(
+            near_store::get_global_contract_deployer(&state_update, &account_id).unwrap(),
+            Some(deployer_id.clone())
+        );
+        assert_eq!(
+            contract_code_hash(&state_update, &account_id, &account, PROTOCOL_VERSION).unwrap(),
+            CryptoHash::hash_bytes(&code)
+        );
+
+        // Deploying fails if the account can't pay for the storage.
+        let mut result = ActionResult::default();
+        action_deploy_global_contract(
+            &mut state_update,
+            &apply_state,
+            &mut deployer,
+            &deployer_id,
+            &deploy,
+            &mut result,
+            &epoch_info_provider,
+        )
+        .unwrap();
+        assert_eq!(
+            result.result,
+            Err(ActionErrorKind::LackBalanceForState {
+                account_id: deployer_id,
+                amount: storage_cost
+            }
+            .into())
+        );
+    }
+
     fn create_delegate_action_receipt() -> (ActionReceipt, SignedDelegateAction) {
         let signed_delegate_action = SignedDelegateAction {
             delegate_action: DelegateAction {
@@ -1464,6 +2293,8 @@ mod tests {
             migration_flags: MigrationFlags::default(),
             congestion_info: BlockCongestionInfo::default(),
             bandwidth_requests: BlockBandwidthRequests::empty(),
+            recent_block_headers: Default::default(),
+            execution_tracer: None,
         }
     }
 
@@ -1,9 +1,9 @@
 use crate::near_primitives::shard_layout::ShardUId;
 use near_crypto::PublicKey;
-use near_primitives::account::{AccessKey, Account};
+use near_primitives::account::{AccessKey, Account, ContractSourceMetadata, StorageSponsorship};
 use near_primitives::hash::CryptoHash;
 use near_primitives::types::{
-    AccountId, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, MerkleHash,
+    AccountId, Balance, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, MerkleHash,
 };
 use near_primitives::version::ProtocolVersion;
 use near_primitives::views::ViewStateResult;
@@ -43,6 +43,28 @@ pub trait ViewRuntimeAdapter {
         current_protocol_version: ProtocolVersion,
     ) -> Result<Vec<u8>, crate::state_viewer::errors::CallFunctionError>;
 
+    fn view_storage_sponsorship(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        account_id: &AccountId,
+    ) -> Result<Option<StorageSponsorship>, crate::state_viewer::errors::ViewAccountError>;
+
+    fn view_contract_source_metadata(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        account_id: &AccountId,
+    ) -> Result<Option<ContractSourceMetadata>, crate::state_viewer::errors::ViewAccountError>;
+
+    fn view_token_balance(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        account_id: &AccountId,
+        token_id: &AccountId,
+    ) -> Result<Balance, crate::state_viewer::errors::ViewAccountError>;
+
     fn view_access_key(
         &self,
         shard_uid: &ShardUId,
@@ -15,8 +15,8 @@ use near_primitives::trie_key::TrieKey;
 use near_primitives::types::{AccountId, Balance, ShardId};
 use near_store::trie::receipts_column_helper::{ShardsOutgoingReceiptBuffer, TrieQueue};
 use near_store::{
-    get, get_account, get_postponed_receipt, get_promise_yield_receipt, Trie, TrieAccess,
-    TrieUpdate,
+    get, get_account, get_postponed_receipt, get_promise_yield_receipt, get_storage_sponsorship,
+    Trie, TrieAccess, TrieUpdate,
 };
 use std::collections::{BTreeSet, HashSet};
 
@@ -66,7 +66,9 @@ fn receipt_cost(
             }
             total_cost
         }
-        ReceiptEnum::Data(_) | ReceiptEnum::PromiseResume(_) => 0,
+        ReceiptEnum::Data(_)
+        | ReceiptEnum::PromiseResume(_)
+        | ReceiptEnum::GlobalContractDistribution(_) => 0,
     })
 }
 
@@ -87,11 +89,14 @@ fn total_accounts_balance(
     accounts_ids: &HashSet<AccountId>,
 ) -> Result<Balance, RuntimeError> {
     accounts_ids.iter().try_fold(0u128, |accumulator, account_id| {
+        // Deposits of storage sponsors are kept with the sponsored accounts.
+        let sponsored_deposit = get_storage_sponsorship(state, account_id)?
+            .map_or(0, |sponsorship| sponsorship.deposit);
         let (amount, locked) = match get_account(state, account_id)? {
-            None => return Ok(accumulator),
+            None => return Ok(safe_add_balance_apply!(accumulator, sponsored_deposit)),
             Some(account) => (account.amount(), account.locked()),
         };
-        Ok(safe_add_balance_apply!(accumulator, amount, locked))
+        Ok(safe_add_balance_apply!(accumulator, amount, locked, sponsored_deposit))
     })
 }
 
@@ -259,6 +264,7 @@ fn potential_postponed_receipt_ids(
                     account_id.clone(),
                     data_receipt.data_id,
                 ))),
+                ReceiptEnum::GlobalContractDistribution(_) => None,
             }
         })
         .collect::<Result<HashSet<_>, StorageError>>()
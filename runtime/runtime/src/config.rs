@@ -2,7 +2,7 @@
 
 use near_primitives::account::AccessKeyPermission;
 use near_primitives::errors::IntegerOverflowError;
-use near_primitives::version::FIXED_MINIMUM_NEW_RECEIPT_GAS_VERSION;
+use near_primitives::version::{ProtocolFeature, FIXED_MINIMUM_NEW_RECEIPT_GAS_VERSION};
 use near_primitives_core::types::ProtocolVersion;
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
@@ -51,6 +51,10 @@ pub fn safe_add_gas(a: Gas, b: Gas) -> Result<Gas, IntegerOverflowError> {
     a.checked_add(b).ok_or(IntegerOverflowError {})
 }
 
+pub fn safe_sub_gas(a: Gas, b: Gas) -> Result<Gas, IntegerOverflowError> {
+    a.checked_sub(b).ok_or(IntegerOverflowError {})
+}
+
 pub fn safe_add_balance(a: Balance, b: Balance) -> Result<Balance, IntegerOverflowError> {
     a.checked_add(b).ok_or(IntegerOverflowError {})
 }
@@ -117,7 +121,9 @@ pub fn total_send_fees(
                     receiver_id.get_account_type(),
                 )
             }
-            Stake(_) => fees.fee(ActionCosts::stake).send_fee(sender_is_receiver),
+            Stake(_) | StakeWithMetadata(_) => {
+                fees.fee(ActionCosts::stake).send_fee(sender_is_receiver)
+            }
             AddKey(add_key_action) => match &add_key_action.access_key.permission {
                 AccessKeyPermission::FunctionCall(call_perm) => {
                     let num_bytes = call_perm
@@ -150,6 +156,37 @@ pub fn total_send_fees(
                         &delegate_action.receiver_id,
                     )?
             }
+            SponsorStorage(_) => {
+                fees.fee(ActionCosts::sponsor_storage).send_fee(sender_is_receiver)
+            }
+            TransferToken(_) => fees.fee(ActionCosts::transfer).send_fee(sender_is_receiver),
+            // Global contracts are charged like deploying the code, or just the identifier
+            // when it's used, to an account.
+            DeployGlobalContract(action) => {
+                let num_bytes = action.code.len() as u64;
+                fees.fee(ActionCosts::deploy_contract_base).send_fee(sender_is_receiver)
+                    + fees.fee(ActionCosts::deploy_contract_byte).send_fee(sender_is_receiver)
+                        * num_bytes
+            }
+            UseGlobalContract(action) => {
+                let num_bytes = action.contract_identifier.len() as u64;
+                fees.fee(ActionCosts::use_global_contract_base).send_fee(sender_is_receiver)
+                    + fees.fee(ActionCosts::use_global_contract_byte).send_fee(sender_is_receiver)
+                        * num_bytes
+            }
+            // A state migration is charged like deploying its code followed by calling its
+            // migration method.
+            MigrateState(action) => {
+                let code_bytes = action.code.len() as u64;
+                let call_bytes =
+                    action.method_name.as_bytes().len() as u64 + action.args.len() as u64;
+                fees.fee(ActionCosts::deploy_contract_base).send_fee(sender_is_receiver)
+                    + fees.fee(ActionCosts::deploy_contract_byte).send_fee(sender_is_receiver)
+                        * code_bytes
+                    + fees.fee(ActionCosts::function_call_base).send_fee(sender_is_receiver)
+                    + fees.fee(ActionCosts::function_call_byte).send_fee(sender_is_receiver)
+                        * call_bytes
+            }
         };
         result = safe_add_gas(result, delta)?;
     }
@@ -160,7 +197,8 @@ pub fn total_send_fees(
 ///
 /// This is only relevant for DelegateAction, where the send fees of the inner actions
 /// need to be prepaid. All other actions burn send fees directly, so calling this function
-/// with other actions will return 0.
+/// with other actions will return 0. Send fees of the actions of nested DelegateActions
+/// are prepaid as well, they are burnt when the nested DelegateAction is applied.
 pub fn total_prepaid_send_fees(
     config: &RuntimeConfig,
     actions: &[Action],
@@ -172,12 +210,16 @@ pub fn total_prepaid_send_fees(
             Delegate(signed_delegate_action) => {
                 let delegate_action = &signed_delegate_action.delegate_action;
                 let sender_is_receiver = delegate_action.sender_id == delegate_action.receiver_id;
+                let actions = delegate_action.get_actions();
 
-                total_send_fees(
-                    config,
-                    sender_is_receiver,
-                    &delegate_action.get_actions(),
-                    &delegate_action.receiver_id,
+                safe_add_gas(
+                    total_send_fees(
+                        config,
+                        sender_is_receiver,
+                        &actions,
+                        &delegate_action.receiver_id,
+                    )?,
+                    total_prepaid_send_fees(config, &actions)?,
                 )?
             }
             _ => 0,
@@ -222,7 +264,7 @@ pub fn exec_fee(config: &RuntimeConfig, action: &Action, receiver_id: &AccountId
                 receiver_id.get_account_type(),
             )
         }
-        Stake(_) => fees.fee(ActionCosts::stake).exec_fee(),
+        Stake(_) | StakeWithMetadata(_) => fees.fee(ActionCosts::stake).exec_fee(),
         AddKey(add_key_action) => match &add_key_action.access_key.permission {
             AccessKeyPermission::FunctionCall(call_perm) => {
                 let num_bytes = call_perm
@@ -241,6 +283,26 @@ pub fn exec_fee(config: &RuntimeConfig, action: &Action, receiver_id: &AccountId
         DeleteKey(_) => fees.fee(ActionCosts::delete_key).exec_fee(),
         DeleteAccount(_) => fees.fee(ActionCosts::delete_account).exec_fee(),
         Delegate(_) => fees.fee(ActionCosts::delegate).exec_fee(),
+        SponsorStorage(_) => fees.fee(ActionCosts::sponsor_storage).exec_fee(),
+        TransferToken(_) => fees.fee(ActionCosts::transfer).exec_fee(),
+        DeployGlobalContract(action) => {
+            let num_bytes = action.code.len() as u64;
+            fees.fee(ActionCosts::deploy_contract_base).exec_fee()
+                + fees.fee(ActionCosts::deploy_contract_byte).exec_fee() * num_bytes
+        }
+        UseGlobalContract(action) => {
+            let num_bytes = action.contract_identifier.len() as u64;
+            fees.fee(ActionCosts::use_global_contract_base).exec_fee()
+                + fees.fee(ActionCosts::use_global_contract_byte).exec_fee() * num_bytes
+        }
+        MigrateState(action) => {
+            let code_bytes = action.code.len() as u64;
+            let call_bytes = action.method_name.as_bytes().len() as u64 + action.args.len() as u64;
+            fees.fee(ActionCosts::deploy_contract_base).exec_fee()
+                + fees.fee(ActionCosts::deploy_contract_byte).exec_fee() * code_bytes
+                + fees.fee(ActionCosts::function_call_base).exec_fee()
+                + fees.fee(ActionCosts::function_call_byte).exec_fee() * call_bytes
+        }
     }
 }
 
@@ -301,7 +363,10 @@ pub fn tx_cost(
         gas_remaining,
         total_prepaid_exec_fees(config, transaction.actions(), transaction.receiver_id())?,
     )?;
-    let burnt_amount = safe_gas_to_balance(gas_price, gas_burnt)?;
+    let mut burnt_amount = safe_gas_to_balance(gas_price, gas_burnt)?;
+    if ProtocolFeature::TransactionPriorityFee.enabled(protocol_version) {
+        burnt_amount = safe_add_balance(burnt_amount, transaction.priority_fee_amount())?;
+    }
     let remaining_gas_amount = safe_gas_to_balance(receipt_gas_price, gas_remaining)?;
     let mut total_cost = safe_add_balance(burnt_amount, remaining_gas_amount)?;
     total_cost = safe_add_balance(total_cost, total_deposit(&transaction.actions())?)?;
@@ -387,4 +452,51 @@ mod tests {
         assert_eq!(safe_gas_price_inflated(10000, Rational32::new(101, 100), 3).unwrap(), 10304);
         assert_eq!(safe_gas_price_inflated(10000, Rational32::new(101, 100), 32).unwrap(), 13750);
     }
+
+    #[test]
+    fn test_nested_delegate_action_prepaid_send_fees() {
+        use near_crypto::{KeyType, PublicKey, Signature};
+        use near_primitives::action::delegate::{
+            DelegateAction, NonDelegateAction, SignedDelegateAction,
+        };
+        use near_primitives::action::CreateAccountAction;
+
+        let config = RuntimeConfig::test();
+        let delegate_action = |sender_id: &str, receiver_id: &str, actions| {
+            Action::Delegate(Box::new(SignedDelegateAction {
+                delegate_action: DelegateAction {
+                    sender_id: sender_id.parse().unwrap(),
+                    receiver_id: receiver_id.parse().unwrap(),
+                    actions,
+                    nonce: 1,
+                    max_block_height: 1,
+                    public_key: PublicKey::empty(KeyType::ED25519),
+                },
+                signature: Signature::empty(KeyType::ED25519),
+            }))
+        };
+        let create_account = Action::CreateAccount(CreateAccountAction {});
+        let inner = delegate_action(
+            "bob.near",
+            "carol.near",
+            vec![NonDelegateAction::try_from(create_account.clone()).unwrap()],
+        );
+        let Action::Delegate(signed_inner) = inner.clone() else { unreachable!() };
+        let outer = delegate_action(
+            "alice.near",
+            "bob.near",
+            vec![NonDelegateAction::from_nested(*signed_inner)],
+        );
+
+        // Send fees of both levels are prepaid by the outermost delegate action.
+        let receiver_id: AccountId = "bob.near".parse().unwrap();
+        let outer_send_fees =
+            total_send_fees(&config, false, &[inner.clone()], &receiver_id).unwrap();
+        let inner_send_fees = total_prepaid_send_fees(&config, &[inner]).unwrap();
+        assert!(inner_send_fees > 0);
+        assert_eq!(
+            total_prepaid_send_fees(&config, &[outer]).unwrap(),
+            outer_send_fees + inner_send_fees
+        );
+    }
 }
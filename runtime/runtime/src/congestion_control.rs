@@ -298,7 +298,7 @@ impl ReceiptSinkV2 {
             let size = receipt_size(&receipt)?;
             let should_update_outgoing_metadatas = receipt.should_update_outgoing_metadatas();
             let receipt = receipt.into_receipt();
-            let target_shard_id = shard_layout.account_id_to_shard_id(receipt.receiver_id());
+            let target_shard_id = receipt.receiver_shard_id(shard_layout);
 
             match Self::try_forward(
                 receipt,
@@ -349,8 +349,8 @@ impl ReceiptSinkV2 {
         state_update: &mut TrieUpdate,
         epoch_info_provider: &dyn EpochInfoProvider,
     ) -> Result<(), RuntimeError> {
-        let shard = epoch_info_provider
-            .account_id_to_shard_id(receipt.receiver_id(), &apply_state.epoch_id)?;
+        let shard_layout = epoch_info_provider.shard_layout(&apply_state.epoch_id)?;
+        let shard = receipt.receiver_shard_id(&shard_layout);
 
         let size = compute_receipt_size(&receipt)?;
         let gas = compute_receipt_congestion_gas(&receipt, &apply_state.config)?;
@@ -611,6 +611,11 @@ pub(crate) fn compute_receipt_congestion_gas(
             // of it without expensive state lookups.
             Ok(0)
         }
+        ReceiptEnum::GlobalContractDistribution(_) => {
+            // Distributing a global contract doesn't run any actions, storing
+            // the code is paid for when the contract is deployed.
+            Ok(0)
+        }
     }
 }
 
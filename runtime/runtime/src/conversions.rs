@@ -34,6 +34,20 @@ mod prepare_error {
                 From::Memory => Self::Memory,
                 From::TooManyFunctions => Self::TooManyFunctions,
                 From::TooManyLocals => Self::TooManyLocals,
+                From::TooManyMemories => Self::TooManyMemories,
+            }
+        }
+    }
+}
+
+mod static_analysis_error {
+    use near_vm_runner::logic::errors::StaticAnalysisError as From;
+    impl super::Convert<From> for near_primitives::errors::StaticAnalysisError {
+        fn convert(outer_err: From) -> Self {
+            match outer_err {
+                From::TooManyLocalsPerFunction => Self::TooManyLocalsPerFunction,
+                From::TableTooLarge => Self::TableTooLarge,
+                From::BlocksNestedTooDeep => Self::BlocksNestedTooDeep,
             }
         }
     }
@@ -52,6 +66,9 @@ mod compilation_error {
                 // Intentionally converting into "Wasmer" error here in order to avoid
                 // this particular detail being visible to the protocol unnecessarily.
                 From::WasmtimeCompileError { msg } => Self::WasmerCompileError { msg },
+                From::StaticAnalysisError(e) => {
+                    Self::StaticAnalysisError(super::Convert::convert(e))
+                }
             }
         }
     }
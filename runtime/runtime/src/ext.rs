@@ -1,19 +1,30 @@
+use crate::actions::{contract_code_hash, execute_function_call};
 use crate::conversions::Convert;
 use crate::receipt_manager::ReceiptManager;
+use crate::trace::{ExecutionTraceEvent, ExecutionTracer};
+use crate::ApplyState;
 use near_primitives::account::id::AccountType;
 use near_primitives::account::Account;
+use near_primitives::action::FunctionCallAction;
 use near_primitives::checked_feature;
-use near_primitives::errors::{EpochError, StorageError};
+use near_primitives::config::ViewConfig;
+use near_primitives::errors::{EpochError, RuntimeError, StorageError};
 use near_primitives::hash::CryptoHash;
-use near_primitives::trie_key::{trie_key_parsers, TrieKey};
-use near_primitives::types::{AccountId, Balance, BlockHeight, EpochId, EpochInfoProvider, Gas};
+use near_primitives::receipt::ActionReceipt;
+use near_primitives::trie_key::{trie_key_parsers, StorageNamespace, TrieKey};
+use near_primitives::types::{
+    AccountId, Balance, BlockHeight, BlockHeightDelta, EpochId, EpochInfoProvider, Gas,
+    StorageUsage,
+};
 use near_primitives::utils::create_receipt_id_from_action_hash;
 use near_primitives::version::ProtocolVersion;
 use near_store::contract::ContractStorage;
-use near_store::{has_promise_yield_receipt, KeyLookupMode, TrieUpdate, TrieUpdateValuePtr};
-use near_vm_runner::logic::errors::{AnyError, VMLogicError};
-use near_vm_runner::logic::types::ReceiptIndex;
-use near_vm_runner::logic::{External, StorageGetMode, ValuePtr};
+use near_store::{
+    get_account, has_promise_yield_receipt, KeyLookupMode, TrieUpdate, TrieUpdateValuePtr,
+};
+use near_vm_runner::logic::errors::{AnyError, HostError, VMLogicError};
+use near_vm_runner::logic::types::{ReceiptIndex, ReturnData, ViewCallOutcome};
+use near_vm_runner::logic::{External, GasCounter, StorageGetMode, ValuePtr};
 use near_vm_runner::{Contract, ContractCode};
 use near_wallet_contract::wallet_contract;
 use std::sync::Arc;
@@ -31,6 +42,20 @@ pub struct RuntimeExt<'a> {
     block_height: BlockHeight,
     epoch_info_provider: &'a dyn EpochInfoProvider,
     current_protocol_version: ProtocolVersion,
+    view_call_env: Option<ViewCallEnv<'a>>,
+    /// Receives the host function calls of the executed contract, together with
+    /// the id of the receipt being executed.
+    execution_tracer: Option<(&'a dyn ExecutionTracer, CryptoHash)>,
+}
+
+/// What `RuntimeExt` needs to run the contracts called through `view_call`.
+#[derive(Clone, Copy)]
+pub(crate) struct ViewCallEnv<'a> {
+    pub apply_state: &'a ApplyState,
+    /// The receipt being executed, which view calls made while executing it share.
+    pub action_receipt: &'a ActionReceipt,
+    /// Number of view calls the current execution is nested in.
+    pub depth: u64,
 }
 
 /// Error used by `RuntimeExt`.
@@ -88,9 +113,26 @@ impl<'a> RuntimeExt<'a> {
             block_height,
             epoch_info_provider,
             current_protocol_version,
+            view_call_env: None,
+            execution_tracer: None,
         }
     }
 
+    /// Allows the executed contract to call view methods of other contracts.
+    /// Without it, all view calls fail.
+    pub(crate) fn set_view_call_env(&mut self, view_call_env: ViewCallEnv<'a>) {
+        self.view_call_env = Some(view_call_env);
+    }
+
+    /// Reports the host function calls of the executed contract to `tracer`.
+    pub(crate) fn set_execution_tracer(
+        &mut self,
+        tracer: &'a dyn ExecutionTracer,
+        receipt_id: CryptoHash,
+    ) {
+        self.execution_tracer = Some((tracer, receipt_id));
+    }
+
     #[inline]
     pub fn account_id(&self) -> &AccountId {
         &self.account_id
@@ -105,6 +147,27 @@ impl<'a> RuntimeExt<'a> {
         TrieKey::ContractData { account_id: self.account_id.clone(), key: key.to_vec() }
     }
 
+    pub fn create_storage_ns_key(&self, namespace: &[u8], key: &[u8]) -> ExtResult<TrieKey> {
+        let namespace = StorageNamespace::try_from(namespace.to_vec()).map_err(|_| {
+            HostError::NamespaceLengthExceeded {
+                length: namespace.len() as u64,
+                limit: StorageNamespace::MAX_LEN as u64,
+            }
+        })?;
+        Ok(TrieKey::NamespacedContractData {
+            account_id: self.account_id.clone(),
+            namespace,
+            key: key.to_vec(),
+        })
+    }
+
+    fn create_storage_ns_usage_key(&self, namespace: &[u8]) -> TrieKey {
+        TrieKey::StorageNamespaceUsage {
+            account_id: self.account_id.clone(),
+            namespace: namespace.to_vec(),
+        }
+    }
+
     #[inline]
     pub fn protocol_version(&self) -> ProtocolVersion {
         self.current_protocol_version
@@ -185,6 +248,52 @@ impl<'a> External for RuntimeExt<'a> {
         Ok(())
     }
 
+    fn storage_ns_set(&mut self, namespace: &[u8], key: &[u8], value: &[u8]) -> ExtResult<()> {
+        let storage_key = self.create_storage_ns_key(namespace, key)?;
+        self.trie_update.set(storage_key, Vec::from(value));
+        Ok(())
+    }
+
+    fn storage_ns_get<'b>(
+        &'b self,
+        namespace: &[u8],
+        key: &[u8],
+        mode: StorageGetMode,
+    ) -> ExtResult<Option<Box<dyn ValuePtr + 'b>>> {
+        let storage_key = self.create_storage_ns_key(namespace, key)?;
+        let mode = match mode {
+            StorageGetMode::FlatStorage => KeyLookupMode::FlatStorage,
+            StorageGetMode::Trie => KeyLookupMode::Trie,
+        };
+        self.trie_update
+            .get_ref(&storage_key, mode)
+            .map_err(wrap_storage_error)
+            .map(|option| option.map(|ptr| Box::new(RuntimeExtValuePtr(ptr)) as Box<_>))
+    }
+
+    fn storage_ns_remove(&mut self, namespace: &[u8], key: &[u8]) -> ExtResult<()> {
+        let storage_key = self.create_storage_ns_key(namespace, key)?;
+        self.trie_update.remove(storage_key);
+        Ok(())
+    }
+
+    fn storage_ns_usage(&self, namespace: &[u8]) -> ExtResult<StorageUsage> {
+        let usage_key = self.create_storage_ns_usage_key(namespace);
+        near_store::get::<StorageUsage>(&*self.trie_update, &usage_key)
+            .map(|usage| usage.unwrap_or(0))
+            .map_err(wrap_storage_error)
+    }
+
+    fn set_storage_ns_usage(&mut self, namespace: &[u8], usage: StorageUsage) -> ExtResult<()> {
+        let usage_key = self.create_storage_ns_usage_key(namespace);
+        if usage == 0 {
+            self.trie_update.remove(usage_key);
+        } else {
+            near_store::set(self.trie_update, usage_key, &usage);
+        }
+        Ok(())
+    }
+
     fn generate_data_id(&mut self) -> CryptoHash {
         let data_id = create_receipt_id_from_action_hash(
             self.current_protocol_version,
@@ -223,6 +332,149 @@ impl<'a> External for RuntimeExt<'a> {
             .map_err(|e| ExternalError::ValidatorError(e).into())
     }
 
+    fn view_call(
+        &mut self,
+        receiver_id: &AccountId,
+        method_name: &str,
+        args: &[u8],
+        gas: Gas,
+    ) -> ExtResult<ViewCallOutcome> {
+        let failed = ViewCallOutcome { return_data: None, burnt_gas: 0, compute_usage: 0 };
+        let Some(env) = self.view_call_env else {
+            return Ok(failed);
+        };
+        let apply_state = env.apply_state;
+        // Accounts of other shards are not in the trie of this one. Calls to them are refused
+        // explicitly rather than treated like calls to missing accounts.
+        let receiver_shard_id = self
+            .epoch_info_provider
+            .account_id_to_shard_id(receiver_id, &self.epoch_id)
+            .map_err(ExternalError::ValidatorError)?;
+        if receiver_shard_id != apply_state.shard_id {
+            return Err(
+                HostError::ViewCallCrossShard { receiver_id: receiver_id.to_string() }.into()
+            );
+        }
+        let Some(account) =
+            get_account(self.trie_update, receiver_id).map_err(wrap_storage_error)?
+        else {
+            return Ok(failed);
+        };
+
+        let config = &apply_state.config;
+        let view_config = ViewConfig { max_gas_burnt: gas };
+        let gas_counter = GasCounter::new(
+            config.wasm_config.ext_costs.clone(),
+            gas,
+            config.wasm_config.regular_op_cost,
+            gas,
+            true,
+        );
+        let code_hash = contract_code_hash(
+            self.trie_update,
+            receiver_id,
+            &account,
+            self.current_protocol_version,
+        )
+        .map_err(wrap_storage_error)?;
+        let code_ext = RuntimeContractExt {
+            storage: self.trie_update.contract_storage(),
+            account_id: receiver_id,
+            code_hash,
+            current_protocol_version: self.current_protocol_version,
+        };
+        let contract = near_vm_runner::prepare(
+            &code_ext,
+            Arc::clone(&config.wasm_config),
+            apply_state.cache.as_deref(),
+            gas_counter,
+            method_name,
+        );
+        let function_call = FunctionCallAction {
+            method_name: method_name.to_string(),
+            args: args.to_vec(),
+            gas,
+            deposit: 0,
+        };
+
+        let mut receipt_manager = ReceiptManager::default();
+        let mut callee_ext = RuntimeExt::new(
+            self.trie_update,
+            &mut receipt_manager,
+            receiver_id.clone(),
+            account,
+            self.action_hash,
+            self.epoch_id,
+            self.prev_block_hash,
+            self.last_block_hash,
+            self.block_height,
+            self.epoch_info_provider,
+            self.current_protocol_version,
+        );
+        callee_ext.set_view_call_env(ViewCallEnv { depth: env.depth + 1, ..env });
+        callee_ext.execution_tracer = self.execution_tracer;
+        let outcome = match execute_function_call(
+            contract,
+            apply_state,
+            &mut callee_ext,
+            &self.account_id,
+            env.action_receipt,
+            Arc::new([]),
+            &function_call,
+            &self.action_hash,
+            config,
+            false,
+            Some(view_config),
+        ) {
+            Ok(outcome) => outcome,
+            Err(RuntimeError::StorageError(err)) => {
+                return Err(ExternalError::StorageError(err).into())
+            }
+            Err(RuntimeError::ValidatorError(err)) => {
+                return Err(ExternalError::ValidatorError(err).into())
+            }
+            // Any other error only concerns the called contract, so it fails the view call
+            // rather than the whole chunk. Its gas usage is unknown, so all of `gas` is burnt.
+            Err(_) => {
+                return Ok(ViewCallOutcome {
+                    return_data: None,
+                    burnt_gas: gas,
+                    compute_usage: gas,
+                })
+            }
+        };
+
+        let return_data = match (outcome.aborted, outcome.return_data) {
+            (Some(_), _) => None,
+            (None, ReturnData::Value(data)) => Some(data),
+            (None, ReturnData::None) => Some(vec![]),
+            // View calls can't create receipts.
+            (None, ReturnData::ReceiptIndex(_)) => None,
+        };
+        Ok(ViewCallOutcome {
+            return_data,
+            burnt_gas: outcome.burnt_gas,
+            compute_usage: outcome.compute_usage,
+        })
+    }
+
+    fn view_call_depth(&self) -> u64 {
+        self.view_call_env.map_or(0, |env| env.depth)
+    }
+
+    fn traces_host_functions(&self) -> bool {
+        self.execution_tracer.is_some()
+    }
+
+    fn trace_host_function(&mut self, name: &'static str) {
+        if let Some((tracer, receipt_id)) = self.execution_tracer {
+            tracer.trace(ExecutionTraceEvent::HostFunctionCall {
+                receipt_id,
+                name: name.to_string(),
+            });
+        }
+    }
+
     fn create_action_receipt(
         &mut self,
         receipt_indices: Vec<ReceiptIndex>,
@@ -237,10 +489,11 @@ impl<'a> External for RuntimeExt<'a> {
     fn create_promise_yield_receipt(
         &mut self,
         receiver_id: AccountId,
+        timeout_length_in_blocks: BlockHeightDelta,
     ) -> Result<(ReceiptIndex, CryptoHash), VMLogicError> {
         let input_data_id = self.generate_data_id();
         self.receipt_manager
-            .create_promise_yield_receipt(input_data_id, receiver_id)
+            .create_promise_yield_receipt(input_data_id, receiver_id, timeout_length_in_blocks)
             .map(|receipt_index| (receipt_index, input_data_id))
     }
 
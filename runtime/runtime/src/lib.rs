@@ -6,7 +6,10 @@ use crate::config::{
 };
 use crate::congestion_control::DelayedReceiptQueueWrapper;
 use crate::prefetch::TriePrefetcher;
-use crate::verifier::{check_storage_stake, validate_receipt, StorageStakingError};
+use crate::verifier::{
+    check_storage_stake, migration_grace_allowance, storage_sponsorship_deposit, validate_receipt,
+    StorageStakingError,
+};
 pub use crate::verifier::{
     validate_transaction, verify_and_charge_transaction, ZERO_BALANCE_ACCOUNT_STORAGE_LIMIT,
 };
@@ -30,7 +33,7 @@ use near_primitives::errors::{
 use near_primitives::hash::CryptoHash;
 use near_primitives::receipt::{
     ActionReceipt, DataReceipt, DelayedReceiptIndices, PromiseYieldIndices, PromiseYieldTimeout,
-    Receipt, ReceiptEnum, ReceiptOrStateStoredReceipt, ReceiptV0, ReceivedData,
+    Receipt, ReceiptEnum, ReceiptOrStateStoredReceipt, ReceiptV0, ReceiptV2, ReceivedData,
 };
 use near_primitives::runtime::migration_data::{MigrationData, MigrationFlags};
 use near_primitives::sandbox::state_patch::SandboxStatePatch;
@@ -39,8 +42,8 @@ use near_primitives::stateless_validation::contract_distribution::ContractUpdate
 #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
 use near_primitives::transaction::NonrefundableStorageTransferAction;
 use near_primitives::transaction::{
-    Action, ExecutionMetadata, ExecutionOutcome, ExecutionOutcomeWithId, ExecutionStatus, LogEntry,
-    SignedTransaction, TransferAction,
+    Action, DeployContractAction, ExecutionMetadata, ExecutionOutcome, ExecutionOutcomeWithId,
+    ExecutionStatus, GasRefund, LogEntry, SignedTransaction, TransferAction,
 };
 use near_primitives::trie_key::TrieKey;
 use near_primitives::types::{
@@ -57,22 +60,24 @@ use near_primitives_core::apply::ApplyChunkReason;
 use near_store::trie::receipts_column_helper::DelayedReceiptQueue;
 use near_store::trie::update::TrieUpdateResult;
 use near_store::{
-    get, get_account, get_postponed_receipt, get_promise_yield_receipt, get_pure,
-    get_received_data, has_received_data, remove_account, remove_postponed_receipt,
-    remove_promise_yield_receipt, set, set_access_key, set_account, set_postponed_receipt,
-    set_promise_yield_receipt, set_received_data, PartialStorage, StorageError, Trie, TrieAccess,
-    TrieChanges, TrieUpdate,
+    get, get_account, get_expired_promise_yield_timeouts_at, get_postponed_receipt,
+    get_promise_yield_receipt, get_pure, get_received_data, has_received_data, remove_account,
+    remove_postponed_receipt, remove_promise_yield_receipt, set, set_access_key, set_account,
+    set_postponed_receipt, set_promise_yield_receipt, set_received_data, PartialStorage,
+    StorageError, Trie, TrieAccess, TrieChanges, TrieUpdate,
 };
 use near_vm_runner::logic::types::PromiseResult;
-use near_vm_runner::logic::ReturnData;
+use near_vm_runner::logic::{RecentBlockHeader, ReturnData};
 pub use near_vm_runner::with_ext_cost_counter;
 use near_vm_runner::ContractCode;
 use near_vm_runner::ContractRuntimeCache;
 use near_vm_runner::ProfileDataV3;
 use pipelining::ReceiptPreparationPipeline;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use std::cmp::max;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use trace::{ExecutionTraceEvent, ExecutionTracer};
 use tracing::{debug, instrument};
 
 mod actions;
@@ -90,6 +95,7 @@ pub mod receipt_manager;
 pub mod state_viewer;
 #[cfg(test)]
 mod tests;
+pub mod trace;
 mod verifier;
 
 const EXPECT_ACCOUNT_EXISTS: &str = "account exists, checked above";
@@ -143,6 +149,11 @@ pub struct ApplyState {
     /// Each shard requests some bandwidth to other shards and then the bandwidth scheduler
     /// decides how much each shard is allowed to send.
     pub bandwidth_requests: BlockBandwidthRequests,
+    /// Headers of the blocks preceding the current one, most recent first,
+    /// which contracts read with the `block_header` host function.
+    pub recent_block_headers: Arc<[RecentBlockHeader]>,
+    /// Receives events about the execution of receipts, see [`trace`].
+    pub execution_tracer: Option<Arc<dyn ExecutionTracer>>,
 }
 
 /// Contains information to update validators accounts at the first block of a new epoch.
@@ -150,7 +161,8 @@ pub struct ApplyState {
 pub struct ValidatorAccountsUpdate {
     /// Maximum stake across last 3 epochs.
     pub stake_info: HashMap<AccountId, Balance>,
-    /// Rewards to distribute to validators.
+    /// Rewards to distribute to validators. Also includes rewards of challengers, which are
+    /// credited to the liquid balance if the challenger doesn't stake.
     pub validator_rewards: HashMap<AccountId, Balance>,
     /// Stake proposals from the last chunk.
     pub last_proposals: HashMap<AccountId, Balance>,
@@ -267,6 +279,18 @@ impl Default for ActionResult {
     }
 }
 
+/// Balances resulting from refunding the unused gas of a receipt.
+#[derive(Debug, Default)]
+struct GasRefundResult {
+    /// The part of the burnt gas that couldn't be paid because the gas price increased since the
+    /// gas was purchased.
+    gas_deficit_amount: Balance,
+    /// The balance burnt for the unused gas that wasn't refunded.
+    refund_penalty_amount: Balance,
+    /// The split of the unused gas, set when `ReducedGasRefunds` is enabled.
+    gas_refund: Option<GasRefund>,
+}
+
 pub struct Runtime {}
 
 impl Runtime {
@@ -311,6 +335,7 @@ impl Runtime {
         state_update: &mut TrieUpdate,
         apply_state: &ApplyState,
         signed_transaction: &SignedTransaction,
+        verify_signature: bool,
         stats: &mut ApplyStats,
     ) -> Result<(Receipt, ExecutionOutcomeWithId), InvalidTxError> {
         let span = tracing::Span::current();
@@ -321,7 +346,7 @@ impl Runtime {
             state_update,
             apply_state.gas_price,
             signed_transaction,
-            true,
+            verify_signature,
             Some(apply_state.block_height),
             apply_state.current_protocol_version,
         ) {
@@ -338,19 +363,34 @@ impl Runtime {
                     &apply_state.block_hash,
                     apply_state.block_height,
                 );
-                let receipt = Receipt::V0(ReceiptV0 {
-                    predecessor_id: transaction.signer_id().clone(),
-                    receiver_id: transaction.receiver_id().clone(),
-                    receipt_id,
-                    receipt: ReceiptEnum::Action(ActionReceipt {
-                        signer_id: transaction.signer_id().clone(),
-                        signer_public_key: transaction.public_key().clone(),
-                        gas_price: verification_result.receipt_gas_price,
-                        output_data_receivers: vec![],
-                        input_data_ids: vec![],
-                        actions: transaction.actions().to_vec(),
-                    }),
+                let action_receipt = ReceiptEnum::Action(ActionReceipt {
+                    signer_id: transaction.signer_id().clone(),
+                    signer_public_key: transaction.public_key().clone(),
+                    gas_price: verification_result.receipt_gas_price,
+                    output_data_receivers: vec![],
+                    input_data_ids: vec![],
+                    actions: transaction.actions().to_vec(),
                 });
+                let receipt = if ProtocolFeature::ReceiptExpiry
+                    .enabled(apply_state.current_protocol_version)
+                {
+                    Receipt::V2(ReceiptV2 {
+                        predecessor_id: transaction.signer_id().clone(),
+                        receiver_id: transaction.receiver_id().clone(),
+                        receipt_id,
+                        receipt: action_receipt,
+                        priority: 0,
+                        expiry_height: apply_state.block_height
+                            + apply_state.config.wasm_config.limit_config.receipt_ttl_in_blocks,
+                    })
+                } else {
+                    Receipt::V0(ReceiptV0 {
+                        predecessor_id: transaction.signer_id().clone(),
+                        receiver_id: transaction.receiver_id().clone(),
+                        receipt_id,
+                        receipt: action_receipt,
+                    })
+                };
                 stats.tx_burnt_amount =
                     safe_add_balance(stats.tx_burnt_amount, verification_result.burnt_amount)
                         .map_err(|_| InvalidTxError::CostOverflow)?;
@@ -462,12 +502,14 @@ impl Runtime {
             }
             Action::FunctionCall(function_call) => {
                 let account = account.as_mut().expect(EXPECT_ACCOUNT_EXISTS);
-                let contract = preparation_pipeline.get_contract(
-                    receipt,
-                    account.code_hash(),
-                    action_index,
-                    None,
-                );
+                let code_hash = contract_code_hash(
+                    state_update,
+                    account_id,
+                    account,
+                    apply_state.current_protocol_version,
+                )?;
+                let contract =
+                    preparation_pipeline.get_contract(receipt, code_hash, action_index, None);
                 let is_last_action = action_index + 1 == actions.len();
                 action_function_call(
                     state_update,
@@ -480,7 +522,7 @@ impl Runtime {
                     account_id,
                     function_call,
                     action_hash,
-                    account.code_hash(),
+                    code_hash,
                     &apply_state.config,
                     is_last_action,
                     epoch_info_provider,
@@ -524,6 +566,7 @@ impl Runtime {
                     &mut result,
                     account_id,
                     stake,
+                    None,
                     &apply_state.prev_block_hash,
                     epoch_info_provider,
                 )?;
@@ -572,6 +615,92 @@ impl Runtime {
                     receipt.priority(),
                 )?;
             }
+            Action::SponsorStorage(sponsor_storage) => {
+                action_sponsor_storage(
+                    state_update,
+                    &mut result,
+                    account_id,
+                    receipt.predecessor_id(),
+                    sponsor_storage,
+                )?;
+            }
+            Action::TransferToken(transfer_token) => {
+                action_transfer_token(
+                    state_update,
+                    &apply_state.config.fees,
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    account_id,
+                    receipt.predecessor_id(),
+                    transfer_token,
+                    &mut result,
+                )?;
+            }
+            Action::StakeWithMetadata(stake_with_metadata) => {
+                action_stake(
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    &mut result,
+                    account_id,
+                    &stake_with_metadata.stake,
+                    Some(&stake_with_metadata.metadata),
+                    &apply_state.prev_block_hash,
+                    epoch_info_provider,
+                )?;
+            }
+            Action::DeployGlobalContract(deploy_global_contract) => {
+                action_deploy_global_contract(
+                    state_update,
+                    apply_state,
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    account_id,
+                    deploy_global_contract,
+                    &mut result,
+                    epoch_info_provider,
+                )?;
+            }
+            Action::UseGlobalContract(use_global_contract) => {
+                action_use_global_contract(
+                    state_update,
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    account_id,
+                    use_global_contract,
+                    &mut result,
+                    apply_state.current_protocol_version,
+                )?;
+            }
+            Action::MigrateState(migrate_state) => {
+                let account = account.as_mut().expect(EXPECT_ACCOUNT_EXISTS);
+                let storage_usage_before = account.storage_usage();
+                action_deploy_contract(
+                    state_update,
+                    account,
+                    account_id,
+                    &DeployContractAction { code: migrate_state.code.clone() },
+                    Arc::clone(&apply_state.config.wasm_config),
+                    apply_state.cache.as_deref(),
+                    apply_state.current_protocol_version,
+                )?;
+                let contract = preparation_pipeline.get_contract(
+                    receipt,
+                    account.code_hash(),
+                    action_index,
+                    None,
+                );
+                action_migrate_state(
+                    state_update,
+                    apply_state,
+                    account,
+                    receipt,
+                    action_receipt,
+                    promise_results,
+                    &mut result,
+                    account_id,
+                    migrate_state,
+                    action_hash,
+                    epoch_info_provider,
+                    storage_usage_before,
+                    contract,
+                )?;
+            }
         };
         Ok(result)
     }
@@ -600,6 +729,11 @@ impl Runtime {
             _ => unreachable!("given receipt should be an action receipt"),
         };
         let account_id = receipt.receiver_id();
+        apply_state.trace(|| ExecutionTraceEvent::ReceiptStart {
+            receipt_id: *receipt.receipt_id(),
+            predecessor_id: receipt.predecessor_id().clone(),
+            receiver_id: account_id.clone(),
+        });
         // Collecting input data and removing it from the state
         let promise_results = action_receipt
             .input_data_ids
@@ -638,9 +772,23 @@ impl Runtime {
         result.compute_usage = exec_fees;
         #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
         let mut nonrefundable_amount_burnt: Balance = 0;
+        let mut global_contract_storage_burnt: Balance = 0;
+
+        // Receipts which waited too long, e.g. in the delayed receipts queue, are not executed.
+        // Failing the receipt refunds the deposits and the prepaid gas below.
+        if receipt.is_expired(apply_state.block_height) {
+            result.result = Err(ActionErrorKind::ReceiptExpired {
+                expiry_height: receipt.expiry_height().expect("expired receipt has expiry height"),
+                block_height: apply_state.block_height,
+            }
+            .into());
+        }
 
         // Executing actions one by one
         for (action_index, action) in action_receipt.actions.iter().enumerate() {
+            if result.result.is_err() {
+                break;
+            }
             let action_hash = create_action_hash_from_receipt_id(
                 apply_state.current_protocol_version,
                 receipt.receipt_id(),
@@ -649,6 +797,11 @@ impl Runtime {
                 apply_state.block_height,
                 action_index,
             );
+            apply_state.trace(|| ExecutionTraceEvent::ActionStart {
+                receipt_id: *receipt.receipt_id(),
+                action_index: action_index as u64,
+                action: action.as_ref().to_string(),
+            });
             let mut new_result = self.apply_action(
                 action,
                 state_update,
@@ -675,6 +828,12 @@ impl Runtime {
                     new_result.result = Err(ActionErrorKind::NewReceiptValidationError(e).into());
                 }
             }
+            apply_state.trace(|| ExecutionTraceEvent::ActionEnd {
+                receipt_id: *receipt.receipt_id(),
+                action_index: action_index as u64,
+                gas_burnt: new_result.gas_burnt,
+                error: new_result.result.as_ref().err().map(|err| err.kind.clone()),
+            });
             result.merge(new_result)?;
             // TODO storage error
             if let Err(ref mut res) = result.result {
@@ -689,13 +848,38 @@ impl Runtime {
             {
                 nonrefundable_amount_burnt = safe_add_balance(nonrefundable_amount_burnt, *deposit)?
             }
+            if let Action::DeployGlobalContract(deploy_global_contract) = action {
+                let storage_cost =
+                    global_contract_storage_cost(&apply_state.config, deploy_global_contract)
+                        .ok_or_else(|| {
+                            RuntimeError::UnexpectedIntegerOverflow(
+                                "global_contract_storage_cost".into(),
+                            )
+                        })?;
+                global_contract_storage_burnt =
+                    safe_add_balance(global_contract_storage_burnt, storage_cost)?;
+            }
         }
 
         // Going to check balance covers account's storage.
         if result.result.is_ok() {
             if let Some(ref mut account) = account {
+                let sponsored_deposit = storage_sponsorship_deposit(
+                    state_update,
+                    account_id,
+                    apply_state.current_protocol_version,
+                )?
+                .saturating_add(migration_grace_allowance(
+                    state_update,
+                    account_id,
+                    account,
+                    apply_state.block_height,
+                    &apply_state.config,
+                    apply_state.current_protocol_version,
+                )?);
                 match check_storage_stake(
                     account,
+                    sponsored_deposit,
                     &apply_state.config,
                     apply_state.current_protocol_version,
                 ) {
@@ -723,7 +907,7 @@ impl Runtime {
             }
         }
 
-        let gas_deficit_amount = if receipt.predecessor_id().is_system() {
+        let refund_result = if receipt.predecessor_id().is_system() {
             // We will set gas_burnt for refund receipts to be 0 when we calculate tx_burnt_amount
             // Here we don't set result.gas_burnt to be zero if CountRefundReceiptsInGasLimit is
             // enabled because we want it to be counted in gas limit calculation later
@@ -744,7 +928,7 @@ impl Runtime {
                     total_deposit(&action_receipt.actions)?,
                 )?
             }
-            0
+            GasRefundResult::default()
         } else {
             // Calculating and generating refunds
             self.generate_refund_receipts(
@@ -753,8 +937,10 @@ impl Runtime {
                 action_receipt,
                 &mut result,
                 &apply_state.config,
+                apply_state.current_protocol_version,
             )?
         };
+        let gas_deficit_amount = refund_result.gas_deficit_amount;
         stats.gas_deficit_amount = safe_add_balance(stats.gas_deficit_amount, gas_deficit_amount)?;
 
         // Moving validator proposals
@@ -777,6 +963,11 @@ impl Runtime {
             stats.other_burnt_amount =
                 safe_add_balance(stats.other_burnt_amount, nonrefundable_amount_burnt)?;
         }
+        // The storage of global contracts is paid for by burning tokens of the deployer.
+        if result.result.is_ok() {
+            stats.other_burnt_amount =
+                safe_add_balance(stats.other_burnt_amount, global_contract_storage_burnt)?;
+        }
 
         // If the receipt is a refund, then we consider it free without burnt gas.
         let gas_burnt: Gas =
@@ -784,6 +975,8 @@ impl Runtime {
         // `gas_deficit_amount` is strictly less than `gas_price * gas_burnt`.
         let mut tx_burnt_amount =
             safe_gas_to_balance(apply_state.gas_price, gas_burnt)? - gas_deficit_amount;
+        // The penalty for the unused gas is burnt as well.
+        tx_burnt_amount = safe_add_balance(tx_burnt_amount, refund_result.refund_penalty_amount)?;
         // The amount of tokens burnt for the execution of this receipt. It's used in the execution
         // outcome.
         let tokens_burnt = tx_burnt_amount;
@@ -908,6 +1101,11 @@ impl Runtime {
             Ok(ReturnData::None) => ExecutionStatus::SuccessValue(vec![]),
             Err(e) => ExecutionStatus::Failure(TxExecutionError::ActionError(e)),
         };
+        apply_state.trace(|| ExecutionTraceEvent::ReceiptEnd {
+            receipt_id: *receipt.receipt_id(),
+            gas_burnt: result.gas_burnt,
+            status: status.clone().into(),
+        });
 
         Self::print_log(&result.logs);
 
@@ -921,9 +1119,13 @@ impl Runtime {
                 compute_usage: Some(result.compute_usage),
                 tokens_burnt,
                 executor_id: account_id.clone(),
-                metadata: ExecutionMetadata::V3(Box::new(conversions::Convert::convert(
-                    *result.profile,
-                ))),
+                metadata: {
+                    let profile = Box::new(conversions::Convert::convert(*result.profile));
+                    match refund_result.gas_refund {
+                        Some(gas_refund) => ExecutionMetadata::V4(profile, gas_refund),
+                        None => ExecutionMetadata::V3(profile),
+                    }
+                },
             },
         })
     }
@@ -935,7 +1137,8 @@ impl Runtime {
         action_receipt: &ActionReceipt,
         result: &mut ActionResult,
         config: &RuntimeConfig,
-    ) -> Result<Balance, RuntimeError> {
+        protocol_version: ProtocolVersion,
+    ) -> Result<GasRefundResult, RuntimeError> {
         let total_deposit = total_deposit(&action_receipt.actions)?;
         let prepaid_gas = safe_add_gas(
             total_prepaid_gas(&action_receipt.actions)?,
@@ -946,11 +1149,21 @@ impl Runtime {
             config.fees.fee(ActionCosts::new_action_receipt).exec_fee(),
         )?;
         let deposit_refund = if result.result.is_err() { total_deposit } else { 0 };
-        let gas_refund = if result.result.is_err() {
+        let unused_gas = if result.result.is_err() {
             safe_add_gas(prepaid_gas, prepaid_exec_gas)? - result.gas_burnt
         } else {
             safe_add_gas(prepaid_gas, prepaid_exec_gas)? - result.gas_used
         };
+        // ReducedGasRefunds: Part of the unused gas is burnt instead of refunded, to make
+        // attaching much more gas than needed less attractive.
+        let gas_refund_penalty = if ProtocolFeature::ReducedGasRefunds.enabled(protocol_version) {
+            config.fees.gas_refund_penalty(unused_gas)
+        } else {
+            0
+        };
+        let gas_refund = unused_gas - gas_refund_penalty;
+        let refund_penalty_amount =
+            safe_gas_to_balance(action_receipt.gas_price, gas_refund_penalty)?;
         // Refund for the unused portion of the gas at the price at which this gas was purchased.
         let mut gas_balance_refund = safe_gas_to_balance(action_receipt.gas_price, gas_refund)?;
         let mut gas_deficit_amount = 0;
@@ -988,6 +1201,18 @@ impl Runtime {
                 receipt.priority(),
             ));
         }
+        if result.result.is_err() {
+            for action in &action_receipt.actions {
+                if let Action::TransferToken(transfer) = action {
+                    result.new_receipts.push(Receipt::new_token_refund(
+                        receipt.predecessor_id(),
+                        &transfer.token_id,
+                        transfer.amount,
+                        receipt.priority(),
+                    ));
+                }
+            }
+        }
         if gas_balance_refund > 0 {
             // Gas refunds refund the allowance of the access key, so if the key exists on the
             // account it will increase the allowance by the refund amount.
@@ -998,7 +1223,13 @@ impl Runtime {
                 receipt.priority(),
             ));
         }
-        Ok(gas_deficit_amount)
+        Ok(GasRefundResult {
+            gas_deficit_amount,
+            refund_penalty_amount,
+            gas_refund: ProtocolFeature::ReducedGasRefunds
+                .enabled(protocol_version)
+                .then_some(GasRefund { gas_refunded: gas_refund, gas_refund_penalty }),
+        })
     }
 
     fn process_receipt(
@@ -1200,6 +1431,31 @@ impl Runtime {
                     return Ok(None);
                 }
             }
+            ReceiptEnum::GlobalContractDistribution(ref distribution_receipt) => {
+                // Store the global contract in this shard and pass it on to the next one.
+                if let Some(mut next_receipt) = apply_global_contract_distribution_receipt(
+                    state_update,
+                    apply_state,
+                    receipt,
+                    distribution_receipt,
+                    epoch_info_provider,
+                )? {
+                    next_receipt.set_receipt_id(create_receipt_id_from_receipt_id(
+                        apply_state.current_protocol_version,
+                        receipt.receipt_id(),
+                        &apply_state.prev_block_hash,
+                        &apply_state.block_hash,
+                        apply_state.block_height,
+                        0,
+                    ));
+                    receipt_sink.forward_or_buffer_receipt(
+                        next_receipt,
+                        apply_state,
+                        state_update,
+                        epoch_info_provider,
+                    )?;
+                }
+            }
         };
         // We didn't trigger execution, so we need to commit the state.
         state_update
@@ -1306,6 +1562,34 @@ impl Runtime {
             }
         }
 
+        for (account_id, reward) in validator_accounts_update.validator_rewards.iter() {
+            // Rewards of staking accounts were already distributed above, and the protocol
+            // treasury is handled below.
+            if validator_accounts_update.stake_info.contains_key(account_id)
+                || validator_accounts_update.protocol_treasury_account_id.as_ref()
+                    == Some(account_id)
+            {
+                continue;
+            }
+            if let Some(mut account) = get_account(state_update, account_id)? {
+                debug!(target: "runtime", "account {} adding challenger reward {}", account_id, reward);
+                account.set_amount(account.amount().checked_add(*reward).ok_or_else(|| {
+                    RuntimeError::UnexpectedIntegerOverflow(
+                        "update_validator_accounts - challenger_reward".into(),
+                    )
+                })?);
+                set_account(state_update, account_id.clone(), &account);
+            } else {
+                // The challenger account was deleted, the reward is burnt.
+                stats.other_burnt_amount =
+                    stats.other_burnt_amount.checked_add(*reward).ok_or_else(|| {
+                        RuntimeError::UnexpectedIntegerOverflow(
+                            "update_validator_accounts - burnt challenger_reward".into(),
+                        )
+                    })?;
+            }
+        }
+
         if let Some(account_id) = &validator_accounts_update.protocol_treasury_account_id {
             // If protocol treasury stakes, then the rewards was already distributed above.
             if !validator_accounts_update.stake_info.contains_key(account_id) {
@@ -1586,11 +1870,17 @@ impl Runtime {
         let total = &mut processing_state.total;
         let apply_state = &mut processing_state.apply_state;
         let state_update = &mut processing_state.state_update;
-        for signed_transaction in processing_state.transactions {
+        let signatures_valid = verify_transaction_signatures(processing_state.transactions);
+        for (signed_transaction, signature_valid) in
+            processing_state.transactions.iter().zip(signatures_valid)
+        {
+            // Transactions with an invalid signature are verified again while
+            // processing them, so that they fail with the same error as before.
             let tx_result = self.process_transaction(
                 state_update,
                 apply_state,
                 signed_transaction,
+                !signature_valid,
                 &mut processing_state.stats,
             );
             let (receipt, outcome_with_id) = match tx_result {
@@ -2349,7 +2639,7 @@ fn resolve_promise_yield_timeouts(
     compute_limit: u64,
     proof_size_limit: Option<usize>,
 ) -> Result<ResolvePromiseYieldTimeoutsResult, RuntimeError> {
-    let mut state_update = &mut processing_state.state_update;
+    let state_update = &mut processing_state.state_update;
     let total = &mut processing_state.total;
     let apply_state = &processing_state.apply_state;
 
@@ -2385,44 +2675,15 @@ fn resolve_promise_yield_timeouts(
             break;
         }
 
-        // Check if the yielded promise still needs to be resolved
-        let promise_yield_key = TrieKey::PromiseYieldReceipt {
-            receiver_id: queue_entry.account_id.clone(),
-            data_id: queue_entry.data_id,
-        };
-        if state_update.contains_key(&promise_yield_key)? {
-            let new_receipt_id = create_receipt_id_from_receipt_id(
-                processing_state.protocol_version,
-                &queue_entry.data_id,
-                &apply_state.prev_block_hash,
-                &apply_state.block_hash,
-                apply_state.block_height,
-                new_receipt_index,
-            );
-            new_receipt_index += 1;
-
-            // Create a PromiseResume receipt to resolve the timed-out yield.
-            let resume_receipt = Receipt::V0(ReceiptV0 {
-                predecessor_id: queue_entry.account_id.clone(),
-                receiver_id: queue_entry.account_id.clone(),
-                receipt_id: new_receipt_id,
-                receipt: ReceiptEnum::PromiseResume(DataReceipt {
-                    data_id: queue_entry.data_id,
-                    data: None,
-                }),
-            });
-
-            // The receipt is destined for the local shard and will be placed in the outgoing
-            // receipts buffer. It is possible that there is already an outgoing receipt resolving
-            // this yield if `yield_resume` was invoked by some receipt which was processed in
-            // the current chunk. The ordering will be maintained because the receipts are
-            // destined for the same shard; the timeout will be processed second and discarded.
-            receipt_sink.forward_or_buffer_receipt(
-                resume_receipt.clone(),
-                apply_state,
-                &mut state_update,
-                processing_state.epoch_info_provider,
-            )?;
+        if let Some(resume_receipt) = resolve_promise_yield_timeout(
+            processing_state.protocol_version,
+            apply_state,
+            processing_state.epoch_info_provider,
+            state_update,
+            receipt_sink,
+            &queue_entry,
+            &mut new_receipt_index,
+        )? {
             timeout_receipts.push(resume_receipt);
         }
 
@@ -2431,8 +2692,52 @@ fn resolve_promise_yield_timeouts(
         // Math checked above: first_index is less than next_available_index
         promise_yield_indices.first_index += 1;
     }
+
+    // Timeouts which don't have the default length are keyed by the height they expire at,
+    // so all expired ones come first when iterating over them.
+    let mut num_processed_yield_timeouts_at = 0;
+    'timeouts_at: loop {
+        let keys = get_expired_promise_yield_timeouts_at(
+            state_update,
+            apply_state.block_height,
+            PROMISE_YIELD_TIMEOUTS_AT_BATCH_SIZE,
+        )?;
+        if keys.is_empty() {
+            break;
+        }
+        for key in keys {
+            if total.compute >= compute_limit
+                || proof_size_limit.is_some_and(|limit| {
+                    state_update.trie.recorded_storage_size_upper_bound() > limit
+                })
+            {
+                break 'timeouts_at;
+            }
+
+            let timeout = get::<PromiseYieldTimeout>(state_update, &key)?.ok_or_else(|| {
+                StorageError::StorageInconsistentState(format!(
+                    "PromiseYield timeout {:?} should be in the state",
+                    key
+                ))
+            })?;
+            if let Some(resume_receipt) = resolve_promise_yield_timeout(
+                processing_state.protocol_version,
+                apply_state,
+                processing_state.epoch_info_provider,
+                state_update,
+                receipt_sink,
+                &timeout,
+                &mut new_receipt_index,
+            )? {
+                timeout_receipts.push(resume_receipt);
+            }
+            state_update.remove(key);
+            num_processed_yield_timeouts_at += 1;
+        }
+    }
+
     processing_state.metrics.yield_timeouts_done(
-        processed_yield_timeouts.len() as u64,
+        processed_yield_timeouts.len() as u64 + num_processed_yield_timeouts_at,
         yield_processing_start.elapsed(),
         total.gas,
         total.compute,
@@ -2445,6 +2750,60 @@ fn resolve_promise_yield_timeouts(
     })
 }
 
+/// Number of expired PromiseYield timeouts keyed by height to read from the state at once.
+const PROMISE_YIELD_TIMEOUTS_AT_BATCH_SIZE: usize = 64;
+
+/// Creates a PromiseResume receipt resolving the yielded promise of the expired `timeout`,
+/// unless the promise was already resolved.
+fn resolve_promise_yield_timeout(
+    protocol_version: ProtocolVersion,
+    apply_state: &ApplyState,
+    epoch_info_provider: &dyn EpochInfoProvider,
+    state_update: &mut TrieUpdate,
+    receipt_sink: &mut ReceiptSink,
+    timeout: &PromiseYieldTimeout,
+    new_receipt_index: &mut usize,
+) -> Result<Option<Receipt>, RuntimeError> {
+    // Check if the yielded promise still needs to be resolved
+    let promise_yield_key = TrieKey::PromiseYieldReceipt {
+        receiver_id: timeout.account_id.clone(),
+        data_id: timeout.data_id,
+    };
+    if !state_update.contains_key(&promise_yield_key)? {
+        return Ok(None);
+    }
+    let new_receipt_id = create_receipt_id_from_receipt_id(
+        protocol_version,
+        &timeout.data_id,
+        &apply_state.prev_block_hash,
+        &apply_state.block_hash,
+        apply_state.block_height,
+        *new_receipt_index,
+    );
+    *new_receipt_index += 1;
+
+    // Create a PromiseResume receipt to resolve the timed-out yield.
+    let resume_receipt = Receipt::V0(ReceiptV0 {
+        predecessor_id: timeout.account_id.clone(),
+        receiver_id: timeout.account_id.clone(),
+        receipt_id: new_receipt_id,
+        receipt: ReceiptEnum::PromiseResume(DataReceipt { data_id: timeout.data_id, data: None }),
+    });
+
+    // The receipt is destined for the local shard and will be placed in the outgoing
+    // receipts buffer. It is possible that there is already an outgoing receipt resolving
+    // this yield if `yield_resume` was invoked by some receipt which was processed in
+    // the current chunk. The ordering will be maintained because the receipts are
+    // destined for the same shard; the timeout will be processed second and discarded.
+    receipt_sink.forward_or_buffer_receipt(
+        resume_receipt.clone(),
+        apply_state,
+        state_update,
+        epoch_info_provider,
+    )?;
+    Ok(Some(resume_receipt))
+}
+
 struct TotalResourceGuard {
     gas: u64,
     compute: u64,
@@ -2676,6 +3035,7 @@ fn schedule_contract_preparation<'b, R: MaybeRefReceipt>(
                     };
                     return handle_receipt(mgr, state_update, receiver, account_id, &yr);
                 }
+                ReceiptEnum::GlobalContractDistribution(_) => false,
             }
         }
         handle_receipt(pipeline_manager, state_update, &receiver, account_id, peek)
@@ -2683,6 +3043,17 @@ fn schedule_contract_preparation<'b, R: MaybeRefReceipt>(
     Some(scheduled_receipt_offset.saturating_add(1))
 }
 
+/// Verifies signatures of all transactions in parallel, with the same check
+/// as `validate_transaction`. Batched verification is not used as it isn't
+/// deterministic: it is cofactored and randomized, so it may accept signatures
+/// rejected by `Signature::verify`, depending on the random coefficients.
+fn verify_transaction_signatures(transactions: &[SignedTransaction]) -> Vec<bool> {
+    transactions
+        .par_iter()
+        .map(|tx| tx.signature.verify(tx.get_hash().as_ref(), tx.transaction.public_key()))
+        .collect()
+}
+
 #[cfg(feature = "estimator")]
 /// Interface provided for gas cost estimations.
 pub mod estimator {
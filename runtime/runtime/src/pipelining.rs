@@ -71,6 +71,9 @@ struct PrepareTaskKey {
 struct PrepareTask {
     status: Mutex<PrepareTaskStatus>,
     condvar: Condvar,
+    /// Hash of the code the task prepares. The code of an account using a global contract
+    /// by the account id of its deployer may change without a deployment to the account.
+    code_hash: CryptoHash,
 }
 
 enum PrepareTaskStatus {
@@ -120,13 +123,17 @@ impl ReceiptPreparationPipeline {
         }
         let actions = match receipt.receipt() {
             ReceiptEnum::Action(a) | ReceiptEnum::PromiseYield(a) => &a.actions,
-            ReceiptEnum::Data(_) | ReceiptEnum::PromiseResume(_) => return false,
+            ReceiptEnum::Data(_)
+            | ReceiptEnum::PromiseResume(_)
+            | ReceiptEnum::GlobalContractDistribution(_) => return false,
         };
         let mut any_function_calls = false;
         for (action_index, action) in actions.iter().enumerate() {
             let account_id = account_id.clone();
             match action {
-                Action::DeployContract(_) => {
+                Action::DeployContract(_)
+                | Action::UseGlobalContract(_)
+                | Action::MigrateState(_) => {
                     // FIXME: instead of blocking these accounts, move the handling of
                     // deploy action into here, so that the necessary data dependencies can be
                     // established.
@@ -136,7 +143,8 @@ impl ReceiptPreparationPipeline {
                     let Some(account) = &**account else { continue };
                     let code_hash = account.code_hash();
                     let key = PrepareTaskKey { receipt_id: receipt.get_hash(), action_index };
-                    let gas_counter = self.gas_counter(view_config.as_ref(), function_call.gas);
+                    let gas_counter =
+                        self.gas_counter(view_config.as_ref(), function_call.gas, None);
                     let entry = match self.map.entry(key) {
                         std::collections::btree_map::Entry::Vacant(v) => v,
                         // Already been submitted.
@@ -150,7 +158,7 @@ impl ReceiptPreparationPipeline {
                     let created = Instant::now();
                     let method_name = function_call.method_name.clone();
                     let status = Mutex::new(PrepareTaskStatus::Pending);
-                    let task = Arc::new(PrepareTask { status, condvar: Condvar::new() });
+                    let task = Arc::new(PrepareTask { status, condvar: Condvar::new(), code_hash });
                     entry.insert(Arc::clone(&task));
                     PIPELINING_ACTIONS_SUBMITTED.inc_by(1);
                     rayon::spawn_fifo(move || {
@@ -189,7 +197,11 @@ impl ReceiptPreparationPipeline {
                 | Action::Stake(_)
                 | Action::AddKey(_)
                 | Action::DeleteKey(_)
-                | Action::DeleteAccount(_) => {}
+                | Action::DeleteAccount(_)
+                | Action::SponsorStorage(_)
+                | Action::TransferToken(_)
+                | Action::StakeWithMetadata(_)
+                | Action::DeployGlobalContract(_) => {}
                 #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
                 Action::NonrefundableStorageTransfer(_) => {}
             }
@@ -218,17 +230,28 @@ impl ReceiptPreparationPipeline {
                 .actions
                 .get(action_index)
                 .expect("indexing receipt actions by an action_index failed!"),
-            ReceiptEnum::Data(_) | ReceiptEnum::PromiseResume(_) => {
+            ReceiptEnum::Data(_)
+            | ReceiptEnum::PromiseResume(_)
+            | ReceiptEnum::GlobalContractDistribution(_) => {
                 panic!("attempting to get_contract with a non-action receipt!?")
             }
         };
-        let Action::FunctionCall(function_call) = action else {
-            panic!("referenced receipt action is not a function call!");
+        // State migrations run with their own, higher, limit of gas burnt.
+        let (method_name, gas, max_gas_burnt) = match action {
+            Action::FunctionCall(function_call) => {
+                (&function_call.method_name, function_call.gas, None)
+            }
+            Action::MigrateState(migrate_state) => (
+                &migrate_state.method_name,
+                migrate_state.gas,
+                Some(self.config.wasm_config.limit_config.max_migration_gas),
+            ),
+            _ => panic!("referenced receipt action is not a function call!"),
         };
         let key = PrepareTaskKey { receipt_id: receipt.get_hash(), action_index };
-        let Some(task) = self.map.get(&key) else {
+        let Some(task) = self.map.get(&key).filter(|task| task.code_hash == code_hash) else {
             let start = Instant::now();
-            let gas_counter = self.gas_counter(view_config.as_ref(), function_call.gas);
+            let gas_counter = self.gas_counter(view_config.as_ref(), gas, max_gas_burnt);
             if !self.block_accounts.contains(account_id) {
                 tracing::debug!(
                     target: "runtime::pipelining",
@@ -245,7 +268,7 @@ impl ReceiptPreparationPipeline {
                 gas_counter,
                 code_hash,
                 &account_id,
-                &function_call.method_name,
+                method_name,
             );
             PIPELINING_ACTIONS_NOT_SUBMITTED.inc_by(1);
             PIPELINING_ACTIONS_MAIN_THREAD_WORKING_TIME.inc_by(start.elapsed().as_secs_f64());
@@ -265,9 +288,9 @@ impl ReceiptPreparationPipeline {
                         receipt=%receipt.get_hash(),
                         action_index
                     );
-                    let gas_counter = self.gas_counter(view_config.as_ref(), function_call.gas);
+                    let gas_counter = self.gas_counter(view_config.as_ref(), gas, max_gas_burnt);
                     let cache = self.contract_cache.as_ref().map(|c| c.handle());
-                    let method_name = function_call.method_name.clone();
+                    let method_name = method_name.clone();
                     let contract = prepare_function_call(
                         &self.storage,
                         cache.as_deref(),
@@ -304,10 +327,16 @@ impl ReceiptPreparationPipeline {
         }
     }
 
-    fn gas_counter(&self, view_config: Option<&ViewConfig>, gas: Gas) -> GasCounter {
-        let max_gas_burnt = match view_config {
-            Some(ViewConfig { max_gas_burnt }) => *max_gas_burnt,
-            None => self.config.wasm_config.limit_config.max_gas_burnt,
+    fn gas_counter(
+        &self,
+        view_config: Option<&ViewConfig>,
+        gas: Gas,
+        max_gas_burnt: Option<Gas>,
+    ) -> GasCounter {
+        let max_gas_burnt = match (view_config, max_gas_burnt) {
+            (Some(ViewConfig { max_gas_burnt }), _) => *max_gas_burnt,
+            (None, Some(max_gas_burnt)) => max_gas_burnt,
+            (None, None) => self.config.wasm_config.limit_config.max_gas_burnt,
         };
         GasCounter::new(
             self.config.wasm_config.ext_costs.clone(),
@@ -7,7 +7,7 @@ use near_primitives::errors::RuntimeError;
 use near_primitives::receipt::DataReceiver;
 use near_primitives_core::account::{AccessKey, AccessKeyPermission, FunctionCallPermission};
 use near_primitives_core::hash::CryptoHash;
-use near_primitives_core::types::{AccountId, Balance, Gas, GasWeight, Nonce};
+use near_primitives_core::types::{AccountId, Balance, BlockHeightDelta, Gas, GasWeight, Nonce};
 use near_vm_runner::logic::HostError;
 use near_vm_runner::logic::VMLogicError;
 use std::collections::HashMap;
@@ -36,6 +36,9 @@ pub struct ActionReceiptMetadata {
     pub actions: Vec<Action>,
     /// Indicates whether the receipt should have type Action or PromiseYield
     pub is_promise_yield: bool,
+    /// Number of blocks after which a PromiseYield receipt times out. Zero for
+    /// other receipts.
+    pub yield_timeout_length_in_blocks: BlockHeightDelta,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -121,6 +124,7 @@ impl ReceiptManager {
             input_data_ids,
             actions: vec![],
             is_promise_yield: false,
+            yield_timeout_length_in_blocks: 0,
         };
         let new_receipt_index = self.action_receipts.len() as ReceiptIndex;
         self.action_receipts.push(new_receipt);
@@ -136,10 +140,12 @@ impl ReceiptManager {
     ///
     /// * `input_data_id` - data id which will be used to later submit the receipt input
     /// * `receiver_id` - account id of the receiver of the receipt created
+    /// * `timeout_length_in_blocks` - number of blocks after which the receipt times out
     pub(super) fn create_promise_yield_receipt(
         &mut self,
         input_data_id: CryptoHash,
         receiver_id: AccountId,
+        timeout_length_in_blocks: BlockHeightDelta,
     ) -> Result<ReceiptIndex, VMLogicError> {
         let new_receipt = ActionReceiptMetadata {
             receiver_id,
@@ -147,6 +153,7 @@ impl ReceiptManager {
             input_data_ids: vec![input_data_id],
             actions: vec![],
             is_promise_yield: true,
+            yield_timeout_length_in_blocks: timeout_length_in_blocks,
         };
         let new_receipt_index = self.action_receipts.len();
         self.action_receipts.push(new_receipt);
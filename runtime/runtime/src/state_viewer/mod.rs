@@ -1,11 +1,11 @@
 use crate::actions::execute_function_call;
-use crate::ext::RuntimeExt;
+use crate::ext::{RuntimeExt, ViewCallEnv};
 use crate::pipelining::ReceiptPreparationPipeline;
 use crate::receipt_manager::ReceiptManager;
 use crate::ApplyState;
 use near_crypto::{KeyType, PublicKey};
 use near_parameters::RuntimeConfigStore;
-use near_primitives::account::{AccessKey, Account};
+use near_primitives::account::{AccessKey, Account, ContractSourceMetadata, StorageSponsorship};
 use near_primitives::apply::ApplyChunkReason;
 use near_primitives::bandwidth_scheduler::BlockBandwidthRequests;
 use near_primitives::borsh::BorshDeserialize;
@@ -15,12 +15,15 @@ use near_primitives::runtime::migration_data::{MigrationData, MigrationFlags};
 use near_primitives::transaction::FunctionCallAction;
 use near_primitives::trie_key::trie_key_parsers;
 use near_primitives::types::{
-    AccountId, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, Gas, ShardId,
+    AccountId, Balance, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, Gas, ShardId,
 };
 use near_primitives::version::PROTOCOL_VERSION;
 use near_primitives::views::{StateItem, ViewStateResult};
 use near_primitives_core::config::ViewConfig;
-use near_store::{get_access_key, get_account, TrieUpdate};
+use near_store::{
+    get_access_key, get_account, get_contract_source_metadata, get_storage_sponsorship,
+    get_token_balance, TrieUpdate,
+};
 use near_vm_runner::logic::{ProtocolVersion, ReturnData};
 use near_vm_runner::{ContractCode, ContractRuntimeCache};
 use std::{str, sync::Arc, time::Instant};
@@ -99,6 +102,38 @@ impl TrieViewer {
         })
     }
 
+    /// Returns the storage sponsorship of an existing account, if there is one.
+    pub fn view_storage_sponsorship(
+        &self,
+        state_update: &TrieUpdate,
+        account_id: &AccountId,
+    ) -> Result<Option<StorageSponsorship>, errors::ViewAccountError> {
+        self.view_account(state_update, account_id)?;
+        Ok(get_storage_sponsorship(state_update, account_id)?)
+    }
+
+    /// Returns the source metadata embedded in the contract of an existing
+    /// account, if there is one.
+    pub fn view_contract_source_metadata(
+        &self,
+        state_update: &TrieUpdate,
+        account_id: &AccountId,
+    ) -> Result<Option<ContractSourceMetadata>, errors::ViewAccountError> {
+        self.view_account(state_update, account_id)?;
+        Ok(get_contract_source_metadata(state_update, account_id)?)
+    }
+
+    /// Returns the balance an existing account holds of the native token issued by `token_id`.
+    pub fn view_token_balance(
+        &self,
+        state_update: &TrieUpdate,
+        account_id: &AccountId,
+        token_id: &AccountId,
+    ) -> Result<Balance, errors::ViewAccountError> {
+        self.view_account(state_update, account_id)?;
+        Ok(get_token_balance(state_update, account_id, token_id)?.unwrap_or(0))
+    }
+
     pub fn view_access_key(
         &self,
         state_update: &TrieUpdate,
@@ -226,6 +261,8 @@ impl TrieViewer {
             migration_flags: MigrationFlags::default(),
             congestion_info: Default::default(),
             bandwidth_requests: BlockBandwidthRequests::empty(),
+            recent_block_headers: Default::default(),
+            execution_tracer: None,
         };
         let function_call = FunctionCallAction {
             method_name: method_name.to_string(),
@@ -270,6 +307,11 @@ impl TrieViewer {
             epoch_info_provider,
             view_state.current_protocol_version,
         );
+        runtime_ext.set_view_call_env(ViewCallEnv {
+            apply_state: &apply_state,
+            action_receipt: &action_receipt,
+            depth: 0,
+        });
         let outcome = execute_function_call(
             contract,
             &apply_state,
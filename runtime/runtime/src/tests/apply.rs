@@ -6,6 +6,7 @@ use crate::tests::{
     MAX_ATTACHED_GAS,
 };
 use crate::total_prepaid_exec_fees;
+use crate::trace::{CollectingTracer, ExecutionTraceEvent};
 use crate::{ApplyResult, ApplyState, Runtime, ValidatorAccountsUpdate};
 use assert_matches::assert_matches;
 use near_crypto::{InMemorySigner, KeyType, PublicKey, Signer};
@@ -22,14 +23,16 @@ use near_primitives::congestion_info::{
 };
 use near_primitives::errors::{ActionErrorKind, FunctionCallError, TxExecutionError};
 use near_primitives::hash::{hash, CryptoHash};
-use near_primitives::receipt::{ActionReceipt, Receipt, ReceiptEnum, ReceiptPriority, ReceiptV0};
+use near_primitives::receipt::{
+    ActionReceipt, Receipt, ReceiptEnum, ReceiptPriority, ReceiptV0, ReceiptV2,
+};
 use near_primitives::runtime::migration_data::{MigrationData, MigrationFlags};
 use near_primitives::shard_layout::{ShardLayout, ShardUId};
 use near_primitives::stateless_validation::contract_distribution::CodeHash;
 use near_primitives::test_utils::{account_new, MockEpochInfoProvider};
 use near_primitives::transaction::{
     AddKeyAction, DeleteKeyAction, DeployContractAction, ExecutionOutcomeWithId, ExecutionStatus,
-    FunctionCallAction, SignedTransaction, TransferAction,
+    FunctionCallAction, GasRefund, SignedTransaction, TransferAction,
 };
 use near_primitives::trie_key::TrieKey;
 use near_primitives::types::{
@@ -37,6 +40,7 @@ use near_primitives::types::{
 };
 use near_primitives::utils::create_receipt_id_from_transaction;
 use near_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
+use near_primitives::views::ExecutionStatusView;
 use near_store::test_utils::TestTriesBuilder;
 use near_store::trie::receipts_column_helper::ShardsOutgoingReceiptBuffer;
 use near_store::{
@@ -134,6 +138,8 @@ fn setup_runtime_for_shard(
         migration_flags: MigrationFlags::default(),
         congestion_info,
         bandwidth_requests: BlockBandwidthRequests::empty(),
+        recent_block_headers: Default::default(),
+        execution_tracer: None,
     };
 
     (runtime, tries, root, apply_state, signers)
@@ -189,6 +195,40 @@ fn test_apply_check_balance_validation_rewards() {
         .unwrap();
 }
 
+#[test]
+fn test_apply_challenger_rewards() {
+    let initial_balance = to_yocto(1_000_000);
+    let reward = to_yocto(1_000);
+    let (runtime, tries, root, mut apply_state, _, epoch_info_provider) =
+        setup_runtime(vec![alice_account()], initial_balance, 0, 10u64.pow(15));
+
+    // Alice doesn't stake, so the reward is credited to the liquid balance.
+    let validator_accounts_update = ValidatorAccountsUpdate {
+        stake_info: HashMap::default(),
+        validator_rewards: vec![(alice_account(), reward)].into_iter().collect(),
+        last_proposals: Default::default(),
+        protocol_treasury_account_id: None,
+        slashing_info: HashMap::default(),
+    };
+
+    let shard_uid = ShardUId::single_shard();
+    let apply_result = runtime
+        .apply(
+            tries.get_trie_for_shard(shard_uid, root),
+            &Some(validator_accounts_update),
+            &apply_state,
+            &[],
+            &[],
+            &epoch_info_provider,
+            Default::default(),
+        )
+        .unwrap();
+    let root = commit_apply_result(&apply_result, &mut apply_state, &tries, shard_uid);
+    let state = tries.new_trie_update(shard_uid, root);
+    let account = get_account(&state, &alice_account()).unwrap().unwrap();
+    assert_eq!(account.amount(), initial_balance + reward);
+}
+
 #[test]
 fn test_apply_refund_receipts() {
     let initial_balance = to_yocto(1_000_000);
@@ -274,6 +314,85 @@ fn test_apply_delayed_receipts_feed_all_at_once() {
     }
 }
 
+/// Receipts which sit in the delayed receipts queue past their expiry height are refunded.
+#[test]
+fn test_apply_delayed_receipts_expired() {
+    let initial_balance = to_yocto(1_000_000);
+    let initial_locked = to_yocto(500_000);
+    let small_transfer = to_yocto(10_000);
+    let gas_limit = 1;
+    let (runtime, tries, mut root, mut apply_state, _, epoch_info_provider) = setup_runtime(
+        vec![alice_account(), bob_account()],
+        initial_balance,
+        initial_locked,
+        gas_limit,
+    );
+
+    let n = 3;
+    let expiry_height = 2;
+    let receipts: Vec<Receipt> = generate_receipts(small_transfer, n)
+        .into_iter()
+        .map(|receipt| {
+            Receipt::V2(ReceiptV2 {
+                predecessor_id: receipt.predecessor_id().clone(),
+                receiver_id: receipt.receiver_id().clone(),
+                receipt_id: *receipt.receipt_id(),
+                receipt: receipt.take_receipt(),
+                priority: 0,
+                expiry_height,
+            })
+        })
+        .collect();
+    let shard_uid = ShardUId::single_shard();
+
+    // One receipt is processed per block, the last one is executed after the expiry height.
+    for i in 1..=n {
+        apply_state.block_height = i;
+        let prev_receipts: &[Receipt] = if i == 1 { &receipts } else { &[] };
+        let apply_result = runtime
+            .apply(
+                tries.get_trie_for_shard(shard_uid, root),
+                &None,
+                &apply_state,
+                prev_receipts,
+                &[],
+                &epoch_info_provider,
+                Default::default(),
+            )
+            .unwrap();
+        root = commit_apply_result(&apply_result, &mut apply_state, &tries, shard_uid);
+
+        let receipt = &receipts[i as usize - 1];
+        let outcome = apply_result
+            .outcomes
+            .iter()
+            .find(|outcome| &outcome.id == receipt.receipt_id())
+            .expect("receipt must be processed");
+        if i <= expiry_height {
+            assert_matches!(outcome.outcome.status, ExecutionStatus::SuccessValue(_));
+            continue;
+        }
+        assert_eq!(
+            outcome.outcome.status,
+            ExecutionStatus::Failure(TxExecutionError::ActionError(
+                ActionErrorKind::ReceiptExpired { expiry_height, block_height: i }.into()
+            ))
+        );
+        // The deposit is refunded to the predecessor.
+        assert!(apply_result.outgoing_receipts.iter().any(|refund| {
+            refund.receiver_id() == &bob_account()
+                && matches!(refund.receipt(), ReceiptEnum::Action(action_receipt)
+                if action_receipt.actions == vec![Action::Transfer(TransferAction {
+                    deposit: small_transfer + Balance::from(i - 1),
+                })])
+        }));
+    }
+
+    let state = tries.new_trie_update(shard_uid, root);
+    let account = get_account(&state, &alice_account()).unwrap().unwrap();
+    assert_eq!(account.amount(), initial_balance + small_transfer * 2 + 1);
+}
+
 #[test]
 fn test_apply_delayed_receipts_add_more_using_chunks() {
     let initial_balance = to_yocto(1_000_000);
@@ -807,6 +926,84 @@ fn test_apply_deficit_gas_for_function_call_covered() {
     };
 }
 
+#[test]
+fn test_apply_reduced_gas_refund() {
+    let initial_balance = to_yocto(1_000_000);
+    let initial_locked = to_yocto(500_000);
+    let gas_limit = 10u64.pow(15);
+    let (runtime, tries, root, mut apply_state, _, epoch_info_provider) = setup_runtime(
+        vec![alice_account(), bob_account()],
+        initial_balance,
+        initial_locked,
+        gas_limit,
+    );
+    let min_gas_refund_penalty = 10u64.pow(13);
+    let mut config = RuntimeConfig::test();
+    Arc::make_mut(&mut config.fees).min_gas_refund_penalty = min_gas_refund_penalty;
+    apply_state.config = Arc::new(config);
+    apply_state.current_protocol_version = ProtocolFeature::ReducedGasRefunds.protocol_version();
+
+    let gas = 2 * 10u64.pow(14);
+    let actions = vec![Action::FunctionCall(Box::new(FunctionCallAction {
+        method_name: "hello".to_string(),
+        args: b"world".to_vec(),
+        gas,
+        deposit: 0,
+    }))];
+    let expected_gas_burnt = safe_add_gas(
+        apply_state.config.fees.fee(ActionCosts::new_action_receipt).exec_fee(),
+        total_prepaid_exec_fees(&apply_state.config, &actions, &alice_account()).unwrap(),
+    )
+    .unwrap();
+    let receipts = vec![Receipt::V0(ReceiptV0 {
+        predecessor_id: bob_account(),
+        receiver_id: alice_account(),
+        receipt_id: CryptoHash::default(),
+        receipt: ReceiptEnum::Action(ActionReceipt {
+            signer_id: bob_account(),
+            signer_public_key: PublicKey::empty(KeyType::ED25519),
+            gas_price: GAS_PRICE,
+            output_data_receivers: vec![],
+            input_data_ids: vec![],
+            actions,
+        }),
+    })];
+
+    let result = runtime
+        .apply(
+            tries.get_trie_for_shard(ShardUId::single_shard(), root),
+            &None,
+            &apply_state,
+            &receipts,
+            &[],
+            &epoch_info_provider,
+            Default::default(),
+        )
+        .unwrap();
+    // The call fails because alice has no contract, all the attached gas is unused and the
+    // minimum penalty is burnt instead of refunded.
+    let expected_refund = Balance::from(gas - min_gas_refund_penalty) * GAS_PRICE;
+    match result.outgoing_receipts[0].receipt() {
+        ReceiptEnum::Action(ActionReceipt { actions, .. }) => {
+            assert_matches!(actions[0], Action::Transfer(TransferAction { deposit }) if deposit == expected_refund);
+        }
+        _ => unreachable!(),
+    };
+    let outcome = &result.outcomes[0].outcome;
+    assert_eq!(
+        outcome.tokens_burnt,
+        Balance::from(expected_gas_burnt + min_gas_refund_penalty) * GAS_PRICE
+    );
+    assert_eq!(
+        outcome.metadata.gas_refund(),
+        Some(&GasRefund {
+            gas_refunded: gas - min_gas_refund_penalty,
+            gas_refund_penalty: min_gas_refund_penalty,
+        })
+    );
+    assert_eq!(result.stats.tx_burnt_amount, outcome.tokens_burnt);
+}
+
 #[test]
 fn test_apply_deficit_gas_for_function_call_partial() {
     let initial_balance = to_yocto(1_000_000);
@@ -1213,7 +1410,7 @@ fn test_main_storage_proof_size_soft_limit() {
 
     // Since contracts are excluded from the partial state, we will get missing trie error below.
     let partial_storage = apply_result.proof.unwrap();
-    let storage = Trie::from_recorded_storage(partial_storage, root, false);
+    let storage = Trie::from_recorded_storage(partial_storage, root, false).unwrap();
     let code_key = TrieKey::ContractCode { account_id: alice_account() };
     assert_matches!(
         storage.get(&code_key.to_vec()),
@@ -1326,12 +1523,14 @@ fn test_exclude_contract_code_from_witness() {
 
     // Check that the proof size is less than the contract size (since it is not included in the storage proof).
     let partial_storage = apply_result.proof.unwrap();
-    let PartialState::TrieValues(storage_proof) = partial_storage.nodes.clone();
+    let PartialState::TrieValues(storage_proof) = partial_storage.nodes.clone() else {
+        panic!("expected trie values in partial storage");
+    };
     let total_size: usize = storage_proof.iter().map(|v| v.len()).sum();
     assert!(total_size < CONTRACT_SIZE);
 
     // Check that both contracts are excluded from the storage proof.
-    let storage = Trie::from_recorded_storage(partial_storage, root, false);
+    let storage = Trie::from_recorded_storage(partial_storage, root, false).unwrap();
     let code_key = TrieKey::ContractCode { account_id: alice_account() };
     assert_matches!(
         storage.get(&code_key.to_vec()),
@@ -1436,7 +1635,7 @@ fn test_exclude_contract_code_from_witness_with_failed_call() {
 
     // Check that both contracts are excluded from the storage proof.
     let partial_storage = apply_result.proof.unwrap();
-    let storage = Trie::from_recorded_storage(partial_storage, root, false);
+    let storage = Trie::from_recorded_storage(partial_storage, root, false).unwrap();
     let code_key = TrieKey::ContractCode { account_id: alice_account() };
     assert_matches!(
         storage.get(&code_key.to_vec()),
@@ -2206,7 +2405,9 @@ fn test_exclude_existing_contract_code_for_deploy_action() {
     );
 
     let partial_storage = apply_result.proof.unwrap();
-    let PartialState::TrieValues(storage_proof) = partial_storage.nodes;
+    let PartialState::TrieValues(storage_proof) = partial_storage.nodes else {
+        panic!("expected trie values in partial storage");
+    };
     let total_size: usize = storage_proof.iter().map(|v| v.len()).sum();
     // Contract size is much larger than the rest of the storage proof, so we compare them to check if the contract is excluded.
     if ProtocolFeature::ExcludeExistingCodeFromWitnessForCodeLen.enabled(PROTOCOL_VERSION) {
@@ -2304,7 +2505,9 @@ fn test_exclude_existing_contract_code_for_delete_account_action() {
     assert_eq!(apply_result.contract_updates.contract_deploy_hashes(), HashSet::new());
 
     let partial_storage = apply_result.proof.unwrap();
-    let PartialState::TrieValues(storage_proof) = partial_storage.nodes;
+    let PartialState::TrieValues(storage_proof) = partial_storage.nodes else {
+        panic!("expected trie values in partial storage");
+    };
     let total_size: usize = storage_proof.iter().map(|v| v.len()).sum();
     // Contract size is much larger than the rest of the storage proof, so we compare them to check if the contract is excluded.
     if ProtocolFeature::ExcludeExistingCodeFromWitnessForCodeLen.enabled(PROTOCOL_VERSION) {
@@ -2790,3 +2993,76 @@ fn test_deploy_and_call_local_receipts() {
         ActionErrorKind::FunctionCallError(FunctionCallError::MethodResolveError(_))
     );
 }
+
+#[test]
+fn test_execution_tracer() {
+    let (runtime, tries, root, mut apply_state, signers, epoch_info_provider) = setup_runtime(
+        vec![alice_account(), bob_account()],
+        to_yocto(1_000_000),
+        to_yocto(500_000),
+        10u64.pow(15),
+    );
+    let tracer = Arc::new(CollectingTracer::default());
+    apply_state.execution_tracer = Some(tracer.clone());
+
+    let contract_code = ContractCode::new(near_test_contracts::rs_contract().to_vec(), None);
+    let receipt = create_receipt_with_actions(
+        alice_account(),
+        signers[0].clone(),
+        vec![
+            Action::DeployContract(DeployContractAction { code: contract_code.code().to_vec() }),
+            Action::FunctionCall(Box::new(FunctionCallAction {
+                method_name: "ext_sha256".to_string(),
+                args: b"first".to_vec(),
+                gas: MAX_ATTACHED_GAS,
+                deposit: 0,
+            })),
+        ],
+    );
+    let receipt_id = *receipt.receipt_id();
+
+    runtime
+        .apply(
+            tries.get_trie_for_shard(ShardUId::single_shard(), root),
+            &None,
+            &apply_state,
+            &[receipt],
+            &[],
+            &epoch_info_provider,
+            Default::default(),
+        )
+        .unwrap();
+
+    let events = tracer.take_events();
+    assert!(events.iter().all(|event| event.receipt_id() == &receipt_id));
+    assert_matches!(events.first(), Some(ExecutionTraceEvent::ReceiptStart { .. }));
+    assert_matches!(
+        events.last(),
+        Some(ExecutionTraceEvent::ReceiptEnd { status: ExecutionStatusView::SuccessValue(_), .. })
+    );
+    let actions: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            ExecutionTraceEvent::ActionStart { action_index, action, .. } => {
+                Some((*action_index, action.as_str()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(actions, vec![(0, "DeployContract"), (1, "FunctionCall")]);
+    let host_functions: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            ExecutionTraceEvent::HostFunctionCall { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert!(host_functions.contains(&"sha256"));
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| matches!(event, ExecutionTraceEvent::ActionEnd { error: None, .. }))
+            .count(),
+        2
+    );
+}
@@ -22,6 +22,19 @@ fn to_yocto(near: Balance) -> Balance {
     near * 10u128.pow(24)
 }
 
+/// An empty WASM module with `metadata` in its `contract_source_metadata` custom section.
+pub(crate) fn contract_with_source_metadata(metadata: &str) -> Vec<u8> {
+    let name = "contract_source_metadata";
+    let section_len = 1 + name.len() + metadata.len();
+    // Keeps the lengths single byte LEB128 numbers.
+    assert!(section_len < 128);
+    let mut code = b"\0asm\x01\0\0\0".to_vec();
+    code.extend([0, section_len as u8, name.len() as u8]);
+    code.extend(name.as_bytes());
+    code.extend(metadata.as_bytes());
+    code
+}
+
 fn create_receipt_with_actions(
     account_id: AccountId,
     signer: Arc<Signer>,
@@ -0,0 +1,43 @@
+//! Hooks observing the execution of receipts.
+//!
+//! A tracer registered in [`ApplyState::execution_tracer`] receives an event
+//! when a receipt or one of its actions starts and finishes executing, and when
+//! a contract calls a host function. It is meant for debugging, for example to
+//! re-apply a chunk and see why a contract failed, and doesn't affect the
+//! outcome of the execution.
+
+use crate::ApplyState;
+pub use near_primitives::views::ExecutionTraceEventView as ExecutionTraceEvent;
+use std::sync::Mutex;
+
+pub trait ExecutionTracer: Send + Sync {
+    fn trace(&self, event: ExecutionTraceEvent);
+}
+
+/// Tracer keeping all events in memory, in the order they were emitted.
+#[derive(Default)]
+pub struct CollectingTracer {
+    events: Mutex<Vec<ExecutionTraceEvent>>,
+}
+
+impl CollectingTracer {
+    pub fn take_events(&self) -> Vec<ExecutionTraceEvent> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+impl ExecutionTracer for CollectingTracer {
+    fn trace(&self, event: ExecutionTraceEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl ApplyState {
+    /// Emits the event to the registered tracer. The event is only built if
+    /// there is one.
+    pub(crate) fn trace(&self, event: impl FnOnce() -> ExecutionTraceEvent) {
+        if let Some(tracer) = &self.execution_tracer {
+            tracer.trace(event());
+        }
+    }
+}
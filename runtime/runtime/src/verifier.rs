@@ -12,14 +12,16 @@ use near_primitives::errors::{
 use near_primitives::receipt::{ActionReceipt, DataReceipt, Receipt, ReceiptEnum};
 use near_primitives::transaction::DeleteAccountAction;
 use near_primitives::transaction::{
-    Action, AddKeyAction, DeployContractAction, FunctionCallAction, SignedTransaction, StakeAction,
+    Action, AddKeyAction, DeployContractAction, DeployGlobalContractAction, FunctionCallAction,
+    MigrateStateAction, SignedTransaction, StakeAction,
 };
-use near_primitives::types::{AccountId, Balance};
-use near_primitives::types::{BlockHeight, StorageUsage};
+use near_primitives::types::{AccountId, Balance, Gas};
+use near_primitives::types::{BlockHeight, StorageUsage, ValidatorMetadata};
 use near_primitives::version::ProtocolFeature;
 use near_primitives::version::ProtocolVersion;
 use near_store::{
-    get_access_key, get_account, set_access_key, set_account, StorageError, TrieUpdate,
+    get_access_key, get_account, get_migration_grace_period, get_storage_sponsorship,
+    get_token_balance, set_access_key, set_account, set_token_balance, StorageError, TrieUpdate,
 };
 use near_vm_runner::logic::LimitConfig;
 
@@ -35,12 +37,49 @@ pub enum StorageStakingError {
     StorageError(String),
 }
 
+/// Returns the deposit sponsoring storage staking of the given account, or 0
+/// if its storage is not sponsored.
+pub fn storage_sponsorship_deposit(
+    state_update: &TrieUpdate,
+    account_id: &AccountId,
+    current_protocol_version: ProtocolVersion,
+) -> Result<Balance, StorageError> {
+    if !ProtocolFeature::SponsoredStorage.enabled(current_protocol_version) {
+        return Ok(0);
+    }
+    Ok(get_storage_sponsorship(state_update, account_id)?.map_or(0, |s| s.deposit))
+}
+
+/// Returns the amount covering the storage added by the last state migration of
+/// the given account while its grace period lasts, or 0 otherwise.
+pub fn migration_grace_allowance(
+    state_update: &TrieUpdate,
+    account_id: &AccountId,
+    account: &Account,
+    block_height: BlockHeight,
+    runtime_config: &RuntimeConfig,
+    current_protocol_version: ProtocolVersion,
+) -> Result<Balance, StorageError> {
+    if !ProtocolFeature::MigrateState.enabled(current_protocol_version) {
+        return Ok(0);
+    }
+    let Some(grace_period) = get_migration_grace_period(state_update, account_id)? else {
+        return Ok(0);
+    };
+    if block_height > grace_period.end_height {
+        return Ok(0);
+    }
+    let added_bytes = account.storage_usage().saturating_sub(grace_period.storage_usage);
+    Ok(Balance::from(added_bytes).saturating_mul(runtime_config.storage_amount_per_byte()))
+}
+
 /// Checks if given account has enough balance for storage stake, and returns:
-///  - Ok(()) if account has enough balance or is a zero-balance account
+///  - Ok(()) if account has enough balance, including the deposit of its storage sponsor, or is a zero-balance account
 ///  - Err(StorageStakingError::LackBalanceForStorageStaking(amount)) if account doesn't have enough and how much need to be added,
 ///  - Err(StorageStakingError::StorageError(err)) if account has invalid storage usage or amount/locked.
 pub fn check_storage_stake(
     account: &Account,
+    sponsored_deposit: Balance,
     runtime_config: &RuntimeConfig,
     current_protocol_version: ProtocolVersion,
 ) -> Result<(), StorageStakingError> {
@@ -62,11 +101,13 @@ pub fn check_storage_stake(
     let available_amount = account
         .amount()
         .checked_add(account.locked())
+        .and_then(|amount| amount.checked_add(sponsored_deposit))
         .ok_or_else(|| {
             format!(
-                "Account's amount {} and locked {} overflow addition",
+                "Account's amount {}, locked {} and sponsored deposit {} overflow addition",
                 account.amount(),
                 account.locked(),
+                sponsored_deposit,
             )
         })
         .map_err(StorageStakingError::StorageError)?;
@@ -97,8 +138,14 @@ pub fn validate_transaction(
     verify_signature: bool,
     current_protocol_version: ProtocolVersion,
 ) -> Result<TransactionCost, InvalidTxError> {
-    // Don't allow V1 currently. This will be changed when the new protocol version is introduced.
-    if matches!(signed_transaction.transaction, near_primitives::transaction::Transaction::V1(_)) {
+    if matches!(signed_transaction.transaction, near_primitives::transaction::Transaction::V1(_))
+        && !ProtocolFeature::TransactionPriorityFee.enabled(current_protocol_version)
+    {
+        return Err(InvalidTxError::InvalidTransactionVersion);
+    }
+    if matches!(signed_transaction.transaction, near_primitives::transaction::Transaction::V2(_))
+        && !ProtocolFeature::TransactionMaxGasPrice.enabled(current_protocol_version)
+    {
         return Err(InvalidTxError::InvalidTransactionVersion);
     }
     let transaction = &signed_transaction.transaction;
@@ -129,6 +176,12 @@ pub fn validate_transaction(
     )
     .map_err(InvalidTxError::ActionsValidation)?;
 
+    if let Some(max_gas_price) = transaction.max_gas_price() {
+        if gas_price > max_gas_price {
+            return Err(InvalidTxError::GasPriceTooHigh { gas_price, max_gas_price });
+        }
+    }
+
     let sender_is_receiver = transaction.receiver_id() == signer_id;
 
     tx_cost(&config, transaction, gas_price, sender_is_receiver, current_protocol_version)
@@ -224,7 +277,19 @@ pub fn verify_and_charge_transaction(
         }
     }
 
-    match check_storage_stake(&signer, config, current_protocol_version) {
+    let token_balances = debit_token_transfers(state_update, signer_id, transaction.actions())?;
+
+    let sponsored_deposit =
+        storage_sponsorship_deposit(state_update, signer_id, current_protocol_version)?
+            .saturating_add(migration_grace_allowance(
+                state_update,
+                signer_id,
+                &signer,
+                block_height.unwrap_or_default(),
+                config,
+                current_protocol_version,
+            )?);
+    match check_storage_stake(&signer, sponsored_deposit, config, current_protocol_version) {
         Ok(()) => {}
         Err(StorageStakingError::LackBalanceForStorageStaking(amount)) => {
             return Err(InvalidTxError::LackBalanceForState {
@@ -282,12 +347,63 @@ pub fn verify_and_charge_transaction(
         }
     };
 
+    for (token_id, balance) in token_balances {
+        set_token_balance(state_update, signer_id.clone(), token_id, balance);
+    }
     set_access_key(state_update, signer_id.clone(), transaction.public_key().clone(), &access_key);
     set_account(state_update, signer_id.clone(), &signer);
 
     Ok(VerificationResult { gas_burnt, gas_remaining, receipt_gas_price, burnt_amount })
 }
 
+/// Debits the tokens sent by the TransferToken actions of a transaction from
+/// the signer and returns the new balances of the signer. The issuer of a token
+/// has an unlimited supply, so its own token isn't debited. The records of the
+/// balances that drop to zero are kept, so the signer can still receive the
+/// token. Sending zero of a token the signer holds no balance of doesn't debit
+/// anything, it is how the signer opts in to receiving the token.
+fn debit_token_transfers(
+    state_update: &TrieUpdate,
+    signer_id: &AccountId,
+    actions: &[Action],
+) -> Result<Vec<(AccountId, Balance)>, InvalidTxError> {
+    let mut balances: Vec<(AccountId, Balance)> = Vec::new();
+    for action in actions {
+        let Action::TransferToken(transfer) = action else { continue };
+        if &transfer.token_id == signer_id {
+            continue;
+        }
+        let index = match balances.iter().position(|(token_id, _)| token_id == &transfer.token_id) {
+            Some(index) => index,
+            None => match get_token_balance(state_update, signer_id, &transfer.token_id)? {
+                Some(balance) => {
+                    balances.push((transfer.token_id.clone(), balance));
+                    balances.len() - 1
+                }
+                None if transfer.amount == 0 => continue,
+                None => {
+                    return Err(InvalidTxError::NotEnoughTokenBalance {
+                        signer_id: signer_id.clone(),
+                        token_id: transfer.token_id.clone(),
+                        balance: 0,
+                        amount: transfer.amount,
+                    })
+                }
+            },
+        };
+        let (token_id, balance) = &mut balances[index];
+        *balance = balance.checked_sub(transfer.amount).ok_or_else(|| {
+            InvalidTxError::NotEnoughTokenBalance {
+                signer_id: signer_id.clone(),
+                token_id: token_id.clone(),
+                balance: *balance,
+                amount: transfer.amount,
+            }
+        })?;
+    }
+    Ok(balances)
+}
+
 /// Validates a given receipt. Checks validity of the Action or Data receipt.
 pub(crate) fn validate_receipt(
     limit_config: &LimitConfig,
@@ -322,6 +438,10 @@ pub(crate) fn validate_receipt(
         ReceiptEnum::Data(data_receipt) | ReceiptEnum::PromiseResume(data_receipt) => {
             validate_data_receipt(limit_config, data_receipt)
         }
+        ReceiptEnum::GlobalContractDistribution(distribution_receipt) => {
+            validate_contract_code_size(limit_config, &distribution_receipt.code)
+                .map_err(ReceiptValidationError::ActionsValidation)
+        }
     }
 }
 
@@ -361,8 +481,10 @@ fn validate_data_receipt(
 /// - Checks limits if applicable.
 /// - Checks that the total number of actions doesn't exceed the limit.
 /// - Checks that there not other action if Action::Delegate is present.
+/// - Checks that there is no other action if Action::MigrateState is present.
 /// - Validates each individual action.
-/// - Checks that the total prepaid gas doesn't exceed the limit.
+/// - Checks that the total prepaid gas doesn't exceed the limit, which is higher for state
+///   migrations.
 pub(crate) fn validate_actions(
     limit_config: &LimitConfig,
     actions: &[Action],
@@ -395,16 +517,26 @@ pub(crate) fn validate_actions(
                 }
                 found_delegate_action = true;
             }
+            if let Action::MigrateState(_) = action {
+                if actions.len() != 1 {
+                    return Err(ActionsValidationError::MigrateStateMustBeOnlyAction);
+                }
+            }
         }
         validate_action(limit_config, action, current_protocol_version)?;
     }
 
     let total_prepaid_gas =
         total_prepaid_gas(actions).map_err(|_| ActionsValidationError::IntegerOverflow)?;
-    if total_prepaid_gas > limit_config.max_total_prepaid_gas {
+    let max_total_prepaid_gas = if let [Action::MigrateState(_)] = actions {
+        limit_config.max_migration_gas
+    } else {
+        limit_config.max_total_prepaid_gas
+    };
+    if total_prepaid_gas > max_total_prepaid_gas {
         return Err(ActionsValidationError::TotalPrepaidGasExceeded {
             total_prepaid_gas,
-            limit: limit_config.max_total_prepaid_gas,
+            limit: max_total_prepaid_gas,
         });
     }
 
@@ -419,7 +551,9 @@ pub fn validate_action(
 ) -> Result<(), ActionsValidationError> {
     match action {
         Action::CreateAccount(_) => Ok(()),
-        Action::DeployContract(a) => validate_deploy_contract_action(limit_config, a),
+        Action::DeployContract(a) => {
+            validate_deploy_contract_action(limit_config, a, current_protocol_version)
+        }
         Action::FunctionCall(a) => validate_function_call_action(limit_config, a),
         Action::Transfer(_) => Ok(()),
         #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
@@ -431,6 +565,26 @@ pub fn validate_action(
         Action::DeleteKey(_) => Ok(()),
         Action::DeleteAccount(a) => validate_delete_action(a),
         Action::Delegate(a) => validate_delegate_action(limit_config, a, current_protocol_version),
+        Action::SponsorStorage(_) => {
+            check_feature_enabled(ProtocolFeature::SponsoredStorage, current_protocol_version)
+        }
+        Action::StakeWithMetadata(a) => {
+            check_feature_enabled(ProtocolFeature::ValidatorMetadata, current_protocol_version)?;
+            validate_stake_action(&a.stake)?;
+            validate_validator_metadata(&a.metadata)
+        }
+        Action::DeployGlobalContract(a) => {
+            validate_deploy_global_contract_action(limit_config, a, current_protocol_version)
+        }
+        Action::UseGlobalContract(_) => {
+            check_feature_enabled(ProtocolFeature::GlobalContracts, current_protocol_version)
+        }
+        Action::MigrateState(a) => {
+            validate_migrate_state_action(limit_config, a, current_protocol_version)
+        }
+        Action::TransferToken(_) => {
+            check_feature_enabled(ProtocolFeature::NativeTokens, current_protocol_version)
+        }
     }
 }
 
@@ -439,19 +593,68 @@ fn validate_delegate_action(
     signed_delegate_action: &SignedDelegateAction,
     current_protocol_version: ProtocolVersion,
 ) -> Result<(), ActionsValidationError> {
+    let depth = signed_delegate_action.delegate_action.nesting_depth();
+    if depth > 1 {
+        check_feature_enabled(ProtocolFeature::NestedDelegateActions, current_protocol_version)?;
+        if depth > limit_config.max_delegate_action_nesting_depth {
+            return Err(ActionsValidationError::DelegateActionNestingTooDeep {
+                depth,
+                limit: limit_config.max_delegate_action_nesting_depth,
+            });
+        }
+    }
     let actions = signed_delegate_action.delegate_action.get_actions();
+    // Token transfers are debited from the signer of the transaction, which is
+    // the relayer rather than the sender of the delegate action.
+    if actions.iter().any(|action| matches!(action, Action::TransferToken(_))) {
+        return Err(ActionsValidationError::TransferTokenInDelegateAction);
+    }
     validate_actions(limit_config, &actions, current_protocol_version)?;
     Ok(())
 }
 
-/// Validates `DeployContractAction`. Checks that the given contract size doesn't exceed the limit.
+/// Validates `DeployContractAction`. Checks that the given contract size doesn't exceed the limit
+/// and that the embedded source metadata is valid.
 fn validate_deploy_contract_action(
     limit_config: &LimitConfig,
     action: &DeployContractAction,
+    current_protocol_version: ProtocolVersion,
+) -> Result<(), ActionsValidationError> {
+    validate_contract_code_size(limit_config, &action.code)?;
+    validate_contract_source_metadata(&action.code, current_protocol_version)
+}
+
+/// Validates `DeployGlobalContractAction`. Global contracts have the same size limit as the
+/// contracts deployed to accounts.
+fn validate_deploy_global_contract_action(
+    limit_config: &LimitConfig,
+    action: &DeployGlobalContractAction,
+    current_protocol_version: ProtocolVersion,
 ) -> Result<(), ActionsValidationError> {
-    if action.code.len() as u64 > limit_config.max_contract_size {
+    check_feature_enabled(ProtocolFeature::GlobalContracts, current_protocol_version)?;
+    validate_contract_code_size(limit_config, &action.code)
+}
+
+/// Checks that the NEP-330 source metadata embedded in the contract code, if any, can be parsed.
+fn validate_contract_source_metadata(
+    code: &[u8],
+    current_protocol_version: ProtocolVersion,
+) -> Result<(), ActionsValidationError> {
+    if !ProtocolFeature::ContractSourceMetadata.enabled(current_protocol_version) {
+        return Ok(());
+    }
+    crate::actions::parse_contract_source_metadata(code).map(|_| ()).map_err(|err| {
+        ActionsValidationError::InvalidContractSourceMetadata { reason: err.to_string() }
+    })
+}
+
+fn validate_contract_code_size(
+    limit_config: &LimitConfig,
+    code: &[u8],
+) -> Result<(), ActionsValidationError> {
+    if code.len() as u64 > limit_config.max_contract_size {
         return Err(ActionsValidationError::ContractSizeExceeded {
-            size: action.code.len() as u64,
+            size: code.len() as u64,
             limit: limit_config.max_contract_size,
         });
     }
@@ -465,20 +668,41 @@ fn validate_function_call_action(
     limit_config: &LimitConfig,
     action: &FunctionCallAction,
 ) -> Result<(), ActionsValidationError> {
-    if action.gas == 0 {
+    validate_method_call(limit_config, &action.method_name, &action.args, action.gas)
+}
+
+/// Validates `MigrateStateAction` like deploying its code and calling its migration method.
+fn validate_migrate_state_action(
+    limit_config: &LimitConfig,
+    action: &MigrateStateAction,
+    current_protocol_version: ProtocolVersion,
+) -> Result<(), ActionsValidationError> {
+    check_feature_enabled(ProtocolFeature::MigrateState, current_protocol_version)?;
+    validate_contract_code_size(limit_config, &action.code)?;
+    validate_contract_source_metadata(&action.code, current_protocol_version)?;
+    validate_method_call(limit_config, &action.method_name, &action.args, action.gas)
+}
+
+fn validate_method_call(
+    limit_config: &LimitConfig,
+    method_name: &str,
+    args: &[u8],
+    gas: Gas,
+) -> Result<(), ActionsValidationError> {
+    if gas == 0 {
         return Err(ActionsValidationError::FunctionCallZeroAttachedGas);
     }
 
-    if action.method_name.len() as u64 > limit_config.max_length_method_name {
+    if method_name.len() as u64 > limit_config.max_length_method_name {
         return Err(ActionsValidationError::FunctionCallMethodNameLengthExceeded {
-            length: action.method_name.len() as u64,
+            length: method_name.len() as u64,
             limit: limit_config.max_length_method_name,
         });
     }
 
-    if action.args.len() as u64 > limit_config.max_arguments_length {
+    if args.len() as u64 > limit_config.max_arguments_length {
         return Err(ActionsValidationError::FunctionCallArgumentsLengthExceeded {
-            length: action.args.len() as u64,
+            length: args.len() as u64,
             limit: limit_config.max_arguments_length,
         });
     }
@@ -497,6 +721,20 @@ fn validate_stake_action(action: &StakeAction) -> Result<(), ActionsValidationEr
     Ok(())
 }
 
+/// Validates that every field of the metadata fits into `ValidatorMetadata::MAX_FIELD_LEN`.
+fn validate_validator_metadata(metadata: &ValidatorMetadata) -> Result<(), ActionsValidationError> {
+    for field in [&metadata.node_version, &metadata.p2p_endpoint].into_iter().flatten() {
+        if field.len() > ValidatorMetadata::MAX_FIELD_LEN {
+            return Err(ActionsValidationError::ValidatorMetadataFieldTooLong {
+                length: field.len() as u64,
+                limit: ValidatorMetadata::MAX_FIELD_LEN as u64,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 /// Validates `AddKeyAction`. If the access key permission is `FunctionCall`, checks that the
 /// total number of bytes of the method names doesn't exceed the limit and
 /// every method name length doesn't exceed the limit.
@@ -555,7 +793,6 @@ fn validate_delete_action(action: &DeleteAccountAction) -> Result<(), ActionsVal
     Ok(())
 }
 
-#[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
 fn check_feature_enabled(
     feature: ProtocolFeature,
     current_protocol_version: ProtocolVersion,
@@ -590,7 +827,8 @@ mod tests {
     use near_primitives::receipt::ReceiptPriority;
     use near_primitives::test_utils::account_new;
     use near_primitives::transaction::{
-        CreateAccountAction, DeleteAccountAction, DeleteKeyAction, StakeAction, TransferAction,
+        CreateAccountAction, DeleteAccountAction, DeleteKeyAction, StakeAction,
+        StakeWithMetadataAction, TransferAction, TransferTokenAction, PRIORITY_FEE_UNIT,
     };
     use near_primitives::types::{AccountId, Balance, MerkleHash, StateChangeCause};
     use near_primitives::version::PROTOCOL_VERSION;
@@ -1082,6 +1320,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_transaction_priority_fee() {
+        let config = RuntimeConfig::test();
+        let (signer, mut state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+        let protocol_version = ProtocolFeature::TransactionPriorityFee.protocol_version();
+
+        let deposit = 100;
+        let priority_fee = 5;
+        let transaction = SignedTransaction::from_actions_v1(
+            1,
+            alice_account(),
+            bob_account(),
+            &*signer,
+            vec![Action::Transfer(TransferAction { deposit })],
+            CryptoHash::default(),
+            priority_fee,
+        );
+        let verification_result = verify_and_charge_transaction(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction,
+            true,
+            None,
+            protocol_version,
+        )
+        .expect("valid transaction");
+        // The priority fee is burnt on top of the gas burnt at the current gas price.
+        assert_eq!(
+            verification_result.burnt_amount,
+            Balance::from(verification_result.gas_burnt) * gas_price
+                + Balance::from(priority_fee) * PRIORITY_FEE_UNIT
+        );
+
+        let account = get_account(&state_update, &alice_account()).unwrap().unwrap();
+        assert_eq!(
+            account.amount(),
+            TESTING_INIT_BALANCE
+                - Balance::from(verification_result.gas_remaining)
+                    * verification_result.receipt_gas_price
+                - verification_result.burnt_amount
+                - deposit
+        );
+    }
+
+    #[test]
+    fn test_validate_transaction_max_gas_price() {
+        let config = RuntimeConfig::test();
+        let (signer, mut state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+        let protocol_version = ProtocolFeature::TransactionMaxGasPrice.protocol_version();
+
+        let deposit = 100;
+        let transaction_with_max_gas_price = |max_gas_price| {
+            SignedTransaction::from_actions_v2(
+                1,
+                alice_account(),
+                bob_account(),
+                &*signer,
+                vec![Action::Transfer(TransferAction { deposit })],
+                CryptoHash::default(),
+                0,
+                max_gas_price,
+            )
+        };
+
+        assert_eq!(
+            validate_transaction(
+                &config,
+                gas_price,
+                &transaction_with_max_gas_price(gas_price),
+                true,
+                protocol_version - 1,
+            )
+            .expect_err("expected an error"),
+            InvalidTxError::InvalidTransactionVersion,
+        );
+        assert_eq!(
+            validate_transaction(
+                &config,
+                gas_price,
+                &transaction_with_max_gas_price(gas_price - 1),
+                true,
+                protocol_version,
+            )
+            .expect_err("expected an error"),
+            InvalidTxError::GasPriceTooHigh { gas_price, max_gas_price: gas_price - 1 },
+        );
+
+        let verification_result = verify_and_charge_transaction(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction_with_max_gas_price(gas_price),
+            true,
+            None,
+            protocol_version,
+        )
+        .expect("valid transaction");
+        // The maximum gas price doesn't lower the pessimistic price of the prepaid gas, so a
+        // later increase of the gas price is still covered by the purchase.
+        assert!(verification_result.receipt_gas_price > gas_price);
+
+        let account = get_account(&state_update, &alice_account()).unwrap().unwrap();
+        assert_eq!(
+            account.amount(),
+            TESTING_INIT_BALANCE
+                - Balance::from(verification_result.gas_remaining) * gas_price
+                - verification_result.burnt_amount
+                - deposit
+        );
+    }
+
     #[test]
     fn test_validate_transaction_invalid_not_enough_balance() {
         let config = RuntimeConfig::test();
@@ -1531,6 +1883,70 @@ mod tests {
         .expect("valid transaction");
     }
 
+    #[test]
+    fn test_validate_transaction_token_transfer() {
+        let config = RuntimeConfig::test();
+        let (signer, mut state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+        let token_id: AccountId = "token.near".parse().unwrap();
+        let mut nonce = 0;
+        let mut transfer = |state_update: &mut TrieUpdate, token_id: &AccountId, amount| {
+            nonce += 1;
+            verify_and_charge_transaction(
+                &config,
+                state_update,
+                gas_price,
+                &SignedTransaction::from_actions(
+                    nonce,
+                    alice_account(),
+                    bob_account(),
+                    &*signer,
+                    vec![Action::TransferToken(Box::new(TransferTokenAction {
+                        token_id: token_id.clone(),
+                        amount,
+                    }))],
+                    CryptoHash::default(),
+                    0,
+                ),
+                true,
+                None,
+                ProtocolFeature::NativeTokens.protocol_version(),
+            )
+        };
+
+        assert_eq!(
+            transfer(&mut state_update, &token_id, 10).expect_err("expected an error"),
+            InvalidTxError::NotEnoughTokenBalance {
+                signer_id: alice_account(),
+                token_id: token_id.clone(),
+                balance: 0,
+                amount: 10,
+            },
+        );
+
+        set_token_balance(&mut state_update, alice_account(), token_id.clone(), 15);
+        transfer(&mut state_update, &token_id, 10).expect("valid transaction");
+        assert_eq!(get_token_balance(&state_update, &alice_account(), &token_id).unwrap(), Some(5));
+        transfer(&mut state_update, &token_id, 5).expect("valid transaction");
+        // The record is kept so that the signer can still receive the token.
+        assert_eq!(get_token_balance(&state_update, &alice_account(), &token_id).unwrap(), Some(0));
+
+        // Sending zero of a token without a balance doesn't create a record.
+        let other_token_id: AccountId = "other_token.near".parse().unwrap();
+        transfer(&mut state_update, &other_token_id, 0).expect("valid transaction");
+        assert_eq!(
+            get_token_balance(&state_update, &alice_account(), &other_token_id).unwrap(),
+            None
+        );
+
+        // The issuer of a token has an unlimited supply.
+        transfer(&mut state_update, &alice_account(), 1_000).expect("valid transaction");
+        assert_eq!(
+            get_token_balance(&state_update, &alice_account(), &alice_account()).unwrap(),
+            None
+        );
+    }
+
     // Receipts
 
     #[test]
@@ -1685,6 +2101,63 @@ mod tests {
         );
     }
 
+    fn migrate_state_action(gas: Gas) -> Action {
+        Action::MigrateState(Box::new(MigrateStateAction {
+            code: vec![1; 5],
+            method_name: "migrate".to_string(),
+            args: b"abc".to_vec(),
+            gas,
+        }))
+    }
+
+    #[test]
+    fn test_validate_actions_migrate_state_gas() {
+        let mut limit_config = test_limit_config();
+        limit_config.max_total_prepaid_gas = 220;
+        limit_config.max_migration_gas = 500;
+        let protocol_version = ProtocolFeature::MigrateState.protocol_version();
+        validate_actions(&limit_config, &[migrate_state_action(400)], protocol_version)
+            .expect("migration may attach more than max_total_prepaid_gas");
+        assert_eq!(
+            validate_actions(&limit_config, &[migrate_state_action(600)], protocol_version)
+                .expect_err("expected an error"),
+            ActionsValidationError::TotalPrepaidGasExceeded { total_prepaid_gas: 600, limit: 500 }
+        );
+    }
+
+    #[test]
+    fn test_validate_actions_migrate_state_must_be_only_action() {
+        let limit_config = test_limit_config();
+        assert_eq!(
+            validate_actions(
+                &limit_config,
+                &[migrate_state_action(100), Action::Transfer(TransferAction { deposit: 1 }),],
+                ProtocolFeature::MigrateState.protocol_version(),
+            )
+            .expect_err("expected an error"),
+            ActionsValidationError::MigrateStateMustBeOnlyAction
+        );
+    }
+
+    #[test]
+    fn test_validate_action_contract_source_metadata() {
+        let limit_config = test_limit_config();
+        let protocol_version = ProtocolFeature::ContractSourceMetadata.protocol_version();
+        let deploy = |metadata: &str| {
+            Action::DeployContract(DeployContractAction {
+                code: crate::tests::contract_with_source_metadata(metadata),
+            })
+        };
+        validate_action(&limit_config, &deploy(r#"{"standards":[]}"#), protocol_version)
+            .expect("valid metadata");
+        assert!(matches!(
+            validate_action(&limit_config, &deploy("{"), protocol_version),
+            Err(ActionsValidationError::InvalidContractSourceMetadata { .. })
+        ));
+        validate_action(&limit_config, &deploy("{"), protocol_version - 1)
+            .expect("metadata is not checked before the feature is enabled");
+    }
+
     #[test]
     fn test_validate_actions_num_actions() {
         let mut limit_config = test_limit_config();
@@ -1831,6 +2304,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_action_stake_with_metadata() {
+        let action = |node_version: String| {
+            Action::StakeWithMetadata(Box::new(StakeWithMetadataAction {
+                stake: StakeAction {
+                    stake: 100,
+                    public_key: "ed25519:KuTCtARNzxZQ3YvXDeLjx83FDqxv2SdQTSbiq876zR7"
+                        .parse()
+                        .unwrap(),
+                },
+                metadata: ValidatorMetadata {
+                    node_version: Some(node_version),
+                    p2p_endpoint: Some("127.0.0.1:24567".to_string()),
+                },
+            }))
+        };
+        let feature_version = ProtocolFeature::ValidatorMetadata.protocol_version();
+        assert_eq!(
+            validate_action(&test_limit_config(), &action("2.4.0".to_string()), feature_version),
+            Ok(()),
+        );
+        assert_eq!(
+            validate_action(
+                &test_limit_config(),
+                &action("2.4.0".to_string()),
+                feature_version - 1
+            ),
+            Err(ActionsValidationError::UnsupportedProtocolFeature {
+                protocol_feature: "ValidatorMetadata".to_string(),
+                version: feature_version,
+            }),
+        );
+        assert_eq!(
+            validate_action(
+                &test_limit_config(),
+                &action("a".repeat(ValidatorMetadata::MAX_FIELD_LEN + 1)),
+                feature_version
+            ),
+            Err(ActionsValidationError::ValidatorMetadataFieldTooLong {
+                length: ValidatorMetadata::MAX_FIELD_LEN as u64 + 1,
+                limit: ValidatorMetadata::MAX_FIELD_LEN as u64,
+            }),
+        );
+    }
+
     #[test]
     fn test_validate_action_valid_add_key_full_permission() {
         validate_action(
@@ -1934,6 +2452,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_transfer_token_in_delegate_action() {
+        let signed_delegate_action = SignedDelegateAction {
+            delegate_action: DelegateAction {
+                sender_id: "bob.test.near".parse().unwrap(),
+                receiver_id: "alice.test.near".parse().unwrap(),
+                actions: vec![NonDelegateAction::try_from(Action::TransferToken(Box::new(
+                    TransferTokenAction { token_id: "token.test.near".parse().unwrap(), amount: 1 },
+                )))
+                .unwrap()],
+                nonce: 19000001,
+                max_block_height: 57,
+                public_key: PublicKey::empty(KeyType::ED25519),
+            },
+            signature: Signature::default(),
+        };
+        assert_eq!(
+            validate_actions(
+                &test_limit_config(),
+                &[Action::Delegate(Box::new(signed_delegate_action))],
+                PROTOCOL_VERSION,
+            ),
+            Err(ActionsValidationError::TransferTokenInDelegateAction),
+        );
+    }
+
+    #[test]
+    fn test_nested_delegate_action() {
+        let delegate_action = |actions| SignedDelegateAction {
+            delegate_action: DelegateAction {
+                sender_id: "bob.test.near".parse().unwrap(),
+                receiver_id: "alice.test.near".parse().unwrap(),
+                actions,
+                nonce: 19000001,
+                max_block_height: 57,
+                public_key: PublicKey::empty(KeyType::ED25519),
+            },
+            signature: Signature::default(),
+        };
+        let inner = delegate_action(vec![NonDelegateAction::try_from(Action::CreateAccount(
+            CreateAccountAction {},
+        ))
+        .unwrap()]);
+        let nested = delegate_action(vec![NonDelegateAction::from_nested(inner)]);
+        let too_deep = delegate_action(vec![NonDelegateAction::from_nested(nested.clone())]);
+        let feature_version = ProtocolFeature::NestedDelegateActions.protocol_version();
+
+        assert_eq!(
+            validate_action(
+                &test_limit_config(),
+                &Action::Delegate(Box::new(nested.clone())),
+                feature_version - 1,
+            ),
+            Err(ActionsValidationError::UnsupportedProtocolFeature {
+                protocol_feature: "NestedDelegateActions".to_string(),
+                version: feature_version,
+            }),
+        );
+        assert_eq!(
+            validate_action(
+                &test_limit_config(),
+                &Action::Delegate(Box::new(nested)),
+                feature_version
+            ),
+            Ok(()),
+        );
+        assert_eq!(
+            validate_action(
+                &test_limit_config(),
+                &Action::Delegate(Box::new(too_deep)),
+                feature_version
+            ),
+            Err(ActionsValidationError::DelegateActionNestingTooDeep { depth: 3, limit: 2 }),
+        );
+    }
+
     #[test]
     fn test_truncate_string() {
         fn check(input: &str, limit: usize, want: &str) {
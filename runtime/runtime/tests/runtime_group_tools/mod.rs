@@ -125,6 +125,8 @@ impl StandaloneRuntime {
             migration_flags: MigrationFlags::default(),
             congestion_info,
             bandwidth_requests: BlockBandwidthRequests::empty(),
+            recent_block_headers: Default::default(),
+            execution_tracer: None,
         };
 
         Self {
@@ -88,6 +88,7 @@ impl Network {
                     received_bytes_per_sec: 0,
                     known_producers: vec![],
                     tier1_connections: vec![],
+                    reachability: Default::default(),
                     tier1_accounts_keys: vec![],
                     tier1_accounts_data: vec![],
                 }),
@@ -248,6 +249,8 @@ impl Network {
                 }
                 Ok(())
             }),
+            block_headers_range_request: Sender::from_async_fn(|_| None),
+            block_headers_range: Sender::from_async_fn(|_| Ok(())),
             challenge: noop().into_sender(),
             network_info: Sender::from_async_fn(move |info: SetNetworkInfo| {
                 let mut n = data.lock().unwrap();
@@ -6,11 +6,13 @@ use crate::analyze_contract_sizes::AnalyzeContractSizesCommand;
 use crate::analyze_delayed_receipt::AnalyzeDelayedReceiptCommand;
 use crate::compact::RunCompactionCommand;
 use crate::corrupt::CorruptStateSnapshotCommand;
+use crate::invalid_blocks::InvalidBlocksCommand;
 use crate::make_snapshot::MakeSnapshotCommand;
 use crate::memtrie::LoadMemTrieCommand;
 use crate::resharding_v2::ReshardingV2Command;
 use crate::run_migrations::RunMigrationsCommand;
 use crate::state_perf::StatePerfCommand;
+use crate::validate_checkpoint::ValidateCheckpointCommand;
 use crate::write_to_db::WriteCryptoHashCommand;
 use clap::Parser;
 use near_chain_configs::GenesisValidationMode;
@@ -40,6 +42,9 @@ enum SubCommand {
     /// Corrupt the state snapshot.
     CorruptStateSnapshot(CorruptStateSnapshotCommand),
 
+    /// List or clear the blocks and chunks the node found invalid
+    InvalidBlocks(InvalidBlocksCommand),
+
     /// Make snapshot of the database
     MakeSnapshot(MakeSnapshotCommand),
 
@@ -50,6 +55,9 @@ enum SubCommand {
     /// Uses RocksDB data specified via --home argument.
     StatePerf(StatePerfCommand),
 
+    /// Check that the database is consistent with the latest chain checkpoint
+    ValidateCheckpoint(ValidateCheckpointCommand),
+
     /// Loads an in-memory trie for research purposes.
     LoadMemTrie(LoadMemTrieCommand),
     /// Write CryptoHash to DB
@@ -78,12 +86,14 @@ impl DatabaseCommand {
             SubCommand::ChangeDbKind(cmd) => cmd.run(home, genesis_validation),
             SubCommand::CompactDatabase(cmd) => cmd.run(home),
             SubCommand::CorruptStateSnapshot(cmd) => cmd.run(home),
+            SubCommand::InvalidBlocks(cmd) => cmd.run(home, genesis_validation),
             SubCommand::MakeSnapshot(cmd) => {
                 let near_config = load_config(home, genesis_validation);
                 cmd.run(home, &near_config.config.store, near_config.config.archival_config())
             }
             SubCommand::RunMigrations(cmd) => cmd.run(home, genesis_validation),
             SubCommand::StatePerf(cmd) => cmd.run(home),
+            SubCommand::ValidateCheckpoint(cmd) => cmd.run(home, genesis_validation),
             SubCommand::LoadMemTrie(cmd) => cmd.run(home, genesis_validation),
             SubCommand::WriteCryptoHash(cmd) => cmd.run(home, genesis_validation),
             SubCommand::HighLoadStats(cmd) => cmd.run(home),
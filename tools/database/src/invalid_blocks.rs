@@ -0,0 +1,79 @@
+use near_chain::ChainStore;
+use near_chain_configs::GenesisValidationMode;
+use near_primitives::hash::CryptoHash;
+use near_primitives::sharding::ChunkHash;
+use near_store::{DBCol, NodeStorage};
+use std::path::Path;
+
+#[derive(clap::Subcommand)]
+enum InvalidBlocksSubCommand {
+    /// Lists the blocks found invalid, with the reason.
+    List,
+    /// Removes the given blocks and chunks from the ones found invalid, so that
+    /// they are processed again when received.
+    Clear {
+        #[clap(long)]
+        block_hash: Vec<CryptoHash>,
+        #[clap(long)]
+        chunk_hash: Vec<CryptoHash>,
+    },
+}
+
+#[derive(clap::Args)]
+pub(crate) struct InvalidBlocksCommand {
+    #[clap(subcommand)]
+    subcmd: InvalidBlocksSubCommand,
+}
+
+impl InvalidBlocksCommand {
+    pub(crate) fn run(
+        &self,
+        home_dir: &Path,
+        genesis_validation: GenesisValidationMode,
+    ) -> anyhow::Result<()> {
+        let near_config = nearcore::config::load_config(&home_dir, genesis_validation)?;
+        let opener = NodeStorage::opener(
+            home_dir,
+            &near_config.config.store,
+            near_config.config.archival_config(),
+        );
+        let storage = opener.open()?;
+        let store = storage.get_hot_store();
+        let chain_store =
+            ChainStore::new(store.clone(), near_config.genesis.config.genesis_height, false);
+
+        match &self.subcmd {
+            InvalidBlocksSubCommand::List => {
+                for (block_hash, invalid_block) in chain_store.iterate_invalid_blocks()? {
+                    println!(
+                        "{block_hash} height={} reason={}",
+                        invalid_block.height, invalid_block.reason
+                    );
+                }
+            }
+            InvalidBlocksSubCommand::Clear { block_hash, chunk_hash } => {
+                for block_hash in block_hash {
+                    match chain_store.clear_invalid_block(block_hash)? {
+                        Some(invalid_block) => println!(
+                            "Cleared block {block_hash} at height {}: {}",
+                            invalid_block.height, invalid_block.reason
+                        ),
+                        None => println!("Block {block_hash} is not known to be invalid"),
+                    }
+                }
+                let mut store_update = store.store_update();
+                for chunk_hash in chunk_hash {
+                    let chunk_hash = ChunkHash(*chunk_hash);
+                    if store.exists(DBCol::InvalidChunks, chunk_hash.as_ref())? {
+                        store_update.delete(DBCol::InvalidChunks, chunk_hash.as_ref());
+                        println!("Cleared chunk {}", chunk_hash.0);
+                    } else {
+                        println!("Chunk {} is not known to be invalid", chunk_hash.0);
+                    }
+                }
+                store_update.commit()?;
+            }
+        }
+        Ok(())
+    }
+}
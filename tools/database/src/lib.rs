@@ -8,10 +8,12 @@ mod block_iterators;
 pub mod commands;
 mod compact;
 mod corrupt;
+mod invalid_blocks;
 mod make_snapshot;
 mod memtrie;
 mod resharding_v2;
 mod run_migrations;
 mod state_perf;
 mod utils;
+mod validate_checkpoint;
 mod write_to_db;
@@ -149,6 +149,10 @@ impl ReshardingV2Command {
         let chain_config = ChainConfig {
             save_trie_changes: client_config.save_trie_changes,
             background_migration_threads: client_config.client_background_migration_threads,
+            apply_chunks_threads: client_config.apply_chunks_threads,
+            catchup: client_config.catchup,
+            max_block_clock_skew: client_config.block_timestamp.max_clock_skew,
+            shadow_validation: false,
             resharding_config: client_config.resharding_config,
         };
         let chain = Chain::new(
@@ -0,0 +1,51 @@
+use near_chain::checkpoint::{get_checkpoint, validate_checkpoint};
+use near_chain_configs::GenesisValidationMode;
+use near_store::{Mode, NodeStorage};
+use std::path::Path;
+
+/// Checks that the database is consistent with the latest chain checkpoint
+/// written by the node, see `chain_checkpoint_period` in `config.json`.
+#[derive(clap::Args)]
+pub(crate) struct ValidateCheckpointCommand {}
+
+impl ValidateCheckpointCommand {
+    pub(crate) fn run(
+        &self,
+        home_dir: &Path,
+        genesis_validation: GenesisValidationMode,
+    ) -> anyhow::Result<()> {
+        let near_config = nearcore::config::load_config(&home_dir, genesis_validation)?;
+        let opener = NodeStorage::opener(
+            home_dir,
+            &near_config.config.store,
+            near_config.config.archival_config(),
+        );
+        let storage = opener.open_in_mode(Mode::ReadOnly)?;
+        let store = storage.get_hot_store();
+
+        let Some(checkpoint) = get_checkpoint(&store)? else {
+            anyhow::bail!("no chain checkpoint in the database");
+        };
+        let recorded = &checkpoint.checkpoint;
+        println!(
+            "Checkpoint: head={} final_head={} flat_storage_heads={} signed_by={}",
+            recorded.head.height,
+            recorded.final_head.height,
+            recorded.flat_storage_heads.len(),
+            checkpoint.public_key
+        );
+        let problems = validate_checkpoint(
+            &store,
+            &checkpoint,
+            &near_config.network_config.node_key.public_key(),
+        )?;
+        if problems.is_empty() {
+            println!("The database is consistent with the checkpoint");
+            return Ok(());
+        }
+        for problem in &problems {
+            println!("{problem}");
+        }
+        anyhow::bail!("the database is inconsistent with the checkpoint");
+    }
+}
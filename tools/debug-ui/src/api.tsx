@@ -64,6 +64,9 @@ export interface CatchupStatusView {
     sync_block_height: number;
     shard_sync_status: { [shard_id: number]: string };
     blocks_to_catchup: BlockStatusView[];
+    num_done_blocks: number;
+    num_scheduled_blocks: number;
+    num_pending_blocks: number;
 }
 
 export interface BlockStatusView {
@@ -942,6 +942,8 @@ impl ForkNetworkCommand {
             num_chunk_validator_seats: epoch_config.num_chunk_validator_seats,
             chunk_producer_assignment_changes_limit: epoch_config
                 .chunk_producer_assignment_changes_limit,
+            protocol_upgrade_heights: vec![],
+            block_production_delays: original_config.block_production_delays,
         };
 
         let genesis = Genesis::new_from_state_roots(new_config, new_state_roots);
@@ -47,7 +47,7 @@ fn map_action(
             }
         }
         // We don't want to mess with the set of validators in the target chain
-        Action::Stake(_) => None,
+        Action::Stake(_) | Action::StakeWithMetadata(_) => None,
         _ => Some(action.clone()),
     }
 }
@@ -986,7 +986,7 @@ impl<T: ChainAccess> TxMirror<T> {
                     actions.push(action.clone());
                 }
                 // We don't want to mess with the set of validators in the target chain
-                Action::Stake(_) => {}
+                Action::Stake(_) | Action::StakeWithMetadata(_) => {}
                 Action::CreateAccount(_) => {
                     account_created = true;
                     actions.push(action.clone());
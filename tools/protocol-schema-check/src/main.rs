@@ -1,6 +1,8 @@
 //! Tool which is able to iterate over all structs and check their hashes.
 //! Iteration is done by `ProtocolSchemaInfo`s generated by `ProtocolSchema`
 //! macro.
+//! It can also dump the whole schema to a JSON manifest and diff two
+//! manifests, see `manifest.rs`.
 #![cfg_attr(enable_const_type_id, feature(const_type_id))]
 #![allow(unused_imports)]
 
@@ -16,6 +18,8 @@ use near_primitives::*;
 use near_store::*;
 use near_vm_runner::*;
 
+use clap::Parser;
+use manifest::SchemaManifest;
 use near_epoch_manager::types::EpochInfoAggregator;
 use near_schema_checker_lib::{FieldName, FieldTypeInfo, ProtocolSchema, ProtocolSchemaInfo};
 use near_stable_hasher::StableHasher;
@@ -23,7 +27,9 @@ use std::any::TypeId;
 use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::hash::{Hash, Hasher};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+mod manifest;
 
 fn compute_hash(
     info: &ProtocolSchemaInfo,
@@ -92,7 +98,36 @@ fn compute_type_hash(
 
 const PROTOCOL_SCHEMA_FILE: &str = "protocol_schema.toml";
 
+#[derive(clap::Parser)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Compares hashes of all protocol structs with `res/protocol_schema.toml`.
+    /// This is the default command.
+    Check,
+    /// Dumps the schema of all protocol structs to a JSON manifest.
+    Dump {
+        /// File to write the manifest to. Printed to stdout if not set.
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+    /// Compares two JSON manifests and fails if there are breaking changes.
+    Diff { old: PathBuf, new: PathBuf },
+}
+
 fn main() {
+    match Cli::parse().command.unwrap_or(Command::Check) {
+        Command::Check => check(),
+        Command::Dump { output } => dump(output),
+        Command::Diff { old, new } => diff(&old, &new),
+    }
+}
+
+fn collect_structs() -> BTreeMap<TypeId, &'static ProtocolSchemaInfo> {
     #[cfg(enable_const_type_id)]
     {
         // For some reason, `EpochInfoAggregator` is not picked up by `inventory`
@@ -108,6 +143,47 @@ fn main() {
         ServerError::ensure_registration();
     }
 
+    inventory::iter::<ProtocolSchemaInfo>.into_iter().map(|info| (info.type_id(), info)).collect()
+}
+
+fn dump(output: Option<PathBuf>) {
+    let structs = collect_structs();
+    let mut manifest = SchemaManifest::default();
+    for info in structs.values() {
+        let mut types_in_compute: HashSet<TypeId> = Default::default();
+        let hash = compute_hash(info, &structs, &mut types_in_compute);
+        manifest.insert(info, hash);
+    }
+    let json = serde_json::to_string_pretty(&manifest).unwrap();
+    match output {
+        Some(output) => {
+            fs::write(&output, json).unwrap();
+            eprintln!("Schema of {} types written to {}", manifest.types.len(), output.display());
+        }
+        None => println!("{}", json),
+    }
+}
+
+fn read_manifest(path: &Path) -> SchemaManifest {
+    let data = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("Failed to read {}: {}", path.display(), err));
+    serde_json::from_str(&data)
+        .unwrap_or_else(|err| panic!("Failed to parse {}: {}", path.display(), err))
+}
+
+fn diff(old: &Path, new: &Path) {
+    let changes = manifest::diff(&read_manifest(old), &read_manifest(new));
+    for change in &changes {
+        println!("{}", change);
+    }
+    if changes.iter().any(|change| change.breaking) {
+        println!("Breaking changes detected in protocol structs");
+        std::process::exit(1);
+    }
+    println!("No breaking changes detected in protocol structs");
+}
+
+fn check() {
     let source_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("res").join(PROTOCOL_SCHEMA_FILE);
     let target_dir = std::env::var("CARGO_TARGET_DIR")
         .map(std::path::PathBuf::from)
@@ -121,11 +197,7 @@ fn main() {
         BTreeMap::new()
     };
 
-    let structs: BTreeMap<TypeId, &'static ProtocolSchemaInfo> =
-        inventory::iter::<ProtocolSchemaInfo>
-            .into_iter()
-            .map(|info| (info.type_id(), info))
-            .collect();
+    let structs = collect_structs();
 
     println!("Loaded {} structs", structs.len());
 
@@ -0,0 +1,313 @@
+//! Machine-readable description of the protocol schema.
+//!
+//! The manifest lists every type implementing `ProtocolSchema` together with
+//! its fields (or variants) in the order in which they are borsh-serialized.
+//! Two manifests can be compared to find changes which break the wire format.
+
+use near_schema_checker_lib::{FieldName, FieldTypeInfo, ProtocolSchemaInfo};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub name: String,
+    /// `None` for unit variants.
+    pub fields: Option<Vec<Field>>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TypeSchema {
+    Struct { hash: u32, fields: Vec<Field> },
+    Enum { hash: u32, variants: Vec<Variant> },
+}
+
+impl TypeSchema {
+    fn kind(&self) -> &'static str {
+        match self {
+            TypeSchema::Struct { .. } => "struct",
+            TypeSchema::Enum { .. } => "enum",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct SchemaManifest {
+    pub types: BTreeMap<String, TypeSchema>,
+}
+
+impl SchemaManifest {
+    pub fn insert(&mut self, info: &ProtocolSchemaInfo, hash: u32) {
+        let schema = match info {
+            ProtocolSchemaInfo::Struct { fields, .. } => {
+                TypeSchema::Struct { hash, fields: to_fields(fields) }
+            }
+            ProtocolSchemaInfo::Enum { variants, .. } => TypeSchema::Enum {
+                hash,
+                variants: variants
+                    .iter()
+                    .map(|(name, fields)| Variant {
+                        name: name.to_string(),
+                        fields: fields.map(to_fields),
+                    })
+                    .collect(),
+            },
+        };
+        self.types.insert(info.type_name().to_string(), schema);
+    }
+}
+
+fn to_fields(fields: &[(FieldName, FieldTypeInfo)]) -> Vec<Field> {
+    fields
+        .iter()
+        .map(|(name, (type_name, _))| Field {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SchemaChange {
+    pub type_name: String,
+    pub description: String,
+    /// Whether data serialized with the old schema can't be read with the new one.
+    pub breaking: bool,
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.breaking { "BREAKING" } else { "compatible" };
+        write!(f, "[{}] {}: {}", severity, self.type_name, self.description)
+    }
+}
+
+/// Lists all changes between two manifests.
+///
+/// Borsh serializes struct fields and enum variants by position, so changing
+/// the type of a field, adding or removing a field, or removing or reordering
+/// enum variants is breaking. Renames and appending new enum variants or new
+/// types are reported as compatible.
+pub fn diff(old: &SchemaManifest, new: &SchemaManifest) -> Vec<SchemaChange> {
+    let mut changes = vec![];
+    for (type_name, old_schema) in &old.types {
+        let mut push = |description: String, breaking: bool| {
+            changes.push(SchemaChange { type_name: type_name.clone(), description, breaking })
+        };
+        let Some(new_schema) = new.types.get(type_name) else {
+            push("type removed".to_string(), true);
+            continue;
+        };
+        match (old_schema, new_schema) {
+            (
+                TypeSchema::Struct { fields: old_fields, .. },
+                TypeSchema::Struct { fields: new_fields, .. },
+            ) => diff_fields("", old_fields, new_fields, &mut push),
+            (
+                TypeSchema::Enum { variants: old_variants, .. },
+                TypeSchema::Enum { variants: new_variants, .. },
+            ) => {
+                for (index, old_variant) in old_variants.iter().enumerate() {
+                    let Some(new_variant) = new_variants.get(index) else {
+                        push(format!("variant {} removed", old_variant.name), true);
+                        continue;
+                    };
+                    if old_variant.name != new_variant.name {
+                        push(
+                            format!("variant {} renamed to {}", old_variant.name, new_variant.name),
+                            false,
+                        );
+                    }
+                    let prefix = format!("variant {}: ", new_variant.name);
+                    match (&old_variant.fields, &new_variant.fields) {
+                        (None, None) => {}
+                        (Some(old_fields), Some(new_fields)) => {
+                            diff_fields(&prefix, old_fields, new_fields, &mut push)
+                        }
+                        _ => push(format!("{}fields changed", prefix), true),
+                    }
+                }
+                for new_variant in new_variants.iter().skip(old_variants.len()) {
+                    push(format!("variant {} added", new_variant.name), false);
+                }
+            }
+            _ => push(format!("changed from {} to {}", old_schema.kind(), new_schema.kind()), true),
+        }
+    }
+    for type_name in new.types.keys().filter(|type_name| !old.types.contains_key(*type_name)) {
+        changes.push(SchemaChange {
+            type_name: type_name.clone(),
+            description: "type added".to_string(),
+            breaking: false,
+        });
+    }
+    changes
+}
+
+fn diff_fields(
+    prefix: &str,
+    old_fields: &[Field],
+    new_fields: &[Field],
+    push: &mut impl FnMut(String, bool),
+) {
+    for (old_field, new_field) in old_fields.iter().zip(new_fields) {
+        if old_field.type_name != new_field.type_name {
+            push(
+                format!(
+                    "{}field {} changed type from {} to {}",
+                    prefix, new_field.name, old_field.type_name, new_field.type_name
+                ),
+                true,
+            );
+        } else if old_field.name != new_field.name {
+            push(
+                format!("{}field {} renamed to {}", prefix, old_field.name, new_field.name),
+                false,
+            );
+        }
+    }
+    for old_field in old_fields.iter().skip(new_fields.len()) {
+        push(format!("{}field {} removed", prefix, old_field.name), true);
+    }
+    for new_field in new_fields.iter().skip(old_fields.len()) {
+        push(format!("{}field {} added", prefix, new_field.name), true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, type_name: &str) -> Field {
+        Field { name: name.to_string(), type_name: type_name.to_string() }
+    }
+
+    fn unit_variant(name: &str) -> Variant {
+        Variant { name: name.to_string(), fields: None }
+    }
+
+    fn manifest(types: Vec<(&str, TypeSchema)>) -> SchemaManifest {
+        SchemaManifest {
+            types: types.into_iter().map(|(name, schema)| (name.to_string(), schema)).collect(),
+        }
+    }
+
+    fn breaking(changes: &[SchemaChange]) -> Vec<&str> {
+        changes.iter().filter(|c| c.breaking).map(|c| c.description.as_str()).collect()
+    }
+
+    #[test]
+    fn test_identical_manifests() {
+        let old = manifest(vec![(
+            "Challenge",
+            TypeSchema::Struct { hash: 1, fields: vec![field("body", "ChallengeBody")] },
+        )]);
+        assert!(diff(&old, &old.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_struct_changes() {
+        let old = manifest(vec![(
+            "Challenge",
+            TypeSchema::Struct {
+                hash: 1,
+                fields: vec![field("body", "ChallengeBody"), field("height", "u64")],
+            },
+        )]);
+        let renamed = manifest(vec![(
+            "Challenge",
+            TypeSchema::Struct {
+                hash: 2,
+                fields: vec![field("body", "ChallengeBody"), field("target_height", "u64")],
+            },
+        )]);
+        let changes = diff(&old, &renamed);
+        assert_eq!(changes.len(), 1);
+        assert!(breaking(&changes).is_empty());
+
+        let retyped = manifest(vec![(
+            "Challenge",
+            TypeSchema::Struct {
+                hash: 3,
+                fields: vec![
+                    field("body", "ChallengeBody"),
+                    field("height", "u32"),
+                    field("signature", "Signature"),
+                ],
+            },
+        )]);
+        assert_eq!(
+            breaking(&diff(&old, &retyped)),
+            vec!["field height changed type from u64 to u32", "field signature added"]
+        );
+    }
+
+    #[test]
+    fn test_enum_changes() {
+        let old = manifest(vec![(
+            "SlashingSeverity",
+            TypeSchema::Enum {
+                hash: 1,
+                variants: vec![unit_variant("Other"), unit_variant("DoubleSign")],
+            },
+        )]);
+        let appended = manifest(vec![(
+            "SlashingSeverity",
+            TypeSchema::Enum {
+                hash: 2,
+                variants: vec![
+                    unit_variant("Other"),
+                    unit_variant("DoubleSign"),
+                    unit_variant("InvalidChunk"),
+                ],
+            },
+        )]);
+        let changes = diff(&old, &appended);
+        assert_eq!(changes.len(), 1);
+        assert!(breaking(&changes).is_empty());
+        // Removing the appended variant is breaking in the other direction.
+        assert_eq!(breaking(&diff(&appended, &old)), vec!["variant InvalidChunk removed"]);
+
+        let with_fields = manifest(vec![(
+            "SlashingSeverity",
+            TypeSchema::Enum {
+                hash: 3,
+                variants: vec![
+                    unit_variant("Other"),
+                    Variant {
+                        name: "DoubleSign".to_string(),
+                        fields: Some(vec![field("0", "u64")]),
+                    },
+                ],
+            },
+        )]);
+        assert_eq!(breaking(&diff(&old, &with_fields)), vec!["variant DoubleSign: fields changed"]);
+    }
+
+    #[test]
+    fn test_types_added_and_removed() {
+        let old = manifest(vec![("A", TypeSchema::Struct { hash: 1, fields: vec![] })]);
+        let new = manifest(vec![("B", TypeSchema::Enum { hash: 2, variants: vec![] })]);
+        let changes = diff(&old, &new);
+        assert_eq!(breaking(&changes), vec!["type removed"]);
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn test_manifest_json_roundtrip() {
+        let manifest = manifest(vec![
+            ("A", TypeSchema::Struct { hash: 1, fields: vec![field("a", "u64")] }),
+            ("B", TypeSchema::Enum { hash: 2, variants: vec![unit_variant("V")] }),
+        ]);
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(json.contains(r#""kind":"struct""#));
+        assert_eq!(serde_json::from_str::<SchemaManifest>(&json).unwrap(), manifest);
+    }
+}
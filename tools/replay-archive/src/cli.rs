@@ -318,6 +318,7 @@ impl ReplayController {
 
         let block_context = Chain::get_apply_chunk_block_context(
             self.epoch_manager.as_ref(),
+            &self.chain_store,
             block,
             prev_block.header(),
             is_new_chunk,
@@ -428,7 +429,7 @@ impl ReplayController {
                 self.epoch_manager.as_ref(),
                 prev_block_hash,
                 prev_chunk_extra,
-                prev_chunk_header.height_included(),
+                prev_chunk_header,
                 chunk_header,
             )
             .context("Failed to validate chunk with chunk extra")?;
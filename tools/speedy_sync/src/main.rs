@@ -3,7 +3,10 @@ use near_async::messaging::{noop, IntoMultiSender};
 use near_chain::rayon_spawner::RayonAsyncComputationSpawner;
 use near_chain::types::{ChainConfig, Tip};
 use near_chain::{Chain, ChainGenesis, DoomslugThresholdMode};
-use near_chain_configs::{GenesisValidationMode, MutableConfigValue, ReshardingConfig};
+use near_chain_configs::{
+    BlockTimestampConfig, CatchupConfig, GenesisValidationMode, MutableConfigValue,
+    ReshardingConfig,
+};
 use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
 use near_epoch_manager::types::EpochInfoAggregator;
 use near_epoch_manager::EpochManager;
@@ -257,6 +260,10 @@ fn load_snapshot(load_cmd: LoadCmd) {
         ChainConfig {
             save_trie_changes: near_config.client_config.save_trie_changes,
             background_migration_threads: 1,
+            apply_chunks_threads: 1,
+            catchup: CatchupConfig::default(),
+            max_block_clock_skew: BlockTimestampConfig::default().max_clock_skew,
+            shadow_validation: false,
             resharding_config: MutableConfigValue::new(
                 ReshardingConfig::default(),
                 "resharding_config",
@@ -6,7 +6,7 @@ use near_chain::migrations::check_if_block_is_first_with_chunk_of_version;
 use near_chain::types::{
     ApplyChunkBlockContext, ApplyChunkResult, ApplyChunkShardContext, RuntimeAdapter,
 };
-use near_chain::{ChainStore, ChainStoreAccess, ChainStoreUpdate, ReceiptFilter};
+use near_chain::{Chain, ChainStore, ChainStoreAccess, ChainStoreUpdate, ReceiptFilter};
 use near_chain_configs::Genesis;
 use near_epoch_manager::{EpochManagerAdapter, EpochManagerHandle};
 use near_primitives::apply::ApplyChunkReason;
@@ -96,6 +96,8 @@ fn apply_block_from_range(
     let block_author = epoch_manager
         .get_block_producer(block.header().epoch_id(), block.header().height())
         .unwrap();
+    let recent_block_headers =
+        Chain::get_recent_block_headers(epoch_manager, &read_chain_store, block.header()).unwrap();
 
     let apply_result = if block.header().is_genesis() {
         if verbose_output {
@@ -198,6 +200,7 @@ fn apply_block_from_range(
                     prev_block.header().next_gas_price(),
                     block.block_congestion_info(),
                     block.block_bandwidth_requests(),
+                    recent_block_headers,
                 ),
                 &receipts,
                 chunk.transactions(),
@@ -225,6 +228,7 @@ fn apply_block_from_range(
                     block.header().next_gas_price(),
                     block.block_congestion_info(),
                     block.block_bandwidth_requests(),
+                    recent_block_headers,
                 ),
                 &[],
                 &[],
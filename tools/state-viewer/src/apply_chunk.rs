@@ -201,6 +201,8 @@ pub(crate) fn apply_chunk(
                 random_seed: hash("random seed".as_ref()),
                 congestion_info: block_congestion_info,
                 bandwidth_requests: block_bandwidth_requests,
+                recent_block_headers: Default::default(),
+                execution_tracer: None,
             },
             &receipts,
             transactions,
@@ -239,7 +241,7 @@ fn find_tx_or_receipt(
             if &receipt.get_hash() == hash {
                 let shard_layout =
                     epoch_manager.get_shard_layout_from_prev_block(chunk.prev_block())?;
-                let to_shard = shard_layout.account_id_to_shard_id(receipt.receiver_id());
+                let to_shard = receipt.receiver_shard_id(&shard_layout);
                 return Ok(Some((HashType::Receipt, to_shard)));
             }
         }
@@ -427,7 +429,7 @@ fn apply_receipt_in_chunk(
                     if receipt.get_hash() == *id {
                         let shard_layout =
                             epoch_manager.get_shard_layout_from_prev_block(chunk.prev_block())?;
-                        let to_shard = shard_layout.account_id_to_shard_id(receipt.receiver_id());
+                        let to_shard = receipt.receiver_shard_id(&shard_layout);
                         to_apply.insert((height, to_shard));
                         println!(
                             "found receipt in chunk {}. Receiver is in shard {}",
@@ -712,8 +714,7 @@ mod test {
                     }
 
                     for receipt in chunk.prev_outgoing_receipts() {
-                        let to_shard_id =
-                            shard_layout.account_id_to_shard_id(receipt.receiver_id());
+                        let to_shard_id = receipt.receiver_shard_id(&shard_layout);
                         let to_shard_index = shard_layout.get_shard_index(to_shard_id).unwrap();
 
                         let results = crate::apply_chunk::apply_receipt(
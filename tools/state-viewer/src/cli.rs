@@ -6,6 +6,7 @@ use crate::rocksdb_stats::get_rocksdb_stats;
 use crate::trie_iteration_benchmark::TrieIterationBenchmarkCmd;
 
 use crate::latest_witnesses::StateWitnessCmd;
+use crate::make_challenge::MakeChallengeCmd;
 use near_chain::types::RuntimeStorageConfig;
 use near_chain_configs::{GenesisChangeConfig, GenesisValidationMode};
 use near_epoch_manager::EpochManager;
@@ -82,6 +83,10 @@ pub enum StateViewerSubCommand {
     /// Regenerates epoch info based on previous epoch.
     #[clap(alias = "epoch_analysis")]
     EpochAnalysis(EpochAnalysisCmd),
+    /// Build a signed `ChunkProofs` or `ChunkState` challenge against a chunk
+    /// from the local store, ready to be broadcast.
+    #[clap(alias = "make_challenge")]
+    MakeChallenge(MakeChallengeCmd),
     /// Looks up a certain partial chunk.
     #[clap(alias = "partial_chunks")]
     PartialChunks(PartialChunksCmd),
@@ -180,6 +185,7 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::DumpTx(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::EpochInfo(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::EpochAnalysis(cmd) => cmd.run(near_config, store),
+            StateViewerSubCommand::MakeChallenge(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::PartialChunks(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::Receipts(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::ReplayHeaders(cmd) => cmd.run(home_dir, near_config, store),
@@ -77,6 +77,8 @@ pub(crate) fn apply_block(
     if matches!(storage, StorageSource::FlatStorage) {
         runtime.get_flat_storage_manager().create_flat_storage_for_shard(shard_uid).unwrap();
     }
+    let recent_block_headers =
+        Chain::get_recent_block_headers(epoch_manager, chain_store, block.header()).unwrap();
     let apply_result = if block.chunks()[shard_index].height_included() == height {
         let chunk = chain_store.get_chunk(&block.chunks()[shard_index].chunk_hash()).unwrap();
         let prev_block = chain_store.get_block(block.header().prev_hash()).unwrap();
@@ -120,6 +122,7 @@ pub(crate) fn apply_block(
                     prev_block.header().next_gas_price(),
                     block.block_congestion_info(),
                     block.block_bandwidth_requests(),
+                    recent_block_headers,
                 ),
                 &receipts,
                 chunk.transactions(),
@@ -146,6 +149,7 @@ pub(crate) fn apply_block(
                     block.header().next_gas_price(),
                     prev_block.block_congestion_info(),
                     prev_block.block_bandwidth_requests(),
+                    recent_block_headers,
                 ),
                 &[],
                 &[],
@@ -136,6 +136,11 @@ pub(crate) enum ActionType {
     DeleteAccount,
     DataReceipt,
     Delegate,
+    SponsorStorage,
+    DeployGlobalContract,
+    UseGlobalContract,
+    MigrateState,
+    TransferToken,
 }
 
 impl ContractAccount {
@@ -343,11 +348,20 @@ fn try_find_actions_spawned_by_receipt(
                                     Action::NonrefundableStorageTransfer(_) => {
                                         ActionType::NonrefundableStorageTransfer
                                     }
-                                    Action::Stake(_) => ActionType::Stake,
+                                    Action::Stake(_) | Action::StakeWithMetadata(_) => {
+                                        ActionType::Stake
+                                    }
                                     Action::AddKey(_) => ActionType::AddKey,
                                     Action::DeleteKey(_) => ActionType::DeleteKey,
                                     Action::DeleteAccount(_) => ActionType::DeleteAccount,
                                     Action::Delegate(_) => ActionType::Delegate,
+                                    Action::SponsorStorage(_) => ActionType::SponsorStorage,
+                                    Action::DeployGlobalContract(_) => {
+                                        ActionType::DeployGlobalContract
+                                    }
+                                    Action::UseGlobalContract(_) => ActionType::UseGlobalContract,
+                                    Action::MigrateState(_) => ActionType::MigrateState,
+                                    Action::TransferToken(_) => ActionType::TransferToken,
                                 };
                                 entry
                                     .actions
@@ -355,6 +369,7 @@ fn try_find_actions_spawned_by_receipt(
                                     .insert(action_type);
                             }
                         }
+                        ReceiptEnum::GlobalContractDistribution(_) => {}
                         ReceiptEnum::Data(_) | ReceiptEnum::PromiseResume(_) => {
                             entry
                                 .actions
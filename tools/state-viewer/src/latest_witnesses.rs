@@ -144,6 +144,7 @@ impl ValidateWitnessCmd {
                 epoch_manager.as_ref(),
                 runtime_adapter.as_ref(),
                 Some(processing_done_tracker),
+                None,
             )
             .unwrap();
         waiter.wait();
@@ -8,6 +8,7 @@ mod congestion_control;
 mod contract_accounts;
 mod epoch_info;
 mod latest_witnesses;
+mod make_challenge;
 pub mod progress_reporter;
 mod replay_headers;
 mod rocksdb_stats;
@@ -0,0 +1,147 @@
+use anyhow::Context;
+use near_chain::validate::challenge_target;
+use near_chain::{Block, ChainStore, ChainStoreAccess};
+use near_epoch_manager::{EpochManager, EpochManagerAdapter};
+use near_primitives::challenge::{
+    Challenge, ChallengeBody, ChunkProofs, ChunkState, MaybeEncodedShardChunk,
+};
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::ShardId;
+use near_primitives_core::serialize::to_base64;
+use near_store::Store;
+use nearcore::{NearConfig, NightshadeRuntime, NightshadeRuntimeExt};
+use std::path::{Path, PathBuf};
+
+use crate::cli::StorageSource;
+use crate::commands::apply_block;
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+#[clap(rename_all = "kebab_case")]
+pub enum ChallengeKind {
+    /// Challenge the chunk proofs or the transactions order of the chunk.
+    ChunkProofs,
+    /// Challenge the state transition of the previous chunk, which doesn't
+    /// match the state root in the chunk header.
+    ChunkState,
+}
+
+/// Builds a challenge against the chunk of the given shard included in the
+/// given block and signs it with the validator key of the node.
+#[derive(clap::Parser)]
+pub struct MakeChallengeCmd {
+    /// Hash of the block which includes the challenged chunk.
+    #[clap(long)]
+    block_hash: CryptoHash,
+    #[clap(long)]
+    shard_id: ShardId,
+    #[clap(long, default_value = "chunk-proofs")]
+    kind: ChallengeKind,
+    /// Storage used to re-execute the previous chunk for `chunk-state` challenges.
+    #[clap(long, default_value = "trie")]
+    storage: StorageSource,
+    /// File to write the base64-encoded challenge to. Printed to stdout if not set.
+    #[clap(long)]
+    output_path: Option<PathBuf>,
+}
+
+impl MakeChallengeCmd {
+    pub(crate) fn run(self, home_dir: &Path, near_config: NearConfig, store: Store) {
+        make_challenge(self, home_dir, near_config, store).expect("Failed to make challenge")
+    }
+}
+
+fn make_challenge(
+    cmd: MakeChallengeCmd,
+    home_dir: &Path,
+    near_config: NearConfig,
+    store: Store,
+) -> anyhow::Result<()> {
+    let signer = near_config
+        .validator_signer
+        .get()
+        .context("validator key is required to sign the challenge")?;
+    let mut chain_store = ChainStore::new(
+        store.clone(),
+        near_config.genesis.config.genesis_height,
+        near_config.client_config.save_trie_changes,
+    );
+    let epoch_manager =
+        EpochManager::new_arc_handle(store.clone(), &near_config.genesis.config, Some(home_dir));
+    let block = chain_store.get_block(&cmd.block_hash)?;
+    // Chunks of the block are ordered by the shard layout of the epoch they
+    // were produced in.
+    let chunk_epoch_id = epoch_manager.get_epoch_id_from_prev_block(block.header().prev_hash())?;
+    let shard_index = epoch_manager.shard_id_to_index(cmd.shard_id, &chunk_epoch_id)?;
+    let chunk_header = block
+        .chunks()
+        .get(shard_index)
+        .cloned()
+        .with_context(|| format!("no chunk for shard {} in block", cmd.shard_id))?;
+    let merkle_proofs = Block::compute_chunk_headers_root(block.chunks().iter_deprecated()).1;
+
+    let body = match cmd.kind {
+        ChallengeKind::ChunkProofs => {
+            let chunk = chain_store.get_chunk(&chunk_header.chunk_hash())?;
+            ChallengeBody::ChunkProofs(ChunkProofs {
+                block_header: borsh::to_vec(block.header())?,
+                merkle_proof: merkle_proofs[shard_index].clone(),
+                chunk: Box::new(MaybeEncodedShardChunk::Decoded((*chunk).clone())),
+            })
+        }
+        ChallengeKind::ChunkState => {
+            let prev_block = chain_store.get_block(block.header().prev_hash())?;
+            // The previous block may belong to the previous epoch with a different shard layout.
+            let prev_chunk_epoch_id =
+                epoch_manager.get_epoch_id_from_prev_block(prev_block.header().prev_hash())?;
+            let prev_shard_index =
+                epoch_manager.shard_id_to_index(cmd.shard_id, &prev_chunk_epoch_id)?;
+            let prev_chunk_header =
+                prev_block.chunks().get(prev_shard_index).cloned().with_context(|| {
+                    format!("no chunk for shard {} in previous block", cmd.shard_id)
+                })?;
+            let prev_chunk = chain_store.get_chunk(&prev_chunk_header.chunk_hash())?;
+            let prev_merkle_proofs =
+                Block::compute_chunk_headers_root(prev_block.chunks().iter_deprecated()).1;
+            // Re-execute the previous chunk to record the trie nodes it touches.
+            let runtime = NightshadeRuntime::from_config(
+                home_dir,
+                store,
+                &near_config,
+                epoch_manager.clone(),
+            )
+            .context("could not create the transaction runtime")?;
+            let (_, apply_result) = apply_block(
+                *prev_block.hash(),
+                cmd.shard_id,
+                epoch_manager.as_ref(),
+                runtime.as_ref(),
+                &mut chain_store,
+                cmd.storage,
+            );
+            let partial_state = apply_result
+                .proof
+                .map(|storage| storage.nodes)
+                .context("state transition of the previous chunk was not recorded")?;
+            ChallengeBody::ChunkState(ChunkState {
+                prev_block_header: borsh::to_vec(prev_block.header())?,
+                block_header: borsh::to_vec(block.header())?,
+                prev_merkle_proof: prev_merkle_proofs[prev_shard_index].clone(),
+                prev_chunk: (*prev_chunk).clone(),
+                merkle_proof: merkle_proofs[shard_index].clone(),
+                chunk_header,
+                partial_state,
+            })
+        }
+    };
+
+    let (target_epoch_id, target_height) = challenge_target(epoch_manager.as_ref(), &body)?;
+    let challenge = Challenge::produce(body, target_epoch_id, target_height, &signer);
+    eprintln!("Challenge {} signed by {}", challenge.hash, challenge.account_id);
+    let encoded = to_base64(&borsh::to_vec(&challenge)?);
+    match cmd.output_path {
+        Some(output_path) => std::fs::write(&output_path, encoded)
+            .with_context(|| format!("failed to write {}", output_path.display()))?,
+        None => println!("{}", encoded),
+    }
+    Ok(())
+}
@@ -233,6 +233,8 @@ fn format_block_misc_value<'a>(key: &'a [u8], value: &'a [u8]) -> Box<dyn Debug
     } else if key == near_store::TAIL_KEY
         || key == near_store::CHUNK_TAIL_KEY
         || key == near_store::FORK_TAIL_KEY
+        || key == near_store::OUTCOMES_TAIL_KEY
+        || key == near_store::HEADER_TAIL_KEY
         || key == near_store::LARGEST_TARGET_HEIGHT_KEY
     {
         Box::new(BlockHeight::try_from_slice(value).unwrap())
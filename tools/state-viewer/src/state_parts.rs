@@ -404,10 +404,13 @@ async fn load_state_parts(
                 tracing::info!(target: "state-parts", part_id, part_length = part.len(), elapsed_sec = timer.elapsed().as_secs_f64(), "Loaded a state part");
             }
             LoadAction::Validate => {
+                let protocol_version =
+                    chain.epoch_manager.get_epoch_protocol_version(&epoch_id).unwrap();
                 assert!(chain.runtime_adapter.validate_state_part(
                     &state_root,
                     PartId::new(part_id, num_parts),
-                    &part
+                    &part,
+                    protocol_version,
                 ));
                 tracing::info!(target: "state-parts", part_id, part_length = part.len(), elapsed_sec = timer.elapsed().as_secs_f64(), "Validated a state part");
             }
@@ -422,7 +425,8 @@ async fn load_state_parts(
 fn print_state_part(state_root: &StateRoot, _part_id: PartId, data: &[u8]) {
     let trie_nodes: PartialState = BorshDeserialize::try_from_slice(data).unwrap();
     let trie =
-        Trie::from_recorded_storage(PartialStorage { nodes: trie_nodes }, *state_root, false);
+        Trie::from_recorded_storage(PartialStorage { nodes: trie_nodes }, *state_root, false)
+            .unwrap();
     trie.print_recursive(
         &mut std::io::stdout().lock(),
         &state_root,
@@ -535,7 +539,8 @@ async fn dump_state_parts(
 fn get_first_state_record(state_root: &StateRoot, data: &[u8]) -> Option<StateRecord> {
     let trie_nodes = BorshDeserialize::try_from_slice(data).unwrap();
     let trie =
-        Trie::from_recorded_storage(PartialStorage { nodes: trie_nodes }, *state_root, false);
+        Trie::from_recorded_storage(PartialStorage { nodes: trie_nodes }, *state_root, false)
+            .unwrap();
 
     for (key, value) in trie.disk_iter().unwrap().flatten() {
         if let Some(sr) = StateRecord::from_raw_key_value(key, value) {